@@ -1,15 +1,18 @@
 //! Cache for autoload symbol tables
 
 use crate::symbols::SymbolTable;
-use crate::symbols::class_info::{ClassInfo, ClassMethodInfo, ClassKind};
+use crate::symbols::class_info::{
+    ClassConstantInfo, ClassInfo, ClassMethodInfo, ClassKind, ClassPropertyInfo, EnumCaseInfo,
+};
+use crate::types::phpdoc::parse_type_string;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
-// Bump version to 2 to invalidate old caches (now includes methods and traits)
-const CACHE_VERSION: u32 = 2;
+// Bump version to 5 to invalidate old caches (now includes @throws)
+const CACHE_VERSION: u32 = 5;
 const CACHE_DIR: &str = ".rustor-cache";
 const CACHE_FILE: &str = "vendor-symbols.json";
 
@@ -23,6 +26,25 @@ pub struct CachedClass {
     pub interfaces: Vec<String>,
     pub traits: Vec<String>,
     pub methods: Vec<String>,  // Just method names for now
+    /// Enum case names (enums only)
+    #[serde(default)]
+    pub enum_cases: Vec<String>,
+    /// Backing type of a backed enum ("int" or "string")
+    #[serde(default)]
+    pub backing_type: Option<String>,
+    /// Deprecation description of the class itself
+    #[serde(default)]
+    pub deprecated: Option<String>,
+    /// Deprecated members: method, constant/case and property name -> description
+    #[serde(default)]
+    pub deprecated_methods: HashMap<String, String>,
+    #[serde(default)]
+    pub deprecated_constants: HashMap<String, String>,
+    #[serde(default)]
+    pub deprecated_properties: HashMap<String, String>,
+    /// Method name -> exception classes declared with `@throws`
+    #[serde(default)]
+    pub method_throws: HashMap<String, Vec<String>>,
 }
 
 impl CachedClass {
@@ -40,6 +62,30 @@ impl CachedClass {
             interfaces: info.interfaces.clone(),
             traits: info.traits.clone(),
             methods: info.methods.keys().cloned().collect(),
+            enum_cases: info.cases.iter().map(|c| c.name.clone()).collect(),
+            backing_type: info.backing_type.as_ref().map(|t| t.to_string()),
+            deprecated: info.deprecated.clone(),
+            deprecated_methods: info
+                .methods
+                .iter()
+                .filter_map(|(key, m)| Some((key.clone(), m.deprecated.clone()?)))
+                .collect(),
+            deprecated_constants: info
+                .constants
+                .values()
+                .filter_map(|c| Some((c.name.clone(), c.deprecated.clone()?)))
+                .chain(info.cases.iter().filter_map(|c| Some((c.name.clone(), c.deprecated.clone()?))))
+                .collect(),
+            deprecated_properties: info
+                .properties
+                .values()
+                .filter_map(|p| Some((p.name.clone(), p.deprecated.clone()?)))
+                .collect(),
+            method_throws: info
+                .methods
+                .iter()
+                .filter_map(|(key, m)| Some((key.clone(), m.throws.clone()?)))
+                .collect(),
         }
     }
 
@@ -55,9 +101,35 @@ impl CachedClass {
         info.parent = self.parent.clone();
         info.interfaces = self.interfaces.clone();
         info.traits = self.traits.clone();
+        info.deprecated = self.deprecated.clone();
         // Add methods (just names, no parameter info in cache)
         for method_name in &self.methods {
-            info.add_method(ClassMethodInfo::new(method_name));
+            let mut method = ClassMethodInfo::new(method_name);
+            method.deprecated = self.deprecated_methods.get(method_name).cloned();
+            method.throws = self.method_throws.get(method_name).cloned();
+            info.add_method(method);
+        }
+        // Only deprecated constants and properties are kept
+        for (name, message) in &self.deprecated_constants {
+            if !self.enum_cases.contains(name) {
+                let mut constant = ClassConstantInfo::new(name);
+                constant.deprecated = Some(message.clone());
+                info.add_constant(constant);
+            }
+        }
+        for (name, message) in &self.deprecated_properties {
+            let mut property = ClassPropertyInfo::new(name);
+            property.deprecated = Some(message.clone());
+            info.add_property(property);
+        }
+        if info.kind == ClassKind::Enum {
+            for case_name in &self.enum_cases {
+                let mut case = EnumCaseInfo::new(case_name);
+                case.deprecated = self.deprecated_constants.get(case_name).cloned();
+                info.add_case(case);
+            }
+            info.backing_type = self.backing_type.as_deref().and_then(parse_type_string);
+            info.add_enum_builtins();
         }
         info
     }
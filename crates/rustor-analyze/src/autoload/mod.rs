@@ -63,7 +63,7 @@ impl AutoloadScanner {
                 let source = fs::read_to_string(file).ok()?;
                 let arena = bumpalo::Bump::new();
                 let file_id = FileId::new(file.to_string_lossy().as_ref());
-                let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+                let program = rustor_core::parse(&arena, file_id, &source).program;
 
                 let collector = SymbolCollector::new(&source, file);
                 Some(collector.collect(&program))
@@ -302,7 +302,7 @@ impl VendorPsr4Scanner {
                     if let Ok(source) = fs::read_to_string(path) {
                         let arena = bumpalo::Bump::new();
                         let file_id = FileId::new(path.to_string_lossy().as_ref());
-                        let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+                        let program = rustor_core::parse(&arena, file_id, &source).program;
 
                         let collector = SymbolCollector::new(&source, path);
                         let symbols = collector.collect(&program);
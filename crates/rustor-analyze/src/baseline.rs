@@ -1,16 +1,20 @@
 //! Baseline support for gradual adoption
 //!
 //! Baselines allow ignoring existing errors so only new errors are reported.
-//! This module is compatible with PHPStan's baseline format.
+//! This module is compatible with PHPStan's baseline formats: NEON
+//! (`phpstan-baseline.neon`) and PHP (`phpstan-baseline.php`), either as one
+//! file or split into files included by a loader.
 
 use crate::config::neon::{NeonParser, Value};
+use crate::config::php;
 use crate::issue::IssueCollection;
 use crate::logging;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
 
 /// A baseline entry representing an ignored error
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +105,27 @@ impl BaselineEntry {
     }
 }
 
+/// How a split baseline groups entries into files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitBy {
+    /// One file per error identifier
+    Identifier,
+    /// One file per directory of the files with errors
+    Directory,
+}
+
+impl FromStr for SplitBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "identifier" => Ok(SplitBy::Identifier),
+            "directory" => Ok(SplitBy::Directory),
+            _ => Err(format!("Invalid baseline split '{}'. Valid options: identifier, directory", s)),
+        }
+    }
+}
+
 /// Baseline file structure
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Baseline {
@@ -114,21 +139,68 @@ impl Baseline {
         Self::default()
     }
 
-    /// Load baseline from a NEON file (PHPStan format)
+    /// Load baseline from a NEON or PHP file (PHPStan format), with the
+    /// files it includes
     pub fn load(path: &Path) -> Result<Self, std::io::Error> {
-        let content = fs::read_to_string(path)?;
+        let mut baseline = Baseline::new();
+        baseline.load_file(path, false, &mut HashSet::new())?;
+        logging::log(&format!("Loaded {} baseline entries", baseline.entries.len()));
+        Ok(baseline)
+    }
+
+    /// Load one baseline file. Paths in included files are relative to
+    /// them, like PHPStan resolves them.
+    fn load_file(&mut self, path: &Path, included: bool, seen: &mut HashSet<PathBuf>) -> Result<(), std::io::Error> {
+        if !seen.insert(path.canonicalize().unwrap_or_else(|_| path.to_path_buf())) {
+            return Ok(());
+        }
         logging::log(&format!("Loading baseline from: {}", path.display()));
-        Self::parse_neon(&content)
+        let value = Self::parse_file(path)?;
+
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for include in includes(&value, dir) {
+            self.load_file(&include, true, seen)?;
+        }
+
+        for mut entry in Self::parse_value(&value).entries {
+            entry.path = if included {
+                resolve_path(dir, &entry.path)
+            } else {
+                php::relative_path(&entry.path)
+            };
+            self.entries.push(entry);
+        }
+        Ok(())
     }
 
-    /// Parse NEON baseline format (PHPStan compatible)
-    fn parse_neon(content: &str) -> Result<Self, std::io::Error> {
+    /// Parse a NEON or PHP baseline file
+    fn parse_file(path: &Path) -> Result<Value, std::io::Error> {
+        let content = fs::read_to_string(path)?;
+        if is_php(path) {
+            php::parse(&content, path).map_err(|e| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, format!("PHP parse error: {}", e))
+            })
+        } else {
+            Self::parse_neon_value(&content)
+        }
+    }
+
+    fn parse_neon_value(content: &str) -> Result<Value, std::io::Error> {
         let mut parser = NeonParser::new(content);
 
-        let value = parser.parse().map_err(|e| {
+        parser.parse().map_err(|e| {
             std::io::Error::new(std::io::ErrorKind::InvalidData, format!("NEON parse error: {}", e))
-        })?;
+        })
+    }
+
+    /// Parse NEON baseline format (PHPStan compatible)
+    #[cfg(test)]
+    fn parse_neon(content: &str) -> Result<Self, std::io::Error> {
+        Ok(Self::parse_value(&Self::parse_neon_value(content)?))
+    }
 
+    /// Entries of a parsed baseline file
+    fn parse_value(value: &Value) -> Self {
         let mut entries = Vec::new();
 
         // Navigate: parameters -> ignoreErrors -> [array of entries]
@@ -151,8 +223,7 @@ impl Baseline {
             }
         }
 
-        logging::log(&format!("Loaded {} baseline entries", entries.len()));
-        Ok(Baseline { entries })
+        Baseline { entries }
     }
 
     /// Parse a single baseline entry from NEON value
@@ -272,12 +343,132 @@ impl Baseline {
         });
     }
 
-    /// Save baseline to a file (PHPStan NEON format)
+    /// Save baseline to a file: PHP format if the path ends in `.php`,
+    /// NEON otherwise
     pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
-        let content = self.to_neon();
+        let content = if is_php(path) {
+            self.to_php(path.parent().unwrap_or(Path::new("")))
+        } else {
+            self.to_neon()
+        };
         fs::write(path, content)
     }
 
+    /// Save baseline as a loader file that includes one file per identifier
+    /// or directory. The files are written to a directory named after the
+    /// loader (`phpstan-baseline.neon` includes `phpstan-baseline/*.neon`),
+    /// in the loader's format. Files the previous loader included that are
+    /// not written again are removed; other files in the loader's format
+    /// in the directory are an error, so they aren't overwritten.
+    ///
+    /// Returns the files written, loader last.
+    pub fn save_split(&self, path: &Path, split: SplitBy) -> Result<Vec<PathBuf>, std::io::Error> {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let extension = if is_php(path) { "php" } else { "neon" };
+        let parts_dir = dir.join(&stem);
+
+        // Parts of an earlier split, from the loader it wrote
+        let previous: HashSet<PathBuf> = if path.exists() {
+            includes(&Self::parse_file(path)?, dir)
+                .iter()
+                .filter_map(|include| include.canonicalize().ok())
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let is_part = |file: &Path| file.extension().is_some_and(|ext| ext == extension);
+        if parts_dir.is_dir() {
+            for file in fs::read_dir(&parts_dir)?.filter_map(|e| e.ok()).map(|e| e.path()) {
+                if is_part(&file) && !file.canonicalize().is_ok_and(|file| previous.contains(&file)) {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("{} is not included by {}; move it out of {} first", file.display(), path.display(), parts_dir.display()),
+                    ));
+                }
+            }
+        }
+        fs::create_dir_all(&parts_dir)?;
+
+        let mut groups: BTreeMap<String, Vec<BaselineEntry>> = BTreeMap::new();
+        for entry in &self.entries {
+            let group = match split {
+                SplitBy::Identifier => entry.identifier.clone().unwrap_or_else(|| "missing-identifier".to_string()),
+                SplitBy::Directory => directory_group(&entry.path),
+            };
+            groups.entry(group).or_default().push(entry.clone());
+        }
+
+        let mut written = Vec::new();
+        for (group, entries) in groups {
+            let part_path = parts_dir.join(format!("{}.{}", group, extension));
+            let content = if extension == "php" {
+                Baseline { entries }.to_php(&parts_dir)
+            } else {
+                // NEON paths are relative to the file that contains them
+                let entries = entries
+                    .into_iter()
+                    .map(|mut entry| {
+                        entry.path = relative_to(&parts_dir, &entry.path);
+                        entry
+                    })
+                    .collect();
+                Baseline { entries }.to_neon()
+            };
+            fs::write(&part_path, content)?;
+            written.push(part_path);
+        }
+
+        // Remove groups that are gone since the last split
+        let current: HashSet<PathBuf> = written.iter().filter_map(|part| part.canonicalize().ok()).collect();
+        for file in previous.difference(&current) {
+            fs::remove_file(file)?;
+        }
+
+        let includes: Vec<String> = written
+            .iter()
+            .filter_map(|part| part.file_name())
+            .map(|name| format!("{}/{}", stem, name.to_string_lossy()))
+            .collect();
+        let loader = if extension == "php" {
+            let mut output = String::from("<?php declare(strict_types = 1);\n\nreturn ['includes' => [\n");
+            for include in &includes {
+                output.push_str(&format!("\t__DIR__ . {},\n", php_string(&format!("/{}", include))));
+            }
+            output.push_str("]];\n");
+            output
+        } else {
+            let mut output = String::from("includes:\n");
+            for include in &includes {
+                output.push_str(&format!("\t- {}\n", include));
+            }
+            output
+        };
+        fs::write(path, loader)?;
+        written.push(path.to_path_buf());
+        Ok(written)
+    }
+
+    /// Convert to PHPStan-compatible PHP format, with paths relative to `dir`
+    fn to_php(&self, dir: &Path) -> String {
+        let mut output = String::from("<?php declare(strict_types = 1);\n\n$ignoreErrors = [];\n");
+
+        for entry in &self.entries {
+            output.push_str("$ignoreErrors[] = [\n");
+            output.push_str(&format!("\t'message' => {},\n", php_string(&entry.message)));
+            if let Some(ref id) = entry.identifier {
+                output.push_str(&format!("\t'identifier' => {},\n", php_string(id)));
+            }
+            output.push_str(&format!("\t'count' => {},\n", entry.count));
+            let path = format!("/{}", relative_to(dir, &entry.path));
+            output.push_str(&format!("\t'path' => __DIR__ . {},\n", php_string(&path)));
+            output.push_str("];\n");
+        }
+
+        output.push_str("\nreturn ['parameters' => ['ignoreErrors' => $ignoreErrors]];\n");
+        output
+    }
+
     /// Convert to PHPStan-compatible NEON format
     fn to_neon(&self) -> String {
         let mut output = String::from("parameters:\n\tignoreErrors:\n");
@@ -425,6 +616,84 @@ impl Baseline {
     }
 }
 
+/// Files a parsed baseline file includes, resolved against its directory
+fn includes(value: &Value, dir: &Path) -> Vec<PathBuf> {
+    value
+        .get("includes")
+        .and_then(|v| v.as_array())
+        .map(|includes| includes.iter().filter_map(|v| v.as_str()).map(|include| dir.join(include)).collect())
+        .unwrap_or_default()
+}
+
+fn is_php(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "php")
+}
+
+/// A PHP single-quoted string literal, like `var_export()` writes it
+fn php_string(s: &str) -> String {
+    format!("'{}'", s.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// Resolve `.` and `..` without touching the file system; leading `..` of
+/// relative paths are kept
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Path of an entry in a file included from `dir`, relative to the current
+/// directory
+fn resolve_path(dir: &Path, path: &str) -> String {
+    if Path::new(path).is_absolute() {
+        return php::relative_path(path);
+    }
+    normalize_path(&dir.join(path)).to_string_lossy().replace('\\', "/")
+}
+
+/// `path` (relative to the current directory) relative to `dir`
+fn relative_to(dir: &Path, path: &str) -> String {
+    let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+    let absolute = |p: &Path| normalize_path(&std::path::absolute(p).unwrap_or_else(|_| p.to_path_buf()));
+    let (dir, path) = (absolute(dir), absolute(Path::new(path)));
+
+    let common = dir.components().zip(path.components()).take_while(|(a, b)| a == b).count();
+    let mut relative = PathBuf::new();
+    for _ in dir.components().skip(common) {
+        relative.push("..");
+    }
+    for component in path.components().skip(common) {
+        relative.push(component);
+    }
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+/// File name for the entries of a directory in a split baseline
+fn directory_group(path: &str) -> String {
+    let parent = normalize_path(Path::new(path).parent().unwrap_or(Path::new("")));
+    let parts: Vec<String> = parent
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            Component::ParentDir => Some("..".to_string()),
+            _ => None,
+        })
+        .collect();
+    if parts.is_empty() {
+        "root".to_string()
+    } else {
+        parts.join("-")
+    }
+}
+
 /// Escape special regex characters for use in a regex pattern
 fn escape_regex(s: &str) -> String {
     let special_chars = ['\\', '.', '+', '*', '?', '(', ')', '[', ']', '{', '}', '^', '$', '|'];
@@ -603,6 +872,117 @@ parameters:
         assert!(neon.contains("path: test.php"));
     }
 
+    #[test]
+    fn test_php_format_round_trip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let baseline_path = temp_dir.path().join("phpstan-baseline.php");
+        let file = temp_dir.path().join("src/it's.php").to_string_lossy().into_owned();
+        let baseline = Baseline {
+            entries: vec![BaselineEntry::new(
+                "#^Call to undefined function foo\\(\\)\\.$#".to_string(),
+                2,
+                file.clone(),
+                Some("function.notFound".to_string()),
+            )],
+        };
+
+        baseline.save(&baseline_path).unwrap();
+        let content = fs::read_to_string(&baseline_path).unwrap();
+        assert!(content.contains("'message' => '#^Call to undefined function foo\\\\(\\\\)\\\\.$#',"));
+        assert!(content.contains("'path' => __DIR__ . '/src/it\\'s.php',"));
+
+        let loaded = Baseline::load(&baseline_path).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].message, baseline.entries[0].message);
+        assert_eq!(loaded.entries[0].identifier.as_deref(), Some("function.notFound"));
+        assert_eq!(loaded.entries[0].count, 2);
+        assert_eq!(loaded.entries[0].path, file);
+        assert!(loaded.entries[0].matches_message("Call to undefined function foo()."));
+    }
+
+    #[test]
+    fn test_save_split() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        let entry = |message: &str, file: &str, identifier: Option<&str>| {
+            BaselineEntry::new(
+                format!("#^{}$#", message),
+                1,
+                src.join(file).to_string_lossy().into_owned(),
+                identifier.map(String::from),
+            )
+        };
+        let baseline = Baseline {
+            entries: vec![
+                entry("Error 1", "a.php", Some("argument.type")),
+                entry("Error 2", "Model/b.php", Some("argument.type")),
+                entry("Error 3", "Model/b.php", None),
+            ],
+        };
+
+        for extension in ["neon", "php"] {
+            let loader = temp_dir.path().join(format!("phpstan-baseline.{}", extension));
+            let written = baseline.save_split(&loader, SplitBy::Identifier).unwrap();
+            let names: Vec<String> = written.iter().map(|p| p.file_name().unwrap().to_string_lossy().into_owned()).collect();
+            assert_eq!(
+                names,
+                vec![
+                    format!("argument.type.{}", extension),
+                    format!("missing-identifier.{}", extension),
+                    format!("phpstan-baseline.{}", extension),
+                ]
+            );
+
+            // Loading the loader reads every part, with paths relative to the parts
+            let loaded = Baseline::load(&loader).unwrap();
+            assert_eq!(loaded.entries.len(), 3);
+            for entry in &loaded.entries {
+                assert!(baseline.entries.iter().any(|e| e.message == entry.message
+                    && Path::new(&e.path) == std::path::absolute(&entry.path).unwrap()));
+            }
+
+            // Splitting again by directory replaces the identifier files
+            let written = baseline.save_split(&loader, SplitBy::Directory).unwrap();
+            assert_eq!(written.len(), 3);
+            let parts = fs::read_dir(temp_dir.path().join("phpstan-baseline")).unwrap().count();
+            assert_eq!(parts, if extension == "neon" { 2 } else { 4 });
+        }
+    }
+
+    #[test]
+    fn test_save_split_keeps_other_files() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let loader = temp_dir.path().join("phpstan-baseline.neon");
+        let parts_dir = temp_dir.path().join("phpstan-baseline");
+        fs::create_dir(&parts_dir).unwrap();
+        let other = parts_dir.join("rules.neon");
+        fs::write(&other, "parameters: []\n").unwrap();
+        let baseline = Baseline {
+            entries: vec![BaselineEntry::new("#^Error$#".to_string(), 1, "a.php".to_string(), Some("argument.type".to_string()))],
+        };
+
+        // Without a loader listing it, the file isn't replaced or removed
+        let error = baseline.save_split(&loader, SplitBy::Identifier).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+        assert!(other.exists());
+        assert!(!loader.exists());
+
+        // A file added after a split isn't removed by the next one either
+        fs::remove_file(&other).unwrap();
+        baseline.save_split(&loader, SplitBy::Identifier).unwrap();
+        fs::write(&other, "parameters: []\n").unwrap();
+        assert!(baseline.save_split(&loader, SplitBy::Directory).is_err());
+        assert!(other.exists());
+        assert!(parts_dir.join("argument.type.neon").exists());
+    }
+
+    #[test]
+    fn test_split_by_from_str() {
+        assert_eq!("identifier".parse::<SplitBy>(), Ok(SplitBy::Identifier));
+        assert_eq!("directory".parse::<SplitBy>(), Ok(SplitBy::Directory));
+        assert!("file".parse::<SplitBy>().is_err());
+    }
+
     #[test]
     fn test_escape_regex() {
         assert_eq!(
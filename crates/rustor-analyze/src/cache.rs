@@ -0,0 +1,332 @@
+//! Incremental analysis result cache
+//!
+//! Stores the issues found in each file together with the symbols its
+//! analysis looked up in the symbol table and a signature of each. A file is
+//! analyzed again only when its content changed, when the configuration or
+//! level changed, or when one of those symbols was added, removed or changed.
+
+use crate::config::PhpStanConfig;
+use crate::issue::Issue;
+use crate::symbols::{ClassInfo, FunctionInfo, Lookups, SymbolTable};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use xxhash_rust::xxh3::xxh3_64;
+
+// Bump to invalidate caches written by older versions
+const CACHE_VERSION: u32 = 1;
+const CACHE_DIR: &str = ".rustor-cache";
+const CACHE_FILE: &str = "analysis.json";
+
+/// Kind of symbol a cached result depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SymbolKind {
+    Class,
+    Function,
+    Constant,
+}
+
+/// A symbol looked up while analyzing a file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Dependency {
+    kind: SymbolKind,
+    /// Lookup key (lowercase for classes and functions)
+    name: String,
+    /// Signature at analysis time, `None` if the symbol was undefined
+    signature: Option<u64>,
+}
+
+/// Cached analysis result of a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedFile {
+    content_hash: u64,
+    issues: Vec<Issue>,
+    dependencies: Vec<Dependency>,
+}
+
+/// On-disk cache format
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedAnalysis {
+    version: u32,
+    config_hash: u64,
+    files: BTreeMap<PathBuf, CachedFile>,
+}
+
+/// Per-file analysis results kept between runs
+pub struct AnalysisCache {
+    cache_dir: PathBuf,
+    config_hash: u64,
+    files: BTreeMap<PathBuf, CachedFile>,
+    /// Signatures computed during this run, shared between files; a loaded
+    /// cache is only ever checked against one symbol table
+    signatures: Mutex<HashMap<(SymbolKind, String), Option<u64>>>,
+}
+
+impl AnalysisCache {
+    /// Load the cache of a project directory
+    ///
+    /// Results written with a different configuration, level or cache version
+    /// are discarded.
+    pub fn load(project_dir: &Path, config: &PhpStanConfig) -> Self {
+        let config_hash = config_hash(config);
+        let cache_dir = project_dir.join(CACHE_DIR);
+
+        let files = fs::read_to_string(cache_dir.join(CACHE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str::<CachedAnalysis>(&content).ok())
+            .filter(|cached| cached.version == CACHE_VERSION && cached.config_hash == config_hash)
+            .map(|cached| cached.files)
+            .unwrap_or_default();
+
+        Self {
+            cache_dir,
+            config_hash,
+            files,
+            signatures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached issues for a file, if neither its content nor its dependencies changed
+    pub fn get(&self, path: &Path, source: &str, table: &SymbolTable) -> Option<Vec<Issue>> {
+        let cached = self.files.get(path)?;
+        if cached.content_hash != xxh3_64(source.as_bytes()) {
+            return None;
+        }
+
+        let unchanged = cached
+            .dependencies
+            .iter()
+            .all(|dep| self.signature(table, dep.kind, &dep.name) == dep.signature);
+        unchanged.then(|| cached.issues.clone())
+    }
+
+    /// Build the cache entry for a freshly analyzed file
+    pub fn entry(&self, source: &str, issues: &[Issue], lookups: &Lookups, table: &SymbolTable) -> CachedFile {
+        let kinds = [
+            (SymbolKind::Class, &lookups.classes),
+            (SymbolKind::Function, &lookups.functions),
+            (SymbolKind::Constant, &lookups.constants),
+        ];
+        let dependencies = kinds
+            .into_iter()
+            .flat_map(|(kind, names)| names.iter().map(move |name| (kind, name)))
+            .map(|(kind, name)| Dependency {
+                kind,
+                name: name.clone(),
+                signature: self.signature(table, kind, name),
+            })
+            .collect();
+
+        CachedFile {
+            content_hash: xxh3_64(source.as_bytes()),
+            issues: issues.to_vec(),
+            dependencies,
+        }
+    }
+
+    /// Store the result of a file
+    pub fn insert(&mut self, path: PathBuf, entry: CachedFile) {
+        self.files.insert(path, entry);
+    }
+
+    /// Write the cache, dropping entries of files that no longer exist
+    pub fn save(mut self) -> std::io::Result<()> {
+        self.files.retain(|path, _| path.exists());
+
+        let cached = CachedAnalysis {
+            version: CACHE_VERSION,
+            config_hash: self.config_hash,
+            files: self.files,
+        };
+        let content = serde_json::to_string(&cached)?;
+
+        fs::create_dir_all(&self.cache_dir)?;
+        fs::write(self.cache_dir.join(CACHE_FILE), content)?;
+
+        Ok(())
+    }
+
+    fn signature(&self, table: &SymbolTable, kind: SymbolKind, name: &str) -> Option<u64> {
+        let key = (kind, name.to_string());
+        if let Some(signature) = self.signatures.lock().unwrap().get(&key) {
+            return *signature;
+        }
+
+        let signature = symbol_signature(table, kind, name);
+        self.signatures.lock().unwrap().insert(key, signature);
+        signature
+    }
+}
+
+/// Hash of everything besides the file itself that affects its issues
+fn config_hash(config: &PhpStanConfig) -> u64 {
+    let text = format!("{}|{:?}", env!("CARGO_PKG_VERSION"), config);
+    xxh3_64(text.as_bytes())
+}
+
+/// Signature of a symbol by lookup key, `None` if it is not defined
+pub(crate) fn symbol_signature(table: &SymbolTable, kind: SymbolKind, name: &str) -> Option<u64> {
+    let text = match kind {
+        SymbolKind::Class => class_signature(table.get_class(name)?),
+        SymbolKind::Function => function_signature(table.get_function(name)?),
+        SymbolKind::Constant => format!("{:?}", table.get_constant(name)?),
+    };
+    Some(xxh3_64(text.as_bytes()))
+}
+
+/// Everything checks can observe about a class, with members in name order
+///
+/// The declaration line is left out so that edits above a class don't
+/// invalidate every file using it.
+fn class_signature(class: &ClassInfo) -> String {
+    let ClassInfo {
+        name,
+        full_name,
+        namespace,
+        kind,
+        parent,
+        interfaces,
+        traits,
+        properties,
+        methods,
+        constants,
+        cases,
+        backing_type,
+        is_abstract,
+        is_final,
+        is_readonly,
+        is_immutable,
+        deprecated,
+        file,
+        line: _,
+    } = class;
+
+    format!(
+        "{name}|{full_name}|{namespace:?}|{kind:?}|{parent:?}|{interfaces:?}|{traits:?}|{:?}|{:?}|{:?}|{cases:?}|{backing_type:?}|{is_abstract}|{is_final}|{is_readonly}|{is_immutable}|{deprecated:?}|{file:?}",
+        sorted(properties),
+        sorted(methods),
+        sorted(constants),
+    )
+}
+
+/// Everything checks can observe about a function
+fn function_signature(function: &FunctionInfo) -> String {
+    let FunctionInfo {
+        name,
+        full_name,
+        namespace,
+        parameters,
+        return_type,
+        returns_reference,
+        deprecated,
+        throws,
+        file,
+        line: _,
+    } = function;
+
+    format!("{name}|{full_name}|{namespace:?}|{parameters:?}|{return_type:?}|{returns_reference}|{deprecated:?}|{throws:?}|{file:?}")
+}
+
+fn sorted<V>(map: &HashMap<String, V>) -> BTreeMap<&String, &V> {
+    map.iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Level;
+    use crate::symbols::class_info::ClassMethodInfo;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = "<?php\n(new Foo())->bar();\n";
+
+    fn table(methods: &[&str]) -> SymbolTable {
+        let mut table = SymbolTable::new();
+        let mut class = ClassInfo::from_fqn("Foo");
+        for method in methods {
+            class.add_method(ClassMethodInfo::new(*method));
+        }
+        table.register_class(class);
+        table
+    }
+
+    fn load(dir: &Path) -> AnalysisCache {
+        AnalysisCache::load(dir, &PhpStanConfig::default())
+    }
+
+    /// Save the result of analyzing SOURCE against `table`, returning the file path
+    fn save_entry(dir: &Path, table: &SymbolTable) -> PathBuf {
+        let path = dir.join("a.php");
+        fs::write(&path, SOURCE).unwrap();
+
+        let mut cache = load(dir);
+        let (_, lookups) = SymbolTable::record_lookups(|| {
+            table.class_has_method("Foo", "bar");
+            table.function_exists("helper");
+        });
+        let issues = vec![Issue::error("test", "Call to undefined method Foo::bar().", path.clone(), 2, 1)];
+        let entry = cache.entry(SOURCE, &issues, &lookups, table);
+        cache.insert(path.clone(), entry);
+        cache.save().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_unchanged_file_reused() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        let issues = load(dir.path()).get(&path, SOURCE, &table(&[])).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(load(dir.path()).get(&dir.path().join("b.php"), SOURCE, &table(&[])).is_none());
+    }
+
+    #[test]
+    fn test_content_change_invalidates() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        assert!(load(dir.path()).get(&path, "<?php\n", &table(&[])).is_none());
+    }
+
+    #[test]
+    fn test_dependency_change_invalidates() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        assert!(load(dir.path()).get(&path, SOURCE, &table(&["bar"])).is_none());
+    }
+
+    #[test]
+    fn test_defining_missing_symbol_invalidates() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        let mut with_helper = table(&[]);
+        with_helper.register_function(FunctionInfo::from_fqn("helper"));
+        assert!(load(dir.path()).get(&path, SOURCE, &with_helper).is_none());
+    }
+
+    #[test]
+    fn test_config_change_discards_cache() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        let config = PhpStanConfig { level: Level::Level5, ..Default::default() };
+        let cache = AnalysisCache::load(dir.path(), &config);
+        assert!(cache.get(&path, SOURCE, &table(&[])).is_none());
+    }
+
+    #[test]
+    fn test_deleted_files_pruned() {
+        let dir = TempDir::new().unwrap();
+        let path = save_entry(dir.path(), &table(&[]));
+
+        fs::remove_file(&path).unwrap();
+        load(dir.path()).save().unwrap();
+        assert!(load(dir.path()).files.is_empty());
+    }
+}
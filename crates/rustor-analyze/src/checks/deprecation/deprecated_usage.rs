@@ -0,0 +1,684 @@
+//! Check for usage of deprecated functions, classes and class members
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::symbols::class_info::{ClassConstantInfo, ClassMethodInfo, ClassPropertyInfo};
+use crate::symbols::{ClassInfo, FunctionInfo};
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Checks for calls, instantiations, inheritance, constant fetches and
+/// property access that touch symbols marked `@deprecated` or `#[\Deprecated]`
+pub struct DeprecatedUsageCheck;
+
+impl Check for DeprecatedUsageCheck {
+    fn id(&self) -> &'static str {
+        "deprecated.usage"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects usage of deprecated functions, classes, methods, constants and properties"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = DeprecationVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            current_class: None,
+            in_deprecated_scope: false,
+            variable_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+/// Append the deprecation description to a message, PHPStan style
+fn with_description(message: String, description: &str) -> String {
+    if description.is_empty() {
+        format!("{}.", message)
+    } else {
+        format!("{}:\n{}", message, description)
+    }
+}
+
+struct DeprecationVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// FQN of the class-like we're inside (for self::/static::/parent::)
+    current_class: Option<String>,
+    /// Deprecated code may freely use other deprecated code
+    in_deprecated_scope: bool,
+    /// Known class of variables (typed parameters, `$x = new Foo()`, `$this`)
+    variable_types: HashMap<String, String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> DeprecationVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn report(&mut self, identifier: &str, message: String, offset: u32) {
+        if self.in_deprecated_scope {
+            return;
+        }
+        let (line, col) = self.get_line_col(offset as usize);
+        self.issues.push(
+            Issue::error(identifier, message, self.file_path.clone(), line, col).with_identifier(identifier),
+        );
+    }
+
+    /// Resolve a class reference to a fully qualified name
+    fn resolve_class_name(&self, name: &str) -> Option<String> {
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+    }
+
+    /// Resolve the class referenced by the left side of `::`
+    fn resolve_class_expression(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Identifier(ident) => self.resolve_class_name(self.get_span_text(&ident.span())),
+            Expression::Self_(_) | Expression::Static(_) => self.current_class.clone(),
+            Expression::Parent(_) => self.resolve_class_name("parent"),
+            _ => None,
+        }
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Look up a function, falling back to the global namespace like PHP does
+    fn find_function(&self, name: &str) -> Option<&FunctionInfo> {
+        self.symbols.find_function(name, self.current_namespace.as_deref())
+    }
+
+    /// Walk the class hierarchy (parents, traits, interfaces) looking for a member
+    fn find_in_hierarchy<'t, T>(
+        &'t self,
+        class_name: &str,
+        find: &dyn Fn(&'t ClassInfo) -> Option<&'t T>,
+    ) -> Option<(&'t ClassInfo, &'t T)> {
+        let mut visited = HashSet::new();
+        let mut queue = vec![class_name.to_string()];
+        while let Some(name) = queue.pop() {
+            if !visited.insert(name.to_lowercase()) {
+                continue;
+            }
+            let Some(info) = self.find_class(&name) else {
+                continue;
+            };
+            if let Some(member) = find(info) {
+                return Some((info, member));
+            }
+            queue.extend(info.interfaces.iter().cloned());
+            queue.extend(info.traits.iter().cloned());
+            queue.extend(info.parent.iter().cloned());
+        }
+        None
+    }
+
+    fn find_method(&self, class_name: &str, method: &str) -> Option<(&ClassInfo, &ClassMethodInfo)> {
+        self.find_in_hierarchy(class_name, &|info: &ClassInfo| info.get_method(method))
+    }
+
+    fn find_property(&self, class_name: &str, property: &str) -> Option<(&ClassInfo, &ClassPropertyInfo)> {
+        self.find_in_hierarchy(class_name, &|info: &ClassInfo| info.get_property(property))
+    }
+
+    fn find_constant(&self, class_name: &str, constant: &str) -> Option<(&ClassInfo, &ClassConstantInfo)> {
+        self.find_in_hierarchy(class_name, &|info: &ClassInfo| info.get_constant(constant))
+    }
+
+    /// Class of the object an instance member is accessed on, if known
+    fn object_class(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self.variable_types.get(var.name).cloned(),
+            Expression::Parenthesized(paren) => self.object_class(paren.expression),
+            Expression::Instantiation(inst) => self.resolve_class_expression(inst.class),
+            _ => None,
+        }
+    }
+
+    /// Class named by a parameter type hint (nullable hints included)
+    fn hint_class(&self, hint: &Hint<'_>) -> Option<String> {
+        let text = self.get_span_text(&hint.span()).trim_start_matches('?');
+        if text.contains(['|', '&', '(']) {
+            return None;
+        }
+        let lower = text.to_lowercase();
+        if matches!(
+            lower.as_str(),
+            "int" | "float" | "string" | "bool" | "array" | "callable" | "iterable" | "object" | "mixed"
+                | "void" | "null" | "never" | "false" | "true"
+        ) {
+            return None;
+        }
+        self.resolve_class_name(text)
+    }
+
+    /// Record the classes of typed parameters
+    fn bind_parameters(&mut self, parameter_list: &FunctionLikeParameterList<'_>) {
+        for param in parameter_list.parameters.iter() {
+            if let Some(class) = param.hint.as_ref().and_then(|h| self.hint_class(h)) {
+                self.variable_types.insert(param.variable.name.to_string(), class);
+            }
+        }
+    }
+
+    fn class_like_name(&self, name: &LocalIdentifier<'_>) -> String {
+        let short_name = self.get_span_text(&name.span);
+        match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name.to_string(),
+        }
+    }
+
+    /// Visit the members of a class-like with class and method scope set
+    fn visit_class_like_members<'a>(&mut self, fqn: String, members: &Sequence<'a, ClassLikeMember<'a>>) {
+        let class_deprecated = self.find_class(&fqn).is_some_and(|c| c.deprecated.is_some());
+        let previous_class = self.current_class.replace(fqn.clone());
+        let previous_scope = self.in_deprecated_scope;
+        self.in_deprecated_scope = previous_scope || class_deprecated;
+
+        for member in members.iter() {
+            match member {
+                ClassLikeMember::TraitUse(trait_use) => {
+                    for trait_name in trait_use.trait_names.iter() {
+                        self.check_trait_use(&fqn, trait_name);
+                    }
+                }
+                ClassLikeMember::Method(method) => {
+                    let method_name = self.get_span_text(&method.name.span).to_string();
+                    let method_deprecated = self
+                        .find_class(&fqn)
+                        .and_then(|c| c.get_method(&method_name))
+                        .is_some_and(|m| m.deprecated.is_some());
+                    let previous_variables = std::mem::take(&mut self.variable_types);
+                    let previous_method_scope = self.in_deprecated_scope;
+                    self.in_deprecated_scope = previous_method_scope || method_deprecated;
+                    self.variable_types.insert("$this".to_string(), fqn.clone());
+                    self.bind_parameters(&method.parameter_list);
+                    self.traverse_class_like_member(member, self.source);
+                    self.in_deprecated_scope = previous_method_scope;
+                    self.variable_types = previous_variables;
+                }
+                _ => self.traverse_class_like_member(member, self.source),
+            }
+        }
+
+        self.in_deprecated_scope = previous_scope;
+        self.current_class = previous_class;
+    }
+
+    fn check_extends(&mut self, kind: &str, fqn: &str, parent: &Identifier<'_>, implements: bool) {
+        let Some(parent_name) = self.resolve_class_name(self.get_span_text(&parent.span())) else {
+            return;
+        };
+        let Some(parent_info) = self.find_class(&parent_name) else {
+            return;
+        };
+        let Some(description) = parent_info.deprecated.clone() else {
+            return;
+        };
+        let is_interface = parent_info.kind == crate::symbols::ClassKind::Interface;
+        let target = if is_interface { "interface" } else { "class" };
+        let verb = if implements { "implements" } else { "extends" };
+        let identifier = match (kind, is_interface) {
+            ("Interface", _) => "interface.extendsDeprecatedInterface",
+            (_, true) => "class.implementsDeprecatedInterface",
+            _ => "class.extendsDeprecatedClass",
+        };
+        let message = format!("{} {} {} deprecated {} {}", kind, fqn, verb, target, parent_info.full_name);
+        self.report(identifier, with_description(message, &description), parent.span().start.offset);
+    }
+
+    fn check_trait_use(&mut self, fqn: &str, trait_name: &Identifier<'_>) {
+        let Some(trait_fqn) = self.resolve_class_name(self.get_span_text(&trait_name.span())) else {
+            return;
+        };
+        let Some(info) = self.find_class(&trait_fqn) else {
+            return;
+        };
+        if let Some(description) = info.deprecated.clone() {
+            let message = format!("Usage of deprecated trait {} in class {}", info.full_name, fqn);
+            self.report("traitUse.deprecated", with_description(message, &description), trait_name.span().start.offset);
+        }
+    }
+
+    fn check_function_call(&mut self, call: &FunctionCall<'_>) {
+        let Expression::Identifier(ident) = call.function else {
+            return;
+        };
+        let name = self.get_span_text(&ident.span());
+        let Some(info) = self.find_function(name) else {
+            return;
+        };
+        if let Some(description) = info.deprecated.clone() {
+            let message = format!("Call to deprecated function {}()", info.full_name);
+            self.report("function.deprecated", with_description(message, &description), ident.span().start.offset);
+        }
+    }
+
+    fn check_method_call(&mut self, object: &Expression<'_>, selector: &ClassLikeMemberSelector<'_>) {
+        let ClassLikeMemberSelector::Identifier(ident) = selector else {
+            return;
+        };
+        let Some(class_name) = self.object_class(object) else {
+            return;
+        };
+        let method_name = self.get_span_text(&ident.span);
+        if let Some((class, method)) = self.find_method(&class_name, method_name) {
+            if let Some(description) = method.deprecated.clone() {
+                let message = format!("Call to deprecated method {}() of class {}", method.name, class.full_name);
+                self.report("method.deprecated", with_description(message, &description), ident.span.start.offset);
+            }
+        }
+    }
+
+    fn check_static_method_call(&mut self, call: &StaticMethodCall<'_>) {
+        let ClassLikeMemberSelector::Identifier(ident) = &call.method else {
+            return;
+        };
+        let Some(class_name) = self.resolve_class_expression(call.class) else {
+            return;
+        };
+        let method_name = self.get_span_text(&ident.span);
+        if let Some((class, method)) = self.find_method(&class_name, method_name) {
+            if let Some(description) = method.deprecated.clone() {
+                let message = format!("Call to deprecated method {}() of class {}", method.name, class.full_name);
+                self.report("staticMethod.deprecated", with_description(message, &description), ident.span.start.offset);
+            }
+        }
+    }
+
+    fn check_instantiation(&mut self, inst: &Instantiation<'_>) {
+        let Expression::Identifier(ident) = inst.class else {
+            return;
+        };
+        let Some(class_name) = self.resolve_class_name(self.get_span_text(&ident.span())) else {
+            return;
+        };
+        let Some(info) = self.find_class(&class_name) else {
+            return;
+        };
+        if let Some(description) = info.deprecated.clone() {
+            let message = format!("Instantiation of deprecated class {}", info.full_name);
+            self.report("new.deprecated", with_description(message, &description), ident.span().start.offset);
+        }
+    }
+
+    fn check_class_constant(&mut self, access: &ClassConstantAccess<'_>) {
+        let ClassLikeConstantSelector::Identifier(ident) = &access.constant else {
+            return;
+        };
+        let Some(class_name) = self.resolve_class_expression(access.class) else {
+            return;
+        };
+        let constant_name = self.get_span_text(&ident.span);
+
+        let found = if let Some((class, case)) = self.find_in_hierarchy(&class_name, &|info: &ClassInfo| info.get_case(constant_name)) {
+            case.deprecated
+                .clone()
+                .map(|d| (format!("Fetching deprecated enum case {} of enum {}", case.name, class.full_name), d))
+        } else {
+            self.find_constant(&class_name, constant_name).and_then(|(class, constant)| {
+                constant.deprecated.clone().map(|d| {
+                    (format!("Fetching deprecated class constant {} of class {}", constant.name, class.full_name), d)
+                })
+            })
+        };
+
+        if let Some((message, description)) = found {
+            self.report("classConstant.deprecated", with_description(message, &description), ident.span.start.offset);
+        }
+    }
+
+    fn check_property_access(&mut self, object: &Expression<'_>, selector: &ClassLikeMemberSelector<'_>) {
+        let ClassLikeMemberSelector::Identifier(ident) = selector else {
+            return;
+        };
+        let Some(class_name) = self.object_class(object) else {
+            return;
+        };
+        let property_name = self.get_span_text(&ident.span);
+        if let Some((class, property)) = self.find_property(&class_name, property_name) {
+            if let Some(description) = property.deprecated.clone() {
+                let message = format!("Access to deprecated property ${} of class {}", property.name, class.full_name);
+                self.report("property.deprecated", with_description(message, &description), ident.span.start.offset);
+            }
+        }
+    }
+
+    fn check_static_property_access(&mut self, access: &StaticPropertyAccess<'_>) {
+        let Variable::Direct(var) = &access.property else {
+            return;
+        };
+        let Some(class_name) = self.resolve_class_expression(access.class) else {
+            return;
+        };
+        let property_name = var.name.trim_start_matches('$');
+        if let Some((class, property)) = self.find_property(&class_name, property_name) {
+            if let Some(description) = property.deprecated.clone() {
+                let message = format!(
+                    "Access to deprecated static property ${} of class {}",
+                    property.name, class.full_name
+                );
+                self.report("staticProperty.deprecated", with_description(message, &description), var.span.start.offset);
+            }
+        }
+    }
+}
+
+impl<'a, 's> Visitor<'a> for DeprecationVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let name = self.get_span_text(&func.name.span);
+                let deprecated = self.find_function(name).is_some_and(|f| f.deprecated.is_some());
+                let previous_variables = std::mem::take(&mut self.variable_types);
+                let previous_scope = self.in_deprecated_scope;
+                self.in_deprecated_scope = previous_scope || deprecated;
+                self.bind_parameters(&func.parameter_list);
+                for inner in func.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.in_deprecated_scope = previous_scope;
+                self.variable_types = previous_variables;
+                false
+            }
+            Statement::Class(class) => {
+                let fqn = self.class_like_name(&class.name);
+                let deprecated = self.find_class(&fqn).is_some_and(|c| c.deprecated.is_some());
+                let previous_scope = self.in_deprecated_scope;
+                self.in_deprecated_scope = previous_scope || deprecated;
+                if let Some(extends) = &class.extends {
+                    for parent in extends.types.iter() {
+                        self.check_extends("Class", &fqn, parent, false);
+                    }
+                }
+                if let Some(implements) = &class.implements {
+                    for iface in implements.types.iter() {
+                        self.check_extends("Class", &fqn, iface, true);
+                    }
+                }
+                self.in_deprecated_scope = previous_scope;
+                self.visit_class_like_members(fqn, &class.members);
+                false
+            }
+            Statement::Interface(interface) => {
+                let fqn = self.class_like_name(&interface.name);
+                let deprecated = self.find_class(&fqn).is_some_and(|c| c.deprecated.is_some());
+                let previous_scope = self.in_deprecated_scope;
+                self.in_deprecated_scope = previous_scope || deprecated;
+                if let Some(extends) = &interface.extends {
+                    for parent in extends.types.iter() {
+                        self.check_extends("Interface", &fqn, parent, false);
+                    }
+                }
+                self.in_deprecated_scope = previous_scope;
+                self.visit_class_like_members(fqn, &interface.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                let fqn = self.class_like_name(&enum_def.name);
+                let deprecated = self.find_class(&fqn).is_some_and(|c| c.deprecated.is_some());
+                let previous_scope = self.in_deprecated_scope;
+                self.in_deprecated_scope = previous_scope || deprecated;
+                if let Some(implements) = &enum_def.implements {
+                    for iface in implements.types.iter() {
+                        self.check_extends("Enum", &fqn, iface, true);
+                    }
+                }
+                self.in_deprecated_scope = previous_scope;
+                self.visit_class_like_members(fqn, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                let fqn = self.class_like_name(&trait_def.name);
+                self.visit_class_like_members(fqn, &trait_def.members);
+                false
+            }
+            Statement::Expression(expr_stmt) => {
+                // Track `$x = new Foo()` so later calls on $x can be resolved
+                if let Expression::Assignment(assign) = &expr_stmt.expression {
+                    if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                        match self.object_class(assign.rhs) {
+                            Some(class) => {
+                                self.variable_types.insert(var.name.to_string(), class);
+                            }
+                            None => {
+                                self.variable_types.remove(var.name);
+                            }
+                        }
+                    }
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, source: &str) -> bool {
+        match expr {
+            Expression::Call(Call::Function(call)) => self.check_function_call(call),
+            Expression::Call(Call::Method(call)) => self.check_method_call(call.object, &call.method),
+            Expression::Call(Call::NullSafeMethod(call)) => self.check_method_call(call.object, &call.method),
+            Expression::Call(Call::StaticMethod(call)) => self.check_static_method_call(call),
+            Expression::Instantiation(inst) => self.check_instantiation(inst),
+            Expression::Access(Access::ClassConstant(access)) => self.check_class_constant(access),
+            Expression::Access(Access::Property(access)) => self.check_property_access(access.object, &access.property),
+            Expression::Access(Access::NullSafeProperty(access)) => {
+                self.check_property_access(access.object, &access.property)
+            }
+            Expression::Access(Access::StaticProperty(access)) => self.check_static_property_access(access),
+            Expression::Closure(closure) => {
+                let previous = self.variable_types.clone();
+                self.bind_parameters(&closure.parameter_list);
+                for inner in closure.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.variable_types = previous;
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        run_check(&DeprecatedUsageCheck, source, &PhpStanConfig::default())
+    }
+
+    fn identifiers(issues: &[Issue]) -> Vec<&str> {
+        issues.iter().filter_map(|i| i.identifier.as_deref()).collect()
+    }
+
+    #[test]
+    fn test_deprecated_calls() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+/** @deprecated use render() instead */
+function old_render() {}
+
+class Mailer {
+    /** @deprecated */
+    public function send() {}
+
+    #[\Deprecated(message: "use create()", since: "2.1")]
+    public static function make() {}
+
+    public function deliver() {
+        $this->send();
+    }
+}
+
+function run(Mailer $mailer) {
+    old_render();
+    $mailer->send();
+    Mailer::make();
+    $other = new Mailer();
+    $other->deliver();
+}
+"#,
+        );
+        assert_eq!(
+            identifiers(&issues),
+            vec!["method.deprecated", "function.deprecated", "method.deprecated", "staticMethod.deprecated"]
+        );
+        assert_eq!(issues[1].message, "Call to deprecated function App\\old_render():\nuse render() instead");
+        assert_eq!(issues[2].message, "Call to deprecated method send() of class App\\Mailer.");
+        assert_eq!(
+            issues[3].message,
+            "Call to deprecated method make() of class App\\Mailer:\nsince 2.1, use create()"
+        );
+    }
+
+    #[test]
+    fn test_deprecated_classes() {
+        let issues = analyze(
+            r#"<?php
+/** @deprecated */
+class Legacy {}
+
+/** @deprecated */
+interface OldContract {}
+
+/** @deprecated */
+trait OldHelpers {}
+
+class Modern extends Legacy implements OldContract {
+    use OldHelpers;
+}
+
+$legacy = new Legacy();
+"#,
+        );
+        assert_eq!(
+            identifiers(&issues),
+            vec![
+                "class.extendsDeprecatedClass",
+                "class.implementsDeprecatedInterface",
+                "traitUse.deprecated",
+                "new.deprecated",
+            ]
+        );
+        assert_eq!(issues[0].message, "Class Modern extends deprecated class Legacy.");
+        assert_eq!(issues[1].message, "Class Modern implements deprecated interface OldContract.");
+    }
+
+    #[test]
+    fn test_deprecated_constants_and_properties() {
+        let issues = analyze(
+            r#"<?php
+enum Status {
+    case Active;
+    #[\Deprecated]
+    case Legacy;
+}
+
+class Config {
+    /** @deprecated use MODERN */
+    const OLD = 1;
+    const MODERN = 2;
+
+    /** @deprecated */
+    public $name;
+
+    /** @deprecated */
+    public static $instance;
+}
+
+function read(Config $config) {
+    $a = Config::OLD;
+    $b = Config::MODERN;
+    $c = Status::Legacy;
+    $d = $config->name;
+    $e = Config::$instance;
+}
+"#,
+        );
+        assert_eq!(
+            identifiers(&issues),
+            vec![
+                "classConstant.deprecated",
+                "classConstant.deprecated",
+                "property.deprecated",
+                "staticProperty.deprecated",
+            ]
+        );
+        assert_eq!(issues[0].message, "Fetching deprecated class constant OLD of class Config:\nuse MODERN");
+        assert_eq!(issues[1].message, "Fetching deprecated enum case Legacy of enum Status.");
+        assert_eq!(issues[2].message, "Access to deprecated property $name of class Config.");
+    }
+
+    #[test]
+    fn test_deprecated_scope_is_not_reported() {
+        let issues = analyze(
+            r#"<?php
+/** @deprecated */
+function old() {}
+
+/** @deprecated */
+function older() {
+    old();
+}
+
+/** @deprecated */
+class Wrapper {
+    public function call() {
+        old();
+    }
+}
+"#,
+        );
+        assert!(issues.is_empty());
+    }
+}
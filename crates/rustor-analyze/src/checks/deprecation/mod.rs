@@ -0,0 +1,17 @@
+//! Deprecation checks (phpstan-deprecation-rules equivalent)
+//!
+//! Not tied to a level: enabled when `phpstan-deprecation-rules` is included
+//! in the configuration or `--deprecations` is passed.
+//!
+//! - Calls to deprecated functions, methods and static methods
+//! - Instantiation of deprecated classes
+//! - Extending/implementing deprecated classes and interfaces, using deprecated traits
+//! - Fetching deprecated class constants and enum cases
+//! - Access to deprecated properties
+//! - Calls to functions deprecated by PHP for the configured `phpVersion`
+
+mod deprecated_usage;
+mod php_core;
+
+pub use deprecated_usage::DeprecatedUsageCheck;
+pub use php_core::PhpCoreDeprecationCheck;
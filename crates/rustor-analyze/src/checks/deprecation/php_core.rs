@@ -0,0 +1,327 @@
+//! Check for calls to functions deprecated by PHP itself
+//!
+//! Functions that were removed later are reported as deprecated before the
+//! removal and as `function.removed` from it on, since calling them is then a
+//! fatal error.
+
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::path::PathBuf;
+
+/// PHP version assumed when `phpVersion` isn't configured
+const LATEST_PHP_VERSION: u32 = 80400;
+
+/// Core functions deprecated in a PHP release: (name, deprecated since, suggestion)
+const DEPRECATED_FUNCTIONS: &[(&str, u32, &str)] = &[
+    // PHP 8.0
+    ("libxml_disable_entity_loader", 80000, "External entity loading is disabled by default since libxml 2.9."),
+    ("enchant_broker_set_dict_path", 80000, ""),
+    ("enchant_broker_get_dict_path", 80000, ""),
+    ("enchant_dict_add_to_personal", 80000, "Use enchant_dict_add() instead."),
+    ("enchant_dict_is_in_session", 80000, "Use enchant_dict_is_added() instead."),
+    ("zip_open", 80000, "Use ZipArchive instead."),
+    ("zip_read", 80000, "Use ZipArchive instead."),
+    ("zip_close", 80000, "Use ZipArchive instead."),
+    ("zip_entry_read", 80000, "Use ZipArchive instead."),
+    ("zip_entry_name", 80000, "Use ZipArchive instead."),
+    // PHP 8.1
+    ("date_sunrise", 80100, "Use date_sun_info() instead."),
+    ("date_sunset", 80100, "Use date_sun_info() instead."),
+    ("strftime", 80100, "Use date() or IntlDateFormatter::format() instead."),
+    ("gmstrftime", 80100, "Use gmdate() or IntlDateFormatter::format() instead."),
+    ("strptime", 80100, "Use date_parse_from_format() or IntlDateFormatter::parse() instead."),
+    ("mhash", 80100, "Use hash() instead."),
+    ("mhash_count", 80100, "Use hash_algos() instead."),
+    ("mhash_get_block_size", 80100, "Use the hash extension instead."),
+    ("mhash_get_hash_name", 80100, "Use the hash extension instead."),
+    ("mhash_keygen_s2k", 80100, "Use hash_pbkdf2() instead."),
+    // PHP 8.2
+    ("utf8_encode", 80200, "Use mb_convert_encoding() instead."),
+    ("utf8_decode", 80200, "Use mb_convert_encoding() instead."),
+    // PHP 8.4
+    ("lcg_value", 80400, "Use Random\\Randomizer::getFloat() instead."),
+    ("mysqli_ping", 80400, ""),
+    ("mysqli_kill", 80400, "Use KILL CONNECTION/QUERY SQL statement instead."),
+    ("mysqli_refresh", 80400, "Use FLUSH SQL statement instead."),
+    ("xml_set_object", 80400, "Pass callables with a bound object to the xml_set_*_handler() functions instead."),
+    ("intlcal_set", 80400, "Use IntlCalendar::setDate() or IntlCalendar::setDateTime() instead."),
+];
+
+/// Deprecated core functions that were removed later:
+/// (name, deprecated since, removed in, suggestion)
+const REMOVED_FUNCTIONS: &[(&str, u32, u32, &str)] = &[
+    // PHP 7.2
+    ("create_function", 70200, 80000, "Use anonymous functions instead."),
+    ("each", 70200, 80000, "Use a foreach loop instead."),
+    ("gmp_random", 70200, 80000, "Use gmp_random_bits() or gmp_random_range() instead."),
+    ("read_exif_data", 70200, 80000, "Use exif_read_data() instead."),
+    // PHP 7.4
+    ("money_format", 70400, 80000, "Use NumberFormatter::formatCurrency() instead."),
+    ("ezmlm_hash", 70400, 80000, ""),
+    ("restore_include_path", 70400, 80000, "Use ini_restore('include_path') instead."),
+    ("get_magic_quotes_gpc", 70400, 80000, "Magic quotes have been removed, it always returns false."),
+    ("get_magic_quotes_runtime", 70400, 80000, "Magic quotes have been removed, it always returns false."),
+    ("hebrevc", 70400, 80000, "Use nl2br(hebrev($str)) instead."),
+    ("convert_cyr_string", 70400, 80000, "Use mb_convert_encoding() or iconv() instead."),
+    ("is_real", 70400, 80000, "Use is_float() instead."),
+    // PHP 8.1
+    ("odbc_result_all", 80100, 80400, ""),
+];
+
+/// Format a PHP version id (e.g. 80100) as "8.1"
+fn format_version(version: u32) -> String {
+    format!("{}.{}", version / 10000, (version / 100) % 100)
+}
+
+/// Checks for calls to PHP core functions deprecated in the configured `phpVersion`
+pub struct PhpCoreDeprecationCheck;
+
+impl Check for PhpCoreDeprecationCheck {
+    fn id(&self) -> &'static str {
+        "function.deprecated"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects calls to functions deprecated by PHP for the configured phpVersion"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = PhpCoreVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            php_version: ctx.config.php_version.unwrap_or(LATEST_PHP_VERSION),
+            current_namespace: None,
+            local_functions: Vec::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.collect_local_functions(program);
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct PhpCoreVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    php_version: u32,
+    current_namespace: Option<String>,
+    /// Lowercased FQNs of functions declared in this file (they shadow core functions)
+    local_functions: Vec<String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> PhpCoreVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn collect_local_functions<'a>(&mut self, program: &Program<'a>) {
+        let mut namespace: Option<String> = None;
+        for stmt in program.statements.iter() {
+            match stmt {
+                Statement::Namespace(ns) => {
+                    namespace = ns.name.as_ref().map(|n| self.get_span_text(&n.span()).to_string());
+                    for inner in ns.statements().iter() {
+                        if let Statement::Function(func) = inner {
+                            self.add_local_function(namespace.as_deref(), func);
+                        }
+                    }
+                }
+                Statement::Function(func) => self.add_local_function(namespace.as_deref(), func),
+                _ => {}
+            }
+        }
+    }
+
+    fn add_local_function(&mut self, namespace: Option<&str>, func: &Function<'_>) {
+        let name = self.get_span_text(&func.name.span);
+        let fqn = match namespace {
+            Some(ns) => format!("{}\\{}", ns, name),
+            None => name.to_string(),
+        };
+        self.local_functions.push(fqn.to_lowercase());
+    }
+
+    fn check_call(&mut self, call: &FunctionCall<'_>) {
+        let Expression::Identifier(ident) = call.function else {
+            return;
+        };
+        let name = self.get_span_text(&ident.span());
+
+        // A namespaced function of the same name takes precedence over the core one
+        let global_name = match name.strip_prefix('\\') {
+            Some(global) => global,
+            None => {
+                if name.contains('\\') {
+                    return;
+                }
+                if let Some(ns) = &self.current_namespace {
+                    let local = format!("{}\\{}", ns, name).to_lowercase();
+                    if self.local_functions.contains(&local) {
+                        return;
+                    }
+                }
+                name
+            }
+        };
+        let lower = global_name.to_lowercase();
+        if self.local_functions.contains(&lower) {
+            return;
+        }
+
+        let deprecated = DEPRECATED_FUNCTIONS
+            .iter()
+            .map(|&(function, since, suggestion)| (function, since, None, suggestion));
+        let removed = REMOVED_FUNCTIONS
+            .iter()
+            .map(|&(function, since, removed, suggestion)| (function, since, Some(removed), suggestion));
+        let Some((function, since, removed, suggestion)) = deprecated.chain(removed).find(|(f, ..)| *f == lower) else {
+            return;
+        };
+        if since > self.php_version {
+            return;
+        }
+
+        let (identifier, message, mut description) = match removed {
+            Some(removed) if removed <= self.php_version => (
+                "function.removed",
+                format!("Call to function {}() that was removed from PHP", function),
+                format!("Removed in PHP {}.", format_version(removed)),
+            ),
+            _ => (
+                "function.deprecated",
+                format!("Call to deprecated function {}()", function),
+                format!("Deprecated since PHP {}.", format_version(since)),
+            ),
+        };
+        if !suggestion.is_empty() {
+            description.push(' ');
+            description.push_str(suggestion);
+        }
+
+        let (line, col) = self.get_line_col(ident.span().start.offset as usize);
+        self.issues.push(
+            Issue::error(
+                identifier,
+                format!("{}:\n{}", message, description),
+                self.file_path.clone(),
+                line,
+                col,
+            )
+            .with_identifier(identifier),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for PhpCoreVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        if let Statement::Namespace(ns) = stmt {
+            self.current_namespace = ns
+                .name
+                .as_ref()
+                .map(|name| self.get_span_text(&name.span()).to_string());
+        }
+        true
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        if let Expression::Call(Call::Function(call)) = expr {
+            self.check_call(call);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str, php_version: Option<u32>) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            php_version,
+            ..PhpStanConfig::default()
+        };
+        run_check(&PhpCoreDeprecationCheck, source, &config)
+    }
+
+    #[test]
+    fn test_respects_php_version() {
+        let source = "<?php\n$a = utf8_encode($s);\n$b = strftime('%Y');\n";
+
+        let issues = analyze(source, Some(80100));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Call to deprecated function strftime():\nDeprecated since PHP 8.1. Use date() or IntlDateFormatter::format() instead."
+        );
+
+        assert_eq!(analyze(source, Some(80200)).len(), 2);
+        assert_eq!(analyze(source, Some(70400)).len(), 0);
+        assert_eq!(analyze(source, None).len(), 2);
+    }
+
+    #[test]
+    fn test_removed_functions() {
+        let source = "<?php\n$f = create_function('$a', 'return $a;');\n";
+
+        let issues = analyze(source, Some(70400));
+        assert_eq!(issues[0].identifier.as_deref(), Some("function.deprecated"));
+
+        let issues = analyze(source, Some(80000));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].identifier.as_deref(), Some("function.removed"));
+        assert_eq!(
+            issues[0].message,
+            "Call to function create_function() that was removed from PHP:\nRemoved in PHP 8.0. Use anonymous functions instead."
+        );
+        assert!(analyze(source, Some(70100)).is_empty());
+    }
+
+    #[test]
+    fn test_user_function_shadows_core() {
+        let source = r#"<?php
+namespace App;
+
+function utf8_encode($s) { return $s; }
+
+utf8_encode('a');
+\utf8_encode('b');
+"#;
+        let issues = analyze(source, Some(80200));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 7);
+    }
+
+    #[test]
+    fn test_format_version() {
+        assert_eq!(format_version(80100), "8.1");
+        assert_eq!(format_version(70400), "7.4");
+    }
+}
@@ -0,0 +1,353 @@
+//! Check that checked exceptions are declared in `@throws` tags
+
+use super::throw_points::{ExceptionScope, ThrowPoint};
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+
+/// Checks `@throws` tags of functions and methods against what their bodies throw.
+///
+/// - `missingType.checkedException`: a checked exception escapes but isn't declared
+///   (`exceptions.check.missingCheckedExceptionInThrows`)
+/// - `throws.unusedType`: a declared exception is never thrown
+///   (`exceptions.check.tooWideThrowType`)
+pub struct CheckedExceptionCheck;
+
+impl Check for CheckedExceptionCheck {
+    fn id(&self) -> &'static str {
+        "missingType.checkedException"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects checked exceptions missing from @throws and declared exceptions that are never thrown"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let exceptions = &ctx.config.exceptions;
+        if !exceptions.check_missing_checked_exception_in_throws && !exceptions.check_too_wide_throw_type {
+            return Vec::new();
+        }
+
+        let mut visitor = CheckedExceptionVisitor {
+            scope: ExceptionScope::new(program, ctx),
+            check_missing: exceptions.check_missing_checked_exception_in_throws,
+            check_too_wide: exceptions.check_too_wide_throw_type,
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct CheckedExceptionVisitor<'s> {
+    scope: ExceptionScope<'s>,
+    check_missing: bool,
+    check_too_wide: bool,
+    issues: Vec<Issue>,
+}
+
+impl<'s> CheckedExceptionVisitor<'s> {
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let class_name = self.scope.declared_name(name);
+        let previous = self.scope.current_class.replace(class_name.clone());
+        for member in members.iter() {
+            let ClassLikeMember::Method(method) = member else {
+                continue;
+            };
+            // Abstract methods declare what implementations may throw
+            let MethodBody::Concrete(body) = &method.body else {
+                continue;
+            };
+            let method_name = self.scope.get_span_text(&method.name.span);
+            let declared = self
+                .scope
+                .find_class(&class_name)
+                .and_then(|info| info.get_method(method_name))
+                .and_then(|info| info.throws.clone())
+                .unwrap_or_default();
+
+            let (points, _) = self.scope.throw_points(&body.statements, Some(&method.parameter_list));
+            let subject = format!("Method {}::{}()", class_name, method_name);
+            self.check_function_like(&subject, &declared, &points, method.name.span.start.offset);
+        }
+        self.scope.current_class = previous;
+    }
+
+    fn visit_function(&mut self, func: &Function<'_>) {
+        let name = self.scope.get_span_text(&func.name.span);
+        let fqn = match &self.scope.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, name),
+            None => name.to_string(),
+        };
+        let declared = self
+            .scope
+            .find_function(&fqn)
+            .and_then(|info| info.throws.clone())
+            .unwrap_or_default();
+
+        let previous = self.scope.current_class.take();
+        let (points, _) = self.scope.throw_points(&func.body.statements, Some(&func.parameter_list));
+        self.scope.current_class = previous;
+
+        let subject = format!("Function {}()", fqn);
+        self.check_function_like(&subject, &declared, &points, func.name.span.start.offset);
+    }
+
+    fn check_function_like(&mut self, subject: &str, declared: &[String], points: &[ThrowPoint], name_offset: u32) {
+        if self.check_missing {
+            let mut reported: Vec<(String, u32)> = Vec::new();
+            for point in points {
+                let Some(class) = &point.class else {
+                    continue;
+                };
+                if !self.scope.is_checked(class) || declared.iter().any(|d| self.scope.is_subclass(class, d)) {
+                    continue;
+                }
+                let key = (class.to_lowercase(), point.offset);
+                if reported.contains(&key) {
+                    continue;
+                }
+                reported.push(key);
+
+                let (line, col) = self.scope.get_line_col(point.offset as usize);
+                self.issues.push(
+                    Issue::error(
+                        "missingType.checkedException",
+                        format!(
+                            "{} throws checked exception {} but it's missing from the PHPDoc @throws tag.",
+                            subject, class
+                        ),
+                        self.scope.file_path.clone(),
+                        line,
+                        col,
+                    )
+                    .with_identifier("missingType.checkedException"),
+                );
+            }
+        }
+
+        if self.check_too_wide {
+            // Implicit throw points don't justify a declared type
+            for declared_class in declared {
+                let thrown = points.iter().any(|point| {
+                    point.class.as_deref().is_some_and(|class| {
+                        self.scope.is_subclass(class, declared_class) || self.scope.is_subclass(declared_class, class)
+                    })
+                });
+                if thrown {
+                    continue;
+                }
+
+                let (line, col) = self.scope.get_line_col(name_offset as usize);
+                self.issues.push(
+                    Issue::error(
+                        "throws.unusedType",
+                        format!(
+                            "{} has {} in PHPDoc @throws tag but it's not thrown.",
+                            subject, declared_class
+                        ),
+                        self.scope.file_path.clone(),
+                        line,
+                        col,
+                    )
+                    .with_identifier("throws.unusedType"),
+                );
+            }
+        }
+    }
+}
+
+impl<'a, 's> Visitor<'a> for CheckedExceptionVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.scope.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.scope.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                self.visit_function(func);
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            Statement::Interface(_) => false,
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{ExceptionsConfig, PhpStanConfig};
+
+    fn analyze(source: &str, exceptions: ExceptionsConfig) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            exceptions,
+            ..PhpStanConfig::default()
+        };
+        run_check(&CheckedExceptionCheck, source, &config)
+    }
+
+    fn enabled() -> ExceptionsConfig {
+        ExceptionsConfig {
+            unchecked_exception_classes: vec!["LogicException".to_string(), "Error".to_string()],
+            check_missing_checked_exception_in_throws: true,
+            check_too_wide_throw_type: true,
+            ..ExceptionsConfig::default()
+        }
+    }
+
+    const SOURCE: &str = r#"<?php
+namespace App;
+
+class NotFound extends \RuntimeException {}
+
+class Repository {
+    /** @throws NotFound */
+    public function find(int $id): string {
+        throw new NotFound();
+    }
+
+    public function get(int $id): string {
+        return $this->find($id);
+    }
+
+    /** @throws NotFound */
+    public function safe(int $id): string {
+        try {
+            return $this->find($id);
+        } catch (NotFound $e) {
+            return '';
+        }
+    }
+
+    public function invalid(): void {
+        throw new \InvalidArgumentException();
+    }
+}
+
+function load(Repository $repo): string {
+    return $repo->find(1);
+}
+"#;
+
+    #[test]
+    fn test_disabled_by_default() {
+        assert!(analyze(SOURCE, ExceptionsConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_missing_checked_exception() {
+        let issues = analyze(SOURCE, enabled());
+        let missing: Vec<_> = issues
+            .iter()
+            .filter(|i| i.identifier.as_deref() == Some("missingType.checkedException"))
+            .collect();
+        assert_eq!(missing.len(), 2);
+        assert_eq!(
+            missing[0].message,
+            "Method App\\Repository::get() throws checked exception App\\NotFound but it's missing from the PHPDoc @throws tag."
+        );
+        assert_eq!(missing[0].line, 13);
+        assert_eq!(
+            missing[1].message,
+            "Function App\\load() throws checked exception App\\NotFound but it's missing from the PHPDoc @throws tag."
+        );
+    }
+
+    #[test]
+    fn test_unused_throws_type() {
+        let issues = analyze(SOURCE, enabled());
+        let unused: Vec<_> = issues
+            .iter()
+            .filter(|i| i.identifier.as_deref() == Some("throws.unusedType"))
+            .collect();
+        assert_eq!(unused.len(), 1);
+        assert_eq!(
+            unused[0].message,
+            "Method App\\Repository::safe() has App\\NotFound in PHPDoc @throws tag but it's not thrown."
+        );
+    }
+
+    #[test]
+    fn test_checked_exception_classes() {
+        let config = ExceptionsConfig {
+            checked_exception_classes: vec!["App\\DomainError".to_string()],
+            check_missing_checked_exception_in_throws: true,
+            ..ExceptionsConfig::default()
+        };
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+class DomainError extends \Exception {}
+
+function a(): void { throw new DomainError(); }
+function b(): void { throw new \RuntimeException(); }
+"#,
+            config,
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("Function App\\a()"));
+    }
+
+    #[test]
+    fn test_unchecked_classes_from_neon() {
+        // PHPStan's documented layout, with `check` before the class list
+        let neon = r#"
+parameters:
+    exceptions:
+        check:
+            missingCheckedExceptionInThrows: true
+        uncheckedExceptionClasses:
+            - LogicException
+            - App\Unchecked
+"#;
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("phpstan.neon");
+        std::fs::write(&path, neon).unwrap();
+        let exceptions = PhpStanConfig::load(&path).unwrap().exceptions;
+
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+class Unchecked extends \RuntimeException {}
+class Invalid extends Unchecked {}
+
+function a(): void { throw new \LogicException(); }
+function b(): void { throw new Invalid(); }
+function c(): void { throw new \RuntimeException(); }
+"#,
+            exceptions,
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("Function App\\c()"), "{}", issues[0].message);
+    }
+}
@@ -0,0 +1,196 @@
+//! Check for catch clauses that can never be reached (Level 4)
+
+use super::throw_points::{DeadCatch, ExceptionScope};
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+
+/// Checks for catch clauses whose exception type is never thrown in the try block
+pub struct DeadCatchCheck;
+
+impl Check for DeadCatchCheck {
+    fn id(&self) -> &'static str {
+        "catch.neverThrown"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects catch clauses for exceptions that are never thrown in the try block"
+    }
+
+    fn level(&self) -> u8 {
+        4
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = DeadCatchVisitor {
+            scope: ExceptionScope::new(program, ctx),
+            issues: Vec::new(),
+        };
+
+        // Top-level code; function and class bodies are analyzed as they're visited
+        let (_, dead) = visitor.scope.throw_points(&program.statements, None);
+        visitor.report(dead);
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct DeadCatchVisitor<'s> {
+    scope: ExceptionScope<'s>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> DeadCatchVisitor<'s> {
+    fn report(&mut self, dead: Vec<DeadCatch>) {
+        for catch in dead {
+            let (line, col) = self.scope.get_line_col(catch.offset as usize);
+            self.issues.push(
+                Issue::error(
+                    "catch.neverThrown",
+                    format!("Dead catch - {} is never thrown in the try block.", catch.class),
+                    self.scope.file_path.clone(),
+                    line,
+                    col,
+                )
+                .with_identifier("catch.neverThrown"),
+            );
+        }
+    }
+
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let previous = self.scope.current_class.replace(self.scope.declared_name(name));
+        for member in members.iter() {
+            if let ClassLikeMember::Method(method) = member {
+                if let MethodBody::Concrete(body) = &method.body {
+                    let (_, dead) = self.scope.throw_points(&body.statements, Some(&method.parameter_list));
+                    self.report(dead);
+                }
+            }
+            // Keep going to find closures inside method bodies
+            self.traverse_class_like_member(member, self.scope.source);
+        }
+        self.scope.current_class = previous;
+    }
+}
+
+impl<'a, 's> Visitor<'a> for DeadCatchVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.scope.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.scope.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let previous = self.scope.current_class.take();
+                let (_, dead) = self.scope.throw_points(&func.body.statements, Some(&func.parameter_list));
+                self.report(dead);
+                self.scope.current_class = previous;
+                true
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        if let Expression::Closure(closure) = expr {
+            let (_, dead) = self.scope.throw_points(&closure.body.statements, Some(&closure.parameter_list));
+            self.report(dead);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        run_check(&DeadCatchCheck, source, &PhpStanConfig::default())
+    }
+
+    #[test]
+    fn test_dead_catch_check_level() {
+        assert_eq!(DeadCatchCheck.level(), 4);
+    }
+
+    #[test]
+    fn test_dead_catch() {
+        let issues = analyze(
+            r#"<?php
+class NotFound extends RuntimeException {}
+class Invalid extends LogicException {}
+
+class Repository {
+    /** @throws NotFound */
+    public function find(): string { throw new NotFound(); }
+
+    public function get(): string {
+        try {
+            return $this->find();
+        } catch (Invalid $e) {
+            return 'invalid';
+        } catch (RuntimeException | InvalidArgumentException $e) {
+            return 'runtime';
+        }
+    }
+}
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "Dead catch - Invalid is never thrown in the try block.");
+        assert_eq!(issues[0].line, 12);
+        assert_eq!(
+            issues[1].message,
+            "Dead catch - InvalidArgumentException is never thrown in the try block."
+        );
+    }
+
+    #[test]
+    fn test_implicit_throw_points_keep_catch_alive() {
+        let issues = analyze(
+            r#"<?php
+class Invalid extends LogicException {}
+
+function run(callable $fn): void {
+    try {
+        $fn();
+        undocumented();
+    } catch (Invalid $e) {
+    }
+}
+
+$handler = function () {
+    try {
+        throw new RuntimeException();
+    } catch (Invalid $e) {
+    }
+};
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 15);
+    }
+}
@@ -0,0 +1,16 @@
+//! Exception checks based on `@throws` tags
+//!
+//! Throw points are collected per function body: `throw` expressions, plus calls to
+//! functions, methods and constructors with the exceptions from their `@throws` tag.
+//! Calls without a tag may throw anything unless `exceptions.implicitThrows` is off.
+//!
+//! - Checked exceptions missing from `@throws` (`exceptions.check.missingCheckedExceptionInThrows`)
+//! - `@throws` types that are never thrown (`exceptions.check.tooWideThrowType`)
+//! - Catch clauses for exceptions the try block never throws (level 4)
+
+mod checked_exception;
+mod dead_catch;
+mod throw_points;
+
+pub use checked_exception::CheckedExceptionCheck;
+pub use dead_catch::DeadCatchCheck;
@@ -0,0 +1,454 @@
+//! Throw point analysis shared by the exception checks
+//!
+//! A throw point is a place in a function body where an exception can leave it:
+//! a `throw` expression, or a call to a function, method or constructor.
+//! Calls contribute the classes declared in the callee's `@throws` tag; calls
+//! without a tag are *implicit* throw points that may throw anything (unless
+//! `exceptions.implicitThrows` is disabled).
+
+use crate::checks::{CheckContext, LocalSymbols};
+use crate::config::ExceptionsConfig;
+use crate::symbols::class_info::ClassMethodInfo;
+use crate::symbols::{ClassInfo, FunctionInfo};
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// A place where an exception may be thrown
+#[derive(Debug, Clone)]
+pub(crate) struct ThrowPoint {
+    /// Exception class, or `None` for an implicit throw point (may throw anything)
+    pub class: Option<String>,
+    /// Source offset of the throw or call
+    pub offset: u32,
+}
+
+/// A catch clause type that nothing in the try block can throw
+#[derive(Debug, Clone)]
+pub(crate) struct DeadCatch {
+    pub class: String,
+    pub offset: u32,
+}
+
+/// Resolution context for exception analysis within one file
+pub(crate) struct ExceptionScope<'s> {
+    pub source: &'s str,
+    pub file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    config: &'s ExceptionsConfig,
+    pub current_namespace: Option<String>,
+    /// FQN of the class-like we're inside (for self::/static::/$this)
+    pub current_class: Option<String>,
+}
+
+impl<'s> ExceptionScope<'s> {
+    pub fn new(program: &Program<'_>, ctx: &CheckContext<'s>) -> Self {
+        Self {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            config: &ctx.config.exceptions,
+            current_namespace: None,
+            current_class: None,
+        }
+    }
+
+    pub fn get_span_text(&self, span: &mago_span::Span) -> &'s str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    pub fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    /// FQN of a class-like declared in the current namespace
+    pub fn declared_name(&self, name: &LocalIdentifier<'_>) -> String {
+        let short_name = self.get_span_text(&name.span);
+        match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name.to_string(),
+        }
+    }
+
+    /// Resolve a class reference to a fully qualified name
+    pub fn resolve_class_name(&self, name: &str) -> Option<String> {
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    pub fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Look up a function, falling back to the global namespace like PHP does
+    pub fn find_function(&self, name: &str) -> Option<&FunctionInfo> {
+        self.symbols.find_function(name, self.current_namespace.as_deref())
+    }
+
+    /// Find a method in a class or its parents, traits and interfaces
+    pub fn find_method(&self, class_name: &str, method: &str) -> Option<&ClassMethodInfo> {
+        let mut visited = HashSet::new();
+        let mut queue = vec![class_name.to_string()];
+        while let Some(name) = queue.pop() {
+            if !visited.insert(name.to_lowercase()) {
+                continue;
+            }
+            let Some(info) = self.find_class(&name) else {
+                continue;
+            };
+            if let Some(found) = info.get_method(method) {
+                return Some(found);
+            }
+            queue.extend(info.interfaces.iter().cloned());
+            queue.extend(info.traits.iter().cloned());
+            queue.extend(info.parent.iter().cloned());
+        }
+        None
+    }
+
+    /// Whether `child` is `parent` or extends/implements it
+    pub fn is_subclass(&self, child: &str, parent: &str) -> bool {
+        let mut visited = HashSet::new();
+        let mut queue = vec![child.to_string()];
+        while let Some(name) = queue.pop() {
+            if name.eq_ignore_ascii_case(parent) {
+                return true;
+            }
+            if !visited.insert(name.to_lowercase()) {
+                continue;
+            }
+            if let Some(info) = self.find_class(&name) {
+                queue.extend(info.interfaces.iter().cloned());
+                queue.extend(info.parent.iter().cloned());
+            }
+        }
+        false
+    }
+
+    /// Whether an exception class must be declared in `@throws`.
+    ///
+    /// Unknown classes are never considered checked, since their hierarchy can't be verified.
+    pub fn is_checked(&self, class: &str) -> bool {
+        if self.find_class(class).is_none() {
+            return false;
+        }
+        if !self.config.checked_exception_classes.is_empty() {
+            return self
+                .config
+                .checked_exception_classes
+                .iter()
+                .any(|checked| self.is_subclass(class, checked));
+        }
+        !self
+            .config
+            .unchecked_exception_classes
+            .iter()
+            .any(|unchecked| self.is_subclass(class, unchecked))
+    }
+
+    /// Class names of a (possibly union) type hint
+    pub fn hint_classes(&self, hint: &Hint<'_>) -> Vec<String> {
+        match hint {
+            Hint::Union(union) => {
+                let mut classes = self.hint_classes(union.left);
+                classes.extend(self.hint_classes(union.right));
+                classes
+            }
+            Hint::Parenthesized(paren) => self.hint_classes(paren.hint),
+            Hint::Nullable(nullable) => self.hint_classes(nullable.hint),
+            _ => {
+                let text = self.get_span_text(&hint.span());
+                let lower = text.to_lowercase();
+                if matches!(
+                    lower.as_str(),
+                    "int" | "float" | "string" | "bool" | "array" | "callable" | "iterable" | "object"
+                        | "mixed" | "void" | "null" | "never" | "false" | "true"
+                ) {
+                    return Vec::new();
+                }
+                self.resolve_class_name(text).into_iter().collect()
+            }
+        }
+    }
+
+    /// Collect the throw points that escape `statements`, and any dead catch clauses within
+    pub fn throw_points(
+        &self,
+        statements: &Sequence<'_, Statement<'_>>,
+        parameters: Option<&FunctionLikeParameterList<'_>>,
+    ) -> (Vec<ThrowPoint>, Vec<DeadCatch>) {
+        let mut visitor = ThrowPointVisitor {
+            scope: self,
+            variable_types: HashMap::new(),
+            frames: vec![Vec::new()],
+            dead_catches: Vec::new(),
+        };
+        if let Some(class) = &self.current_class {
+            visitor.variable_types.insert("$this".to_string(), vec![class.clone()]);
+        }
+        if let Some(parameters) = parameters {
+            for param in parameters.parameters.iter() {
+                if let Some(hint) = &param.hint {
+                    let classes = self.hint_classes(hint);
+                    if !classes.is_empty() {
+                        visitor.variable_types.insert(param.variable.name.to_string(), classes);
+                    }
+                }
+            }
+        }
+
+        let points = visitor.collect(statements);
+        (points, visitor.dead_catches)
+    }
+}
+
+struct ThrowPointVisitor<'v, 's> {
+    scope: &'v ExceptionScope<'s>,
+    /// Known classes of variables (typed parameters, `$x = new Foo()`, catch variables)
+    variable_types: HashMap<String, Vec<String>>,
+    /// Throw points per enclosing try block (innermost last)
+    frames: Vec<Vec<ThrowPoint>>,
+    dead_catches: Vec<DeadCatch>,
+}
+
+impl<'v, 's> ThrowPointVisitor<'v, 's> {
+    fn collect(&mut self, statements: &Sequence<'_, Statement<'_>>) -> Vec<ThrowPoint> {
+        self.frames.push(Vec::new());
+        for stmt in statements.iter() {
+            self.traverse_statement(stmt, self.scope.source);
+        }
+        self.frames.pop().unwrap_or_default()
+    }
+
+    fn add(&mut self, class: Option<String>, offset: u32) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.push(ThrowPoint { class, offset });
+        }
+    }
+
+    /// Add throw points for a call to something with the given `@throws` declaration
+    fn add_declared(&mut self, throws: Option<Vec<String>>, offset: u32) {
+        match throws {
+            Some(classes) => {
+                for class in classes {
+                    self.add(Some(class), offset);
+                }
+            }
+            None => self.add_implicit(offset),
+        }
+    }
+
+    fn add_implicit(&mut self, offset: u32) {
+        if self.scope.config.implicit_throws {
+            self.add(None, offset);
+        }
+    }
+
+    /// Classes an expression may evaluate to, if known
+    fn expression_classes(&self, expr: &Expression<'_>) -> Option<Vec<String>> {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self.variable_types.get(var.name).cloned(),
+            Expression::Parenthesized(paren) => self.expression_classes(paren.expression),
+            Expression::Instantiation(inst) => self.class_reference(inst.class).map(|c| vec![c]),
+            _ => None,
+        }
+    }
+
+    /// Resolve the class named on the left side of `::` or after `new`
+    fn class_reference(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Identifier(ident) => self.scope.resolve_class_name(self.scope.get_span_text(&ident.span())),
+            Expression::Self_(_) | Expression::Static(_) => self.scope.current_class.clone(),
+            Expression::Parent(_) => self.scope.resolve_class_name("parent"),
+            _ => None,
+        }
+    }
+
+    fn method_throws(&self, classes: Option<Vec<String>>, selector: &ClassLikeMemberSelector<'_>) -> Option<Vec<String>> {
+        let ClassLikeMemberSelector::Identifier(ident) = selector else {
+            return None;
+        };
+        let name = self.scope.get_span_text(&ident.span);
+        let mut throws: Option<Vec<String>> = None;
+        for class in classes? {
+            let declared = self.scope.find_method(&class, name)?.throws.clone()?;
+            throws.get_or_insert_with(Vec::new).extend(declared);
+        }
+        throws
+    }
+
+    fn visit_throw(&mut self, throw: &Throw<'_>) {
+        let offset = throw.throw.span.start.offset;
+        match self.expression_classes(throw.exception) {
+            Some(classes) => {
+                for class in classes {
+                    self.add(Some(class), offset);
+                }
+            }
+            None => self.add_implicit(offset),
+        }
+    }
+
+    fn visit_try(&mut self, try_stmt: &Try<'_>) {
+        let try_points = self.collect(&try_stmt.block.statements);
+        let mut escaping = try_points.clone();
+
+        for clause in try_stmt.catch_clauses.iter() {
+            let types = self.scope.hint_classes(&clause.hint);
+
+            for (class, hint) in types.iter().zip(catch_hint_offsets(&clause.hint)) {
+                // Only judge catch types whose hierarchy we know
+                if self.scope.find_class(class).is_none() {
+                    continue;
+                }
+                let reachable = try_points.iter().any(|point| match &point.class {
+                    Some(thrown) => self.scope.is_subclass(thrown, class) || self.scope.is_subclass(class, thrown),
+                    None => true,
+                });
+                if !reachable {
+                    self.dead_catches.push(DeadCatch { class: class.clone(), offset: hint });
+                }
+            }
+
+            escaping.retain(|point| match &point.class {
+                Some(thrown) => !types.iter().any(|t| self.scope.is_subclass(thrown, t)),
+                None => !types.iter().any(|t| t.eq_ignore_ascii_case("Throwable")),
+            });
+
+            let previous = clause.variable.as_ref().map(|var| {
+                (var.name.to_string(), self.variable_types.insert(var.name.to_string(), types.clone()))
+            });
+            let catch_points = self.collect(&clause.block.statements);
+            if let Some((name, previous)) = previous {
+                match previous {
+                    Some(classes) => self.variable_types.insert(name, classes),
+                    None => self.variable_types.remove(&name),
+                };
+            }
+            if let Some(frame) = self.frames.last_mut() {
+                frame.extend(catch_points);
+            }
+        }
+
+        if let Some(finally) = &try_stmt.finally_clause {
+            let finally_points = self.collect(&finally.block.statements);
+            if let Some(frame) = self.frames.last_mut() {
+                frame.extend(finally_points);
+            }
+        }
+
+        if let Some(frame) = self.frames.last_mut() {
+            frame.extend(escaping);
+        }
+    }
+}
+
+/// Start offsets of the individual class names in a catch hint
+fn catch_hint_offsets(hint: &Hint<'_>) -> Vec<u32> {
+    match hint {
+        Hint::Union(union) => {
+            let mut offsets = catch_hint_offsets(union.left);
+            offsets.extend(catch_hint_offsets(union.right));
+            offsets
+        }
+        Hint::Parenthesized(paren) => catch_hint_offsets(paren.hint),
+        Hint::Nullable(nullable) => catch_hint_offsets(nullable.hint),
+        _ => vec![hint.span().start.offset],
+    }
+}
+
+impl<'a, 'v, 's> Visitor<'a> for ThrowPointVisitor<'v, 's> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            // Nested declarations have their own throw points
+            Statement::Function(_)
+            | Statement::Class(_)
+            | Statement::Interface(_)
+            | Statement::Trait(_)
+            | Statement::Enum(_) => false,
+            Statement::Try(try_stmt) => {
+                self.visit_try(try_stmt);
+                false
+            }
+            Statement::Expression(expr_stmt) => {
+                // Track `$x = new Foo()` so later calls and throws on $x can be resolved
+                if let Expression::Assignment(assign) = &expr_stmt.expression {
+                    if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                        match self.expression_classes(assign.rhs) {
+                            Some(classes) => {
+                                self.variable_types.insert(var.name.to_string(), classes);
+                            }
+                            None => {
+                                self.variable_types.remove(var.name);
+                            }
+                        }
+                    }
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            // Closures throw when called, not where they're defined
+            Expression::Closure(_) | Expression::ArrowFunction(_) => return false,
+            Expression::Throw(throw) => self.visit_throw(throw),
+            Expression::Call(Call::Function(call)) => {
+                let offset = call.function.span().start.offset;
+                let throws = match call.function {
+                    Expression::Identifier(ident) => self
+                        .scope
+                        .find_function(self.scope.get_span_text(&ident.span()))
+                        .and_then(|f| f.throws.clone()),
+                    _ => None,
+                };
+                self.add_declared(throws, offset);
+            }
+            Expression::Call(Call::Method(call)) => {
+                let throws = self.method_throws(self.expression_classes(call.object), &call.method);
+                self.add_declared(throws, call.method.span().start.offset);
+            }
+            Expression::Call(Call::NullSafeMethod(call)) => {
+                let throws = self.method_throws(self.expression_classes(call.object), &call.method);
+                self.add_declared(throws, call.method.span().start.offset);
+            }
+            Expression::Call(Call::StaticMethod(call)) => {
+                let classes = self.class_reference(call.class).map(|c| vec![c]);
+                let throws = self.method_throws(classes, &call.method);
+                self.add_declared(throws, call.method.span().start.offset);
+            }
+            Expression::Instantiation(inst) => {
+                let offset = inst.new.span.start.offset;
+                match self.class_reference(inst.class) {
+                    Some(class) if self.scope.find_class(&class).is_some() => {
+                        // Without a constructor, instantiation can't throw
+                        if let Some(constructor) = self.scope.find_method(&class, "__construct") {
+                            let throws = constructor.throws.clone();
+                            self.add_declared(throws, offset);
+                        }
+                    }
+                    _ => self.add_implicit(offset),
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
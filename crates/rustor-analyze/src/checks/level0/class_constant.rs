@@ -99,31 +99,7 @@ impl<'s> ClassConstantVisitor<'s> {
 
                 self.class_constants.insert(interface_name, constants);
             }
-            Statement::Enum(enum_def) => {
-                let enum_name = self.get_span_text(&enum_def.name.span).to_lowercase();
-                let mut constants = HashSet::new();
-
-                constants.insert("class".to_string());
-
-                // Enum cases are also accessible as constants
-                for member in enum_def.members.iter() {
-                    match member {
-                        ClassLikeMember::EnumCase(case) => {
-                            let case_name = self.get_span_text(&case.item.name().span).to_lowercase();
-                            constants.insert(case_name);
-                        }
-                        ClassLikeMember::Constant(const_member) => {
-                            for item in const_member.items.iter() {
-                                let const_name = self.get_span_text(&item.name.span).to_lowercase();
-                                constants.insert(const_name);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-
-                self.class_constants.insert(enum_name, constants);
-            }
+            // Enum case fetches are reported by EnumCaseCheck
             Statement::Namespace(ns) => match &ns.body {
                 NamespaceBody::Implicit(body) => {
                     for inner in body.statements.iter() {
@@ -0,0 +1,313 @@
+//! Check for access to undefined enum cases (Level 0)
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::symbols::ClassInfo;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Checks for enum case fetches like Status::Unknown where the case doesn't exist
+pub struct EnumCaseCheck;
+
+impl Check for EnumCaseCheck {
+    fn id(&self) -> &'static str {
+        "enum.caseNotFound"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects access to undefined enum cases"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = EnumCaseVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            current_class: None,
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct EnumCaseVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// FQN of the class-like we're inside (for self::/static::)
+    current_class: Option<String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> EnumCaseVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    /// Resolve a class reference to a fully qualified name
+    fn resolve_class_name(&self, name: &str) -> Option<String> {
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Whether a constant is declared on a class-like or an interface or trait
+    /// it inherits from. `None` if part of the hierarchy is unknown.
+    fn inherits_constant(&self, info: &ClassInfo, name: &str) -> Option<bool> {
+        let mut visited = HashSet::new();
+        let mut queue: Vec<String> = info.interfaces.iter().chain(&info.traits).cloned().collect();
+        let mut complete = true;
+        while let Some(fqn) = queue.pop() {
+            if !visited.insert(fqn.to_lowercase()) {
+                continue;
+            }
+            let Some(ancestor) = self.find_class(&fqn) else {
+                complete = false;
+                continue;
+            };
+            if ancestor.has_constant(name) {
+                return Some(true);
+            }
+            queue.extend(ancestor.interfaces.iter().chain(&ancestor.traits).chain(&ancestor.parent).cloned());
+        }
+        complete.then_some(false)
+    }
+
+    /// Visit the members of a class-like with `current_class` set
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let short_name = self.get_span_text(&name.span).to_string();
+        let previous = self.current_class.replace(match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name,
+        });
+        for member in members.iter() {
+            self.traverse_class_like_member(member, self.source);
+        }
+        self.current_class = previous;
+    }
+
+    fn check_case_fetch(&mut self, access: &ClassConstantAccess<'_>) {
+        let class_name = match access.class {
+            Expression::Identifier(ident) => self.get_span_text(&ident.span()).to_string(),
+            Expression::Self_(_) => "self".to_string(),
+            Expression::Static(_) => "static".to_string(),
+            _ => return,
+        };
+
+        let ClassLikeConstantSelector::Identifier(ident) = &access.constant else {
+            return;
+        };
+        let case_name = self.get_span_text(&ident.span).to_string();
+
+        let Some(fqn) = self.resolve_class_name(&class_name) else {
+            return;
+        };
+        let Some(info) = self.find_class(&fqn) else {
+            return;
+        };
+
+        // Only enums with known cases (vendor classmaps don't record them)
+        if !info.is_enum() || info.cases.is_empty() {
+            return;
+        }
+
+        if case_name.eq_ignore_ascii_case("class")
+            || info.has_case(&case_name)
+            || info.has_constant(&case_name)
+            || self.inherits_constant(info, &case_name) != Some(false)
+        {
+            return;
+        }
+
+        let (line, col) = self.get_line_col(ident.span.start.offset as usize);
+        let mut issue = Issue::error(
+            "enum.caseNotFound",
+            format!("Access to undefined enum case {}::{}.", info.full_name, case_name),
+            self.file_path.clone(),
+            line,
+            col,
+        )
+        .with_identifier("enum.caseNotFound");
+
+        // Enum case names are case-sensitive; point at a near miss
+        if let Some(similar) = info.cases.iter().find(|c| c.name.eq_ignore_ascii_case(&case_name)) {
+            issue = issue.with_tip(format!("Did you mean {}::{}?", info.name, similar.name));
+        }
+
+        self.issues.push(issue);
+    }
+}
+
+impl<'a, 's> Visitor<'a> for EnumCaseVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            Statement::Interface(interface) => {
+                self.visit_class_like_members(&interface.name, &interface.members);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        if let Expression::Access(Access::ClassConstant(access)) = expr {
+            self.check_case_fetch(access);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        run_check(&EnumCaseCheck, source, &PhpStanConfig::default())
+    }
+
+    #[test]
+    fn test_enum_case_check_level() {
+        let check = EnumCaseCheck;
+        assert_eq!(check.level(), 0);
+    }
+
+    #[test]
+    fn test_undefined_enum_case() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+enum Status: string {
+    case Active = 'active';
+    const DEFAULT = self::Active;
+
+    public function isFinal(): bool {
+        return $this === self::Archived;
+    }
+}
+
+$a = Status::Active;
+$b = Status::DEFAULT;
+$c = Status::active;
+$d = \App\Status::class;
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.contains("App\\Status::Archived"));
+        assert!(issues[1].message.contains("App\\Status::active"));
+        assert_eq!(issues[1].tip.as_deref(), Some("Did you mean Status::Active?"));
+    }
+
+    #[test]
+    fn test_inherited_constants() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+interface HasDefault extends HasLabel {
+    const DEFAULT = 'a';
+}
+
+interface HasLabel {
+    const LABEL = 'Status';
+}
+
+trait Limits {
+    const MAX = 3;
+}
+
+enum Status: string implements HasDefault {
+    use Limits;
+
+    case Active = 'active';
+}
+
+enum Mode implements \Vendor\Unknown {
+    case On;
+}
+
+$a = Status::DEFAULT;
+$b = Status::LABEL;
+$c = Status::MAX;
+$d = Status::Missing;
+$e = Mode::FROM_VENDOR;
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("App\\Status::Missing"));
+    }
+
+    #[test]
+    fn test_case_fetch_inside_match_arm() {
+        let issues = analyze(
+            r#"<?php
+enum Suit { case Hearts; case Spades; }
+
+function color(Suit $s): string {
+    return match ($s) {
+        Suit::Hearts => 'Red',
+        Suit::Clubs => 'Black',
+    };
+}
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].identifier.as_deref(), Some("enum.caseNotFound"));
+    }
+}
@@ -9,6 +9,9 @@
 //! - Missing return statements
 //! - Invalid uses of new static()
 //! - Class name case mismatches
+//! - Undefined enum cases
+//! - Readonly properties assigned outside the constructor
+//! - Typed properties not initialized by the constructor
 
 mod undefined_function;
 mod undefined_class;
@@ -19,6 +22,9 @@ mod argument_count;
 mod missing_return;
 mod invalid_static_new;
 mod class_name_case;
+mod enum_case;
+mod readonly_property;
+mod uninitialized_property;
 
 pub use undefined_function::UndefinedFunctionCheck;
 pub use undefined_class::UndefinedClassCheck;
@@ -29,3 +35,6 @@ pub use argument_count::ArgumentCountCheck;
 pub use missing_return::MissingReturnCheck;
 pub use invalid_static_new::InvalidStaticNewCheck;
 pub use class_name_case::ClassNameCaseCheck;
+pub use enum_case::EnumCaseCheck;
+pub use readonly_property::ReadonlyPropertyAssignCheck;
+pub use uninitialized_property::UninitializedPropertyCheck;
@@ -0,0 +1,440 @@
+//! Check for assignments to readonly properties outside their initialization scope (Level 0)
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::symbols::class_info::ClassPropertyInfo;
+use crate::symbols::ClassInfo;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Checks that readonly properties (native or `@readonly`) are only assigned
+/// in the constructor of their declaring class
+pub struct ReadonlyPropertyAssignCheck;
+
+impl Check for ReadonlyPropertyAssignCheck {
+    fn id(&self) -> &'static str {
+        "property.readOnlyAssignNotInConstructor"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects readonly properties assigned outside the constructor of their declaring class"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = ReadonlyAssignVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            current_class: None,
+            in_constructor: false,
+            variable_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct ReadonlyAssignVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// FQN of the class-like we're inside
+    current_class: Option<String>,
+    /// Whether we're directly inside `__construct`
+    in_constructor: bool,
+    /// Known classes of variables ($this, typed parameters, `$x = new Foo()`)
+    variable_types: HashMap<String, String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> ReadonlyAssignVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    /// Resolve a class reference to a fully qualified name
+    fn resolve_class_name(&self, name: &str) -> Option<String> {
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Find a property and the class that declares it.
+    ///
+    /// Properties from traits belong to the class using the trait.
+    fn find_declaring_class(&self, class_name: &str, property: &str) -> Option<(String, &ClassPropertyInfo)> {
+        let mut visited = HashSet::new();
+        let mut current = Some(class_name.to_string());
+        while let Some(name) = current {
+            if !visited.insert(name.to_lowercase()) {
+                break;
+            }
+            let info = self.find_class(&name)?;
+            if let Some(found) = info.get_property(property) {
+                return Some((info.full_name.clone(), found));
+            }
+            if let Some(found) = self.find_trait_property(&info.traits, property, &mut visited) {
+                return Some((info.full_name.clone(), found));
+            }
+            current = info.parent.clone();
+        }
+        None
+    }
+
+    fn find_trait_property(
+        &self,
+        traits: &[String],
+        property: &str,
+        visited: &mut HashSet<String>,
+    ) -> Option<&ClassPropertyInfo> {
+        for trait_name in traits {
+            if !visited.insert(trait_name.to_lowercase()) {
+                continue;
+            }
+            let Some(info) = self.find_class(trait_name) else {
+                continue;
+            };
+            if let Some(found) = info.get_property(property) {
+                return Some(found);
+            }
+            if let Some(found) = self.find_trait_property(&info.traits, property, visited) {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    fn resolve_class_expression(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Identifier(ident) => self.resolve_class_name(self.get_span_text(&ident.span())),
+            Expression::Self_(_) | Expression::Static(_) => self.current_class.clone(),
+            _ => None,
+        }
+    }
+
+    /// Class of the object a property is accessed on, if known
+    fn object_class(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self.variable_types.get(var.name).cloned(),
+            Expression::Parenthesized(paren) => self.object_class(paren.expression),
+            _ => None,
+        }
+    }
+
+    /// Class named by a parameter type hint (nullable hints included)
+    fn hint_class(&self, hint: &Hint<'_>) -> Option<String> {
+        let text = self.get_span_text(&hint.span()).trim_start_matches('?');
+        if text.contains(['|', '&', '(']) {
+            return None;
+        }
+        let lower = text.to_lowercase();
+        if matches!(
+            lower.as_str(),
+            "int" | "float" | "string" | "bool" | "array" | "callable" | "iterable" | "object" | "mixed"
+                | "void" | "null" | "never" | "false" | "true"
+        ) {
+            return None;
+        }
+        self.resolve_class_name(text)
+    }
+
+    /// Record the classes of typed parameters
+    fn bind_parameters(&mut self, parameter_list: &FunctionLikeParameterList<'_>) {
+        for param in parameter_list.parameters.iter() {
+            if let Some(class) = param.hint.as_ref().and_then(|h| self.hint_class(h)) {
+                self.variable_types.insert(param.variable.name.to_string(), class);
+            }
+        }
+    }
+
+    /// Visit the members of a class-like with class and method scope set
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let short_name = self.get_span_text(&name.span).to_string();
+        let fqn = match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name,
+        };
+        let previous_class = self.current_class.replace(fqn.clone());
+
+        for member in members.iter() {
+            if let ClassLikeMember::Method(method) = member {
+                let previous_variables = std::mem::take(&mut self.variable_types);
+                let previous_constructor = self.in_constructor;
+                self.in_constructor = self.get_span_text(&method.name.span).eq_ignore_ascii_case("__construct");
+                self.variable_types.insert("$this".to_string(), fqn.clone());
+                self.bind_parameters(&method.parameter_list);
+                self.traverse_class_like_member(member, self.source);
+                self.in_constructor = previous_constructor;
+                self.variable_types = previous_variables;
+            } else {
+                self.traverse_class_like_member(member, self.source);
+            }
+        }
+
+        self.current_class = previous_class;
+    }
+
+    /// Check the target of an assignment, increment or decrement
+    fn check_assignment_target(&mut self, target: &Expression<'_>) {
+        let access = match target {
+            // Modifying an element of a readonly array property is a write too
+            Expression::ArrayAccess(access) => return self.check_assignment_target(access.array),
+            Expression::ArrayAppend(append) => return self.check_assignment_target(append.array),
+            Expression::Access(Access::Property(access)) => access,
+            _ => return,
+        };
+        let ClassLikeMemberSelector::Identifier(ident) = &access.property else {
+            return;
+        };
+        let Some(class_name) = self.object_class(access.object) else {
+            return;
+        };
+        let property_name = self.get_span_text(&ident.span).to_string();
+        let Some((declaring_class, property)) = self.find_declaring_class(&class_name, &property_name) else {
+            return;
+        };
+
+        let (prefix, by_phpdoc) = if property.is_readonly {
+            ("Readonly", false)
+        } else if property.is_readonly_by_phpdoc {
+            ("@readonly", true)
+        } else {
+            return;
+        };
+
+        let in_declaring_class = self
+            .current_class
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case(&declaring_class));
+        let (location, identifier) = if !in_declaring_class {
+            ("its declaring class", "property.readOnlyAssignOutOfClass")
+        } else if !self.in_constructor {
+            ("the constructor", "property.readOnlyAssignNotInConstructor")
+        } else {
+            return;
+        };
+        let identifier = if by_phpdoc { "property.readOnlyByPhpDocAssign" } else { identifier };
+
+        let (line, col) = self.get_line_col(ident.span.start.offset as usize);
+        self.issues.push(
+            Issue::error(
+                identifier,
+                format!(
+                    "{} property {}::${} is assigned outside of {}.",
+                    prefix, declaring_class, property.name, location
+                ),
+                self.file_path.clone(),
+                line,
+                col,
+            )
+            .with_identifier(identifier),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for ReadonlyAssignVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let previous_variables = std::mem::take(&mut self.variable_types);
+                let previous_class = self.current_class.take();
+                self.bind_parameters(&func.parameter_list);
+                for inner in func.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.current_class = previous_class;
+                self.variable_types = previous_variables;
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            Statement::Interface(_) => false,
+            Statement::Expression(expr_stmt) => {
+                // Track `$x = new Foo()` so later property writes on $x can be resolved
+                if let Expression::Assignment(assign) = &expr_stmt.expression {
+                    if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                        match assign.rhs {
+                            Expression::Instantiation(inst) => {
+                                if let Some(class) = self.resolve_class_expression(inst.class) {
+                                    self.variable_types.insert(var.name.to_string(), class);
+                                }
+                            }
+                            _ => {
+                                self.variable_types.remove(var.name);
+                            }
+                        }
+                    }
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            Expression::Assignment(assign) => self.check_assignment_target(assign.lhs),
+            Expression::UnaryPrefix(prefix) if prefix.operator.is_increment_or_decrement() => {
+                self.check_assignment_target(prefix.operand)
+            }
+            Expression::UnaryPostfix(postfix) => self.check_assignment_target(postfix.operand),
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        run_check(&ReadonlyPropertyAssignCheck, source, &PhpStanConfig::default())
+    }
+
+    #[test]
+    fn test_readonly_assign_outside_constructor() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+final class Money {
+    public readonly int $amount;
+    public readonly array $tags;
+
+    public function __construct(int $amount) {
+        $this->amount = $amount;
+        $this->tags = [];
+    }
+
+    public function add(int $more): void {
+        $this->amount += $more;
+        $this->tags[] = 'changed';
+    }
+}
+
+function reset(Money $money): void {
+    $money->amount = 0;
+}
+"#,
+        );
+        assert_eq!(issues.len(), 3);
+        assert_eq!(
+            issues[0].message,
+            "Readonly property App\\Money::$amount is assigned outside of the constructor."
+        );
+        assert_eq!(issues[1].identifier.as_deref(), Some("property.readOnlyAssignNotInConstructor"));
+        assert_eq!(
+            issues[2].message,
+            "Readonly property App\\Money::$amount is assigned outside of its declaring class."
+        );
+        assert_eq!(issues[2].identifier.as_deref(), Some("property.readOnlyAssignOutOfClass"));
+    }
+
+    #[test]
+    fn test_readonly_class_and_child_class() {
+        let issues = analyze(
+            r#"<?php
+readonly class Point {
+    public int $x;
+    public function __construct() { $this->x = 0; }
+}
+
+class Point3D extends Point {
+    public function __construct() { $this->x = 1; }
+}
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].identifier.as_deref(), Some("property.readOnlyAssignOutOfClass"));
+    }
+
+    #[test]
+    fn test_readonly_by_phpdoc() {
+        let issues = analyze(
+            r#"<?php
+class User {
+    /** @readonly */
+    public string $name;
+    public string $email;
+
+    public function __construct(string $name) { $this->name = $name; }
+
+    public function rename(string $name): void {
+        $this->name = $name;
+        $this->email = '';
+    }
+}
+
+/** @immutable */
+class Settings {
+    public array $values = [];
+}
+
+$settings = new Settings();
+$settings->values = [];
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "@readonly property User::$name is assigned outside of the constructor.");
+        assert_eq!(issues[0].identifier.as_deref(), Some("property.readOnlyByPhpDocAssign"));
+        assert_eq!(
+            issues[1].message,
+            "@readonly property Settings::$values is assigned outside of its declaring class."
+        );
+    }
+}
@@ -0,0 +1,492 @@
+//! Check for typed properties not initialized by the constructor (Level 0)
+
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Checks that typed properties without a default are assigned on every constructor path.
+///
+/// Readonly properties are always checked (`property.uninitializedReadonly`); other
+/// typed properties only with `checkUninitializedProperties` (`property.uninitialized`).
+pub struct UninitializedPropertyCheck;
+
+impl Check for UninitializedPropertyCheck {
+    fn id(&self) -> &'static str {
+        "property.uninitializedReadonly"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects typed properties that are not assigned on every constructor path"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = UninitializedPropertyVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            check_all: ctx.config.check_uninitialized_properties,
+            current_namespace: None,
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+/// A typed property that needs to be initialized
+struct RequiredProperty {
+    name: String,
+    offset: u32,
+    is_readonly: bool,
+}
+
+struct UninitializedPropertyVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    /// Also check properties that aren't readonly
+    check_all: bool,
+    current_namespace: Option<String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> UninitializedPropertyVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn check_class(&mut self, class: &Class<'_>) {
+        // Subclasses or trait methods may initialize what we can't see
+        if class.modifiers.contains_abstract()
+            || class.members.iter().any(|m| matches!(m, ClassLikeMember::TraitUse(_)))
+        {
+            return;
+        }
+
+        let class_readonly = class.modifiers.contains_readonly();
+        let mut required = Vec::new();
+        let mut methods = HashMap::new();
+        for member in class.members.iter() {
+            match member {
+                ClassLikeMember::Property(Property::Plain(property)) => {
+                    if property.hint.is_none() || property.modifiers.contains_static() {
+                        continue;
+                    }
+                    let is_readonly = class_readonly || property.modifiers.contains_readonly();
+                    if !is_readonly && !self.check_all {
+                        continue;
+                    }
+                    for item in property.items.iter() {
+                        if let PropertyItem::Abstract(item) = item {
+                            required.push(RequiredProperty {
+                                name: item.variable.name.trim_start_matches('$').to_string(),
+                                offset: item.variable.span.start.offset,
+                                is_readonly,
+                            });
+                        }
+                    }
+                }
+                ClassLikeMember::Method(method) => {
+                    if let MethodBody::Concrete(body) = &method.body {
+                        methods.insert(self.get_span_text(&method.name.span).to_lowercase(), body);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if required.is_empty() {
+            return;
+        }
+
+        let mut analyzer = InitializationAnalyzer {
+            source: self.source,
+            properties: required.iter().map(|p| p.name.clone()).collect(),
+            methods,
+            visiting: Vec::new(),
+            returns: Vec::new(),
+            collected: HashSet::new(),
+        };
+        let initialized = match analyzer.methods.get("__construct").copied() {
+            Some(body) => {
+                analyzer.visiting.push("__construct".to_string());
+                analyzer.definitely_assigned(&body.statements)
+            }
+            None => HashSet::new(),
+        };
+
+        let short_name = self.get_span_text(&class.name.span);
+        let class_name = match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name.to_string(),
+        };
+        for property in required {
+            if initialized.contains(&property.name) {
+                continue;
+            }
+            let (message, identifier) = if property.is_readonly {
+                (
+                    format!(
+                        "Class {} has an uninitialized readonly property ${}. Assign it in the constructor.",
+                        class_name, property.name
+                    ),
+                    "property.uninitializedReadonly",
+                )
+            } else {
+                (
+                    format!(
+                        "Class {} has an uninitialized property ${}. Give it default value or assign it in the constructor.",
+                        class_name, property.name
+                    ),
+                    "property.uninitialized",
+                )
+            };
+            let (line, col) = self.get_line_col(property.offset as usize);
+            self.issues.push(
+                Issue::error(identifier, message, self.file_path.clone(), line, col).with_identifier(identifier),
+            );
+        }
+    }
+}
+
+impl<'a, 's> Visitor<'a> for UninitializedPropertyVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Class(class) => {
+                self.check_class(class);
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Flow analysis of which properties are assigned on every path through a method.
+///
+/// Calls to `$this->method()` are followed into methods of the same class. Statements
+/// other than `if` (loops, switch, try) are treated optimistically: anything they
+/// assign counts, so uncertain flow never produces a report.
+struct InitializationAnalyzer<'b, 'a> {
+    source: &'b str,
+    /// Names of the properties being tracked
+    properties: HashSet<String>,
+    /// Concrete method bodies by lowercased name
+    methods: HashMap<String, &'b Block<'a>>,
+    /// Methods currently being analyzed (to stop recursion)
+    visiting: Vec<String>,
+    /// Assigned properties at each `return` in the current method
+    returns: Vec<HashSet<String>>,
+    /// Properties assigned anywhere in the expression or statement being scanned
+    collected: HashSet<String>,
+}
+
+impl<'b, 'a> InitializationAnalyzer<'b, 'a> {
+    /// Properties assigned on every path that leaves the method normally
+    fn definitely_assigned(&mut self, statements: &Sequence<'_, Statement<'_>>) -> HashSet<String> {
+        let previous_returns = std::mem::take(&mut self.returns);
+        let fallthrough = self.block(statements, HashSet::new());
+        let returns = std::mem::replace(&mut self.returns, previous_returns);
+
+        // A method that always throws never leaves anything uninitialized
+        let mut result: Option<HashSet<String>> = fallthrough;
+        for assigned in returns {
+            result = Some(match result {
+                Some(current) => current.intersection(&assigned).cloned().collect(),
+                None => assigned,
+            });
+        }
+        result.unwrap_or_else(|| self.properties.clone())
+    }
+
+    /// Properties assigned after `statements`, or `None` if they never complete normally
+    fn block(&mut self, statements: &Sequence<'_, Statement<'_>>, assigned: HashSet<String>) -> Option<HashSet<String>> {
+        let mut assigned = assigned;
+        for stmt in statements.iter() {
+            assigned = self.statement(stmt, assigned)?;
+        }
+        Some(assigned)
+    }
+
+    fn statement(&mut self, stmt: &Statement<'_>, mut assigned: HashSet<String>) -> Option<HashSet<String>> {
+        match stmt {
+            Statement::Expression(expr_stmt) => {
+                assigned.extend(self.expression_assignments(expr_stmt.expression));
+                if is_terminating(expr_stmt.expression) {
+                    return None;
+                }
+                Some(assigned)
+            }
+            Statement::Return(ret) => {
+                if let Some(value) = &ret.value {
+                    assigned.extend(self.expression_assignments(value));
+                }
+                self.returns.push(assigned);
+                None
+            }
+            Statement::Block(block) => self.block(&block.statements, assigned),
+            Statement::If(if_stmt) => {
+                assigned.extend(self.expression_assignments(if_stmt.condition));
+                let mut branches = Vec::new();
+                let has_else = match &if_stmt.body {
+                    IfBody::Statement(body) => {
+                        branches.push(self.statement(body.statement, assigned.clone()));
+                        for else_if in body.else_if_clauses.iter() {
+                            let mut branch = assigned.clone();
+                            branch.extend(self.expression_assignments(else_if.condition));
+                            branches.push(self.statement(else_if.statement, branch));
+                        }
+                        if let Some(else_clause) = &body.else_clause {
+                            branches.push(self.statement(else_clause.statement, assigned.clone()));
+                        }
+                        body.else_clause.is_some()
+                    }
+                    IfBody::ColonDelimited(body) => {
+                        branches.push(self.block(&body.statements, assigned.clone()));
+                        for else_if in body.else_if_clauses.iter() {
+                            let mut branch = assigned.clone();
+                            branch.extend(self.expression_assignments(else_if.condition));
+                            branches.push(self.block(&else_if.statements, branch));
+                        }
+                        if let Some(else_clause) = &body.else_clause {
+                            branches.push(self.block(&else_clause.statements, assigned.clone()));
+                        }
+                        body.else_clause.is_some()
+                    }
+                };
+                if !has_else {
+                    branches.push(Some(assigned));
+                }
+
+                // Only branches that fall through constrain what's assigned afterwards
+                branches
+                    .into_iter()
+                    .flatten()
+                    .reduce(|a, b| a.intersection(&b).cloned().collect())
+            }
+            _ => {
+                assigned.extend(self.statement_assignments(stmt));
+                Some(assigned)
+            }
+        }
+    }
+
+    fn expression_assignments(&mut self, expr: &Expression<'_>) -> HashSet<String> {
+        let previous = std::mem::take(&mut self.collected);
+        self.traverse_expression(expr, self.source);
+        std::mem::replace(&mut self.collected, previous)
+    }
+
+    fn statement_assignments(&mut self, stmt: &Statement<'_>) -> HashSet<String> {
+        let previous = std::mem::take(&mut self.collected);
+        self.traverse_statement(stmt, self.source);
+        std::mem::replace(&mut self.collected, previous)
+    }
+
+    /// Properties a `$this->method()` call definitely assigns
+    fn method_assignments(&mut self, method: &str) -> HashSet<String> {
+        let method = method.to_lowercase();
+        if self.visiting.contains(&method) {
+            return HashSet::new();
+        }
+        let Some(body) = self.methods.get(&method).copied() else {
+            return HashSet::new();
+        };
+        self.visiting.push(method);
+        let assigned = self.definitely_assigned(&body.statements);
+        self.visiting.pop();
+        assigned
+    }
+}
+
+impl<'a, 'b, 'm> Visitor<'a> for InitializationAnalyzer<'b, 'm> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        !matches!(
+            stmt,
+            Statement::Function(_) | Statement::Class(_) | Statement::Interface(_) | Statement::Trait(_) | Statement::Enum(_)
+        )
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            // Closures run later, if at all
+            Expression::Closure(_) | Expression::ArrowFunction(_) => return false,
+            Expression::Assignment(assign) => {
+                if let Some(name) = this_property(assign.lhs, self.source) {
+                    self.collected.insert(name.to_string());
+                }
+            }
+            Expression::Call(Call::Method(call)) if is_this(call.object) => {
+                if let ClassLikeMemberSelector::Identifier(ident) = &call.method {
+                    let name = &self.source[ident.span.start.offset as usize..ident.span.end.offset as usize];
+                    let assigned = self.method_assignments(name);
+                    self.collected.extend(assigned);
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+fn is_this(expr: &Expression<'_>) -> bool {
+    matches!(expr, Expression::Variable(Variable::Direct(var)) if var.name == "$this")
+}
+
+/// Name of the property in `$this->name`
+fn this_property<'s>(expr: &Expression<'_>, source: &'s str) -> Option<&'s str> {
+    let Expression::Access(Access::Property(access)) = expr else {
+        return None;
+    };
+    if !is_this(access.object) {
+        return None;
+    }
+    let ClassLikeMemberSelector::Identifier(ident) = &access.property else {
+        return None;
+    };
+    Some(&source[ident.span.start.offset as usize..ident.span.end.offset as usize])
+}
+
+/// Whether evaluating the expression never completes (`throw`, `exit`, `die`)
+fn is_terminating(expr: &Expression<'_>) -> bool {
+    matches!(
+        expr,
+        Expression::Throw(_) | Expression::Construct(Construct::Exit(_)) | Expression::Construct(Construct::Die(_))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str, check_uninitialized_properties: bool) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            check_uninitialized_properties,
+            ..PhpStanConfig::default()
+        };
+        run_check(&UninitializedPropertyCheck, source, &config)
+    }
+
+    const SOURCE: &str = r#"<?php
+namespace App;
+
+class Order {
+    public readonly int $id;
+    public readonly string $status;
+    private string $note;
+    private ?string $comment = null;
+    private $untyped;
+
+    public function __construct(int $id, bool $paid) {
+        $this->id = $id;
+        if ($paid) {
+            $this->status = 'paid';
+            $this->note = '';
+        } elseif ($id === 0) {
+            throw new \InvalidArgumentException();
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_uninitialized_readonly_on_some_paths() {
+        let issues = analyze(SOURCE, false);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Class App\\Order has an uninitialized readonly property $status. Assign it in the constructor."
+        );
+        assert_eq!(issues[0].line, 6);
+    }
+
+    #[test]
+    fn test_check_uninitialized_properties() {
+        let issues = analyze(SOURCE, true);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(
+            issues[1].message,
+            "Class App\\Order has an uninitialized property $note. Give it default value or assign it in the constructor."
+        );
+        assert_eq!(issues[1].identifier.as_deref(), Some("property.uninitialized"));
+    }
+
+    #[test]
+    fn test_initialized_through_paths_and_methods() {
+        let issues = analyze(
+            r#"<?php
+final readonly class Config {
+    public array $values;
+    public string $env;
+    public int $port;
+
+    public function __construct(?array $values) {
+        if ($values === null) {
+            throw new RuntimeException();
+        } else {
+            $this->values = $values;
+        }
+        $this->init();
+        foreach ($values as $key => $value) {
+            $this->port = 80;
+        }
+    }
+
+    private function init(): void {
+        $this->env = 'prod';
+    }
+}
+
+final class NoConstructor {
+    public readonly int $id;
+}
+
+final class EarlyReturn {
+    public readonly int $id;
+
+    public function __construct(bool $skip) {
+        if ($skip) {
+            return;
+        }
+        $this->id = 1;
+    }
+}
+"#,
+            false,
+        );
+        assert_eq!(issues.len(), 2);
+        assert!(issues[0].message.starts_with("Class NoConstructor"));
+        assert!(issues[1].message.starts_with("Class EarlyReturn"));
+    }
+}
@@ -0,0 +1,639 @@
+//! Check for non-exhaustive and redundant match expressions (Level 4)
+//!
+//! When the match subject has a finite set of possible values (an enum, `bool`,
+//! or a union of literals), the arms are compared against that set:
+//! - values no arm handles are reported as `match.unhandled` (unless there is a `default` arm)
+//! - an arm that can only ever match because every other value was already handled
+//!   is reported as `match.alwaysTrue`
+//! - an arm whose value was already handled by an earlier arm is reported as `match.alwaysFalse`
+//!
+//! Variable subjects are typed by their parameter declaration, so parameters the
+//! function assigns to anywhere are left alone.
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::symbols::ClassInfo;
+use crate::types::phpdoc::{find_docblock, parse_phpdoc, parse_type_string, PhpDoc};
+use crate::types::Type;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Checks match expressions over enums, bools and literal unions
+pub struct MatchExpressionCheck;
+
+impl Check for MatchExpressionCheck {
+    fn id(&self) -> &'static str {
+        "match"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects match expressions that don't handle all possible values or have redundant arms"
+    }
+
+    fn level(&self) -> u8 {
+        4
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let mut visitor = MatchVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            current_class: None,
+            param_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct MatchVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// FQN of the class-like we're inside (for self::/static::)
+    current_class: Option<String>,
+    /// Declared types of the parameters of the enclosing function-like that
+    /// its body never assigns to (name includes `$`)
+    param_types: HashMap<String, Type>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> MatchVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    /// Resolve a class reference to a fully qualified name
+    fn resolve_class_name(&self, name: &str) -> Option<String> {
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Resolve class names inside a parsed type
+    fn qualify_type(&self, ty: Type) -> Type {
+        match ty {
+            Type::Object { class_name: Some(name) } => Type::Object {
+                class_name: self.resolve_class_name(&name).or(Some(name)),
+            },
+            Type::Nullable(inner) => Type::Nullable(Box::new(self.qualify_type(*inner))),
+            Type::Union(types) => Type::Union(types.into_iter().map(|t| self.qualify_type(t)).collect()),
+            other => other,
+        }
+    }
+
+    /// Collect parameter types from native hints, preferring `@param` docblock types
+    fn parameter_types(
+        &self,
+        parameter_list: &FunctionLikeParameterList<'_>,
+        doc: Option<&PhpDoc>,
+    ) -> HashMap<String, Type> {
+        let mut types = HashMap::new();
+        for param in parameter_list.parameters.iter() {
+            let name = self.get_span_text(&param.variable.span).to_string();
+            let doc_type = doc.and_then(|d| {
+                d.params
+                    .iter()
+                    .find(|(n, _)| n == name.trim_start_matches('$'))
+                    .map(|(_, ty)| ty.clone())
+            });
+            let ty = doc_type.or_else(|| {
+                param
+                    .hint
+                    .as_ref()
+                    .and_then(|h| parse_type_string(self.get_span_text(&h.span())))
+            });
+            if let Some(ty) = ty {
+                types.insert(name, self.qualify_type(ty));
+            }
+        }
+        types
+    }
+
+    fn docblock_for(&self, span: mago_span::Span) -> Option<PhpDoc> {
+        find_docblock(self.source, span.start.offset as usize).map(parse_phpdoc)
+    }
+
+    /// Visit the members of a class-like with `current_class` and method parameters set
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let short_name = self.get_span_text(&name.span).to_string();
+        let previous_class = self.current_class.replace(match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name,
+        });
+        for member in members.iter() {
+            if let ClassLikeMember::Method(method) = member {
+                let doc = self.docblock_for(method.span());
+                let mut params = self.parameter_types(&method.parameter_list, doc.as_ref());
+                if let MethodBody::Concrete(block) = &method.body {
+                    forget_written(&mut params, WrittenVariables::in_statements(block.statements.iter(), self.source));
+                }
+                let previous_params = std::mem::replace(&mut self.param_types, params);
+                self.traverse_class_like_member(member, self.source);
+                self.param_types = previous_params;
+            } else {
+                self.traverse_class_like_member(member, self.source);
+            }
+        }
+        self.current_class = previous_class;
+    }
+
+    /// Expand a type into its finite set of possible values, if it has one
+    fn finite_values(&self, ty: &Type) -> Option<Vec<Type>> {
+        match ty {
+            Type::Bool => Some(vec![Type::ConstantBool(true), Type::ConstantBool(false)]),
+            Type::ConstantBool(_)
+            | Type::ConstantInt(_)
+            | Type::ConstantString(_)
+            | Type::Null
+            | Type::EnumCase { .. } => Some(vec![ty.clone()]),
+            Type::Object { class_name: Some(name) } => {
+                let info = self.find_class(name)?;
+                if info.is_enum() && !info.cases.is_empty() {
+                    Some(info.case_types())
+                } else {
+                    None
+                }
+            }
+            Type::Nullable(inner) => {
+                let mut values = self.finite_values(inner)?;
+                values.push(Type::Null);
+                Some(values)
+            }
+            Type::Union(types) => {
+                let mut values = Vec::new();
+                for t in types {
+                    for value in self.finite_values(t)? {
+                        if !values.contains(&value) {
+                            values.push(value);
+                        }
+                    }
+                }
+                Some(values)
+            }
+            _ => None,
+        }
+    }
+
+    /// The single value a match arm condition compares against, if statically known
+    fn constant_value(&self, expr: &Expression<'_>) -> Option<Type> {
+        match expr {
+            Expression::Literal(Literal::True(_)) => Some(Type::ConstantBool(true)),
+            Expression::Literal(Literal::False(_)) => Some(Type::ConstantBool(false)),
+            Expression::Literal(Literal::Null(_)) => Some(Type::Null),
+            Expression::Literal(Literal::Integer(int)) => int.value.map(|v| Type::ConstantInt(v as i64)),
+            Expression::Literal(Literal::String(string)) => {
+                string.value.map(|v| Type::ConstantString(v.to_string()))
+            }
+            Expression::Parenthesized(paren) => self.constant_value(paren.expression),
+            Expression::Access(Access::ClassConstant(access)) => {
+                let class_name = match access.class {
+                    Expression::Identifier(ident) => self.get_span_text(&ident.span()),
+                    Expression::Self_(_) => "self",
+                    Expression::Static(_) => "static",
+                    _ => return None,
+                };
+                let ClassLikeConstantSelector::Identifier(ident) = &access.constant else {
+                    return None;
+                };
+                let case_name = self.get_span_text(&ident.span);
+                let info = self.find_class(&self.resolve_class_name(class_name)?)?;
+                if info.has_case(case_name) {
+                    Some(info.case_type(case_name))
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Type of the match subject, as far as this check can tell
+    fn subject_type(&self, expr: &Expression<'_>) -> Option<Type> {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self.param_types.get(var.name).cloned(),
+            Expression::Parenthesized(paren) => self.subject_type(paren.expression),
+            _ => self.constant_value(expr),
+        }
+    }
+
+    fn check_match(&mut self, match_expr: &Match<'_>) {
+        let Some(subject_type) = self.subject_type(match_expr.expression) else {
+            return;
+        };
+        let Some(mut remaining) = self.finite_values(&subject_type) else {
+            return;
+        };
+
+        let has_default = match_expr.arms.iter().any(|arm| arm.is_default());
+
+        // Flatten the conditions in evaluation order; bail out on anything we can't evaluate
+        let mut conditions = Vec::new();
+        for arm in match_expr.arms.iter() {
+            if let MatchArm::Expression(arm) = arm {
+                for condition in arm.conditions.iter() {
+                    let Some(value) = self.constant_value(condition) else {
+                        return;
+                    };
+                    conditions.push((value, condition.span()));
+                }
+            }
+        }
+
+        let total = conditions.len();
+        for (index, (value, span)) in conditions.into_iter().enumerate() {
+            let is_last = index + 1 == total && !has_default;
+            let (line, col) = self.get_line_col(span.start.offset as usize);
+
+            if !remaining.contains(&value) {
+                let subject = Type::union(remaining.clone());
+                self.issues.push(
+                    Issue::error(
+                        "match.alwaysFalse",
+                        format!(
+                            "Match arm comparison between {} and {} is always false.",
+                            subject, value
+                        ),
+                        self.file_path.clone(),
+                        line,
+                        col,
+                    )
+                    .with_identifier("match.alwaysFalse"),
+                );
+                continue;
+            }
+
+            // Only one value left: this arm always matches, so any later arm is dead
+            if remaining.len() == 1 && !is_last {
+                self.issues.push(
+                    Issue::error(
+                        "match.alwaysTrue",
+                        format!(
+                            "Match arm comparison between {} and {} is always true.",
+                            remaining[0], value
+                        ),
+                        self.file_path.clone(),
+                        line,
+                        col,
+                    )
+                    .with_identifier("match.alwaysTrue")
+                    .with_tip("Remove remaining cases below this one and this error will disappear too."),
+                );
+            }
+
+            remaining.retain(|v| v != &value);
+        }
+
+        if has_default || remaining.is_empty() {
+            return;
+        }
+
+        let (line, col) = self.get_line_col(match_expr.r#match.span.start.offset as usize);
+        let values = remaining.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("|");
+        let noun = if remaining.len() == 1 { "value" } else { "values" };
+        self.issues.push(
+            Issue::error(
+                "match.unhandled",
+                format!("Match expression does not handle remaining {}: {}", noun, values),
+                self.file_path.clone(),
+                line,
+                col,
+            )
+            .with_identifier("match.unhandled"),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for MatchVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let doc = self.docblock_for(func.span());
+                let mut params = self.parameter_types(&func.parameter_list, doc.as_ref());
+                forget_written(&mut params, WrittenVariables::in_statements(func.body.statements.iter(), source));
+                let previous = std::mem::replace(&mut self.param_types, params);
+                for inner in func.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.param_types = previous;
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, source: &str) -> bool {
+        match expr {
+            Expression::Match(match_expr) => {
+                self.check_match(match_expr);
+                true
+            }
+            Expression::Closure(closure) => {
+                let params = self.parameter_types(&closure.parameter_list, None);
+                let previous = self.param_types.clone();
+                self.param_types.extend(params);
+                forget_written(&mut self.param_types, WrittenVariables::in_statements(closure.body.statements.iter(), source));
+                for inner in closure.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.param_types = previous;
+                false
+            }
+            Expression::ArrowFunction(arrow) => {
+                let params = self.parameter_types(&arrow.parameter_list, None);
+                let previous = self.param_types.clone();
+                self.param_types.extend(params);
+                let mut written = WrittenVariables::default();
+                written.traverse_expression(arrow.expression, source);
+                forget_written(&mut self.param_types, written.names);
+                self.traverse_expression(arrow.expression, source);
+                self.param_types = previous;
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Drop the types of variables that are assigned to: their declared type
+/// no longer tells what they hold
+fn forget_written(types: &mut HashMap<String, Type>, written: HashSet<String>) {
+    types.retain(|name, _| !written.contains(name));
+}
+
+/// Variables a function-like body writes to, including through `??=`,
+/// `++`, `foreach`, `unset`, `global` and `static`
+#[derive(Default)]
+struct WrittenVariables {
+    names: HashSet<String>,
+    /// Whether variables visited now are being written to
+    in_target: bool,
+}
+
+impl WrittenVariables {
+    fn in_statements<'a>(statements: impl Iterator<Item = &'a Statement<'a>>, source: &str) -> HashSet<String> {
+        let mut written = Self::default();
+        for statement in statements {
+            written.traverse_statement(statement, source);
+        }
+        written.names
+    }
+
+    /// Record the variables of an assignment target, like `$a`, `$a['k']` or `[$a, $b]`
+    fn target<'a>(&mut self, expr: &Expression<'a>, source: &str) {
+        let previous = std::mem::replace(&mut self.in_target, true);
+        self.traverse_expression(expr, source);
+        self.in_target = previous;
+    }
+}
+
+impl<'a> Visitor<'a> for WrittenVariables {
+    fn visit_expression(&mut self, expr: &Expression<'a>, source: &str) -> bool {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) if self.in_target => {
+                self.names.insert(var.name.to_string());
+            }
+            Expression::Assignment(assign) => self.target(assign.lhs, source),
+            Expression::UnaryPrefix(prefix)
+                if matches!(prefix.operator, UnaryPrefixOperator::PreIncrement(_) | UnaryPrefixOperator::PreDecrement(_)) =>
+            {
+                self.target(prefix.operand, source)
+            }
+            Expression::UnaryPostfix(postfix) => self.target(postfix.operand, source),
+            _ => {}
+        }
+        true
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Foreach(foreach) => match &foreach.target {
+                ForeachTarget::Value(target) => self.target(target.value, source),
+                ForeachTarget::KeyValue(target) => {
+                    self.target(target.key, source);
+                    self.target(target.value, source);
+                }
+            },
+            Statement::Unset(unset) => {
+                for value in unset.values.iter() {
+                    self.target(value, source);
+                }
+            }
+            Statement::Global(global) => {
+                for variable in global.variables.iter() {
+                    if let Variable::Direct(var) = variable {
+                        self.names.insert(var.name.to_string());
+                    }
+                }
+            }
+            Statement::Static(declaration) => {
+                for item in declaration.items.iter() {
+                    let var = match item {
+                        StaticItem::Abstract(item) => &item.variable,
+                        StaticItem::Concrete(item) => &item.variable,
+                    };
+                    self.names.insert(var.name.to_string());
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::PhpStanConfig;
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        run_check(&MatchExpressionCheck, source, &PhpStanConfig::default())
+    }
+
+    #[test]
+    fn test_unhandled_enum_case() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+enum Suit { case Hearts; case Diamonds; case Clubs; case Spades; }
+
+function color(Suit $s): string {
+    return match ($s) {
+        Suit::Hearts, Suit::Diamonds => 'Red',
+        Suit::Clubs => 'Black',
+    };
+}
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].message,
+            "Match expression does not handle remaining value: App\\Suit::Spades"
+        );
+        assert_eq!(issues[0].line, 7);
+    }
+
+    #[test]
+    fn test_exhaustive_and_default_arms() {
+        let issues = analyze(
+            r#"<?php
+enum Suit { case Hearts; case Spades; }
+
+class Card {
+    public function label(Suit $s, bool $faceUp): string {
+        $a = match ($s) { Suit::Hearts => 'H', Suit::Spades => 'S' };
+        $b = match ($s) { Suit::Hearts => 'H', default => '?' };
+        $c = match ($faceUp) { true => 'up', false => 'down' };
+        return $a . $b . $c;
+    }
+}
+"#,
+        );
+        assert!(issues.is_empty(), "unexpected issues: {:?}", issues);
+    }
+
+    #[test]
+    fn test_literal_union_and_bool() {
+        let issues = analyze(
+            r#"<?php
+/** @param 'asc'|'desc' $dir */
+function sort_by(string $dir, ?bool $strict): int {
+    $x = match ($dir) { 'asc' => 1 };
+    return match ($strict) { true => 1, false => 0 };
+}
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "Match expression does not handle remaining value: 'desc'");
+        assert_eq!(issues[1].message, "Match expression does not handle remaining value: null");
+    }
+
+    #[test]
+    fn test_redundant_arms() {
+        let issues = analyze(
+            r#"<?php
+enum Suit { case Hearts; case Spades; }
+
+function f(Suit $s): int {
+    return match ($s) {
+        Suit::Hearts => 1,
+        Suit::Spades => 2,
+        default => 3,
+    };
+}
+
+function g(Suit $s): int {
+    return match ($s) {
+        Suit::Hearts => 1,
+        Suit::Hearts => 2,
+        Suit::Spades => 3,
+    };
+}
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].identifier.as_deref(), Some("match.alwaysTrue"));
+        assert_eq!(
+            issues[0].message,
+            "Match arm comparison between Suit::Spades and Suit::Spades is always true."
+        );
+        assert_eq!(issues[1].identifier.as_deref(), Some("match.alwaysFalse"));
+        let prefix = format!("{}.", MatchExpressionCheck.id());
+        assert!(issues.iter().all(|issue| issue.check_id.starts_with(&prefix)));
+    }
+
+    #[test]
+    fn test_unknown_subject_is_ignored() {
+        let issues = analyze(
+            r#"<?php
+function f($x, int $n) {
+    $a = match ($x) { 1 => 'a' };
+    $b = match ($n) { 1 => 'a' };
+    $c = match (true) { $n > 1 => 'a', $n < 0 => 'b' };
+}
+"#,
+        );
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn test_assigned_parameters_are_ignored() {
+        let issues = analyze(
+            r#"<?php
+enum Suit { case Hearts; case Spades; }
+
+function f(?Suit $s, bool $b, bool $c): string {
+    $s ??= Suit::Hearts;
+    $c = 1;
+    return match ($s) { Suit::Hearts => 'H', Suit::Spades => 'S' }
+        . match ($c) { true => 'y' };
+}
+
+class Deck {
+    public function g(bool $b): void {
+        foreach ([1, 2] as $i) {
+            $x = match ($b) { true => 'a' };
+            [$b] = [$i];
+        }
+    }
+}
+
+$h = fn (bool $b) => match ($b) { true => 'a' } . $b++;
+"#,
+        );
+        assert!(issues.is_empty(), "unexpected issues: {:?}", issues);
+    }
+}
@@ -11,11 +11,15 @@ pub mod level7;
 pub mod level8;
 pub mod level9;
 pub mod level10;
+pub mod deprecation;
+pub mod exceptions;
+pub mod strict_rules;
 
 use crate::config::PhpStanConfig;
 use crate::issue::Issue;
+use crate::resolver::SymbolCollector;
 use crate::scope::Scope;
-use crate::symbols::SymbolTable;
+use crate::symbols::{ClassInfo, FunctionInfo, SymbolTable};
 use mago_syntax::ast::Program;
 use std::path::Path;
 
@@ -54,6 +58,67 @@ pub trait Check: Send + Sync {
     fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue>;
 }
 
+/// Class and function lookup for the file being checked
+///
+/// Symbols declared in the file are always known, even without a project
+/// symbol table.
+pub(crate) struct LocalSymbols<'s> {
+    file_path: &'s Path,
+    local_symbols: SymbolTable,
+    symbol_table: Option<&'s SymbolTable>,
+}
+
+impl<'s> LocalSymbols<'s> {
+    pub fn new(program: &Program<'_>, ctx: &CheckContext<'s>) -> Self {
+        let collected = SymbolCollector::new(ctx.source, ctx.file_path).collect(program);
+        Self {
+            file_path: ctx.file_path,
+            local_symbols: SymbolCollector::build_symbol_table_from_symbols(vec![collected]),
+            symbol_table: ctx.symbol_table,
+        }
+    }
+
+    /// Resolve a class reference in `namespace` to a fully qualified name
+    ///
+    /// `self` and `static` resolve to `current_class` and `parent` to its
+    /// parent, `None` outside a class.
+    pub fn resolve_class_name(&self, name: &str, namespace: Option<&str>, current_class: Option<&str>) -> Option<String> {
+        if name.eq_ignore_ascii_case("self") || name.eq_ignore_ascii_case("static") {
+            return current_class.map(str::to_string);
+        }
+        if name.eq_ignore_ascii_case("parent") {
+            return self.find_class(current_class?)?.parent.clone();
+        }
+        Some(self.local_symbols.resolve_class_name(name, self.file_path, namespace))
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    pub fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.local_symbols
+            .get_class(fqn)
+            .or_else(|| self.symbol_table.and_then(|t| t.get_class(fqn)))
+    }
+
+    /// Look up a function called in `namespace`, falling back to the global
+    /// namespace like PHP does
+    pub fn find_function(&self, name: &str, namespace: Option<&str>) -> Option<&FunctionInfo> {
+        let lookup = |fqn: &str| {
+            self.local_symbols
+                .get_function(fqn)
+                .or_else(|| self.symbol_table.and_then(|t| t.get_function(fqn)))
+        };
+        if let Some(fqn) = name.strip_prefix('\\') {
+            return lookup(fqn);
+        }
+        if let Some(ns) = namespace {
+            if let Some(info) = lookup(&format!("{}\\{}", ns, name)) {
+                return Some(info);
+            }
+        }
+        lookup(name)
+    }
+}
+
 /// Registry of all available checks
 #[derive(Default)]
 pub struct CheckRegistry {
@@ -79,6 +144,10 @@ impl CheckRegistry {
         registry.register(Box::new(level0::MissingReturnCheck));
         registry.register(Box::new(level0::InvalidStaticNewCheck));
         registry.register(Box::new(level0::ClassNameCaseCheck));
+        registry.register(Box::new(level0::EnumCaseCheck));
+        registry.register(Box::new(level0::ReadonlyPropertyAssignCheck));
+        registry.register(Box::new(level0::UninitializedPropertyCheck));
+        registry.register(Box::new(exceptions::CheckedExceptionCheck));
 
         // Level 1 checks
         registry.register(Box::new(level1::UndefinedVariableCheck));
@@ -101,6 +170,8 @@ impl CheckRegistry {
         registry.register(Box::new(level4::AlwaysFalseBooleanCheck));
         registry.register(Box::new(level4::WriteOnlyPropertyCheck));
         registry.register(Box::new(level4::InvalidBinaryOpCheck));
+        registry.register(Box::new(level4::MatchExpressionCheck));
+        registry.register(Box::new(exceptions::DeadCatchCheck));
 
         // Level 5 checks
         registry.register(Box::new(level5::ArgumentTypeCheck));
@@ -125,6 +196,22 @@ impl CheckRegistry {
         registry
     }
 
+    /// Register the deprecation checks (phpstan-deprecation-rules)
+    pub fn register_deprecation_checks(&mut self) {
+        self.register(Box::new(deprecation::DeprecatedUsageCheck));
+        self.register(Box::new(deprecation::PhpCoreDeprecationCheck));
+    }
+
+    /// Register the strict rules checks (phpstan-strict-rules)
+    pub fn register_strict_rules_checks(&mut self) {
+        self.register(Box::new(strict_rules::BooleanConditionsCheck));
+        self.register(Box::new(strict_rules::DisallowedConstructsCheck));
+        self.register(Box::new(strict_rules::VariableVariablesCheck));
+        self.register(Box::new(strict_rules::ForeachOverwriteCheck));
+        self.register(Box::new(strict_rules::StrictFunctionCallCheck));
+        self.register(Box::new(strict_rules::DynamicStaticCallCheck));
+    }
+
     /// Register a check
     pub fn register(&mut self, check: Box<dyn Check>) {
         self.checks.push(check);
@@ -628,6 +715,30 @@ pub const PHP_BUILTIN_CLASSES: &[&str] = &[
     "Randomizer",
 ];
 
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+    use mago_database::file::FileId;
+
+    /// Run a check on `source` as test.php, without a project symbol table
+    pub(crate) fn run_check(check: &dyn Check, source: &str, config: &PhpStanConfig) -> Vec<Issue> {
+        let arena = bumpalo::Bump::new();
+        let file_id = FileId::new("test.php");
+        let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, source);
+        let ctx = CheckContext {
+            file_path: Path::new("test.php"),
+            source,
+            config,
+            builtin_functions: &[],
+            builtin_classes: &[],
+            symbol_table: None,
+            scope: None,
+            analysis_level: check.level(),
+        };
+        check.check(program, &ctx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,331 @@
+//! Check for non-boolean conditions
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::types::phpdoc::parse_type_string;
+use crate::types::Type;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Checks that `if`/`elseif` conditions and `!` operands are booleans (`booleansInConditions`)
+///
+/// Only expressions whose type is known are reported: literals, arrays, `new`,
+/// typed parameters and variables assigned from those.
+pub struct BooleanConditionsCheck;
+
+impl Check for BooleanConditionsCheck {
+    fn id(&self) -> &'static str {
+        "if.condNotBoolean"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects non-boolean values used as conditions"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        if !ctx.config.strict_rules.booleans_in_conditions {
+            return Vec::new();
+        }
+
+        let mut visitor = BooleanConditionsVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            variable_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct BooleanConditionsVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// Known types of variables in the current function scope
+    variable_types: HashMap<String, Type>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> BooleanConditionsVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn resolve_class_name(&self, name: &str) -> String {
+        self.symbols
+            .resolve_class_name(name, self.current_namespace.as_deref(), None)
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Infer the type of an expression, if it's known
+    fn infer_type(&self, expr: &Expression<'_>) -> Option<Type> {
+        match expr {
+            Expression::Parenthesized(inner) => self.infer_type(inner.expression),
+            Expression::Literal(literal) => Some(match literal {
+                Literal::String(_) => Type::String,
+                Literal::Integer(_) => Type::Int,
+                Literal::Float(_) => Type::Float,
+                Literal::True(_) | Literal::False(_) => Type::Bool,
+                Literal::Null(_) => Type::Null,
+            }),
+            Expression::CompositeString(_) => Some(Type::String),
+            Expression::Array(_) | Expression::LegacyArray(_) => Some(Type::mixed_array()),
+            Expression::Instantiation(instantiation) => match instantiation.class {
+                Expression::Identifier(ident) => {
+                    Some(Type::object(self.resolve_class_name(self.get_span_text(&ident.span()))))
+                }
+                _ => Some(Type::Object { class_name: None }),
+            },
+            Expression::Binary(binary) => {
+                let op = &binary.operator;
+                if op.is_comparison() || op.is_logical() || op.is_instanceof() {
+                    Some(Type::Bool)
+                } else if op.is_concatenation() {
+                    Some(Type::String)
+                } else {
+                    None
+                }
+            }
+            Expression::UnaryPrefix(prefix) => match &prefix.operator {
+                UnaryPrefixOperator::Not(_)
+                | UnaryPrefixOperator::BoolCast(..)
+                | UnaryPrefixOperator::BooleanCast(..) => Some(Type::Bool),
+                UnaryPrefixOperator::IntCast(..) | UnaryPrefixOperator::IntegerCast(..) => Some(Type::Int),
+                UnaryPrefixOperator::StringCast(..) | UnaryPrefixOperator::BinaryCast(..) => Some(Type::String),
+                UnaryPrefixOperator::ArrayCast(..) => Some(Type::mixed_array()),
+                _ => None,
+            },
+            Expression::Construct(Construct::Isset(_) | Construct::Empty(_)) => Some(Type::Bool),
+            Expression::Variable(Variable::Direct(var)) => self.variable_types.get(var.name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Report a condition whose type is known not to be a boolean
+    fn check_condition(&mut self, identifier: &str, context: &str, expr: &Expression<'_>) {
+        let Some(ty) = self.infer_type(expr) else {
+            return;
+        };
+        if matches!(ty, Type::Bool | Type::ConstantBool(_) | Type::Mixed) {
+            return;
+        }
+
+        let message = format!("Only booleans are allowed in {}, {} given.", context, describe(&ty));
+        let (line, col) = self.get_line_col(expr.span().start.offset as usize);
+        self.issues.push(
+            Issue::error(identifier, message, self.file_path.clone(), line, col).with_identifier(identifier),
+        );
+    }
+
+    /// Record the types of parameters with a type hint
+    fn bind_parameters(&mut self, parameter_list: &FunctionLikeParameterList<'_>) {
+        for param in parameter_list.parameters.iter() {
+            let Some(hint) = &param.hint else {
+                continue;
+            };
+            let ty = match hint {
+                Hint::Identifier(ident) => Some(Type::object(self.resolve_class_name(self.get_span_text(&ident.span())))),
+                _ => parse_type_string(self.get_span_text(&hint.span())),
+            };
+            if let Some(ty) = ty {
+                self.variable_types.insert(param.variable.name.to_string(), ty);
+            }
+        }
+    }
+
+    fn visit_function_body<'a>(&mut self, parameter_list: &FunctionLikeParameterList<'a>, body: &Block<'a>) {
+        let previous_variables = std::mem::take(&mut self.variable_types);
+        self.bind_parameters(parameter_list);
+        for inner in body.statements.iter() {
+            self.traverse_statement(inner, self.source);
+        }
+        self.variable_types = previous_variables;
+    }
+
+    fn visit_class_like_members<'a>(&mut self, members: &Sequence<'a, ClassLikeMember<'a>>) {
+        for member in members.iter() {
+            match member {
+                ClassLikeMember::Method(method) => {
+                    if let MethodBody::Concrete(body) = &method.body {
+                        self.visit_function_body(&method.parameter_list, body);
+                    }
+                }
+                _ => self.traverse_class_like_member(member, self.source),
+            }
+        }
+    }
+}
+
+/// Describe a type the way PHPStan does in these messages (`int`, `string|null`)
+fn describe(ty: &Type) -> String {
+    match ty {
+        Type::Nullable(inner) => format!("{}|null", describe(inner)),
+        _ => ty.generalize().to_string(),
+    }
+}
+
+impl<'a, 's> Visitor<'a> for BooleanConditionsVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                self.visit_function_body(&func.parameter_list, &func.body);
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.members);
+                false
+            }
+            Statement::If(if_stmt) => {
+                self.check_condition("if.condNotBoolean", "an if condition", if_stmt.condition);
+                match &if_stmt.body {
+                    IfBody::Statement(body) => {
+                        for else_if in body.else_if_clauses.iter() {
+                            self.check_condition("elseif.condNotBoolean", "an elseif condition", else_if.condition);
+                        }
+                    }
+                    IfBody::ColonDelimited(body) => {
+                        for else_if in body.else_if_clauses.iter() {
+                            self.check_condition("elseif.condNotBoolean", "an elseif condition", else_if.condition);
+                        }
+                    }
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            Expression::UnaryPrefix(prefix) if matches!(prefix.operator, UnaryPrefixOperator::Not(_)) => {
+                self.check_condition("booleanNot.exprNotBoolean", "a negated boolean", prefix.operand);
+            }
+            Expression::Assignment(assign) => {
+                if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                    // Compound assignments (`.=`, `+=`) change the type in ways we don't track
+                    let ty = if matches!(assign.operator, AssignmentOperator::Assign(_)) {
+                        self.infer_type(assign.rhs)
+                    } else {
+                        None
+                    };
+                    match ty {
+                        Some(ty) => self.variable_types.insert(var.name.to_string(), ty),
+                        None => self.variable_types.remove(var.name),
+                    };
+                }
+            }
+            Expression::Closure(closure) => {
+                let previous = self.variable_types.clone();
+                self.bind_parameters(&closure.parameter_list);
+                for inner in closure.body.statements.iter() {
+                    self.traverse_statement(inner, self.source);
+                }
+                self.variable_types = previous;
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{PhpStanConfig, StrictRulesConfig};
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            strict_rules: StrictRulesConfig::all(),
+            ..PhpStanConfig::default()
+        };
+        run_check(&BooleanConditionsCheck, source, &config)
+    }
+
+    #[test]
+    fn test_non_boolean_conditions() {
+        let issues = analyze(
+            r#"<?php
+function check(int $count, ?string $name, bool $flag, $unknown) {
+    if ($count) {}
+    elseif ($name) {}
+    if (!$flag || !$unknown) {}
+    $items = [];
+    if (!$items) {}
+    if ($count > 0 && isset($name)) {}
+}
+"#,
+        );
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].message, "Only booleans are allowed in an if condition, int given.");
+        assert_eq!(issues[1].message, "Only booleans are allowed in an elseif condition, string|null given.");
+        assert_eq!(issues[1].identifier.as_deref(), Some("elseif.condNotBoolean"));
+        assert_eq!(issues[2].message, "Only booleans are allowed in a negated boolean, array given.");
+        assert_eq!(issues[2].line, 7);
+    }
+
+    #[test]
+    fn test_reassigned_variables_are_forgotten() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+class Job {
+    public function run() {
+        $result = new Job();
+        if ($result) {}
+        $result = compute();
+        if ($result) {}
+    }
+}
+"#,
+        );
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "Only booleans are allowed in an if condition, App\\Job given.");
+    }
+}
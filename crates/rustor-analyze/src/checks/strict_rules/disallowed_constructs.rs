@@ -0,0 +1,140 @@
+//! Check for loose comparisons and `empty()`
+
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::path::PathBuf;
+
+/// Checks for `==`/`!=` (`disallowedLooseComparison`) and `empty()` (`disallowedEmpty`)
+pub struct DisallowedConstructsCheck;
+
+impl Check for DisallowedConstructsCheck {
+    fn id(&self) -> &'static str {
+        "equal.notAllowed"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects loose comparisons and empty()"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        let rules = &ctx.config.strict_rules;
+        if !rules.disallowed_loose_comparison && !rules.disallowed_empty {
+            return Vec::new();
+        }
+
+        let mut visitor = DisallowedConstructsVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            loose_comparison: rules.disallowed_loose_comparison,
+            empty: rules.disallowed_empty,
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct DisallowedConstructsVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    loose_comparison: bool,
+    empty: bool,
+    issues: Vec<Issue>,
+}
+
+impl<'s> DisallowedConstructsVisitor<'s> {
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn report(&mut self, identifier: &str, message: String, offset: u32) {
+        let (line, col) = self.get_line_col(offset as usize);
+        self.issues.push(
+            Issue::error(identifier, message, self.file_path.clone(), line, col).with_identifier(identifier),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for DisallowedConstructsVisitor<'s> {
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            Expression::Binary(binary) if self.loose_comparison => {
+                let (identifier, operator, span) = match &binary.operator {
+                    BinaryOperator::Equal(span) => ("equal.notAllowed", "==", span),
+                    BinaryOperator::NotEqual(span) => ("notEqual.notAllowed", "!=", span),
+                    BinaryOperator::AngledNotEqual(span) => ("notEqual.notAllowed", "<>", span),
+                    _ => return true,
+                };
+                let message = format!("Loose comparison via \"{}\" is not allowed.", operator);
+                self.report(identifier, message, span.start.offset);
+            }
+            Expression::Construct(Construct::Empty(empty)) if self.empty => {
+                let message = "Construct empty() is not allowed. Use more strict comparison.".to_string();
+                self.report("empty.notAllowed", message, empty.empty.span.start.offset);
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{PhpStanConfig, StrictRulesConfig};
+
+    fn analyze(source: &str, strict_rules: StrictRulesConfig) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            strict_rules,
+            ..PhpStanConfig::default()
+        };
+        run_check(&DisallowedConstructsCheck, source, &config)
+    }
+
+    const SOURCE: &str = "<?php\nif ($a == 1 || $b != 2 || $c <> 3 || $d === 4) {}\nif (empty($e)) {}\n";
+
+    #[test]
+    fn test_loose_comparison_and_empty() {
+        let issues = analyze(SOURCE, StrictRulesConfig::all());
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues[0].message, "Loose comparison via \"==\" is not allowed.");
+        assert_eq!(issues[1].identifier.as_deref(), Some("notEqual.notAllowed"));
+        assert_eq!(issues[2].message, "Loose comparison via \"<>\" is not allowed.");
+        assert_eq!(issues[3].identifier.as_deref(), Some("empty.notAllowed"));
+        assert_eq!(issues[3].line, 3);
+    }
+
+    #[test]
+    fn test_individual_toggles() {
+        let only_empty = StrictRulesConfig {
+            disallowed_empty: true,
+            ..StrictRulesConfig::default()
+        };
+        let issues = analyze(SOURCE, only_empty);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].identifier.as_deref(), Some("empty.notAllowed"));
+
+        assert!(analyze(SOURCE, StrictRulesConfig::default()).is_empty());
+    }
+}
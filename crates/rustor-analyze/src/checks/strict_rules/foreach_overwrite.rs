@@ -0,0 +1,327 @@
+//! Check for foreach loops that overwrite existing variables
+
+use crate::checks::{Check, CheckContext};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+/// Checks for foreach key/value variables that are already defined (`overwriteVariablesWithLoop`)
+pub struct ForeachOverwriteCheck;
+
+impl Check for ForeachOverwriteCheck {
+    fn id(&self) -> &'static str {
+        "foreach.valueOverwrite"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects foreach loops that overwrite existing variables"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        if !ctx.config.strict_rules.overwrite_variables_with_loop {
+            return Vec::new();
+        }
+
+        let mut visitor = ForeachOverwriteVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            issues: Vec::new(),
+        };
+
+        // Top-level code; function-likes are analyzed as they're visited
+        visitor.walk(&program.statements, &mut HashSet::new());
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct ForeachOverwriteVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    issues: Vec<Issue>,
+}
+
+impl<'s> ForeachOverwriteVisitor<'s> {
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    /// Analyze the body of a function-like, starting with its parameters defined
+    fn walk_function_like(&mut self, parameters: &FunctionLikeParameterList<'_>, body: &Block<'_>, uses: &[&str]) {
+        let mut defined: HashSet<String> = parameters
+            .parameters
+            .iter()
+            .map(|p| p.variable.name.to_string())
+            .collect();
+        defined.extend(uses.iter().map(|u| u.to_string()));
+        self.walk(&body.statements, &mut defined);
+    }
+
+    /// Walk a statement sequence, tracking variables that are definitely defined.
+    ///
+    /// Variables assigned inside branches and loops are only maybe-defined afterwards,
+    /// so nested statements work on a copy of `defined`.
+    fn walk(&mut self, statements: &Sequence<'_, Statement<'_>>, defined: &mut HashSet<String>) {
+        for stmt in statements.iter() {
+            self.walk_statement(stmt, defined);
+        }
+    }
+
+    fn walk_statement(&mut self, stmt: &Statement<'_>, defined: &mut HashSet<String>) {
+        match stmt {
+            Statement::Namespace(ns) => self.walk(ns.statements(), defined),
+            Statement::Block(block) => self.walk(&block.statements, defined),
+            Statement::Expression(expr_stmt) => {
+                if let Expression::Assignment(assign) = &expr_stmt.expression {
+                    if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                        defined.insert(var.name.to_string());
+                    }
+                }
+            }
+            Statement::Global(global) => {
+                for variable in global.variables.iter() {
+                    if let Variable::Direct(var) = variable {
+                        defined.insert(var.name.to_string());
+                    }
+                }
+            }
+            Statement::Static(static_stmt) => {
+                for item in static_stmt.items.iter() {
+                    let var = match item {
+                        StaticItem::Abstract(item) => &item.variable,
+                        StaticItem::Concrete(item) => &item.variable,
+                    };
+                    defined.insert(var.name.to_string());
+                }
+            }
+            Statement::Foreach(foreach) => self.walk_foreach(foreach, defined),
+            Statement::If(if_stmt) => match &if_stmt.body {
+                IfBody::Statement(body) => {
+                    self.walk_statement(body.statement, &mut defined.clone());
+                    for else_if in body.else_if_clauses.iter() {
+                        self.walk_statement(else_if.statement, &mut defined.clone());
+                    }
+                    if let Some(else_clause) = &body.else_clause {
+                        self.walk_statement(else_clause.statement, &mut defined.clone());
+                    }
+                }
+                IfBody::ColonDelimited(body) => {
+                    self.walk(&body.statements, &mut defined.clone());
+                    for else_if in body.else_if_clauses.iter() {
+                        self.walk(&else_if.statements, &mut defined.clone());
+                    }
+                    if let Some(else_clause) = &body.else_clause {
+                        self.walk(&else_clause.statements, &mut defined.clone());
+                    }
+                }
+            },
+            Statement::While(while_stmt) => match &while_stmt.body {
+                WhileBody::Statement(inner) => self.walk_statement(inner, &mut defined.clone()),
+                WhileBody::ColonDelimited(body) => self.walk(&body.statements, &mut defined.clone()),
+            },
+            Statement::DoWhile(do_while) => self.walk_statement(do_while.statement, &mut defined.clone()),
+            Statement::For(for_stmt) => match &for_stmt.body {
+                ForBody::Statement(inner) => self.walk_statement(inner, &mut defined.clone()),
+                ForBody::ColonDelimited(body) => self.walk(&body.statements, &mut defined.clone()),
+            },
+            Statement::Switch(switch) => {
+                let cases = match &switch.body {
+                    SwitchBody::BraceDelimited(body) => &body.cases,
+                    SwitchBody::ColonDelimited(body) => &body.cases,
+                };
+                for case in cases.iter() {
+                    let statements = match case {
+                        SwitchCase::Expression(case) => &case.statements,
+                        SwitchCase::Default(case) => &case.statements,
+                    };
+                    self.walk(statements, &mut defined.clone());
+                }
+            }
+            Statement::Try(try_stmt) => {
+                self.walk(&try_stmt.block.statements, &mut defined.clone());
+                for clause in try_stmt.catch_clauses.iter() {
+                    let mut catch_defined = defined.clone();
+                    if let Some(var) = &clause.variable {
+                        catch_defined.insert(var.name.to_string());
+                    }
+                    self.walk(&clause.block.statements, &mut catch_defined);
+                }
+                if let Some(finally) = &try_stmt.finally_clause {
+                    self.walk(&finally.block.statements, &mut defined.clone());
+                }
+            }
+            // Functions and classes have their own variable scope
+            _ => {}
+        }
+    }
+
+    fn walk_foreach(&mut self, foreach: &Foreach<'_>, defined: &HashSet<String>) {
+        let (key, value) = match &foreach.target {
+            ForeachTarget::Value(target) => (None, target.value),
+            ForeachTarget::KeyValue(target) => (Some(target.key), target.value),
+        };
+
+        let mut inner = defined.clone();
+        if let Some(key) = key.and_then(loop_variable) {
+            if defined.contains(key.name) {
+                self.report("foreach.keyOverwrite", key, "key");
+            }
+            inner.insert(key.name.to_string());
+        }
+        if let Some(value) = loop_variable(value) {
+            if defined.contains(value.name) {
+                self.report("foreach.valueOverwrite", value, "value");
+            }
+            inner.insert(value.name.to_string());
+        }
+
+        match &foreach.body {
+            ForeachBody::Statement(inner_stmt) => self.walk_statement(inner_stmt, &mut inner),
+            ForeachBody::ColonDelimited(body) => self.walk(&body.statements, &mut inner),
+        }
+    }
+
+    fn report(&mut self, identifier: &str, var: &DirectVariable<'_>, kind: &str) {
+        let (line, col) = self.get_line_col(var.span().start.offset as usize);
+        self.issues.push(
+            Issue::error(
+                identifier,
+                format!("Foreach overwrites {} with its {} variable.", var.name, kind),
+                self.file_path.clone(),
+                line,
+                col,
+            )
+            .with_identifier(identifier),
+        );
+    }
+}
+
+/// The variable a foreach key or value is assigned to (`$v` or `&$v`)
+fn loop_variable<'e, 'a>(expr: &'e Expression<'a>) -> Option<&'e DirectVariable<'a>> {
+    match expr {
+        Expression::Variable(Variable::Direct(var)) => Some(var),
+        Expression::UnaryPrefix(prefix) if matches!(prefix.operator, UnaryPrefixOperator::Reference(_)) => {
+            loop_variable(prefix.operand)
+        }
+        _ => None,
+    }
+}
+
+impl<'a, 's> Visitor<'a> for ForeachOverwriteVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, _source: &str) -> bool {
+        match stmt {
+            Statement::Function(func) => self.walk_function_like(&func.parameter_list, &func.body, &[]),
+            Statement::Class(class) => self.walk_methods(&class.members),
+            Statement::Enum(enum_def) => self.walk_methods(&enum_def.members),
+            Statement::Trait(trait_def) => self.walk_methods(&trait_def.members),
+            _ => {}
+        }
+        true
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        if let Expression::Closure(closure) = expr {
+            let uses: Vec<&str> = closure
+                .use_clause
+                .iter()
+                .flat_map(|clause| clause.variables.iter().map(|v| v.variable.name))
+                .collect();
+            self.walk_function_like(&closure.parameter_list, &closure.body, &uses);
+        }
+        true
+    }
+}
+
+impl<'s> ForeachOverwriteVisitor<'s> {
+    fn walk_methods(&mut self, members: &Sequence<'_, ClassLikeMember<'_>>) {
+        for member in members.iter() {
+            if let ClassLikeMember::Method(method) = member {
+                if let MethodBody::Concrete(body) = &method.body {
+                    self.walk_function_like(&method.parameter_list, body, &[]);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{PhpStanConfig, StrictRulesConfig};
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            strict_rules: StrictRulesConfig::all(),
+            ..PhpStanConfig::default()
+        };
+        run_check(&ForeachOverwriteCheck, source, &config)
+    }
+
+    #[test]
+    fn test_foreach_overwrites_variables() {
+        let issues = analyze(
+            r#"<?php
+function process(array $items, $item) {
+    $key = 'id';
+    foreach ($items as $key => $item) {
+        foreach ($item as $key2 => &$item) {}
+    }
+    foreach ($items as $value) {}
+    foreach ($items as $value) {}
+    if ($items) {
+        $maybe = 1;
+    }
+    foreach ($items as $maybe) {}
+}
+"#,
+        );
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].message, "Foreach overwrites $key with its key variable.");
+        assert_eq!(issues[0].identifier.as_deref(), Some("foreach.keyOverwrite"));
+        assert_eq!(issues[1].message, "Foreach overwrites $item with its value variable.");
+        assert_eq!(issues[1].line, 4);
+        assert_eq!(issues[2].line, 5);
+    }
+
+    #[test]
+    fn test_closure_and_method_scopes() {
+        let issues = analyze(
+            r#"<?php
+$row = null;
+class Report {
+    public function rows(array $rows) {
+        foreach ($rows as $row) {}
+        return function () use ($rows) {
+            foreach ($rows as $rows) {}
+        };
+    }
+}
+foreach ([] as $row) {}
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].line, 11);
+        assert_eq!(issues[1].line, 7);
+    }
+}
@@ -0,0 +1,25 @@
+//! Strict rules (phpstan-strict-rules equivalent)
+//!
+//! Not tied to a level: enabled when `phpstan-strict-rules` is included in the
+//! configuration or a `strictRules` section is present. Each rule group can be
+//! toggled individually.
+//!
+//! - Non-boolean `if`/`elseif` conditions and `!` operands (`booleansInConditions`)
+//! - Loose comparisons with `==` and `!=` (`disallowedLooseComparison`)
+//! - `empty()` (`disallowedEmpty`)
+//! - Variable variables and variable method calls (`noVariableVariables`)
+//! - Foreach loops overwriting existing variables (`overwriteVariablesWithLoop`)
+//! - `in_array()`/`array_search()` without the strict flag (`strictFunctionCalls`)
+//! - Static methods called with `->` (`dynamicCallOnStaticMethod`)
+
+mod boolean_conditions;
+mod disallowed_constructs;
+mod foreach_overwrite;
+mod strict_calls;
+mod variable_variables;
+
+pub use boolean_conditions::BooleanConditionsCheck;
+pub use disallowed_constructs::DisallowedConstructsCheck;
+pub use foreach_overwrite::ForeachOverwriteCheck;
+pub use strict_calls::{DynamicStaticCallCheck, StrictFunctionCallCheck};
+pub use variable_variables::VariableVariablesCheck;
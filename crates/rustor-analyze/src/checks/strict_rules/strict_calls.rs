@@ -0,0 +1,403 @@
+//! Checks for non-strict function calls and static methods called dynamically
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use crate::symbols::class_info::ClassMethodInfo;
+use crate::symbols::ClassInfo;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Functions with a strict flag: (name, strict parameter position, minimum arguments for it to matter)
+const STRICT_FUNCTIONS: &[(&str, usize, usize)] = &[
+    ("in_array", 3, 2),
+    ("array_search", 3, 2),
+    ("array_keys", 3, 2),
+    ("base64_decode", 2, 1),
+];
+
+fn get_line_col(source: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for (i, ch) in source.char_indices() {
+        if i >= offset {
+            break;
+        }
+        if ch == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+/// Checks that `in_array()`, `array_search()` and friends are called with the strict flag
+/// (`strictFunctionCalls`)
+pub struct StrictFunctionCallCheck;
+
+impl Check for StrictFunctionCallCheck {
+    fn id(&self) -> &'static str {
+        "function.strict"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects in_array()/array_search() calls without the strict flag"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        if !ctx.config.strict_rules.strict_function_calls {
+            return Vec::new();
+        }
+
+        let mut visitor = StrictFunctionCallVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct StrictFunctionCallVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    issues: Vec<Issue>,
+}
+
+impl<'s> StrictFunctionCallVisitor<'s> {
+    fn check_call(&mut self, call: &FunctionCall<'_>) {
+        let Expression::Identifier(ident) = call.function else {
+            return;
+        };
+        let span = ident.span();
+        let name = self.source[span.start.offset as usize..span.end.offset as usize].trim_start_matches('\\');
+        let Some((function, position, minimum)) =
+            STRICT_FUNCTIONS.iter().find(|(f, _, _)| f.eq_ignore_ascii_case(name))
+        else {
+            return;
+        };
+
+        let arguments = &call.argument_list.arguments;
+        let mut strict_value = None;
+        for (index, argument) in arguments.iter().enumerate() {
+            match argument {
+                // Unpacked arguments may contain the flag
+                Argument::Positional(positional) if positional.ellipsis.is_some() => return,
+                Argument::Positional(positional) if index + 1 == *position => strict_value = Some(&positional.value),
+                Argument::Named(named) if named.name.value.eq_ignore_ascii_case("strict") => {
+                    strict_value = Some(&named.value)
+                }
+                _ => {}
+            }
+        }
+        if arguments.len() < *minimum {
+            return;
+        }
+
+        let message = match strict_value {
+            None => format!("Call to function {}() requires parameter #{} to be set.", function, position),
+            Some(Expression::Literal(Literal::False(_))) => {
+                format!("Call to function {}() requires parameter #{} to be true.", function, position)
+            }
+            Some(_) => return,
+        };
+
+        let (line, col) = get_line_col(self.source, span.start.offset as usize);
+        self.issues.push(
+            Issue::error("function.strict", message, self.file_path.clone(), line, col)
+                .with_identifier("function.strict"),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for StrictFunctionCallVisitor<'s> {
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        if let Expression::Call(Call::Function(call)) = expr {
+            self.check_call(call);
+        }
+        true
+    }
+}
+
+/// Checks for static methods called with `->` (`dynamicCallOnStaticMethod`)
+pub struct DynamicStaticCallCheck;
+
+impl Check for DynamicStaticCallCheck {
+    fn id(&self) -> &'static str {
+        "staticMethod.dynamicCall"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects static methods called on an instance"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        if !ctx.config.strict_rules.dynamic_call_on_static_method {
+            return Vec::new();
+        }
+
+        let mut visitor = DynamicStaticCallVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            variable_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct DynamicStaticCallVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// Known classes of variables ($this, typed parameters, `$x = new Foo()`)
+    variable_types: HashMap<String, String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> DynamicStaticCallVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn resolve_class_name(&self, name: &str) -> Option<String> {
+        let current_class = self.variable_types.get("$this").map(String::as_str);
+        self.symbols.resolve_class_name(name, self.current_namespace.as_deref(), current_class)
+    }
+
+    /// Look up a class in this file first, then in the project symbol table
+    fn find_class(&self, fqn: &str) -> Option<&ClassInfo> {
+        self.symbols.find_class(fqn)
+    }
+
+    /// Find a method and its declaring class in the class hierarchy
+    fn find_method(&self, class_name: &str, method: &str) -> Option<(&ClassInfo, &ClassMethodInfo)> {
+        let mut visited = HashSet::new();
+        let mut queue = vec![class_name.to_string()];
+        while let Some(name) = queue.pop() {
+            if !visited.insert(name.to_lowercase()) {
+                continue;
+            }
+            let Some(info) = self.find_class(&name) else {
+                continue;
+            };
+            if let Some(found) = info.get_method(method) {
+                return Some((info, found));
+            }
+            queue.extend(info.interfaces.iter().cloned());
+            queue.extend(info.traits.iter().cloned());
+            queue.extend(info.parent.iter().cloned());
+        }
+        None
+    }
+
+    fn object_class(&self, expr: &Expression<'_>) -> Option<String> {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self.variable_types.get(var.name).cloned(),
+            Expression::Parenthesized(paren) => self.object_class(paren.expression),
+            _ => None,
+        }
+    }
+
+    /// Record the classes of parameters with a plain class type hint
+    fn bind_parameters(&mut self, parameter_list: &FunctionLikeParameterList<'_>) {
+        for param in parameter_list.parameters.iter() {
+            if let Some(Hint::Identifier(ident)) = &param.hint {
+                if let Some(class) = self.resolve_class_name(self.get_span_text(&ident.span())) {
+                    self.variable_types.insert(param.variable.name.to_string(), class);
+                }
+            }
+        }
+    }
+
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let short_name = self.get_span_text(&name.span).to_string();
+        let fqn = match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name,
+        };
+        for member in members.iter() {
+            let previous_variables = std::mem::take(&mut self.variable_types);
+            self.variable_types.insert("$this".to_string(), fqn.clone());
+            if let ClassLikeMember::Method(method) = member {
+                self.bind_parameters(&method.parameter_list);
+            }
+            self.traverse_class_like_member(member, self.source);
+            self.variable_types = previous_variables;
+        }
+    }
+
+    fn check_call(&mut self, object: &Expression<'_>, selector: &ClassLikeMemberSelector<'_>) {
+        let ClassLikeMemberSelector::Identifier(ident) = selector else {
+            return;
+        };
+        let Some(class_name) = self.object_class(object) else {
+            return;
+        };
+        let method_name = self.get_span_text(&ident.span).to_string();
+        let Some((class, method)) = self.find_method(&class_name, &method_name) else {
+            return;
+        };
+        if !method.is_static {
+            return;
+        }
+
+        let message = format!("Dynamic call to static method {}::{}().", class.full_name, method.name);
+        let (line, col) = get_line_col(self.source, ident.span.start.offset as usize);
+        self.issues.push(
+            Issue::error("staticMethod.dynamicCall", message, self.file_path.clone(), line, col)
+                .with_identifier("staticMethod.dynamicCall"),
+        );
+    }
+}
+
+impl<'a, 's> Visitor<'a> for DynamicStaticCallVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let previous_variables = std::mem::take(&mut self.variable_types);
+                self.bind_parameters(&func.parameter_list);
+                for inner in func.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.variable_types = previous_variables;
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            Statement::Expression(expr_stmt) => {
+                // Track `$x = new Foo()` so later calls on $x can be resolved
+                if let Expression::Assignment(assign) = &expr_stmt.expression {
+                    if let Expression::Variable(Variable::Direct(var)) = assign.lhs {
+                        let class = match assign.rhs {
+                            Expression::Instantiation(inst) => match inst.class {
+                                Expression::Identifier(ident) => {
+                                    self.resolve_class_name(self.get_span_text(&ident.span()))
+                                }
+                                _ => None,
+                            },
+                            _ => None,
+                        };
+                        match class {
+                            Some(class) => self.variable_types.insert(var.name.to_string(), class),
+                            None => self.variable_types.remove(var.name),
+                        };
+                    }
+                }
+                true
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            Expression::Call(Call::Method(call)) => self.check_call(call.object, &call.method),
+            Expression::Call(Call::NullSafeMethod(call)) => self.check_call(call.object, &call.method),
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{PhpStanConfig, StrictRulesConfig};
+
+    fn analyze(check: &dyn Check, source: &str) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            strict_rules: StrictRulesConfig::all(),
+            ..PhpStanConfig::default()
+        };
+        run_check(check, source, &config)
+    }
+
+    #[test]
+    fn test_strict_function_calls() {
+        let issues = analyze(
+            &StrictFunctionCallCheck,
+            r#"<?php
+in_array($a, $list);
+in_array($a, $list, true);
+\array_search($a, $list, false);
+array_search($a, $list, strict: true);
+array_keys($list);
+array_keys($list, 'x');
+in_array(...$args);
+"#,
+        );
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0].message, "Call to function in_array() requires parameter #3 to be set.");
+        assert_eq!(issues[1].message, "Call to function array_search() requires parameter #3 to be true.");
+        assert_eq!(issues[2].line, 7);
+    }
+
+    #[test]
+    fn test_dynamic_call_on_static_method() {
+        let issues = analyze(
+            &DynamicStaticCallCheck,
+            r#"<?php
+namespace App;
+
+class Factory {
+    public static function create(): self { return new self(); }
+
+    public function build(): self {
+        return $this->create();
+    }
+}
+
+$factory = new Factory();
+$factory->create();
+$factory->build();
+Factory::create();
+"#,
+        );
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "Dynamic call to static method App\\Factory::create().");
+        assert_eq!(issues[0].line, 8);
+        assert_eq!(issues[1].line, 13);
+    }
+}
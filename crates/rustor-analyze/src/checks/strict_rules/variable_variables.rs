@@ -0,0 +1,277 @@
+//! Check for variable variables and variable member names
+
+use crate::checks::{Check, CheckContext, LocalSymbols};
+use crate::issue::Issue;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use rustor_core::Visitor;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Checks for `$$name`, `$obj->$method()`, `Foo::$method()` and `$obj->$property`
+/// (`noVariableVariables`)
+pub struct VariableVariablesCheck;
+
+impl Check for VariableVariablesCheck {
+    fn id(&self) -> &'static str {
+        "variable.dynamicName"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects variable variables and variable method calls"
+    }
+
+    fn level(&self) -> u8 {
+        0
+    }
+
+    fn check<'a>(&self, program: &Program<'a>, ctx: &CheckContext<'_>) -> Vec<Issue> {
+        if !ctx.config.strict_rules.no_variable_variables {
+            return Vec::new();
+        }
+
+        let mut visitor = VariableVariablesVisitor {
+            source: ctx.source,
+            file_path: ctx.file_path.to_path_buf(),
+            symbols: LocalSymbols::new(program, ctx),
+            current_namespace: None,
+            current_class: None,
+            variable_types: HashMap::new(),
+            issues: Vec::new(),
+        };
+
+        visitor.visit_program(program, ctx.source);
+        visitor.issues
+    }
+}
+
+struct VariableVariablesVisitor<'s> {
+    source: &'s str,
+    file_path: PathBuf,
+    symbols: LocalSymbols<'s>,
+    current_namespace: Option<String>,
+    /// FQN of the class-like we're inside
+    current_class: Option<String>,
+    /// Known classes of variables ($this and typed parameters)
+    variable_types: HashMap<String, String>,
+    issues: Vec<Issue>,
+}
+
+impl<'s> VariableVariablesVisitor<'s> {
+    fn get_span_text(&self, span: &mago_span::Span) -> &str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    fn get_line_col(&self, offset: usize) -> (usize, usize) {
+        let mut line = 1;
+        let mut col = 1;
+        for (i, ch) in self.source.char_indices() {
+            if i >= offset {
+                break;
+            }
+            if ch == '\n' {
+                line += 1;
+                col = 1;
+            } else {
+                col += 1;
+            }
+        }
+        (line, col)
+    }
+
+    fn report(&mut self, identifier: &str, message: String, offset: u32) {
+        let (line, col) = self.get_line_col(offset as usize);
+        self.issues.push(
+            Issue::error(identifier, message, self.file_path.clone(), line, col).with_identifier(identifier),
+        );
+    }
+
+    fn resolve_class_name(&self, name: &str) -> String {
+        self.symbols
+            .resolve_class_name(name, self.current_namespace.as_deref(), self.current_class.as_deref())
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Describe the type of the object a member is accessed on
+    fn describe_object(&self, expr: &Expression<'_>) -> String {
+        match expr {
+            Expression::Variable(Variable::Direct(var)) => self
+                .variable_types
+                .get(var.name)
+                .cloned()
+                .unwrap_or_else(|| "mixed".to_string()),
+            _ => "mixed".to_string(),
+        }
+    }
+
+    /// Record the classes of parameters with a plain class type hint
+    fn bind_parameters(&mut self, parameter_list: &FunctionLikeParameterList<'_>) {
+        for param in parameter_list.parameters.iter() {
+            let Some(hint) = &param.hint else {
+                continue;
+            };
+            if let Hint::Identifier(ident) = hint {
+                let class = self.resolve_class_name(self.get_span_text(&ident.span()));
+                self.variable_types.insert(param.variable.name.to_string(), class);
+            }
+        }
+    }
+
+    fn visit_class_like_members<'a>(
+        &mut self,
+        name: &LocalIdentifier<'a>,
+        members: &Sequence<'a, ClassLikeMember<'a>>,
+    ) {
+        let short_name = self.get_span_text(&name.span).to_string();
+        let fqn = match &self.current_namespace {
+            Some(ns) => format!("{}\\{}", ns, short_name),
+            None => short_name,
+        };
+        let previous_class = self.current_class.replace(fqn.clone());
+        for member in members.iter() {
+            let previous_variables = std::mem::take(&mut self.variable_types);
+            if let ClassLikeMember::Method(method) = member {
+                self.variable_types.insert("$this".to_string(), fqn.clone());
+                self.bind_parameters(&method.parameter_list);
+            }
+            self.traverse_class_like_member(member, self.source);
+            self.variable_types = previous_variables;
+        }
+        self.current_class = previous_class;
+    }
+}
+
+/// Whether a member selector is a variable or expression instead of a name
+fn is_dynamic(selector: &ClassLikeMemberSelector<'_>) -> bool {
+    !matches!(selector, ClassLikeMemberSelector::Identifier(_))
+}
+
+impl<'a, 's> Visitor<'a> for VariableVariablesVisitor<'s> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>, source: &str) -> bool {
+        match stmt {
+            Statement::Namespace(ns) => {
+                self.current_namespace = ns
+                    .name
+                    .as_ref()
+                    .map(|name| self.get_span_text(&name.span()).to_string());
+                true
+            }
+            Statement::Function(func) => {
+                let previous_variables = std::mem::take(&mut self.variable_types);
+                self.bind_parameters(&func.parameter_list);
+                for inner in func.body.statements.iter() {
+                    self.traverse_statement(inner, source);
+                }
+                self.variable_types = previous_variables;
+                false
+            }
+            Statement::Class(class) => {
+                self.visit_class_like_members(&class.name, &class.members);
+                false
+            }
+            Statement::Enum(enum_def) => {
+                self.visit_class_like_members(&enum_def.name, &enum_def.members);
+                false
+            }
+            Statement::Trait(trait_def) => {
+                self.visit_class_like_members(&trait_def.name, &trait_def.members);
+                false
+            }
+            _ => true,
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>, _source: &str) -> bool {
+        match expr {
+            Expression::Variable(Variable::Indirect(_) | Variable::Nested(_)) => {
+                self.report(
+                    "variable.dynamicName",
+                    "Variable variables are not allowed.".to_string(),
+                    expr.span().start.offset,
+                );
+                // `$$$a` is one variable variable, not two
+                return false;
+            }
+            Expression::Call(Call::Method(call)) if is_dynamic(&call.method) => {
+                let message = format!("Variable method call on {}.", self.describe_object(call.object));
+                self.report("method.dynamicName", message, call.method.span().start.offset);
+            }
+            Expression::Call(Call::NullSafeMethod(call)) if is_dynamic(&call.method) => {
+                let message = format!("Variable method call on {}.", self.describe_object(call.object));
+                self.report("method.dynamicName", message, call.method.span().start.offset);
+            }
+            Expression::Call(Call::StaticMethod(call)) if is_dynamic(&call.method) => {
+                let class = match call.class {
+                    Expression::Identifier(ident) => self.resolve_class_name(self.get_span_text(&ident.span())),
+                    Expression::Self_(_) | Expression::Static(_) => self.resolve_class_name("static"),
+                    _ => "mixed".to_string(),
+                };
+                let message = format!("Variable static method call on {}.", class);
+                self.report("staticMethod.dynamicName", message, call.method.span().start.offset);
+            }
+            Expression::Access(Access::Property(access)) if is_dynamic(&access.property) => {
+                let message = format!("Variable property access on {}.", self.describe_object(access.object));
+                self.report("property.dynamicName", message, access.property.span().start.offset);
+            }
+            Expression::Closure(closure) => {
+                let previous = self.variable_types.clone();
+                self.bind_parameters(&closure.parameter_list);
+                for inner in closure.body.statements.iter() {
+                    self.traverse_statement(inner, self.source);
+                }
+                self.variable_types = previous;
+                return false;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checks::test_support::run_check;
+    use crate::config::{PhpStanConfig, StrictRulesConfig};
+
+    fn analyze(source: &str) -> Vec<Issue> {
+        let config = PhpStanConfig {
+            strict_rules: StrictRulesConfig::all(),
+            ..PhpStanConfig::default()
+        };
+        run_check(&VariableVariablesCheck, source, &config)
+    }
+
+    #[test]
+    fn test_variable_variables() {
+        let issues = analyze("<?php\n$name = 'a';\n$$name = 1;\necho ${'b'};\necho $a;\n");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].message, "Variable variables are not allowed.");
+        assert_eq!(issues[0].line, 3);
+        assert_eq!(issues[1].line, 4);
+    }
+
+    #[test]
+    fn test_variable_member_names() {
+        let issues = analyze(
+            r#"<?php
+namespace App;
+
+class Handler {
+    public function run(Request $request, string $method) {
+        $this->$method();
+        $request->{$method . 'Action'}();
+        self::$method();
+        return $request->$method;
+    }
+}
+"#,
+        );
+        assert_eq!(issues.len(), 4);
+        assert_eq!(issues[0].message, "Variable method call on App\\Handler.");
+        assert_eq!(issues[1].message, "Variable method call on App\\Request.");
+        assert_eq!(issues[2].message, "Variable static method call on App\\Handler.");
+        assert_eq!(issues[2].identifier.as_deref(), Some("staticMethod.dynamicName"));
+        assert_eq!(issues[3].identifier.as_deref(), Some("property.dynamicName"));
+    }
+}
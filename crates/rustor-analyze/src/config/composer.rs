@@ -12,6 +12,10 @@ pub struct ComposerJson {
 
     #[serde(default, rename = "autoload-dev")]
     pub autoload_dev: AutoloadSection,
+
+    /// Package requirements, e.g. `"php": "^8.1"`
+    #[serde(default)]
+    pub require: HashMap<String, String>,
 }
 
 /// Autoload configuration section
@@ -130,4 +134,61 @@ impl ComposerJson {
     pub fn has_autoload(&self) -> bool {
         !self.autoload.psr4.is_empty()
     }
+
+    /// Lowest PHP version (`major.minor`) allowed by `require.php`
+    ///
+    /// `"^8.1"`, `">=8.1 <9.0"` and `"~8.1.0 || ^8.2"` all give `8.1`.
+    pub fn min_php_version(&self) -> Option<String> {
+        let (major, minor) = min_version(self.require.get("php")?)?;
+        Some(format!("{}.{}", major, minor))
+    }
+}
+
+/// Lowest `major.minor` version allowed by a Composer version constraint
+fn min_version(constraint: &str) -> Option<(u32, u32)> {
+    constraint
+        .split('|')
+        .filter_map(|alternative| {
+            // Within an alternative, the lower bound is the one that counts
+            alternative
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|part| !part.is_empty() && !part.starts_with('<') && !part.starts_with('!'))
+                .filter_map(|part| parse_version(part.trim_start_matches(['^', '~', '>', '=', 'v'])))
+                .max()
+        })
+        .min()
+}
+
+/// `major.minor` of a version like `8`, `8.1`, `8.1.*` or `8.1.0`
+fn parse_version(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = match parts.next() {
+        None | Some("*") | Some("x") => 0,
+        Some(minor) => minor.parse().ok()?,
+    };
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_version() {
+        assert_eq!(min_version("^8.1"), Some((8, 1)));
+        assert_eq!(min_version(">=7.4"), Some((7, 4)));
+        assert_eq!(min_version(">=8.1 <9.0"), Some((8, 1)));
+        assert_eq!(min_version("~8.0 || ~8.1"), Some((8, 0)));
+        assert_eq!(min_version("^8.2|^8.1.0"), Some((8, 1)));
+        assert_eq!(min_version("8.*"), Some((8, 0)));
+        assert_eq!(min_version("*"), None);
+    }
+
+    #[test]
+    fn test_min_php_version() {
+        let composer: ComposerJson = serde_json::from_str(r#"{"require": {"php": ">=7.4", "ext-json": "*"}}"#).unwrap();
+        assert_eq!(composer.min_php_version(), Some("7.4".to_string()));
+        assert_eq!(ComposerJson::default().min_php_version(), None);
+    }
 }
@@ -2,8 +2,9 @@
 
 pub mod composer;
 pub mod neon;
+pub mod php;
 pub mod phpstan;
 pub mod level;
 
-pub use phpstan::PhpStanConfig;
+pub use phpstan::{ExceptionsConfig, PhpStanConfig, StrictRulesConfig};
 pub use level::Level;
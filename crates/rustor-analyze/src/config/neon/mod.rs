@@ -78,6 +78,30 @@ parameters:
         }
     }
 
+    #[test]
+    fn test_parse_sibling_after_nested_object() {
+        let input = r#"
+parameters:
+    exceptions:
+        check:
+            tooWideThrowType: true
+        uncheckedExceptionClasses:
+            - LogicException
+    level: 5
+"#;
+        let result = parse(input).unwrap();
+        let exceptions = result.get("parameters").and_then(|p| p.get("exceptions")).unwrap();
+        assert_eq!(
+            exceptions.get("check").and_then(|c| c.get("tooWideThrowType")),
+            Some(&Value::Bool(true))
+        );
+        assert_eq!(
+            exceptions.get("uncheckedExceptionClasses"),
+            Some(&Value::Array(vec![Value::String("LogicException".to_string())]))
+        );
+        assert_eq!(result.get("parameters").and_then(|p| p.get("level")), Some(&Value::Integer(5)));
+    }
+
     #[test]
     fn test_parse_inline_array() {
         let input = "paths: [src/, tests/]";
@@ -161,6 +161,7 @@ impl<'a> NeonParser<'a> {
             };
 
             // Check indentation
+            let line_start = self.position;
             let current_indent = match &token.kind {
                 TokenKind::Indent(n) => {
                     let indent = *n;
@@ -175,8 +176,10 @@ impl<'a> NeonParser<'a> {
                 _ => 0,
             };
 
-            // If we're back to a lower indentation level, we're done with this block
+            // If we're back to a lower indentation level, we're done with this
+            // block; the enclosing block reads the line's indentation again
             if current_indent < base_indent {
+                self.position = line_start;
                 break;
             }
 
@@ -0,0 +1,356 @@
+//! PHP config file parser
+//!
+//! PHPStan also reads config files written in PHP, such as `phpstan-baseline.php`:
+//!
+//! ```php
+//! <?php declare(strict_types = 1);
+//!
+//! $ignoreErrors = [];
+//! $ignoreErrors[] = [
+//!     'message' => '#^Call to undefined function foo\(\)\.$#',
+//!     'count' => 1,
+//!     'path' => __DIR__ . '/src/foo.php',
+//! ];
+//!
+//! return ['parameters' => ['ignoreErrors' => $ignoreErrors]];
+//! ```
+//!
+//! The file is parsed with mago-syntax and the subset of PHP these files use is
+//! evaluated into the same [`Value`] NEON files produce: array literals, scalars,
+//! `__DIR__` and `__FILE__`, string concatenation, local variables and `return`.
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use super::neon::Value;
+
+/// Error evaluating a PHP config file
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error("Syntax error: {0}")]
+    Syntax(String),
+    #[error("Unsupported PHP at line {line}: {code}")]
+    Unsupported { line: usize, code: String },
+    #[error("Undefined variable {name} at line {line}")]
+    UndefinedVariable { line: usize, name: String },
+    #[error("The file does not return a value")]
+    NoReturn,
+}
+
+/// Parse a PHP config file into the value it returns
+///
+/// `path` is used for `__DIR__` and `__FILE__`, which evaluate to absolute paths.
+pub fn parse(content: &str, path: &Path) -> Result<Value, ParseError> {
+    let arena = Bump::new();
+    let file_id = FileId::new(path.to_string_lossy().as_ref());
+    let (program, error) = mago_syntax::parser::parse_file_content(&arena, file_id, content);
+    if let Some(error) = error {
+        return Err(ParseError::Syntax(error.to_string()));
+    }
+
+    let file = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let dir = file.parent().unwrap_or(Path::new("/"));
+    let mut evaluator = Evaluator {
+        content,
+        file: file.to_string_lossy().into_owned(),
+        dir: dir.to_string_lossy().into_owned(),
+        variables: HashMap::new(),
+    };
+
+    for statement in program.statements.iter() {
+        if let Some(value) = evaluator.statement(statement)? {
+            return Ok(value.into_value());
+        }
+    }
+    Err(ParseError::NoReturn)
+}
+
+/// Make a path built from `__DIR__` relative to the current directory
+///
+/// PHP config files give absolute paths while NEON files give relative
+/// ones, and error paths are matched by suffix. `..` is resolved; paths
+/// outside the current directory stay absolute and relative paths are
+/// returned as is.
+pub fn relative_path(path: &str) -> String {
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return path.to_string_lossy().into_owned();
+    }
+
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    let relative = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| normalized.strip_prefix(cwd).ok().map(Path::to_path_buf));
+    relative.unwrap_or(normalized).to_string_lossy().into_owned()
+}
+
+/// A PHP value; arrays keep their keys in order
+#[derive(Debug, Clone)]
+enum PhpValue {
+    Scalar(Value),
+    Array(Vec<(Option<String>, PhpValue)>),
+}
+
+impl PhpValue {
+    /// Lists become arrays, arrays with keys become objects
+    fn into_value(self) -> Value {
+        match self {
+            PhpValue::Scalar(value) => value,
+            PhpValue::Array(items) if items.iter().all(|(key, _)| key.is_none()) => {
+                Value::Array(items.into_iter().map(|(_, value)| value.into_value()).collect())
+            }
+            PhpValue::Array(items) => {
+                let mut next_index = 0;
+                let mut object = HashMap::new();
+                for (key, value) in items {
+                    let key = key.unwrap_or_else(|| {
+                        next_index += 1;
+                        (next_index - 1).to_string()
+                    });
+                    object.insert(key, value.into_value());
+                }
+                Value::Object(object)
+            }
+        }
+    }
+}
+
+struct Evaluator<'c> {
+    content: &'c str,
+    file: String,
+    dir: String,
+    variables: HashMap<String, PhpValue>,
+}
+
+impl Evaluator<'_> {
+    /// Run a statement, returning the value of a `return`
+    fn statement(&mut self, statement: &Statement<'_>) -> Result<Option<PhpValue>, ParseError> {
+        match statement {
+            Statement::OpeningTag(_) | Statement::ClosingTag(_) | Statement::Declare(_) | Statement::Noop(_) => Ok(None),
+            Statement::Inline(inline) if inline.value.trim().is_empty() => Ok(None),
+            Statement::Return(ret) => match &ret.value {
+                Some(value) => Ok(Some(self.expression(value)?)),
+                None => Err(ParseError::NoReturn),
+            },
+            Statement::Expression(statement) => {
+                self.assignment(statement.expression)?;
+                Ok(None)
+            }
+            other => Err(self.unsupported(other)),
+        }
+    }
+
+    /// `$var = ...`, `$var[] = ...` or `$var['key'] = ...`
+    fn assignment(&mut self, expression: &Expression<'_>) -> Result<(), ParseError> {
+        let Expression::Assignment(assignment) = expression else {
+            return Err(self.unsupported(expression));
+        };
+        if !matches!(assignment.operator, AssignmentOperator::Assign(_)) {
+            return Err(self.unsupported(expression));
+        }
+        let value = self.expression(assignment.rhs)?;
+
+        let (target, key) = match assignment.lhs {
+            Expression::Variable(Variable::Direct(variable)) => {
+                self.variables.insert(variable.name.to_string(), value);
+                return Ok(());
+            }
+            Expression::ArrayAppend(append) => (append.array, None),
+            Expression::ArrayAccess(access) => (access.array, Some(self.key(access.index)?)),
+            _ => return Err(self.unsupported(expression)),
+        };
+        let Expression::Variable(Variable::Direct(variable)) = target else {
+            return Err(self.unsupported(expression));
+        };
+
+        let array = self
+            .variables
+            .entry(variable.name.to_string())
+            .or_insert_with(|| PhpValue::Array(Vec::new()));
+        let PhpValue::Array(items) = array else {
+            return Err(self.unsupported(expression));
+        };
+        match key {
+            Some(key) => match items.iter_mut().find(|(k, _)| k.as_deref() == Some(key.as_str())) {
+                Some((_, existing)) => *existing = value,
+                None => items.push((Some(key), value)),
+            },
+            None => items.push((None, value)),
+        }
+        Ok(())
+    }
+
+    fn expression(&self, expression: &Expression<'_>) -> Result<PhpValue, ParseError> {
+        let scalar = |value| Ok(PhpValue::Scalar(value));
+        match expression {
+            Expression::Literal(literal) => match literal {
+                Literal::String(string) => scalar(Value::String(string.value.unwrap_or(string.raw).to_string())),
+                Literal::Integer(integer) => match integer.value {
+                    Some(value) => scalar(Value::Integer(value as i64)),
+                    None => Err(self.unsupported(expression)),
+                },
+                Literal::Float(float) => scalar(Value::Float(float.value.into_inner())),
+                Literal::True(_) => scalar(Value::Bool(true)),
+                Literal::False(_) => scalar(Value::Bool(false)),
+                Literal::Null(_) => scalar(Value::Null),
+            },
+            Expression::MagicConstant(MagicConstant::Directory(_)) => scalar(Value::String(self.dir.clone())),
+            Expression::MagicConstant(MagicConstant::File(_)) => scalar(Value::String(self.file.clone())),
+            Expression::Parenthesized(parenthesized) => self.expression(parenthesized.expression),
+            Expression::Binary(binary) if matches!(binary.operator, BinaryOperator::StringConcat(_)) => {
+                let lhs = self.string(binary.lhs)?;
+                let rhs = self.string(binary.rhs)?;
+                scalar(Value::String(lhs + &rhs))
+            }
+            Expression::Variable(Variable::Direct(variable)) => {
+                self.variables.get(variable.name).cloned().ok_or_else(|| ParseError::UndefinedVariable {
+                    line: self.line(expression),
+                    name: variable.name.to_string(),
+                })
+            }
+            Expression::Array(array) => self.array(array.elements.iter()),
+            Expression::LegacyArray(array) => self.array(array.elements.iter()),
+            _ => Err(self.unsupported(expression)),
+        }
+    }
+
+    fn array<'a, 'arena: 'a>(
+        &self,
+        elements: impl Iterator<Item = &'a ArrayElement<'arena>>,
+    ) -> Result<PhpValue, ParseError> {
+        let mut items: Vec<(Option<String>, PhpValue)> = Vec::new();
+        for element in elements {
+            match element {
+                ArrayElement::KeyValue(element) => {
+                    let key = self.key(element.key)?;
+                    let value = self.expression(element.value)?;
+                    items.retain(|(k, _)| k.as_deref() != Some(key.as_str()));
+                    items.push((Some(key), value));
+                }
+                ArrayElement::Value(element) => items.push((None, self.expression(element.value)?)),
+                ArrayElement::Variadic(element) => match self.expression(element.value)? {
+                    PhpValue::Array(spread) => items.extend(spread),
+                    PhpValue::Scalar(_) => return Err(self.unsupported(element.value)),
+                },
+                ArrayElement::Missing(_) => {}
+            }
+        }
+        Ok(PhpValue::Array(items))
+    }
+
+    /// An array key, as a string
+    fn key(&self, expression: &Expression<'_>) -> Result<String, ParseError> {
+        self.string(expression)
+    }
+
+    /// A scalar converted to a string, like PHP's string conversion
+    fn string(&self, expression: &Expression<'_>) -> Result<String, ParseError> {
+        match self.expression(expression)? {
+            PhpValue::Scalar(Value::String(s)) => Ok(s),
+            PhpValue::Scalar(Value::Integer(n)) => Ok(n.to_string()),
+            PhpValue::Scalar(Value::Float(n)) => Ok(n.to_string()),
+            PhpValue::Scalar(Value::Bool(true)) => Ok("1".to_string()),
+            PhpValue::Scalar(Value::Bool(false) | Value::Null) => Ok(String::new()),
+            _ => Err(self.unsupported(expression)),
+        }
+    }
+
+    fn line(&self, node: &impl HasSpan) -> usize {
+        let offset = (node.span().start.offset as usize).min(self.content.len());
+        self.content[..offset].matches('\n').count() + 1
+    }
+
+    fn unsupported(&self, node: &impl HasSpan) -> ParseError {
+        let span = node.span();
+        let code = self
+            .content
+            .get(span.start.offset as usize..span.end.offset as usize)
+            .unwrap_or_default();
+        ParseError::Unsupported {
+            line: self.line(node),
+            code: code.lines().next().unwrap_or_default().chars().take(60).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_phpstan_baseline() {
+        let content = r#"<?php declare(strict_types = 1);
+
+$ignoreErrors = [];
+$ignoreErrors[] = [
+	'message' => '#^Call to undefined function foo\\(\\)\\.$#',
+	'identifier' => 'function.notFound',
+	'count' => 2,
+	'path' => __DIR__ . '/src/test.php',
+];
+$ignoreErrors[] = array(
+	'message' => '#^Variable \\$bar might not be defined\\.$#',
+	'count' => 1,
+	'path' => __DIR__ . '/../src/other.php',
+);
+
+return ['parameters' => ['ignoreErrors' => $ignoreErrors]];
+"#;
+        let value = parse(content, Path::new("/project/phpstan-baseline.php")).unwrap();
+        let errors = value.get("parameters").and_then(|p| p.get("ignoreErrors")).unwrap();
+        let errors = errors.as_array().unwrap();
+        assert_eq!(errors.len(), 2);
+
+        let first = errors[0].as_object().unwrap();
+        assert_eq!(first.get("message").and_then(|v| v.as_str()), Some("#^Call to undefined function foo\\(\\)\\.$#"));
+        assert_eq!(first.get("count").and_then(|v| v.as_i64()), Some(2));
+        assert_eq!(first.get("path").and_then(|v| v.as_str()), Some("/project/src/test.php"));
+
+        let second = errors[1].as_object().unwrap();
+        assert_eq!(second.get("message").and_then(|v| v.as_str()), Some("#^Variable \\$bar might not be defined\\.$#"));
+        assert_eq!(second.get("path").and_then(|v| v.as_str()), Some("/project/../src/other.php"));
+    }
+
+    #[test]
+    fn test_parse_includes_list() {
+        let content = "<?php\nreturn ['includes' => [__DIR__ . '/baseline/a.php', \"b.php\"]];\n";
+        let value = parse(content, Path::new("/project/loader.php")).unwrap();
+        let includes = value.get("includes").and_then(|v| v.as_array()).unwrap();
+        assert_eq!(includes[0].as_str(), Some("/project/baseline/a.php"));
+        assert_eq!(includes[1].as_str(), Some("b.php"));
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let cwd = std::env::current_dir().unwrap();
+        let path = cwd.join("baseline/../src/a.php");
+        assert_eq!(relative_path(&path.to_string_lossy()), "src/a.php");
+        assert_eq!(relative_path("/elsewhere/./src/../a.php"), "/elsewhere/a.php");
+        assert_eq!(relative_path("../src/a.php"), "../src/a.php");
+    }
+
+    #[test]
+    fn test_unsupported_code() {
+        let error = parse("<?php\n\nreturn foo();\n", Path::new("/project/a.php")).unwrap_err();
+        assert!(matches!(error, ParseError::Unsupported { line: 3, .. }));
+
+        let error = parse("<?php\nreturn $missing;\n", Path::new("/project/a.php")).unwrap_err();
+        assert!(matches!(error, ParseError::UndefinedVariable { line: 2, .. }));
+
+        assert!(matches!(parse("<?php\n$a = [];\n", Path::new("a.php")), Err(ParseError::NoReturn)));
+        assert!(matches!(parse("<?php\nreturn [;\n", Path::new("a.php")), Err(ParseError::Syntax(_))));
+    }
+}
@@ -4,7 +4,8 @@ use super::level::Level;
 use super::neon::{parse, Value};
 use crate::logging;
 use regex::Regex;
-use std::collections::HashSet;
+use rustor_core::paths::matches_pattern;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -68,6 +69,78 @@ impl IgnoreError {
     }
 }
 
+/// Exception analysis settings (`parameters.exceptions`)
+#[derive(Debug, Clone)]
+pub struct ExceptionsConfig {
+    /// Exception base classes that don't need to be declared in `@throws`
+    pub unchecked_exception_classes: Vec<String>,
+    /// If non-empty, only subclasses of these are checked exceptions
+    pub checked_exception_classes: Vec<String>,
+    /// Whether calls without `@throws` may throw anything
+    pub implicit_throws: bool,
+    /// Report checked exceptions missing from `@throws`
+    pub check_missing_checked_exception_in_throws: bool,
+    /// Report `@throws` types that are never thrown
+    pub check_too_wide_throw_type: bool,
+}
+
+impl Default for ExceptionsConfig {
+    fn default() -> Self {
+        Self {
+            unchecked_exception_classes: Vec::new(),
+            checked_exception_classes: Vec::new(),
+            implicit_throws: true,
+            check_missing_checked_exception_in_throws: false,
+            check_too_wide_throw_type: false,
+        }
+    }
+}
+
+/// Strict rule toggles (`parameters.strictRules`, phpstan-strict-rules equivalent)
+#[derive(Debug, Clone, Default)]
+pub struct StrictRulesConfig {
+    /// Only booleans in `if`/`elseif` conditions and `!` (`booleansInConditions`)
+    pub booleans_in_conditions: bool,
+    /// Disallow `==` and `!=` (`disallowedLooseComparison`)
+    pub disallowed_loose_comparison: bool,
+    /// Disallow `empty()` (`disallowedEmpty`)
+    pub disallowed_empty: bool,
+    /// Disallow variable variables and variable method calls (`noVariableVariables`)
+    pub no_variable_variables: bool,
+    /// Disallow foreach overwriting existing variables (`overwriteVariablesWithLoop`)
+    pub overwrite_variables_with_loop: bool,
+    /// Require the strict flag for `in_array()` and friends (`strictFunctionCalls`)
+    pub strict_function_calls: bool,
+    /// Disallow calling static methods with `->` (`dynamicCallOnStaticMethod`)
+    pub dynamic_call_on_static_method: bool,
+}
+
+impl StrictRulesConfig {
+    /// All strict rules enabled
+    pub fn all() -> Self {
+        Self {
+            booleans_in_conditions: true,
+            disallowed_loose_comparison: true,
+            disallowed_empty: true,
+            no_variable_variables: true,
+            overwrite_variables_with_loop: true,
+            strict_function_calls: true,
+            dynamic_call_on_static_method: true,
+        }
+    }
+
+    /// Whether any strict rule is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.booleans_in_conditions
+            || self.disallowed_loose_comparison
+            || self.disallowed_empty
+            || self.no_variable_variables
+            || self.overwrite_variables_with_loop
+            || self.strict_function_calls
+            || self.dynamic_call_on_static_method
+    }
+}
+
 /// PHPStan configuration
 #[derive(Debug, Clone)]
 pub struct PhpStanConfig {
@@ -89,6 +162,8 @@ pub struct PhpStanConfig {
     pub check_missing_typehints: bool,
     /// Report unmatched ignored errors
     pub report_unmatched_ignored_errors: bool,
+    /// Report typed properties not initialized in the constructor
+    pub check_uninitialized_properties: bool,
     /// Parallel processing threads
     pub parallel_max_processes: Option<usize>,
     /// Memory limit
@@ -105,6 +180,13 @@ pub struct PhpStanConfig {
     pub scan_directories: Vec<PathBuf>,
     /// PHPStan exact compatibility mode
     pub phpstan_compat: bool,
+    /// Report usage of deprecated symbols (`parameters.deprecationRules`, or
+    /// including phpstan-deprecation-rules)
+    pub deprecation_rules: bool,
+    /// Exception analysis settings
+    pub exceptions: ExceptionsConfig,
+    /// Strict rules (phpstan-strict-rules)
+    pub strict_rules: StrictRulesConfig,
 }
 
 impl Default for PhpStanConfig {
@@ -119,6 +201,7 @@ impl Default for PhpStanConfig {
             treat_phpdoc_types_as_certain: true,
             check_missing_typehints: false,
             report_unmatched_ignored_errors: true,
+            check_uninitialized_properties: false,
             parallel_max_processes: None,
             memory_limit: None,
             custom_rule_paths: Vec::new(),
@@ -127,6 +210,9 @@ impl Default for PhpStanConfig {
             scan_files: Vec::new(),
             scan_directories: Vec::new(),
             phpstan_compat: false,
+            deprecation_rules: false,
+            exceptions: ExceptionsConfig::default(),
+            strict_rules: StrictRulesConfig::default(),
         }
     }
 }
@@ -167,7 +253,11 @@ impl PhpStanConfig {
         logging::log(&format!("Reading config file: {}", path.display()));
 
         let content = fs::read_to_string(path)?;
-        let value = parse(&content).map_err(|e| ConfigError::ParseError(e.to_string()))?;
+        let value = if path.extension().is_some_and(|ext| ext == "php") {
+            super::php::parse(&content, path).map_err(|e| ConfigError::ParseError(e.to_string()))?
+        } else {
+            parse(&content).map_err(|e| ConfigError::ParseError(e.to_string()))?
+        };
 
         // Get the base directory for resolving relative paths
         let base_dir = path.parent().unwrap_or(Path::new("."));
@@ -201,6 +291,18 @@ impl PhpStanConfig {
 
             for item in arr {
                 if let Some(path_str) = item.as_str() {
+                    // Extension rule sets are implemented natively, no need to load them
+                    if path_str.contains("phpstan-deprecation-rules") {
+                        logging::log("Enabling deprecation rules");
+                        self.deprecation_rules = true;
+                        continue;
+                    }
+                    if path_str.contains("phpstan-strict-rules") {
+                        logging::log("Enabling strict rules");
+                        self.strict_rules = StrictRulesConfig::all();
+                        continue;
+                    }
+
                     let include_path = base_dir.join(path_str);
                     logging::log_include_start(&include_path, base_dir);
 
@@ -302,6 +404,10 @@ impl PhpStanConfig {
             logging::log_parameters_merge(&source_name, "reportUnmatchedIgnoredErrors", &b.to_string());
             self.report_unmatched_ignored_errors = *b;
         }
+        if let Some(Value::Bool(b)) = obj.get("checkUninitializedProperties") {
+            logging::log_parameters_merge(&source_name, "checkUninitializedProperties", &b.to_string());
+            self.check_uninitialized_properties = *b;
+        }
 
         // Parallel
         if let Some(parallel) = obj.get("parallel") {
@@ -313,6 +419,20 @@ impl PhpStanConfig {
             }
         }
 
+        // Exceptions
+        if let Some(Value::Object(exceptions)) = obj.get("exceptions") {
+            self.process_exceptions(exceptions, &source_name);
+        }
+
+        if let Some(Value::Bool(b)) = obj.get("deprecationRules") {
+            logging::log_parameters_merge(&source_name, "deprecationRules", &b.to_string());
+            self.deprecation_rules = *b;
+        }
+
+        if let Some(Value::Object(strict_rules)) = obj.get("strictRules") {
+            self.process_strict_rules(strict_rules, &source_name);
+        }
+
         // Stub files
         if let Some(stubs) = obj.get("stubFiles") {
             if let Some(arr) = stubs.as_array() {
@@ -370,6 +490,62 @@ impl PhpStanConfig {
         Ok(())
     }
 
+    fn process_exceptions(&mut self, exceptions: &HashMap<String, Value>, source_name: &str) {
+        for (key, target) in [
+            ("uncheckedExceptionClasses", &mut self.exceptions.unchecked_exception_classes),
+            ("checkedExceptionClasses", &mut self.exceptions.checked_exception_classes),
+        ] {
+            if let Some(Value::Array(arr)) = exceptions.get(key) {
+                logging::log_parameters_merge(source_name, &format!("exceptions.{}", key), &format!("{} classes", arr.len()));
+                for class in arr {
+                    if let Some(s) = class.as_str() {
+                        target.push(s.trim_start_matches('\\').to_string());
+                    }
+                }
+            }
+        }
+
+        if let Some(Value::Bool(b)) = exceptions.get("implicitThrows") {
+            logging::log_parameters_merge(source_name, "exceptions.implicitThrows", &b.to_string());
+            self.exceptions.implicit_throws = *b;
+        }
+
+        if let Some(Value::Object(check)) = exceptions.get("check") {
+            if let Some(Value::Bool(b)) = check.get("missingCheckedExceptionInThrows") {
+                logging::log_parameters_merge(source_name, "exceptions.check.missingCheckedExceptionInThrows", &b.to_string());
+                self.exceptions.check_missing_checked_exception_in_throws = *b;
+            }
+            if let Some(Value::Bool(b)) = check.get("tooWideThrowType") {
+                logging::log_parameters_merge(source_name, "exceptions.check.tooWideThrowType", &b.to_string());
+                self.exceptions.check_too_wide_throw_type = *b;
+            }
+        }
+    }
+
+    fn process_strict_rules(&mut self, strict_rules: &HashMap<String, Value>, source_name: &str) {
+        // allRules sets the default, individual toggles override it
+        if let Some(Value::Bool(b)) = strict_rules.get("allRules") {
+            logging::log_parameters_merge(source_name, "strictRules.allRules", &b.to_string());
+            self.strict_rules = if *b { StrictRulesConfig::all() } else { StrictRulesConfig::default() };
+        }
+
+        let rules = &mut self.strict_rules;
+        for (key, target) in [
+            ("booleansInConditions", &mut rules.booleans_in_conditions),
+            ("disallowedLooseComparison", &mut rules.disallowed_loose_comparison),
+            ("disallowedEmpty", &mut rules.disallowed_empty),
+            ("noVariableVariables", &mut rules.no_variable_variables),
+            ("overwriteVariablesWithLoop", &mut rules.overwrite_variables_with_loop),
+            ("strictFunctionCalls", &mut rules.strict_function_calls),
+            ("dynamicCallOnStaticMethod", &mut rules.dynamic_call_on_static_method),
+        ] {
+            if let Some(Value::Bool(b)) = strict_rules.get(key) {
+                logging::log_parameters_merge(source_name, &format!("strictRules.{}", key), &b.to_string());
+                *target = *b;
+            }
+        }
+    }
+
     fn process_exclude_paths(&mut self, exclude: &Value, base_dir: &Path, source_name: &str) {
         match exclude {
             Value::Array(arr) => {
@@ -426,7 +602,7 @@ impl PhpStanConfig {
                         .and_then(|v| v.as_str())
                         .unwrap_or("")
                         .to_string();
-                    let path = obj.get("path").and_then(|v| v.as_str()).map(String::from);
+                    let path = obj.get("path").and_then(|v| v.as_str()).map(super::php::relative_path);
                     let count = obj.get("count").and_then(|v| v.as_i64()).map(|n| n as usize);
                     let identifier = obj
                         .get("identifier")
@@ -468,29 +644,11 @@ impl PhpStanConfig {
     /// Check if a path should be excluded
     pub fn is_excluded(&self, path: &Path) -> bool {
         for exclude in &self.exclude_paths {
-            if path.starts_with(exclude) {
-                logging::log(&format!(
-                    "EXCLUDED: {} (matched prefix: {})",
-                    path.display(),
-                    exclude.display()
-                ));
+            let pattern = exclude.to_string_lossy();
+            if matches_pattern(&pattern, path) {
+                logging::log(&format!("EXCLUDED: {} (matched: {})", path.display(), pattern));
                 return true;
             }
-            // Check glob patterns
-            let exclude_str = exclude.to_string_lossy();
-            if exclude_str.contains('*') {
-                // Simple glob matching
-                if let Ok(pattern) = glob::Pattern::new(&exclude_str) {
-                    if pattern.matches_path(path) {
-                        logging::log(&format!(
-                            "EXCLUDED: {} (matched glob: {})",
-                            path.display(),
-                            exclude_str
-                        ));
-                        return true;
-                    }
-                }
-            }
         }
         false
     }
@@ -608,19 +766,25 @@ parameters:
     #[test]
     fn test_should_ignore_error() {
         let mut config = PhpStanConfig::default();
-        config.ignore_errors.push(IgnoreError {
-            message: "undefined function".to_string(),
-            path: None,
-            is_regex: false,
-            count: None,
-            identifier: None,
-        });
+        config.ignore_errors.push(IgnoreError::new(
+            "Call to undefined function foo()".to_string(),
+            None,
+            false,
+            None,
+            None,
+        ));
 
         assert!(config.should_ignore_error(
             "Call to undefined function foo()",
             Path::new("test.php"),
             None
         ));
+        // Plain messages match exactly, not as a substring
+        assert!(!config.should_ignore_error(
+            "Call to undefined function foo() in closure",
+            Path::new("test.php"),
+            None
+        ));
         assert!(!config.should_ignore_error(
             "Undefined variable $bar",
             Path::new("test.php"),
@@ -689,6 +853,149 @@ parameters:
         assert_eq!(config.includes.len(), 1);
     }
 
+    #[test]
+    fn test_php_include() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let baseline_content = r#"<?php declare(strict_types = 1);
+
+$ignoreErrors = [];
+$ignoreErrors[] = [
+	'message' => '#^Error from baseline$#',
+	'identifier' => 'baseline.error',
+	'count' => 1,
+	'path' => __DIR__ . '/src/a.php',
+];
+
+return ['parameters' => ['ignoreErrors' => $ignoreErrors]];
+"#;
+        std::fs::write(temp_dir.path().join("phpstan-baseline.php"), baseline_content).unwrap();
+        let main_path = temp_dir.path().join("phpstan.neon");
+        std::fs::write(&main_path, "includes:\n    - phpstan-baseline.php\nparameters:\n    level: 3\n").unwrap();
+
+        let config = PhpStanConfig::load(&main_path).unwrap();
+        assert_eq!(config.level, Level::Level3);
+        assert_eq!(config.ignore_errors.len(), 1);
+        assert_eq!(config.ignore_errors[0].identifier.as_deref(), Some("baseline.error"));
+        let expected = temp_dir.path().join("src/a.php");
+        assert_eq!(config.ignore_errors[0].path.as_deref(), Some(expected.to_string_lossy().as_ref()));
+    }
+
+    #[test]
+    fn test_deprecation_rules_include() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let main_content = "includes:\n    - vendor/phpstan/phpstan-deprecation-rules/rules.neon\nparameters:\n    level: 2\n";
+        let main_path = temp_dir.path().join("phpstan.neon");
+        std::fs::write(&main_path, main_content).unwrap();
+
+        let config = PhpStanConfig::load(&main_path).unwrap();
+        assert!(config.deprecation_rules);
+        assert!(config.includes.is_empty());
+    }
+
+    #[test]
+    fn test_deprecation_rules_parameter() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+        let main_path = temp_dir.path().join("phpstan.neon");
+        std::fs::write(&main_path, "parameters:\n    deprecationRules: true\n").unwrap();
+        assert!(PhpStanConfig::load(&main_path).unwrap().deprecation_rules);
+
+        // The parameter turns off rules the include turned on
+        let content = "includes:\n    - vendor/phpstan/phpstan-deprecation-rules/rules.neon\nparameters:\n    deprecationRules: false\n";
+        std::fs::write(&main_path, content).unwrap();
+        assert!(!PhpStanConfig::load(&main_path).unwrap().deprecation_rules);
+        assert!(!PhpStanConfig::default().deprecation_rules);
+    }
+
+    #[test]
+    fn test_parse_exceptions() {
+        let content = r#"
+parameters:
+    exceptions:
+        uncheckedExceptionClasses:
+            - 'LogicException'
+            - '\App\Unchecked'
+        implicitThrows: false
+        check:
+            missingCheckedExceptionInThrows: true
+            tooWideThrowType: true
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let config = PhpStanConfig::load(file.path()).unwrap();
+        assert_eq!(
+            config.exceptions.unchecked_exception_classes,
+            vec!["LogicException".to_string(), "App\\Unchecked".to_string()]
+        );
+        assert!(!config.exceptions.implicit_throws);
+        assert!(config.exceptions.check_missing_checked_exception_in_throws);
+        assert!(config.exceptions.check_too_wide_throw_type);
+    }
+
+    #[test]
+    fn test_parse_exceptions_check_first() {
+        // PHPStan's documented layout, with the nested `check` map first
+        let content = r#"
+parameters:
+    exceptions:
+        check:
+            missingCheckedExceptionInThrows: true
+            tooWideThrowType: true
+        uncheckedExceptionClasses:
+            - 'LogicException'
+        implicitThrows: false
+    level: 5
+"#;
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+
+        let config = PhpStanConfig::load(file.path()).unwrap();
+        assert_eq!(config.exceptions.unchecked_exception_classes, vec!["LogicException".to_string()]);
+        assert!(!config.exceptions.implicit_throws);
+        assert!(config.exceptions.check_missing_checked_exception_in_throws);
+        assert!(config.exceptions.check_too_wide_throw_type);
+        assert_eq!(config.level, Level::Level5);
+    }
+
+    #[test]
+    fn test_strict_rules_config() {
+        use tempfile::TempDir;
+
+        let temp_dir = TempDir::new().unwrap();
+
+        // Including the extension enables every rule without reading the file
+        let included = "includes:\n    - vendor/phpstan/phpstan-strict-rules/rules.neon\n";
+        let included_path = temp_dir.path().join("included.neon");
+        std::fs::write(&included_path, included).unwrap();
+        let config = PhpStanConfig::load(&included_path).unwrap();
+        assert!(config.strict_rules.is_enabled());
+        assert!(config.strict_rules.disallowed_empty);
+        assert!(config.strict_rules.dynamic_call_on_static_method);
+
+        let toggled = r#"
+parameters:
+    strictRules:
+        allRules: false
+        booleansInConditions: true
+        disallowedLooseComparison: true
+"#;
+        let toggled_path = temp_dir.path().join("toggled.neon");
+        std::fs::write(&toggled_path, toggled).unwrap();
+        let config = PhpStanConfig::load(&toggled_path).unwrap();
+        assert!(config.strict_rules.booleans_in_conditions);
+        assert!(config.strict_rules.disallowed_loose_comparison);
+        assert!(!config.strict_rules.disallowed_empty);
+        assert!(!config.strict_rules.no_variable_variables);
+
+        assert!(!PhpStanConfig::default().strict_rules.is_enabled());
+    }
+
     #[test]
     fn test_includes_with_tab_indentation() {
         use tempfile::TempDir;
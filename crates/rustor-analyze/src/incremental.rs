@@ -0,0 +1,265 @@
+//! Incremental re-analysis for watch mode
+//!
+//! Keeps the symbol table and the issues of each file between runs. When
+//! files change, their symbols are replaced in the symbol table and only the
+//! changed files, plus the files that looked up a symbol whose signature
+//! changed, are analyzed again.
+
+use crate::cache::{symbol_signature, SymbolKind};
+use crate::issue::{Issue, IssueCollection};
+use crate::resolver::symbol_collector::{CollectedSymbols, SymbolCollector};
+use crate::symbols::{Lookups, SymbolTable};
+use crate::{collect_file_symbols, AnalyzeError, Analyzer};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Result of analyzing one file
+struct FileResult {
+    issues: Vec<Issue>,
+    /// Symbols looked up while analyzing the file
+    lookups: Lookups,
+}
+
+/// Analysis of a set of paths that is kept up to date as files change
+pub struct IncrementalAnalysis<'a> {
+    analyzer: &'a Analyzer,
+    paths: Vec<PathBuf>,
+    symbol_table: SymbolTable,
+    /// Symbols declared by each analyzed file
+    symbols: BTreeMap<PathBuf, CollectedSymbols>,
+    results: BTreeMap<PathBuf, FileResult>,
+}
+
+impl<'a> IncrementalAnalysis<'a> {
+    /// Analyze all files under `paths`
+    pub fn new(analyzer: &'a Analyzer, paths: &[&Path]) -> Self {
+        let files = analyzer.collect_files(paths);
+        let symbols: BTreeMap<_, _> = analyzer
+            .collect_symbols(&files)
+            .into_iter()
+            .map(|symbols| (symbols.file_path.clone(), symbols))
+            .collect();
+        let symbol_table = analyzer.build_symbol_table(paths, &files, symbols.values().cloned().collect());
+
+        let mut analysis = Self {
+            analyzer,
+            paths: paths.iter().map(|p| p.to_path_buf()).collect(),
+            symbol_table,
+            symbols,
+            results: BTreeMap::new(),
+        };
+        analysis.analyze(&files);
+        analysis
+    }
+
+    /// Update the analysis after files were modified, created or deleted
+    ///
+    /// Files outside the analyzed paths are ignored; created and deleted files
+    /// are picked up even if they are not listed. Returns every file that was
+    /// analyzed again or dropped.
+    pub fn update(&mut self, changed: &[PathBuf]) -> Vec<PathBuf> {
+        let paths: Vec<&Path> = self.paths.iter().map(|p| p.as_path()).collect();
+        let current: BTreeSet<PathBuf> = self.analyzer.collect_files(&paths).into_iter().collect();
+
+        let mut modified: BTreeSet<PathBuf> = changed
+            .iter()
+            .filter(|file| current.contains(*file) || self.results.contains_key(*file))
+            .cloned()
+            .collect();
+        modified.extend(current.iter().filter(|file| !self.results.contains_key(*file)).cloned());
+        modified.extend(self.results.keys().filter(|file| !current.contains(*file)).cloned());
+
+        // Replace the symbols of modified files, remembering what they declared
+        // before and after so changed signatures can be found
+        let mut declared = Lookups::default();
+        let mut replacements = Vec::new();
+        for file in &modified {
+            let symbols = if current.contains(file) { collect_file_symbols(file) } else { None };
+            for symbols in self.symbols.get(file).into_iter().chain(symbols.as_ref()) {
+                add_declared(&mut declared, symbols);
+            }
+            replacements.push((file.clone(), symbols));
+        }
+
+        let before = signatures(&self.symbol_table, &declared);
+        // Remove everything first, so a symbol moved between files survives
+        for (file, _) in &replacements {
+            if let Some(old) = self.symbols.remove(file) {
+                SymbolCollector::unregister_symbols(&mut self.symbol_table, &old);
+            }
+        }
+        for (file, symbols) in replacements {
+            if let Some(symbols) = symbols {
+                SymbolCollector::register_symbols(&mut self.symbol_table, symbols.clone());
+                self.symbols.insert(file, symbols);
+            }
+        }
+        let after = signatures(&self.symbol_table, &declared);
+
+        let mut changed_symbols = Lookups::default();
+        for ((kind, name), (old, new)) in keys(&declared).zip(before.into_iter().zip(after)) {
+            if old != new {
+                insert_key(&mut changed_symbols, kind, name.clone());
+            }
+        }
+
+        // Modified files and files depending on a changed symbol
+        let mut stale: BTreeSet<PathBuf> = modified.iter().filter(|file| current.contains(*file)).cloned().collect();
+        stale.extend(
+            self.results
+                .iter()
+                .filter(|(file, result)| current.contains(*file) && result.lookups.intersects(&changed_symbols))
+                .map(|(file, _)| file.clone()),
+        );
+
+        for file in modified.iter().filter(|file| !current.contains(*file)) {
+            self.results.remove(file);
+        }
+        let stale: Vec<PathBuf> = stale.into_iter().collect();
+        self.analyze(&stale);
+
+        modified.extend(stale);
+        modified.into_iter().collect()
+    }
+
+    /// Issues of a file, empty if it has none or is not analyzed
+    pub fn file_issues(&self, file: &Path) -> &[Issue] {
+        self.results.get(file).map_or(&[], |result| result.issues.as_slice())
+    }
+
+    /// Analyzed files
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.results.keys().map(|file| file.as_path())
+    }
+
+    /// All current issues, sorted
+    pub fn issues(&self) -> IssueCollection {
+        let mut issues = IssueCollection::new();
+        for result in self.results.values() {
+            issues.extend(result.issues.iter().cloned());
+        }
+        issues.sort();
+        issues
+    }
+
+    /// Analyze files against the current symbol table
+    fn analyze(&mut self, files: &[PathBuf]) {
+        let results: Vec<_> = files
+            .par_iter()
+            .map(|file| {
+                let (issues, lookups) = SymbolTable::record_lookups(|| -> Result<_, AnalyzeError> {
+                    let source = fs::read_to_string(file)?;
+                    self.analyzer.analyze_source_with_symbols(file, &source, &self.symbol_table)
+                });
+                (file, issues, lookups)
+            })
+            .collect();
+
+        for (file, issues, lookups) in results {
+            match issues {
+                Ok(issues) => {
+                    let issues = issues.into_issues();
+                    self.results.insert(file.clone(), FileResult { issues, lookups });
+                }
+                Err(e) => {
+                    // Log error but continue
+                    eprintln!("Warning: {}", e);
+                    self.results.remove(file);
+                }
+            }
+        }
+    }
+}
+
+/// Lookup keys of the symbols a file declares
+fn add_declared(keys: &mut Lookups, symbols: &CollectedSymbols) {
+    keys.classes.extend(symbols.classes.iter().map(|c| c.full_name.to_lowercase()));
+    keys.functions.extend(symbols.functions.iter().map(|f| f.full_name.to_lowercase()));
+    keys.constants.extend(symbols.constants.iter().map(|(name, _)| name.clone()));
+}
+
+fn insert_key(keys: &mut Lookups, kind: SymbolKind, name: String) {
+    match kind {
+        SymbolKind::Class => keys.classes.insert(name),
+        SymbolKind::Function => keys.functions.insert(name),
+        SymbolKind::Constant => keys.constants.insert(name),
+    };
+}
+
+fn keys(lookups: &Lookups) -> impl Iterator<Item = (SymbolKind, &String)> {
+    let classes = lookups.classes.iter().map(|name| (SymbolKind::Class, name));
+    let functions = lookups.functions.iter().map(|name| (SymbolKind::Function, name));
+    let constants = lookups.constants.iter().map(|name| (SymbolKind::Constant, name));
+    classes.chain(functions).chain(constants)
+}
+
+fn signatures(table: &SymbolTable, lookups: &Lookups) -> Vec<Option<u64>> {
+    keys(lookups).map(|(kind, name)| symbol_signature(table, kind, name)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write(dir: &TempDir, name: &str, source: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_dependents_reanalyzed() {
+        let dir = TempDir::new().unwrap();
+        let caller = write(&dir, "caller.php", "<?php\nformat_name();\n");
+        let helpers = write(&dir, "helpers.php", "<?php\n");
+        let other = write(&dir, "other.php", "<?php\necho 1;\n");
+
+        let analyzer = Analyzer::with_defaults();
+        let mut analysis = IncrementalAnalysis::new(&analyzer, &[dir.path()]);
+        assert_eq!(analysis.file_issues(&caller).len(), 1);
+
+        write(&dir, "helpers.php", "<?php\nfunction format_name() {}\n");
+        let updated = analysis.update(std::slice::from_ref(&helpers));
+
+        assert_eq!(updated, vec![caller.clone(), helpers]);
+        assert!(!updated.contains(&other));
+        assert!(analysis.file_issues(&caller).is_empty());
+    }
+
+    #[test]
+    fn test_unchanged_signature_keeps_dependents() {
+        let dir = TempDir::new().unwrap();
+        write(&dir, "caller.php", "<?php\nformat_name();\n");
+        let helpers = write(&dir, "helpers.php", "<?php\nfunction format_name() {}\n");
+
+        let analyzer = Analyzer::with_defaults();
+        let mut analysis = IncrementalAnalysis::new(&analyzer, &[dir.path()]);
+
+        // Adding a blank line moves the function but doesn't change its signature
+        write(&dir, "helpers.php", "<?php\n\nfunction format_name() {}\n");
+        assert_eq!(analysis.update(std::slice::from_ref(&helpers)), vec![helpers]);
+    }
+
+    #[test]
+    fn test_created_and_deleted_files() {
+        let dir = TempDir::new().unwrap();
+        let caller = write(&dir, "caller.php", "<?php\nformat_name();\n");
+        let helpers = write(&dir, "helpers.php", "<?php\nfunction format_name() {}\n");
+
+        let analyzer = Analyzer::with_defaults();
+        let mut analysis = IncrementalAnalysis::new(&analyzer, &[dir.path()]);
+        assert!(analysis.issues().is_empty());
+
+        fs::remove_file(&helpers).unwrap();
+        let created = write(&dir, "new.php", "<?php\nundefined_thing();\n");
+        let updated = analysis.update(&[]);
+
+        assert_eq!(updated, vec![caller.clone(), helpers, created.clone()]);
+        assert_eq!(analysis.file_issues(&caller).len(), 1);
+        assert_eq!(analysis.file_issues(&created).len(), 1);
+        assert_eq!(analysis.files().count(), 2);
+    }
+}
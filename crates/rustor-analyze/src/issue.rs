@@ -1,9 +1,10 @@
 //! Issue/diagnostic types for static analysis results
 
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Severity level for issues
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Severity {
     /// Error - must be fixed
     Error,
@@ -21,7 +22,7 @@ impl std::fmt::Display for Severity {
 }
 
 /// A single issue found during analysis
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
     /// The check that found this issue (e.g., "undefined.variable")
     pub check_id: String,
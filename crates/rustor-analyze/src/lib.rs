@@ -30,8 +30,10 @@
 
 pub mod autoload;
 pub mod baseline;
+pub mod cache;
 pub mod checks;
 pub mod config;
+pub mod incremental;
 pub mod issue;
 pub mod logging;
 pub mod output;
@@ -40,30 +42,41 @@ pub mod scope;
 pub mod symbols;
 pub mod types;
 
+use autoload::include_scanner::IncludeScanner;
 use autoload::AutoloadScanner;
+use cache::{AnalysisCache, CachedFile};
 use checks::{CheckContext, CheckRegistry, PHP_BUILTIN_CLASSES, PHP_BUILTIN_FUNCTIONS};
 use config::composer::ComposerJson;
 use config::PhpStanConfig;
-use issue::IssueCollection;
+use issue::{Issue, IssueCollection};
 use mago_database::file::FileId;
 use rayon::prelude::*;
-use resolver::symbol_collector::SymbolCollector;
+use resolver::symbol_collector::{CollectedSymbols, SymbolCollector};
+use rustor_core::PARSE_ERROR_IDENTIFIER;
 use symbols::SymbolTable;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
 /// Main analyzer that runs static analysis checks
 pub struct Analyzer {
     config: PhpStanConfig,
     registry: CheckRegistry,
+    /// Whether `analyze_paths` reuses results from the analysis cache
+    use_cache: bool,
 }
 
 impl Analyzer {
     /// Create a new analyzer with the given configuration
     pub fn new(config: PhpStanConfig) -> Self {
-        let registry = CheckRegistry::with_builtin_checks();
-        Self { config, registry }
+        let mut registry = CheckRegistry::with_builtin_checks();
+        if config.deprecation_rules {
+            registry.register_deprecation_checks();
+        }
+        if config.strict_rules.is_enabled() {
+            registry.register_strict_rules_checks();
+        }
+        Self { config, registry, use_cache: false }
     }
 
     /// Create analyzer with default configuration
@@ -76,6 +89,14 @@ impl Analyzer {
         self.config.level = level;
     }
 
+    /// Enable the incremental analysis cache
+    ///
+    /// Results are kept in `.rustor-cache/` of the project directory and a
+    /// file is only analyzed again when it or a symbol it uses changed.
+    pub fn set_cache(&mut self, enabled: bool) {
+        self.use_cache = enabled;
+    }
+
     /// Get the current configuration
     pub fn config(&self) -> &PhpStanConfig {
         &self.config
@@ -92,19 +113,16 @@ impl Analyzer {
         // Parse the PHP file using bumpalo arena
         let arena = bumpalo::Bump::new();
         let file_id = FileId::new(path.to_string_lossy().as_ref());
-        let (program, parse_error) = mago_syntax::parser::parse_file_content(&arena, file_id, source);
+        let parsed = rustor_core::parse(&arena, file_id, source);
+        let program = parsed.program;
 
+        // Report parse errors; the checks still run on the statements without them
         let mut issues = IssueCollection::new();
-
-        // Report parse errors
-        if let Some(error) = parse_error {
-            issues.add(issue::Issue::error(
-                "parse.error",
-                error.to_string(),
-                path.to_path_buf(),
-                1, // TODO: Get line from error
-                1,
-            ));
+        for error in parsed.errors {
+            issues.add(
+                issue::Issue::error(PARSE_ERROR_IDENTIFIER, error.message, path.to_path_buf(), error.line, error.column)
+                    .with_identifier(PARSE_ERROR_IDENTIFIER),
+            );
         }
 
         // Create check context
@@ -140,7 +158,53 @@ impl Analyzer {
 
     /// Analyze multiple paths (files or directories)
     pub fn analyze_paths(&self, paths: &[&Path]) -> Result<IssueCollection, AnalyzeError> {
-        // Collect all PHP files
+        let files = self.collect_files(paths);
+
+        // First pass: collect symbols from all files to build symbol table
+        let collected_symbols = self.collect_symbols(&files);
+        let symbol_table = self.build_symbol_table(paths, &files, collected_symbols);
+
+        // Second pass: analyze files with symbol table, reusing cached results
+        // of files whose content and dependencies are unchanged
+        let mut cache = self.use_cache.then(|| AnalysisCache::load(project_dir(paths), &self.config));
+        let results: Vec<_> = files
+            .par_iter()
+            .map(|file| self.analyze_file_cached(file, &symbol_table, cache.as_ref()))
+            .collect();
+
+        // Combine results
+        let mut combined = IssueCollection::new();
+        let mut reused = 0;
+        for (file, result) in files.iter().zip(results) {
+            match result {
+                Ok((issues, entry)) => {
+                    match (cache.as_mut(), entry) {
+                        (Some(cache), Some(entry)) => cache.insert(file.clone(), entry),
+                        (Some(_), None) => reused += 1,
+                        _ => {}
+                    }
+                    combined.extend(issues);
+                }
+                Err(e) => {
+                    // Log error but continue
+                    eprintln!("Warning: {}", e);
+                }
+            }
+        }
+
+        if let Some(cache) = cache {
+            eprintln!("Cache: reused results for {} of {} files", reused, files.len());
+            if let Err(e) = cache.save() {
+                eprintln!("Warning: failed to save analysis cache: {}", e);
+            }
+        }
+
+        combined.sort();
+        Ok(combined)
+    }
+
+    /// Collect the PHP files to analyze under the given paths
+    pub(crate) fn collect_files(&self, paths: &[&Path]) -> Vec<PathBuf> {
         let mut files: Vec<_> = Vec::new();
 
         for path in paths {
@@ -165,51 +229,75 @@ impl Analyzer {
             }
         }
 
-        // Load autoload symbols from composer.json if available
-        let mut symbol_table = self.load_autoload_symbols(paths);
+        files
+    }
 
-        // First pass: collect symbols from all files to build symbol table
-        let collected_symbols: Vec<_> = files
+    /// Collect the symbols declared in each file
+    pub(crate) fn collect_symbols(&self, files: &[PathBuf]) -> Vec<CollectedSymbols> {
+        files
             .par_iter()
-            .filter_map(|file| {
-                let source = fs::read_to_string(file).ok()?;
-                let arena = bumpalo::Bump::new();
-                let file_id = FileId::new(file.to_string_lossy().as_ref());
-                let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+            .filter_map(|file| collect_file_symbols(file))
+            .collect()
+    }
 
-                let collector = SymbolCollector::new(&source, file);
-                Some(collector.collect(&program))
-            })
-            .collect();
+    /// Build the symbol table from autoloaded, collected and included symbols
+    pub(crate) fn build_symbol_table(
+        &self,
+        paths: &[&Path],
+        files: &[PathBuf],
+        collected_symbols: Vec<CollectedSymbols>,
+    ) -> SymbolTable {
+        let includes = files.iter().flat_map(|file| scan_file_includes(file)).collect();
+        self.build_symbol_table_with_includes(paths, collected_symbols, includes)
+    }
+
+    /// Build the symbol table from autoloaded and collected symbols and the
+    /// symbols of the files the target files include
+    fn build_symbol_table_with_includes(
+        &self,
+        paths: &[&Path],
+        collected_symbols: Vec<CollectedSymbols>,
+        includes: Vec<PathBuf>,
+    ) -> SymbolTable {
+        // Load autoload symbols from composer.json if available
+        let mut symbol_table = self.load_autoload_symbols(paths);
 
         // Merge target file symbols into autoload symbol table
         let target_symbols = SymbolCollector::build_symbol_table_from_symbols(collected_symbols);
         symbol_table.merge(target_symbols);
 
         // Collect symbols from files included via require/include statements
-        let include_symbols = self.collect_include_symbols(&files);
+        let include_symbols = self.collect_include_symbols(includes);
         symbol_table.merge(include_symbols);
 
-        // Second pass: analyze files with symbol table
-        let results: Vec<_> = files
-            .par_iter()
-            .map(|file| self.analyze_file_with_symbols(file, &symbol_table))
-            .collect();
+        symbol_table
+    }
 
-        // Combine results
-        let mut combined = IssueCollection::new();
-        for result in results {
-            match result {
-                Ok(issues) => combined.extend(issues.into_issues()),
-                Err(e) => {
-                    // Log error but continue
-                    eprintln!("Warning: {}", e);
-                }
-            }
+    /// Analyze a file, taking its issues from the cache if nothing it depends on changed
+    ///
+    /// Returns the new cache entry when the file had to be analyzed.
+    fn analyze_file_cached(
+        &self,
+        path: &Path,
+        symbol_table: &SymbolTable,
+        cache: Option<&AnalysisCache>,
+    ) -> Result<(Vec<Issue>, Option<CachedFile>), AnalyzeError> {
+        let Some(cache) = cache else {
+            let issues = self.analyze_file_with_symbols(path, symbol_table)?;
+            return Ok((issues.into_issues(), None));
+        };
+
+        let source = fs::read_to_string(path)?;
+        if let Some(issues) = cache.get(path, &source, symbol_table) {
+            return Ok((issues, None));
         }
 
-        combined.sort();
-        Ok(combined)
+        let (issues, lookups) = SymbolTable::record_lookups(|| {
+            self.analyze_source_with_symbols(path, &source, symbol_table)
+        });
+        let issues = issues?.into_issues();
+        let entry = cache.entry(&source, &issues, &lookups, symbol_table);
+        Ok((issues, Some(entry)))
     }
 
     /// Analyze a file with a pre-built symbol table
@@ -219,24 +307,56 @@ impl Analyzer {
     }
 
     /// Analyze source code with a given path and symbol table
-    fn analyze_source_with_symbols(&self, path: &Path, source: &str, symbol_table: &SymbolTable) -> Result<IssueCollection, AnalyzeError> {
+    pub(crate) fn analyze_source_with_symbols(&self, path: &Path, source: &str, symbol_table: &SymbolTable) -> Result<IssueCollection, AnalyzeError> {
         // Parse the PHP file using bumpalo arena
         let arena = bumpalo::Bump::new();
         let file_id = FileId::new(path.to_string_lossy().as_ref());
-        let (program, parse_error) = mago_syntax::parser::parse_file_content(&arena, file_id, source);
+        let parsed = rustor_core::parse(&arena, file_id, source);
+        let program = parsed.program;
 
+        // Report parse errors; the checks still run on the statements without them
         let mut issues = IssueCollection::new();
+        for error in parsed.errors {
+            issues.add(
+                issue::Issue::error(PARSE_ERROR_IDENTIFIER, error.message, path.to_path_buf(), error.line, error.column)
+                    .with_identifier(PARSE_ERROR_IDENTIFIER),
+            );
+        }
+
+        issues.extend(self.analyze_program(path, source, program, symbol_table).into_issues());
+        Ok(issues)
+    }
+
+    /// Build the symbol table from the symbols of files the caller already
+    /// parsed, found under `paths`
+    ///
+    /// Use [`Analyzer::file_symbols`] to get the symbols of each file, and
+    /// [`Analyzer::analyze_program`] to analyze the files with the table.
+    pub fn symbol_table_from_files(&self, paths: &[&Path], files: Vec<FileSymbols>) -> SymbolTable {
+        let (collected_symbols, includes): (Vec<_>, Vec<_>) =
+            files.into_iter().map(|file| (file.symbols, file.includes)).unzip();
+        self.build_symbol_table_with_includes(paths, collected_symbols, includes.into_iter().flatten().collect())
+    }
 
-        // Report parse errors
-        if let Some(error) = parse_error {
-            issues.add(issue::Issue::error(
-                "parse.error",
-                error.to_string(),
-                path.to_path_buf(),
-                1, // TODO: Get line from error
-                1,
-            ));
+    /// Symbols declared in an already parsed file, and the files it includes
+    pub fn file_symbols(&self, path: &Path, source: &str, program: &mago_syntax::ast::Program<'_>) -> FileSymbols {
+        FileSymbols {
+            symbols: SymbolCollector::new(source, path).collect(program),
+            includes: IncludeScanner::new(source, path).scan(program),
         }
+    }
+
+    /// Analyze an already parsed file with a symbol table
+    ///
+    /// Parse errors are not reported; the caller has them from parsing.
+    pub fn analyze_program(
+        &self,
+        path: &Path,
+        source: &str,
+        program: &mago_syntax::ast::Program<'_>,
+        symbol_table: &SymbolTable,
+    ) -> IssueCollection {
+        let mut issues = IssueCollection::new();
 
         // Create check context with symbol table
         let ctx = CheckContext {
@@ -253,7 +373,7 @@ impl Analyzer {
         // Run checks for the configured level
         let checks = self.registry.checks_for_level(self.config.level.as_u8());
         for check in checks {
-            let check_issues = check.check(&program, &ctx);
+            let check_issues = check.check(program, &ctx);
             for issue in check_issues {
                 // Filter ignored errors
                 if !self.config.should_ignore_error(
@@ -266,7 +386,7 @@ impl Analyzer {
             }
         }
 
-        Ok(issues)
+        issues
     }
 
     /// Analyze paths specified in the configuration
@@ -278,28 +398,16 @@ impl Analyzer {
         self.analyze_paths(&paths)
     }
 
-    /// Collect symbols from files included via require/include statements
-    fn collect_include_symbols(&self, files: &[std::path::PathBuf]) -> SymbolTable {
-        use autoload::include_scanner::IncludeScanner;
+    /// Collect symbols from files included via require/include statements,
+    /// starting with the files the target files include
+    fn collect_include_symbols(&self, includes: Vec<PathBuf>) -> SymbolTable {
         use std::collections::HashSet;
 
-        let mut all_includes: HashSet<std::path::PathBuf> = HashSet::new();
-        let mut to_process: Vec<std::path::PathBuf> = Vec::new();
-
-        // First, find all includes from the target files
-        for file in files {
-            if let Ok(source) = fs::read_to_string(file) {
-                let arena = bumpalo::Bump::new();
-                let file_id = FileId::new(file.to_string_lossy().as_ref());
-                let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
-
-                let scanner = IncludeScanner::new(&source, file);
-                for include_path in scanner.scan(program) {
-                    if !all_includes.contains(&include_path) {
-                        all_includes.insert(include_path.clone());
-                        to_process.push(include_path);
-                    }
-                }
+        let mut all_includes: HashSet<PathBuf> = HashSet::new();
+        let mut to_process: Vec<PathBuf> = Vec::new();
+        for include_path in includes {
+            if all_includes.insert(include_path.clone()) {
+                to_process.push(include_path);
             }
         }
 
@@ -314,7 +422,7 @@ impl Analyzer {
                 if let Ok(source) = fs::read_to_string(&file) {
                     let arena = bumpalo::Bump::new();
                     let file_id = FileId::new(file.to_string_lossy().as_ref());
-                    let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+                    let program = rustor_core::parse(&arena, file_id, &source).program;
 
                     let scanner = IncludeScanner::new(&source, &file);
                     for include_path in scanner.scan(program) {
@@ -334,7 +442,7 @@ impl Analyzer {
                 let source = fs::read_to_string(file).ok()?;
                 let arena = bumpalo::Bump::new();
                 let file_id = FileId::new(file.to_string_lossy().as_ref());
-                let (program, _) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+                let program = rustor_core::parse(&arena, file_id, &source).program;
 
                 let collector = SymbolCollector::new(&source, file);
                 Some(collector.collect(&program))
@@ -346,9 +454,7 @@ impl Analyzer {
 
     /// Load symbols from Composer autoload (classmap + PSR-4 + vendor PSR-4)
     fn load_autoload_symbols(&self, paths: &[&Path]) -> SymbolTable {
-        let search_dir = paths.first()
-            .map(|p| if p.is_file() { p.parent().unwrap_or(*p) } else { *p })
-            .unwrap_or(Path::new("."));
+        let search_dir = project_dir(paths);
 
         let mut symbol_table = SymbolTable::new();
 
@@ -446,6 +552,42 @@ impl Analyzer {
     }
 }
 
+/// Parse a file and collect the symbols it declares
+pub(crate) fn collect_file_symbols(file: &Path) -> Option<CollectedSymbols> {
+    let source = fs::read_to_string(file).ok()?;
+    let arena = bumpalo::Bump::new();
+    let file_id = FileId::new(file.to_string_lossy().as_ref());
+    let program = rustor_core::parse(&arena, file_id, &source).program;
+
+    let collector = SymbolCollector::new(&source, file);
+    Some(collector.collect(&program))
+}
+
+/// Files included from a file via require/include statements
+fn scan_file_includes(file: &Path) -> Vec<PathBuf> {
+    let Ok(source) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+    let arena = bumpalo::Bump::new();
+    let file_id = FileId::new(file.to_string_lossy().as_ref());
+    let program = rustor_core::parse(&arena, file_id, &source).program;
+
+    IncludeScanner::new(&source, file).scan(program)
+}
+
+/// Symbols of one file for [`Analyzer::symbol_table_from_files`]
+pub struct FileSymbols {
+    symbols: CollectedSymbols,
+    includes: Vec<PathBuf>,
+}
+
+/// Directory of the project being analyzed: the first path, or its parent for a file
+fn project_dir<'a>(paths: &[&'a Path]) -> &'a Path {
+    paths.first()
+        .map(|p| if p.is_file() { p.parent().unwrap_or(*p) } else { *p })
+        .unwrap_or(Path::new("."))
+}
+
 /// Errors that can occur during analysis
 #[derive(Debug, thiserror::Error)]
 pub enum AnalyzeError {
@@ -486,4 +628,25 @@ mod tests {
         // Should find undefined function
         assert!(issues.issues().iter().any(|i| i.message.contains("undefined function")));
     }
+
+    #[test]
+    fn test_cached_analysis_follows_dependencies() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let caller = dir.path().join("caller.php");
+        let helpers = dir.path().join("helpers.php");
+        fs::write(&caller, "<?php\nformat_name();\n").unwrap();
+        fs::write(&helpers, "<?php\n").unwrap();
+
+        let mut analyzer = Analyzer::with_defaults();
+        analyzer.set_cache(true);
+        let analyze = || analyzer.analyze_paths(&[dir.path()]).unwrap();
+
+        let issues = analyze();
+        assert!(issues.issues().iter().any(|i| i.message.contains("format_name")));
+        assert_eq!(analyze().len(), issues.len());
+
+        // Defining the function re-analyzes the unchanged caller
+        fs::write(&helpers, "<?php\nfunction format_name() {}\n").unwrap();
+        assert!(analyze().is_empty());
+    }
 }
@@ -3,10 +3,14 @@
 //! Collects class, function, and constant definitions from PHP files.
 //! This is a simplified version that extracts basic symbol information.
 
-use crate::symbols::{ClassInfo, FunctionInfo, SymbolTable};
-use crate::symbols::class_info::{ClassKind, ClassMethodInfo, MethodParameterInfo};
+use crate::symbols::{ClassInfo, EnumCaseInfo, FunctionInfo, SymbolTable};
+use crate::symbols::class_info::{
+    ClassConstantInfo, ClassKind, ClassMethodInfo, ClassPropertyInfo, MethodParameterInfo,
+};
+use crate::symbols::function_info::FunctionParameterInfo;
 use crate::types::Type;
 use crate::types::php_type::Visibility;
+use crate::types::phpdoc::{find_docblock, parse_phpdoc, parse_type_string, PhpDoc};
 use mago_span::HasSpan;
 use mago_syntax::ast::*;
 use rustor_core::Visitor;
@@ -51,25 +55,45 @@ impl<'s> SymbolCollector<'s> {
         let mut table = SymbolTable::with_builtins();
 
         for symbols in collected {
-            // Register aliases for this file
-            if !symbols.aliases.is_empty() {
-                table.set_aliases(&symbols.file_path, symbols.aliases);
-            }
-
-            for class in symbols.classes {
-                table.register_class(class);
-            }
-            for func in symbols.functions {
-                table.register_function(func);
-            }
-            for (name, ty) in symbols.constants {
-                table.register_constant(name, ty);
-            }
+            Self::register_symbols(&mut table, symbols);
         }
 
         table
     }
 
+    /// Register the symbols collected from one file
+    pub fn register_symbols(table: &mut SymbolTable, symbols: CollectedSymbols) {
+        // Register aliases for this file
+        if !symbols.aliases.is_empty() {
+            table.set_aliases(&symbols.file_path, symbols.aliases);
+        }
+
+        for class in symbols.classes {
+            table.register_class(class);
+        }
+        for func in symbols.functions {
+            table.register_function(func);
+        }
+        for (name, ty) in symbols.constants {
+            table.register_constant(name, ty);
+        }
+    }
+
+    /// Remove the symbols collected from one file
+    pub fn unregister_symbols(table: &mut SymbolTable, symbols: &CollectedSymbols) {
+        table.remove_aliases(&symbols.file_path);
+
+        for class in &symbols.classes {
+            table.remove_class(&class.full_name);
+        }
+        for func in &symbols.functions {
+            table.remove_function(&func.full_name);
+        }
+        for (name, _) in &symbols.constants {
+            table.remove_constant(name);
+        }
+    }
+
     /// Get text for a span
     fn get_span_text(&self, span: &mago_span::Span) -> &str {
         &self.source[span.start.offset as usize..span.end.offset as usize]
@@ -144,39 +168,274 @@ impl<'s> SymbolCollector<'s> {
         modifiers.contains_final()
     }
 
-    /// Collect methods from class members
-    fn collect_methods_from_members(&self, members: &mago_syntax::ast::Sequence<'_, ClassLikeMember<'_>>, info: &mut ClassInfo) {
+    /// Qualify class names inside a type with the current namespace and aliases
+    fn qualify_type(&self, ty: Type) -> Type {
+        match ty {
+            Type::Object { class_name: Some(name) } => Type::Object {
+                class_name: Some(self.qualify_name(&name)),
+            },
+            Type::Nullable(inner) => Type::Nullable(Box::new(self.qualify_type(*inner))),
+            Type::Union(types) => Type::Union(types.into_iter().map(|t| self.qualify_type(t)).collect()),
+            Type::Intersection(types) => {
+                Type::Intersection(types.into_iter().map(|t| self.qualify_type(t)).collect())
+            }
+            Type::List { value } => Type::List {
+                value: Box::new(self.qualify_type(*value)),
+            },
+            Type::Array { key, value } => Type::Array {
+                key,
+                value: Box::new(self.qualify_type(*value)),
+            },
+            other => other,
+        }
+    }
+
+    /// Convert a native type hint into a (namespace-qualified) type
+    fn hint_type(&self, hint: &Hint<'_>) -> Option<Type> {
+        parse_type_string(self.get_span_text(&hint.span())).map(|t| self.qualify_type(t))
+    }
+
+    /// Parse the docblock directly preceding a declaration, if any
+    fn docblock_for(&self, span: mago_span::Span) -> Option<PhpDoc> {
+        find_docblock(self.source, span.start.offset as usize).map(parse_phpdoc)
+    }
+
+    /// Deprecation description from a `@deprecated` tag or a `#[\Deprecated]` attribute
+    fn deprecation_for(
+        &self,
+        doc: Option<&PhpDoc>,
+        attribute_lists: &mago_syntax::ast::Sequence<'_, AttributeList<'_>>,
+    ) -> Option<String> {
+        if let Some(message) = doc.and_then(|d| d.deprecated.clone()) {
+            return Some(message);
+        }
+
+        for attribute in attribute_lists.iter().flat_map(|list| list.attributes.iter()) {
+            let name = self.get_span_text(&attribute.name.span());
+            if self.qualify_name(name) != "Deprecated" {
+                continue;
+            }
+            let Some(argument_list) = &attribute.argument_list else {
+                return Some(String::new());
+            };
+            let mut message = None;
+            let mut since = None;
+            for (index, argument) in argument_list.arguments.iter().enumerate() {
+                let (name, value) = match argument {
+                    Argument::Positional(positional) => (if index == 0 { "message" } else { "since" }, &positional.value),
+                    Argument::Named(named) => (self.get_span_text(&named.name.span), &named.value),
+                };
+                let Expression::Literal(Literal::String(string)) = value else {
+                    continue;
+                };
+                match name {
+                    "message" => message = string.value,
+                    "since" => since = string.value,
+                    _ => {}
+                }
+            }
+            return Some(match (since, message) {
+                (Some(since), Some(message)) => format!("since {}, {}", since, message),
+                (Some(since), None) => format!("since {}", since),
+                (None, message) => message.unwrap_or_default().to_string(),
+            });
+        }
+
+        None
+    }
+
+    /// Exception classes from `@throws` tags (`Some(vec![])` for `@throws void`)
+    fn throws_for(&self, doc: Option<&PhpDoc>) -> Option<Vec<String>> {
+        let doc = doc?;
+        if doc.throws.is_empty() {
+            return None;
+        }
+        let mut classes = Vec::new();
+        for ty in &doc.throws {
+            let types = match ty {
+                Type::Union(types) => types.clone(),
+                other => vec![other.clone()],
+            };
+            for ty in types {
+                if let Type::Object { class_name: Some(name) } = ty {
+                    classes.push(self.qualify_name(&name));
+                }
+            }
+        }
+        Some(classes)
+    }
+
+    /// Type of a literal expression (used for backed enum case values)
+    fn literal_type(&self, expr: &Expression<'_>) -> Option<Type> {
+        match expr {
+            Expression::Literal(Literal::Integer(int)) => int.value.map(|v| Type::ConstantInt(v as i64)),
+            Expression::Literal(Literal::String(string)) => {
+                string.value.map(|v| Type::ConstantString(v.to_string()))
+            }
+            Expression::UnaryPrefix(unary) if matches!(unary.operator, UnaryPrefixOperator::Negation(_)) => {
+                match self.literal_type(unary.operand)? {
+                    Type::ConstantInt(v) => Some(Type::ConstantInt(-v)),
+                    _ => None,
+                }
+            }
+            Expression::Parenthesized(paren) => self.literal_type(paren.expression),
+            _ => None,
+        }
+    }
+
+    /// Collect parameter name, type and flags; docblock types win over native hints
+    fn collect_parameters(
+        &self,
+        parameter_list: &FunctionLikeParameterList<'_>,
+        doc: Option<&PhpDoc>,
+    ) -> Vec<MethodParameterInfo> {
+        let mut parameters = Vec::new();
+        for param in parameter_list.parameters.iter() {
+            let param_name = self.get_span_text(&param.variable.span).to_string();
+            let mut param_info = MethodParameterInfo::new(&param_name);
+            param_info.is_optional = param.default_value.is_some();
+            param_info.is_variadic = param.ellipsis.is_some();
+            param_info.is_reference = param.ampersand.is_some();
+
+            let doc_type = doc.and_then(|d| {
+                let bare_name = param_name.trim_start_matches('$');
+                d.params
+                    .iter()
+                    .find(|(name, _)| name == bare_name)
+                    .map(|(_, ty)| self.qualify_type(ty.clone()))
+            });
+            param_info.type_ = doc_type.or_else(|| param.hint.as_ref().and_then(|h| self.hint_type(h)));
+
+            parameters.push(param_info);
+        }
+        parameters
+    }
+
+    /// Collect methods, properties, constants, enum cases and trait usage from class members
+    fn collect_members(&self, members: &mago_syntax::ast::Sequence<'_, ClassLikeMember<'_>>, info: &mut ClassInfo) {
         for member in members.iter() {
             match member {
                 ClassLikeMember::Method(method) => {
                     let method_name = self.get_span_text(&method.name.span).to_string();
                     let mut method_info = ClassMethodInfo::new(&method_name);
+                    let doc = self.docblock_for(method.span());
 
                     // Extract visibility and modifiers
                     method_info.visibility = self.extract_visibility(&method.modifiers);
                     method_info.is_static = self.has_static_modifier(&method.modifiers);
                     method_info.is_abstract = matches!(method.body, MethodBody::Abstract(_));
                     method_info.is_final = self.has_final_modifier(&method.modifiers);
-
-                    // Extract parameters
-                    for param in method.parameter_list.parameters.iter() {
-                        let param_name = self.get_span_text(&param.variable.span).to_string();
-                        let mut param_info = MethodParameterInfo::new(&param_name);
-                        param_info.is_optional = param.default_value.is_some();
-                        param_info.is_variadic = param.ellipsis.is_some();
-                        param_info.is_reference = param.ampersand.is_some();
-                        method_info.parameters.push(param_info);
+                    method_info.deprecated = self.deprecation_for(doc.as_ref(), &method.attribute_lists);
+                    method_info.throws = self.throws_for(doc.as_ref());
+
+                    // Extract parameters and return type
+                    method_info.parameters = self.collect_parameters(&method.parameter_list, doc.as_ref());
+                    method_info.return_type = doc
+                        .as_ref()
+                        .and_then(|d| d.return_type.clone())
+                        .map(|t| self.qualify_type(t))
+                        .or_else(|| {
+                            method
+                                .return_type_hint
+                                .as_ref()
+                                .and_then(|r| self.hint_type(&r.hint))
+                        });
+
+                    // Promoted constructor parameters are properties too
+                    if method_name.eq_ignore_ascii_case("__construct") {
+                        for param in method.parameter_list.parameters.iter() {
+                            if param.modifiers.is_empty() {
+                                continue;
+                            }
+                            let name = self.get_span_text(&param.variable.span).trim_start_matches('$');
+                            let mut property = ClassPropertyInfo::new(name)
+                                .with_visibility(self.extract_visibility(&param.modifiers));
+                            property.type_ = param.hint.as_ref().and_then(|h| self.hint_type(h));
+                            property.is_readonly = info.is_readonly || param.modifiers.contains_readonly();
+                            property.has_default = true;
+                            info.add_property(property);
+                        }
                     }
 
                     info.add_method(method_info);
                 }
+                ClassLikeMember::Property(property) => {
+                    let modifiers = property.modifiers();
+                    let doc = self.docblock_for(property.span());
+                    let attribute_lists = match property {
+                        Property::Plain(plain) => &plain.attribute_lists,
+                        Property::Hooked(hooked) => &hooked.attribute_lists,
+                    };
+                    let deprecated = self.deprecation_for(doc.as_ref(), attribute_lists);
+                    let native_type = property.hint().and_then(|h| self.hint_type(h));
+                    let doc_type = doc
+                        .as_ref()
+                        .and_then(|d| d.var_type.clone())
+                        .map(|t| self.qualify_type(t));
+
+                    let items: Vec<(&DirectVariable<'_>, bool)> = match property {
+                        Property::Plain(plain) => plain
+                            .items
+                            .iter()
+                            .map(|item| match item {
+                                PropertyItem::Abstract(a) => (&a.variable, false),
+                                PropertyItem::Concrete(c) => (&c.variable, true),
+                            })
+                            .collect(),
+                        Property::Hooked(hooked) => match &hooked.item {
+                            PropertyItem::Abstract(a) => vec![(&a.variable, false)],
+                            PropertyItem::Concrete(c) => vec![(&c.variable, true)],
+                        },
+                    };
+
+                    for (variable, has_default) in items {
+                        let name = self.get_span_text(&variable.span).trim_start_matches('$');
+                        let mut property_info = ClassPropertyInfo::new(name)
+                            .with_visibility(self.extract_visibility(modifiers))
+                            .with_static(modifiers.contains_static());
+                        property_info.type_ = doc_type.clone().or_else(|| native_type.clone());
+                        property_info.is_readonly = info.is_readonly || modifiers.contains_readonly();
+                        property_info.is_readonly_by_phpdoc =
+                            info.is_immutable || doc.as_ref().is_some_and(|d| d.readonly);
+                        // Untyped properties are implicitly initialized to null
+                        property_info.has_default = has_default || property.hint().is_none();
+                        property_info.deprecated = deprecated.clone();
+                        info.add_property(property_info);
+                    }
+                }
+                ClassLikeMember::Constant(constant) => {
+                    let doc = self.docblock_for(constant.span());
+                    let deprecated = self.deprecation_for(doc.as_ref(), &constant.attribute_lists);
+                    for item in constant.items.iter() {
+                        let name = self.get_span_text(&item.name.span);
+                        let mut constant_info = ClassConstantInfo::new(name)
+                            .with_visibility(self.extract_visibility(&constant.modifiers));
+                        constant_info.type_ = constant
+                            .hint
+                            .as_ref()
+                            .and_then(|h| self.hint_type(h))
+                            .or_else(|| self.literal_type(&item.value));
+                        constant_info.is_final = self.has_final_modifier(&constant.modifiers);
+                        constant_info.deprecated = deprecated.clone();
+                        info.add_constant(constant_info);
+                    }
+                }
+                ClassLikeMember::EnumCase(case) => {
+                    let name = self.get_span_text(&case.item.name().span);
+                    let mut case_info = EnumCaseInfo::new(name);
+                    if let EnumCaseItem::Backed(backed) = &case.item {
+                        case_info.value = self.literal_type(&backed.value);
+                    }
+                    let doc = self.docblock_for(case.span());
+                    case_info.deprecated = self.deprecation_for(doc.as_ref(), &case.attribute_lists);
+                    info.add_case(case_info);
+                }
                 ClassLikeMember::TraitUse(trait_use) => {
                     for trait_name in trait_use.trait_names.iter() {
                         let trait_text = self.get_span_text(&trait_name.span());
                         info.traits.push(self.qualify_name(trait_text));
                     }
                 }
-                _ => {}
             }
         }
     }
@@ -326,6 +585,23 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                 let mut info = FunctionInfo::new(name, &full_name);
                 info.file = Some(self.file.clone());
                 info.line = Some(self.get_line(span.start.offset as usize));
+                info.returns_reference = func.ampersand.is_some();
+
+                let doc = self.docblock_for(func.span());
+                for param in self.collect_parameters(&func.parameter_list, doc.as_ref()) {
+                    let mut param_info = FunctionParameterInfo::new(param.name.trim_start_matches('$'))
+                        .with_optional(param.is_optional)
+                        .with_variadic(param.is_variadic)
+                        .with_reference(param.is_reference);
+                    param_info.type_ = param.type_;
+                    info.parameters.push(param_info);
+                }
+                info.deprecated = self.deprecation_for(doc.as_ref(), &func.attribute_lists);
+                info.throws = self.throws_for(doc.as_ref());
+                info.return_type = doc
+                    .and_then(|d| d.return_type)
+                    .map(|t| self.qualify_type(t))
+                    .or_else(|| func.return_type_hint.as_ref().and_then(|r| self.hint_type(&r.hint)));
 
                 self.symbols.functions.push(info);
                 true
@@ -339,6 +615,12 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                 info.kind = ClassKind::Class;
                 info.file = Some(self.file.clone());
                 info.line = Some(self.get_line(span.start.offset as usize));
+                let doc = self.docblock_for(class.span());
+                info.deprecated = self.deprecation_for(doc.as_ref(), &class.attribute_lists);
+                info.is_immutable = doc.as_ref().is_some_and(|d| d.readonly);
+                info.is_abstract = self.has_abstract_modifier(&class.modifiers);
+                info.is_final = self.has_final_modifier(&class.modifiers);
+                info.is_readonly = class.modifiers.contains_readonly();
 
                 // Extract extends (parent class) - classes extend only one parent
                 if let Some(extends) = &class.extends {
@@ -356,8 +638,8 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                     }
                 }
 
-                // Collect methods and trait usage from class members
-                self.collect_methods_from_members(&class.members, &mut info);
+                // Collect members and trait usage from class members
+                self.collect_members(&class.members, &mut info);
 
                 self.symbols.classes.push(info);
                 true
@@ -371,6 +653,10 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                 info.kind = ClassKind::Interface;
                 info.file = Some(self.file.clone());
                 info.line = Some(self.get_line(span.start.offset as usize));
+                info.deprecated = self.deprecation_for(
+                    self.docblock_for(interface.span()).as_ref(),
+                    &interface.attribute_lists,
+                );
 
                 // Extract extends (interfaces can extend other interfaces)
                 if let Some(extends) = &interface.extends {
@@ -380,8 +666,8 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                     }
                 }
 
-                // Collect method signatures from interface members
-                self.collect_methods_from_members(&interface.members, &mut info);
+                // Collect method signatures and constants from interface members
+                self.collect_members(&interface.members, &mut info);
 
                 self.symbols.classes.push(info);
                 true
@@ -395,9 +681,13 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                 info.kind = ClassKind::Trait;
                 info.file = Some(self.file.clone());
                 info.line = Some(self.get_line(span.start.offset as usize));
+                info.deprecated = self.deprecation_for(
+                    self.docblock_for(trait_def.span()).as_ref(),
+                    &trait_def.attribute_lists,
+                );
 
-                // Collect methods and trait usage from trait members
-                self.collect_methods_from_members(&trait_def.members, &mut info);
+                // Collect members and trait usage from trait members
+                self.collect_members(&trait_def.members, &mut info);
 
                 self.symbols.classes.push(info);
                 true
@@ -411,9 +701,27 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
                 info.kind = ClassKind::Enum;
                 info.file = Some(self.file.clone());
                 info.line = Some(self.get_line(span.start.offset as usize));
+                info.deprecated = self.deprecation_for(
+                    self.docblock_for(enum_def.span()).as_ref(),
+                    &enum_def.attribute_lists,
+                );
+                // Enums are implicitly final
+                info.is_final = true;
+                info.backing_type = enum_def
+                    .backing_type_hint
+                    .as_ref()
+                    .and_then(|b| self.hint_type(&b.hint));
+
+                if let Some(implements) = &enum_def.implements {
+                    for iface in implements.types.iter() {
+                        let iface_text = self.get_span_text(&iface.span());
+                        info.interfaces.push(self.qualify_name(iface_text));
+                    }
+                }
 
-                // Collect methods and trait usage from enum members
-                self.collect_methods_from_members(&enum_def.members, &mut info);
+                // Collect cases, methods, constants and trait usage from enum members
+                self.collect_members(&enum_def.members, &mut info);
+                info.add_enum_builtins();
 
                 self.symbols.classes.push(info);
                 true
@@ -432,7 +740,7 @@ impl<'a, 's> Visitor<'a> for SymbolCollector<'s> {
 }
 
 /// Symbols collected from a file
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct CollectedSymbols {
     pub classes: Vec<ClassInfo>,
     pub functions: Vec<FunctionInfo>,
@@ -490,6 +798,80 @@ interface Nameable {
         assert_eq!(symbols.classes[0].kind, ClassKind::Interface);
     }
 
+    #[test]
+    fn test_collect_backed_enum() {
+        let source = r#"<?php
+namespace App;
+
+enum Status: string {
+    case Active = 'active';
+    case Archived = 'archived';
+
+    const DEFAULT = self::Active;
+}
+"#;
+        let symbols = parse_and_collect(source);
+        let info = &symbols.classes[0];
+        assert!(info.is_backed_enum());
+        assert_eq!(info.backing_type, Some(Type::String));
+        assert_eq!(info.cases.len(), 2);
+        assert_eq!(info.get_case("Archived").and_then(|c| c.value.clone()), Some(Type::ConstantString("archived".into())));
+        assert!(info.has_constant("DEFAULT"));
+        assert_eq!(
+            info.get_method("from").and_then(|m| m.return_type.clone()),
+            Some(Type::object("App\\Status"))
+        );
+    }
+
+    #[test]
+    fn test_collect_pure_enum() {
+        let source = r#"<?php
+enum Suit {
+    case Hearts;
+    case Spades;
+}
+"#;
+        let symbols = parse_and_collect(source);
+        let info = &symbols.classes[0];
+        assert_eq!(info.kind, ClassKind::Enum);
+        assert!(!info.is_backed_enum());
+        assert_eq!(info.case_types().len(), 2);
+        assert!(info.interfaces.contains(&"UnitEnum".to_string()));
+        assert!(info.get_method("tryFrom").is_none());
+    }
+
+    #[test]
+    fn test_collect_typed_members() {
+        let source = r#"<?php
+namespace App;
+
+final class Order {
+    private Status $status;
+    public readonly int $id;
+
+    public function __construct(private readonly string $ref) {}
+
+    /** @param 'draft'|'sent' $mode */
+    public function send(string $mode): ?Status {}
+}
+"#;
+        let symbols = parse_and_collect(source);
+        let info = &symbols.classes[0];
+        assert!(info.is_final);
+        assert_eq!(info.get_property("status").and_then(|p| p.type_.clone()), Some(Type::object("App\\Status")));
+        assert!(info.get_property("id").is_some_and(|p| p.is_readonly));
+        assert!(info.get_property("ref").is_some_and(|p| p.is_readonly));
+        let send = info.get_method("send").unwrap();
+        assert_eq!(
+            send.parameters[0].type_,
+            Some(Type::Union(vec![
+                Type::ConstantString("draft".into()),
+                Type::ConstantString("sent".into()),
+            ]))
+        );
+        assert_eq!(send.return_type, Some(Type::Nullable(Box::new(Type::object("App\\Status")))));
+    }
+
     #[test]
     fn test_collect_with_namespace() {
         let source = r#"<?php
@@ -39,12 +39,20 @@ pub struct ClassInfo {
     pub methods: HashMap<String, ClassMethodInfo>,
     /// Constants
     pub constants: HashMap<String, ClassConstantInfo>,
+    /// Enum cases in declaration order (enums only)
+    pub cases: Vec<EnumCaseInfo>,
+    /// Backing type of a backed enum (`int` or `string`)
+    pub backing_type: Option<Type>,
     /// Whether it's abstract
     pub is_abstract: bool,
     /// Whether it's final
     pub is_final: bool,
     /// Whether it's readonly (PHP 8.2+)
     pub is_readonly: bool,
+    /// Whether it's marked `@readonly`/`@immutable` in PHPDoc (all properties are readonly by PHPDoc)
+    pub is_immutable: bool,
+    /// Deprecation description from `@deprecated` or `#[\Deprecated]` (empty if none given)
+    pub deprecated: Option<String>,
     /// File where this class is defined
     pub file: Option<PathBuf>,
     /// Line number where defined
@@ -73,9 +81,13 @@ impl ClassInfo {
             properties: HashMap::new(),
             methods: HashMap::new(),
             constants: HashMap::new(),
+            cases: Vec::new(),
+            backing_type: None,
             is_abstract: false,
             is_final: false,
             is_readonly: false,
+            is_immutable: false,
+            deprecated: None,
             file: None,
             line: None,
         }
@@ -139,6 +151,140 @@ impl ClassInfo {
             class_name: Some(self.full_name.clone()),
         }
     }
+
+    /// Check if this is an enum (pure or backed)
+    pub fn is_enum(&self) -> bool {
+        self.kind == ClassKind::Enum
+    }
+
+    /// Check if this is a backed enum
+    pub fn is_backed_enum(&self) -> bool {
+        self.is_enum() && self.backing_type.is_some()
+    }
+
+    /// Check if the enum has a case (case-sensitive, like constants)
+    pub fn has_case(&self, name: &str) -> bool {
+        self.cases.iter().any(|c| c.name == name)
+    }
+
+    /// Get an enum case by name
+    pub fn get_case(&self, name: &str) -> Option<&EnumCaseInfo> {
+        self.cases.iter().find(|c| c.name == name)
+    }
+
+    /// Add an enum case
+    pub fn add_case(&mut self, case: EnumCaseInfo) {
+        self.cases.push(case);
+    }
+
+    /// Get the type of a single enum case
+    pub fn case_type(&self, name: &str) -> Type {
+        Type::EnumCase {
+            class_name: self.full_name.clone(),
+            case_name: name.to_string(),
+        }
+    }
+
+    /// Get the types of all enum cases, in declaration order
+    pub fn case_types(&self) -> Vec<Type> {
+        self.cases.iter().map(|c| self.case_type(&c.name)).collect()
+    }
+
+    /// Type of `->value` on this enum: the union of all backing values,
+    /// or the backing type if the values are unknown
+    pub fn enum_value_type(&self) -> Option<Type> {
+        let backing = self.backing_type.clone()?;
+        let values: Vec<Type> = self.cases.iter().filter_map(|c| c.value.clone()).collect();
+        if values.is_empty() || values.len() != self.cases.len() {
+            Some(backing)
+        } else {
+            Some(Type::union(values))
+        }
+    }
+
+    /// Type of `->name` on this enum: the union of all case names
+    pub fn enum_name_type(&self) -> Type {
+        if self.cases.is_empty() {
+            return Type::String;
+        }
+        Type::union(
+            self.cases
+                .iter()
+                .map(|c| Type::ConstantString(c.name.clone()))
+                .collect(),
+        )
+    }
+
+    /// Register the members PHP implicitly adds to every enum:
+    /// the `UnitEnum`/`BackedEnum` interfaces, the readonly `name`/`value`
+    /// properties and the static `cases()`, `from()` and `tryFrom()` methods.
+    pub fn add_enum_builtins(&mut self) {
+        let self_type = self.instance_type();
+
+        if !self.interfaces.iter().any(|i| i.eq_ignore_ascii_case("UnitEnum")) {
+            self.interfaces.push("UnitEnum".to_string());
+        }
+
+        let mut name_prop = ClassPropertyInfo::new("name").with_type(self.enum_name_type());
+        name_prop.is_readonly = true;
+        self.add_property(name_prop);
+
+        self.add_method(
+            ClassMethodInfo::new("cases")
+                .with_static(true)
+                .with_return_type(Type::list(self_type.clone())),
+        );
+
+        if let Some(backing) = self.backing_type.clone() {
+            if !self.interfaces.iter().any(|i| i.eq_ignore_ascii_case("BackedEnum")) {
+                self.interfaces.push("BackedEnum".to_string());
+            }
+
+            let value_type = self.enum_value_type().unwrap_or_else(|| backing.clone());
+            let mut value_prop = ClassPropertyInfo::new("value").with_type(value_type);
+            value_prop.is_readonly = true;
+            self.add_property(value_prop);
+
+            self.add_method(
+                ClassMethodInfo::new("from")
+                    .with_static(true)
+                    .with_parameter(MethodParameterInfo::new("value").with_type(backing.clone()))
+                    .with_return_type(self_type.clone()),
+            );
+            self.add_method(
+                ClassMethodInfo::new("tryFrom")
+                    .with_static(true)
+                    .with_parameter(MethodParameterInfo::new("value").with_type(backing))
+                    .with_return_type(Type::nullable(self_type)),
+            );
+        }
+    }
+}
+
+/// Information about an enum case
+#[derive(Debug, Clone)]
+pub struct EnumCaseInfo {
+    /// Case name
+    pub name: String,
+    /// Backing value (`ConstantInt` or `ConstantString`) for backed enums
+    pub value: Option<Type>,
+    /// Deprecation description from `@deprecated` or `#[\Deprecated]` (empty if none given)
+    pub deprecated: Option<String>,
+}
+
+impl EnumCaseInfo {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: None,
+            deprecated: None,
+        }
+    }
+
+    pub fn with_value(mut self, value: Type) -> Self {
+        self.value = Some(value);
+        self
+    }
 }
 
 /// Information about a class property
@@ -154,8 +300,12 @@ pub struct ClassPropertyInfo {
     pub is_static: bool,
     /// Whether it's readonly
     pub is_readonly: bool,
+    /// Whether it's marked `@readonly` in PHPDoc (or its class is `@immutable`)
+    pub is_readonly_by_phpdoc: bool,
     /// Whether it has a default value
     pub has_default: bool,
+    /// Deprecation description from `@deprecated` or `#[\Deprecated]` (empty if none given)
+    pub deprecated: Option<String>,
 }
 
 impl ClassPropertyInfo {
@@ -166,7 +316,9 @@ impl ClassPropertyInfo {
             visibility: Visibility::Public,
             is_static: false,
             is_readonly: false,
+            is_readonly_by_phpdoc: false,
             has_default: false,
+            deprecated: None,
         }
     }
 
@@ -203,6 +355,10 @@ pub struct ClassMethodInfo {
     pub is_abstract: bool,
     /// Whether it's final
     pub is_final: bool,
+    /// Deprecation description from `@deprecated` or `#[\Deprecated]` (empty if none given)
+    pub deprecated: Option<String>,
+    /// Exception classes declared with `@throws` (`None` without a tag, empty for `@throws void`)
+    pub throws: Option<Vec<String>>,
 }
 
 impl ClassMethodInfo {
@@ -215,6 +371,8 @@ impl ClassMethodInfo {
             is_static: false,
             is_abstract: false,
             is_final: false,
+            deprecated: None,
+            throws: None,
         }
     }
 
@@ -300,6 +458,7 @@ pub struct ClassConstantInfo {
     pub type_: Option<Type>,
     pub visibility: Visibility,
     pub is_final: bool,
+    pub deprecated: Option<String>,
 }
 
 impl ClassConstantInfo {
@@ -309,6 +468,7 @@ impl ClassConstantInfo {
             type_: None,
             visibility: Visibility::Public,
             is_final: false,
+            deprecated: None,
         }
     }
 
@@ -354,4 +514,48 @@ mod tests {
         assert_eq!(method.required_args(), 1);
         assert_eq!(method.max_args(), Some(2));
     }
+
+    #[test]
+    fn test_backed_enum_builtins() {
+        let mut info = ClassInfo::from_fqn("App\\Status");
+        info.kind = ClassKind::Enum;
+        info.backing_type = Some(Type::String);
+        info.add_case(EnumCaseInfo::new("Active").with_value(Type::ConstantString("active".into())));
+        info.add_case(EnumCaseInfo::new("Archived").with_value(Type::ConstantString("archived".into())));
+        info.add_enum_builtins();
+
+        assert!(info.is_backed_enum());
+        assert!(info.has_case("Active"));
+        assert!(!info.has_case("active"));
+        assert!(info.interfaces.contains(&"BackedEnum".to_string()));
+        assert_eq!(
+            info.get_property("value").and_then(|p| p.type_.clone()),
+            Some(Type::union(vec![
+                Type::ConstantString("active".into()),
+                Type::ConstantString("archived".into()),
+            ]))
+        );
+        assert_eq!(
+            info.get_method("tryFrom").and_then(|m| m.return_type.clone()),
+            Some(Type::nullable(Type::object("App\\Status")))
+        );
+    }
+
+    #[test]
+    fn test_pure_enum_builtins() {
+        let mut info = ClassInfo::from_fqn("Suit");
+        info.kind = ClassKind::Enum;
+        info.add_case(EnumCaseInfo::new("Hearts"));
+        info.add_enum_builtins();
+
+        assert!(!info.is_backed_enum());
+        assert!(info.has_property("name"));
+        assert!(!info.has_property("value"));
+        assert!(info.has_method("cases"));
+        assert!(!info.has_method("from"));
+        assert_eq!(info.case_types(), vec![Type::EnumCase {
+            class_name: "Suit".into(),
+            case_name: "Hearts".into(),
+        }]);
+    }
 }
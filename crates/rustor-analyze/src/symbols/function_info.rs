@@ -20,6 +20,10 @@ pub struct FunctionInfo {
     pub return_type: Option<Type>,
     /// Whether it returns by reference
     pub returns_reference: bool,
+    /// Deprecation description from `@deprecated` or `#[\Deprecated]` (empty if none given)
+    pub deprecated: Option<String>,
+    /// Exception classes declared with `@throws` (`None` without a tag, empty for `@throws void`)
+    pub throws: Option<Vec<String>>,
     /// File where this function is defined
     pub file: Option<PathBuf>,
     /// Line number where defined
@@ -44,6 +48,8 @@ impl FunctionInfo {
             parameters: Vec::new(),
             return_type: None,
             returns_reference: false,
+            deprecated: None,
+            throws: None,
             file: None,
             line: None,
         }
@@ -7,6 +7,6 @@ pub mod symbol_table;
 pub mod class_info;
 pub mod function_info;
 
-pub use symbol_table::SymbolTable;
-pub use class_info::{ClassInfo, ClassKind};
+pub use symbol_table::{Lookups, SymbolTable};
+pub use class_info::{ClassInfo, ClassKind, EnumCaseInfo};
 pub use function_info::FunctionInfo;
@@ -6,9 +6,47 @@
 use super::class_info::{ClassInfo, ClassKind};
 use super::function_info::FunctionInfo;
 use crate::types::Type;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 
+thread_local! {
+    /// Lookups made on this thread while [`SymbolTable::record_lookups`] runs
+    static LOOKUPS: RefCell<Option<Lookups>> = const { RefCell::new(None) };
+}
+
+/// Symbols looked up in a symbol table, by lookup key
+///
+/// Misses are recorded too, so a symbol defined later invalidates results
+/// that relied on it being undefined.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lookups {
+    /// Lowercase class names
+    pub classes: BTreeSet<String>,
+    /// Lowercase function names
+    pub functions: BTreeSet<String>,
+    /// Constant names
+    pub constants: BTreeSet<String>,
+}
+
+impl Lookups {
+    /// Whether any symbol was looked up in both
+    pub fn intersects(&self, other: &Lookups) -> bool {
+        !self.classes.is_disjoint(&other.classes)
+            || !self.functions.is_disjoint(&other.functions)
+            || !self.constants.is_disjoint(&other.constants)
+    }
+}
+
+fn note(f: impl FnOnce(&mut Lookups)) {
+    LOOKUPS.with(|lookups| {
+        if let Some(lookups) = lookups.borrow_mut().as_mut() {
+            f(lookups);
+        }
+    });
+}
+
 /// Symbol table containing all known symbols
 #[derive(Debug, Clone, Default)]
 pub struct SymbolTable {
@@ -52,34 +90,68 @@ impl SymbolTable {
         self.constants.insert(name.into(), type_);
     }
 
+    /// Remove a class, returning it if it was registered
+    pub fn remove_class(&mut self, name: &str) -> Option<ClassInfo> {
+        self.classes.remove(&name.to_lowercase())
+    }
+
+    /// Remove a function, returning it if it was registered
+    pub fn remove_function(&mut self, name: &str) -> Option<FunctionInfo> {
+        self.functions.remove(&name.to_lowercase())
+    }
+
+    /// Remove a constant, returning its type if it was registered
+    pub fn remove_constant(&mut self, name: &str) -> Option<Type> {
+        self.constants.remove(name)
+    }
+
     /// Get a class by fully qualified name (case-insensitive)
     pub fn get_class(&self, name: &str) -> Option<&ClassInfo> {
-        self.classes.get(&name.to_lowercase())
+        let key = name.to_lowercase();
+        let class = self.classes.get(&key);
+        note(|l| { l.classes.insert(key); });
+        class
     }
 
     /// Get a function by fully qualified name (case-insensitive)
     pub fn get_function(&self, name: &str) -> Option<&FunctionInfo> {
-        self.functions.get(&name.to_lowercase())
+        let key = name.to_lowercase();
+        let function = self.functions.get(&key);
+        note(|l| { l.functions.insert(key); });
+        function
     }
 
     /// Get a constant by name (case-sensitive)
     pub fn get_constant(&self, name: &str) -> Option<&Type> {
+        note(|l| { l.constants.insert(name.to_string()); });
         self.constants.get(name)
     }
 
     /// Check if a class exists
     pub fn class_exists(&self, name: &str) -> bool {
-        self.classes.contains_key(&name.to_lowercase())
+        self.get_class(name).is_some()
     }
 
     /// Check if a function exists
     pub fn function_exists(&self, name: &str) -> bool {
-        self.functions.contains_key(&name.to_lowercase())
+        self.get_function(name).is_some()
     }
 
     /// Check if a constant exists
     pub fn constant_exists(&self, name: &str) -> bool {
-        self.constants.contains_key(name)
+        self.get_constant(name).is_some()
+    }
+
+    /// Run `f` and return the symbols it looked up on the current thread
+    ///
+    /// Used by the analysis cache to learn which symbols a file's result
+    /// depends on. Checks run single-threaded per file, so every lookup made
+    /// while analyzing the file happens on this thread.
+    pub fn record_lookups<R>(f: impl FnOnce() -> R) -> (R, Lookups) {
+        let previous = LOOKUPS.with(|l| l.borrow_mut().replace(Lookups::default()));
+        let result = f();
+        let lookups = LOOKUPS.with(|l| std::mem::replace(&mut *l.borrow_mut(), previous));
+        (result, lookups.unwrap_or_default())
     }
 
     /// Check if a class has a method
@@ -121,6 +193,11 @@ impl SymbolTable {
             .insert(file.to_string_lossy().to_string(), aliases);
     }
 
+    /// Remove the namespace aliases of a file
+    pub fn remove_aliases(&mut self, file: &Path) {
+        self.namespace_aliases.remove(&file.to_string_lossy().to_string());
+    }
+
     /// Get namespace aliases for a file
     pub fn get_aliases(&self, file: &Path) -> Option<&HashMap<String, String>> {
         self.namespace_aliases.get(&file.to_string_lossy().to_string())
@@ -183,6 +260,12 @@ impl SymbolTable {
             ("UnderflowException", ClassKind::Class, Some("RuntimeException"), &[]),
             ("BadMethodCallException", ClassKind::Class, Some("BadFunctionCallException"), &[]),
             ("BadFunctionCallException", ClassKind::Class, Some("LogicException"), &[]),
+            ("ErrorException", ClassKind::Class, Some("Exception"), &[]),
+            ("JsonException", ClassKind::Class, Some("Exception"), &[]),
+            ("PDOException", ClassKind::Class, Some("RuntimeException"), &[]),
+            ("ArithmeticError", ClassKind::Class, Some("Error"), &[]),
+            ("DivisionByZeroError", ClassKind::Class, Some("ArithmeticError"), &[]),
+            ("UnhandledMatchError", ClassKind::Class, Some("Error"), &[]),
         ];
 
         for (name, kind, parent, interfaces) in builtin_classes_with_hierarchy {
@@ -222,7 +305,6 @@ impl SymbolTable {
             ("ReflectionFunction", ClassKind::Class),
             ("PDO", ClassKind::Class),
             ("PDOStatement", ClassKind::Class),
-            ("PDOException", ClassKind::Class),
             ("SplFileInfo", ClassKind::Class),
             ("SplFileObject", ClassKind::Class),
             ("SplObjectStorage", ClassKind::Class),
@@ -358,4 +440,26 @@ mod tests {
         assert!(table.class_has_property("Foo", "baz"));
         assert!(!table.class_has_property("Foo", "BAZ")); // Properties are case sensitive
     }
+
+    #[test]
+    fn test_record_lookups() {
+        let mut table = SymbolTable::new();
+        table.register_class(ClassInfo::from_fqn("App\\User"));
+
+        let (found, lookups) = SymbolTable::record_lookups(|| {
+            table.class_has_method("App\\User", "save");
+            table.function_exists("missing_helper");
+            table.get_constant("APP_ENV").is_some()
+        });
+
+        assert!(!found);
+        assert_eq!(lookups.classes.iter().collect::<Vec<_>>(), ["app\\user"]);
+        assert_eq!(lookups.functions.iter().collect::<Vec<_>>(), ["missing_helper"]);
+        assert_eq!(lookups.constants.iter().collect::<Vec<_>>(), ["APP_ENV"]);
+
+        // Nothing is recorded outside record_lookups
+        table.get_class("Other");
+        let (_, lookups) = SymbolTable::record_lookups(|| ());
+        assert_eq!(lookups, Lookups::default());
+    }
 }
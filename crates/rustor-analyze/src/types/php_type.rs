@@ -138,6 +138,12 @@ pub enum Type {
 
     /// Literal type for specific values
     Literal(String),
+
+    /// A single enum case (e.g., `Status::Active`)
+    EnumCase {
+        class_name: String,
+        case_name: String,
+    },
 }
 
 impl Type {
@@ -264,6 +270,7 @@ impl Type {
     pub fn get_class_name(&self) -> Option<&str> {
         match self {
             Type::Object { class_name } => class_name.as_deref(),
+            Type::EnumCase { class_name, .. } => Some(class_name),
             _ => None,
         }
     }
@@ -346,6 +353,7 @@ impl fmt::Display for Type {
                 }
             }
             Type::Literal(v) => write!(f, "{}", v),
+            Type::EnumCase { class_name, case_name } => write!(f, "{}::{}", class_name, case_name),
         }
     }
 }
@@ -21,6 +21,10 @@ pub struct PhpDoc {
     pub templates: Vec<String>,
     /// @throws annotations
     pub throws: Vec<Type>,
+    /// @deprecated description (empty if the tag has no description)
+    pub deprecated: Option<String>,
+    /// @readonly (or @immutable on classes)
+    pub readonly: bool,
 }
 
 /// Property access mode
@@ -43,13 +47,30 @@ pub struct MethodSignature {
 /// Parse a PHPDoc comment block
 pub fn parse_phpdoc(comment: &str) -> PhpDoc {
     let mut doc = PhpDoc::default();
+    // Whether we're still collecting the (multi-line) @deprecated description
+    let mut in_deprecated = false;
 
     for line in comment.lines() {
         let line = line.trim()
             .trim_start_matches(['/', '*', ' '])
             .trim_end_matches(['/', '*', ' ']);
 
-        if let Some(rest) = line.strip_prefix("@param") {
+        if in_deprecated {
+            if line.is_empty() || line.starts_with('@') {
+                in_deprecated = false;
+            } else if let Some(message) = doc.deprecated.as_mut() {
+                if !message.is_empty() {
+                    message.push(' ');
+                }
+                message.push_str(line);
+                continue;
+            }
+        }
+
+        if let Some(rest) = line.strip_prefix("@deprecated") {
+            doc.deprecated = Some(rest.trim().to_string());
+            in_deprecated = true;
+        } else if let Some(rest) = line.strip_prefix("@param") {
             if let Some((type_str, name)) = parse_param_line(rest.trim()) {
                 if let Some(ty) = parse_type_string(&type_str) {
                     doc.params.push((name, ty));
@@ -64,7 +85,8 @@ pub fn parse_phpdoc(comment: &str) -> PhpDoc {
                 doc.var_type = Some(ty);
             }
         } else if let Some(rest) = line.strip_prefix("@throws") {
-            if let Some(ty) = parse_type_string(rest.trim()) {
+            // The type may be followed by a description
+            if let Some(ty) = parse_type_string(rest.split_whitespace().next().unwrap_or("")) {
                 doc.throws.push(ty);
             }
         } else if let Some(rest) = line.strip_prefix("@template") {
@@ -90,12 +112,30 @@ pub fn parse_phpdoc(comment: &str) -> PhpDoc {
                     doc.properties.push((name, ty, PropertyAccess::ReadWrite));
                 }
             }
+        } else if matches!(
+            line.split_whitespace().next(),
+            Some("@readonly" | "@psalm-readonly" | "@phpstan-readonly" | "@immutable" | "@psalm-immutable" | "@phpstan-immutable")
+        ) {
+            doc.readonly = true;
         }
     }
 
     doc
 }
 
+/// Find the PHPDoc block that directly precedes `offset` in `source`.
+///
+/// Only whitespace may separate the end of the comment from `offset`, so a
+/// docblock belonging to an earlier declaration is never picked up.
+pub fn find_docblock(source: &str, offset: usize) -> Option<&str> {
+    let before = source.get(..offset)?.trim_end();
+    if !before.ends_with("*/") {
+        return None;
+    }
+    let start = before.rfind("/**")?;
+    Some(&before[start..])
+}
+
 /// Parse a @param line: "Type $name" or "$name Type"
 fn parse_param_line(line: &str) -> Option<(String, String)> {
     let parts: Vec<&str> = line.split_whitespace().collect();
@@ -228,6 +268,16 @@ pub fn parse_type_string(s: &str) -> Option<Type> {
         }
     }
 
+    // Literal types: 'foo', "foo", 42
+    if s.len() >= 2
+        && ((s.starts_with('\'') && s.ends_with('\'')) || (s.starts_with('"') && s.ends_with('"')))
+    {
+        return Some(Type::ConstantString(s[1..s.len() - 1].to_string()));
+    }
+    if let Ok(n) = s.parse::<i64>() {
+        return Some(Type::ConstantInt(n));
+    }
+
     // Simple types
     match s.to_lowercase().as_str() {
         "mixed" => Some(Type::Mixed),
@@ -366,6 +416,22 @@ mod tests {
         assert_eq!(doc.return_type, Some(Type::Int));
     }
 
+    #[test]
+    fn test_parse_phpdoc_throws() {
+        let doc = parse_phpdoc("/**\n * @throws NotFound when the user is missing\n * @throws \\RuntimeException|LogicException\n */");
+        assert_eq!(doc.throws.len(), 2);
+        assert_eq!(doc.throws[0], Type::object("NotFound"));
+        assert!(matches!(&doc.throws[1], Type::Union(types) if types.len() == 2));
+    }
+
+    #[test]
+    fn test_parse_phpdoc_readonly() {
+        assert!(parse_phpdoc("/** @readonly */").readonly);
+        assert!(parse_phpdoc("/**\n * @psalm-immutable\n */").readonly);
+        assert!(!parse_phpdoc("/** @psalm-readonly-allow-private-mutation */").readonly);
+        assert!(!parse_phpdoc("/** @var string */").readonly);
+    }
+
     #[test]
     fn test_parse_phpdoc_var() {
         let doc = parse_phpdoc("/** @var DateTime */");
@@ -378,6 +444,40 @@ mod tests {
         assert_eq!(parse_type_string("false"), Some(Type::ConstantBool(false)));
     }
 
+    #[test]
+    fn test_parse_literal_types() {
+        assert_eq!(parse_type_string("'draft'"), Some(Type::ConstantString("draft".into())));
+        assert_eq!(parse_type_string("42"), Some(Type::ConstantInt(42)));
+        assert_eq!(
+            parse_type_string("'a'|'b'"),
+            Some(Type::Union(vec![
+                Type::ConstantString("a".into()),
+                Type::ConstantString("b".into()),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_find_docblock() {
+        let source = "<?php\n/** @return int */\nfunction foo() {}\nfunction bar() {}\n";
+        let foo = source.find("function foo").unwrap();
+        let bar = source.find("function bar").unwrap();
+        assert_eq!(find_docblock(source, foo), Some("/** @return int */"));
+        assert_eq!(find_docblock(source, bar), None);
+    }
+
+    #[test]
+    fn test_parse_deprecated() {
+        assert_eq!(parse_phpdoc("/** @deprecated */").deprecated, Some(String::new()));
+        assert_eq!(parse_phpdoc("/** @return int */").deprecated, None);
+
+        let doc = parse_phpdoc(
+            "/**\n * @deprecated since 2.0, use bar()\n *             instead.\n * @return int\n */",
+        );
+        assert_eq!(doc.deprecated.as_deref(), Some("since 2.0, use bar() instead."));
+        assert_eq!(doc.return_type, Some(Type::Int));
+    }
+
     #[test]
     fn test_parse_special_int_types() {
         assert!(matches!(
@@ -3,43 +3,88 @@
 use anyhow::Result;
 use colored::*;
 use rustor_analyze::{
-    baseline::Baseline,
+    baseline::{Baseline, SplitBy},
     config::{Level, PhpStanConfig},
+    incremental::IncrementalAnalysis,
+    issue::IssueCollection,
     logging,
     output::{format_issues, OutputFormat},
     Analyzer,
 };
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+use crate::config::Config;
+use crate::watch::{self, Finding, WatchConfig, WatchTarget};
+
 /// Arguments for the analyze subcommand
+#[derive(clap::Args)]
+#[command(after_help = "EXAMPLES:
+    rustor analyze
+    rustor analyze src/ tests/ --level 5
+    rustor analyze -c phpstan.neon --error-format json
+    rustor analyze --generate-baseline baseline.neon
+    rustor analyze --generate-baseline phpstan-baseline.php --baseline-split-by identifier
+    rustor analyze --phpstan-compat --level 1
+    rustor analyze src/ --deprecations
+    rustor analyze src/ --watch --summary
+    rustor analyze --debug-log /tmp/my-analyze.log")]
 pub struct AnalyzeArgs {
-    /// Paths to analyze
+    /// Paths to analyze (files or directories)
     pub paths: Vec<PathBuf>,
-    /// PHPStan config file
+    /// PHPStan config file (phpstan.neon)
+    #[arg(long, short = 'c', value_name = "FILE")]
     pub configuration: Option<PathBuf>,
-    /// Analysis level (0-10)
+    /// Analysis level (0-10, max)
+    #[arg(long, short = 'l', value_name = "LEVEL", value_parser = parse_level)]
     pub level: Option<u8>,
     /// Output format: raw, json, table, github
+    #[arg(long, value_name = "FORMAT", default_value = "table")]
     pub error_format: String,
-    /// Generate baseline
+    /// Generate baseline file (.neon, or .php for PHP format)
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "phpstan-baseline.neon")]
     pub generate_baseline: Option<PathBuf>,
-    /// Use baseline file
+    /// Use baseline file to filter issues
+    #[arg(long, value_name = "FILE")]
     pub baseline: Option<PathBuf>,
+    /// Split the generated baseline per identifier or directory
+    #[arg(long, value_name = "BY", requires = "generate_baseline")]
+    pub baseline_split_by: Option<SplitBy>,
     /// Verbose output
+    #[arg(long, short = 'v')]
     pub verbose: bool,
-    /// PHPStan compatibility mode - exactly matches PHPStan behavior
+    /// PHPStan exact compatibility mode
+    #[arg(long)]
     pub phpstan_compat: bool,
-    /// Debug log file path (enables detailed logging)
+    /// Enable debug logging (default: /tmp/rustor-analyze.log)
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "/tmp/rustor-analyze.log")]
     pub debug_log: Option<PathBuf>,
     /// Ignore config files
+    #[arg(long)]
     pub no_config: bool,
     /// Ignore baseline counts (match patterns unlimited times)
+    #[arg(long)]
     pub ignore_baseline_counts: bool,
+    /// Report usage of deprecated symbols
+    #[arg(long)]
+    pub deprecations: bool,
+    /// Analyze all files instead of reusing cached results
+    #[arg(long)]
+    pub no_cache: bool,
+    /// Re-analyze changed files and their dependents on save
+    #[arg(long, short = 'w')]
+    pub watch: bool,
+    /// With --watch, clear the screen before each run
+    #[arg(long, requires = "watch")]
+    pub clear: bool,
+    /// With --watch, print new/fixed/total counts after each run
+    #[arg(long, requires = "watch")]
+    pub summary: bool,
 }
 
 /// Run the analyze subcommand
-pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
+pub fn run_analyze(mut args: AnalyzeArgs) -> Result<ExitCode> {
     // Initialize logging if debug-log is specified
     if let Some(log_path) = &args.debug_log {
         match logging::init_logger(Some(log_path)) {
@@ -55,13 +100,20 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
     }
 
     // Load PHPStan configuration
-    let mut config = load_config(&args)?;
+    let (mut config, rustor_config) = load_config(&args)?;
+    if args.baseline.is_none() {
+        args.baseline = rustor_config.analyze.baseline.as_deref().map(|p| rustor_config.resolve(p));
+    }
 
     // Apply phpstan-compat mode if requested
     if args.phpstan_compat {
         config.phpstan_compat = true;
     }
 
+    if args.deprecations {
+        config.deprecation_rules = true;
+    }
+
     if args.verbose {
         println!("{}: {}", "Analysis level".bold(), config.level);
         if !config.paths.is_empty() {
@@ -81,6 +133,8 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
         analyzer.set_level(Level::from_u8(level));
     }
 
+    analyzer.set_cache(!args.no_cache);
+
     // Determine paths to analyze
     let paths_to_analyze: Vec<&Path> = if args.paths.is_empty() {
         // Use paths from config
@@ -104,6 +158,10 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
         println!();
     }
 
+    if args.watch {
+        return run_analyze_watch(&args, &analyzer, &paths_to_analyze);
+    }
+
     // Run analysis
     let mut issues = analyzer.analyze_paths(&paths_to_analyze)?;
 
@@ -137,22 +195,7 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
     }
     // Also apply baseline filtering from config's ignoreErrors (from includes)
     else if !analyzer.config().ignore_errors.is_empty() {
-        // Convert config ignoreErrors to baseline entries
-        let mut baseline = Baseline::new();
-        for ignore_error in &analyzer.config().ignore_errors {
-            // Create a baseline entry from the ignore error
-            // If no path is specified, it applies to all files (use empty string)
-            let path = ignore_error.path.clone().unwrap_or_else(|| String::from(""));
-            let count = ignore_error.count.unwrap_or(usize::MAX);
-
-            let entry = rustor_analyze::baseline::BaselineEntry::new(
-                ignore_error.message.clone(),
-                count,
-                path,
-                ignore_error.identifier.clone(),
-            );
-            baseline.entries.push(entry);
-        }
+        let baseline = ignore_errors_baseline(analyzer.config());
 
         let before_count = issues.len();
         issues = baseline.filter_with_options(issues, args.ignore_baseline_counts);
@@ -214,7 +257,16 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
         let new_count = baseline.entries.len();
         let existing_count = existing_baseline.map(|b| b.len()).unwrap_or(0);
 
-        baseline.save(baseline_output)?;
+        let files = match args.baseline_split_by {
+            Some(split) => baseline.save_split(baseline_output, split)?.len(),
+            None => {
+                baseline.save(baseline_output)?;
+                1
+            }
+        };
+        if files > 1 {
+            println!("{}: Split baseline into {} file(s)", "Info".bold(), files - 1);
+        }
 
         if existing_count > 0 {
             println!(
@@ -252,199 +304,199 @@ pub fn run_analyze(args: AnalyzeArgs) -> Result<ExitCode> {
 }
 
 /// Load PHPStan configuration
-fn load_config(args: &AnalyzeArgs) -> Result<PhpStanConfig> {
+fn load_config(args: &AnalyzeArgs) -> Result<(PhpStanConfig, Config)> {
     // If --no-config is specified, use defaults
     if args.no_config {
         if args.verbose {
             println!("{}: Ignoring config files (--no-config)", "Info".bold());
         }
-        return Ok(PhpStanConfig::default());
+        return Ok((PhpStanConfig::default(), Config::default()));
     }
 
-    // If config path is specified, use it
-    if let Some(config_path) = &args.configuration {
+    let rustor_config = match Config::load()? {
+        Some((config, path)) => {
+            if args.verbose {
+                println!("{}: {}", "Using config".bold(), path.display());
+            }
+            config
+        }
+        None => Config::default(),
+    };
+    let config = load_phpstan_config(args.configuration.as_deref(), &rustor_config, args.verbose)?;
+    Ok((config, rustor_config))
+}
+
+/// Load the PHPStan config with the `[analyze]` section of `.rustor.toml` applied
+///
+/// The PHPStan config is `configuration`, or `[analyze] config`, or
+/// phpstan.neon or phpstan.neon.dist in the current directory.
+pub(crate) fn load_phpstan_config(configuration: Option<&Path>, rustor_config: &Config, verbose: bool) -> Result<PhpStanConfig> {
+    let config_path = if let Some(config_path) = configuration {
         if !config_path.exists() {
             anyhow::bail!("Configuration file not found: {}", config_path.display());
         }
-        return Ok(PhpStanConfig::load(config_path)?);
-    }
+        Some(config_path.to_path_buf())
+    } else if let Some(config_path) = &rustor_config.analyze.config {
+        let config_path = rustor_config.resolve(config_path);
+        if !config_path.exists() {
+            anyhow::bail!("Configuration file from [analyze] config not found: {}", config_path.display());
+        }
+        Some(config_path)
+    } else {
+        // Try to find phpstan.neon or phpstan.neon.dist in current directory
+        PhpStanConfig::find_config(&std::env::current_dir()?)
+    };
 
-    // Try to find phpstan.neon or phpstan.neon.dist in current directory
-    let current_dir = std::env::current_dir()?;
-    if let Some(config_path) = PhpStanConfig::find_config(&current_dir) {
-        if args.verbose {
-            println!("{}: {}", "Using config".bold(), config_path.display());
+    let mut config = match config_path {
+        Some(config_path) => {
+            if verbose {
+                println!("{}: {}", "Using config".bold(), config_path.display());
+            }
+            PhpStanConfig::load(&config_path)?
         }
-        return Ok(PhpStanConfig::load(&config_path)?);
-    }
+        None => {
+            if verbose {
+                println!("{}: Using default configuration", "Info".bold());
+            }
+            PhpStanConfig::default()
+        }
+    };
+    rustor_config.apply_analyze(&mut config)?;
+    Ok(config)
+}
 
-    // No config found, use defaults
-    if args.verbose {
-        println!("{}: Using default configuration", "Info".bold());
+/// Convert the config's ignoreErrors (from includes) to baseline entries
+pub(crate) fn ignore_errors_baseline(config: &PhpStanConfig) -> Baseline {
+    let mut baseline = Baseline::new();
+    for ignore_error in &config.ignore_errors {
+        // Create a baseline entry from the ignore error
+        // If no path is specified, it applies to all files (use empty string)
+        let path = ignore_error.path.clone().unwrap_or_else(|| String::from(""));
+        let count = ignore_error.count.unwrap_or(usize::MAX);
+
+        let entry = rustor_analyze::baseline::BaselineEntry::new(
+            ignore_error.message.clone(),
+            count,
+            path,
+            ignore_error.identifier.clone(),
+        );
+        baseline.entries.push(entry);
     }
-    Ok(PhpStanConfig::default())
+    baseline
 }
 
-/// Parse arguments for analyze subcommand from command line
-pub fn parse_analyze_args(args: &[String]) -> Result<AnalyzeArgs> {
-    let mut paths = Vec::new();
-    let mut configuration = None;
-    let mut level = None;
-    let mut error_format = "table".to_string();
-    let mut generate_baseline = None;
-    let mut baseline = None;
-    let mut verbose = false;
-    let mut phpstan_compat = false;
-    let mut debug_log = None;
-    let mut no_config = false;
-    let mut ignore_baseline_counts = false;
-
-    let mut i = 0;
-    while i < args.len() {
-        let arg = &args[i];
-
-        if arg == "--no-config" {
-            no_config = true;
-        } else if arg == "-c" || arg == "--configuration" {
-            i += 1;
-            if i < args.len() {
-                configuration = Some(PathBuf::from(&args[i]));
-            }
-        } else if arg == "-l" || arg == "--level" {
-            i += 1;
-            if i >= args.len() {
-                anyhow::bail!("--level requires a value (0-10 or 'max')");
-            }
+/// Keep analyzing as files change, printing new and fixed issues
+fn run_analyze_watch(args: &AnalyzeArgs, analyzer: &Analyzer, paths: &[&Path]) -> Result<ExitCode> {
+    let paths: Vec<PathBuf> = paths.iter().map(|p| watch::canonical(p)).collect();
 
-            // Handle special "max" value
-            let level_value = &args[i];
-            if level_value == "max" {
-                level = Some(10);
-            } else {
-                match level_value.parse::<u8>() {
-                    Ok(l) if l <= 10 => level = Some(l),
-                    Ok(l) => anyhow::bail!("Invalid level: {}. Level must be between 0 and 10", l),
-                    Err(_) => anyhow::bail!("Invalid level: '{}'. Expected a number 0-10 or 'max'", level_value),
-                }
-            }
-        } else if let Some(level_str) = arg.strip_prefix("--level=") {
-            // Handle --level=5 format
-            if level_str == "max" {
-                level = Some(10);
-            } else {
-                match level_str.parse::<u8>() {
-                    Ok(l) if l <= 10 => level = Some(l),
-                    Ok(l) => anyhow::bail!("Invalid level: {}. Level must be between 0 and 10", l),
-                    Err(_) => anyhow::bail!("Invalid level: '{}'. Expected a number 0-10 or 'max'", level_str),
-                }
-            }
-        } else if let Some(level_str) = arg.strip_prefix("-l=") {
-            // Handle -l=5 format
-            if level_str == "max" {
-                level = Some(10);
-            } else {
-                match level_str.parse::<u8>() {
-                    Ok(l) if l <= 10 => level = Some(l),
-                    Ok(l) => anyhow::bail!("Invalid level: {}. Level must be between 0 and 10", l),
-                    Err(_) => anyhow::bail!("Invalid level: '{}'. Expected a number 0-10 or 'max'", level_str),
-                }
-            }
-        } else if arg == "--error-format" {
-            i += 1;
-            if i < args.len() {
-                error_format = args[i].clone();
-            }
-        } else if let Some(format) = arg.strip_prefix("--error-format=") {
-            error_format = format.to_string();
-        } else if arg == "--generate-baseline" {
-            i += 1;
-            if i < args.len() {
-                generate_baseline = Some(PathBuf::from(&args[i]));
-            } else {
-                generate_baseline = Some(PathBuf::from("phpstan-baseline.neon"));
-            }
-        } else if arg == "--baseline" {
-            i += 1;
-            if i < args.len() {
-                baseline = Some(PathBuf::from(&args[i]));
-            }
-        } else if arg == "-v" || arg == "--verbose" {
-            verbose = true;
-        } else if arg == "--phpstan-compat" {
-            phpstan_compat = true;
-        } else if arg == "--ignore-baseline-counts" {
-            ignore_baseline_counts = true;
-        } else if arg == "--debug-log" {
-            i += 1;
-            if i < args.len() {
-                debug_log = Some(PathBuf::from(&args[i]));
-            } else {
-                // Default path in /tmp
-                debug_log = Some(PathBuf::from("/tmp/rustor-analyze.log"));
-            }
-        } else if let Some(path) = arg.strip_prefix("--debug-log=") {
-            debug_log = Some(PathBuf::from(path));
-        } else if arg == "-h" || arg == "--help" {
-            print_analyze_help();
-            std::process::exit(0);
-        } else if !arg.starts_with('-') {
-            paths.push(PathBuf::from(arg));
-        } else {
-            // Unknown flag
-            anyhow::bail!("Unknown option: '{}'. Use --help to see available options", arg);
+    // Same baseline as a single run: --baseline, or the config's ignoreErrors
+    let baseline = match &args.baseline {
+        Some(path) if path.exists() => Some(Baseline::load(path)?),
+        Some(path) => {
+            eprintln!("{}: Baseline file not found: {}", "Warning".yellow(), path.display());
+            None
         }
+        None if !analyzer.config().ignore_errors.is_empty() => Some(ignore_errors_baseline(analyzer.config())),
+        None => None,
+    };
 
-        i += 1;
-    }
-
-    Ok(AnalyzeArgs {
-        paths,
-        configuration,
-        level,
-        error_format,
-        generate_baseline,
+    let mut target = AnalyzeWatch {
+        analyzer,
+        paths: paths.clone(),
+        analysis: None,
         baseline,
-        verbose,
-        phpstan_compat,
-        debug_log,
-        no_config,
-        ignore_baseline_counts,
-    })
+        ignore_counts: args.ignore_baseline_counts,
+    };
+    let config = WatchConfig {
+        paths,
+        verbose: args.verbose,
+        clear_screen: args.clear,
+        summary: args.summary,
+        ..Default::default()
+    };
+    watch::run_watch_incremental(&config, &mut target)?;
+
+    Ok(ExitCode::SUCCESS)
 }
 
-/// Print help for analyze subcommand
-pub fn print_analyze_help() {
-    println!("{}  PHPStan-compatible static analysis", "rustor analyze".bold());
-    println!();
-    println!("{}", "USAGE:".bold());
-    println!("    rustor analyze [OPTIONS] [PATHS]...");
-    println!();
-    println!("{}", "ARGS:".bold());
-    println!("    <PATHS>...    Paths to analyze (files or directories)");
-    println!();
-    println!("{}", "OPTIONS:".bold());
-    println!("    -c, --configuration <FILE>    PHPStan config file (phpstan.neon)");
-    println!("    -l, --level <LEVEL>           Analysis level (0-10, max)");
-    println!("        --error-format <FORMAT>   Output format: raw, json, table, github");
-    println!("        --generate-baseline <FILE>  Generate baseline file");
-    println!("        --baseline <FILE>         Use baseline file to filter issues");
-    println!("        --ignore-baseline-counts  Ignore baseline counts (match patterns unlimited times)");
-    println!("        --no-config               Ignore config files");
-    println!("        --phpstan-compat          PHPStan exact compatibility mode");
-    println!("        --debug-log [FILE]        Enable debug logging (default: /tmp/rustor-analyze.log)");
-    println!("    -v, --verbose                 Verbose output");
-    println!("    -h, --help                    Print help");
-    println!();
-    println!("{}", "EXAMPLES:".bold());
-    println!("    rustor analyze");
-    println!("    rustor analyze src/ tests/ --level 5");
-    println!("    rustor analyze -c phpstan.neon --error-format json");
-    println!("    rustor analyze --generate-baseline baseline.neon");
-    println!("    rustor analyze --phpstan-compat --level 1");
-    println!("    rustor analyze --debug-log /tmp/my-analyze.log");
+/// Watch target keeping an incremental analysis up to date
+struct AnalyzeWatch<'a> {
+    analyzer: &'a Analyzer,
+    paths: Vec<PathBuf>,
+    analysis: Option<IncrementalAnalysis<'a>>,
+    baseline: Option<Baseline>,
+    ignore_counts: bool,
+}
+
+impl AnalyzeWatch<'_> {
+    /// Reported issues of a file, after normalization and baseline filtering
+    fn findings(&self, file: &Path) -> Vec<Finding> {
+        let Some(analysis) = &self.analysis else {
+            return Vec::new();
+        };
+
+        let mut issues = IssueCollection::new();
+        issues.extend(analysis.file_issues(file).iter().cloned());
+        if self.analyzer.config().phpstan_compat {
+            issues.normalize_identifiers();
+        }
+        if let Some(baseline) = &self.baseline {
+            issues = baseline.filter_with_options(issues, self.ignore_counts);
+        }
+
+        issues
+            .into_issues()
+            .into_iter()
+            .map(|issue| Finding {
+                file: issue.file,
+                line: issue.line,
+                source: issue.identifier.unwrap_or(issue.check_id),
+                message: issue.message,
+            })
+            .collect()
+    }
 }
 
-/// Check if we should run the analyze subcommand
-pub fn should_run_analyze(args: &[String]) -> bool {
-    args.first().map(|s| s == "analyze").unwrap_or(false)
+impl WatchTarget for AnalyzeWatch<'_> {
+    fn check_all(&mut self) -> BTreeMap<PathBuf, Vec<Finding>> {
+        let paths: Vec<&Path> = self.paths.iter().map(|p| p.as_path()).collect();
+        let analysis = IncrementalAnalysis::new(self.analyzer, &paths);
+        let files: Vec<PathBuf> = analysis.files().map(Path::to_path_buf).collect();
+        self.analysis = Some(analysis);
+
+        files
+            .into_iter()
+            .map(|file| {
+                let findings = self.findings(&file);
+                (file, findings)
+            })
+            .collect()
+    }
+
+    fn check_changed(&mut self, changed: &[PathBuf]) -> BTreeMap<PathBuf, Vec<Finding>> {
+        let Some(analysis) = self.analysis.as_mut() else {
+            return BTreeMap::new();
+        };
+
+        analysis
+            .update(changed)
+            .into_iter()
+            .map(|file| {
+                let findings = self.findings(&file);
+                (file, findings)
+            })
+            .collect()
+    }
+}
+
+/// Parse an analysis level: 0-10 or `max`
+pub(crate) fn parse_level(value: &str) -> Result<u8> {
+    if value == "max" {
+        return Ok(10);
+    }
+    match value.parse::<u8>() {
+        Ok(level) if level <= 10 => Ok(level),
+        Ok(level) => anyhow::bail!("Invalid level: {}. Level must be between 0 and 10", level),
+        Err(_) => anyhow::bail!("Invalid level: '{}'. Expected a number 0-10 or 'max'", value),
+    }
 }
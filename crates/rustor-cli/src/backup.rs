@@ -2,10 +2,140 @@
 //!
 //! Creates timestamped backup directories before applying fixes,
 //! allowing restoration if something goes wrong.
+//!
+//! Each session directory mirrors the fixed files and holds a `session.json`
+//! manifest recording where each file came from, the rules applied to it and
+//! a hash of the fixed content, so `rustor undo` can tell whether a file was
+//! changed again since the fix.
 
 use anyhow::{Context, Result};
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Name of the session manifest inside a session directory
+const MANIFEST_FILE: &str = "session.json";
+/// Format of session directory names
+const SESSION_FORMAT: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// A file backed up in a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// Path of the fixed file, as given on the command line
+    pub path: PathBuf,
+    /// Path of the backup, relative to the session directory
+    pub backup: PathBuf,
+    /// Rules whose edits were applied
+    pub rules: Vec<String>,
+    /// Hash of the file content written by the fix, `None` if unknown
+    pub fixed_hash: Option<u64>,
+}
+
+/// Manifest of a backup session (session.json)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionManifest {
+    /// When the session was created (RFC 3339)
+    pub created: String,
+    /// Working directory of the run, relative paths are resolved against it
+    pub cwd: PathBuf,
+    pub files: Vec<BackupEntry>,
+}
+
+/// A backup session found in the backup directory
+#[derive(Debug)]
+pub struct BackupSession {
+    /// Session id (directory name)
+    pub id: String,
+    pub dir: PathBuf,
+    /// Manifest, `None` for sessions written without one
+    pub manifest: Option<SessionManifest>,
+}
+
+impl BackupSession {
+    /// Load a session from its directory
+    fn load(dir: PathBuf) -> Result<Self> {
+        let id = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            let content = fs::read_to_string(&manifest_path)
+                .with_context(|| format!("Failed to read {}", manifest_path.display()))?;
+            Some(serde_json::from_str(&content).with_context(|| format!("Failed to parse {}", manifest_path.display()))?)
+        } else {
+            None
+        };
+
+        Ok(Self { id, dir, manifest })
+    }
+
+    /// When the session was created, from the manifest or the directory name
+    pub fn created(&self) -> Option<DateTime<Local>> {
+        if let Some(manifest) = &self.manifest {
+            if let Ok(created) = DateTime::parse_from_rfc3339(&manifest.created) {
+                return Some(created.with_timezone(&Local));
+            }
+        }
+        let timestamp = self.id.get(..19)?;
+        let naive = NaiveDateTime::parse_from_str(timestamp, SESSION_FORMAT).ok()?;
+        Local.from_local_datetime(&naive).earliest()
+    }
+
+    /// Backed up files
+    ///
+    /// Without a manifest, every file in the session directory is listed with
+    /// its path relative to the current directory and no rules or hash.
+    pub fn files(&self) -> Vec<BackupEntry> {
+        if let Some(manifest) = &self.manifest {
+            return manifest.files.clone();
+        }
+
+        let mut files: Vec<BackupEntry> = walkdir::WalkDir::new(&self.dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| e.path().strip_prefix(&self.dir).ok().map(Path::to_path_buf))
+            .map(|relative| BackupEntry {
+                path: relative.clone(),
+                backup: relative,
+                rules: Vec::new(),
+                fixed_hash: None,
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    /// Path of the file an entry restores
+    pub fn target(&self, entry: &BackupEntry) -> PathBuf {
+        match &self.manifest {
+            Some(manifest) => manifest.cwd.join(&entry.path),
+            None => entry.path.clone(),
+        }
+    }
+
+    /// Whether the file was changed (or deleted) since the fix, `None` if unknown
+    pub fn changed_since_fix(&self, entry: &BackupEntry) -> Option<bool> {
+        let fixed_hash = entry.fixed_hash?;
+        match fs::read(self.target(entry)) {
+            Ok(content) => Some(xxh3_64(&content) != fixed_hash),
+            Err(_) => Some(true),
+        }
+    }
+
+    /// Restore one file from the session
+    pub fn restore(&self, entry: &BackupEntry) -> Result<()> {
+        let target = self.target(entry);
+        if let Some(parent) = target.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        let backup = self.dir.join(&entry.backup);
+        fs::copy(&backup, &target)
+            .with_context(|| format!("Failed to restore file: {} -> {}", backup.display(), target.display()))?;
+        Ok(())
+    }
+}
 
 /// Backup manager for file modifications
 pub struct BackupManager {
@@ -15,6 +145,8 @@ pub struct BackupManager {
     session_dir: Option<PathBuf>,
     /// Whether backups are enabled
     enabled: bool,
+    /// Files fixed in this session
+    entries: Vec<BackupEntry>,
 }
 
 impl BackupManager {
@@ -24,6 +156,7 @@ impl BackupManager {
             backup_dir,
             session_dir: None,
             enabled,
+            entries: Vec::new(),
         }
     }
 
@@ -33,8 +166,14 @@ impl BackupManager {
             return Ok(());
         }
 
-        let timestamp = chrono::Local::now().format("%Y-%m-%dT%H-%M-%S").to_string();
-        let session_dir = self.backup_dir.join(&timestamp);
+        let timestamp = Local::now().format(SESSION_FORMAT).to_string();
+        let mut session_dir = self.backup_dir.join(&timestamp);
+        // Don't mix two runs started within the same second
+        let mut n = 1;
+        while session_dir.exists() {
+            session_dir = self.backup_dir.join(format!("{}-{}", timestamp, n));
+            n += 1;
+        }
 
         fs::create_dir_all(&session_dir)
             .with_context(|| format!("Failed to create backup directory: {}", session_dir.display()))?;
@@ -78,6 +217,79 @@ impl BackupManager {
         Ok(Some(backup_path))
     }
 
+    /// Record that a backed up file was fixed, for the session manifest
+    pub fn record_fix(&mut self, path: &Path, backup_path: &Path, rules: Vec<String>, fixed_source: &str) {
+        let Some(session_dir) = &self.session_dir else {
+            return;
+        };
+
+        let mut rules = rules;
+        rules.sort();
+        rules.dedup();
+        self.entries.push(BackupEntry {
+            path: path.to_path_buf(),
+            backup: backup_path.strip_prefix(session_dir).unwrap_or(backup_path).to_path_buf(),
+            rules,
+            fixed_hash: Some(xxh3_64(fixed_source.as_bytes())),
+        });
+    }
+
+    /// Finish the session: write its manifest, or remove it if nothing was fixed
+    pub fn finish_session(&mut self) -> Result<()> {
+        let Some(session_dir) = &self.session_dir else {
+            return Ok(());
+        };
+
+        if self.entries.is_empty() {
+            // Only removes the directory if no file was backed up either
+            let _ = fs::remove_dir(session_dir);
+            return Ok(());
+        }
+
+        let manifest = SessionManifest {
+            created: Local::now().to_rfc3339(),
+            cwd: std::env::current_dir()?,
+            files: std::mem::take(&mut self.entries),
+        };
+        let manifest_path = session_dir.join(MANIFEST_FILE);
+        fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)
+            .with_context(|| format!("Failed to write {}", manifest_path.display()))?;
+        Ok(())
+    }
+
+    /// Sessions in the backup directory, oldest first
+    pub fn sessions(&self) -> Result<Vec<BackupSession>> {
+        if !self.backup_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut dirs = Vec::new();
+        for entry in fs::read_dir(&self.backup_dir)
+            .with_context(|| format!("Failed to read backup directory: {}", self.backup_dir.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            }
+        }
+        dirs.sort();
+
+        dirs.into_iter().map(BackupSession::load).collect()
+    }
+
+    /// Delete sessions created before `cutoff`, returning their ids
+    pub fn prune(&self, cutoff: DateTime<Local>) -> Result<Vec<String>> {
+        let mut pruned = Vec::new();
+        for session in self.sessions()? {
+            if session.created().is_some_and(|created| created < cutoff) {
+                fs::remove_dir_all(&session.dir)
+                    .with_context(|| format!("Failed to delete {}", session.dir.display()))?;
+                pruned.push(session.id);
+            }
+        }
+        Ok(pruned)
+    }
+
     /// Restore a file from backup
     pub fn restore_file(&self, original_path: &Path, backup_path: &Path) -> Result<()> {
         fs::copy(backup_path, original_path)
@@ -96,8 +308,8 @@ impl BackupManager {
     }
 }
 
-/// Verify a PHP file parses correctly
-pub fn verify_php_file(path: &Path) -> Result<bool> {
+/// Verify a fixed PHP file has no more parse errors than its `original` source
+pub fn verify_php_file(path: &Path, original: &str) -> Result<bool> {
     use bumpalo::Bump;
     use mago_database::file::FileId;
 
@@ -106,9 +318,12 @@ pub fn verify_php_file(path: &Path) -> Result<bool> {
 
     let arena = Bump::new();
     let file_id = FileId::new(path.to_string_lossy().as_ref());
-    let (_, parse_error) = mago_syntax::parser::parse_file_content(&arena, file_id, &source);
+    let errors = rustor_core::parse(&arena, file_id, &source).errors.len();
+    if errors == 0 {
+        return Ok(true);
+    }
 
-    Ok(parse_error.is_none())
+    Ok(errors <= rustor_core::parse(&arena, file_id, original).errors.len())
 }
 
 #[cfg(test)]
@@ -187,7 +402,7 @@ mod tests {
         let test_file = temp.path().join("valid.php");
         fs::write(&test_file, "<?php\necho 'hello';\n").unwrap();
 
-        assert!(verify_php_file(&test_file).unwrap());
+        assert!(verify_php_file(&test_file, "<?php\necho 'hello';\n").unwrap());
     }
 
     #[test]
@@ -196,6 +411,102 @@ mod tests {
         let test_file = temp.path().join("invalid.php");
         fs::write(&test_file, "<?php\necho 'hello\n").unwrap(); // Missing closing quote
 
-        assert!(!verify_php_file(&test_file).unwrap());
+        assert!(!verify_php_file(&test_file, "<?php\necho 'hello';\n").unwrap());
+    }
+
+    #[test]
+    fn test_verify_keeps_existing_parse_errors() {
+        let temp = TempDir::new().unwrap();
+        let test_file = temp.path().join("broken.php");
+        fs::write(&test_file, "<?php\n$a = ;\n$b = [1, 2];\n").unwrap();
+
+        // Fixing code around an existing parse error is fine, adding one isn't
+        assert!(verify_php_file(&test_file, "<?php\n$a = ;\n$b = array(1, 2);\n").unwrap());
+        assert!(!verify_php_file(&test_file, "<?php\n$a = 1;\n$b = array(1, 2);\n").unwrap());
+    }
+
+    /// Run a fix of `file` through a session, returning the manager
+    fn fix_in_session(backups: &Path, file: &Path, fixed: &str) -> BackupManager {
+        let mut manager = BackupManager::new(backups.to_path_buf(), true);
+        manager.init_session().unwrap();
+        let backup_path = manager.backup_file(file).unwrap().unwrap();
+        fs::write(file, fixed).unwrap();
+        manager.record_fix(file, &backup_path, vec!["sizeof".into(), "is_null".into(), "sizeof".into()], fixed);
+        manager.finish_session().unwrap();
+        manager
+    }
+
+    #[test]
+    fn test_session_manifest() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("test.php");
+        fs::write(&file, "<?php sizeof($a);").unwrap();
+
+        let manager = fix_in_session(&temp.path().join("backups"), &file, "<?php count($a);");
+        let sessions = manager.sessions().unwrap();
+        assert_eq!(sessions.len(), 1);
+
+        let session = &sessions[0];
+        let files = session.files();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].rules, vec!["is_null", "sizeof"]);
+        assert_eq!(session.target(&files[0]), file);
+        assert!(session.created().is_some());
+        assert_eq!(session.changed_since_fix(&files[0]), Some(false));
+
+        session.restore(&files[0]).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "<?php sizeof($a);");
+        assert_eq!(session.changed_since_fix(&files[0]), Some(true));
+    }
+
+    #[test]
+    fn test_empty_session_removed() {
+        let temp = TempDir::new().unwrap();
+        let mut manager = BackupManager::new(temp.path().to_path_buf(), true);
+        manager.init_session().unwrap();
+        manager.finish_session().unwrap();
+
+        assert!(manager.sessions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sessions_in_same_second() {
+        let temp = TempDir::new().unwrap();
+        let mut first = BackupManager::new(temp.path().to_path_buf(), true);
+        let mut second = BackupManager::new(temp.path().to_path_buf(), true);
+        first.init_session().unwrap();
+        second.init_session().unwrap();
+
+        assert_ne!(first.session_path(), second.session_path());
+    }
+
+    #[test]
+    fn test_session_without_manifest() {
+        let temp = TempDir::new().unwrap();
+        let session_dir = temp.path().join("2024-01-02T03-04-05");
+        fs::create_dir_all(session_dir.join("src")).unwrap();
+        fs::write(session_dir.join("src/a.php"), "<?php").unwrap();
+
+        let manager = BackupManager::new(temp.path().to_path_buf(), false);
+        let session = manager.sessions().unwrap().remove(0);
+        assert_eq!(session.id, "2024-01-02T03-04-05");
+        assert_eq!(session.created().unwrap().format("%Y-%m-%d %H:%M").to_string(), "2024-01-02 03:04");
+
+        let files = session.files();
+        assert_eq!(files[0].path, PathBuf::from("src/a.php"));
+        assert_eq!(session.changed_since_fix(&files[0]), None);
+    }
+
+    #[test]
+    fn test_prune() {
+        let temp = TempDir::new().unwrap();
+        fs::create_dir_all(temp.path().join("2020-01-01T00-00-00")).unwrap();
+        let file = temp.path().join("test.php");
+        fs::write(&file, "<?php").unwrap();
+        let manager = fix_in_session(temp.path(), &file, "<?php\n");
+
+        let cutoff = Local::now() - chrono::Duration::days(30);
+        assert_eq!(manager.prune(cutoff).unwrap(), vec!["2020-01-01T00-00-00"]);
+        assert_eq!(manager.sessions().unwrap().len(), 1);
     }
 }
@@ -1,7 +1,12 @@
 //! Baseline support for rustor
 //!
 //! Allows gradual adoption of rustor by tracking existing issues
-//! in a baseline file and only reporting new issues.
+//! in a baseline file and only reporting new issues. Rules and fixers
+//! share the format.
+//!
+//! Entries are keyed on rule, file and a hash of the finding's line with
+//! whitespace normalized, and carry a count like PHPStan baselines, so
+//! they survive code moving around in the file.
 //!
 //! Usage:
 //! ```bash
@@ -10,30 +15,44 @@
 //!
 //! # Run with baseline (only new issues reported)
 //! rustor src/ --baseline .rustor-baseline.json
+//!
+//! # Drop entries that were fixed since
+//! rustor src/ --baseline .rustor-baseline.json --update-baseline
 //! ```
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::Path;
 use anyhow::{Context, Result};
+use colored::*;
+use xxhash_rust::xxh3::xxh3_64;
 
 use crate::output::EditInfo;
 
 /// Baseline file format version
-const BASELINE_VERSION: u32 = 1;
+const BASELINE_VERSION: u32 = 2;
 
-/// A single issue in the baseline
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
-pub struct BaselineIssue {
-    /// Relative file path
-    pub file: String,
-    /// Line number (1-based)
-    pub line: usize,
-    /// Rule that triggered the issue
+/// Snippets longer than this are cut in the baseline file
+const SNIPPET_LEN: usize = 80;
+
+/// Findings of one rule on matching lines of a file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BaselineEntry {
+    /// Rule or fixer that reported the findings
     pub rule: String,
-    /// Content hash for fuzzy matching (first 20 chars of the line)
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub context: Option<String>,
+    /// File path, as passed on the command line
+    pub file: String,
+    /// Hash of the line the findings are on, with whitespace normalized
+    pub hash: String,
+    /// The normalized line, for people reading the baseline
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub snippet: String,
+    /// Number of findings covered
+    pub count: usize,
+    /// Converted from a version 1 baseline: `hash` is of the first 40
+    /// characters of the trimmed line
+    #[serde(skip)]
+    legacy: bool,
 }
 
 /// The baseline file structure
@@ -43,8 +62,23 @@ pub struct Baseline {
     pub version: u32,
     /// When the baseline was generated (ISO 8601)
     pub generated: String,
-    /// List of known issues
-    pub issues: Vec<BaselineIssue>,
+    /// Known findings, sorted by file and rule
+    #[serde(default)]
+    pub entries: Vec<BaselineEntry>,
+}
+
+/// A version 1 baseline file, with one issue per finding
+#[derive(Deserialize)]
+struct LegacyBaseline {
+    generated: String,
+    issues: Vec<LegacyIssue>,
+}
+
+#[derive(Deserialize)]
+struct LegacyIssue {
+    file: String,
+    rule: String,
+    context: Option<String>,
 }
 
 impl Baseline {
@@ -53,104 +87,76 @@ impl Baseline {
         Self {
             version: BASELINE_VERSION,
             generated: chrono_lite_now(),
-            issues: Vec::new(),
+            entries: Vec::new(),
         }
     }
 
-    /// Load a baseline from a file
+    /// Load a baseline from a file. Version 1 files are converted.
     pub fn load(path: &Path) -> Result<Self> {
         let content = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read baseline file: {}", path.display()))?;
+        Self::from_json(&content)
+            .with_context(|| format!("Failed to parse baseline file: {}", path.display()))
+    }
 
-        let baseline: Baseline = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse baseline file: {}", path.display()))?;
+    fn from_json(content: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(content)?;
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0);
 
         // Check version compatibility
-        if baseline.version > BASELINE_VERSION {
+        if version > BASELINE_VERSION as u64 {
             anyhow::bail!(
                 "Baseline file version {} is newer than supported version {}",
-                baseline.version,
+                version,
                 BASELINE_VERSION
             );
         }
 
-        Ok(baseline)
+        if version == 1 {
+            let legacy: LegacyBaseline = serde_json::from_value(value)?;
+            return Ok(Self::from_legacy(legacy));
+        }
+        Ok(serde_json::from_value(value)?)
     }
 
-    /// Generate a baseline from processing results
-    pub fn generate(files: &[(String, Vec<EditInfo>, Option<String>)]) -> Self {
-        let mut issues = Vec::new();
-
-        for (path, edits, source) in files {
-            let lines: Vec<&str> = source.as_ref().map(|s| s.lines().collect()).unwrap_or_default();
-
-            for edit in edits {
-                let context = if edit.line > 0 && edit.line <= lines.len() {
-                    let line_content = lines[edit.line - 1];
-                    // Take first 40 chars as context for fuzzy matching
-                    Some(line_content.trim().chars().take(40).collect())
-                } else {
-                    None
-                };
-
-                issues.push(BaselineIssue {
-                    file: path.clone(),
-                    line: edit.line,
-                    rule: edit.rule.clone(),
-                    context,
-                });
-            }
+    fn from_legacy(legacy: LegacyBaseline) -> Self {
+        let mut counts: BTreeMap<(String, String, String), usize> = BTreeMap::new();
+        for issue in legacy.issues {
+            let hash = hash(issue.context.as_deref().unwrap_or(""));
+            *counts.entry((issue.file, issue.rule, hash)).or_default() += 1;
         }
 
+        let entries = counts
+            .into_iter()
+            .map(|((file, rule, hash), count)| BaselineEntry {
+                rule,
+                file,
+                hash,
+                snippet: String::new(),
+                count,
+                legacy: true,
+            })
+            .collect();
+
         Self {
             version: BASELINE_VERSION,
-            generated: chrono_lite_now(),
-            issues,
+            generated: legacy.generated,
+            entries,
         }
     }
 
-    /// Filter out issues that are in the baseline
-    pub fn filter_edits(&self, path: &str, edits: Vec<EditInfo>, source: &str) -> Vec<EditInfo> {
-        let lines: Vec<&str> = source.lines().collect();
-
-        // Build a set of baseline issues for this file
-        let baseline_issues: HashSet<_> = self.issues
-            .iter()
-            .filter(|issue| issue.file == path)
-            .cloned()
-            .collect();
+    /// Generate a baseline from processing results
+    pub fn generate(files: &[(String, Vec<EditInfo>, Option<String>)]) -> Self {
+        let mut findings = Findings::default();
 
-        edits.into_iter().filter(|edit| {
-            let context = if edit.line > 0 && edit.line <= lines.len() {
-                let line_content = lines[edit.line - 1];
-                Some(line_content.trim().chars().take(40).collect::<String>())
-            } else {
-                None
-            };
-
-            // Create issue to compare
-            let current_issue = BaselineIssue {
-                file: path.to_string(),
-                line: edit.line,
-                rule: edit.rule.clone(),
-                context: context.clone(),
-            };
-
-            // Check exact match first
-            if baseline_issues.contains(&current_issue) {
-                return false;
+        for (path, edits, source) in files {
+            let lines: Vec<&str> = source.as_deref().map(|s| s.lines().collect()).unwrap_or_default();
+            for edit in edits {
+                findings.add(path, &edit.rule, line_at(&lines, edit.line));
             }
+        }
 
-            // Fuzzy match: same file, rule, and context (line may have shifted)
-            let fuzzy_match = baseline_issues.iter().any(|base| {
-                base.file == path
-                    && base.rule == edit.rule
-                    && base.context.is_some()
-                    && base.context == context
-            });
-
-            !fuzzy_match
-        }).collect()
+        findings.into_baseline()
     }
 
     /// Serialize baseline to JSON
@@ -159,14 +165,20 @@ impl Baseline {
             .context("Failed to serialize baseline")
     }
 
-    /// Get the number of issues
+    /// Write the baseline to a file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, self.to_json()? + "\n")
+            .with_context(|| format!("Failed to write baseline file: {}", path.display()))
+    }
+
+    /// Get the number of findings covered
     pub fn len(&self) -> usize {
-        self.issues.len()
+        self.entries.iter().map(|e| e.count).sum()
     }
 
     /// Check if baseline is empty
     pub fn is_empty(&self) -> bool {
-        self.issues.is_empty()
+        self.entries.is_empty()
     }
 }
 
@@ -176,6 +188,226 @@ impl Default for Baseline {
     }
 }
 
+/// Baseline entry with findings it no longer matches
+pub struct StaleEntry<'a> {
+    pub entry: &'a BaselineEntry,
+    /// How many of the entry's findings were not found
+    pub missing: usize,
+}
+
+/// Filters findings against a baseline during a run.
+///
+/// Each entry covers `count` findings; further findings on matching lines
+/// are new. Entries not used up are stale if their rule ran on their file:
+/// the rule is one of the run's `rules`, the file was checked and the
+/// config didn't skip the rule there.
+pub struct BaselineMatcher<'a> {
+    baseline: &'a Baseline,
+    /// Entry indexes per file
+    by_file: HashMap<&'a str, Vec<usize>>,
+    /// Findings each entry can still cover
+    remaining: Vec<usize>,
+    /// Rules and fixers the run has
+    rules: HashSet<String>,
+    /// Files the run checked, with the rules skipped on them
+    checked: HashMap<String, HashSet<String>>,
+    /// Findings covered by the baseline, for `--update-baseline`
+    matched: Findings,
+}
+
+impl<'a> BaselineMatcher<'a> {
+    pub fn new(baseline: &'a Baseline, rules: HashSet<String>) -> Self {
+        let mut by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (index, entry) in baseline.entries.iter().enumerate() {
+            by_file.entry(entry.file.as_str()).or_default().push(index);
+        }
+
+        Self {
+            baseline,
+            by_file,
+            remaining: baseline.entries.iter().map(|e| e.count).collect(),
+            rules,
+            checked: HashMap::new(),
+            matched: Findings::default(),
+        }
+    }
+
+    /// Filter out a file's findings that are in the baseline
+    pub fn filter_edits(&mut self, path: &str, edits: Vec<EditInfo>, source: &str) -> Vec<EditInfo> {
+        self.filter(path, source, edits, |edit| (edit.rule.as_str(), edit.line))
+    }
+
+    /// Filter out findings that are in the baseline, given their rule and
+    /// 1-based line
+    pub fn filter<T>(
+        &mut self,
+        path: &str,
+        source: &str,
+        findings: Vec<T>,
+        rule_line: impl Fn(&T) -> (&str, usize),
+    ) -> Vec<T> {
+        self.mark_checked(path);
+        let Some(indexes) = self.by_file.get(path) else {
+            return findings;
+        };
+        let lines: Vec<&str> = source.lines().collect();
+
+        let mut new = Vec::new();
+        for finding in findings {
+            let (rule, line) = rule_line(&finding);
+            let line = line_at(&lines, line);
+            let (line_hash, legacy_hash) = (hash(&normalize(line)), hash(&legacy_context(line)));
+
+            let entry = indexes.iter().copied().find(|&i| {
+                let entry = &self.baseline.entries[i];
+                let expected = if entry.legacy { &legacy_hash } else { &line_hash };
+                self.remaining[i] > 0 && entry.rule == rule && &entry.hash == expected
+            });
+            match entry {
+                Some(i) => {
+                    self.remaining[i] -= 1;
+                    self.matched.add(path, rule, line);
+                }
+                None => new.push(finding),
+            }
+        }
+        new
+    }
+
+    /// Record a file without findings as checked
+    pub fn mark_checked(&mut self, path: &str) {
+        self.checked.entry(path.to_string()).or_default();
+    }
+
+    /// Record rules that were skipped on a file
+    pub fn skip_rules(&mut self, path: &str, rules: impl IntoIterator<Item = String>) {
+        self.checked.entry(path.to_string()).or_default().extend(rules);
+    }
+
+    /// Whether a rule ran on a file
+    fn ran(&self, file: &str, rule: &str) -> bool {
+        self.rules.contains(rule) && self.checked.get(file).is_some_and(|skipped| !skipped.contains(rule))
+    }
+
+    /// Entries of rules that ran on their file and matched fewer findings
+    /// than they cover
+    pub fn stale(&self) -> Vec<StaleEntry<'a>> {
+        let baseline = self.baseline;
+        baseline
+            .entries
+            .iter()
+            .zip(&self.remaining)
+            .filter(|(entry, &missing)| missing > 0 && self.ran(&entry.file, &entry.rule))
+            .map(|(entry, &missing)| StaleEntry { entry, missing })
+            .collect()
+    }
+
+    /// The baseline without resolved findings. Entries of rules that did
+    /// not run on their file are kept, and new findings are not added.
+    pub fn updated(&self) -> Baseline {
+        let mut baseline = self.matched.clone().into_baseline();
+        baseline.entries.retain(|entry| self.ran(&entry.file, &entry.rule));
+        baseline.entries.extend(
+            self.baseline
+                .entries
+                .iter()
+                .filter(|entry| !self.ran(&entry.file, &entry.rule))
+                .cloned(),
+        );
+        baseline.entries.sort_by(|a, b| (&a.file, &a.rule, &a.hash).cmp(&(&b.file, &b.rule, &b.hash)));
+        baseline
+    }
+}
+
+/// Report baseline entries that no longer match and, with
+/// `--update-baseline`, remove them from the baseline file
+pub fn finish(matcher: &BaselineMatcher, path: &Path, update: bool) -> Result<()> {
+    let stale = matcher.stale();
+    if update {
+        let removed: usize = stale.iter().map(|s| s.missing).sum();
+        matcher.updated().save(path)?;
+        eprintln!("Removed {} resolved finding(s) from {}", removed, path.display());
+        return Ok(());
+    }
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    eprintln!(
+        "{}: {} stale baseline entr{}, not found anymore:",
+        "Warning".yellow(),
+        stale.len(),
+        if stale.len() == 1 { "y" } else { "ies" }
+    );
+    for stale in &stale {
+        let entry = stale.entry;
+        eprintln!(
+            "  {} [{}] {} of {} not found: {}",
+            entry.file,
+            entry.rule,
+            stale.missing,
+            entry.count,
+            entry.snippet.dimmed()
+        );
+    }
+    eprintln!("Run with --update-baseline to remove them.");
+    Ok(())
+}
+
+/// Findings counted per file, rule and line hash
+#[derive(Clone, Default)]
+struct Findings {
+    counts: BTreeMap<(String, String, String), (String, usize)>,
+}
+
+impl Findings {
+    fn add(&mut self, file: &str, rule: &str, line: &str) {
+        let normalized = normalize(line);
+        let key = (file.to_string(), rule.to_string(), hash(&normalized));
+        let snippet = normalized.chars().take(SNIPPET_LEN).collect();
+        self.counts.entry(key).or_insert((snippet, 0)).1 += 1;
+    }
+
+    fn into_baseline(self) -> Baseline {
+        let entries = self
+            .counts
+            .into_iter()
+            .map(|((file, rule, hash), (snippet, count))| BaselineEntry {
+                rule,
+                file,
+                hash,
+                snippet,
+                count,
+                legacy: false,
+            })
+            .collect();
+
+        Baseline {
+            entries,
+            ..Baseline::new()
+        }
+    }
+}
+
+/// The 1-based line of a source, or "" if out of range
+fn line_at<'s>(lines: &[&'s str], line: usize) -> &'s str {
+    line.checked_sub(1).and_then(|i| lines.get(i)).copied().unwrap_or("")
+}
+
+/// Trim a line and collapse its whitespace
+fn normalize(line: &str) -> String {
+    line.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// What version 1 baselines stored for a line
+fn legacy_context(line: &str) -> String {
+    line.trim().chars().take(40).collect()
+}
+
+fn hash(text: &str) -> String {
+    format!("{:016x}", xxh3_64(text.as_bytes()))
+}
+
 /// Simple ISO 8601 timestamp without external dependencies
 fn chrono_lite_now() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -237,108 +469,149 @@ fn is_leap_year(year: i64) -> bool {
 mod tests {
     use super::*;
 
+    fn edit(rule: &str, line: usize) -> EditInfo {
+        EditInfo {
+            rule: rule.to_string(),
+            line,
+            column: 1,
+            message: "test".to_string(),
+        }
+    }
+
+    fn rules(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn generate(source: &str, edits: Vec<EditInfo>) -> Baseline {
+        Baseline::generate(&[("test.php".to_string(), edits, Some(source.to_string()))])
+    }
+
     #[test]
     fn test_baseline_new() {
         let baseline = Baseline::new();
         assert_eq!(baseline.version, BASELINE_VERSION);
-        assert!(baseline.issues.is_empty());
+        assert!(baseline.entries.is_empty());
     }
 
     #[test]
     fn test_baseline_generate() {
-        let files = vec![(
-            "test.php".to_string(),
-            vec![
-                EditInfo {
-                    rule: "is_null".to_string(),
-                    line: 5,
-                    column: 1,
-                    message: "test".to_string(),
-                },
-            ],
-            Some("<?php\nline2\nline3\nline4\n$x = is_null($y);".to_string()),
-        )];
-
-        let baseline = Baseline::generate(&files);
-        assert_eq!(baseline.issues.len(), 1);
-        assert_eq!(baseline.issues[0].file, "test.php");
-        assert_eq!(baseline.issues[0].rule, "is_null");
-        assert_eq!(baseline.issues[0].line, 5);
+        let source = "<?php\n$a = sizeof($x);\n  $a   = sizeof($x);\n$x = is_null($y);";
+        let baseline = generate(source, vec![edit("sizeof", 2), edit("sizeof", 3), edit("is_null", 4)]);
+
+        // Lines that only differ in whitespace share an entry
+        assert_eq!(baseline.entries.len(), 2);
+        assert_eq!(baseline.len(), 3);
+        let entry = baseline.entries.iter().find(|e| e.rule == "sizeof").unwrap();
+        assert_eq!(entry.file, "test.php");
+        assert_eq!(entry.count, 2);
+        assert_eq!(entry.snippet, "$a = sizeof($x);");
     }
 
     #[test]
-    fn test_baseline_filter_exact() {
-        let mut baseline = Baseline::new();
-        baseline.issues.push(BaselineIssue {
-            file: "test.php".to_string(),
-            line: 5,
-            rule: "is_null".to_string(),
-            context: Some("$x = is_null($y);".to_string()),
-        });
+    fn test_baseline_filter_shifted_lines() {
+        let baseline = generate("<?php\n$x = is_null($y);", vec![edit("is_null", 2)]);
 
-        let edits = vec![
-            EditInfo {
-                rule: "is_null".to_string(),
-                line: 5,
-                column: 1,
-                message: "test".to_string(),
-            },
-            EditInfo {
-                rule: "array_push".to_string(),
-                line: 10,
-                column: 1,
-                message: "test2".to_string(),
-            },
-        ];
-
-        let source = "line1\nline2\nline3\nline4\n$x = is_null($y);\nline6\nline7\nline8\nline9\narray_push($arr, $v);";
-        let filtered = baseline.filter_edits("test.php", edits, source);
+        // Same content on another line, next to a new finding
+        let source = "<?php\n\n// moved\n    $x = is_null($y);\narray_push($arr, $v);";
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["is_null", "array_push"]));
+        let filtered = matcher.filter_edits("test.php", vec![edit("is_null", 4), edit("array_push", 5)], source);
 
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].rule, "array_push");
+        assert!(matcher.stale().is_empty());
+    }
+
+    #[test]
+    fn test_baseline_counts() {
+        let source = "<?php\n$a = sizeof($x);\n$a = sizeof($x);";
+        let baseline = generate(source, vec![edit("sizeof", 2), edit("sizeof", 3)]);
+
+        // A third copy of the line is new
+        let source = "<?php\n$a = sizeof($x);\n$a = sizeof($x);\n$a = sizeof($x);";
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["sizeof"]));
+        let filtered = matcher.filter_edits("test.php", vec![edit("sizeof", 2), edit("sizeof", 3), edit("sizeof", 4)], source);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].line, 4);
+
+        // One copy fixed: the entry has a finding left over
+        let source = "<?php\n$a = count($x);\n$a = sizeof($x);";
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["sizeof"]));
+        assert!(matcher.filter_edits("test.php", vec![edit("sizeof", 3)], source).is_empty());
+        let stale = matcher.stale();
+        assert_eq!(stale.len(), 1);
+        assert_eq!(stale[0].missing, 1);
     }
 
     #[test]
-    fn test_baseline_filter_fuzzy() {
-        let mut baseline = Baseline::new();
-        baseline.issues.push(BaselineIssue {
-            file: "test.php".to_string(),
-            line: 5, // Original line
-            rule: "is_null".to_string(),
-            context: Some("$x = is_null($y);".to_string()),
+    fn test_baseline_update() {
+        let mut baseline = generate("<?php\nsizeof($a);\nsizeof($b);", vec![edit("sizeof", 2), edit("sizeof", 3)]);
+        baseline.entries.push(BaselineEntry {
+            file: "other.php".to_string(),
+            snippet: "kept".to_string(),
+            ..baseline.entries[0].clone()
         });
 
-        // Same content but on line 7 (line shifted due to new code)
-        let edits = vec![EditInfo {
-            rule: "is_null".to_string(),
-            line: 7,
-            column: 1,
-            message: "test".to_string(),
-        }];
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["sizeof", "is_null"]));
+        let source = "<?php\nsizeof($a);\ncount($b);\nis_null($c);";
+        matcher.filter_edits("test.php", vec![edit("sizeof", 2), edit("is_null", 4)], source);
+
+        // The fixed entry is removed, the new finding is not added and the
+        // file that wasn't checked keeps its entry
+        let updated = matcher.updated();
+        let files: Vec<(&str, &str)> = updated.entries.iter().map(|e| (e.file.as_str(), e.snippet.as_str())).collect();
+        assert_eq!(files, vec![("other.php", "kept"), ("test.php", "sizeof($a);")]);
+    }
 
-        let source = "line1\nline2\nline3\nline4\nline5\nline6\n$x = is_null($y);";
-        let filtered = baseline.filter_edits("test.php", edits, source);
+    #[test]
+    fn test_baseline_update_keeps_rules_that_did_not_run() {
+        let source = "<?php\nsizeof($a);\nis_null($b);\narray_push($c, 1);";
+        let baseline = generate(source, vec![edit("sizeof", 2), edit("is_null", 3), edit("array_push", 4)]);
+
+        // Only sizeof and array_push ran, and array_push is skipped on the file
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["sizeof", "array_push"]));
+        matcher.skip_rules("test.php", ["array_push".to_string()]);
+        let source = "<?php\ncount($a);\nis_null($b);\narray_push($c, 1);";
+        assert!(matcher.filter_edits("test.php", Vec::new(), source).is_empty());
+
+        let stale: Vec<&str> = matcher.stale().iter().map(|s| s.entry.rule.as_str()).collect();
+        assert_eq!(stale, vec!["sizeof"]);
+        let updated = matcher.updated();
+        let rules: Vec<&str> = updated.entries.iter().map(|e| e.rule.as_str()).collect();
+        assert_eq!(rules, vec!["array_push", "is_null"]);
+    }
 
-        // Should be filtered out due to fuzzy match on context
-        assert_eq!(filtered.len(), 0);
+    #[test]
+    fn test_baseline_legacy() {
+        let json = r#"{
+            "version": 1,
+            "generated": "2024-01-01T00:00:00Z",
+            "issues": [{"file": "test.php", "line": 5, "rule": "is_null", "context": "$x = is_null($y);"}]
+        }"#;
+        let baseline = Baseline::from_json(json).unwrap();
+        assert_eq!(baseline.version, BASELINE_VERSION);
+
+        let mut matcher = BaselineMatcher::new(&baseline, rules(&["is_null"]));
+        let source = "<?php\n  $x = is_null($y);";
+        assert!(matcher.filter_edits("test.php", vec![edit("is_null", 2)], source).is_empty());
+
+        // Updating rewrites the entry in the current format
+        let updated = matcher.updated();
+        assert_eq!(updated.entries[0].hash, hash("$x = is_null($y);"));
+        assert!(!updated.entries[0].legacy);
     }
 
     #[test]
     fn test_baseline_serialization() {
-        let mut baseline = Baseline::new();
-        baseline.issues.push(BaselineIssue {
-            file: "test.php".to_string(),
-            line: 5,
-            rule: "is_null".to_string(),
-            context: Some("test context".to_string()),
-        });
+        let baseline = generate("<?php\nsizeof($a);", vec![edit("sizeof", 2)]);
 
         let json = baseline.to_json().unwrap();
-        assert!(json.contains("\"version\": 1"));
+        assert!(json.contains("\"version\": 2"));
         assert!(json.contains("\"file\": \"test.php\""));
+        assert!(json.contains("\"count\": 1"));
 
         // Verify it can be parsed back
-        let parsed: Baseline = serde_json::from_str(&json).unwrap();
-        assert_eq!(parsed.issues.len(), 1);
+        let parsed = Baseline::from_json(&json).unwrap();
+        assert_eq!(parsed.entries, baseline.entries);
+        assert!(Baseline::from_json(r#"{"version": 3, "generated": ""}"#).is_err());
     }
 }
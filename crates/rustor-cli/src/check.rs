@@ -0,0 +1,676 @@
+//! Combined check subcommand (`rustor check`)
+//!
+//! Runs refactoring rules, formatting fixers and static analysis in one pass
+//! over the files, with one report and one exit code. Each file is parsed
+//! once: rules, symbol collection and analyzer checks share its syntax tree,
+//! and fixers format the rule-fixed source.
+
+use anyhow::Result;
+use bumpalo::Bump;
+use colored::*;
+use mago_database::file::FileId;
+use rustor_analyze::{
+    config::{Level, PhpStanConfig},
+    issue::{Issue, IssueCollection, Severity},
+    symbols::SymbolTable,
+    Analyzer, FileSymbols,
+};
+use mago_syntax::ast::Program;
+use rustor_core::ParseError;
+use rustor_rules::{PhpVersion, Preset, RuleRegistry};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+
+use crate::analyze::{ignore_errors_baseline, load_phpstan_config, parse_level};
+use crate::baseline::{self, Baseline, BaselineMatcher};
+use crate::config::Config;
+use crate::fixer::FixerSetup;
+use crate::output::{EditInfo, OutputFormat, Reporter};
+use crate::plugin::PluginHost;
+use crate::process::{offset_to_line_column, process_program};
+
+/// Fixer configs used when `--fixer-config` is not given, in order
+pub(crate) const FIXER_CONFIG_FILES: &[&str] = &[".php-cs-fixer.php", ".php-cs-fixer.dist.php", "phpcs.xml", "phpcs.xml.dist"];
+
+/// Arguments for the check subcommand
+#[derive(Default, clap::Args)]
+#[command(after_help = "EXIT CODES:
+    0    Nothing found
+    1    Analysis errors, or files that could not be checked
+    2    Only rule, fixer or analysis warning findings
+
+EXAMPLES:
+    rustor check src/
+    rustor check src/ --format github
+    rustor check --level 5 --no-fixers
+    rustor check src/ --baseline .rustor-baseline.json")]
+pub struct CheckArgs {
+    /// Files or directories (default: [paths] include, or paths in phpstan.neon)
+    pub paths: Vec<PathBuf>,
+    /// Output format: text, json, diff, sarif, html, checkstyle, github
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<String>,
+    /// Only run this rule (repeatable, overrides config)
+    #[arg(long = "rule", short = 'r', value_name = "RULE")]
+    pub rules: Vec<String>,
+    /// Don't run refactoring rules
+    #[arg(long, conflicts_with = "rules")]
+    pub no_rules: bool,
+    /// Don't run formatting fixers
+    #[arg(long)]
+    pub no_fixers: bool,
+    /// Don't run static analysis
+    #[arg(long)]
+    pub no_analysis: bool,
+    /// rustor config (default: search for .rustor.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// PHPStan config (default: phpstan.neon or phpstan.neon.dist)
+    #[arg(long, short = 'c', value_name = "FILE")]
+    pub configuration: Option<PathBuf>,
+    /// Ignore config files
+    #[arg(long)]
+    pub no_config: bool,
+    /// Analysis level (0-10, max)
+    #[arg(long, short = 'l', value_name = "LEVEL", value_parser = parse_level)]
+    pub level: Option<u8>,
+    /// .php-cs-fixer.php or phpcs.xml (default: found in the current directory)
+    #[arg(long, value_name = "FILE")]
+    pub fixer_config: Option<PathBuf>,
+    /// Fixer preset without a fixer config (default: psr12)
+    #[arg(long, value_name = "PRESET")]
+    pub fixer_preset: Option<String>,
+    /// Run risky fixers
+    #[arg(long)]
+    pub allow_risky: bool,
+    /// Only report rule and fixer findings not in this baseline
+    #[arg(long, value_name = "FILE")]
+    pub baseline: Option<PathBuf>,
+    /// Write rule and fixer findings to a baseline file
+    #[arg(long, value_name = "FILE")]
+    pub generate_baseline: Option<PathBuf>,
+    /// Remove resolved entries from the --baseline file
+    #[arg(long, requires = "baseline", conflicts_with = "generate_baseline")]
+    pub update_baseline: bool,
+    /// Verbose output
+    #[arg(long, short = 'v')]
+    pub verbose: bool,
+}
+
+/// Findings in one file
+enum FileCheck {
+    Checked {
+        /// Rule and fixer findings
+        edits: Vec<EditInfo>,
+        /// Analysis issues
+        issues: Vec<Issue>,
+        old_source: String,
+        /// Source after rule fixes and formatting
+        new_source: String,
+        /// Parse errors; the stages only saw the statements without them
+        parse_errors: Vec<ParseError>,
+    },
+    Error(String),
+}
+
+/// A file after rules and fixers, with its syntax tree kept for analysis
+struct ParsedFile<'arena> {
+    source: String,
+    program: &'arena Program<'arena>,
+    edits: Vec<EditInfo>,
+    new_source: String,
+    parse_errors: Vec<ParseError>,
+}
+
+/// The rules, fixers and analyzer of a run, set up once
+struct Pipeline {
+    config: Config,
+    registry: RuleRegistry,
+    /// Enabled rules, `None` when rules are turned off
+    rules: Option<HashSet<String>>,
+    fixers: Option<FixerSetup>,
+    analyzer: Option<Analyzer>,
+    /// Plugins enabled in the config, run along with the rules
+    plugins: PluginHost,
+}
+
+/// Run the check subcommand
+pub fn run_check(args: CheckArgs) -> Result<ExitCode> {
+    let (config, config_path) = load_config(&args)?;
+
+    let format = args.format.as_deref().or(config.output.format.as_deref()).unwrap_or("text");
+    let format = OutputFormat::from_str(format).ok_or_else(|| {
+        anyhow::anyhow!(
+            "Invalid output format '{}'. Valid options: text, json, diff, sarif, html, checkstyle, github",
+            format
+        )
+    })?;
+    let verbose = args.verbose && format == OutputFormat::Text;
+    if verbose {
+        if let Some(path) = &config_path {
+            println!("{}: {}", "Using config".bold(), path.display());
+        }
+    }
+
+    let mut pipeline = Pipeline::new(&args, config, verbose)?;
+
+    // Paths: command line, then [paths] include, then the PHPStan config's
+    // paths, then the fixer config's Finder
+    let paths = if !args.paths.is_empty() {
+        args.paths.clone()
+    } else if !pipeline.config.paths.include.is_empty() {
+        pipeline.config.paths.include.iter().map(|p| pipeline.config.resolve(p)).collect()
+    } else if let Some(analyzer) = pipeline.analyzer.as_ref().filter(|a| !a.config().paths.is_empty()) {
+        analyzer.config().paths.clone()
+    } else {
+        pipeline.fixers.as_ref().map(|f| f.filter.include.clone()).unwrap_or_default()
+    };
+    if paths.is_empty() {
+        anyhow::bail!(
+            "No paths to check. Pass paths, or set [paths] include in .rustor.toml, paths in phpstan.neon or the fixer config's Finder"
+        );
+    }
+    for path in paths.iter().filter(|p| !p.exists()) {
+        eprintln!("{}: Path does not exist: {}", "Warning".yellow(), path.display());
+    }
+
+    let files = pipeline.files(&paths);
+    if verbose {
+        println!("{}: {}", "Files".bold(), files.len());
+        println!();
+    }
+    // Run project plugins once on all files before the per-file pass
+    pipeline.plugins.check_project(&files).map_err(anyhow::Error::msg)?;
+    let results = pipeline.check(&paths, files);
+
+    let loaded_baseline = args.baseline.as_deref().map(Baseline::load).transpose()?;
+    // [skip] applies to rules and plugins, not to fixers
+    let rules: HashSet<String> = pipeline.rules.iter().flatten().cloned().chain(pipeline.plugins.names()).collect();
+    let fixer_rules = pipeline.fixers.as_ref().map(|fixers| fixers.rule_names()).unwrap_or_default();
+    let mut matcher = loaded_baseline
+        .as_ref()
+        .map(|loaded| BaselineMatcher::new(loaded, rules.iter().chain(&fixer_rules).cloned().collect()));
+    let mut baseline_data: Vec<(String, Vec<EditInfo>, Option<String>)> = Vec::new();
+
+    // One report for all stages
+    let mut reporter = Reporter::new(format, args.verbose);
+    let mut reported: BTreeSet<String> = pipeline.rules.iter().flatten().cloned().collect();
+    let (mut fixable, mut analysis_errors, mut analysis_warnings) = (0, 0, 0);
+    for (path, result) in results {
+        match result {
+            FileCheck::Checked { mut edits, issues, old_source, new_source, parse_errors } => {
+                // Baselines cover rule and fixer findings; analysis has phpstan.neon baselines
+                let file = path.display().to_string();
+                if let Some(matcher) = matcher.as_mut() {
+                    matcher.skip_rules(&file, pipeline.config.rules_skipped_on(&path, &rules));
+                    edits = matcher.filter_edits(&file, edits, &old_source);
+                }
+                if args.generate_baseline.is_some() && !edits.is_empty() {
+                    baseline_data.push((file, edits.clone(), Some(old_source.clone())));
+                }
+                fixable += edits.len();
+                for issue in issues {
+                    match issue.severity {
+                        Severity::Error => analysis_errors += 1,
+                        Severity::Warning => analysis_warnings += 1,
+                    }
+                    edits.push(EditInfo {
+                        rule: issue.identifier.unwrap_or(issue.check_id),
+                        line: issue.line,
+                        column: issue.column,
+                        message: issue.message,
+                    });
+                }
+                edits.sort_by_key(|edit| (edit.line, edit.column));
+                reported.extend(edits.iter().map(|edit| edit.rule.clone()));
+                if !parse_errors.is_empty() {
+                    reporter.report_parse_errors(&path, &parse_errors);
+                }
+                reporter.report_check(&path, edits, &old_source, &new_source);
+            }
+            FileCheck::Error(error) => reporter.report_error(&path, &error),
+        }
+    }
+    reporter.set_enabled_rules(reported.into_iter().collect());
+
+    if let (Some(matcher), Some(baseline_path)) = (&matcher, &args.baseline) {
+        baseline::finish(matcher, baseline_path, args.update_baseline)?;
+    }
+    if let Some(baseline_path) = &args.generate_baseline {
+        let generated = Baseline::generate(&baseline_data);
+        generated.save(baseline_path)?;
+        eprintln!("Baseline with {} finding(s) written to {}", generated.len(), baseline_path.display());
+    }
+
+    let errors = reporter.summary().errors;
+    reporter.finish(false);
+    if format == OutputFormat::Text {
+        println!(
+            "  Fixable (rules and fixers): {}, analysis errors: {}, analysis warnings: {}",
+            fixable, analysis_errors, analysis_warnings
+        );
+    }
+
+    // 1: analysis errors or files that couldn't be checked, 2: anything else found
+    Ok(if errors > 0 || analysis_errors > 0 {
+        ExitCode::from(1)
+    } else if fixable > 0 || analysis_warnings > 0 {
+        ExitCode::from(2)
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn load_config(args: &CheckArgs) -> Result<(Config, Option<PathBuf>)> {
+    if args.no_config {
+        return Ok((Config::default(), None));
+    }
+    match &args.config {
+        Some(path) => Ok((Config::load_path(path)?, Some(path.clone()))),
+        None => Ok(Config::load()?.map(|(config, path)| (config, Some(path))).unwrap_or_default()),
+    }
+}
+
+impl Pipeline {
+    fn new(args: &CheckArgs, config: Config, verbose: bool) -> Result<Self> {
+        let registry = RuleRegistry::new_with_config(&config.rules.to_rule_configs());
+
+        let rules = if args.no_rules {
+            None
+        } else {
+            Some(enabled_rules(&registry, &config, &args.rules)?)
+        };
+        let plugins = if rules.is_some() {
+            crate::plugin::start_enabled(&config, None).map_err(anyhow::Error::msg)?
+        } else {
+            PluginHost::default()
+        };
+
+        let fixers = if args.no_fixers {
+            None
+        } else {
+            // --fixer-config, then [fixer] config, then a config in the current directory
+            let fixer_config = args.fixer_config.clone().or_else(|| {
+                if args.no_config {
+                    return None;
+                }
+                let configured = config.fixer.config.as_deref().map(|p| config.resolve(p));
+                configured.or_else(|| FIXER_CONFIG_FILES.iter().map(PathBuf::from).find(|path| path.is_file()))
+            });
+            if verbose {
+                if let Some(path) = &fixer_config {
+                    println!("{}: {}", "Using fixer config".bold(), path.display());
+                }
+            }
+            let setup = FixerSetup::load(
+                fixer_config.as_deref(),
+                args.fixer_preset.as_deref(),
+                args.allow_risky,
+                &config.fixer,
+            )?;
+            setup.warn_skipped_risky();
+            Some(setup)
+        };
+
+        let analyzer = if args.no_analysis {
+            None
+        } else {
+            let phpstan_config = if args.no_config {
+                PhpStanConfig::default()
+            } else {
+                load_phpstan_config(args.configuration.as_deref(), &config, verbose)?
+            };
+
+            let mut analyzer = Analyzer::new(phpstan_config);
+            if let Some(level) = args.level {
+                analyzer.set_level(Level::from_u8(level));
+            }
+            if verbose {
+                println!("{}: {}", "Analysis level".bold(), analyzer.config().level);
+            }
+            Some(analyzer)
+        };
+
+        if rules.is_none() && fixers.is_none() && analyzer.is_none() {
+            anyhow::bail!("Nothing to check: rules, fixers and analysis are all turned off");
+        }
+
+        Ok(Self { config, registry, rules, fixers, analyzer, plugins })
+    }
+
+    /// PHP files under `paths`, minus the files either config excludes
+    fn files(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_file() {
+                files.push(path.clone());
+            } else if path.is_dir() {
+                for entry in walkdir::WalkDir::new(path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.path().extension().is_some_and(|ext| ext == "php"))
+                {
+                    let file = entry.path();
+                    let excluded = self.config.should_exclude(file)
+                        || self.fixers.as_ref().is_some_and(|f| f.filter.is_excluded(file))
+                        || self.analyzer.as_ref().is_some_and(|a| a.config().is_excluded(file));
+                    if !excluded {
+                        files.push(file.to_path_buf());
+                    }
+                }
+            }
+        }
+        files.sort();
+        files.dedup();
+        files
+    }
+
+    /// Check all files, sorted by path
+    ///
+    /// Syntax trees can't move between threads, so each worker thread parses
+    /// its share of the files, keeps their trees while the symbols of all
+    /// files are collected, and then analyzes them.
+    fn check(&self, paths: &[PathBuf], files: Vec<PathBuf>) -> Vec<(PathBuf, FileCheck)> {
+        let chunk_size = files.len().div_ceil(rayon::current_num_threads()).max(1);
+        let mut results: Vec<(PathBuf, FileCheck)> = std::thread::scope(|scope| {
+            let (symbols_tx, symbols_rx) = mpsc::channel();
+            let workers: Vec<_> = files
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let symbols_tx = symbols_tx.clone();
+                    let (table_tx, table_rx) = mpsc::channel();
+                    (scope.spawn(move || self.check_files(chunk, symbols_tx, table_rx)), table_tx)
+                })
+                .collect();
+            drop(symbols_tx);
+
+            // Cross-file analysis needs the symbols of every file
+            let file_symbols: Vec<FileSymbols> = symbols_rx.iter().flatten().collect();
+            let symbol_table = self.analyzer.as_ref().map(|analyzer| {
+                let paths: Vec<&Path> = paths.iter().map(|p| p.as_path()).collect();
+                Arc::new(analyzer.symbol_table_from_files(&paths, file_symbols))
+            });
+            for (_, table_tx) in &workers {
+                let _ = table_tx.send(symbol_table.clone());
+            }
+
+            workers
+                .into_iter()
+                .flat_map(|(worker, _)| worker.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+                .collect()
+        });
+
+        // ignoreErrors from the PHPStan config's includes, with their counts
+        if let Some(analyzer) = self.analyzer.as_ref().filter(|a| !a.config().ignore_errors.is_empty()) {
+            let mut issues = IssueCollection::new();
+            for (_, result) in &mut results {
+                if let FileCheck::Checked { issues: file_issues, .. } = result {
+                    issues.extend(std::mem::take(file_issues));
+                }
+            }
+
+            let mut by_file: BTreeMap<PathBuf, Vec<Issue>> = BTreeMap::new();
+            for issue in ignore_errors_baseline(analyzer.config()).filter_with_options(issues, false).into_issues() {
+                by_file.entry(issue.file.clone()).or_default().push(issue);
+            }
+            for (path, result) in &mut results {
+                if let FileCheck::Checked { issues, .. } = result {
+                    *issues = by_file.remove(path).unwrap_or_default();
+                }
+            }
+        }
+
+        results
+    }
+
+    /// Check files on one worker thread
+    ///
+    /// Sends the symbols of the files to `symbols`, then waits for the symbol
+    /// table on `symbol_table` to analyze them.
+    fn check_files(
+        &self,
+        files: &[PathBuf],
+        symbols: Sender<Vec<FileSymbols>>,
+        symbol_table: Receiver<Option<Arc<SymbolTable>>>,
+    ) -> Vec<(PathBuf, FileCheck)> {
+        let arenas: Vec<Bump> = files.iter().map(|_| Bump::new()).collect();
+        let parsed: Vec<Result<ParsedFile<'_>, String>> =
+            files.iter().zip(&arenas).map(|(path, arena)| self.check_file(path, arena)).collect();
+
+        let file_symbols = match &self.analyzer {
+            Some(analyzer) => files
+                .iter()
+                .zip(&parsed)
+                .filter_map(|(path, file)| {
+                    let file = file.as_ref().ok()?;
+                    Some(analyzer.file_symbols(path, &file.source, file.program))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let _ = symbols.send(file_symbols);
+        drop(symbols);
+        let symbol_table = symbol_table.recv().ok().flatten();
+
+        files
+            .iter()
+            .zip(parsed)
+            .map(|(path, file)| {
+                let result = match file {
+                    Ok(file) => {
+                        let issues = match (&self.analyzer, &symbol_table) {
+                            (Some(analyzer), Some(symbol_table)) => analyzer
+                                .analyze_program(path, &file.source, file.program, symbol_table)
+                                .into_issues(),
+                            _ => Vec::new(),
+                        };
+                        FileCheck::Checked {
+                            edits: file.edits,
+                            issues,
+                            old_source: file.source,
+                            new_source: file.new_source,
+                            parse_errors: file.parse_errors,
+                        }
+                    }
+                    Err(error) => FileCheck::Error(error),
+                };
+                (path.clone(), result)
+            })
+            .collect()
+    }
+
+    /// Parse a file and run the rules and fixers on it
+    fn check_file<'arena>(&self, path: &Path, arena: &'arena Bump) -> Result<ParsedFile<'arena>, String> {
+        let source = std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+
+        let file_id = FileId::new(path.to_string_lossy().as_ref());
+        let parsed = rustor_core::parse(arena, file_id, &source);
+        let program = parsed.program;
+
+        let mut edits = Vec::new();
+        let mut new_source = source.clone();
+
+        if let Some(rules) = &self.rules {
+            if !self.config.should_skip_all_rules(path) {
+                let skip_rules = self.config.skipped_rules_for_path(path);
+                let result = process_program(path, source.clone(), program, rules, &self.registry, &skip_rules, &self.plugins)
+                    .map_err(|e| format!("{:#}", e))?;
+                edits = result.edits;
+                new_source = result.new_source.unwrap_or(new_source);
+            }
+        }
+
+        // Formatting goes on top of the rule fixes, so fixer findings are at
+        // their place in the rule-fixed source
+        if let Some(fixers) = &self.fixers {
+            let (formatted, fixer_edits) = fixers.check(&new_source, &fixers.file_config(path));
+            edits.extend(fixer_edits.iter().map(|edit| {
+                let (line, column) = offset_to_line_column(&new_source, edit.start_offset());
+                EditInfo {
+                    rule: edit.rule.clone().unwrap_or_else(|| "fixer".to_string()),
+                    line,
+                    column,
+                    message: edit.message.clone(),
+                }
+            }));
+            new_source = formatted;
+        }
+
+        Ok(ParsedFile { source, program, edits, new_source, parse_errors: parsed.errors })
+    }
+}
+
+/// Rules to run: `--rule`, else the config's preset minus disabled rules,
+/// else its enabled rules; filtered by the config's PHP version
+fn enabled_rules(registry: &RuleRegistry, config: &Config, cli_rules: &[String]) -> Result<HashSet<String>> {
+    let all_rules = registry.all_names();
+    for rule in cli_rules {
+        if !all_rules.contains(&rule.as_str()) {
+            anyhow::bail!("Unknown rule '{}'. Use rustor --list-rules to see available rules", rule);
+        }
+    }
+
+    let rules: HashSet<String> = match &config.rules.preset {
+        Some(preset) if cli_rules.is_empty() => {
+            let preset: Preset = preset.parse().map_err(anyhow::Error::msg)?;
+            registry
+                .get_preset_rules(preset)
+                .into_iter()
+                .filter(|r| !config.rules.disabled.contains(r))
+                .collect()
+        }
+        _ => config.effective_rules(&all_rules, cli_rules),
+    };
+
+    let Some(version) = &config.php.version else {
+        return Ok(rules);
+    };
+    let version: PhpVersion = version.parse().map_err(anyhow::Error::msg)?;
+    let infos = registry.list_rules_full();
+    Ok(rules
+        .into_iter()
+        .filter(|name| {
+            infos
+                .iter()
+                .find(|r| r.name == *name)
+                .and_then(|r| r.min_php_version)
+                .is_none_or(|min| min <= version)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn parse_check_args(list: &[&str]) -> Result<CheckArgs, clap::Error> {
+        use clap::Parser;
+        match crate::Cli::try_parse_from(["rustor", "check"].iter().chain(list))?.command {
+            Some(crate::Command::Check(args)) => Ok(args),
+            _ => panic!("not parsed as check"),
+        }
+    }
+
+    #[test]
+    fn test_parse_check_args() {
+        let parsed = parse_check_args(&["src", "-r", "sizeof", "--level=max", "--no-fixers", "--format", "json"]).unwrap();
+        assert_eq!(parsed.paths, vec![PathBuf::from("src")]);
+        assert_eq!(parsed.rules, vec!["sizeof"]);
+        assert_eq!(parsed.level, Some(10));
+        assert!(parsed.no_fixers);
+        assert_eq!(parsed.format.as_deref(), Some("json"));
+
+        assert!(parse_check_args(&["--level", "11"]).is_err());
+        assert!(parse_check_args(&["--format"]).is_err());
+        assert!(parse_check_args(&["--no-rules", "--rule", "sizeof"]).is_err());
+        assert!(parse_check_args(&["--update-baseline"]).is_err());
+        assert!(parse_check_args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_all_stages_in_one_pass() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.php");
+        fs::write(&file, "<?php\n$x = [];\n$a = sizeof($x);   \nundefined_function();\n").unwrap();
+
+        let args = CheckArgs {
+            rules: vec!["sizeof".to_string()],
+            no_config: true,
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(&args, Config::default(), false).unwrap();
+        let paths = vec![temp.path().to_path_buf()];
+        let files = pipeline.files(&paths);
+        assert_eq!(files, vec![file.clone()]);
+
+        let mut results = pipeline.check(&paths, files);
+        let (path, FileCheck::Checked { edits, issues, new_source, .. }) = results.remove(0) else {
+            panic!("file was not checked");
+        };
+        assert_eq!(path, file);
+
+        // A rule finding and a formatting finding on line 3, an analysis issue on line 4
+        let rules: Vec<&str> = edits.iter().map(|e| e.rule.as_str()).collect();
+        assert!(rules.contains(&"sizeof"));
+        assert!(rules.contains(&"no_trailing_whitespace"));
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 4);
+        assert!(new_source.contains("$a = count($x);\n"));
+    }
+
+    #[test]
+    fn test_symbols_from_other_files() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("a.php"), "<?php\nfunction helper(): void {}\n").unwrap();
+        fs::write(temp.path().join("b.php"), "<?php\nhelper();\nmissing();\n").unwrap();
+
+        let args = CheckArgs { no_rules: true, no_fixers: true, no_config: true, ..Default::default() };
+        let pipeline = Pipeline::new(&args, Config::default(), false).unwrap();
+        let paths = vec![temp.path().to_path_buf()];
+        let files = pipeline.files(&paths);
+        let results = pipeline.check(&paths, files);
+        assert_eq!(results.len(), 2);
+
+        // helper() is declared in a.php, whichever worker checks it
+        let FileCheck::Checked { issues, .. } = &results[1].1 else {
+            panic!("file was not checked");
+        };
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("missing"));
+    }
+
+    #[test]
+    fn test_parse_errors_reported() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("broken.php");
+        fs::write(&file, "<?php\n$a = ;\n$b = sizeof([]);\n").unwrap();
+
+        let args = CheckArgs {
+            rules: vec!["sizeof".to_string()],
+            no_fixers: true,
+            no_config: true,
+            ..Default::default()
+        };
+        let pipeline = Pipeline::new(&args, Config::default(), false).unwrap();
+        let mut results = pipeline.check(std::slice::from_ref(&file), vec![file.clone()]);
+        let (_, FileCheck::Checked { edits, parse_errors, .. }) = results.remove(0) else {
+            panic!("file was not checked");
+        };
+
+        // The error is reported where it is and the rules still ran on the next statement
+        assert_eq!(parse_errors.len(), 1);
+        assert_eq!((parse_errors[0].line, parse_errors[0].column), (2, 6));
+        assert!(edits.iter().any(|e| e.rule == "sizeof" && e.line == 3));
+    }
+
+    #[test]
+    fn test_nothing_to_check() {
+        let args = CheckArgs { no_rules: true, no_fixers: true, no_analysis: true, ..Default::default() };
+        assert!(Pipeline::new(&args, Config::default(), false).is_err());
+    }
+}
@@ -3,8 +3,10 @@
 //! Loads `.rustor.toml` from current directory or parent directories.
 
 use anyhow::{Context, Result};
+use rustor_analyze::config::{Level, PhpStanConfig};
+use rustor_core::paths::matches_any;
 use serde::Deserialize;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 /// Configuration file structure
@@ -16,9 +18,16 @@ pub struct Config {
     pub output: OutputConfig,
     pub php: PhpConfig,
     pub fix: FixConfig,
+    pub fmt: FmtConfig,
+    pub plugins: PluginsConfig,
+    pub analyze: AnalyzeConfig,
+    pub fixer: FixerSection,
     /// Skip rules for specific paths (Rector-style)
     #[serde(default)]
     pub skip: HashMap<String, Vec<String>>,
+    /// Directory of the config file, which relative paths in it are relative to
+    #[serde(skip)]
+    pub dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -116,6 +125,79 @@ pub struct FixConfig {
     pub backup_dir: Option<String>,
 }
 
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FmtConfig {
+    /// Maximum line width for `rustor fmt` (default: 120)
+    pub line_width: Option<usize>,
+    /// Spaces per indentation level (default: 4)
+    pub indent_size: Option<usize>,
+    /// Indent with tabs instead of spaces (default: false)
+    pub use_tabs: Option<bool>,
+    /// Line ending: "lf" or "crlf" (default: "lf")
+    pub line_ending: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct PluginsConfig {
+    /// Directory to discover plugins in (default: ~/.rustor/plugins)
+    pub dir: Option<String>,
+    /// Plugins to run, by name
+    pub enabled: Vec<String>,
+    /// Seconds to wait for a plugin response before killing it (default: 30)
+    pub timeout: Option<u64>,
+}
+
+/// `[analyze]`: static analysis settings, on top of or instead of a phpstan.neon
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct AnalyzeConfig {
+    /// PHPStan config to load (default: phpstan.neon or phpstan.neon.dist)
+    pub config: Option<String>,
+    /// Analysis level, 0-10 or "max" (overrides the PHPStan config's level)
+    pub level: Option<toml::Value>,
+    /// Paths to analyze (override the PHPStan config's paths)
+    pub paths: Vec<String>,
+    /// Patterns to exclude, in addition to the PHPStan config's excludePaths
+    pub exclude: Vec<String>,
+    /// Baseline used when --baseline is not given
+    pub baseline: Option<String>,
+}
+
+impl AnalyzeConfig {
+    /// The configured analysis level
+    pub fn level(&self) -> Result<Option<Level>> {
+        let level = match &self.level {
+            None => return Ok(None),
+            Some(toml::Value::Integer(n)) => u8::try_from(*n).ok().filter(|n| *n <= 10).map(Level::from_u8),
+            Some(toml::Value::String(s)) => Level::from_str(s),
+            Some(_) => None,
+        };
+        match level {
+            Some(level) => Ok(Some(level)),
+            None => anyhow::bail!("Invalid [analyze] level: expected 0-10 or \"max\""),
+        }
+    }
+}
+
+/// `[fixer]`: formatting fixer settings, on top of or instead of a PHP-CS-Fixer config
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FixerSection {
+    /// PHP-CS-Fixer config or PHP_CodeSniffer ruleset to load
+    pub config: Option<String>,
+    /// Preset used when no fixers are configured (default: psr12)
+    pub preset: Option<String>,
+    /// Fixers to turn on (`true` or a table of options) or off (`false`),
+    /// on top of the fixer config's rules or the preset
+    pub rules: BTreeMap<String, toml::Value>,
+    /// Run risky fixers
+    pub risky: Option<bool>,
+    /// Patterns to exclude, in addition to the fixer config's Finder
+    pub exclude: Vec<String>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(default)]
 pub struct OutputConfig {
@@ -140,8 +222,9 @@ impl Config {
             if config_path.exists() {
                 let contents = std::fs::read_to_string(&config_path)
                     .with_context(|| format!("Failed to read {}", config_path.display()))?;
-                let config: Config = toml::from_str(&contents)
+                let mut config: Config = toml::from_str(&contents)
                     .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+                config.dir = Some(dir.to_path_buf());
                 return Ok(Some((config, config_path)));
             }
             current = dir.parent();
@@ -154,11 +237,32 @@ impl Config {
     pub fn load_path(path: &Path) -> Result<Config> {
         let contents = std::fs::read_to_string(path)
             .with_context(|| format!("Failed to read {}", path.display()))?;
-        let config: Config = toml::from_str(&contents)
+        let mut config: Config = toml::from_str(&contents)
             .with_context(|| format!("Failed to parse {}", path.display()))?;
+        config.dir = path.parent().map(Path::to_path_buf);
         Ok(config)
     }
 
+    /// A path from the config, relative to the config file's directory
+    pub fn resolve(&self, path: &str) -> PathBuf {
+        match &self.dir {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        }
+    }
+
+    /// Apply the `[analyze]` section to a PHPStan config
+    pub fn apply_analyze(&self, phpstan: &mut PhpStanConfig) -> Result<()> {
+        if let Some(level) = self.analyze.level()? {
+            phpstan.level = level;
+        }
+        if !self.analyze.paths.is_empty() {
+            phpstan.paths = self.analyze.paths.iter().map(|p| self.resolve(p)).collect();
+        }
+        phpstan.exclude_paths.extend(self.analyze.exclude.iter().map(PathBuf::from));
+        Ok(())
+    }
+
     /// Compute the effective set of enabled rules
     pub fn effective_rules(&self, all_rules: &[&str], cli_rules: &[String]) -> HashSet<String> {
         // CLI rules override config completely
@@ -182,51 +286,22 @@ impl Config {
 
     /// Check if a path should be excluded based on config patterns
     pub fn should_exclude(&self, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-
-        for pattern in &self.paths.exclude {
-            // Try glob matching
-            if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-                if glob_pattern.matches(&path_str) {
-                    return true;
-                }
-                // Also try matching against just the file/dir name
-                if let Some(file_name) = path.file_name() {
-                    if glob_pattern.matches(&file_name.to_string_lossy()) {
-                        return true;
-                    }
-                }
-            }
-
-            // Also do simple prefix/contains matching for directory patterns
-            if pattern.ends_with('/') {
-                let dir_pattern = pattern.trim_end_matches('/');
-                if path_str.contains(&format!("/{}/", dir_pattern))
-                    || path_str.starts_with(&format!("{}/", dir_pattern))
-                {
-                    return true;
-                }
-            }
-        }
-
-        false
+        matches_any(&self.paths.exclude, path)
     }
 
     /// Check if a specific rule should be skipped for a given path
     /// Returns true if the rule should be skipped based on [skip] config
     pub fn should_skip_rule(&self, rule_name: &str, path: &Path) -> bool {
-        let path_str = path.to_string_lossy();
-
         // Check rule-specific skip patterns
         if let Some(patterns) = self.skip.get(rule_name) {
-            if Self::matches_any_pattern(&path_str, path, patterns) {
+            if matches_any(patterns, path) {
                 return true;
             }
         }
 
         // Check wildcard skip patterns (applies to all rules)
         if let Some(patterns) = self.skip.get("*") {
-            if Self::matches_any_pattern(&path_str, path, patterns) {
+            if matches_any(patterns, path) {
                 return true;
             }
         }
@@ -236,14 +311,13 @@ impl Config {
 
     /// Get list of rules to skip for a given path
     pub fn skipped_rules_for_path(&self, path: &Path) -> HashSet<String> {
-        let path_str = path.to_string_lossy();
         let mut skipped = HashSet::new();
 
         for (rule_name, patterns) in &self.skip {
             if rule_name == "*" {
                 continue; // Wildcard handled separately
             }
-            if Self::matches_any_pattern(&path_str, path, patterns) {
+            if matches_any(patterns, path) {
                 skipped.insert(rule_name.clone());
             }
         }
@@ -254,47 +328,17 @@ impl Config {
     /// Check if all rules should be skipped for this path (wildcard match)
     pub fn should_skip_all_rules(&self, path: &Path) -> bool {
         if let Some(patterns) = self.skip.get("*") {
-            let path_str = path.to_string_lossy();
-            return Self::matches_any_pattern(&path_str, path, patterns);
+            return matches_any(patterns, path);
         }
         false
     }
 
-    /// Helper to check if a path matches any of the given patterns
-    fn matches_any_pattern(path_str: &str, path: &Path, patterns: &[String]) -> bool {
-        for pattern in patterns {
-            // Try glob matching
-            if let Ok(glob_pattern) = glob::Pattern::new(pattern) {
-                if glob_pattern.matches(path_str) {
-                    return true;
-                }
-                // Also try matching against just the file/dir name
-                if let Some(file_name) = path.file_name() {
-                    if glob_pattern.matches(&file_name.to_string_lossy()) {
-                        return true;
-                    }
-                }
-            }
-
-            // Also do simple prefix/contains matching for directory patterns
-            if pattern.ends_with('/') {
-                let dir_pattern = pattern.trim_end_matches('/');
-                if path_str.contains(&format!("/{}/", dir_pattern))
-                    || path_str.starts_with(&format!("{}/", dir_pattern))
-                {
-                    return true;
-                }
-            }
-
-            // Simple contains matching for patterns like "*/Fixtures/*"
-            if pattern.contains('/') && !pattern.starts_with('/') {
-                let simple_pattern = pattern.replace('*', "");
-                if path_str.contains(&simple_pattern) {
-                    return true;
-                }
-            }
+    /// Rules of `rules` that are skipped for a given path
+    pub fn rules_skipped_on(&self, path: &Path, rules: &HashSet<String>) -> HashSet<String> {
+        if self.should_skip_all_rules(path) {
+            return rules.clone();
         }
-        false
+        self.skipped_rules_for_path(path).into_iter().filter(|rule| rules.contains(rule)).collect()
     }
 }
 
@@ -603,6 +647,10 @@ sizeof = ["vendor/*"]
         assert!(config.should_skip_all_rules(Path::new("generated/models/User.php")));
         assert!(config.should_skip_all_rules(Path::new("src/Model.generated.php")));
         assert!(!config.should_skip_all_rules(Path::new("src/Model.php")));
+
+        let rules: HashSet<String> = ["sizeof".to_string()].into();
+        assert_eq!(config.rules_skipped_on(Path::new("generated/User.php"), &rules), rules);
+        assert!(config.rules_skipped_on(Path::new("src/Model.php"), &rules).is_empty());
     }
 
     #[test]
@@ -648,4 +696,94 @@ rename_class = ["**/*.test.php", "tests/**"]
         assert!(config.should_skip_rule("rename_class", Path::new("tests/Unit/ServiceTest.php")));
         assert!(!config.should_skip_rule("rename_class", Path::new("src/Service.php")));
     }
+
+    #[test]
+    fn test_plugins_config() {
+        let temp = TempDir::new().unwrap();
+        create_config(
+            temp.path(),
+            r#"
+[plugins]
+dir = "tools/rustor-plugins"
+enabled = ["no-debug", "naming"]
+timeout = 5
+"#,
+        );
+
+        let (config, _) = Config::load_from(temp.path().to_path_buf())
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(config.plugins.dir.as_deref(), Some("tools/rustor-plugins"));
+        assert_eq!(config.plugins.enabled, vec!["no-debug", "naming"]);
+        assert_eq!(config.plugins.timeout, Some(5));
+    }
+
+    #[test]
+    fn test_analyze_config() {
+        let temp = TempDir::new().unwrap();
+        create_config(
+            temp.path(),
+            r#"
+[analyze]
+level = "max"
+paths = ["src"]
+exclude = ["src/Legacy/"]
+baseline = "phpstan-baseline.neon"
+"#,
+        );
+
+        let (config, _) = Config::load_from(temp.path().to_path_buf())
+            .unwrap()
+            .unwrap();
+
+        let mut phpstan = PhpStanConfig::default();
+        config.apply_analyze(&mut phpstan).unwrap();
+        assert_eq!(phpstan.level, Level::Level10);
+        assert_eq!(phpstan.paths, vec![temp.path().join("src")]);
+        assert!(phpstan.is_excluded(&temp.path().join("src/Legacy/Old.php")));
+        assert!(!phpstan.is_excluded(&temp.path().join("src/New.php")));
+        assert_eq!(
+            config.resolve(config.analyze.baseline.as_deref().unwrap()),
+            temp.path().join("phpstan-baseline.neon")
+        );
+    }
+
+    #[test]
+    fn test_analyze_level() {
+        let level = |value: &str| {
+            let config: Config = toml::from_str(&format!("[analyze]\nlevel = {}", value)).unwrap();
+            config.analyze.level()
+        };
+        assert_eq!(level("6").unwrap(), Some(Level::Level6));
+        assert_eq!(level("\"max\"").unwrap(), Some(Level::Level10));
+        assert!(level("11").is_err());
+        assert!(level("\"strict\"").is_err());
+    }
+
+    #[test]
+    fn test_fixer_config() {
+        let config: Config = toml::from_str(
+            r#"
+[fixer]
+config = ".php-cs-fixer.dist.php"
+risky = true
+exclude = ["var/"]
+
+[fixer.rules]
+no_unused_imports = true
+single_quote = false
+
+[fixer.rules.concat_space]
+spacing = "one"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.fixer.config.as_deref(), Some(".php-cs-fixer.dist.php"));
+        assert_eq!(config.fixer.rules.len(), 3);
+        assert_eq!(config.fixer.rules["single_quote"], toml::Value::Boolean(false));
+        assert_eq!(config.fixer.risky, Some(true));
+        assert_eq!(config.fixer.exclude, vec!["var/"]);
+    }
 }
@@ -1,20 +1,197 @@
 //! PHP-CS-Fixer integration for rustor CLI
 //!
-//! Provides formatting fixers compatible with PHP-CS-Fixer configuration.
+//! Provides formatting fixers compatible with PHP-CS-Fixer configuration
+//! and PHP_CodeSniffer rulesets.
 
-use std::path::Path;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use anyhow::Result;
 use colored::*;
-use rustor_core::apply_edits;
+use rayon::prelude::*;
+use rustor_core::{apply_edits, PathFilter};
 use rustor_fixer::{
     PhpCsFixerConfig, FixerRegistry, FixerConfig,
-    config::{IndentStyle, LineEnding},
+    config::{is_phpcs_ruleset, FinderConfig, EditorConfigResolver, IndentStyle, LineEnding, PhpcsRuleset},
 };
 
-/// Load fixer configuration from a .php-cs-fixer.php file
+use crate::config::FixerSection;
+use crate::watch::{Finding, WatchTarget};
+
+/// Load fixer configuration from a .php-cs-fixer.php file or a
+/// PHP_CodeSniffer ruleset (phpcs.xml)
+///
+/// Sniffs of a ruleset that have no equivalent fixer are listed on stderr.
 pub fn load_fixer_config(path: &Path) -> Result<PhpCsFixerConfig> {
-    PhpCsFixerConfig::from_file(path)
-        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))
+    if !is_phpcs_ruleset(path) {
+        return PhpCsFixerConfig::from_file(path)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e));
+    }
+
+    let ruleset = PhpcsRuleset::from_file(path)
+        .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", path.display(), e))?;
+    let import = ruleset.to_fixer_config();
+    if !import.unmapped.is_empty() {
+        eprintln!(
+            "{}: {}: no equivalent fixer for {} sniff(s), they are ignored:",
+            "Warning".yellow(),
+            path.display(),
+            import.unmapped.len()
+        );
+        for name in &import.unmapped {
+            eprintln!("  {}", name);
+        }
+    }
+    Ok(import.config)
+}
+
+/// Fixers to run and their configuration, from a fixer config file or a preset
+pub struct FixerSetup {
+    pub registry: FixerRegistry,
+    pub config: FixerConfig,
+    /// Fixers enabled in the config file; `None` runs the preset
+    pub rules: Option<Vec<String>>,
+    pub preset: String,
+    /// Default paths and exclusions from the fixer config's Finder
+    pub filter: PathFilter,
+    php_config: Option<PhpCsFixerConfig>,
+    editorconfig: EditorConfigResolver,
+}
+
+impl FixerSetup {
+    /// Load a `.php-cs-fixer.php` file or PHP_CodeSniffer ruleset, or use
+    /// `preset` (default: psr12) without one
+    ///
+    /// The `[fixer]` section of `.rustor.toml` fills in `preset` and
+    /// `allow_risky`, `[fixer.rules]` turns fixers on or off on top of the
+    /// config's rules or the preset, and its exclusions add to the Finder's.
+    pub fn load(config_path: Option<&Path>, preset: Option<&str>, allow_risky: bool, section: &FixerSection) -> Result<Self> {
+        let registry = FixerRegistry::new();
+
+        let (mut config, rules, php_config) = if let Some(config_path) = config_path {
+            let php_config = load_fixer_config(config_path)?;
+            for warning in &php_config.warnings {
+                eprintln!("{}: {}: {}", "Warning".yellow(), config_path.display(), warning);
+            }
+            let config = config_from_php_cs_fixer(&php_config);
+
+            let errors = registry.validate_options(&config.rule_options);
+            if !errors.is_empty() {
+                let messages: Vec<String> = errors.iter().map(|e| format!("  {}", e)).collect();
+                anyhow::bail!("Invalid fixer configuration in {}:\n{}", config_path.display(), messages.join("\n"));
+            }
+
+            // Get enabled rules from the PHP config
+            let rules: Vec<String> = php_config
+                .rules
+                .iter()
+                .filter(|(_, rule)| rule.enabled)
+                .map(|(name, _)| name.clone())
+                .collect();
+            (config, if rules.is_empty() { None } else { Some(rules) }, Some(php_config))
+        } else {
+            (FixerConfig {
+                line_ending: LineEnding::Lf,
+                ..Default::default()
+            }, None, None)
+        };
+        config.risky_allowed |= allow_risky || section.risky == Some(true);
+
+        let mut filter = match (&php_config, config_path) {
+            (Some(php_config), Some(config_path)) => {
+                finder_filter(&php_config.finder, config_path.parent().unwrap_or(Path::new("")))
+            }
+            _ => PathFilter::default(),
+        };
+        filter.extend(PathFilter::new(Vec::new(), section.exclude.clone()));
+
+        let rules = if section.rules.is_empty() {
+            rules
+        } else {
+            let base = rules.unwrap_or_else(|| {
+                let preset = preset.or(section.preset.as_deref()).unwrap_or("psr12");
+                registry.get_preset_fixers(preset).iter().map(|name| name.to_string()).collect()
+            });
+            let rules = apply_section_rules(&registry, base, section, &mut config)?;
+            let errors = registry.validate_options(&config.rule_options);
+            if !errors.is_empty() {
+                let messages: Vec<String> = errors.iter().map(|e| format!("  {}", e)).collect();
+                anyhow::bail!("Invalid fixer configuration in [fixer.rules]:\n{}", messages.join("\n"));
+            }
+            Some(rules)
+        };
+
+        Ok(Self {
+            registry,
+            config,
+            rules,
+            preset: preset.or(section.preset.as_deref()).unwrap_or("psr12").to_string(),
+            filter,
+            php_config,
+            editorconfig: EditorConfigResolver::new(),
+        })
+    }
+
+    /// Warn about requested risky fixers, which only run when explicitly
+    /// allowed, like in PHP-CS-Fixer
+    pub fn warn_skipped_risky(&self) {
+        let requested: Vec<&str> = match &self.rules {
+            Some(rules) => rules.iter().map(|s| s.as_str()).collect(),
+            None => self.registry.get_preset_fixers(&self.preset),
+        };
+        let skipped = self.registry.skipped_risky(&requested, &self.config);
+        if !skipped.is_empty() {
+            eprintln!(
+                "{}: Skipping risky rules (enable with setRiskyAllowed(true) or --allow-risky): {}",
+                "Warning".yellow(),
+                skipped.join(", ")
+            );
+        }
+    }
+
+    /// Rules the fixers report their findings under: the PHP-CS-Fixer names
+    /// of the fixers that run, and "fixer" for edits without a rule
+    pub fn rule_names(&self) -> HashSet<String> {
+        let requested: Vec<&str> = match &self.rules {
+            Some(rules) => rules.iter().map(|s| s.as_str()).collect(),
+            None => self.registry.get_preset_fixers(&self.preset),
+        };
+        let mut names: HashSet<String> = self
+            .registry
+            .active(&requested, &self.config)
+            .into_iter()
+            .map(|name| name.to_string())
+            .collect();
+        names.insert("fixer".to_string());
+        names
+    }
+
+    /// Config for a file, with its .editorconfig settings applied unless the
+    /// fixer config sets them
+    pub fn file_config(&self, path: &Path) -> Cow<'_, FixerConfig> {
+        let mut file_config = Cow::Borrowed(&self.config);
+        let settings = self.editorconfig.resolve(path);
+        if !settings.is_empty() {
+            settings.apply(file_config.to_mut(), self.php_config.as_ref());
+        }
+        file_config
+    }
+
+    /// Run the enabled fixers, or the preset, on a source
+    ///
+    /// Only edits whose effect shows in the fixed source are returned, so
+    /// fixers undoing each other's changes report nothing.
+    pub fn check(&self, source: &str, config: &FixerConfig) -> (String, Vec<rustor_core::Edit>) {
+        let (fixed, edits) = match &self.rules {
+            Some(rules) => {
+                let rule_refs: Vec<&str> = rules.iter().map(|s| s.as_str()).collect();
+                self.registry.check(source, &rule_refs, config)
+            }
+            None => self.registry.check_preset(source, &self.preset, config),
+        };
+        let edits = net_edits(source, &fixed, edits);
+        (fixed, edits)
+    }
 }
 
 /// Run fixers on a single file
@@ -53,6 +230,109 @@ pub fn run_fixers_with_preset(
     Ok((fixed, edits))
 }
 
+/// Lines of the original file changed by one fixer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedFixer {
+    pub name: String,
+    /// Inclusive, 1-based line ranges, sorted and merged
+    pub lines: Vec<(usize, usize)>,
+}
+
+/// 1-based lines of the original source spanned by an edit
+pub fn edit_lines(source: &str, edit: &rustor_core::Edit) -> (usize, usize) {
+    let line_at = |offset: usize| source[..offset.min(source.len())].matches('\n').count() + 1;
+    let start = edit.start_offset();
+    // The end is exclusive, so an edit ending right after a newline stays on its line
+    let end = edit.end_offset().max(start + 1) - 1;
+    (line_at(start), line_at(end.max(start)))
+}
+
+/// Edits on lines of `source` that differ in `fixed`, so none if nothing
+/// changed or every edit was undone by a later fixer
+fn net_edits(source: &str, fixed: &str, edits: Vec<rustor_core::Edit>) -> Vec<rustor_core::Edit> {
+    use similar::{DiffOp, TextDiff};
+
+    if source == fixed {
+        return Vec::new();
+    }
+
+    // 1-based lines of `source` that were changed or removed, or that new
+    // lines were inserted next to
+    let mut changed: Vec<(usize, usize)> = Vec::new();
+    for op in TextDiff::from_lines(source, fixed).ops() {
+        match *op {
+            DiffOp::Equal { .. } => {}
+            DiffOp::Insert { old_index, .. } => changed.push((old_index, old_index + 1)),
+            DiffOp::Delete { old_index, old_len, .. } | DiffOp::Replace { old_index, old_len, .. } => {
+                changed.push((old_index + 1, old_index + old_len));
+            }
+        }
+    }
+
+    edits
+        .into_iter()
+        .filter(|edit| {
+            let (start, end) = edit_lines(source, edit);
+            changed.iter().any(|&(from, to)| start <= to && from <= end)
+        })
+        .collect()
+}
+
+/// Parse a `--lines` value: `10-40` or a single line `12`
+pub fn parse_line_range(value: &str) -> Result<(usize, usize)> {
+    let (start, end) = value.split_once('-').unwrap_or((value, value));
+    match (start.trim().parse::<usize>(), end.trim().parse::<usize>()) {
+        (Ok(start), Ok(end)) if start >= 1 && start <= end => Ok((start, end)),
+        _ => anyhow::bail!("Invalid line range: '{}'. Expected START-END, e.g. 10-40", value),
+    }
+}
+
+/// Byte ranges of `source` covering 1-based, inclusive line ranges,
+/// each including the line's newline
+pub fn line_byte_ranges(source: &str, lines: &[(usize, usize)]) -> Vec<std::ops::Range<usize>> {
+    let mut line_starts = vec![0];
+    line_starts.extend(source.match_indices('\n').map(|(idx, _)| idx + 1));
+    let offset = |line: usize| line_starts.get(line - 1).copied().unwrap_or(source.len());
+
+    lines
+        .iter()
+        .map(|&(start, end)| offset(start)..offset(end + 1))
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// Group edits by fixer, in the order the fixers ran, with the lines each changed
+///
+/// The edits must come from `FixerRegistry::check`, whose spans refer to `source`.
+pub fn applied_fixers(source: &str, edits: &[rustor_core::Edit]) -> Vec<AppliedFixer> {
+    let mut applied: Vec<AppliedFixer> = Vec::new();
+    for edit in edits {
+        let name = edit.rule.as_deref().unwrap_or("unknown");
+        let idx = match applied.iter().position(|fixer| fixer.name == name) {
+            Some(idx) => idx,
+            None => {
+                applied.push(AppliedFixer { name: name.to_string(), lines: Vec::new() });
+                applied.len() - 1
+            }
+        };
+        applied[idx].lines.push(edit_lines(source, edit));
+    }
+
+    for fixer in &mut applied {
+        fixer.lines.sort();
+        let mut merged: Vec<(usize, usize)> = Vec::new();
+        for &(start, end) in &fixer.lines {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        fixer.lines = merged;
+    }
+
+    applied
+}
+
 /// List available fixers
 pub fn list_fixers(registry: &FixerRegistry) {
     println!("{}", "Available fixers:".bold());
@@ -89,30 +369,193 @@ pub fn config_from_php_cs_fixer(php_config: &PhpCsFixerConfig) -> FixerConfig {
     use rustor_fixer::{ConfigValue, PhpConfigValue};
     use std::collections::HashMap;
 
-    // Merge all rule options into a single HashMap
-    let mut options: HashMap<String, ConfigValue> = HashMap::new();
-
-    for (_rule_name, rule_config) in &php_config.rules {
-        for (opt_name, opt_value) in &rule_config.options {
-            // Convert from php_parser::ConfigValue to fixer::ConfigValue
-            let converted = match opt_value {
-                PhpConfigValue::Bool(b) => ConfigValue::Bool(*b),
-                PhpConfigValue::String(s) => ConfigValue::String(s.clone()),
-                PhpConfigValue::Number(n) => ConfigValue::Number(*n),
-                PhpConfigValue::Array(arr) => ConfigValue::Array(arr.clone()),
-                PhpConfigValue::Map(map) => {
-                    // Convert Map to StringMap
-                    ConfigValue::StringMap(map.clone())
-                }
-            };
-            options.insert(opt_name.clone(), converted);
-        }
+    // Keep each rule's options separate, so rules sharing an option name don't clash
+    let mut rule_options: HashMap<String, HashMap<String, ConfigValue>> = HashMap::new();
+
+    for (rule_name, rule_config) in &php_config.rules {
+        let options = rule_config
+            .options
+            .iter()
+            .map(|(opt_name, opt_value)| {
+                // Convert from php_parser::ConfigValue to fixer::ConfigValue
+                let converted = match opt_value {
+                    PhpConfigValue::Bool(b) => ConfigValue::Bool(*b),
+                    PhpConfigValue::String(s) => ConfigValue::String(s.clone()),
+                    PhpConfigValue::Number(n) => ConfigValue::Number(*n),
+                    PhpConfigValue::Array(arr) => ConfigValue::Array(arr.clone()),
+                    PhpConfigValue::Map(map) => ConfigValue::StringMap(map.clone()),
+                };
+                (opt_name.clone(), converted)
+            })
+            .collect();
+        rule_options.insert(rule_name.clone(), options);
     }
 
     FixerConfig {
         indent: php_config.whitespace.indent,
         line_ending: php_config.whitespace.line_ending,
-        options,
+        rule_options,
+        risky_allowed: php_config.risky_allowed,
+        ..Default::default()
+    }
+}
+
+/// Turn the fixers in `[fixer.rules]` on or off in `rules`, and add their options to `config`
+fn apply_section_rules(
+    registry: &FixerRegistry,
+    mut rules: Vec<String>,
+    section: &FixerSection,
+    config: &mut FixerConfig,
+) -> Result<Vec<String>> {
+    let php_name = |name: &str| {
+        registry
+            .get_by_php_name(name)
+            .or_else(|| registry.get(name))
+            .map(|fixer| fixer.php_cs_fixer_name())
+    };
+
+    for (name, value) in &section.rules {
+        let fixer = php_name(name).ok_or_else(|| {
+            anyhow::anyhow!("Unknown fixer '{}' in [fixer.rules]. Use --list-fixers to see available fixers", name)
+        })?;
+        rules.retain(|rule| php_name(rule) != Some(fixer));
+        match value {
+            toml::Value::Boolean(false) => continue,
+            toml::Value::Boolean(true) => {}
+            toml::Value::Table(options) => {
+                let options = options
+                    .iter()
+                    .map(|(option, value)| Ok((option.clone(), fixer_option(name, option, value)?)))
+                    .collect::<Result<_>>()?;
+                config.rule_options.insert(fixer.to_string(), options);
+            }
+            _ => anyhow::bail!("Invalid [fixer.rules] {}: expected true, false or a table of options", name),
+        }
+        rules.push(fixer.to_string());
+    }
+    Ok(rules)
+}
+
+/// A fixer option from `[fixer.rules.<fixer>]`
+fn fixer_option(fixer: &str, option: &str, value: &toml::Value) -> Result<rustor_fixer::ConfigValue> {
+    use rustor_fixer::ConfigValue;
+
+    let converted = match value {
+        toml::Value::Boolean(b) => Some(ConfigValue::Bool(*b)),
+        toml::Value::Integer(n) => Some(ConfigValue::Number(*n)),
+        toml::Value::String(s) => Some(ConfigValue::String(s.clone())),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()
+            .map(ConfigValue::Array),
+        toml::Value::Table(table) => table
+            .iter()
+            .map(|(key, item)| item.as_str().map(|s| (key.clone(), s.to_string())))
+            .collect::<Option<_>>()
+            .map(ConfigValue::StringMap),
+        _ => None,
+    };
+    converted.ok_or_else(|| anyhow::anyhow!("Unsupported value for [fixer.rules.{}] {}", fixer, option))
+}
+
+/// Paths and exclusions of a PHP-CS-Fixer Finder, with its paths relative to `base`
+///
+/// `exclude()` directories match a directory of that name anywhere,
+/// `notPath()` matches anywhere in the path and `notName()` the file name.
+/// Regular expressions are not supported and are skipped.
+pub fn finder_filter(finder: &FinderConfig, base: &Path) -> PathFilter {
+    let mut include: Vec<PathBuf> = finder.paths.iter().map(|path| base.join(path)).collect();
+    if finder.use_cwd {
+        include.push(PathBuf::from("."));
+    }
+
+    let mut exclude: Vec<String> = finder
+        .exclude
+        .iter()
+        .map(|dir| format!("{}/", dir.trim_end_matches('/')))
+        .collect();
+    for pattern in &finder.not_path_patterns {
+        if is_regex(pattern) {
+            eprintln!("{}: Finder notPath regex is not supported, ignoring: {}", "Warning".yellow(), pattern);
+        } else if pattern.contains('*') {
+            exclude.push(pattern.clone());
+        } else {
+            exclude.push(format!("*{}*", pattern));
+        }
+    }
+    for pattern in &finder.not_name_patterns {
+        if is_regex(pattern) {
+            eprintln!("{}: Finder notName regex is not supported, ignoring: {}", "Warning".yellow(), pattern);
+        } else {
+            exclude.push(pattern.clone());
+        }
+    }
+
+    PathFilter::new(include, exclude)
+}
+
+/// Whether a Finder pattern is a regex like `#^tests/#` or `/\.tpl$/i`
+fn is_regex(pattern: &str) -> bool {
+    let Some(delimiter) = pattern.chars().next().filter(|c| matches!(c, '#' | '~' | '/' | '!' | '@')) else {
+        return false;
+    };
+    let body = pattern.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    body.len() > 1 && body.ends_with(delimiter)
+}
+
+/// Fixer violations as watch mode findings, one per edit
+pub fn edit_findings(path: &Path, source: &str, edits: &[rustor_core::Edit]) -> Vec<Finding> {
+    edits
+        .iter()
+        .map(|edit| Finding {
+            file: path.to_path_buf(),
+            line: edit_lines(source, edit).0,
+            source: edit.rule.clone().unwrap_or_else(|| "fixer".to_string()),
+            message: edit.message.clone(),
+        })
+        .collect()
+}
+
+/// Re-runs fixers on changed files for `--fixer --watch`
+pub struct FixerWatch<F> {
+    /// Watched files and directories
+    pub paths: Vec<PathBuf>,
+    /// Files left out of the watched directories
+    pub filter: PathFilter,
+    /// Violations found in one file
+    pub check: F,
+}
+
+impl<F: Fn(&Path) -> Vec<Finding> + Sync> FixerWatch<F> {
+    fn is_watched(&self, file: &Path) -> bool {
+        file.extension().is_some_and(|ext| ext == "php")
+            && self.paths.iter().any(|path| file.starts_with(path))
+            && !self.filter.is_excluded(file)
+    }
+}
+
+impl<F: Fn(&Path) -> Vec<Finding> + Sync> WatchTarget for FixerWatch<F> {
+    fn check_all(&mut self) -> BTreeMap<PathBuf, Vec<Finding>> {
+        self.filter
+            .php_files(&self.paths)
+            .into_par_iter()
+            .map(|file| {
+                let findings = (self.check)(&file);
+                (file, findings)
+            })
+            .collect()
+    }
+
+    fn check_changed(&mut self, changed: &[PathBuf]) -> BTreeMap<PathBuf, Vec<Finding>> {
+        changed
+            .iter()
+            .filter(|file| self.is_watched(file))
+            .map(|file| {
+                let findings = if file.is_file() { (self.check)(file) } else { Vec::new() };
+                (file.clone(), findings)
+            })
+            .collect()
     }
 }
 
@@ -121,7 +564,7 @@ pub fn default_fixer_config() -> FixerConfig {
     FixerConfig {
         indent: IndentStyle::Spaces(4),
         line_ending: LineEnding::Lf,
-        options: Default::default(),
+        ..Default::default()
     }
 }
 
@@ -143,6 +586,68 @@ mod tests {
         assert!(!fixed.contains("   \n")); // No trailing whitespace
     }
 
+    #[test]
+    fn test_line_ranges() {
+        assert_eq!(parse_line_range("10-40").unwrap(), (10, 40));
+        assert_eq!(parse_line_range("7").unwrap(), (7, 7));
+        assert!(parse_line_range("40-10").is_err());
+        assert!(parse_line_range("0-3").is_err());
+
+        let source = "<?php\n$a = 1;   \n$b = 2;   \n";
+        assert_eq!(line_byte_ranges(source, &[(2, 2)]), vec![6..17]);
+        assert_eq!(line_byte_ranges(source, &[(3, 9)]), vec![17..28]);
+        assert!(line_byte_ranges(source, &[(5, 6)]).is_empty());
+
+        let registry = FixerRegistry::new();
+        let config = FixerConfig {
+            ranges: Some(line_byte_ranges(source, &[(3, 3)])),
+            ..default_fixer_config()
+        };
+        let (fixed, _) = run_fixers_on_file(source, &registry, &config, None).unwrap();
+        assert_eq!(fixed, "<?php\n$a = 1;   \n$b = 2;\n");
+    }
+
+    #[test]
+    fn test_applied_fixers_use_original_lines() {
+        let registry = FixerRegistry::new();
+        let config = default_fixer_config();
+
+        let source = "<?php\n$a = TRUE;\n\n\n\n$b = FALSE;   \n$c = NULL;\n";
+        let rules = ["no_extra_blank_lines", "constant_case", "no_trailing_whitespace"];
+        let (_, edits) = run_fixers_on_file(source, &registry, &config, Some(&rules)).unwrap();
+
+        let applied = applied_fixers(source, &edits);
+        let summary: Vec<_> = applied.iter().map(|f| (f.name.as_str(), f.lines.clone())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("no_trailing_whitespace", vec![(6, 6)]),
+                ("constant_case", vec![(2, 2), (6, 7)]),
+                ("no_extra_blank_lines", vec![(2, 5)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_net_edits_drop_undone_changes() {
+        let edit = |start: usize, end: usize, rule: &str| {
+            rustor_fixer::fixers::edit_with_rule(start, end, String::new(), "test".to_string(), rule)
+        };
+        let source = "<?php\nfoo( $a );\n$b = 1;   \n";
+        let edits = vec![edit(10, 11, "spaces_inside_parentheses"), edit(10, 10, "method_argument_space"), edit(24, 27, "no_trailing_whitespace")];
+
+        // Only the trailing whitespace is gone in the end
+        let fixed = "<?php\nfoo( $a );\n$b = 1;\n";
+        let rules: Vec<_> = net_edits(source, fixed, edits.clone()).into_iter().map(|e| e.rule.unwrap()).collect();
+        assert_eq!(rules, vec!["no_trailing_whitespace"]);
+        assert!(net_edits(source, source, edits.clone()).is_empty());
+
+        // A later fixer reverted the only reported change, so the remaining
+        // difference isn't attributed to it
+        let fixed = "<?php\nfoo( $a );\n$b = 1;\n\n";
+        assert!(net_edits(source, fixed, edits[..2].to_vec()).is_empty());
+    }
+
     #[test]
     fn test_run_fixers_with_preset() {
         let registry = FixerRegistry::new();
@@ -155,4 +660,50 @@ mod tests {
         assert!(!edits.is_empty());
         assert!(fixed.contains("if"));
     }
+
+    #[test]
+    fn test_fixer_watch_checks_changed_files_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("a.php");
+        let deleted = dir.path().join("b.php");
+        std::fs::write(&file, "<?php\n$a = TRUE;\n").unwrap();
+
+        let registry = FixerRegistry::new();
+        let config = default_fixer_config();
+        let mut watch = FixerWatch {
+            paths: vec![dir.path().to_path_buf()],
+            filter: PathFilter::default(),
+            check: |path: &Path| {
+                let source = std::fs::read_to_string(path).unwrap();
+                let (_, edits) = registry.check_preset(&source, "psr12", &config);
+                edit_findings(path, &source, &edits)
+            },
+        };
+
+        let all = watch.check_all();
+        assert_eq!(all[&file].len(), 1);
+        assert_eq!(all[&file][0].line, 2);
+
+        let changed = watch.check_changed(&[file.clone(), deleted.clone(), PathBuf::from("/elsewhere/c.php")]);
+        assert_eq!(changed.keys().collect::<Vec<_>>(), [&file, &deleted]);
+        assert!(changed[&deleted].is_empty());
+    }
+
+    #[test]
+    fn test_finder_filter() {
+        let finder = FinderConfig {
+            paths: vec!["src".to_string(), "tests".to_string()],
+            exclude: vec!["var".to_string()],
+            not_path_patterns: vec!["Fixtures".to_string(), "#^legacy/#".to_string()],
+            not_name_patterns: vec!["*.blade.php".to_string()],
+            ..Default::default()
+        };
+        let filter = finder_filter(&finder, Path::new("/app"));
+        assert_eq!(filter.include, vec![PathBuf::from("/app/src"), PathBuf::from("/app/tests")]);
+        assert!(filter.is_excluded(Path::new("/app/src/var/cache.php")));
+        assert!(filter.is_excluded(Path::new("/app/tests/Fixtures/a.php")));
+        assert!(filter.is_excluded(Path::new("/app/src/view.blade.php")));
+        assert!(!filter.is_excluded(Path::new("/app/src/legacy/a.php")));
+        assert!(!filter.is_excluded(Path::new("/app/src/User.php")));
+    }
 }
@@ -0,0 +1,174 @@
+//! Pretty-printer subcommand (`rustor fmt`)
+
+use anyhow::{Context, Result};
+use colored::*;
+use rustor_fmt::{format, FormatSettings};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::config::{Config, FmtConfig};
+
+/// Arguments for the fmt subcommand
+#[derive(clap::Args)]
+#[command(after_help = "EXAMPLES:
+    rustor fmt src/
+    rustor fmt --check src/ tests/
+    cat file.php | rustor fmt -")]
+pub struct FmtArgs {
+    /// Files or directories to format (default: current directory); `-` for stdin
+    pub paths: Vec<PathBuf>,
+    /// List files that would change, exit 1 if any
+    #[arg(long)]
+    pub check: bool,
+    /// Format stdin and write the result to stdout
+    #[arg(long)]
+    pub stdin: bool,
+    /// Maximum line width (default: 120)
+    #[arg(long, value_name = "N", value_parser = parse_line_width)]
+    pub line_width: Option<usize>,
+    /// Config file (default: search for .rustor.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+/// Run the fmt subcommand
+pub fn run_fmt(args: FmtArgs) -> Result<ExitCode> {
+    let config = match &args.config {
+        Some(path) => Config::load_path(path)?,
+        None => Config::load()?.map(|(config, _)| config).unwrap_or_default(),
+    };
+    let mut settings = settings_from_config(&config.fmt)?;
+    if let Some(line_width) = args.line_width {
+        settings.line_width = line_width;
+    }
+
+    if args.stdin || args.paths.iter().any(|p| p == Path::new("-")) {
+        let mut source = String::new();
+        std::io::stdin().read_to_string(&mut source).context("Failed to read stdin")?;
+        let formatted = format(&source, &settings)?;
+        if args.check {
+            return Ok(if formatted == source { ExitCode::SUCCESS } else { ExitCode::from(1) });
+        }
+        std::io::stdout().write_all(formatted.as_bytes())?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let paths = if args.paths.is_empty() { vec![PathBuf::from(".")] } else { args.paths.clone() };
+    let mut files = Vec::new();
+    for path in &paths {
+        if path.is_file() {
+            files.push(path.clone());
+        } else if path.is_dir() {
+            for entry in walkdir::WalkDir::new(path)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().extension().is_some_and(|ext| ext == "php"))
+            {
+                if !config.should_exclude(entry.path()) {
+                    files.push(entry.path().to_path_buf());
+                }
+            }
+        } else {
+            anyhow::bail!("Path not found: {}", path.display());
+        }
+    }
+
+    let mut changed = 0;
+    let mut failed = 0;
+    for file in &files {
+        let source = std::fs::read_to_string(file).with_context(|| format!("Failed to read {}", file.display()))?;
+        let formatted = match format(&source, &settings) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}: {}: {}", "Error".red(), file.display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+        if formatted == source {
+            continue;
+        }
+
+        changed += 1;
+        if args.check {
+            println!("{}", file.display());
+        } else {
+            std::fs::write(file, formatted).with_context(|| format!("Failed to write {}", file.display()))?;
+        }
+    }
+
+    let verb = if args.check { "would be reformatted" } else { "reformatted" };
+    eprintln!("{} of {} files {}", changed, files.len(), verb);
+
+    if failed > 0 || (args.check && changed > 0) {
+        Ok(ExitCode::from(1))
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Formatter settings from the `[fmt]` section, defaulting to PER Coding Style
+pub fn settings_from_config(config: &FmtConfig) -> Result<FormatSettings> {
+    let mut settings = FormatSettings::default();
+    if let Some(line_width) = config.line_width {
+        settings.line_width = line_width;
+    }
+    if let Some(indent_size) = config.indent_size {
+        settings.indent_size = indent_size;
+    }
+    if let Some(use_tabs) = config.use_tabs {
+        settings.use_tabs = use_tabs;
+    }
+    match config.line_ending.as_deref() {
+        None | Some("lf") => {}
+        Some("crlf") => settings.line_ending = "\r\n".to_string(),
+        Some(other) => anyhow::bail!("Invalid [fmt] line_ending: '{}'. Expected 'lf' or 'crlf'", other),
+    }
+    Ok(settings)
+}
+
+fn parse_line_width(value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(width) if width > 0 => Ok(width),
+        _ => anyhow::bail!("Invalid line width: '{}'. Expected a positive number", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_fmt_args(list: &[&str]) -> Result<FmtArgs, clap::Error> {
+        use clap::Parser;
+        match crate::Cli::try_parse_from(["rustor", "fmt"].iter().chain(list))?.command {
+            Some(crate::Command::Fmt(args)) => Ok(args),
+            _ => panic!("not parsed as fmt"),
+        }
+    }
+
+    #[test]
+    fn test_parse_fmt_args() {
+        let parsed = parse_fmt_args(&["--check", "--line-width=80", "src"]).unwrap();
+        assert!(parsed.check);
+        assert_eq!(parsed.line_width, Some(80));
+        assert_eq!(parsed.paths, vec![PathBuf::from("src")]);
+
+        assert_eq!(parse_fmt_args(&["-"]).unwrap().paths, vec![PathBuf::from("-")]);
+        assert!(parse_fmt_args(&["--line-width", "0"]).is_err());
+        assert!(parse_fmt_args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_settings_from_config() {
+        let config: Config = toml::from_str("[fmt]\nline_width = 80\nuse_tabs = true\nline_ending = \"crlf\"\n").unwrap();
+        let settings = settings_from_config(&config.fmt).unwrap();
+        assert_eq!(settings.line_width, 80);
+        assert!(settings.use_tabs);
+        assert_eq!(settings.indent_size, 4);
+        assert_eq!(settings.line_ending, "\r\n");
+
+        let config: Config = toml::from_str("[fmt]\nline_ending = \"cr\"\n").unwrap();
+        assert!(settings_from_config(&config.fmt).is_err());
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides functions to discover PHP files based on git state:
 //! - Staged files (for pre-commit hooks)
 //! - Changed files since a git ref (for CI workflows)
+//! - Changed lines of a file since a git ref (for range-restricted fixing)
 
 use anyhow::{Context, Result, bail};
 use std::path::{Path, PathBuf};
@@ -113,6 +114,44 @@ pub fn get_changed_files_since(repo_root: &Path, ref_name: &str) -> Result<Vec<P
     parse_git_file_list(&output.stdout, repo_root)
 }
 
+/// Get the lines of a file added or changed since a git ref
+///
+/// Compares the ref against the working tree, so uncommitted changes count.
+/// Returns 1-based inclusive line ranges of the current file.
+pub fn get_changed_lines_since(repo_root: &Path, ref_name: &str, path: &Path) -> Result<Vec<(usize, usize)>> {
+    let output = Command::new("git")
+        .current_dir(repo_root)
+        .args(["diff", "--unified=0", "--no-color", "--no-ext-diff", ref_name, "--"])
+        .arg(path)
+        .output()
+        .context("Failed to execute git diff")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("Failed to get changed lines of {}: {}", path.display(), stderr.trim());
+    }
+
+    Ok(parse_changed_lines(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the new-file side of `git diff --unified=0` hunk headers
+/// (`@@ -12,3 +14,5 @@`) into line ranges; pure deletions are skipped
+fn parse_changed_lines(diff: &str) -> Vec<(usize, usize)> {
+    diff.lines()
+        .filter_map(|line| line.strip_prefix("@@ "))
+        .filter_map(|header| header.split(' ').find(|part| part.starts_with('+')))
+        .filter_map(|new_side| {
+            let mut parts = new_side[1..].splitn(2, ',');
+            let start: usize = parts.next()?.parse().ok()?;
+            let count: usize = match parts.next() {
+                Some(count) => count.parse().ok()?,
+                None => 1,
+            };
+            (count > 0).then(|| (start, start + count - 1))
+        })
+        .collect()
+}
+
 /// Parse git output into list of absolute file paths
 fn parse_git_file_list(output: &[u8], repo_root: &Path) -> Result<Vec<PathBuf>> {
     let files = String::from_utf8_lossy(output);
@@ -185,4 +224,18 @@ mod tests {
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_parse_changed_lines() {
+        let diff = "diff --git a/src/Foo.php b/src/Foo.php\n\
+                    --- a/src/Foo.php\n\
+                    +++ b/src/Foo.php\n\
+                    @@ -3 +3 @@ class Foo\n\
+                    -old\n\
+                    +new\n\
+                    @@ -10,2 +10,0 @@\n\
+                    @@ -20,0 +19,4 @@ function bar()\n";
+
+        assert_eq!(parse_changed_lines(diff), vec![(3, 3), (19, 22)]);
+    }
 }
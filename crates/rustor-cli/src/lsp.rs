@@ -1,6 +1,7 @@
 //! LSP server for rustor IDE integration
 //!
-//! Provides real-time diagnostics and code actions for PHP files.
+//! Provides real-time diagnostics, code actions and range formatting (with
+//! the PSR-12 fixers) for PHP files.
 //!
 //! Usage:
 //!   rustor --lsp
@@ -13,28 +14,37 @@
 //! }
 //! ```
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
 
 use bumpalo::Bump;
 use mago_database::file::FileId;
-use mago_span::HasSpan;
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
+use rustor_core::PARSE_ERROR_IDENTIFIER;
+use rustor_fixer::config::{EditorConfigResolver, EditorConfigSettings};
+use rustor_fixer::FixerRegistry;
 use rustor_rules::{RuleRegistry, Preset};
 
+use crate::fixer;
+
 /// Rustor LSP server backend
 pub struct RustorLsp {
     client: Client,
+    /// Text of open documents, for formatting requests
+    documents: Mutex<HashMap<Url, String>>,
 }
 
 impl RustorLsp {
     pub fn new(client: Client) -> Self {
-        Self { client }
+        Self { client, documents: Mutex::new(HashMap::new()) }
     }
 
     async fn check_document(&self, uri: &Url, text: &str) {
+        self.documents.lock().unwrap().insert(uri.clone(), text.to_string());
+
         // Do all synchronous work first (parsing and rule checking)
         let diagnostics = analyze_php_sync(text);
 
@@ -52,11 +62,12 @@ fn analyze_php_sync(source: &str) -> Vec<Diagnostic> {
     // Parse the PHP file
     let arena = Bump::new();
     let file_id = FileId::new("buffer");
-    let (program, parse_errors) = mago_syntax::parser::parse_file_content(&arena, file_id, source);
+    let parsed = rustor_core::parse(&arena, file_id, source);
+    let program = parsed.program;
 
     // Report parse errors
-    if let Some(error) = parse_errors {
-        let span = error.span();
+    for error in parsed.errors {
+        let span = error.span;
         let (start_line, start_col) = offset_to_line_col(source, span.start.offset as usize);
         let (end_line, end_col) = offset_to_line_col(source, span.end.offset as usize);
 
@@ -72,10 +83,10 @@ fn analyze_php_sync(source: &str) -> Vec<Diagnostic> {
                 },
             },
             severity: Some(DiagnosticSeverity::ERROR),
-            code: None,
+            code: Some(NumberOrString::String(PARSE_ERROR_IDENTIFIER.to_string())),
             code_description: None,
             source: Some("rustor".to_string()),
-            message: error.to_string(),
+            message: error.message,
             related_information: None,
             tags: None,
             data: None,
@@ -123,6 +134,52 @@ fn analyze_php_sync(source: &str) -> Vec<Diagnostic> {
     diagnostics
 }
 
+/// Run the PSR-12 fixers on the lines of `range`, as a single edit of the
+/// part of the document that changed
+fn format_range_sync(source: &str, range: Range, settings: &EditorConfigSettings) -> Vec<TextEdit> {
+    let start_line = range.start.line as usize + 1;
+    let mut end_line = range.end.line as usize + 1;
+    // A selection ending at the start of a line doesn't include that line
+    if range.end.character == 0 && end_line > start_line {
+        end_line -= 1;
+    }
+
+    let mut config = rustor_fixer::FixerConfig {
+        ranges: Some(fixer::line_byte_ranges(source, &[(start_line, end_line)])),
+        ..fixer::default_fixer_config()
+    };
+    settings.apply(&mut config, None);
+    let (fixed, edits) = FixerRegistry::new().check_preset(source, "psr12", &config);
+    if edits.is_empty() || fixed == source {
+        return Vec::new();
+    }
+
+    // Fixers' edits can overlap, so replace the changed region as a whole
+    let prefix = source
+        .char_indices()
+        .zip(fixed.chars())
+        .find(|((_, a), b)| a != b)
+        .map(|((idx, _), _)| idx)
+        .unwrap_or(source.len().min(fixed.len()));
+    let suffix = source[prefix..]
+        .chars()
+        .rev()
+        .zip(fixed[prefix..].chars().rev())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a.len_utf8())
+        .sum::<usize>();
+
+    let (start_line, start_col) = offset_to_line_col(source, prefix);
+    let (end_line, end_col) = offset_to_line_col(source, source.len() - suffix);
+    vec![TextEdit {
+        range: Range {
+            start: Position { line: start_line as u32, character: start_col as u32 },
+            end: Position { line: end_line as u32, character: end_col as u32 },
+        },
+        new_text: fixed[prefix..fixed.len() - suffix].to_string(),
+    }]
+}
+
 fn offset_to_line_col(source: &str, offset: usize) -> (usize, usize) {
     let mut line = 0;
     let mut col = 0;
@@ -162,6 +219,7 @@ impl LanguageServer for RustorLsp {
                     TextDocumentSyncKind::FULL,
                 )),
                 code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
                 ..Default::default()
             },
             server_info: Some(ServerInfo {
@@ -211,6 +269,8 @@ impl LanguageServer for RustorLsp {
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        self.documents.lock().unwrap().remove(&params.text_document.uri);
+
         // Clear diagnostics when file is closed
         self.client
             .publish_diagnostics(params.text_document.uri, vec![], None)
@@ -268,6 +328,17 @@ impl LanguageServer for RustorLsp {
             Ok(Some(actions))
         }
     }
+
+    async fn range_formatting(&self, params: DocumentRangeFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        let text = self.documents.lock().unwrap().get(&params.text_document.uri).cloned();
+        let settings = params
+            .text_document
+            .uri
+            .to_file_path()
+            .map(|path| EditorConfigResolver::new().resolve(&path))
+            .unwrap_or_default();
+        Ok(text.map(|text| format_range_sync(&text, params.range, &settings)))
+    }
 }
 
 /// Run the LSP server
@@ -278,3 +349,42 @@ pub async fn run_lsp_server() {
     let (service, socket) = LspService::new(RustorLsp::new);
     Server::new(stdin, stdout, socket).serve(service).await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(start: u32, end: u32) -> Range {
+        Range {
+            start: Position { line: start, character: 0 },
+            end: Position { line: end, character: 0 },
+        }
+    }
+
+    #[test]
+    fn test_format_range_only_changes_selected_lines() {
+        let source = "<?php\n$a = TRUE;   \n$b = TRUE;   \n$c = TRUE;   \n";
+
+        let edits = format_range_sync(source, lines(2, 3), &EditorConfigSettings::default());
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].range.start, Position { line: 2, character: 5 });
+        assert_eq!(edits[0].range.end, Position { line: 2, character: 13 });
+        assert_eq!(edits[0].new_text, "true;");
+
+        assert!(format_range_sync("<?php\n$a = 1;\n", lines(1, 2), &EditorConfigSettings::default()).is_empty());
+    }
+
+    #[test]
+    fn test_every_parse_error_is_a_diagnostic() {
+        let source = "<?php\n$a = ;\n$b = );\n";
+
+        let diagnostics = analyze_php_sync(source);
+        let parse_errors: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| d.code == Some(NumberOrString::String(PARSE_ERROR_IDENTIFIER.to_string())))
+            .collect();
+        assert_eq!(parse_errors.len(), 2);
+        assert_eq!(parse_errors[0].range.start, Position { line: 1, character: 5 });
+        assert_eq!(parse_errors[1].range.start, Position { line: 2, character: 5 });
+    }
+}
@@ -16,30 +16,37 @@ mod analyze;
 mod baseline;
 mod cache;
 mod backup;
+mod check;
 mod config;
 mod fixer;
+mod fmt;
 mod git;
 mod ignore;
 mod lsp;
 mod output;
 mod plugin;
 mod process;
+mod project;
+mod review;
+mod test_rules;
+mod undo;
 mod watch;
 
 use anyhow::Result;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use colored::*;
 use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Mutex;
 
 use cache::{hash_file, hash_rules, Cache};
 use config::Config;
 use output::{EditInfo, OutputFormat, Reporter};
-use process::{process_file_with_registry, process_file_with_skip, write_file};
+use process::{process_file_with_plugins, process_file_with_skip, write_file};
+use rustor_core::PathFilter;
 use rustor_rules::{Category, PhpVersion, Preset, RuleConfigs, RuleRegistry};
 
 #[derive(Parser)]
@@ -47,9 +54,15 @@ use rustor_rules::{Category, PhpVersion, Preset, RuleConfigs, RuleRegistry};
 #[command(version = "0.2.0")]
 #[command(about = "A Rust-based PHP refactoring tool")]
 #[command(author = "rustor contributors")]
+// A subcommand name only counts as the first argument, so `rustor --fix check`
+// or `rustor -- check` still treats `check` as a path
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true, disable_help_subcommand = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Files or directories to process
-    #[arg(required_unless_present_any = ["list_rules", "list_fixers", "staged", "since"])]
+    #[arg(required_unless_present_any = ["list_rules", "list_fixers", "list_plugins", "staged", "since"])]
     paths: Vec<PathBuf>,
 
     /// Check for issues without applying fixes (default mode)
@@ -60,6 +73,10 @@ struct Cli {
     #[arg(long, conflicts_with = "check")]
     fix: bool,
 
+    /// Review each fix before applying it (implies --fix)
+    #[arg(long, short = 'i', conflicts_with_all = ["check", "dry_run", "watch", "generate_baseline", "baseline"])]
+    interactive: bool,
+
     /// Show changes without applying them (alias for --check)
     #[arg(long, short = 'n', hide = true, conflicts_with = "fix")]
     dry_run: bool,
@@ -112,6 +129,14 @@ struct Cli {
     #[arg(long)]
     bundled_yaml_rules: bool,
 
+    /// Directory to discover plugins in (default: [plugins] dir or ~/.rustor/plugins)
+    #[arg(long, value_name = "DIR")]
+    plugin_dir: Option<PathBuf>,
+
+    /// List discovered plugins and exit
+    #[arg(long)]
+    list_plugins: bool,
+
     /// Disable caching (always re-process all files)
     #[arg(long)]
     no_cache: bool,
@@ -124,11 +149,20 @@ struct Cli {
     #[arg(long, short = 'w')]
     watch: bool,
 
+    /// With --watch, clear the screen before each run
+    #[arg(long, requires = "watch")]
+    clear: bool,
+
+    /// With --watch, print a "new, fixed, total" summary after each run
+    #[arg(long, requires = "watch")]
+    summary: bool,
+
     /// Only check git-staged files (for pre-commit hooks)
     #[arg(long)]
     staged: bool,
 
-    /// Only check files changed since this git ref (branch, tag, or commit)
+    /// Only check files changed since this git ref (branch, tag, or commit).
+    /// With --fixer, only the changed lines are fixed.
     #[arg(long, value_name = "REF")]
     since: Option<String>,
 
@@ -144,6 +178,10 @@ struct Cli {
     #[arg(long, value_name = "FILE")]
     baseline: Option<PathBuf>,
 
+    /// Remove resolved entries from the --baseline file (new findings are not added)
+    #[arg(long, requires = "baseline", conflicts_with = "generate_baseline")]
+    update_baseline: bool,
+
     /// Create backup files before applying fixes (default: true)
     #[arg(long, default_value = "true")]
     backup: bool,
@@ -165,7 +203,7 @@ struct Cli {
     lsp: bool,
 
     // Fixer options
-    /// Path to .php-cs-fixer.php config file for formatting rules
+    /// Path to .php-cs-fixer.php or phpcs.xml config file for formatting rules
     #[arg(long, value_name = "PATH")]
     fixer_config: Option<PathBuf>,
 
@@ -177,11 +215,38 @@ struct Cli {
     #[arg(long, value_name = "PRESET")]
     fixer_preset: Option<String>,
 
+    /// Only fix these lines, e.g. 10-40 (can be repeated or comma-separated)
+    #[arg(long, value_name = "START-END", value_delimiter = ',', requires = "fixer")]
+    lines: Vec<String>,
+
+    /// Allow risky fixers even if the config doesn't call setRiskyAllowed(true)
+    #[arg(long)]
+    allow_risky: bool,
+
     /// List available formatters/fixers
     #[arg(long)]
     list_fixers: bool,
 }
 
+/// Subcommands; without one, rustor runs refactoring rules or fixers on the paths
+#[derive(Subcommand)]
+enum Command {
+    /// PHPStan-compatible static analysis
+    Analyze(analyze::AnalyzeArgs),
+    /// Run rules, formatting fixers and static analysis in one pass
+    Check(check::CheckArgs),
+    /// Opinionated PHP pretty-printer (PER Coding Style)
+    Fmt(fmt::FmtArgs),
+    /// Restore files from the backups written by --fix
+    Undo(undo::UndoArgs),
+    /// Write a .rustor.toml from the project's existing configs
+    Init(project::InitArgs),
+    /// Inspect the effective configuration
+    Config(project::ConfigArgs),
+    /// Run YAML rule tests and .php.inc fixtures
+    TestRules(test_rules::TestRulesArgs),
+}
+
 fn main() -> ExitCode {
     // Check for LSP mode early (before parsing other args)
     if std::env::args().any(|arg| arg == "--lsp") {
@@ -191,25 +256,6 @@ fn main() -> ExitCode {
         return ExitCode::SUCCESS;
     }
 
-    // Check for analyze subcommand early
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    if analyze::should_run_analyze(&args) {
-        let analyze_args = args.into_iter().skip(1).collect::<Vec<_>>();
-        match analyze::parse_analyze_args(&analyze_args) {
-            Ok(args) => match analyze::run_analyze(args) {
-                Ok(code) => return code,
-                Err(e) => {
-                    eprintln!("{}: {:#}", "Error".red(), e);
-                    return ExitCode::from(1);
-                }
-            },
-            Err(e) => {
-                eprintln!("{}: {:#}", "Error".red(), e);
-                return ExitCode::from(1);
-            }
-        }
-    }
-
     match run() {
         Ok(code) => code,
         Err(e) => {
@@ -221,6 +267,16 @@ fn main() -> ExitCode {
 
 fn run() -> Result<ExitCode> {
     let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Analyze(args)) => return analyze::run_analyze(args),
+        Some(Command::Check(args)) => return check::run_check(args),
+        Some(Command::Fmt(args)) => return fmt::run_fmt(args),
+        Some(Command::Undo(args)) => return undo::run_undo(args),
+        Some(Command::Init(args)) => return project::run_init(args),
+        Some(Command::Config(args)) => return project::run_config(args),
+        Some(Command::TestRules(args)) => return test_rules::run_test_rules(args),
+        None => {}
+    }
 
     // Determine output format early (needed for verbose output)
     let output_format = if cli.json {
@@ -235,23 +291,23 @@ fn run() -> Result<ExitCode> {
     };
 
     // Load config file
-    let config = if cli.no_config {
-        Config::default()
+    let (config, config_path) = if cli.no_config {
+        (Config::default(), None)
     } else if let Some(config_path) = &cli.config {
         let cfg = Config::load_path(config_path)?;
         if cli.verbose && output_format == OutputFormat::Text {
             println!("{}: {}", "Using config".bold(), config_path.display());
         }
-        cfg
+        (cfg, Some(config_path.clone()))
     } else {
         match Config::load()? {
             Some((cfg, path)) => {
                 if cli.verbose && output_format == OutputFormat::Text {
                     println!("{}: {}", "Using config".bold(), path.display());
                 }
-                cfg
+                (cfg, Some(path))
             }
-            None => Config::default(),
+            None => (Config::default(), None),
         }
     };
 
@@ -308,6 +364,13 @@ fn run() -> Result<ExitCode> {
         return Ok(ExitCode::SUCCESS);
     }
 
+    // Handle --list-plugins
+    if cli.list_plugins {
+        let manager = plugin::PluginManager::discover(cli.plugin_dir.clone(), &config).map_err(anyhow::Error::msg)?;
+        list_plugins(&manager, &config.plugins.enabled);
+        return Ok(ExitCode::SUCCESS);
+    }
+
     // Handle --list-fixers
     if cli.list_fixers {
         let fixer_registry = rustor_fixer::FixerRegistry::new();
@@ -317,7 +380,7 @@ fn run() -> Result<ExitCode> {
 
     // Handle --fixer mode (run formatting fixers only)
     if cli.fixer {
-        return run_fixer_mode(&cli, output_format);
+        return run_fixer_mode(&cli, &config, output_format);
     }
 
     // Get all available rule names from registry
@@ -425,13 +488,21 @@ fn run() -> Result<ExitCode> {
         return Ok(ExitCode::from(1));
     }
 
+    // Start plugins enabled in the config
+    let mut plugins = plugin::start_enabled(&config, cli.plugin_dir.clone()).map_err(anyhow::Error::msg)?;
+
     // Handle watch mode
     if cli.watch {
+        if !plugins.is_empty() {
+            eprintln!("{}: Plugins are not run in watch mode", "Warning".yellow());
+        }
         let watch_config = watch::WatchConfig {
             paths: cli.paths.clone(),
             enabled_rules: enabled_rules.clone(),
             format: output_format,
             verbose: cli.verbose,
+            clear_screen: cli.clear,
+            summary: cli.summary,
             ..Default::default()
         };
         watch::run_watch(watch_config)?;
@@ -439,7 +510,7 @@ fn run() -> Result<ExitCode> {
     }
 
     // Determine mode: fix or check (check is default)
-    let fix_mode = cli.fix;
+    let fix_mode = cli.fix || cli.interactive;
     let check_mode = !fix_mode; // --check, --dry-run, or default
 
     // Set up backup manager for fix mode
@@ -474,8 +545,14 @@ fn run() -> Result<ExitCode> {
         }
     }
 
-    // Load cache (unless disabled)
-    let use_cache = !cli.no_cache;
+    // Load cache (unless disabled). Results of project plugins can change
+    // without the file changing, so they are never cached. Baselines need
+    // the findings of every file, which cached results don't have.
+    let use_cache = !cli.no_cache
+        && !cli.interactive
+        && !plugins.has_project_plugins()
+        && cli.baseline.is_none()
+        && !cli.generate_baseline;
     let cache = if use_cache {
         Cache::load(&cache_dir).unwrap_or_default()
     } else {
@@ -483,8 +560,10 @@ fn run() -> Result<ExitCode> {
     };
     let cache = Mutex::new(cache);
 
-    // Compute rules hash for cache invalidation
-    let rules_hash = hash_rules(&enabled_rules);
+    // Compute rules hash for cache invalidation, including plugin versions
+    let mut cached_rules = enabled_rules.clone();
+    cached_rules.extend(plugins.versions().into_iter().map(|v| format!("plugin:{}", v)));
+    let rules_hash = hash_rules(&cached_rules);
 
     if cli.verbose && output_format == OutputFormat::Text {
         println!(
@@ -576,10 +655,14 @@ fn run() -> Result<ExitCode> {
         }
     }
 
+    // Run project plugins once on all files before the per-file pass
+    plugins.check_project(&file_paths).map_err(anyhow::Error::msg)?;
+
     // Create progress bar (only for text format with TTY and not disabled)
     let show_progress = !cli.no_progress
         && output_format == OutputFormat::Text
         && atty::is(atty::Stream::Stdout)
+        && !cli.interactive
         && file_paths.len() > 10;  // Only show for larger scans
 
     let progress = if show_progress {
@@ -596,53 +679,60 @@ fn run() -> Result<ExitCode> {
         None
     };
 
-    // Process files in parallel (with caching)
+    // Process files in parallel (with caching), or one at a time in the
+    // order they are reported when reviewing interactively
     let cache_hits = Mutex::new(0usize);
-    let results: Vec<FileResult> = file_paths
-        .par_iter()
-        .map(|path| {
-            // Check cache first
-            if use_cache {
-                if let Ok(content_hash) = hash_file(path) {
-                    let cache_guard = cache.lock().unwrap();
-                    if let Some(entry) = cache_guard.get_if_valid(path, content_hash, rules_hash) {
-                        // Cache hit - use cached result
-                        *cache_hits.lock().unwrap() += 1;
-                        if let Some(ref pb) = progress {
-                            pb.inc(1);
+    let results: Vec<FileResult> = if cli.interactive {
+        file_paths.sort();
+        review_files(&file_paths, &enabled_rules, &registry, &config, config_path.as_deref(), &plugins)?
+    } else {
+        file_paths
+            .par_iter()
+            .map(|path| {
+                // Check cache first
+                if use_cache {
+                    if let Ok(content_hash) = hash_file(path) {
+                        let cache_guard = cache.lock().unwrap();
+                        if let Some(entry) = cache_guard.get_if_valid(path, content_hash, rules_hash) {
+                            // Cache hit - use cached result
+                            *cache_hits.lock().unwrap() += 1;
+                            if let Some(ref pb) = progress {
+                                pb.inc(1);
+                            }
+                            return if entry.has_edits {
+                                FileResult::CachedWithEdits { edit_count: entry.edit_count }
+                            } else {
+                                FileResult::NoChanges
+                            };
                         }
-                        return if entry.has_edits {
-                            FileResult::CachedWithEdits { edit_count: entry.edit_count }
-                        } else {
-                            FileResult::NoChanges
-                        };
                     }
                 }
-            }
 
-            // Cache miss - process the file
-            let result = process_file_to_result(path, &enabled_rules, &registry, &config);
-
-            // Update cache with result
-            if use_cache {
-                if let Ok(content_hash) = hash_file(path) {
-                    let (has_edits, edit_count) = match &result {
-                        FileResult::HasChanges { edits, .. } => (true, edits.len()),
-                        FileResult::NoChanges => (false, 0),
-                        _ => (false, 0),
-                    };
-                    let mut cache_guard = cache.lock().unwrap();
-                    cache_guard.update(path.clone(), content_hash, rules_hash, has_edits, edit_count);
+                // Cache miss - process the file
+                let result = process_file_to_result(path, &enabled_rules, &registry, &config, &plugins);
+
+                // Update cache with result; files with parse errors are
+                // processed again so their errors are reported every run
+                if use_cache && !matches!(result, FileResult::ParseErrors { .. }) {
+                    if let Ok(content_hash) = hash_file(path) {
+                        let (has_edits, edit_count) = match &result {
+                            FileResult::HasChanges { edits, .. } => (true, edits.len()),
+                            FileResult::NoChanges => (false, 0),
+                            _ => (false, 0),
+                        };
+                        let mut cache_guard = cache.lock().unwrap();
+                        cache_guard.update(path.clone(), content_hash, rules_hash, has_edits, edit_count);
+                    }
                 }
-            }
 
-            if let Some(ref pb) = progress {
-                pb.inc(1);
-            }
+                if let Some(ref pb) = progress {
+                    pb.inc(1);
+                }
 
-            result
-        })
-        .collect();
+                result
+            })
+            .collect()
+    };
 
     // Clear progress bar before output
     if let Some(pb) = progress {
@@ -680,6 +770,12 @@ fn run() -> Result<ExitCode> {
     let mut reporter = Reporter::new(reporter_format, cli.verbose && !cli.generate_baseline);
     reporter.set_enabled_rules(enabled_rules.iter().cloned().collect());
 
+    // Plugin findings without a rule of their own carry the plugin's name
+    let baseline_rules: HashSet<String> = enabled_rules.iter().cloned().chain(plugins.names()).collect();
+    let mut baseline_matcher = loaded_baseline
+        .as_ref()
+        .map(|loaded| baseline::BaselineMatcher::new(loaded, baseline_rules.clone()));
+
     // Collect data for baseline generation if requested
     let mut baseline_data: Vec<(String, Vec<EditInfo>, Option<String>)> = Vec::new();
 
@@ -697,15 +793,20 @@ fn run() -> Result<ExitCode> {
     // Report file results
     for (result, path) in sorted_results {
         // Apply baseline filtering if baseline is loaded
-        let result = if let Some(ref baseline) = loaded_baseline {
-            apply_baseline_filter(result, path, baseline)
+        let result = if let Some(matcher) = baseline_matcher.as_mut() {
+            matcher.skip_rules(&path.display().to_string(), config.rules_skipped_on(path, &baseline_rules));
+            apply_baseline_filter(result, path, matcher)
         } else {
             result
         };
 
         // Collect for baseline generation
         if cli.generate_baseline {
-            if let FileResult::HasChanges { ref edits, ref old_source, .. } = result {
+            let changes = match &result {
+                FileResult::ParseErrors { result, .. } => result.as_ref(),
+                result => result,
+            };
+            if let FileResult::HasChanges { edits, old_source, .. } = changes {
                 baseline_data.push((
                     path.display().to_string(),
                     edits.clone(),
@@ -714,7 +815,16 @@ fn run() -> Result<ExitCode> {
             }
         }
 
-        report_result(path, result, fix_mode, &mut reporter, &backup_manager, cli.verify)?;
+        report_result(path, result, fix_mode, &mut reporter, &mut backup_manager, cli.verify)?;
+    }
+
+    if let (Some(matcher), Some(baseline_path)) = (&baseline_matcher, &cli.baseline) {
+        baseline::finish(matcher, baseline_path, cli.update_baseline)?;
+    }
+
+    // Write the backup session manifest
+    if let Err(e) = backup_manager.finish_session() {
+        eprintln!("{}: Failed to save backup session: {:#}", "Warning".yellow(), e);
     }
 
     // Save cache
@@ -750,11 +860,11 @@ fn run() -> Result<ExitCode> {
 }
 
 /// Apply baseline filtering to a file result
-fn apply_baseline_filter(result: FileResult, path: &PathBuf, baseline: &baseline::Baseline) -> FileResult {
+fn apply_baseline_filter(result: FileResult, path: &Path, matcher: &mut baseline::BaselineMatcher) -> FileResult {
+    let path_str = path.display().to_string();
     match result {
         FileResult::HasChanges { edits, old_source, new_source } => {
-            let path_str = path.display().to_string();
-            let filtered_edits = baseline.filter_edits(&path_str, edits, &old_source);
+            let filtered_edits = matcher.filter_edits(&path_str, edits, &old_source);
 
             if filtered_edits.is_empty() {
                 FileResult::NoChanges
@@ -769,6 +879,14 @@ fn apply_baseline_filter(result: FileResult, path: &PathBuf, baseline: &baseline
                 }
             }
         }
+        FileResult::NoChanges => {
+            matcher.mark_checked(&path_str);
+            FileResult::NoChanges
+        }
+        FileResult::ParseErrors { errors, result } => FileResult::ParseErrors {
+            errors,
+            result: Box::new(apply_baseline_filter(*result, path, matcher)),
+        },
         other => other,
     }
 }
@@ -785,8 +903,11 @@ enum FileResult {
     },
     /// Cached result with edits (we don't have the details, just the count)
     CachedWithEdits { edit_count: usize },
-    /// Parse error occurred
-    ParseError,
+    /// The file has parse errors; `result` covers the statements without them
+    ParseErrors {
+        errors: Vec<rustor_core::ParseError>,
+        result: Box<FileResult>,
+    },
     /// Other error occurred
     Error(String),
 }
@@ -797,6 +918,7 @@ fn process_file_to_result(
     enabled_rules: &HashSet<String>,
     registry: &RuleRegistry,
     config: &Config,
+    plugins: &plugin::PluginHost,
 ) -> FileResult {
     // Check if all rules should be skipped for this path
     if config.should_skip_all_rules(path) {
@@ -806,9 +928,9 @@ fn process_file_to_result(
     // Get rules to skip for this specific path
     let skip_rules = config.skipped_rules_for_path(path);
 
-    match process_file_with_registry(path, enabled_rules, registry, &skip_rules) {
-        Ok(Some(result)) => {
-            if result.edits.is_empty() {
+    match process_file_with_plugins(path, enabled_rules, registry, &skip_rules, plugins) {
+        Ok(result) => {
+            let file_result = if result.edits.is_empty() {
                 FileResult::NoChanges
             } else {
                 FileResult::HasChanges {
@@ -816,20 +938,89 @@ fn process_file_to_result(
                     old_source: result.old_source,
                     new_source: result.new_source.unwrap_or_default(),
                 }
-            }
+            };
+            with_parse_errors(result.parse_errors, file_result)
         }
-        Ok(None) => FileResult::ParseError,
         Err(e) => FileResult::Error(format!("{:#}", e)),
     }
 }
 
+/// Attach parse errors to a file result, if there are any
+fn with_parse_errors(errors: Vec<rustor_core::ParseError>, result: FileResult) -> FileResult {
+    if errors.is_empty() {
+        result
+    } else {
+        FileResult::ParseErrors { errors, result: Box::new(result) }
+    }
+}
+
+/// Review the fixes of each file with the user (--interactive)
+///
+/// Returns one result per file, holding only the accepted fixes, for
+/// `report_result` to apply.
+fn review_files(
+    file_paths: &[PathBuf],
+    enabled_rules: &HashSet<String>,
+    registry: &RuleRegistry,
+    config: &Config,
+    config_path: Option<&Path>,
+    plugins: &plugin::PluginHost,
+) -> Result<Vec<FileResult>> {
+    let stdin = std::io::stdin();
+    let mut reviewer = review::Reviewer::new(stdin.lock(), std::io::stdout());
+
+    let mut results = Vec::new();
+    for path in file_paths {
+        if reviewer.has_quit() || config.should_skip_all_rules(path) {
+            results.push(FileResult::NoChanges);
+            continue;
+        }
+
+        let skip_rules = config.skipped_rules_for_path(path);
+        let result = match process_file_with_plugins(path, enabled_rules, registry, &skip_rules, plugins) {
+            Ok(result) => result,
+            Err(e) => {
+                results.push(FileResult::Error(format!("{:#}", e)));
+                continue;
+            }
+        };
+
+        let review = reviewer.review_file(path, &result.old_source, result.fixes)?;
+        let file_result = match review.new_source {
+            Some(new_source) => FileResult::HasChanges {
+                edits: review.applied,
+                old_source: result.old_source,
+                new_source,
+            },
+            None => FileResult::NoChanges,
+        };
+        results.push(with_parse_errors(result.parse_errors, file_result));
+    }
+
+    // Make "skip this rule for this file" answers permanent
+    if !reviewer.skip_entries().is_empty() {
+        let config_path = config_path.unwrap_or(Path::new(".rustor.toml"));
+        review::record_skips(config_path, reviewer.skip_entries())?;
+        println!(
+            "{}: {} [skip] entr{} added to {}",
+            "Config".bold(),
+            reviewer.skip_entries().len(),
+            if reviewer.skip_entries().len() == 1 { "y" } else { "ies" },
+            config_path.display()
+        );
+    }
+    println!();
+
+    Ok(results)
+}
+
 /// Report a file result and optionally apply fixes
 fn report_result(
     path: &PathBuf,
     result: FileResult,
     fix_mode: bool,
     reporter: &mut Reporter,
-    backup_manager: &backup::BackupManager,
+    backup_manager: &mut backup::BackupManager,
     verify: bool,
 ) -> Result<()> {
     match result {
@@ -841,7 +1032,7 @@ fn report_result(
             old_source,
             new_source,
         } => {
-            if fix_mode {
+            if fix_mode && new_source != old_source {
                 // Create backup before modifying
                 let backup_path = backup_manager.backup_file(path)?;
 
@@ -850,7 +1041,7 @@ fn report_result(
 
                 // Verify if requested
                 if verify {
-                    if !backup::verify_php_file(path)? {
+                    if !backup::verify_php_file(path, &old_source)? {
                         // Restore from backup on parse failure
                         if let Some(bp) = backup_path {
                             backup_manager.restore_file(path, &bp)?;
@@ -863,6 +1054,12 @@ fn report_result(
                     }
                 }
 
+                // Remember the fix so `rustor undo` can revert it
+                if let Some(bp) = &backup_path {
+                    let rules = edits.iter().map(|e| e.rule.clone()).collect();
+                    backup_manager.record_fix(path, bp, rules, &new_source);
+                }
+
                 reporter.report_fix(path, edits);
             } else {
                 reporter.report_check(path, edits, &old_source, &new_source);
@@ -872,8 +1069,14 @@ fn report_result(
             // File was cached with edits - report as having changes but no details
             reporter.report_cached(path, edit_count);
         }
-        FileResult::ParseError => {
-            reporter.report_error(path, "Parse error, skipping");
+        FileResult::ParseErrors { errors, result } => {
+            reporter.report_parse_errors(path, &errors);
+            // Like a fix that fails --verify, fixes to a file that doesn't parse aren't written
+            if fix_mode && matches!(*result, FileResult::HasChanges { .. }) {
+                reporter.report_error(path, "File has parse errors, fixes not applied");
+                return Ok(());
+            }
+            report_result(path, *result, fix_mode, reporter, backup_manager, verify)?;
         }
         FileResult::Error(msg) => {
             reporter.report_error(path, &msg);
@@ -882,47 +1085,115 @@ fn report_result(
     Ok(())
 }
 
+/// Print discovered plugins, marking the enabled ones
+fn list_plugins(manager: &plugin::PluginManager, enabled: &[String]) {
+    println!("{} {}", "Plugins in".bold(), manager.plugin_dir().display().to_string().bold());
+
+    let mut names = manager.plugin_names();
+    names.sort();
+    if names.is_empty() {
+        println!("  {}", "(none)".dimmed());
+    }
+    for name in names {
+        let manifest = &manager.plugins()[name].manifest;
+        let status = if enabled.iter().any(|e| e == name) { " [enabled]" } else { "" };
+        println!(
+            "  {} {} - {}{}",
+            name.green(),
+            manifest.version.dimmed(),
+            manifest.description,
+            status.yellow()
+        );
+    }
+}
+
 /// Run fixer-only mode (formatting fixers, no refactoring rules)
-fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
-    use rustor_fixer::{FixerRegistry, FixerConfig, PhpCsFixerConfig};
-    use rustor_fixer::config::LineEnding;
+fn run_fixer_mode(cli: &Cli, config: &Config, output_format: OutputFormat) -> Result<ExitCode> {
     use rayon::prelude::*;
 
-    let fixer_registry = FixerRegistry::new();
+    let fixer_config = cli.fixer_config.clone().or_else(|| config.fixer.config.as_deref().map(|p| config.resolve(p)));
+    let setup = fixer::FixerSetup::load(
+        fixer_config.as_deref(),
+        cli.fixer_preset.as_deref(),
+        cli.allow_risky,
+        &config.fixer,
+    )?;
+    setup.warn_skipped_risky();
+
+    let lines = cli
+        .lines
+        .iter()
+        .map(|value| fixer::parse_line_range(value))
+        .collect::<Result<Vec<_>>>()?;
+    let repo_root = match &cli.since {
+        Some(_) => Some(git::find_repo_root()?),
+        None => None,
+    };
 
-    // Get fixer preset or use all fixers
-    let fixer_preset = cli.fixer_preset.as_deref().unwrap_or("psr12");
+    // Run the fixers on one file: (original source, fixed source, edits)
+    let fix_file = |path: &Path| -> Option<(String, String, Vec<rustor_core::Edit>)> {
+        let source = std::fs::read_to_string(path).ok()?;
+        let mut file_config = setup.file_config(path);
+
+        // Restrict fixes to --lines, or to the lines changed since --since
+        if !lines.is_empty() {
+            file_config.to_mut().ranges = Some(fixer::line_byte_ranges(&source, &lines));
+        } else if let (Some(repo_root), Some(ref_name)) = (&repo_root, &cli.since) {
+            let changed = match git::get_changed_lines_since(repo_root, ref_name, path) {
+                Ok(changed) => changed,
+                Err(e) => {
+                    eprintln!("{}: {:#}", "Warning".yellow(), e);
+                    return None;
+                }
+            };
+            file_config.to_mut().ranges = Some(fixer::line_byte_ranges(&source, &changed));
+        }
 
-    // Create fixer config and get enabled rules from PHP config file if provided
-    let (fixer_config, enabled_rules): (FixerConfig, Option<Vec<String>>) = if let Some(config_path) = &cli.fixer_config {
-        let php_config = PhpCsFixerConfig::from_file(config_path)
-            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", config_path.display(), e))?;
-        let config = fixer::config_from_php_cs_fixer(&php_config);
-        // Get enabled rules from the PHP config
-        let rules: Vec<String> = php_config.rules.keys().cloned().collect();
-        (config, if rules.is_empty() { None } else { Some(rules) })
-    } else {
-        (FixerConfig {
-            line_ending: LineEnding::Lf,
-            ..Default::default()
-        }, None)
+        let (fixed_source, edits) = setup.check(&source, &file_config);
+        Some((source, fixed_source, edits))
     };
 
+    // Exclusions of the fixer config's Finder, [fixer] and [paths]
+    let mut filter = setup.filter.clone();
+    filter.extend(PathFilter::new(Vec::new(), config.paths.exclude.clone()));
+
+    // Watch mode reports violations as files change
+    let apply_fixes = cli.fix;
+    if cli.watch {
+        if apply_fixes {
+            anyhow::bail!("--fixer --watch only reports violations and cannot be combined with --fix");
+        }
+        if cli.paths.is_empty() {
+            anyhow::bail!("--fixer --watch needs paths to watch");
+        }
+
+        let paths: Vec<PathBuf> = cli.paths.iter().map(|p| watch::canonical(p)).collect();
+        let mut target = fixer::FixerWatch {
+            paths: paths.clone(),
+            filter,
+            check: |path: &Path| match fix_file(path) {
+                Some((source, _, edits)) => fixer::edit_findings(path, &source, &edits),
+                None => Vec::new(),
+            },
+        };
+        let watch_config = watch::WatchConfig {
+            paths,
+            format: output_format,
+            verbose: cli.verbose,
+            clear_screen: cli.clear,
+            summary: cli.summary,
+            ..Default::default()
+        };
+        watch::run_watch_incremental(&watch_config, &mut target)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
     // Collect PHP files
     let mut files: Vec<PathBuf> = Vec::new();
-    for path in &cli.paths {
-        if path.is_file() {
-            files.push(path.clone());
-        } else if path.is_dir() {
-            for entry in walkdir::WalkDir::new(path)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().extension().is_some_and(|ext| ext == "php"))
-            {
-                files.push(entry.path().to_path_buf());
-            }
-        }
+    if let (Some(repo_root), Some(ref_name), true) = (&repo_root, &cli.since, cli.paths.is_empty()) {
+        files = git::get_changed_files_since(repo_root, ref_name)?;
     }
+    files.extend(filter.php_files(&cli.paths));
 
     if files.is_empty() {
         if output_format == OutputFormat::Text {
@@ -932,26 +1203,20 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
     }
 
     let file_count = files.len();
+    let use_baseline = cli.baseline.is_some() || cli.generate_baseline;
+    if use_baseline && apply_fixes {
+        anyhow::bail!("--fixer baselines only filter reported violations and cannot be combined with --fix");
+    }
 
     // Process files in parallel
-    let apply_fixes = cli.fix;
-    let results: Vec<(PathBuf, Vec<rustor_core::Edit>, bool)> = files
+    // (path, original source, fixed source, edits, written)
+    let results: Vec<(PathBuf, String, String, Vec<rustor_core::Edit>, bool)> = files
         .par_iter()
         .filter_map(|path| {
-            let source = match std::fs::read_to_string(path) {
-                Ok(s) => s,
-                Err(_) => return None,
-            };
-
-            // Use enabled rules from config if available, otherwise use preset
-            let (fixed_source, edits) = if let Some(ref rules) = enabled_rules {
-                let rule_refs: Vec<&str> = rules.iter().map(|s| s.as_str()).collect();
-                fixer_registry.check(&source, &rule_refs, &fixer_config)
-            } else {
-                fixer_registry.check_preset(&source, fixer_preset, &fixer_config)
-            };
+            let (source, fixed_source, edits) = fix_file(path)?;
 
-            if edits.is_empty() {
+            // Fixers can undo each other's edits, so only the result counts
+            if fixed_source == source {
                 None
             } else {
                 // Apply fixes if requested
@@ -965,11 +1230,55 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
                 } else {
                     false
                 };
-                Some((path.clone(), edits, fixed))
+                Some((path.clone(), source, fixed_source, edits, fixed))
             }
         })
         .collect();
 
+    let edit_rule = |edit: &rustor_core::Edit| edit.rule.as_deref().unwrap_or("fixer").to_string();
+    if cli.generate_baseline {
+        let data: Vec<(String, Vec<EditInfo>, Option<String>)> = results
+            .iter()
+            .map(|(path, source, _, edits, _)| {
+                let edits = edits
+                    .iter()
+                    .map(|edit| EditInfo {
+                        rule: edit_rule(edit),
+                        line: fixer::edit_lines(source, edit).0,
+                        column: 1,
+                        message: edit.message.clone(),
+                    })
+                    .collect();
+                (path.display().to_string(), edits, Some(source.clone()))
+            })
+            .collect();
+        println!("{}", baseline::Baseline::generate(&data).to_json()?);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Only report violations that are not in the baseline
+    let loaded_baseline = cli.baseline.as_deref().map(baseline::Baseline::load).transpose()?;
+    let results = match (&loaded_baseline, &cli.baseline) {
+        (Some(loaded), Some(baseline_path)) => {
+            let mut matcher = baseline::BaselineMatcher::new(loaded, setup.rule_names());
+            for path in &files {
+                matcher.mark_checked(&path.display().to_string());
+            }
+            let results: Vec<_> = results
+                .into_iter()
+                .filter_map(|(path, source, fixed_source, edits, fixed)| {
+                    let edits = matcher.filter(&path.display().to_string(), &source, edits, |edit| {
+                        (edit.rule.as_deref().unwrap_or("fixer"), fixer::edit_lines(&source, edit).0)
+                    });
+                    (!edits.is_empty()).then_some((path, source, fixed_source, edits, fixed))
+                })
+                .collect();
+            baseline::finish(&matcher, baseline_path, cli.update_baseline)?;
+            results
+        }
+        _ => results,
+    };
+
     // Output results
     let mut total_edits = 0;
     let mut files_with_changes = 0;
@@ -982,17 +1291,26 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
 
             let file_results: Vec<_> = results
                 .iter()
-                .map(|(path, edits, fixed)| {
+                .map(|(path, source, _, edits, fixed)| {
                     total_edits += edits.len();
                     files_with_changes += 1;
                     if *fixed { files_fixed += 1; }
                     json!({
                         "path": path.display().to_string(),
                         "fixed": fixed,
+                        "applied_fixers": fixer::applied_fixers(source, edits).iter().map(|f| {
+                            json!({
+                                "name": f.name,
+                                "lines": f.lines.iter().map(|(start, end)| json!([start, end])).collect::<Vec<_>>(),
+                            })
+                        }).collect::<Vec<_>>(),
                         "edits": edits.iter().map(|e| {
+                            let (line, end_line) = fixer::edit_lines(source, e);
                             json!({
                                 "rule": e.rule.as_deref().unwrap_or("unknown"),
                                 "message": e.message,
+                                "line": line,
+                                "end_line": end_line,
                             })
                         }).collect::<Vec<_>>()
                     })
@@ -1013,7 +1331,7 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
             println!("{}", serde_json::to_string_pretty(&output).unwrap());
         }
         OutputFormat::Text => {
-            for (path, edits, fixed) in &results {
+            for (path, source, _, edits, fixed) in &results {
                 files_with_changes += 1;
                 total_edits += edits.len();
                 if *fixed { files_fixed += 1; }
@@ -1024,8 +1342,10 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
                     status
                 );
                 for edit in edits {
+                    let (line, _) = fixer::edit_lines(source, edit);
                     println!(
-                        "  {} {}",
+                        "  {} {} {}",
+                        format!("line {}:", line).dimmed(),
                         format!("[{}]", edit.rule.as_deref().unwrap_or("fixer")).yellow(),
                         edit.message
                     );
@@ -1050,25 +1370,19 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
             }
         }
         OutputFormat::Diff => {
-            for (path, edits, _) in &results {
-                let source = std::fs::read_to_string(path).unwrap_or_default();
-                if let Ok(fixed) = rustor_core::apply_edits(&source, edits) {
-                    println!("--- a/{}", path.display());
-                    println!("+++ b/{}", path.display());
-                    // Simple diff output
-                    for (i, (old, new)) in source.lines().zip(fixed.lines()).enumerate() {
-                        if old != new {
-                            println!("@@ -{},{} +{},{} @@", i + 1, 1, i + 1, 1);
-                            println!("-{}", old);
-                            println!("+{}", new);
-                        }
-                    }
-                }
+            for (path, source, fixed_source, edits, _) in &results {
+                // Like PHP-CS-Fixer's `--diff`, name the fixers before the diff
+                let names: Vec<String> = fixer::applied_fixers(source, edits)
+                    .into_iter()
+                    .map(|f| f.name)
+                    .collect();
+                println!("# Applied fixers: {}", names.join(", "));
+                output::print_unified_diff(path, source, fixed_source);
             }
         }
         _ => {
             // For other formats, just output basic info
-            for (path, edits, _) in &results {
+            for (path, _, _, edits, _) in &results {
                 println!("{}: {} edit(s)", path.display(), edits.len());
             }
         }
@@ -1076,3 +1390,59 @@ fn run_fixer_mode(cli: &Cli, output_format: OutputFormat) -> Result<ExitCode> {
 
     Ok(ExitCode::SUCCESS)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from(["rustor"].iter().chain(args)).unwrap()
+    }
+
+    #[test]
+    fn test_subcommand_names_as_paths() {
+        assert!(matches!(parse(&["check", "src"]).command, Some(Command::Check(_))));
+        assert!(matches!(parse(&["analyze"]).command, Some(Command::Analyze(_))));
+
+        // After another argument or `--`, a subcommand name is a path
+        for args in [&["--fix", "check"][..], &["src", "analyze"], &["--", "check"]] {
+            let cli = parse(args);
+            assert!(cli.command.is_none());
+            assert!(cli.paths.contains(&PathBuf::from(args[1])));
+        }
+    }
+
+    #[test]
+    fn test_fix_skips_files_with_parse_errors() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let path = temp.path().join("broken.php");
+        let source = "<?php\n$a = ;\n$b = sizeof([]);\n";
+        std::fs::write(&path, source).unwrap();
+
+        let result = process_file_with_plugins(
+            &path,
+            &HashSet::from(["sizeof".to_string()]),
+            &RuleRegistry::new(),
+            &HashSet::new(),
+            &plugin::PluginHost::default(),
+        )
+        .unwrap();
+        assert_eq!(result.parse_errors.len(), 1);
+        let file_result = with_parse_errors(
+            result.parse_errors,
+            FileResult::HasChanges {
+                edits: result.edits,
+                old_source: result.old_source,
+                new_source: result.new_source.unwrap(),
+            },
+        );
+
+        let mut reporter = Reporter::new(OutputFormat::Json, false);
+        let mut backup_manager = backup::BackupManager::new(temp.path().join("backups"), false);
+        report_result(&path, file_result, true, &mut reporter, &mut backup_manager, false).unwrap();
+
+        // The parse error and the skipped file are reported, the file is unchanged
+        assert_eq!(reporter.summary().errors, 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), source);
+    }
+}
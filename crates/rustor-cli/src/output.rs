@@ -3,6 +3,7 @@
 //! Supports text (colored terminal), JSON, SARIF, HTML, and unified diff output formats.
 
 use colored::*;
+use rustor_core::{ParseError, PARSE_ERROR_IDENTIFIER};
 use serde::Serialize;
 use std::path::Path;
 
@@ -51,6 +52,8 @@ pub struct FileResult {
     pub edits: Vec<EditInfo>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parse_errors: Vec<EditInfo>,
 }
 
 impl FileResult {
@@ -59,6 +62,7 @@ impl FileResult {
             path: path.display().to_string(),
             edits,
             error: None,
+            parse_errors: Vec::new(),
         }
     }
 
@@ -67,6 +71,7 @@ impl FileResult {
             path: path.display().to_string(),
             edits: Vec::new(),
             error: Some(error),
+            parse_errors: Vec::new(),
         }
     }
 
@@ -106,6 +111,7 @@ pub struct ExtendedFileResult {
     pub old_source: Option<String>,
     pub new_source: Option<String>,
     pub error: Option<String>,
+    pub parse_errors: Vec<EditInfo>,
 }
 
 /// Reporter for accumulating and outputting results
@@ -116,6 +122,8 @@ pub struct Reporter {
     extended_results: Vec<ExtendedFileResult>,
     summary: Summary,
     enabled_rules: Vec<String>,
+    /// Parse errors of the file reported next, for its JSON entry
+    pending_parse_errors: Vec<EditInfo>,
 }
 
 impl Reporter {
@@ -127,6 +135,7 @@ impl Reporter {
             extended_results: Vec::new(),
             summary: Summary::default(),
             enabled_rules: Vec::new(),
+            pending_parse_errors: Vec::new(),
         }
     }
 
@@ -143,7 +152,7 @@ impl Reporter {
             if self.verbose && self.format == OutputFormat::Text {
                 println!("{}: No changes needed", path.display());
             }
-            self.results.push(FileResult::success(path, vec![]));
+            self.push_result(FileResult::success(path, vec![]));
             return;
         }
 
@@ -188,9 +197,10 @@ impl Reporter {
             old_source: Some(old_source.to_string()),
             new_source: Some(new_source.to_string()),
             error: None,
+            parse_errors: Vec::new(),
         });
 
-        self.results.push(FileResult::success(path, edits));
+        self.push_result(FileResult::success(path, edits));
     }
 
     /// Report a file after applying fixes
@@ -201,7 +211,7 @@ impl Reporter {
             if self.verbose && self.format == OutputFormat::Text {
                 println!("{}: No changes needed", path.display());
             }
-            self.results.push(FileResult::success(path, vec![]));
+            self.push_result(FileResult::success(path, vec![]));
             return;
         }
 
@@ -218,7 +228,7 @@ impl Reporter {
             println!();
         }
 
-        self.results.push(FileResult::success(path, edits));
+        self.push_result(FileResult::success(path, edits));
     }
 
     /// Report a file that was skipped (no changes, not verbose)
@@ -227,7 +237,7 @@ impl Reporter {
         if self.verbose && self.format == OutputFormat::Text {
             println!("{}: No changes needed", path.display());
         }
-        self.results.push(FileResult::success(path, vec![]));
+        self.push_result(FileResult::success(path, vec![]));
     }
 
     /// Report a file with cached results (has edits but no details available)
@@ -247,7 +257,67 @@ impl Reporter {
         }
 
         // For JSON output, we don't have detailed edit info
-        self.results.push(FileResult::success(path, vec![]));
+        self.push_result(FileResult::success(path, vec![]));
+    }
+
+    /// Report the parse errors of a file
+    ///
+    /// Rules still ran on the rest of the file, so its changes are reported
+    /// afterwards as usual; in JSON output they share the file's entry.
+    pub fn report_parse_errors(&mut self, path: &Path, errors: &[ParseError]) {
+        self.summary.errors += errors.len();
+
+        let errors: Vec<EditInfo> = errors
+            .iter()
+            .map(|error| EditInfo {
+                rule: PARSE_ERROR_IDENTIFIER.to_string(),
+                line: error.line,
+                column: error.column,
+                message: error.message.clone(),
+            })
+            .collect();
+
+        for error in &errors {
+            match self.format {
+                OutputFormat::Text => {
+                    eprintln!(
+                        "{}: {}:{}:{} - {}",
+                        "Parse error".red(),
+                        path.display(),
+                        error.line,
+                        error.column,
+                        error.message
+                    );
+                }
+                OutputFormat::Github => {
+                    println!(
+                        "::error file={},line={},col={}::{} ({})",
+                        path.display(),
+                        error.line,
+                        error.column,
+                        error.message,
+                        error.rule
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        self.extended_results.push(ExtendedFileResult {
+            path: path.display().to_string(),
+            edits: Vec::new(),
+            old_source: None,
+            new_source: None,
+            error: None,
+            parse_errors: errors.clone(),
+        });
+        self.pending_parse_errors = errors;
+    }
+
+    /// Add a file's JSON entry, with the parse errors reported for it
+    fn push_result(&mut self, mut result: FileResult) {
+        result.parse_errors = std::mem::take(&mut self.pending_parse_errors);
+        self.results.push(result);
     }
 
     /// Report an error processing a file
@@ -264,7 +334,7 @@ impl Reporter {
             );
         }
 
-        self.results.push(FileResult::error(path, error.to_string()));
+        self.push_result(FileResult::error(path, error.to_string()));
     }
 
     /// Print final summary/output
@@ -347,7 +417,7 @@ fn print_diff(old: &str, new: &str) {
 }
 
 /// Print unified diff format (standard diff -u compatible)
-fn print_unified_diff(path: &Path, old: &str, new: &str) {
+pub(crate) fn print_unified_diff(path: &Path, old: &str, new: &str) {
     use similar::{ChangeTag, TextDiff};
 
     let diff = TextDiff::from_lines(old, new);
@@ -374,6 +444,27 @@ fn print_unified_diff(path: &Path, old: &str, new: &str) {
     }
 }
 
+/// Write a colored diff with `context` unchanged lines around each change
+/// and line numbers of the old source
+pub(crate) fn write_context_diff(out: &mut dyn std::io::Write, old: &str, new: &str, context: usize) -> std::io::Result<()> {
+    use similar::{ChangeTag, TextDiff};
+
+    let diff = TextDiff::from_lines(old, new);
+    for hunk in diff.unified_diff().context_radius(context).iter_hunks() {
+        for change in hunk.iter_changes() {
+            let line = change.to_string_lossy();
+            let line = line.trim_end_matches(['\n', '\r']);
+            let number = change.old_index().map(|i| format!("{:>5}", i + 1)).unwrap_or_else(|| " ".repeat(5));
+            match change.tag() {
+                ChangeTag::Delete => writeln!(out, "{} {}", number.dimmed(), format!("- {}", line).red())?,
+                ChangeTag::Insert => writeln!(out, "{} {}", number.dimmed(), format!("+ {}", line).green())?,
+                ChangeTag::Equal => writeln!(out, "{}   {}", number.dimmed(), line)?,
+            }
+        }
+    }
+    Ok(())
+}
+
 // ==================== SARIF Output ====================
 
 /// SARIF 2.1.0 output structure
@@ -489,7 +580,7 @@ struct SarifContent {
 /// Generate SARIF 2.1.0 output
 fn generate_sarif(results: &[ExtendedFileResult], enabled_rules: &[String]) -> SarifOutput {
     // Build rule definitions
-    let rules: Vec<SarifRule> = enabled_rules
+    let mut rules: Vec<SarifRule> = enabled_rules
         .iter()
         .map(|rule| SarifRule {
             id: rule.clone(),
@@ -499,14 +590,25 @@ fn generate_sarif(results: &[ExtendedFileResult], enabled_rules: &[String]) -> S
             help_uri: None,
         })
         .collect();
+    if results.iter().any(|file| !file.parse_errors.is_empty()) {
+        rules.push(SarifRule {
+            id: PARSE_ERROR_IDENTIFIER.to_string(),
+            short_description: SarifMessage {
+                text: "PHP parse error".to_string(),
+            },
+            help_uri: None,
+        });
+    }
 
-    // Build results
+    // Build results; parse errors are errors, rule findings warnings
     let sarif_results: Vec<SarifResult> = results
         .iter()
         .flat_map(|file| {
-            file.edits.iter().map(move |edit| SarifResult {
+            let parse_errors = file.parse_errors.iter().map(|error| (error, "error"));
+            let edits = file.edits.iter().map(|edit| (edit, "warning"));
+            parse_errors.chain(edits).map(move |(edit, level)| SarifResult {
                 rule_id: edit.rule.clone(),
-                level: "warning",
+                level,
                 message: SarifMessage {
                     text: edit.message.clone(),
                 },
@@ -826,19 +928,22 @@ fn generate_checkstyle(results: &[ExtendedFileResult]) -> String {
 
     // Group edits by file
     for file in results {
-        if file.edits.is_empty() {
+        if file.edits.is_empty() && file.parse_errors.is_empty() {
             continue;
         }
 
         xml.push_str(&format!(r#"  <file name="{}">
 "#, xml_escape(&file.path)));
 
-        for edit in &file.edits {
+        let parse_errors = file.parse_errors.iter().map(|error| (error, "error"));
+        let edits = file.edits.iter().map(|edit| (edit, "warning"));
+        for (edit, severity) in parse_errors.chain(edits) {
             xml.push_str(&format!(
-                r#"    <error line="{}" column="{}" severity="warning" message="{}" source="rustor.{}"/>
+                r#"    <error line="{}" column="{}" severity="{}" message="{}" source="rustor.{}"/>
 "#,
                 edit.line,
                 edit.column,
+                severity,
                 xml_escape(&edit.message),
                 edit.rule
             ));
@@ -852,7 +957,7 @@ fn generate_checkstyle(results: &[ExtendedFileResult]) -> String {
 }
 
 /// Escape XML special characters
-fn xml_escape(s: &str) -> String {
+pub(crate) fn xml_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -944,5 +1049,27 @@ mod tests {
         assert!(json.contains("\"version\":\"0.2.0\""));
         assert!(json.contains("\"files_processed\":10"));
         assert!(json.contains("\"rule\":\"array_push\""));
+        assert!(!json.contains("parse_errors"));
+    }
+
+    #[test]
+    fn test_parse_errors_in_sarif() {
+        let path = Path::new("broken.php");
+        let mut reporter = Reporter::new(OutputFormat::Sarif, false);
+        let arena = bumpalo::Bump::new();
+        let parsed = rustor_core::parse(&arena, mago_database::file::FileId::zero(), "<?php\n$a = ;\n");
+        reporter.report_parse_errors(path, &parsed.errors);
+        reporter.report_skipped(path);
+
+        assert_eq!(reporter.summary().errors, 1);
+        assert_eq!(reporter.results.len(), 1);
+        assert_eq!(reporter.results[0].parse_errors[0].line, 2);
+
+        let sarif = serde_json::to_value(generate_sarif(&reporter.extended_results, &[])).unwrap();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "parse.error");
+        assert_eq!(result["level"], "error");
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startLine"], 2);
+        assert_eq!(result["locations"][0]["physicalLocation"]["region"]["startColumn"], 6);
     }
 }
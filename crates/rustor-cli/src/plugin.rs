@@ -1,30 +1,65 @@
 //! Plugin system for custom rules
 //!
-//! Plugins are external executables that can analyze PHP code and suggest edits.
+//! Plugins are external executables that can analyze PHP code, suggest edits
+//! and report diagnostics. They can be written in any language, e.g. PHP or
+//! Python.
 //!
 //! Plugin manifest format (plugin.toml):
 //! ```toml
 //! name = "my-plugin"
 //! version = "1.0.0"
 //! description = "My custom rule"
-//! command = "./my-plugin"
+//! command = "php"
+//! args = ["plugin.php"]
 //! min_php_version = "8.0"
 //! category = "custom"
 //! ```
 //!
 //! Plugin protocol:
-//! 1. Plugin receives JSON on stdin: { "source": "<?php ...", "file": "path/to/file.php" }
-//! 2. Plugin outputs JSON on stdout: { "edits": [...] }
 //!
-//! Edit format:
-//! { "start": 10, "end": 20, "replacement": "new code", "message": "description" }
+//! A plugin is started once per run and speaks newline-delimited JSON-RPC 2.0
+//! on stdin/stdout: one request or response per line. Anything it writes to
+//! stderr is passed through.
+//!
+//! 1. `initialize` with `{ "root": "/project", "rustorVersion": "0.2.0" }`
+//!    returns `{ "capabilities": ["edits", "diagnostics", "per-file"] }`.
+//!    `edits` and `diagnostics` declare what the plugin reports; `per-file`
+//!    (the default) or `project` declare how it is called.
+//! 2. `per-file` plugins get `checkFile` with `{ "file": "src/a.php", "source": "<?php ..." }`
+//!    for every file and return `{ "edits": [...], "diagnostics": [...] }`.
+//! 3. `project` plugins get a single `checkProject` with `{ "files": ["src/a.php", ...] }`
+//!    and return `{ "files": [{ "file": "src/a.php", "edits": [...], "diagnostics": [...] }] }`.
+//! 4. `shutdown` returns `null`, after which stdin is closed.
+//!
+//! A plugin that doesn't answer a request within `[plugins] timeout` seconds
+//! (default: 30) is killed.
+//!
+//! Edit format (byte offsets into the source):
+//! { "start": 10, "end": 20, "replacement": "new code", "message": "description", "rule": "optional-id" }
+//!
+//! Diagnostic format (1-based line and column):
+//! { "line": 3, "column": 5, "message": "description", "rule": "optional-id" }
+//!
+//! Edits and diagnostics are reported under the plugin name unless they name
+//! a `rule`, which is also what ignore directives and `[skip]` match.
 
+use mago_database::file::FileId;
+use mago_span::{Position, Span};
+use rustor_core::Edit;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{self, BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::output::EditInfo;
 
 /// Plugin manifest (plugin.toml)
 #[derive(Debug, Clone, Deserialize)]
@@ -42,20 +77,28 @@ pub struct PluginManifest {
     pub category: String,
 }
 
-/// Input sent to plugin
-#[derive(Debug, Serialize)]
-pub struct PluginInput {
-    pub source: String,
-    pub file: String,
-}
-
-/// Output from plugin
-#[derive(Debug, Deserialize)]
+/// Findings reported by a plugin for one file
+#[derive(Debug, Default, Deserialize)]
 pub struct PluginOutput {
     #[serde(default)]
     pub edits: Vec<PluginEdit>,
     #[serde(default)]
-    pub error: Option<String>,
+    pub diagnostics: Vec<PluginDiagnostic>,
+}
+
+/// Findings of a project plugin for one file
+#[derive(Debug, Deserialize)]
+struct ProjectFileOutput {
+    file: PathBuf,
+    #[serde(flatten)]
+    output: PluginOutput,
+}
+
+/// Output of `checkProject`
+#[derive(Debug, Deserialize)]
+struct ProjectOutput {
+    #[serde(default)]
+    files: Vec<ProjectFileOutput>,
 }
 
 /// Edit suggested by plugin
@@ -66,6 +109,66 @@ pub struct PluginEdit {
     pub replacement: String,
     #[serde(default)]
     pub message: String,
+    #[serde(default)]
+    pub rule: Option<String>,
+}
+
+/// Diagnostic reported by plugin, without a fix
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginDiagnostic {
+    pub line: usize,
+    #[serde(default = "default_column")]
+    pub column: usize,
+    pub message: String,
+    #[serde(default)]
+    pub rule: Option<String>,
+}
+
+fn default_column() -> usize {
+    1
+}
+
+/// How a plugin is called
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PluginScope {
+    /// One `checkFile` request per file
+    #[default]
+    PerFile,
+    /// One `checkProject` request for all files
+    Project,
+}
+
+/// Capabilities a plugin declares in the `initialize` handshake
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    pub edits: bool,
+    pub diagnostics: bool,
+    pub scope: PluginScope,
+}
+
+impl Capabilities {
+    /// Parse the capability list of an `initialize` result
+    pub fn parse(names: &[String]) -> Result<Self, String> {
+        let mut capabilities = Capabilities::default();
+        let mut scopes = Vec::new();
+
+        for name in names {
+            match name.as_str() {
+                "edits" => capabilities.edits = true,
+                "diagnostics" => capabilities.diagnostics = true,
+                "per-file" => scopes.push(PluginScope::PerFile),
+                "project" => scopes.push(PluginScope::Project),
+                // Unknown capabilities are ignored for forward compatibility
+                _ => {}
+            }
+        }
+
+        if scopes.len() > 1 {
+            return Err("declares both \"per-file\" and \"project\"".to_string());
+        }
+        capabilities.scope = scopes.pop().unwrap_or_default();
+        Ok(capabilities)
+    }
 }
 
 /// Plugin manager
@@ -102,6 +205,21 @@ impl PluginManager {
         }
     }
 
+    /// Plugin manager for `dir`, else the config's `[plugins] dir`, with its plugins discovered
+    pub fn discover(dir: Option<PathBuf>, config: &Config) -> Result<Self, String> {
+        let mut manager = match dir.or_else(|| config.plugins.dir.clone().map(PathBuf::from)) {
+            Some(dir) => Self::with_dir(dir),
+            None => Self::new(),
+        };
+        manager.discover_plugins()?;
+        Ok(manager)
+    }
+
+    /// Directory plugins are discovered in
+    pub fn plugin_dir(&self) -> &Path {
+        &self.plugin_dir
+    }
+
     /// Discover and load plugins from the plugin directory
     pub fn discover_plugins(&mut self) -> Result<usize, String> {
         if !self.plugin_dir.exists() {
@@ -158,89 +276,353 @@ impl PluginManager {
         self.plugins.keys().map(|s| s.as_str()).collect()
     }
 
-    /// Run a plugin on source code
-    pub fn run_plugin(
-        &self,
-        name: &str,
-        source: &str,
-        file_path: &str,
-    ) -> Result<Vec<PluginEdit>, String> {
-        let plugin = self
-            .plugins
-            .get(name)
-            .ok_or_else(|| format!("Plugin not found: {}", name))?;
+    /// Start the named plugins and run the `initialize` handshake with each
+    ///
+    /// `root` is the project directory the plugins are told about. A plugin
+    /// that takes longer than `timeout` to answer a request is killed.
+    pub fn start(&self, names: &[String], root: &Path, timeout: Duration) -> Result<PluginHost, String> {
+        let mut running = Vec::new();
+
+        for name in names {
+            let plugin = self
+                .plugins
+                .get(name)
+                .ok_or_else(|| format!("Plugin not found: {}", name))?;
+            running.push(RunningPlugin::start(plugin, root, timeout)?);
+        }
+
+        Ok(PluginHost {
+            plugins: running,
+            project_results: HashMap::new(),
+        })
+    }
+}
+
+/// Seconds to wait for a plugin response unless `[plugins] timeout` is set
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Start the plugins enabled in the config, discovered in `dir` (default:
+/// `[plugins] dir` or ~/.rustor/plugins), with the current directory as root
+pub fn start_enabled(config: &Config, dir: Option<PathBuf>) -> Result<PluginHost, String> {
+    if config.plugins.enabled.is_empty() {
+        return Ok(PluginHost::default());
+    }
+
+    let manager = PluginManager::discover(dir, config)?;
+    if let Some(name) = config.plugins.enabled.iter().find(|name| !manager.plugins.contains_key(*name)) {
+        return Err(format!(
+            "Unknown plugin '{}' in {}. Use --list-plugins to see available plugins.",
+            name,
+            manager.plugin_dir.display()
+        ));
+    }
+    let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    let timeout = Duration::from_secs(config.plugins.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+    manager.start(&config.plugins.enabled, &root, timeout)
+}
+
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A JSON-RPC request
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    jsonrpc: &'static str,
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+/// A JSON-RPC response
+#[derive(Debug, Deserialize)]
+struct Response {
+    id: Option<u64>,
+    #[serde(default)]
+    result: Value,
+    #[serde(default)]
+    error: Option<ResponseError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseError {
+    message: String,
+}
+
+/// Pipes of a plugin process
+///
+/// Stdout is read on a helper thread, so a plugin that hangs can't block a
+/// request for longer than `timeout`.
+struct PluginProcess {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    lines: Receiver<io::Result<String>>,
+    timeout: Duration,
+    next_id: u64,
+}
+
+impl PluginProcess {
+    fn spawn(mut child: Child, timeout: Duration) -> Self {
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().expect("stdout is piped"));
+        let (sender, lines) = mpsc::channel();
+        // Ends when stdout is closed, i.e. when the plugin exits or is killed
+        thread::spawn(move || {
+            for line in stdout.lines() {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Self { child, stdin, lines, timeout, next_id: 0 }
+    }
+
+    /// Kill the process, after which every request fails
+    fn kill(&mut self) {
+        self.stdin.take();
+        let _ = self.child.kill();
+    }
+
+    /// Send a request and wait for its response
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        let request = Request { jsonrpc: "2.0", id, method, params };
+        let mut line = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+        line.push('\n');
+
+        let stdin = self.stdin.as_mut().ok_or("stdin is closed")?;
+        stdin
+            .write_all(line.as_bytes())
+            .and_then(|_| stdin.flush())
+            .map_err(|e| format!("failed to send {}: {}", method, e))?;
+
+        loop {
+            let line = match self.lines.recv_timeout(self.timeout) {
+                Ok(line) => line.map_err(|e| e.to_string())?,
+                Err(RecvTimeoutError::Timeout) => {
+                    self.kill();
+                    return Err(format!(
+                        "killed after not answering {} within {}s",
+                        method,
+                        self.timeout.as_secs_f64()
+                    ));
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err(format!("exited while handling {}", method)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response: Response =
+                serde_json::from_str(&line).map_err(|e| format!("invalid JSON-RPC message: {}", e))?;
+            // Skip notifications, the protocol doesn't define any yet
+            let Some(response_id) = response.id else {
+                continue;
+            };
+            if response_id != id {
+                return Err(format!("expected response {} but got {}", id, response_id));
+            }
+            if let Some(error) = response.error {
+                return Err(error.message);
+            }
+            return Ok(response.result);
+        }
+    }
+}
+
+impl Drop for PluginProcess {
+    fn drop(&mut self) {
+        if self.stdin.is_some() && self.request("shutdown", Value::Null).is_err() {
+            self.kill();
+        }
+        // Closing stdin lets the plugin exit its read loop
+        self.stdin.take();
+        let _ = self.child.wait();
+    }
+}
+
+/// A started plugin
+struct RunningPlugin {
+    name: String,
+    version: String,
+    capabilities: Capabilities,
+    process: Mutex<PluginProcess>,
+}
+
+impl RunningPlugin {
+    fn start(plugin: &LoadedPlugin, root: &Path, timeout: Duration) -> Result<Self, String> {
+        let name = &plugin.manifest.name;
 
         // Resolve command path
-        let command_path = if plugin.manifest.command.starts_with("./") {
-            plugin.path.join(&plugin.manifest.command[2..])
+        let command_path = if let Some(relative) = plugin.manifest.command.strip_prefix("./") {
+            plugin.path.join(relative)
         } else {
             PathBuf::from(&plugin.manifest.command)
         };
 
-        // Build input
-        let input = PluginInput {
-            source: source.to_string(),
-            file: file_path.to_string(),
-        };
-        let input_json = serde_json::to_string(&input).map_err(|e| e.to_string())?;
-
-        // Run plugin
-        let mut child = Command::new(&command_path)
+        let child = Command::new(&command_path)
             .args(&plugin.manifest.args)
             .current_dir(&plugin.path)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
+            .stderr(Stdio::inherit())
             .spawn()
             .map_err(|e| format!("Failed to run plugin {}: {}", name, e))?;
 
-        // Write input
-        if let Some(mut stdin) = child.stdin.take() {
-            stdin
-                .write_all(input_json.as_bytes())
-                .map_err(|e| e.to_string())?;
-        }
+        let mut process = PluginProcess::spawn(child, timeout);
+
+        let params = json!({ "root": root, "rustorVersion": env!("CARGO_PKG_VERSION") });
+        let result = process
+            .request("initialize", params)
+            .map_err(|e| format!("Plugin {} failed to initialize: {}", name, e))?;
+        let names: Vec<String> = serde_json::from_value(result.get("capabilities").cloned().unwrap_or_default())
+            .map_err(|e| format!("Plugin {} sent invalid capabilities: {}", name, e))?;
+        let capabilities =
+            Capabilities::parse(&names).map_err(|e| format!("Plugin {} {}", name, e))?;
+
+        Ok(Self {
+            name: name.clone(),
+            version: plugin.manifest.version.clone(),
+            capabilities,
+            process: Mutex::new(process),
+        })
+    }
 
-        // Read output
-        let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        self.process
+            .lock()
+            .unwrap()
+            .request(method, params)
+            .map_err(|e| format!("Plugin {}: {}", self.name, e))
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("Plugin {} failed: {}", name, stderr));
+    /// Check that the output only contains what the plugin declared
+    fn validate(&self, output: &PluginOutput) -> Result<(), String> {
+        if !output.edits.is_empty() && !self.capabilities.edits {
+            return Err(format!("Plugin {} returned edits without the \"edits\" capability", self.name));
         }
+        if !output.diagnostics.is_empty() && !self.capabilities.diagnostics {
+            return Err(format!(
+                "Plugin {} returned diagnostics without the \"diagnostics\" capability",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+}
 
-        // Parse output
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let plugin_output: PluginOutput =
-            serde_json::from_str(&stdout).map_err(|e| format!("Invalid plugin output: {}", e))?;
+/// Edits and diagnostics of all plugins for one file
+#[derive(Debug, Default)]
+pub struct PluginFindings {
+    /// Edits, with `rule` set to the reporting rule
+    pub edits: Vec<Edit>,
+    /// Diagnostics, with line and column resolved
+    pub diagnostics: Vec<EditInfo>,
+}
 
-        if let Some(error) = plugin_output.error {
-            return Err(format!("Plugin error: {}", error));
-        }
+/// The started plugins of a run
+///
+/// Plugins are shut down when the host is dropped. Requests to the same
+/// plugin are serialized, so the host can be shared between threads.
+#[derive(Default)]
+pub struct PluginHost {
+    plugins: Vec<RunningPlugin>,
+    /// Results of project plugins by file: (plugin name, output)
+    project_results: HashMap<PathBuf, Vec<(String, PluginOutput)>>,
+}
 
-        Ok(plugin_output.edits)
+impl PluginHost {
+    /// Whether no plugins are running
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
     }
 
-    /// Run all plugins on source code
-    pub fn run_all_plugins(
-        &self,
-        source: &str,
-        file_path: &str,
-    ) -> HashMap<String, Result<Vec<PluginEdit>, String>> {
-        let mut results = HashMap::new();
+    /// Whether a plugin needs the whole project, so a file's findings can
+    /// change without the file changing
+    pub fn has_project_plugins(&self) -> bool {
+        self.plugins.iter().any(|p| p.capabilities.scope == PluginScope::Project)
+    }
 
-        for name in self.plugins.keys() {
-            results.insert(name.clone(), self.run_plugin(name, source, file_path));
+    /// Names of the plugins
+    pub fn names(&self) -> impl Iterator<Item = String> + '_ {
+        self.plugins.iter().map(|p| p.name.clone())
+    }
+
+    /// `name@version` of each plugin, for cache invalidation
+    pub fn versions(&self) -> Vec<String> {
+        self.plugins.iter().map(|p| format!("{}@{}", p.name, p.version)).collect()
+    }
+
+    /// Run the project plugins on all files
+    ///
+    /// Their results are returned by [`Self::check_file`] afterwards.
+    pub fn check_project(&mut self, files: &[PathBuf]) -> Result<(), String> {
+        for plugin in self.plugins.iter().filter(|p| p.capabilities.scope == PluginScope::Project) {
+            let result = plugin.request("checkProject", json!({ "files": files }))?;
+            let output: ProjectOutput = serde_json::from_value(result)
+                .map_err(|e| format!("Plugin {} sent invalid output: {}", plugin.name, e))?;
+
+            for file in output.files {
+                plugin.validate(&file.output)?;
+                self.project_results
+                    .entry(file.file)
+                    .or_default()
+                    .push((plugin.name.clone(), file.output));
+            }
         }
+        Ok(())
+    }
+
+    /// Findings of all plugins for a file
+    pub fn check_file(&self, path: &Path, source: &str) -> Result<PluginFindings, String> {
+        let mut findings = PluginFindings::default();
 
-        results
+        for plugin in self.plugins.iter().filter(|p| p.capabilities.scope == PluginScope::PerFile) {
+            let result = plugin.request("checkFile", json!({ "file": path, "source": source }))?;
+            let output: PluginOutput = serde_json::from_value(result)
+                .map_err(|e| format!("Plugin {} sent invalid output: {}", plugin.name, e))?;
+            plugin.validate(&output)?;
+            add_findings(&mut findings, &plugin.name, &output, source)?;
+        }
+
+        for (name, output) in self.project_results.get(path).into_iter().flatten() {
+            add_findings(&mut findings, name, output, source)?;
+        }
+
+        Ok(findings)
     }
 }
 
-impl Default for PluginManager {
-    fn default() -> Self {
-        Self::new()
+/// Convert the output of a plugin to edits and diagnostics
+fn add_findings(findings: &mut PluginFindings, plugin: &str, output: &PluginOutput, source: &str) -> Result<(), String> {
+    for edit in &output.edits {
+        if edit.start > edit.end
+            || edit.end > source.len()
+            || !source.is_char_boundary(edit.start)
+            || !source.is_char_boundary(edit.end)
+        {
+            return Err(format!("Plugin {} returned an edit outside the file ({}..{})", plugin, edit.start, edit.end));
+        }
+
+        let span = Span::new(FileId::zero(), Position::new(edit.start as u32), Position::new(edit.end as u32));
+        let rule = edit.rule.clone().unwrap_or_else(|| plugin.to_string());
+        findings.edits.push(Edit::with_rule(span, edit.replacement.clone(), edit.message.clone(), rule));
+    }
+
+    for diagnostic in &output.diagnostics {
+        findings.diagnostics.push(EditInfo {
+            rule: diagnostic.rule.clone().unwrap_or_else(|| plugin.to_string()),
+            line: diagnostic.line,
+            column: diagnostic.column,
+            message: diagnostic.message.clone(),
+        });
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -249,6 +631,47 @@ mod tests {
     use std::fs;
     use tempfile::TempDir;
 
+    /// A Python plugin that flags `var_dump` calls
+    const PYTHON_PLUGIN: &str = r#"
+import json, sys
+
+for line in sys.stdin:
+    request = json.loads(line)
+    method, params = request["method"], request.get("params")
+    if method == "initialize":
+        result = {"capabilities": ["edits", "diagnostics", "per-file"]}
+    elif method == "checkFile":
+        source = params["source"]
+        start = source.find("var_dump")
+        edits, diagnostics = [], []
+        if start >= 0:
+            edits.append({"start": start, "end": start + 8, "replacement": "dump", "message": "Use dump()"})
+            line_no = source.count("\n", 0, start) + 1
+            diagnostics.append({"line": line_no, "message": "Debug call", "rule": "no-debug"})
+        result = {"edits": edits, "diagnostics": diagnostics, "pid": __import__("os").getpid()}
+    elif method == "shutdown":
+        result = None
+    print(json.dumps({"jsonrpc": "2.0", "id": request["id"], "result": result}), flush=True)
+"#;
+
+    fn python_available() -> bool {
+        Command::new("python3").arg("--version").output().is_ok()
+    }
+
+    fn write_plugin(dir: &Path, name: &str, script: &str) {
+        let plugin_dir = dir.join(name);
+        fs::create_dir(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("plugin.py"), script).unwrap();
+        let manifest = format!("name = \"{}\"\nversion = \"1.0.0\"\ncommand = \"python3\"\nargs = [\"plugin.py\"]\n", name);
+        fs::write(plugin_dir.join("plugin.toml"), manifest).unwrap();
+    }
+
+    fn start(dir: &Path, name: &str) -> PluginHost {
+        let mut manager = PluginManager::with_dir(dir.to_path_buf());
+        manager.discover_plugins().unwrap();
+        manager.start(&[name.to_string()], dir, Duration::from_secs(DEFAULT_TIMEOUT_SECS)).unwrap()
+    }
+
     #[test]
     fn test_plugin_manifest_parsing() {
         let toml = r#"
@@ -293,15 +716,18 @@ mod tests {
     }
 
     #[test]
-    fn test_plugin_input_serialization() {
-        let input = PluginInput {
-            source: "<?php echo 'hello';".to_string(),
-            file: "test.php".to_string(),
-        };
+    fn test_capabilities_parsing() {
+        let names = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
 
-        let json = serde_json::to_string(&input).unwrap();
-        assert!(json.contains("source"));
-        assert!(json.contains("file"));
+        let caps = Capabilities::parse(&names(&["edits", "project", "future-thing"])).unwrap();
+        assert!(caps.edits);
+        assert!(!caps.diagnostics);
+        assert_eq!(caps.scope, PluginScope::Project);
+
+        let caps = Capabilities::parse(&names(&["diagnostics"])).unwrap();
+        assert_eq!(caps.scope, PluginScope::PerFile);
+
+        assert!(Capabilities::parse(&names(&["per-file", "project"])).is_err());
     }
 
     #[test]
@@ -314,24 +740,149 @@ mod tests {
                     "replacement": "print",
                     "message": "Use print instead of echo"
                 }
-            ]
+            ],
+            "diagnostics": [{ "line": 2, "message": "Avoid echo", "rule": "no-echo" }]
         }"#;
 
         let output: PluginOutput = serde_json::from_str(json).unwrap();
         assert_eq!(output.edits.len(), 1);
         assert_eq!(output.edits[0].start, 6);
         assert_eq!(output.edits[0].replacement, "print");
+        assert_eq!(output.diagnostics[0].column, 1);
+        assert_eq!(output.diagnostics[0].rule.as_deref(), Some("no-echo"));
     }
 
     #[test]
-    fn test_plugin_output_with_error() {
-        let json = r#"{
-            "edits": [],
-            "error": "Failed to parse PHP"
-        }"#;
+    fn test_findings_outside_source_rejected() {
+        let output = PluginOutput {
+            edits: vec![PluginEdit {
+                start: 4,
+                end: 100,
+                replacement: String::new(),
+                message: String::new(),
+                rule: None,
+            }],
+            diagnostics: vec![],
+        };
 
-        let output: PluginOutput = serde_json::from_str(json).unwrap();
-        assert!(output.error.is_some());
-        assert_eq!(output.error.unwrap(), "Failed to parse PHP");
+        let mut findings = PluginFindings::default();
+        assert!(add_findings(&mut findings, "p", &output, "<?php").is_err());
+    }
+
+    #[test]
+    fn test_persistent_plugin_process() {
+        if !python_available() {
+            return;
+        }
+        let temp = TempDir::new().unwrap();
+        write_plugin(temp.path(), "no-debug", PYTHON_PLUGIN);
+        let host = start(temp.path(), "no-debug");
+
+        assert_eq!(host.versions(), vec!["no-debug@1.0.0"]);
+        assert!(!host.has_project_plugins());
+
+        let source = "<?php\n\nvar_dump($x);\n";
+        let findings = host.check_file(Path::new("a.php"), source).unwrap();
+        assert_eq!(findings.edits.len(), 1);
+        assert_eq!(findings.edits[0].rule.as_deref(), Some("no-debug"));
+        assert_eq!(rustor_core::apply_edits(source, &findings.edits).unwrap(), "<?php\n\ndump($x);\n");
+        assert_eq!(findings.diagnostics[0].line, 3);
+        assert_eq!(findings.diagnostics[0].rule, "no-debug");
+
+        // The same process answers every request
+        let pid = |host: &PluginHost| {
+            host.plugins[0].request("checkFile", json!({ "file": "b.php", "source": "" })).unwrap()["pid"].clone()
+        };
+        assert_eq!(pid(&host), pid(&host));
+    }
+
+    #[test]
+    fn test_project_plugin() {
+        if !python_available() {
+            return;
+        }
+        let script = r#"
+import json, sys
+
+for line in sys.stdin:
+    request = json.loads(line)
+    method = request["method"]
+    if method == "initialize":
+        result = {"capabilities": ["diagnostics", "project"]}
+    elif method == "checkProject":
+        files = request["params"]["files"]
+        result = {"files": [{"file": f, "diagnostics": [{"line": 1, "message": "%d files" % len(files)}]} for f in files]}
+    else:
+        result = None
+    print(json.dumps({"jsonrpc": "2.0", "id": request["id"], "result": result}), flush=True)
+"#;
+        let temp = TempDir::new().unwrap();
+        write_plugin(temp.path(), "counter", script);
+        let mut host = start(temp.path(), "counter");
+        assert!(host.has_project_plugins());
+
+        let files = vec![PathBuf::from("a.php"), PathBuf::from("b.php")];
+        host.check_project(&files).unwrap();
+
+        let findings = host.check_file(Path::new("b.php"), "<?php\n").unwrap();
+        assert_eq!(findings.diagnostics.len(), 1);
+        assert_eq!(findings.diagnostics[0].message, "2 files");
+        assert_eq!(findings.diagnostics[0].rule, "counter");
+        assert!(host.check_file(Path::new("c.php"), "<?php\n").unwrap().diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_start_enabled() {
+        let temp = TempDir::new().unwrap();
+        let mut config = Config::default();
+        config.plugins.dir = Some(temp.path().to_string_lossy().into_owned());
+        assert!(start_enabled(&config, None).unwrap().is_empty());
+
+        config.plugins.enabled = vec!["missing".to_string()];
+        let err = start_enabled(&config, None).err().unwrap();
+        assert!(err.contains("Unknown plugin 'missing'"), "{}", err);
+
+        if !python_available() {
+            return;
+        }
+        write_plugin(temp.path(), "no-debug", PYTHON_PLUGIN);
+        config.plugins.enabled = vec!["no-debug".to_string()];
+        assert_eq!(start_enabled(&config, None).unwrap().versions(), vec!["no-debug@1.0.0"]);
+    }
+
+    #[test]
+    fn test_undeclared_edits_rejected() {
+        if !python_available() {
+            return;
+        }
+        let script = PYTHON_PLUGIN.replace("\"edits\", \"diagnostics\", ", "\"diagnostics\", ");
+        let temp = TempDir::new().unwrap();
+        write_plugin(temp.path(), "sneaky", &script);
+        let host = start(temp.path(), "sneaky");
+
+        let err = host.check_file(Path::new("a.php"), "<?php var_dump(1);").unwrap_err();
+        assert!(err.contains("\"edits\" capability"), "{}", err);
+    }
+
+    #[test]
+    fn test_hanging_plugin_is_killed() {
+        if !python_available() {
+            return;
+        }
+        let script = PYTHON_PLUGIN.replace("    if method == \"initialize\":", "    if method != \"initialize\":\n        __import__(\"time\").sleep(60)\n    if method == \"initialize\":");
+        let temp = TempDir::new().unwrap();
+        write_plugin(temp.path(), "hangs", &script);
+        let mut manager = PluginManager::with_dir(temp.path().to_path_buf());
+        manager.discover_plugins().unwrap();
+        let host = manager
+            .start(&["hangs".to_string()], temp.path(), Duration::from_millis(500))
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let err = host.check_file(Path::new("a.php"), "<?php\n").unwrap_err();
+        assert!(err.contains("killed after not answering checkFile"), "{}", err);
+        // Dropping the host doesn't wait for a shutdown answer either
+        drop(host);
+        assert!(started.elapsed() < Duration::from_secs(10));
     }
 }
@@ -3,30 +3,36 @@
 use anyhow::{Context, Result};
 use bumpalo::Bump;
 use mago_database::file::FileId;
+use mago_syntax::ast::Program;
 use std::collections::HashSet;
 use std::path::Path;
 
-use rustor_core::apply_edits;
+use rustor_core::{apply_edits, Edit, ParseError};
 use rustor_rules::{RuleConfigs, RuleRegistry};
 
 use crate::ignore::IgnoreDirectives;
 use crate::output::EditInfo;
+use crate::plugin::PluginHost;
 
 /// Result of processing a single file
 pub struct ProcessResult {
     /// Edits that were found/applied
     pub edits: Vec<EditInfo>,
+    /// The source edits behind `edits`, without plugin diagnostics
+    pub fixes: Vec<Edit>,
     /// Original source code
     pub old_source: String,
     /// New source code after edits (only if edits were found)
     pub new_source: Option<String>,
+    /// Parse errors; rules only ran on the statements without errors
+    pub parse_errors: Vec<ParseError>,
 }
 
 /// Process a single PHP file and return the edits found
 pub fn process_file(
     path: &Path,
     enabled_rules: &HashSet<String>,
-) -> Result<Option<ProcessResult>> {
+) -> Result<ProcessResult> {
     process_file_with_config(path, enabled_rules, &RuleConfigs::new())
 }
 
@@ -35,7 +41,7 @@ pub fn process_file_with_config(
     path: &Path,
     enabled_rules: &HashSet<String>,
     rule_configs: &RuleConfigs,
-) -> Result<Option<ProcessResult>> {
+) -> Result<ProcessResult> {
     process_file_with_skip(path, enabled_rules, rule_configs, &HashSet::new())
 }
 
@@ -45,7 +51,7 @@ pub fn process_file_with_skip(
     enabled_rules: &HashSet<String>,
     rule_configs: &RuleConfigs,
     skip_rules: &HashSet<String>,
-) -> Result<Option<ProcessResult>> {
+) -> Result<ProcessResult> {
     // Create a registry from config (for backwards compatibility)
     let registry = RuleRegistry::new_with_config(rule_configs);
     process_file_with_registry(path, enabled_rules, &registry, skip_rules)
@@ -57,7 +63,22 @@ pub fn process_file_with_registry(
     enabled_rules: &HashSet<String>,
     registry: &RuleRegistry,
     skip_rules: &HashSet<String>,
-) -> Result<Option<ProcessResult>> {
+) -> Result<ProcessResult> {
+    process_file_with_plugins(path, enabled_rules, registry, skip_rules, &PluginHost::default())
+}
+
+/// Process a single PHP file with an existing registry, skip rules and plugins
+///
+/// Plugin edits and diagnostics go through the same ignore directives and
+/// skip rules as rule edits. Diagnostics are reported as edits that don't
+/// change the source.
+pub fn process_file_with_plugins(
+    path: &Path,
+    enabled_rules: &HashSet<String>,
+    registry: &RuleRegistry,
+    skip_rules: &HashSet<String>,
+    plugins: &PluginHost,
+) -> Result<ProcessResult> {
     let source_code = std::fs::read_to_string(path)
         .with_context(|| format!("Failed to read file: {}", path.display()))?;
 
@@ -65,78 +86,96 @@ pub fn process_file_with_registry(
     let arena = Bump::new();
     let file_id = FileId::new(path.to_string_lossy().as_ref());
 
-    // Parse the PHP file
-    let (program, parse_error) =
-        mago_syntax::parser::parse_file_content(&arena, file_id, &source_code);
+    // Parse the PHP file, recovering from parse errors
+    let parsed = rustor_core::parse(&arena, file_id, &source_code);
 
-    // Check for parse errors
-    if let Some(_error) = parse_error {
-        return Ok(None); // Signal parse error by returning None
-    }
+    let mut result = process_program(path, source_code, parsed.program, enabled_rules, registry, skip_rules, plugins)?;
+    result.parse_errors = parsed.errors;
+    Ok(result)
+}
 
+/// Run rules and plugins on an already parsed file
+pub fn process_program(
+    path: &Path,
+    source_code: String,
+    program: &Program<'_>,
+    enabled_rules: &HashSet<String>,
+    registry: &RuleRegistry,
+    skip_rules: &HashSet<String>,
+    plugins: &PluginHost,
+) -> Result<ProcessResult> {
     // Apply enabled refactoring rules using the provided registry
-    let edits = registry.check_all(program, &source_code, enabled_rules);
+    let mut edits = registry.check_all(program, &source_code, enabled_rules);
 
-    if edits.is_empty() {
-        return Ok(Some(ProcessResult {
+    // Add edits and diagnostics from plugins
+    let findings = plugins.check_file(path, &source_code).map_err(anyhow::Error::msg)?;
+    edits.extend(findings.edits);
+
+    if edits.is_empty() && findings.diagnostics.is_empty() {
+        return Ok(ProcessResult {
             edits: vec![],
+            fixes: vec![],
             old_source: source_code,
             new_source: None,
-        }));
+            parse_errors: Vec::new(),
+        });
     }
 
     // Parse ignore directives from source
     let ignores = IgnoreDirectives::parse(&source_code);
+    let is_ignored = |line: usize, rule: &str| ignores.should_ignore(line, rule) || skip_rules.contains(rule);
 
-    // Convert Edit to EditInfo with line/column info, filtering out ignored edits
-    let edit_infos: Vec<EditInfo> = edits
+    // Filter out ignored edits (inline comments or skip config)
+    let edits: Vec<_> = edits
+        .into_iter()
+        .filter(|edit| {
+            let (line, _) = offset_to_line_column(&source_code, edit.span.start.offset as usize);
+            !is_ignored(line, &edit_rule(edit))
+        })
+        .collect();
+
+    // Convert Edit to EditInfo with line/column info
+    let mut edit_infos: Vec<EditInfo> = edits
         .iter()
-        .filter_map(|edit| {
+        .map(|edit| {
             let (line, column) = offset_to_line_column(&source_code, edit.span.start.offset as usize);
-            let rule = extract_rule_name(&edit.message);
-
-            // Check if this edit should be ignored (inline comments or skip config)
-            if ignores.should_ignore(line, &rule) || skip_rules.contains(&rule) {
-                return None;
-            }
-
-            Some(EditInfo {
-                rule,
+            EditInfo {
+                rule: edit_rule(edit),
                 line,
                 column,
                 message: edit.message.clone(),
-            })
+            }
         })
         .collect();
+    edit_infos.extend(
+        findings
+            .diagnostics
+            .into_iter()
+            .filter(|diagnostic| !is_ignored(diagnostic.line, &diagnostic.rule)),
+    );
 
     // If all edits were filtered out, return no changes
     if edit_infos.is_empty() {
-        return Ok(Some(ProcessResult {
+        return Ok(ProcessResult {
             edits: vec![],
+            fixes: vec![],
             old_source: source_code,
             new_source: None,
-        }));
+            parse_errors: Vec::new(),
+        });
     }
 
-    // Filter the actual edits to only include non-ignored/non-skipped ones
-    let filtered_edits: Vec<_> = edits
-        .into_iter()
-        .filter(|edit| {
-            let (line, _) = offset_to_line_column(&source_code, edit.span.start.offset as usize);
-            let rule = extract_rule_name(&edit.message);
-            !ignores.should_ignore(line, &rule) && !skip_rules.contains(&rule)
-        })
-        .collect();
-
     // Apply edits to get new source
-    let new_source = apply_edits(&source_code, &filtered_edits)
+    let new_source = apply_edits(&source_code, &edits)
         .with_context(|| format!("Failed to apply edits to {}", path.display()))?;
 
-    Ok(Some(ProcessResult {
+    Ok(ProcessResult {
         edits: edit_infos,
+        fixes: edits,
         old_source: source_code,
         new_source: Some(new_source),
-    }))
+        parse_errors: Vec::new(),
+    })
 }
 
 /// Write the processed result to the file
@@ -146,7 +185,7 @@ pub fn write_file(path: &Path, content: &str) -> Result<()> {
 }
 
 /// Convert byte offset to line and column numbers (1-based)
-fn offset_to_line_column(source: &str, offset: usize) -> (usize, usize) {
+pub(crate) fn offset_to_line_column(source: &str, offset: usize) -> (usize, usize) {
     let mut line = 1;
     let mut column = 1;
 
@@ -165,6 +204,11 @@ fn offset_to_line_column(source: &str, offset: usize) -> (usize, usize) {
     (line, column)
 }
 
+/// Rule name of an edit, from the rule it carries or its message
+pub(crate) fn edit_rule(edit: &Edit) -> String {
+    edit.rule.clone().unwrap_or_else(|| extract_rule_name(&edit.message))
+}
+
 /// Extract rule name from edit message (heuristic)
 fn extract_rule_name(message: &str) -> String {
     // Messages typically start with "Convert X" or "Replace X"
@@ -0,0 +1,578 @@
+//! Project configuration subcommands (`rustor init`, `rustor config`)
+//!
+//! `rustor init` finds the PHPStan, PHP-CS-Fixer and Rector configs and the
+//! composer.json of a project and writes a `.rustor.toml` that references
+//! or takes over their settings. `rustor config --show` prints the settings
+//! rustor runs with once `.rustor.toml`, those configs and the defaults are
+//! merged, each with the file it comes from.
+
+use anyhow::{Context, Result};
+use colored::*;
+use rustor_analyze::config::{composer::ComposerJson, PhpStanConfig};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::analyze::load_phpstan_config;
+use crate::check::FIXER_CONFIG_FILES;
+use crate::config::Config;
+use crate::fixer::FixerSetup;
+
+/// Rector configs, in order
+const RECTOR_CONFIG_FILES: &[&str] = &["rector.php", "rector.dist.php"];
+
+/// Arguments for the init subcommand
+#[derive(clap::Args)]
+#[command(after_help = "DETECTS:
+    phpstan.neon, phpstan.neon.dist              Referenced from [analyze]
+    .php-cs-fixer(.dist).php, phpcs.xml(.dist)   Referenced from [fixer]
+    rector.php, rector.dist.php                  Paths and skips copied to [paths]
+    composer.json                                require.php copied to [php] version")]
+pub struct InitArgs {
+    /// Project directory
+    #[arg(default_value = ".")]
+    pub dir: PathBuf,
+    /// Overwrite an existing .rustor.toml
+    #[arg(long)]
+    pub force: bool,
+    /// Print the config instead of writing it
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments for the config subcommand
+#[derive(clap::Args)]
+pub struct ConfigArgs {
+    /// Print the settings after merging .rustor.toml, the PHPStan and fixer
+    /// configs and the defaults, with the source of each
+    #[arg(long, required = true)]
+    pub show: bool,
+    /// rustor config file (default: search for .rustor.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+/// Settings read from a rector.php
+#[derive(Debug, Default, PartialEq)]
+struct RectorSettings {
+    /// `withPaths()` or `$rectorConfig->paths()`
+    paths: Vec<String>,
+    /// Paths in `withSkip()` or `$rectorConfig->skip()`
+    skip: Vec<String>,
+    /// Rector rules in `withSkip()`, which have no rustor equivalent
+    skipped_rules: Vec<String>,
+    /// Highest PHP version of the configured PHP sets
+    php_version: Option<String>,
+}
+
+/// Run the init subcommand
+pub fn run_init(args: InitArgs) -> Result<ExitCode> {
+    let target = args.dir.join(".rustor.toml");
+    if target.exists() && !args.force && !args.dry_run {
+        eprintln!("{}: {} already exists. Use --force to overwrite it", "Error".red(), target.display());
+        return Ok(ExitCode::from(1));
+    }
+
+    let contents = init_config(&args.dir)?;
+    if args.dry_run {
+        print!("{}", contents);
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    std::fs::write(&target, &contents).with_context(|| format!("Failed to write {}", target.display()))?;
+    println!("{} {}", "Wrote".green(), target.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+/// The `.rustor.toml` for a project directory
+fn init_config(dir: &Path) -> Result<String> {
+    let phpstan = PhpStanConfig::find_config(dir);
+    let fixer = FIXER_CONFIG_FILES.iter().map(|name| dir.join(name)).find(|path| path.is_file());
+    let rector = RECTOR_CONFIG_FILES.iter().map(|name| dir.join(name)).find(|path| path.is_file());
+    let composer_path = dir.join("composer.json");
+    let composer = if composer_path.is_file() {
+        Some(ComposerJson::load(&composer_path).with_context(|| format!("Failed to read {}", composer_path.display()))?)
+    } else {
+        None
+    };
+    let rector_settings = match &rector {
+        Some(path) => {
+            let source = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            Some(scan_rector(&source))
+        }
+        None => None,
+    };
+
+    let name = |path: &Path| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let mut detected: Vec<String> = [&phpstan, &fixer, &rector].into_iter().flatten().map(|p| name(p)).collect();
+    if composer.is_some() {
+        detected.push("composer.json".to_string());
+    }
+
+    let mut out = String::from("# rustor configuration, written by `rustor init`\n");
+    if detected.is_empty() {
+        out.push_str("# No PHPStan, PHP-CS-Fixer or Rector config or composer.json found\n");
+    } else {
+        out.push_str(&format!("# Detected: {}\n", detected.join(", ")));
+    }
+
+    // composer.json's constraint is what the code has to run on; Rector's
+    // PHP sets are the version it is being upgraded to
+    let composer_version = composer.as_ref().and_then(|c| Some((c.min_php_version()?, c.require.get("php")?.clone())));
+    let rector_version = rector_settings.as_ref().and_then(|r| r.php_version.clone());
+    if let Some((version, constraint)) = composer_version {
+        out.push_str(&format!("\n[php]\n# From composer.json (require.php {})\nversion = {}\n", constraint, quote(&version)));
+    } else if let (Some(version), Some(rector)) = (rector_version, &rector) {
+        out.push_str(&format!("\n[php]\n# From the PHP sets in {}\nversion = {}\n", name(rector), quote(&version)));
+    }
+
+    out.push_str("\n[rules]\npreset = \"recommended\"\n");
+
+    if let (Some(settings), Some(rector)) = (&rector_settings, &rector) {
+        if !settings.paths.is_empty() || !settings.skip.is_empty() {
+            out.push_str(&format!("\n[paths]\n# From {}\n", name(rector)));
+            if !settings.paths.is_empty() {
+                out.push_str(&format!("include = {}\n", array(&settings.paths)));
+            }
+            if !settings.skip.is_empty() {
+                out.push_str(&format!("exclude = {}\n", array(&settings.skip)));
+            }
+        }
+        if !settings.skipped_rules.is_empty() {
+            out.push_str(&format!(
+                "# Rector rules skipped in {}, without a rustor equivalent: {}\n",
+                name(rector),
+                settings.skipped_rules.join(", ")
+            ));
+        }
+    }
+
+    if let Some(phpstan) = &phpstan {
+        out.push_str("\n[analyze]\n# Its level, paths and excludePaths apply unless set here\n");
+        out.push_str(&format!("config = {}\n", quote(&name(phpstan))));
+    }
+
+    out.push_str("\n[fixer]\n");
+    match &fixer {
+        Some(fixer) => {
+            out.push_str("# Its rules and Finder apply unless set here\n");
+            out.push_str(&format!("config = {}\n", quote(&name(fixer))));
+        }
+        None => out.push_str("preset = \"psr12\"\n"),
+    }
+
+    Ok(out)
+}
+
+/// Read the paths, skips and PHP version of a rector.php
+///
+/// Only literal values are read: Rector configs are PHP and aren't run.
+fn scan_rector(source: &str) -> RectorSettings {
+    let mut settings = RectorSettings::default();
+
+    for call in ["withPaths(", "->paths("] {
+        if let Some(args) = call_args(source, call) {
+            settings.paths.extend(strings(args).into_iter().map(|path| project_path(&path)));
+        }
+    }
+
+    for call in ["withSkip(", "->skip("] {
+        let Some(args) = call_args(source, call) else { continue };
+        for item in array_items(args) {
+            if let Some(class) = item.split("::class").next().filter(|_| item.contains("::class")) {
+                let rule = class.trim().rsplit('\\').next().unwrap_or_default();
+                settings.skipped_rules.push(rule.to_string());
+            } else {
+                settings.skip.extend(strings(item).into_iter().map(|path| project_path(&path)));
+            }
+        }
+    }
+
+    settings.php_version = php_versions(source).into_iter().max().map(|(major, minor)| format!("{}.{}", major, minor));
+    settings
+}
+
+/// The arguments of the first call to `call` (which ends in `(`)
+fn call_args<'a>(source: &'a str, call: &str) -> Option<&'a str> {
+    let start = source.find(call)? + call.len();
+    let mut depth = 1;
+    for (i, c) in source[start..].char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&source[start..start + i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The top-level items of the array in `args`
+fn array_items(args: &str) -> Vec<&str> {
+    let args = args.trim();
+    let inner = args.strip_prefix('[').and_then(|a| a.strip_suffix(']')).unwrap_or(args);
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// The string literals in `code`
+fn strings(code: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut chars = code.chars();
+    while let Some(c) = chars.next() {
+        if c == '\'' || c == '"' {
+            let literal: String = chars.by_ref().take_while(|&end| end != c).collect();
+            found.push(literal);
+        }
+    }
+    found
+}
+
+/// A path from `__DIR__ . '/src'`, relative to the project
+fn project_path(path: &str) -> String {
+    path.trim_start_matches('/').to_string()
+}
+
+/// PHP versions named in PHP sets: `php81: true`, `PHP_81`, `UP_TO_PHP_81`
+fn php_versions(source: &str) -> Vec<(u32, u32)> {
+    let mut versions = Vec::new();
+    let lower = source.to_ascii_lowercase();
+    for prefix in ["php_", "php"] {
+        for (i, _) in lower.match_indices(prefix) {
+            let digits: String = lower[i + prefix.len()..].chars().take_while(|c| c.is_ascii_digit()).collect();
+            if digits.len() == 2 {
+                let mut digits = digits.chars().filter_map(|c| c.to_digit(10));
+                if let (Some(major), Some(minor)) = (digits.next(), digits.next()) {
+                    if (5..=9).contains(&major) {
+                        versions.push((major, minor));
+                    }
+                }
+            }
+        }
+    }
+    versions
+}
+
+fn quote(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn array(values: &[String]) -> String {
+    toml::Value::Array(values.iter().map(|v| toml::Value::String(v.clone())).collect()).to_string()
+}
+
+/// Run the config subcommand
+pub fn run_config(args: ConfigArgs) -> Result<ExitCode> {
+    let (config, config_path) = match &args.config {
+        Some(path) => (Config::load_path(path)?, Some(path.clone())),
+        None => Config::load()?.map(|(config, path)| (config, Some(path))).unwrap_or_default(),
+    };
+    print!("{}", show_config(&config, config_path.as_deref())?);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// A value of the effective configuration and where it comes from
+struct Setting {
+    key: &'static str,
+    value: toml::Value,
+    source: String,
+}
+
+impl Setting {
+    fn new(key: &'static str, value: impl Into<toml::Value>, source: impl Into<String>) -> Self {
+        Self { key, value: value.into(), source: source.into() }
+    }
+}
+
+/// The effective configuration as TOML, with the source of each value
+fn show_config(config: &Config, config_path: Option<&Path>) -> Result<String> {
+    let rustor = config_path.map_or_else(|| "default".to_string(), display);
+    let mut sections: Vec<(&str, Vec<Setting>)> = Vec::new();
+
+    let mut php = Vec::new();
+    if let Some(version) = &config.php.version {
+        php.push(Setting::new("version", version.as_str(), &rustor));
+    }
+    sections.push(("php", php));
+
+    let mut rules = Vec::new();
+    if let Some(preset) = &config.rules.preset {
+        rules.push(Setting::new("preset", preset.as_str(), &rustor));
+    }
+    if let Some(enabled) = &config.rules.enabled {
+        rules.push(Setting::new("enabled", enabled.clone(), &rustor));
+    }
+    if !config.rules.disabled.is_empty() {
+        rules.push(Setting::new("disabled", config.rules.disabled.clone(), &rustor));
+    }
+    if rules.is_empty() {
+        rules.push(Setting::new("enabled", "all", "default"));
+    }
+    sections.push(("rules", rules));
+
+    let mut paths = Vec::new();
+    if !config.paths.include.is_empty() {
+        paths.push(Setting::new("include", config.paths.include.clone(), &rustor));
+    }
+    if !config.paths.exclude.is_empty() {
+        paths.push(Setting::new("exclude", config.paths.exclude.clone(), &rustor));
+    }
+    sections.push(("paths", paths));
+
+    sections.push(("analyze", analyze_settings(config, &rustor)?));
+    sections.push(("fixer", fixer_settings(config, &rustor)?));
+
+    let mut output = Vec::new();
+    let format = config.output.format.as_deref().unwrap_or("text");
+    let source = if config.output.format.is_some() { rustor.as_str() } else { "default" };
+    output.push(Setting::new("format", format, source));
+    sections.push(("output", output));
+
+    let mut out = String::new();
+    for (name, settings) in sections {
+        if settings.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("[{}]\n", name));
+        for setting in settings {
+            out.push_str(&format!("{} = {}  # {}\n", setting.key, setting.value, setting.source));
+        }
+    }
+    Ok(out)
+}
+
+fn analyze_settings(config: &Config, rustor: &str) -> Result<Vec<Setting>> {
+    let phpstan_path = match &config.analyze.config {
+        Some(path) => Some((config.resolve(path), rustor.to_string())),
+        None => PhpStanConfig::find_config(&std::env::current_dir()?).map(|path| (path, "found".to_string())),
+    };
+    let phpstan = load_phpstan_config(None, config, false)?;
+    let phpstan_source = phpstan_path.as_ref().map_or_else(|| "default".to_string(), |(path, _)| display(path));
+
+    let mut settings = Vec::new();
+    if let Some((path, source)) = &phpstan_path {
+        settings.push(Setting::new("config", display(path), source));
+    }
+    let level_source = if config.analyze.level.is_some() { rustor } else { &phpstan_source };
+    settings.push(Setting::new("level", i64::from(phpstan.level.as_u8()), level_source));
+    if !phpstan.paths.is_empty() {
+        let paths_source = if config.analyze.paths.is_empty() { &phpstan_source } else { rustor };
+        settings.push(Setting::new("paths", path_strings(&phpstan.paths), paths_source));
+    }
+    if !phpstan.exclude_paths.is_empty() {
+        let mut sources = Vec::new();
+        if phpstan.exclude_paths.len() > config.analyze.exclude.len() {
+            sources.push(phpstan_source.as_str());
+        }
+        if !config.analyze.exclude.is_empty() {
+            sources.push(rustor);
+        }
+        settings.push(Setting::new("exclude", path_strings(&phpstan.exclude_paths), sources.join(", ")));
+    }
+    if let Some(baseline) = &config.analyze.baseline {
+        settings.push(Setting::new("baseline", display(&config.resolve(baseline)), rustor));
+    }
+    Ok(settings)
+}
+
+fn fixer_settings(config: &Config, rustor: &str) -> Result<Vec<Setting>> {
+    let fixer_path = match &config.fixer.config {
+        Some(path) => Some((config.resolve(path), rustor.to_string())),
+        None => FIXER_CONFIG_FILES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file())
+            .map(|path| (path, "found (rustor check)".to_string())),
+    };
+    let setup = FixerSetup::load(fixer_path.as_ref().map(|(path, _)| path.as_path()), None, false, &config.fixer)?;
+    let fixer_source = fixer_path.as_ref().map_or_else(|| "default".to_string(), |(path, _)| display(path));
+
+    let mut settings = Vec::new();
+    if let Some((path, source)) = &fixer_path {
+        settings.push(Setting::new("config", display(path), source));
+    }
+    match &setup.rules {
+        Some(rules) => {
+            let mut source = match &fixer_path {
+                Some(_) => fixer_source.clone(),
+                None => format!("preset {}", setup.preset),
+            };
+            if !config.fixer.rules.is_empty() {
+                source = format!("{}, {}", source, rustor);
+            }
+            let mut rules = rules.clone();
+            rules.sort();
+            settings.push(Setting::new("rules", rules, source));
+        }
+        None => {
+            let source = if config.fixer.preset.is_some() { rustor } else { "default" };
+            settings.push(Setting::new("preset", setup.preset.as_str(), source));
+        }
+    }
+    let risky_source = if config.fixer.risky.is_some() { rustor } else { &fixer_source };
+    settings.push(Setting::new("risky", setup.config.risky_allowed, risky_source));
+    if !setup.filter.include.is_empty() {
+        settings.push(Setting::new("paths", path_strings(&setup.filter.include), format!("Finder in {}", fixer_source)));
+    }
+    if !setup.filter.exclude.is_empty() {
+        let mut sources = Vec::new();
+        if setup.filter.exclude.len() > config.fixer.exclude.len() {
+            sources.push(format!("Finder in {}", fixer_source));
+        }
+        if !config.fixer.exclude.is_empty() {
+            sources.push(rustor.to_string());
+        }
+        settings.push(Setting::new("exclude", setup.filter.exclude.clone(), sources.join(", ")));
+    }
+    Ok(settings)
+}
+
+/// A path, relative to the current directory when it is below it
+fn display(path: &Path) -> String {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    path.strip_prefix(&cwd).unwrap_or(path).display().to_string()
+}
+
+fn path_strings(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter().map(|path| display(path)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const RECTOR: &str = r#"<?php
+use Rector\Config\RectorConfig;
+use Rector\Php80\Rector\Class_\ClassPropertyAssignToConstructorPromotionRector;
+
+return RectorConfig::configure()
+    ->withPaths([
+        __DIR__ . '/src',
+        __DIR__ . '/tests',
+    ])
+    ->withSkip([
+        __DIR__ . '/src/Legacy',
+        '*/Fixtures/*',
+        ClassPropertyAssignToConstructorPromotionRector::class => [__DIR__ . '/src/Dto'],
+    ])
+    ->withPhpSets(php81: true);
+"#;
+
+    #[test]
+    fn test_scan_rector() {
+        let settings = scan_rector(RECTOR);
+        assert_eq!(settings.paths, vec!["src", "tests"]);
+        assert_eq!(settings.skip, vec!["src/Legacy", "*/Fixtures/*"]);
+        assert_eq!(settings.skipped_rules, vec!["ClassPropertyAssignToConstructorPromotionRector"]);
+        assert_eq!(settings.php_version.as_deref(), Some("8.1"));
+
+        let old_style = r#"<?php
+return static function (RectorConfig $rectorConfig): void {
+    $rectorConfig->paths([__DIR__ . "/app"]);
+    $rectorConfig->sets([LevelSetList::UP_TO_PHP_74]);
+};
+"#;
+        let settings = scan_rector(old_style);
+        assert_eq!(settings.paths, vec!["app"]);
+        assert_eq!(settings.php_version.as_deref(), Some("7.4"));
+    }
+
+    #[test]
+    fn test_init_config() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("phpstan.neon"), "parameters:\n    level: 5\n").unwrap();
+        fs::write(temp.path().join(".php-cs-fixer.dist.php"), "<?php return new PhpCsFixer\\Config();").unwrap();
+        fs::write(temp.path().join("rector.php"), RECTOR).unwrap();
+        fs::write(temp.path().join("composer.json"), r#"{"require": {"php": "^8.2"}}"#).unwrap();
+
+        let contents = init_config(temp.path()).unwrap();
+        let config: Config = toml::from_str(&contents).unwrap();
+        assert_eq!(config.php.version.as_deref(), Some("8.2"));
+        assert_eq!(config.paths.include, vec!["src", "tests"]);
+        assert_eq!(config.paths.exclude, vec!["src/Legacy", "*/Fixtures/*"]);
+        assert_eq!(config.analyze.config.as_deref(), Some("phpstan.neon"));
+        assert_eq!(config.fixer.config.as_deref(), Some(".php-cs-fixer.dist.php"));
+        assert!(contents.contains("ClassPropertyAssignToConstructorPromotionRector"));
+    }
+
+    #[test]
+    fn test_init_refuses_to_overwrite() {
+        let temp = TempDir::new().unwrap();
+        let target = temp.path().join(".rustor.toml");
+        fs::write(&target, "[php]\n").unwrap();
+
+        let init = |force| run_init(InitArgs { dir: temp.path().to_path_buf(), force, dry_run: false }).unwrap();
+        assert_eq!(init(false), ExitCode::from(1));
+        assert_eq!(fs::read_to_string(&target).unwrap(), "[php]\n");
+
+        assert_eq!(init(true), ExitCode::SUCCESS);
+        assert!(fs::read_to_string(&target).unwrap().contains("[fixer]\npreset = \"psr12\""));
+    }
+
+    #[test]
+    fn test_show_config_sources() {
+        let temp = TempDir::new().unwrap();
+        let phpstan = temp.path().join("phpstan.neon");
+        fs::write(&phpstan, "parameters:\n    level: 5\n    paths:\n        - src\n").unwrap();
+        let config_path = temp.path().join(".rustor.toml");
+        fs::write(
+            &config_path,
+            "[analyze]\nconfig = \"phpstan.neon\"\nexclude = [\"vendor/\"]\n\n[fixer]\npreset = \"symfony\"\nexclude = [\"var/\"]\n",
+        )
+        .unwrap();
+
+        let config = Config::load_path(&config_path).unwrap();
+        let shown = show_config(&config, Some(&config_path)).unwrap();
+        let rustor = display(&config_path);
+        assert!(shown.contains(&format!("level = 5  # {}\n", display(&phpstan))));
+        assert!(shown.contains(&format!("exclude = [\"vendor/\"]  # {}\n", rustor)));
+        assert!(shown.contains(&format!("preset = \"symfony\"  # {}\n", rustor)));
+        assert!(shown.contains(&format!("exclude = [\"var/\"]  # {}\n", rustor)));
+        assert!(shown.contains("format = \"text\"  # default\n"));
+    }
+
+    fn parse(list: &[&str]) -> Result<crate::Command, clap::Error> {
+        use clap::Parser;
+        Ok(crate::Cli::try_parse_from(["rustor"].iter().chain(list))?.command.expect("not parsed as a subcommand"))
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let Ok(crate::Command::Init(init)) = parse(&["init", "--force", "app"]) else { panic!("not parsed as init") };
+        assert!(init.force);
+        assert_eq!(init.dir, PathBuf::from("app"));
+        let Ok(crate::Command::Init(init)) = parse(&["init"]) else { panic!("not parsed as init") };
+        assert_eq!(init.dir, PathBuf::from("."));
+        assert!(parse(&["init", "--bogus"]).is_err());
+
+        let Ok(crate::Command::Config(config)) = parse(&["config", "--show", "--config=ci.toml"]) else {
+            panic!("not parsed as config")
+        };
+        assert!(config.show);
+        assert_eq!(config.config, Some(PathBuf::from("ci.toml")));
+        assert!(parse(&["config", "--config"]).is_err());
+        assert!(parse(&["config"]).is_err());
+    }
+}
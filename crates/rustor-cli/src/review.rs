@@ -0,0 +1,568 @@
+//! Interactive review of fixes (`--interactive`)
+//!
+//! Walks through the proposed fixes of each file, one edit or edit group at a
+//! time, shows each as a diff with context and asks what to do with it.
+//! Decisions can be made permanent with a `rustor-ignore` comment or a
+//! `[skip]` entry in `.rustor.toml`.
+
+use anyhow::{Context, Result};
+use colored::*;
+use mago_syntax::token::TokenKind;
+use rustor_core::{apply_edits, Edit};
+use rustor_fixer::{Region, TokenStream};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use crate::output::{write_context_diff, EditInfo};
+use crate::process::{edit_rule, offset_to_line_column};
+
+/// Unchanged lines shown around each fix
+const CONTEXT_LINES: usize = 3;
+
+const HELP: &str = "\
+y - apply this fix
+n - skip this fix
+a - apply this fix and all remaining fixes of this rule
+r - skip this fix and all remaining fixes of this rule
+i - skip this fix and add a `rustor-ignore` comment above it
+x - skip this rule for this file and record it under [skip] in .rustor.toml
+e - edit the fixed code in $EDITOR, then apply it
+q - quit, keeping the fixes accepted so far
+? - show this help";
+
+/// Answer to a review prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Apply,
+    Skip,
+    ApplyRule,
+    SkipRule,
+    IgnoreComment,
+    SkipConfig,
+    Edit,
+    Quit,
+    Help,
+}
+
+impl Decision {
+    fn from_key(key: &str) -> Option<Self> {
+        match key.trim() {
+            "y" => Some(Decision::Apply),
+            "n" => Some(Decision::Skip),
+            "a" => Some(Decision::ApplyRule),
+            "r" => Some(Decision::SkipRule),
+            "i" => Some(Decision::IgnoreComment),
+            "x" => Some(Decision::SkipConfig),
+            "e" => Some(Decision::Edit),
+            "q" => Some(Decision::Quit),
+            "?" | "h" => Some(Decision::Help),
+            _ => None,
+        }
+    }
+}
+
+/// One fix to review: a single edit, or all edits of an `EditGroup`
+struct Change {
+    rule: String,
+    message: String,
+    edits: Vec<Edit>,
+}
+
+impl Change {
+    fn start(&self) -> usize {
+        self.edits.iter().map(|e| e.start_offset()).min().unwrap_or(0)
+    }
+
+    fn end(&self) -> usize {
+        self.edits.iter().map(|e| e.end_offset()).max().unwrap_or(0)
+    }
+
+    fn overlaps(&self, accepted: &[Edit]) -> bool {
+        self.edits.iter().any(|edit| accepted.iter().any(|other| overlap(edit, other)))
+    }
+}
+
+fn overlap(a: &Edit, b: &Edit) -> bool {
+    a.start_offset() < b.end_offset() && b.start_offset() < a.end_offset()
+}
+
+/// Split edits into changes, keeping the edits of a group together
+fn changes(edits: Vec<Edit>) -> Vec<Change> {
+    let mut changes: Vec<Change> = Vec::new();
+    let mut groups: HashMap<u32, usize> = HashMap::new();
+
+    for edit in edits {
+        if let Some(&index) = edit.group.and_then(|id| groups.get(&id)) {
+            changes[index].edits.push(edit);
+            continue;
+        }
+        if let Some(id) = edit.group {
+            groups.insert(id, changes.len());
+        }
+        changes.push(Change {
+            rule: edit_rule(&edit),
+            message: edit.message.clone(),
+            edits: vec![edit],
+        });
+    }
+
+    changes.sort_by_key(|c| c.start());
+    changes
+}
+
+/// Outcome of reviewing one file
+pub struct FileReview {
+    /// Source with the accepted fixes and ignore comments, `None` if unchanged
+    pub new_source: Option<String>,
+    /// Accepted fixes
+    pub applied: Vec<EditInfo>,
+}
+
+/// Asks about each fix and remembers rule-wide decisions across files
+pub struct Reviewer<R, W> {
+    input: R,
+    output: W,
+    /// Rules whose remaining fixes are applied without asking
+    apply_rules: HashSet<String>,
+    /// Rules whose remaining fixes are skipped without asking
+    skip_rules: HashSet<String>,
+    /// (rule, path) pairs to record under [skip]
+    skip_entries: Vec<(String, String)>,
+    /// Editor command for `e`
+    editor: String,
+    quit: bool,
+}
+
+impl<R: BufRead, W: Write> Reviewer<R, W> {
+    /// Create a reviewer reading answers from `input`
+    pub fn new(input: R, output: W) -> Self {
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string());
+
+        Self {
+            input,
+            output,
+            apply_rules: HashSet::new(),
+            skip_rules: HashSet::new(),
+            skip_entries: Vec::new(),
+            editor,
+            quit: false,
+        }
+    }
+
+    /// Whether the user quit the review
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// (rule, path) pairs the user chose to record under [skip]
+    pub fn skip_entries(&self) -> &[(String, String)] {
+        &self.skip_entries
+    }
+
+    /// Review the fixes of one file
+    pub fn review_file(&mut self, path: &Path, source: &str, edits: Vec<Edit>) -> Result<FileReview> {
+        let changes = changes(edits);
+        let total = changes.len();
+
+        let mut accepted: Vec<Edit> = Vec::new();
+        let mut applied = Vec::new();
+        // Line -> rules to ignore there
+        let mut ignores: BTreeMap<usize, BTreeSet<String>> = BTreeMap::new();
+        let mut file_skips: HashSet<String> = HashSet::new();
+
+        for (index, mut change) in changes.into_iter().enumerate() {
+            if self.quit {
+                break;
+            }
+            if self.skip_rules.contains(&change.rule) || file_skips.contains(&change.rule) {
+                continue;
+            }
+
+            let (line, column) = offset_to_line_column(source, change.start());
+            let location = format!("{}:{}", path.display(), line);
+            if change.overlaps(&accepted) {
+                writeln!(self.output, "{} [{}] overlaps a fix already accepted, skipped", location, change.rule)?;
+                continue;
+            }
+
+            let decision = if self.apply_rules.contains(&change.rule) {
+                Decision::Apply
+            } else {
+                writeln!(self.output)?;
+                writeln!(
+                    self.output,
+                    "{} {} {} {}",
+                    location.bold(),
+                    format!("[{}]", change.rule).cyan(),
+                    change.message,
+                    format!("({}/{})", index + 1, total).dimmed()
+                )?;
+                let fixed = apply_edits(source, &change.edits)?;
+                write_context_diff(&mut self.output, source, &fixed, CONTEXT_LINES)?;
+                self.prompt()?
+            };
+
+            let accept = match decision {
+                Decision::Apply => true,
+                Decision::ApplyRule => {
+                    self.apply_rules.insert(change.rule.clone());
+                    true
+                }
+                Decision::Skip => false,
+                Decision::SkipRule => {
+                    self.skip_rules.insert(change.rule.clone());
+                    false
+                }
+                Decision::IgnoreComment => {
+                    ignores.entry(line).or_default().insert(change.rule.clone());
+                    false
+                }
+                Decision::SkipConfig => {
+                    file_skips.insert(change.rule.clone());
+                    self.skip_entries.push((change.rule.clone(), path.display().to_string()));
+                    false
+                }
+                Decision::Edit => match self.edit(source, &change)? {
+                    Some(edit) => {
+                        change.edits = vec![edit];
+                        true
+                    }
+                    None => {
+                        writeln!(self.output, "Unchanged, skipped")?;
+                        false
+                    }
+                },
+                Decision::Quit => {
+                    self.quit = true;
+                    false
+                }
+                Decision::Help => unreachable!("prompt() handles help"),
+            };
+
+            if accept {
+                applied.push(EditInfo {
+                    rule: change.rule,
+                    line,
+                    column,
+                    message: change.message,
+                });
+                accepted.extend(change.edits);
+            }
+        }
+
+        // Ignore comments go after the accepted edits, so an edit starting at
+        // the same line start is applied first
+        let mut edits = accepted;
+        let tokens = TokenStream::new(source);
+        for (line, rules) in ignores {
+            let Some(comment) = ignore_comment(source, &tokens, line, &rules) else {
+                writeln!(self.output, "{}:{} can't add an ignore comment inside a string or outside PHP code", path.display(), line)?;
+                continue;
+            };
+            if edits.iter().any(|edit| overlap(&comment, edit)) {
+                writeln!(self.output, "{}:{} can't add an ignore comment inside an accepted fix", path.display(), line)?;
+                continue;
+            }
+            edits.push(comment);
+        }
+
+        let new_source = if edits.is_empty() {
+            None
+        } else {
+            Some(apply_edits(source, &edits).with_context(|| format!("Failed to apply fixes to {}", path.display()))?)
+        };
+
+        Ok(FileReview { new_source, applied })
+    }
+
+    /// Ask until a valid answer is given; end of input quits
+    fn prompt(&mut self) -> Result<Decision> {
+        loop {
+            write!(self.output, "{} ", "Apply this fix [y,n,a,r,i,x,e,q,?]?".cyan().bold())?;
+            self.output.flush()?;
+
+            let mut answer = String::new();
+            if self.input.read_line(&mut answer)? == 0 {
+                writeln!(self.output)?;
+                return Ok(Decision::Quit);
+            }
+
+            match Decision::from_key(&answer) {
+                Some(Decision::Help) => writeln!(self.output, "{}", HELP)?,
+                Some(decision) => return Ok(decision),
+                None => writeln!(self.output, "Unknown answer '{}', ? for help", answer.trim())?,
+            }
+        }
+    }
+
+    /// Let the user edit the fixed lines, returning an edit replacing the
+    /// original lines with the result, or `None` if they didn't change
+    fn edit(&mut self, source: &str, change: &Change) -> Result<Option<Edit>> {
+        let start = source[..change.start()].rfind('\n').map_or(0, |i| i + 1);
+        let end = source[change.end()..].find('\n').map_or(source.len(), |i| change.end() + i + 1);
+
+        let mut region_edits = change.edits.clone();
+        for edit in &mut region_edits {
+            edit.span.start.offset -= start as u32;
+            edit.span.end.offset -= start as u32;
+        }
+        let proposed = apply_edits(&source[start..end], &region_edits)?;
+
+        let file = std::env::temp_dir().join(format!("rustor-review-{}.php", std::process::id()));
+        fs::write(&file, &proposed).with_context(|| format!("Failed to write {}", file.display()))?;
+
+        let mut command = self.editor.split_whitespace();
+        let program = command.next().unwrap_or("vi");
+        let status = std::process::Command::new(program)
+            .args(command)
+            .arg(&file)
+            .status()
+            .with_context(|| format!("Failed to run editor '{}'", self.editor));
+        let edited = fs::read_to_string(&file);
+        let _ = fs::remove_file(&file);
+
+        if !status?.success() {
+            anyhow::bail!("Editor '{}' failed", self.editor);
+        }
+        let edited = edited.context("Failed to read the edited fix")?;
+        if edited == source[start..end] {
+            return Ok(None);
+        }
+
+        let span = change.edits[0].span;
+        let span = mago_span::Span::new(
+            span.file_id,
+            mago_span::Position::new(start as u32),
+            mago_span::Position::new(end as u32),
+        );
+        Ok(Some(Edit::with_rule(span, edited, change.message.clone(), change.rule.clone())))
+    }
+}
+
+/// Edit inserting `// rustor-ignore: <rules>` above a line, with its
+/// indentation, or after the opening tag that starts the line. `None` if
+/// the line starts inside a string or heredoc, or in inline HTML.
+fn ignore_comment(source: &str, tokens: &TokenStream, line: usize, rules: &BTreeSet<String>) -> Option<Edit> {
+    let start = if line <= 1 {
+        0
+    } else {
+        source.match_indices('\n').nth(line - 2).map_or(source.len(), |(i, _)| i + 1)
+    };
+    let end = source[start..].find('\n').map_or(source.len(), |i| start + i);
+    let comment = format!("// rustor-ignore: {}", rules.iter().map(|r| r.as_str()).collect::<Vec<_>>().join(", "));
+    let span = |start: usize, end: usize| {
+        mago_span::Span::new(
+            mago_database::file::FileId::zero(),
+            mago_span::Position::new(start as u32),
+            mago_span::Position::new(end as u32),
+        )
+    };
+
+    // Code on the opening tag's line moves to a line of its own
+    let first = tokens
+        .tokens()
+        .iter()
+        .skip_while(|token| token.end <= start)
+        .take_while(|token| token.start < end)
+        .find(|token| token.region != Region::InlineHtml);
+    if let Some(tag) = first.filter(|t| matches!(t.kind, TokenKind::OpenTag | TokenKind::EchoTag | TokenKind::ShortOpenTag)) {
+        let code = tag.end + (source[tag.end..end].len() - source[tag.end..end].trim_start().len());
+        return Some(Edit::new(span(tag.end, code), format!("\n{}\n", comment), "Add ignore comment"));
+    }
+    if !tokens.is_code(start) {
+        return None;
+    }
+
+    let indent: String = source[start..].chars().take_while(|c| *c == ' ' || *c == '\t').collect();
+    Some(Edit::new(span(start, start), format!("{}{}\n", indent, comment), "Add ignore comment"))
+}
+
+/// Add `[skip]` entries to a config file, creating it if needed
+pub fn record_skips(config_path: &Path, entries: &[(String, String)]) -> Result<()> {
+    let content = if config_path.exists() {
+        fs::read_to_string(config_path).with_context(|| format!("Failed to read {}", config_path.display()))?
+    } else {
+        String::new()
+    };
+    let mut doc: toml_edit::DocumentMut =
+        content.parse().with_context(|| format!("Failed to parse {}", config_path.display()))?;
+
+    let skip = doc
+        .entry("skip")
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .with_context(|| format!("[skip] in {} is not a table", config_path.display()))?;
+    for (rule, path) in entries {
+        let patterns = skip
+            .entry(rule)
+            .or_insert(toml_edit::value(toml_edit::Array::new()))
+            .as_array_mut()
+            .with_context(|| format!("[skip] {} in {} is not an array", rule, config_path.display()))?;
+        if !patterns.iter().any(|p| p.as_str() == Some(path)) {
+            patterns.push(path.as_str());
+        }
+    }
+
+    fs::write(config_path, doc.to_string()).with_context(|| format!("Failed to write {}", config_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mago_database::file::FileId;
+    use mago_span::{Position, Span};
+    use rustor_core::EditGroup;
+    use std::io::Cursor;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = "<?php\nif (true) {\n    $a = sizeof($x);\n    $b = sizeof($y);\n    $c = is_null($z);\n}\n";
+
+    fn span(start: usize, end: usize) -> Span {
+        Span::new(FileId::zero(), Position::new(start as u32), Position::new(end as u32))
+    }
+
+    /// Edits replacing each `sizeof` with `count` and `is_null($z)` with `$z === null`
+    fn edits() -> Vec<Edit> {
+        let mut edits: Vec<Edit> = SOURCE
+            .match_indices("sizeof")
+            .map(|(i, _)| Edit::with_rule(span(i, i + 6), "count", "Convert sizeof() to count()", "sizeof"))
+            .collect();
+        let start = SOURCE.find("is_null").unwrap();
+        edits.push(Edit::with_rule(span(start, start + 11), "$z === null", "Convert is_null", "is_null"));
+        edits
+    }
+
+    fn review(answers: &str, edits: Vec<Edit>) -> (FileReview, Vec<(String, String)>, String) {
+        let mut output = Vec::new();
+        let mut reviewer = Reviewer::new(Cursor::new(answers.to_string()), &mut output);
+        let result = reviewer.review_file(Path::new("a.php"), SOURCE, edits).unwrap();
+        let skips = reviewer.skip_entries().to_vec();
+        drop(reviewer);
+        (result, skips, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_apply_and_skip() {
+        let (result, _, output) = review("y\nn\ny\n", edits());
+        let new_source = result.new_source.unwrap();
+        assert!(new_source.contains("$a = count($x)"));
+        assert!(new_source.contains("$b = sizeof($y)"));
+        assert!(new_source.contains("$z === null"));
+        assert_eq!(result.applied.len(), 2);
+        assert!(output.contains("a.php:3"));
+        assert!(output.contains("(1/3)"));
+    }
+
+    #[test]
+    fn test_rule_decisions() {
+        // Apply all sizeof fixes, then skip is_null for the rest of the run
+        let (result, _, output) = review("a\nr\n", edits());
+        let new_source = result.new_source.unwrap();
+        assert!(new_source.contains("$b = count($y)"));
+        assert!(new_source.contains("is_null($z)"));
+        assert!(!output.contains("(2/3)"));
+    }
+
+    #[test]
+    fn test_ignore_comment_and_skip_entry() {
+        let (result, skips, _) = review("i\nx\ny\n", edits());
+        let new_source = result.new_source.unwrap();
+        assert!(new_source.contains("    // rustor-ignore: sizeof\n    $a = sizeof($x);"));
+        assert!(new_source.contains("$b = sizeof($y)"));
+        assert_eq!(skips, vec![("sizeof".to_string(), "a.php".to_string())]);
+    }
+
+    #[test]
+    fn test_ignore_comment_placement() {
+        let rules: BTreeSet<String> = ["sizeof".to_string()].into();
+        let ignore = |source: &str, line: usize| {
+            let comment = ignore_comment(source, &TokenStream::new(source), line, &rules)?;
+            Some(apply_edits(source, &[comment]).unwrap())
+        };
+
+        // After the opening tag that shares the edit's line
+        assert_eq!(
+            ignore("<?php $a = sizeof($x);\n", 1).unwrap(),
+            "<?php\n// rustor-ignore: sizeof\n$a = sizeof($x);\n"
+        );
+        assert_eq!(
+            ignore("<p><?php echo sizeof($x); ?></p>\n", 1).unwrap(),
+            "<p><?php\n// rustor-ignore: sizeof\necho sizeof($x); ?></p>\n"
+        );
+
+        // Not inside heredocs, multi-line strings or inline HTML
+        assert!(ignore("<?php\n$a = <<<EOT\n{$b}\nEOT . sizeof($x);\n", 4).is_none());
+        assert!(ignore("<?php\n$a = 'one\ntwo' . sizeof($x);\n", 3).is_none());
+        assert!(ignore("<p>\n<b><?= sizeof($x) ?></b>\n", 1).is_none());
+        assert_eq!(
+            ignore("<?php\n$a = <<<EOT\nx\nEOT;\n  $b = sizeof($x);\n", 5).unwrap(),
+            "<?php\n$a = <<<EOT\nx\nEOT;\n  // rustor-ignore: sizeof\n  $b = sizeof($x);\n"
+        );
+    }
+
+    #[test]
+    fn test_quit_and_unknown_answers() {
+        let (result, _, output) = review("z\n?\nq\n", edits());
+        assert!(result.new_source.is_none());
+        assert!(output.contains("Unknown answer 'z'"));
+        assert!(output.contains("apply this fix and all remaining"));
+
+        // End of input quits as well
+        let (result, _, _) = review("y\n", edits());
+        assert_eq!(result.applied.len(), 1);
+    }
+
+    #[test]
+    fn test_groups_reviewed_together() {
+        let mut group = EditGroup::new("is_null", "Convert both");
+        for (i, _) in SOURCE.match_indices("sizeof") {
+            group.add_edit(span(i, i + 6), "count");
+        }
+
+        let (result, _, output) = review("y\n", group.edits);
+        assert_eq!(result.new_source.unwrap().matches("count(").count(), 2);
+        assert!(output.contains("(1/1)"));
+    }
+
+    #[test]
+    fn test_edit_in_editor() {
+        let temp = TempDir::new().unwrap();
+        let editor = temp.path().join("editor.sh");
+        fs::write(&editor, "#!/bin/sh\nsed -i 's/count/COUNT/' \"$1\"\n").unwrap();
+        let mut permissions = fs::metadata(&editor).unwrap().permissions();
+        std::os::unix::fs::PermissionsExt::set_mode(&mut permissions, 0o755);
+        fs::set_permissions(&editor, permissions).unwrap();
+
+        let mut output = Vec::new();
+        let mut reviewer = Reviewer::new(Cursor::new("e\nn\nn\n".to_string()), &mut output);
+        reviewer.editor = editor.display().to_string();
+        let result = reviewer.review_file(Path::new("a.php"), SOURCE, edits()).unwrap();
+
+        assert!(result.new_source.unwrap().contains("    $a = COUNT($x);\n    $b = sizeof($y);"));
+    }
+
+    #[test]
+    fn test_record_skips() {
+        let temp = TempDir::new().unwrap();
+        let config = temp.path().join(".rustor.toml");
+        fs::write(&config, "# Project config\n[rules]\npreset = \"recommended\"\n\n[skip]\nsizeof = [\"vendor/*\"]\n").unwrap();
+
+        let entries = vec![
+            ("sizeof".to_string(), "src/a.php".to_string()),
+            ("match_expression".to_string(), "src/b.php".to_string()),
+            ("sizeof".to_string(), "src/a.php".to_string()),
+        ];
+        record_skips(&config, &entries).unwrap();
+
+        let content = fs::read_to_string(&config).unwrap();
+        assert!(content.starts_with("# Project config\n"));
+        let parsed: crate::config::Config = toml::from_str(&content).unwrap();
+        assert_eq!(parsed.skip["sizeof"], vec!["vendor/*", "src/a.php"]);
+        assert_eq!(parsed.skip["match_expression"], vec!["src/b.php"]);
+        assert_eq!(parsed.rules.preset.as_deref(), Some("recommended"));
+    }
+}
@@ -0,0 +1,506 @@
+//! Rule test runner (`rustor test-rules`)
+//!
+//! Runs the `tests:` embedded in YAML rules and Rector-style `.php.inc`
+//! fixtures, so a custom rule library can be regression tested in CI
+//! without writing Rust.
+//!
+//! A fixture holds the code before the rule, a `-----` line and the code
+//! after it; without the separator the rule must leave the code unchanged.
+//! Fixtures run the rule named by their nearest parent directory, native or
+//! YAML, e.g. `tests/sizeof/Fixture/basic.php.inc` runs `sizeof`.
+
+use anyhow::{Context, Result};
+use bumpalo::Bump;
+use colored::*;
+use mago_database::file::FileId;
+use rustor_core::apply_edits;
+use rustor_rules::{load_rules_from_file, RuleRegistry, TestCase};
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use walkdir::WalkDir;
+
+use crate::config::Config;
+use crate::output::{write_context_diff, xml_escape};
+
+/// Line separating the code before and after the rule in a fixture
+const FIXTURE_SEPARATOR: &str = "-----";
+
+/// Lines of context around differences
+const CONTEXT_LINES: usize = 3;
+
+/// Arguments for the test-rules subcommand
+#[derive(Default, clap::Args)]
+#[command(after_help = "FIXTURES:
+    A .php.inc file holds the code before the rule, a ----- line, and the
+    code after it. Without the separator the code must stay unchanged.
+    The rule is the nearest parent directory named after a rule.
+
+EXAMPLES:
+    rustor test-rules rules/
+    rustor test-rules rules/ tests/fixtures/ --format junit > rule-tests.xml
+    rustor test-rules --bundled --rule sizeof_to_count")]
+pub struct TestRulesArgs {
+    /// YAML rule files, or directories of rules and fixtures
+    pub paths: Vec<PathBuf>,
+    /// Only run tests of this rule (repeatable)
+    #[arg(long = "rule", short = 'r', value_name = "RULE")]
+    pub rules: Vec<String>,
+    /// Also test the YAML rules shipped with rustor
+    #[arg(long)]
+    pub bundled: bool,
+    /// Output format: text, junit (default: text)
+    #[arg(long, value_name = "FORMAT")]
+    pub format: Option<String>,
+    /// rustor config for rule options (default: search for .rustor.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Ignore config files
+    #[arg(long)]
+    pub no_config: bool,
+    /// Also list passing tests
+    #[arg(long, short = 'v')]
+    pub verbose: bool,
+}
+
+/// Outcome of one embedded test or fixture
+#[derive(Debug)]
+struct RuleTest {
+    /// Rule under test, empty when no rule was found for a fixture
+    rule: String,
+    /// `rules.yaml#2` for embedded tests, the path for fixtures
+    name: String,
+    file: PathBuf,
+    failure: Option<Failure>,
+}
+
+#[derive(Debug)]
+struct Failure {
+    message: String,
+    expected: String,
+    actual: String,
+}
+
+impl Failure {
+    fn new(message: impl Into<String>, expected: &str, actual: &str) -> Self {
+        Self { message: message.into(), expected: expected.to_string(), actual: actual.to_string() }
+    }
+}
+
+/// YAML rule files and fixtures found in the given paths
+#[derive(Debug, Default, PartialEq)]
+struct TestFiles {
+    yaml: Vec<PathBuf>,
+    fixtures: Vec<PathBuf>,
+}
+
+impl TestFiles {
+    fn collect(&mut self, path: &Path) {
+        if path.is_file() {
+            self.add(path);
+            return;
+        }
+        let mut files: Vec<PathBuf> = WalkDir::new(path)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.into_path())
+            .collect();
+        files.sort();
+        for file in files {
+            self.add(&file);
+        }
+    }
+
+    fn add(&mut self, file: &Path) {
+        let name = file.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let list = if name.ends_with(".php.inc") {
+            &mut self.fixtures
+        } else if name.ends_with(".yaml") || name.ends_with(".yml") {
+            &mut self.yaml
+        } else {
+            return;
+        };
+        if !list.iter().any(|f| f == file) {
+            list.push(file.to_path_buf());
+        }
+    }
+}
+
+/// Run the test-rules subcommand
+pub fn run_test_rules(args: TestRulesArgs) -> Result<ExitCode> {
+    let junit = match args.format.as_deref().unwrap_or("text") {
+        "text" => false,
+        "junit" => true,
+        other => anyhow::bail!("Invalid output format '{}'. Valid options: text, junit", other),
+    };
+    if args.paths.is_empty() && !args.bundled {
+        anyhow::bail!("No rule tests given. Pass YAML rule files or fixture directories, or use --bundled");
+    }
+
+    let config = if args.no_config {
+        Config::default()
+    } else {
+        match &args.config {
+            Some(path) => Config::load_path(path)?,
+            None => Config::load()?.map(|(config, _)| config).unwrap_or_default(),
+        }
+    };
+
+    let mut files = TestFiles::default();
+    if args.bundled {
+        if let Some(dir) = RuleRegistry::bundled_yaml_rules_dir() {
+            files.collect(&dir);
+        }
+    }
+    for path in &args.paths {
+        if !path.exists() {
+            anyhow::bail!("Path does not exist: {}", path.display());
+        }
+        files.collect(path);
+    }
+
+    let mut registry = RuleRegistry::new_with_config(&config.rules.to_rule_configs());
+    for file in &files.yaml {
+        registry.load_yaml_rules_from_file(file).map_err(anyhow::Error::msg)?;
+    }
+
+    let tests = run_tests(&registry, &files, &args.rules)?;
+    if tests.is_empty() {
+        anyhow::bail!("No rule tests found");
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    if junit {
+        write_junit(&mut stdout, &tests)?;
+    } else {
+        write_text(&mut stdout, &tests, args.verbose)?;
+    }
+
+    Ok(if tests.iter().any(|t| t.failure.is_some()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Run the embedded tests and fixtures of the selected rules
+fn run_tests(registry: &RuleRegistry, files: &TestFiles, only: &[String]) -> Result<Vec<RuleTest>> {
+    let selected = |rule: &str| only.is_empty() || only.iter().any(|r| r == rule);
+    let mut tests = Vec::new();
+
+    for file in &files.yaml {
+        let rules = load_rules_from_file(file).with_context(|| format!("Failed to load {}", file.display()))?;
+        for interpreter in rules.iter().filter(|r| selected(&r.rule().name)) {
+            for (i, test) in interpreter.rule().tests.iter().enumerate().filter(|(_, t)| !t.skip) {
+                let result = interpreter.run_single_test(test);
+                tests.push(RuleTest {
+                    rule: interpreter.rule().name.clone(),
+                    name: format!("{}#{}", file.display(), i + 1),
+                    file: file.clone(),
+                    failure: (!result.passed).then(|| embedded_failure(test, result.actual, result.error)),
+                });
+            }
+        }
+    }
+
+    let names: HashSet<&str> = registry.all_names().into_iter().collect();
+    for fixture in &files.fixtures {
+        let rule = fixture_rule(&names, fixture);
+        if !only.is_empty() && !rule.is_some_and(selected) {
+            continue;
+        }
+        let failure = match rule {
+            Some(rule) => run_fixture(registry, rule, fixture),
+            None => Some(Failure::new("No rule found: name a parent directory after the rule", "", "")),
+        };
+        tests.push(RuleTest {
+            rule: rule.unwrap_or_default().to_string(),
+            name: fixture.display().to_string(),
+            file: fixture.clone(),
+            failure,
+        });
+    }
+
+    Ok(tests)
+}
+
+fn embedded_failure(test: &TestCase, actual: Option<String>, error: Option<String>) -> Failure {
+    let message = error.unwrap_or_else(|| "Output differs".to_string());
+    match &test.output {
+        // Without edits the input is what the rule leaves behind
+        Some(expected) => Failure::new(message, expected, actual.as_deref().unwrap_or(&test.input)),
+        None => Failure::new(message, &test.input, actual.as_deref().unwrap_or_default()),
+    }
+}
+
+/// The rule a fixture tests: its nearest parent directory named after a rule
+fn fixture_rule<'a>(names: &HashSet<&'a str>, fixture: &Path) -> Option<&'a str> {
+    fixture
+        .ancestors()
+        .skip(1)
+        .filter_map(|dir| dir.file_name())
+        .find_map(|name| names.get(name.to_string_lossy().as_ref()).copied())
+}
+
+/// Run one rule on a fixture, `None` when it passes
+fn run_fixture(registry: &RuleRegistry, rule: &str, fixture: &Path) -> Option<Failure> {
+    let content = match std::fs::read_to_string(fixture) {
+        Ok(content) => content,
+        Err(e) => return Some(Failure::new(format!("Failed to read fixture: {}", e), "", "")),
+    };
+    let (before, expected) = split_fixture(&content);
+
+    let arena = Bump::new();
+    let file_id = FileId::new(fixture.to_string_lossy().as_ref());
+    let parsed = rustor_core::parse(&arena, file_id, before);
+    if let Some(error) = parsed.errors.first() {
+        let message = format!("Parse error at line {}, column {}: {}", error.line, error.column, error.message);
+        return Some(Failure::new(message, "", ""));
+    }
+
+    let enabled = HashSet::from([rule.to_string()]);
+    let edits = registry.check_all(parsed.program, before, &enabled);
+    let actual = match apply_edits(before, &edits) {
+        Ok(actual) => actual,
+        Err(e) => return Some(Failure::new(e.to_string(), expected, before)),
+    };
+
+    if actual.trim_end() == expected.trim_end() {
+        None
+    } else {
+        Some(Failure::new("Output differs", expected, &actual))
+    }
+}
+
+/// Split a fixture into the code before and after the rule
+///
+/// Without a separator the code is expected to stay the same.
+fn split_fixture(content: &str) -> (&str, &str) {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.trim_end() == FIXTURE_SEPARATOR {
+            return (&content[..offset], &content[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (content, content)
+}
+
+fn write_text(out: &mut dyn Write, tests: &[RuleTest], verbose: bool) -> std::io::Result<()> {
+    for test in tests {
+        match &test.failure {
+            None if verbose => writeln!(out, "{} {} {}", "PASS".green().bold(), test.rule.bold(), test.name)?,
+            None => {}
+            Some(failure) => {
+                writeln!(out, "{} {} {}", "FAIL".red().bold(), test.rule.bold(), test.name)?;
+                writeln!(out, "  {}", failure.message)?;
+                if failure.expected != failure.actual {
+                    writeln!(out, "  {} {}", "- expected".red(), "+ actual".green())?;
+                    write_context_diff(out, &failure.expected, &failure.actual, CONTEXT_LINES)?;
+                }
+                writeln!(out)?;
+            }
+        }
+    }
+
+    let failed = tests.iter().filter(|t| t.failure.is_some()).count();
+    let passed = format!("{} passed", tests.len() - failed);
+    if failed == 0 {
+        writeln!(out, "{}", passed.green())
+    } else {
+        writeln!(out, "{}, {}", passed, format!("{} failed", failed).red())
+    }
+}
+
+/// Write results as JUnit XML, one test suite per rule
+fn write_junit(out: &mut dyn Write, tests: &[RuleTest]) -> std::io::Result<()> {
+    let mut suites: BTreeMap<&str, Vec<&RuleTest>> = BTreeMap::new();
+    for test in tests {
+        suites.entry(&test.rule).or_default().push(test);
+    }
+    let failures = |tests: &[&RuleTest]| tests.iter().filter(|t| t.failure.is_some()).count();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="rustor test-rules" tests="{}" failures="{}">"#,
+        tests.len(),
+        tests.iter().filter(|t| t.failure.is_some()).count()
+    )?;
+    for (rule, tests) in &suites {
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{}" failures="{}">"#,
+            xml_escape(rule),
+            tests.len(),
+            failures(tests)
+        )?;
+        for test in tests {
+            let attributes = format!(
+                r#"name="{}" classname="{}" file="{}""#,
+                xml_escape(&test.name),
+                xml_escape(rule),
+                xml_escape(&test.file.display().to_string())
+            );
+            match &test.failure {
+                None => writeln!(out, "    <testcase {} />", attributes)?,
+                Some(failure) => {
+                    let diff = similar::TextDiff::from_lines(&failure.expected, &failure.actual)
+                        .unified_diff()
+                        .context_radius(CONTEXT_LINES)
+                        .header("expected", "actual")
+                        .to_string();
+                    writeln!(out, "    <testcase {}>", attributes)?;
+                    writeln!(
+                        out,
+                        r#"      <failure message="{}">{}</failure>"#,
+                        xml_escape(&failure.message),
+                        xml_escape(&diff)
+                    )?;
+                    writeln!(out, "    </testcase>")?;
+                }
+            }
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+    writeln!(out, "</testsuites>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const RULE: &str = r#"
+name: sizeof_alias
+description: "Replace sizeof() with count()"
+match:
+  node: FuncCall
+  name: sizeof
+  args:
+    - capture: $arr
+replace: "count($arr)"
+tests:
+  - input: "sizeof($x)"
+    output: "count($x)"
+  - input: "count($x)"
+  - input: "sizeof($y)"
+    output: "sizeof($y)"
+"#;
+
+    fn run(paths: &[&Path], only: &[&str]) -> Vec<RuleTest> {
+        let mut files = TestFiles::default();
+        for path in paths {
+            files.collect(path);
+        }
+        let mut registry = RuleRegistry::new();
+        for file in &files.yaml {
+            registry.load_yaml_rules_from_file(file).unwrap();
+        }
+        let only: Vec<String> = only.iter().map(|s| s.to_string()).collect();
+        run_tests(&registry, &files, &only).unwrap()
+    }
+
+    #[test]
+    fn test_split_fixture() {
+        assert_eq!(split_fixture("<?php\n$a;\n-----\n<?php\n$b;\n"), ("<?php\n$a;\n", "<?php\n$b;\n"));
+        assert_eq!(split_fixture("<?php\r\n$a;\r\n-----\r\n$b;"), ("<?php\r\n$a;\r\n", "$b;"));
+        assert_eq!(split_fixture("<?php\n$a; // -----\n"), ("<?php\n$a; // -----\n", "<?php\n$a; // -----\n"));
+    }
+
+    #[test]
+    fn test_embedded_tests() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("rules.yaml");
+        fs::write(&file, RULE).unwrap();
+
+        let tests = run(&[temp.path()], &[]);
+        assert_eq!(tests.len(), 3);
+        assert!(tests[0].failure.is_none());
+        assert!(tests[1].failure.is_none());
+
+        let failure = tests[2].failure.as_ref().unwrap();
+        assert_eq!(tests[2].name, format!("{}#3", file.display()));
+        assert_eq!((failure.expected.as_str(), failure.actual.as_str()), ("sizeof($y)", "count($y)"));
+
+        assert!(run(&[temp.path()], &["other"]).is_empty());
+    }
+
+    #[test]
+    fn test_fixtures() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path().join("sizeof/Fixture");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.php.inc"), "<?php\n$n = sizeof($x);\n-----\n<?php\n$n = count($x);\n").unwrap();
+        fs::write(dir.join("b.php.inc"), "<?php\n$n = count($x);\n").unwrap();
+        fs::write(dir.join("c.php.inc"), "<?php\n$n = sizeof($x);\n-----\n<?php\n$n = sizeof($x);\n").unwrap();
+        fs::write(temp.path().join("orphan.php.inc"), "<?php\n").unwrap();
+
+        let tests = run(&[temp.path()], &[]);
+        let outcomes: Vec<(&str, bool)> = tests.iter().map(|t| (t.rule.as_str(), t.failure.is_none())).collect();
+        assert_eq!(outcomes, vec![("", false), ("sizeof", true), ("sizeof", true), ("sizeof", false)]);
+
+        let failure = tests[3].failure.as_ref().unwrap();
+        assert_eq!(failure.actual, "<?php\n$n = count($x);\n");
+
+        // The fixture without a rule is left out when filtering by rule
+        assert_eq!(run(&[temp.path()], &["sizeof"]).len(), 3);
+    }
+
+    #[test]
+    fn test_yaml_rule_fixtures() {
+        let temp = TempDir::new().unwrap();
+        fs::write(temp.path().join("rules.yaml"), RULE).unwrap();
+        let dir = temp.path().join("sizeof_alias");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.php.inc"), "<?php\n$n = sizeof($x);\n-----\n<?php\n$n = count($x);\n").unwrap();
+
+        let tests = run(&[temp.path()], &["sizeof_alias"]);
+        let fixture = tests.iter().find(|t| t.name.ends_with("a.php.inc")).unwrap();
+        assert_eq!(fixture.rule, "sizeof_alias");
+        assert!(fixture.failure.is_none());
+    }
+
+    #[test]
+    fn test_junit_output() {
+        let tests = vec![
+            RuleTest { rule: "sizeof".into(), name: "a.php.inc".into(), file: "a.php.inc".into(), failure: None },
+            RuleTest {
+                rule: "sizeof".into(),
+                name: "b.php.inc".into(),
+                file: "b.php.inc".into(),
+                failure: Some(Failure::new("Output differs", "count($x) < 1\n", "sizeof($x) < 1\n")),
+            },
+        ];
+        let mut out = Vec::new();
+        write_junit(&mut out, &tests).unwrap();
+        let xml = String::from_utf8(out).unwrap();
+
+        assert!(xml.contains(r#"<testsuite name="sizeof" tests="2" failures="1">"#));
+        assert!(xml.contains(r#"<testcase name="a.php.inc" classname="sizeof" file="a.php.inc" />"#));
+        assert!(xml.contains(r#"<failure message="Output differs">"#));
+        assert!(xml.contains("-count($x) &lt; 1\n+sizeof($x) &lt; 1"));
+    }
+
+    fn parse_test_rules_args(list: &[&str]) -> Result<TestRulesArgs, clap::Error> {
+        use clap::Parser;
+        match crate::Cli::try_parse_from(["rustor", "test-rules"].iter().chain(list))?.command {
+            Some(crate::Command::TestRules(args)) => Ok(args),
+            _ => panic!("not parsed as test-rules"),
+        }
+    }
+
+    #[test]
+    fn test_parse_test_rules_args() {
+        let parsed = parse_test_rules_args(&["rules/", "--rule=sizeof", "--bundled", "--format", "junit"]).unwrap();
+        assert_eq!(parsed.paths, vec![PathBuf::from("rules/")]);
+        assert_eq!(parsed.rules, vec!["sizeof"]);
+        assert!(parsed.bundled);
+        assert_eq!(parsed.format.as_deref(), Some("junit"));
+        assert!(parse_test_rules_args(&["--bogus"]).is_err());
+    }
+}
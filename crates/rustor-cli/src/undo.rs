@@ -0,0 +1,267 @@
+//! Backup session subcommand (`rustor undo`)
+//!
+//! Lists, restores and prunes the backup sessions `--fix` writes.
+
+use anyhow::{Context, Result};
+use colored::*;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use crate::backup::{BackupEntry, BackupManager, BackupSession};
+use crate::config::Config;
+
+/// What the undo subcommand does
+#[derive(Debug, PartialEq)]
+pub enum UndoAction {
+    /// Restore a session (default: the latest)
+    Restore,
+    /// List sessions
+    List,
+    /// Delete sessions older than this many days
+    Prune(u32),
+}
+
+/// Arguments for the undo subcommand
+#[derive(Default, clap::Args)]
+#[command(after_help = "EXAMPLES:
+    rustor undo
+    rustor undo --list
+    rustor undo --session 2024-05-01T10-20-30 src/User.php
+    rustor undo --prune 30")]
+pub struct UndoArgs {
+    /// Only restore these files (default: every file in the session)
+    pub files: Vec<PathBuf>,
+    /// List backup sessions with file counts and rules applied
+    #[arg(long, conflicts_with_all = ["prune", "session", "force", "files"])]
+    pub list: bool,
+    /// Delete sessions older than DAYS days
+    #[arg(long, value_name = "DAYS", conflicts_with_all = ["session", "force", "files"])]
+    pub prune: Option<u32>,
+    /// Session to restore (default: the latest)
+    #[arg(long, value_name = "ID")]
+    pub session: Option<String>,
+    /// Restore files even if they changed since the fix
+    #[arg(long)]
+    pub force: bool,
+    /// Backup directory (default: [fix] backup_dir or .rustor-backups)
+    #[arg(long, value_name = "DIR")]
+    pub backup_dir: Option<PathBuf>,
+    /// Config file (default: search for .rustor.toml)
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+}
+
+impl UndoArgs {
+    /// What to do: list, prune, or restore
+    pub fn action(&self) -> UndoAction {
+        match self.prune {
+            Some(days) => UndoAction::Prune(days),
+            None if self.list => UndoAction::List,
+            None => UndoAction::Restore,
+        }
+    }
+}
+
+/// Run the undo subcommand
+pub fn run_undo(args: UndoArgs) -> Result<ExitCode> {
+    let config = match &args.config {
+        Some(path) => Config::load_path(path)?,
+        None => Config::load()?.map(|(config, _)| config).unwrap_or_default(),
+    };
+    let backup_dir = args
+        .backup_dir
+        .clone()
+        .or_else(|| config.fix.backup_dir.clone().map(PathBuf::from))
+        .unwrap_or_else(|| PathBuf::from(".rustor-backups"));
+    let manager = BackupManager::new(backup_dir.clone(), false);
+
+    match args.action() {
+        UndoAction::List => list_sessions(&manager, &backup_dir),
+        UndoAction::Prune(days) => {
+            let cutoff = chrono::Local::now() - chrono::Duration::days(days.into());
+            let pruned = manager.prune(cutoff)?;
+            for id in &pruned {
+                println!("Deleted {}", id);
+            }
+            eprintln!("{} session(s) older than {} day(s) deleted", pruned.len(), days);
+            Ok(ExitCode::SUCCESS)
+        }
+        UndoAction::Restore => restore_session(&manager, &backup_dir, &args),
+    }
+}
+
+fn list_sessions(manager: &BackupManager, backup_dir: &Path) -> Result<ExitCode> {
+    let sessions = manager.sessions()?;
+    if sessions.is_empty() {
+        println!("No backup sessions in {}", backup_dir.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    for session in sessions.iter().rev() {
+        let files = session.files();
+        let mut rules: Vec<&str> = files.iter().flat_map(|f| f.rules.iter().map(|r| r.as_str())).collect();
+        rules.sort();
+        rules.dedup();
+
+        let rules = if session.manifest.is_none() {
+            "(no manifest)".dimmed().to_string()
+        } else {
+            rules.join(", ")
+        };
+        println!("{}  {:>4} file(s)  {}", session.id.bold(), files.len(), rules);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn restore_session(manager: &BackupManager, backup_dir: &Path, args: &UndoArgs) -> Result<ExitCode> {
+    let mut sessions = manager.sessions()?;
+    let session = match &args.session {
+        Some(id) => {
+            let index = sessions
+                .iter()
+                .position(|s| &s.id == id)
+                .with_context(|| format!("No backup session '{}' in {}. Use --list to see sessions", id, backup_dir.display()))?;
+            sessions.swap_remove(index)
+        }
+        None => sessions
+            .pop()
+            .with_context(|| format!("No backup sessions in {}", backup_dir.display()))?,
+    };
+
+    let entries = select_files(&session, &args.files)?;
+
+    // Refuse to overwrite work done after the fix
+    let conflicts: Vec<&BackupEntry> = entries
+        .iter()
+        .filter(|entry| session.changed_since_fix(entry).unwrap_or(true))
+        .collect();
+    if !conflicts.is_empty() && !args.force {
+        eprintln!("{}: These files changed since the fix (or can't be checked):", "Error".red());
+        for entry in conflicts {
+            eprintln!("  {}", relative(&session.target(entry)).display());
+        }
+        eprintln!("Nothing was restored. Use --force to restore them anyway.");
+        return Ok(ExitCode::from(1));
+    }
+
+    for entry in &entries {
+        session.restore(entry)?;
+        println!("Restored {}", relative(&session.target(entry)).display());
+    }
+    eprintln!("{} file(s) restored from session {}", entries.len(), session.id);
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Entries of the session to restore: all, or the ones matching `files`
+fn select_files(session: &BackupSession, files: &[PathBuf]) -> Result<Vec<BackupEntry>> {
+    let entries = session.files();
+    if files.is_empty() {
+        return Ok(entries);
+    }
+
+    let mut selected = Vec::new();
+    for file in files {
+        let wanted = absolute(file);
+        let entry = entries
+            .iter()
+            .find(|entry| absolute(&session.target(entry)) == wanted)
+            .with_context(|| format!("{} is not in backup session {}", file.display(), session.id))?;
+        selected.push(entry.clone());
+    }
+    Ok(selected)
+}
+
+/// Path relative to the current directory, if it is inside it
+fn relative(path: &Path) -> PathBuf {
+    std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok().map(Path::to_path_buf))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Absolute form of a path that may not exist
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .or_else(|_| std::path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn parse_undo_args(list: &[&str]) -> Result<UndoArgs, clap::Error> {
+        use clap::Parser;
+        match crate::Cli::try_parse_from(["rustor", "undo"].iter().chain(list))?.command {
+            Some(crate::Command::Undo(args)) => Ok(args),
+            _ => panic!("not parsed as undo"),
+        }
+    }
+
+    #[test]
+    fn test_parse_undo_args() {
+        let parsed = parse_undo_args(&["--session", "2024-01-01T00-00-00", "--force", "src/a.php"]).unwrap();
+        assert_eq!(parsed.action(), UndoAction::Restore);
+        assert_eq!(parsed.session.as_deref(), Some("2024-01-01T00-00-00"));
+        assert!(parsed.force);
+        assert_eq!(parsed.files, vec![PathBuf::from("src/a.php")]);
+
+        assert_eq!(parse_undo_args(&["--prune=7"]).unwrap().action(), UndoAction::Prune(7));
+        assert_eq!(parse_undo_args(&["--list"]).unwrap().action(), UndoAction::List);
+        assert!(parse_undo_args(&["--prune", "soon"]).is_err());
+        assert!(parse_undo_args(&["--list", "--force"]).is_err());
+        assert!(parse_undo_args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn test_restore_refuses_changed_files() {
+        let temp = TempDir::new().unwrap();
+        let backup_dir = temp.path().join("backups");
+        let file = temp.path().join("a.php");
+        fs::write(&file, "<?php sizeof($a);").unwrap();
+
+        let mut manager = BackupManager::new(backup_dir.clone(), true);
+        manager.init_session().unwrap();
+        let backup_path = manager.backup_file(&file).unwrap().unwrap();
+        fs::write(&file, "<?php count($a);").unwrap();
+        manager.record_fix(&file, &backup_path, vec!["sizeof".into()], "<?php count($a);");
+        manager.finish_session().unwrap();
+
+        // Edited after the fix
+        fs::write(&file, "<?php count($a) + 1;").unwrap();
+        let undo = |force: bool| {
+            let args = UndoArgs {
+                files: vec![file.clone()],
+                force,
+                backup_dir: Some(backup_dir.clone()),
+                ..Default::default()
+            };
+            restore_session(&manager, &backup_dir, &args).unwrap()
+        };
+
+        assert_eq!(undo(false), ExitCode::from(1));
+        assert_eq!(fs::read_to_string(&file).unwrap(), "<?php count($a) + 1;");
+
+        assert_eq!(undo(true), ExitCode::SUCCESS);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "<?php sizeof($a);");
+    }
+
+    #[test]
+    fn test_select_unknown_file() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("a.php");
+        fs::write(&file, "<?php").unwrap();
+
+        let mut manager = BackupManager::new(temp.path().join("backups"), true);
+        manager.init_session().unwrap();
+        let backup_path = manager.backup_file(&file).unwrap().unwrap();
+        manager.record_fix(&file, &backup_path, vec![], "<?php");
+        manager.finish_session().unwrap();
+
+        let session = manager.sessions().unwrap().remove(0);
+        assert_eq!(select_files(&session, &[]).unwrap().len(), 1);
+        assert!(select_files(&session, &[temp.path().join("b.php")]).is_err());
+    }
+}
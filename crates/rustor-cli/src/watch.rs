@@ -1,14 +1,16 @@
 //! Watch mode for rustor - re-run analysis on file changes
 //!
 //! Uses the `notify` crate for cross-platform file watching with debouncing.
+//! Refactoring rules are re-run through [`run_watch`]; static analysis and
+//! fixers implement [`WatchTarget`] and only report what changed between runs.
 
 use anyhow::Result;
 use colored::*;
 use notify::RecursiveMode;
-use notify_debouncer_mini::{new_debouncer, DebouncedEventKind};
-use std::collections::HashSet;
-use std::path::PathBuf;
-use std::sync::mpsc::channel;
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, DebouncedEventKind, Debouncer};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
 use std::time::Duration;
 
 use crate::cache::{hash_file, hash_rules, Cache};
@@ -28,6 +30,10 @@ pub struct WatchConfig {
     pub verbose: bool,
     /// Debounce duration (default 100ms)
     pub debounce: Duration,
+    /// Clear the screen before each re-run
+    pub clear_screen: bool,
+    /// Print a "3 new, 5 fixed, 120 total" line after each run
+    pub summary: bool,
 }
 
 impl Default for WatchConfig {
@@ -38,12 +44,187 @@ impl Default for WatchConfig {
             format: OutputFormat::Text,
             verbose: false,
             debounce: Duration::from_millis(100),
+            clear_screen: false,
+            summary: false,
         }
     }
 }
 
 /// Run watch mode - monitors files and re-runs analysis on changes
 pub fn run_watch(config: WatchConfig) -> Result<()> {
+    let (_debouncer, rx) = start_watcher(&config)?;
+
+    let rules_hash = hash_rules(&config.enabled_rules);
+    let mut cache = Cache::default();
+
+    if config.format == OutputFormat::Text {
+        println!();
+        println!("{}", "Watching for changes (Ctrl+C to stop)...".dimmed());
+        println!();
+    }
+
+    // Initial run
+    run_analysis(&config, &mut cache, rules_hash)?;
+
+    // Watch loop
+    while let Some(changed_files) = next_changes(&rx) {
+        if config.format == OutputFormat::Text {
+            if config.clear_screen {
+                clear_screen();
+            }
+            println!("{}", "File changes detected, re-analyzing...".cyan());
+            println!();
+        }
+
+        // Invalidate cache for changed files
+        for path in &changed_files {
+            cache.entries.remove(path);
+        }
+
+        // Re-run analysis
+        run_analysis(&config, &mut cache, rules_hash)?;
+
+        if config.format == OutputFormat::Text {
+            println!();
+            println!("{}", "Watching for changes (Ctrl+C to stop)...".dimmed());
+        }
+    }
+
+    Ok(())
+}
+
+/// A problem reported in watch mode: an analysis issue or a fixer violation
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub file: PathBuf,
+    pub line: usize,
+    /// Check identifier or fixer name
+    pub source: String,
+    pub message: String,
+}
+
+/// Checks re-run by [`run_watch_incremental`]
+pub trait WatchTarget {
+    /// Check every file, returning the findings per file
+    fn check_all(&mut self) -> BTreeMap<PathBuf, Vec<Finding>>;
+
+    /// Check again after `changed` files were modified, created or deleted
+    ///
+    /// Returns the findings of every file that was checked again; files that
+    /// are gone map to no findings.
+    fn check_changed(&mut self, changed: &[PathBuf]) -> BTreeMap<PathBuf, Vec<Finding>>;
+}
+
+/// Run watch mode for a [`WatchTarget`], printing only new and fixed findings
+pub fn run_watch_incremental(config: &WatchConfig, target: &mut impl WatchTarget) -> Result<()> {
+    let (_debouncer, rx) = start_watcher(config)?;
+
+    let mut findings = target.check_all();
+    let total: usize = findings.values().map(Vec::len).sum();
+    let all: Vec<&Finding> = findings.values().flatten().collect();
+    print_findings(&all, &[]);
+    if config.summary {
+        print_summary(total, 0, total);
+    }
+
+    println!();
+    println!("{}", "Watching for changes (Ctrl+C to stop)...".dimmed());
+
+    while let Some(changed_files) = next_changes(&rx) {
+        if config.clear_screen {
+            clear_screen();
+        }
+
+        let mut added = Vec::new();
+        let mut fixed = Vec::new();
+        for (file, new) in target.check_changed(&changed_files) {
+            let old = findings.remove(&file).unwrap_or_default();
+            let (file_added, file_fixed) = diff_findings(&old, &new);
+            added.extend(file_added);
+            fixed.extend(file_fixed);
+            if !new.is_empty() {
+                findings.insert(file, new);
+            }
+        }
+
+        println!();
+        if added.is_empty() && fixed.is_empty() && !config.summary {
+            println!("{}", "No new or fixed issues".dimmed());
+        }
+        print_findings(&added.iter().collect::<Vec<_>>(), &fixed.iter().collect::<Vec<_>>());
+        if config.summary {
+            let total = findings.values().map(Vec::len).sum();
+            print_summary(added.len(), fixed.len(), total);
+        }
+    }
+
+    Ok(())
+}
+
+/// Findings in `new` but not in `old`, and in `old` but not in `new`
+///
+/// Findings are matched by source and message only, so code moving up or
+/// down doesn't report its findings as fixed and new again.
+fn diff_findings(old: &[Finding], new: &[Finding]) -> (Vec<Finding>, Vec<Finding>) {
+    let mut remaining: Vec<&Finding> = old.iter().collect();
+    let mut added = Vec::new();
+
+    for finding in new {
+        let same = remaining
+            .iter()
+            .position(|o| o.source == finding.source && o.message == finding.message);
+        match same {
+            Some(index) => {
+                remaining.remove(index);
+            }
+            None => added.push(finding.clone()),
+        }
+    }
+
+    (added, remaining.into_iter().cloned().collect())
+}
+
+fn print_findings(added: &[&Finding], fixed: &[&Finding]) {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let location = |finding: &Finding| {
+        let file = finding.file.strip_prefix(&cwd).unwrap_or(&finding.file);
+        format!("{}:{}", file.display(), finding.line)
+    };
+
+    for finding in added {
+        println!(
+            "{} {} {} {}",
+            "+".red().bold(),
+            location(finding).cyan(),
+            finding.message,
+            format!("[{}]", finding.source).dimmed()
+        );
+    }
+    for finding in fixed {
+        println!(
+            "{} {} {} {}",
+            "-".green().bold(),
+            location(finding).cyan(),
+            finding.message.dimmed(),
+            format!("[{}]", finding.source).dimmed()
+        );
+    }
+}
+
+fn print_summary(added: usize, fixed: usize, total: usize) {
+    println!(
+        "{}: {} new, {} fixed, {} total",
+        "Summary".bold(),
+        added,
+        fixed,
+        total
+    );
+}
+
+/// Watch the configured paths, announcing each in text output
+fn start_watcher(
+    config: &WatchConfig,
+) -> Result<(Debouncer<notify::RecommendedWatcher>, Receiver<DebounceEventResult>)> {
     let (tx, rx) = channel();
 
     // Create a debounced file watcher
@@ -66,19 +247,11 @@ pub fn run_watch(config: WatchConfig) -> Result<()> {
         }
     }
 
-    let rules_hash = hash_rules(&config.enabled_rules);
-    let mut cache = Cache::default();
-
-    if config.format == OutputFormat::Text {
-        println!();
-        println!("{}", "Watching for changes (Ctrl+C to stop)...".dimmed());
-        println!();
-    }
-
-    // Initial run
-    run_analysis(&config, &mut cache, rules_hash)?;
+    Ok((debouncer, rx))
+}
 
-    // Watch loop
+/// Wait for the next batch of changed PHP files, `None` once the watcher stops
+fn next_changes(rx: &Receiver<DebounceEventResult>) -> Option<Vec<PathBuf>> {
     loop {
         match rx.recv() {
             Ok(Ok(events)) => {
@@ -86,35 +259,15 @@ pub fn run_watch(config: WatchConfig) -> Result<()> {
 
                 for event in events {
                     if event.kind == DebouncedEventKind::Any {
-                        let path = &event.path;
-                        if path.extension().is_some_and(|ext| ext == "php") {
-                            if !changed_files.contains(path) {
-                                changed_files.push(path.clone());
-                            }
+                        let path = canonical(&event.path);
+                        if path.extension().is_some_and(|ext| ext == "php") && !changed_files.contains(&path) {
+                            changed_files.push(path);
                         }
                     }
                 }
 
                 if !changed_files.is_empty() {
-                    // Clear screen for fresh output
-                    if config.format == OutputFormat::Text {
-                        print!("\x1B[2J\x1B[1;1H"); // Clear screen and move cursor to top
-                        println!("{}", "File changes detected, re-analyzing...".cyan());
-                        println!();
-                    }
-
-                    // Invalidate cache for changed files
-                    for path in &changed_files {
-                        cache.entries.remove(path);
-                    }
-
-                    // Re-run analysis
-                    run_analysis(&config, &mut cache, rules_hash)?;
-
-                    if config.format == OutputFormat::Text {
-                        println!();
-                        println!("{}", "Watching for changes (Ctrl+C to stop)...".dimmed());
-                    }
+                    return Some(changed_files);
                 }
             }
             Ok(Err(error)) => {
@@ -122,12 +275,19 @@ pub fn run_watch(config: WatchConfig) -> Result<()> {
             }
             Err(_) => {
                 // Channel closed, exit cleanly
-                break;
+                return None;
             }
         }
     }
+}
 
-    Ok(())
+/// Canonical form of a path, or the path itself if it no longer exists
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn clear_screen() {
+    print!("\x1B[2J\x1B[1;1H"); // Clear screen and move cursor to top
 }
 
 /// Run analysis on all watched files
@@ -175,7 +335,20 @@ fn run_analysis(config: &WatchConfig, cache: &mut Cache, rules_hash: u64) -> Res
 
         // Process file
         match process_file(path, &config.enabled_rules) {
-            Ok(Some(result)) => {
+            Ok(result) => {
+                if config.format == OutputFormat::Text {
+                    for error in &result.parse_errors {
+                        eprintln!(
+                            "{}: {}:{}:{} - {}",
+                            "Parse error".red(),
+                            path.display(),
+                            error.line,
+                            error.column,
+                            error.message
+                        );
+                    }
+                }
+
                 if !result.edits.is_empty() {
                     files_with_changes += 1;
                     total_edits += result.edits.len();
@@ -187,21 +360,15 @@ fn run_analysis(config: &WatchConfig, cache: &mut Cache, rules_hash: u64) -> Res
                         }
                         println!();
                     }
+                }
 
-                    // Update cache
+                // Files with parse errors aren't cached, so the errors show on every run
+                if result.parse_errors.is_empty() {
                     if let Ok(content_hash) = hash_file(path) {
-                        cache.update(path.clone(), content_hash, rules_hash, true, result.edits.len());
-                    }
-                } else {
-                    // No changes - update cache
-                    if let Ok(content_hash) = hash_file(path) {
-                        cache.update(path.clone(), content_hash, rules_hash, false, 0);
+                        cache.update(path.clone(), content_hash, rules_hash, !result.edits.is_empty(), result.edits.len());
                     }
                 }
             }
-            Ok(None) => {
-                // Parse error - skip silently in watch mode
-            }
             Err(_) => {
                 // Error - skip silently in watch mode
             }
@@ -237,5 +404,26 @@ mod tests {
         assert_eq!(config.format, OutputFormat::Text);
         assert!(!config.verbose);
         assert_eq!(config.debounce, Duration::from_millis(100));
+        assert!(!config.clear_screen);
+        assert!(!config.summary);
+    }
+
+    fn finding(line: usize, message: &str) -> Finding {
+        Finding {
+            file: PathBuf::from("a.php"),
+            line,
+            source: "test".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_findings() {
+        let old = vec![finding(1, "kept"), finding(2, "gone"), finding(3, "twice"), finding(4, "twice")];
+        let new = vec![finding(5, "kept"), finding(6, "twice"), finding(7, "added")];
+
+        let (added, fixed) = diff_findings(&old, &new);
+        assert_eq!(added.iter().map(|f| f.line).collect::<Vec<_>>(), [7]);
+        assert_eq!(fixed.iter().map(|f| f.message.as_str()).collect::<Vec<_>>(), ["gone", "twice"]);
     }
 }
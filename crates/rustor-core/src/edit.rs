@@ -1,8 +1,12 @@
 //! Span-based source code editing with format preservation
 
 use mago_span::Span;
+use std::sync::atomic::{AtomicU32, Ordering};
 use thiserror::Error;
 
+/// Source of unique `EditGroup` ids
+static NEXT_GROUP_ID: AtomicU32 = AtomicU32::new(1);
+
 /// Errors that can occur during edit application
 #[derive(Error, Debug)]
 pub enum EditError {
@@ -24,6 +28,8 @@ pub struct Edit {
     pub message: String,
     /// Optional rule name for identification
     pub rule: Option<String>,
+    /// Id of the `EditGroup` this edit belongs to, if any
+    pub group: Option<u32>,
 }
 
 /// Represents a group of related edits that should be applied atomically
@@ -41,6 +47,9 @@ pub struct EditGroup {
     pub message: String,
     /// Rule name that generated this group
     pub rule: String,
+    /// Unique id, stored in each edit so the group can be found again
+    /// after its edits were flattened
+    pub id: u32,
 }
 
 impl EditGroup {
@@ -50,6 +59,7 @@ impl EditGroup {
             edits: Vec::new(),
             message: message.into(),
             rule: rule.into(),
+            id: NEXT_GROUP_ID.fetch_add(1, Ordering::Relaxed),
         }
     }
 
@@ -58,8 +68,9 @@ impl EditGroup {
         self.edits.push(Edit {
             span,
             replacement: replacement.into(),
-            message: String::new(),
+            message: self.message.clone(),
             rule: Some(self.rule.clone()),
+            group: Some(self.id),
         });
     }
 
@@ -75,6 +86,7 @@ impl EditGroup {
             replacement: replacement.into(),
             message: message.into(),
             rule: Some(self.rule.clone()),
+            group: Some(self.id),
         });
     }
 
@@ -102,6 +114,7 @@ impl Edit {
             replacement: replacement.into(),
             message: message.into(),
             rule: None,
+            group: None,
         }
     }
 
@@ -117,6 +130,7 @@ impl Edit {
             replacement: replacement.into(),
             message: message.into(),
             rule: Some(rule.into()),
+            group: None,
         }
     }
 
@@ -129,6 +143,14 @@ impl Edit {
     pub fn end_offset(&self) -> usize {
         self.span.end.offset as usize
     }
+
+    /// The text `apply_edits` inserts for this edit into `source`
+    ///
+    /// This can differ from `replacement`, since the leading whitespace of
+    /// the replaced text is preserved.
+    pub fn applied_replacement(&self, source: &str) -> String {
+        adjust_whitespace(&source[self.start_offset()..self.end_offset()], &self.replacement)
+    }
 }
 
 /// Apply edits to source code, preserving surrounding formatting
@@ -186,11 +208,8 @@ pub fn apply_edits(source: &str, edits: &[Edit]) -> Result<String, EditError> {
         let start = edit.start_offset();
         let end = edit.end_offset();
 
-        // Get original text for whitespace analysis
-        let original = &source[start..end];
-
         // Preserve leading whitespace from original
-        let replacement = adjust_whitespace(original, &edit.replacement);
+        let replacement = edit.applied_replacement(source);
 
         result.replace_range(start..end, &replacement);
     }
@@ -287,4 +306,19 @@ mod tests {
         let result = apply_edits(source, &[edit]);
         assert!(matches!(result, Err(EditError::SpanOutOfBounds { .. })));
     }
+
+    #[test]
+    fn test_group_edits_share_id() {
+        let mut first = EditGroup::new("rule", "Promote $a");
+        first.add_edit(make_span(0, 1), "");
+        first.add_edit(make_span(2, 3), "");
+        let mut second = EditGroup::new("rule", "Promote $a");
+        second.add_edit(make_span(4, 5), "");
+
+        assert_eq!(first.edits[0].group, Some(first.id));
+        assert_eq!(first.edits[1].group, Some(first.id));
+        assert_eq!(first.edits[1].message, "Promote $a");
+        assert_ne!(first.id, second.id);
+        assert_eq!(Edit::new(make_span(0, 1), "", "").group, None);
+    }
 }
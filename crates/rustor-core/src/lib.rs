@@ -5,10 +5,16 @@
 //! - `EditGroup`: A group of related edits for atomic application
 //! - `apply_edits()`: Function to apply edits preserving formatting
 //! - `apply_edit_groups()`: Function to apply edit groups atomically
+//! - `parse()`: Function to parse PHP, recovering from parse errors
+//! - `PathFilter`: Include and exclude patterns shared by all configs
 //! - `Visitor`: Trait for traversing PHP AST
 
 mod edit;
+pub mod parse;
+pub mod paths;
 pub mod visitor;
 
 pub use edit::{apply_edit_groups, apply_edits, Edit, EditError, EditGroup};
+pub use parse::{parse, ParseError, ParsedFile, PARSE_ERROR_IDENTIFIER};
+pub use paths::PathFilter;
 pub use visitor::{visit, Visitor};
@@ -0,0 +1,216 @@
+//! Parsing with error recovery
+//!
+//! mago-syntax stops at the first parse error and returns only the
+//! statements before it. [`parse`] recovers from each error by blanking the
+//! statement it occurred in and parsing again, so that every syntax error in
+//! a file is reported and the statements around them can still be checked.
+//!
+//! Blanked bytes are replaced with spaces (newlines are kept), so offsets,
+//! lines and columns in the recovered program match the original source.
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::{HasSpan, Span};
+use mago_syntax::ast::Program;
+use mago_syntax::lexer::Lexer;
+use mago_syntax::token::TokenKind;
+use mago_syntax_core::input::Input;
+use std::ops::Range;
+
+/// Identifier of parse errors in reports
+pub const PARSE_ERROR_IDENTIFIER: &str = "parse.error";
+
+/// Parse errors reported per file before giving up on recovery
+const MAX_ERRORS: usize = 100;
+
+/// A syntax error in a PHP file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Description of the error, as given by mago-syntax
+    pub message: String,
+    /// Location of the offending token in the source
+    pub span: Span,
+    /// Line of the error (1-based)
+    pub line: usize,
+    /// Column of the error in characters (1-based)
+    pub column: usize,
+}
+
+/// A parsed PHP file with all of its parse errors
+pub struct ParsedFile<'arena> {
+    /// The statements that could be parsed
+    ///
+    /// With parse errors, statements containing an error are missing.
+    pub program: &'arena Program<'arena>,
+    /// Parse errors in source order
+    pub errors: Vec<ParseError>,
+}
+
+impl ParsedFile<'_> {
+    /// Whether the file parsed without errors
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Parse PHP source, recovering from parse errors
+pub fn parse<'arena>(arena: &'arena Bump, file_id: FileId, source: &str) -> ParsedFile<'arena> {
+    let (mut program, mut error) = mago_syntax::parser::parse_file_content(arena, file_id, source);
+    let mut errors = Vec::new();
+    let mut text = source.as_bytes().to_vec();
+
+    while let Some(e) = error.take() {
+        let span = e.span();
+        let offset = span.start.offset as usize;
+        if errors.last().is_some_and(|last: &ParseError| last.span.start.offset as usize >= offset) {
+            break;
+        }
+
+        let (line, column) = line_column(source, offset);
+        errors.push(ParseError { message: e.to_string(), span, line, column });
+        if errors.len() >= MAX_ERRORS {
+            break;
+        }
+
+        let Some(range) = statement_range(&text, offset) else {
+            break;
+        };
+        for byte in &mut text[range] {
+            if *byte != b'\n' && *byte != b'\r' {
+                *byte = b' ';
+            }
+        }
+
+        // Whole tokens are blanked, so the text stays valid UTF-8
+        let Ok(recovered) = std::str::from_utf8(&text) else {
+            break;
+        };
+        (program, error) = mago_syntax::parser::parse_file_content(arena, file_id, recovered);
+    }
+
+    ParsedFile { program, errors }
+}
+
+/// The byte range of the statement around a parse error at `offset`
+///
+/// The statement starts after the previous `;`, `{`, `}` or PHP tag and ends
+/// at the next `;` or with its braced block. A stray `}` or `;` at the error
+/// is skipped on its own.
+fn statement_range(text: &[u8], offset: usize) -> Option<Range<usize>> {
+    let arena = Bump::new();
+    let mut lexer = Lexer::new(&arena, Input::new(FileId::zero(), text));
+    let mut tokens = Vec::new();
+    while let Some(Ok(token)) = lexer.advance() {
+        if !token.kind.is_trivia() {
+            tokens.push((token.kind, token.span.start.offset as usize, token.span.end.offset as usize));
+        }
+    }
+
+    let error = tokens.iter().position(|&(_, _, end)| end > offset)?;
+    let start = tokens[..error]
+        .iter()
+        .rposition(|&(kind, _, _)| is_statement_boundary(kind))
+        .map_or(error, |boundary| boundary + 1);
+
+    let mut depth = 0usize;
+    let mut end = tokens[error].2;
+    for (i, &(kind, _, token_end)) in tokens.iter().enumerate().skip(error) {
+        match kind {
+            TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightBrace if depth == 0 => {
+                if i == error {
+                    end = token_end;
+                }
+                break;
+            }
+            TokenKind::RightBrace => depth -= 1,
+            TokenKind::CloseTag if depth == 0 && i > error => break,
+            _ => {}
+        }
+        end = token_end;
+        if depth == 0 && matches!(kind, TokenKind::Semicolon | TokenKind::RightBrace) {
+            break;
+        }
+    }
+
+    Some(tokens[start].1..end)
+}
+
+fn is_statement_boundary(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Semicolon
+            | TokenKind::LeftBrace
+            | TokenKind::RightBrace
+            | TokenKind::OpenTag
+            | TokenKind::EchoTag
+            | TokenKind::ShortOpenTag
+            | TokenKind::CloseTag
+    )
+}
+
+/// Line and column (1-based) of a byte offset
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut end = offset.min(source.len());
+    while !source.is_char_boundary(end) {
+        end -= 1;
+    }
+    let before = &source[..end];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(source: &str) -> Vec<(usize, usize)> {
+        let arena = Bump::new();
+        parse(&arena, FileId::zero(), source).errors.iter().map(|e| (e.line, e.column)).collect()
+    }
+
+    #[test]
+    fn test_valid_source() {
+        let arena = Bump::new();
+        let parsed = parse(&arena, FileId::zero(), "<?php\n$a = 1;\n");
+        assert!(parsed.is_valid());
+        assert_eq!(parsed.program.statements.len(), 2);
+    }
+
+    #[test]
+    fn test_error_position() {
+        assert_eq!(errors("<?php\n\n$a = ;\n"), vec![(3, 6)]);
+    }
+
+    #[test]
+    fn test_reports_every_error() {
+        let source = "<?php\nfunction a() {\n    $x = ;\n}\nfunction b() {\n    $y = );\n}\n";
+        assert_eq!(errors(source), vec![(3, 10), (6, 10)]);
+    }
+
+    #[test]
+    fn test_keeps_unaffected_statements() {
+        let source = "<?php\n$a = 1;\nif ($b {\n    foo();\n}\n$c = 2;\n";
+        let arena = Bump::new();
+        let parsed = parse(&arena, FileId::zero(), source);
+        assert_eq!(parsed.errors.len(), 1);
+        assert_eq!(parsed.errors[0].line, 3);
+
+        // The statement after the error is still there, at its original offset
+        let last = parsed.program.statements.last().unwrap();
+        assert_eq!(last.span().start.offset as usize, source.find("$c").unwrap());
+    }
+
+    #[test]
+    fn test_stray_brace() {
+        let source = "<?php\n$a = 1;\n}\n$b = ;\n";
+        assert_eq!(errors(source), vec![(3, 1), (4, 6)]);
+    }
+
+    #[test]
+    fn test_unexpected_end_of_file() {
+        let source = "<?php\nfunction a() {\n    $x = 1;\n";
+        assert_eq!(errors(source).len(), 1);
+    }
+}
@@ -0,0 +1,174 @@
+//! Path include and exclude patterns
+//!
+//! One implementation for `.rustor.toml` (`[paths]`, `[skip]`), PHPStan's
+//! `paths` and `excludePaths`, and the Finder of a PHP-CS-Fixer config, so a
+//! pattern means the same thing whichever config it comes from.
+
+use std::path::{Component, Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Whether `path` matches an include or exclude pattern
+///
+/// A pattern matches:
+/// - the path itself or anything below it (`vendor`, `/project/src/Legacy`)
+/// - as a glob, the whole path or its file name (`*.generated.php`, `tests/**`)
+/// - with a trailing `/`, a directory of that name anywhere (`vendor/`)
+/// - containing `/`, any path containing it once `*` are removed (`*/Fixtures/*`)
+pub fn matches_pattern(pattern: &str, path: &Path) -> bool {
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let path = normalize(path);
+    if path.starts_with(normalize(Path::new(pattern))) {
+        return true;
+    }
+
+    let path_str = path.to_string_lossy();
+    if let Ok(glob) = glob::Pattern::new(pattern) {
+        if glob.matches(&path_str) {
+            return true;
+        }
+        if path.file_name().is_some_and(|name| glob.matches(&name.to_string_lossy())) {
+            return true;
+        }
+    }
+
+    if let Some(dir) = pattern.strip_suffix('/') {
+        let dir = dir.trim_start_matches("./");
+        if path_str.contains(&format!("/{}/", dir)) || path_str.starts_with(&format!("{}/", dir)) {
+            return true;
+        }
+    }
+
+    if pattern.contains('/') && !pattern.starts_with('/') {
+        let literal = pattern.replace('*', "");
+        if !literal.is_empty() && path_str.contains(&literal) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Whether `path` matches any of `patterns`
+pub fn matches_any<S: AsRef<str>>(patterns: &[S], path: &Path) -> bool {
+    patterns.iter().any(|pattern| matches_pattern(pattern.as_ref(), path))
+}
+
+/// `path` without `.` components, so `./src/a.php` matches `src`
+fn normalize(path: &Path) -> PathBuf {
+    path.components().filter(|c| !matches!(c, Component::CurDir)).collect()
+}
+
+/// The paths a tool looks in and the paths it leaves out
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathFilter {
+    /// Files and directories to check when no paths are given
+    pub include: Vec<PathBuf>,
+    /// Patterns of paths to leave out, see [`matches_pattern`]
+    pub exclude: Vec<String>,
+}
+
+impl PathFilter {
+    /// Create a filter
+    pub fn new(include: Vec<PathBuf>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Whether a path is left out
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        matches_any(&self.exclude, path)
+    }
+
+    /// Add another filter's paths and exclusions
+    pub fn extend(&mut self, other: PathFilter) {
+        for path in other.include {
+            if !self.include.contains(&path) {
+                self.include.push(path);
+            }
+        }
+        for pattern in other.exclude {
+            if !self.exclude.contains(&pattern) {
+                self.exclude.push(pattern);
+            }
+        }
+    }
+
+    /// The given paths, or the included ones if none are given
+    pub fn paths(&self, given: &[PathBuf]) -> Vec<PathBuf> {
+        if given.is_empty() {
+            self.include.clone()
+        } else {
+            given.to_vec()
+        }
+    }
+
+    /// PHP files under `paths`, minus excluded ones
+    ///
+    /// Files given directly are always kept.
+    pub fn php_files(&self, paths: &[PathBuf]) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        for path in paths {
+            if path.is_file() {
+                files.push(path.clone());
+            } else if path.is_dir() {
+                for entry in WalkDir::new(path).follow_links(true).into_iter().filter_map(|e| e.ok()) {
+                    let file = entry.path();
+                    if file.is_file() && file.extension().is_some_and(|ext| ext == "php") && !self.is_excluded(file) {
+                        files.push(file.to_path_buf());
+                    }
+                }
+            }
+        }
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix() {
+        assert!(matches_pattern("vendor", Path::new("vendor/autoload.php")));
+        assert!(matches_pattern("/app/src/Legacy", Path::new("/app/src/Legacy/Old.php")));
+        assert!(matches_pattern("src", Path::new("./src/a.php")));
+        assert!(!matches_pattern("vendor", Path::new("src/vendor.php")));
+    }
+
+    #[test]
+    fn test_glob() {
+        assert!(matches_pattern("*.generated.php", Path::new("src/Model.generated.php")));
+        assert!(matches_pattern("tests/**", Path::new("tests/Unit/ATest.php")));
+        assert!(matches_pattern("/app/*/Fixtures/*", Path::new("/app/tests/Fixtures/a.php")));
+        assert!(!matches_pattern("*.generated.php", Path::new("src/Model.php")));
+    }
+
+    #[test]
+    fn test_directory_name() {
+        assert!(matches_pattern("vendor/", Path::new("project/vendor/autoload.php")));
+        assert!(matches_pattern("vendor/", Path::new("vendor/package/file.php")));
+        assert!(!matches_pattern("vendor/", Path::new("src/vendor.php")));
+        assert!(matches_pattern("*/Fixtures/*", Path::new("tests/Fixtures/a.php")));
+    }
+
+    #[test]
+    fn test_filter_files() {
+        let dir = std::env::temp_dir().join(format!("rustor-paths-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src/Legacy")).unwrap();
+        std::fs::write(dir.join("src/a.php"), "<?php").unwrap();
+        std::fs::write(dir.join("src/Legacy/b.php"), "<?php").unwrap();
+        std::fs::write(dir.join("src/notes.txt"), "").unwrap();
+
+        let filter = PathFilter::new(vec![dir.join("src")], vec!["Legacy/".to_string()]);
+        let files = filter.php_files(&filter.paths(&[]));
+        assert_eq!(files, vec![dir.join("src/a.php")]);
+
+        // Given files are kept even when excluded
+        let legacy = dir.join("src/Legacy/b.php");
+        assert_eq!(filter.php_files(std::slice::from_ref(&legacy)), vec![legacy]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -85,6 +85,16 @@ pub trait Visitor<'a> {
                     self.traverse_class_like_member(member, source);
                 }
             }
+            Statement::Interface(iface) => {
+                for member in iface.members.iter() {
+                    self.traverse_class_like_member(member, source);
+                }
+            }
+            Statement::Enum(en) => {
+                for member in en.members.iter() {
+                    self.traverse_class_like_member(member, source);
+                }
+            }
             Statement::Namespace(ns) => {
                 match &ns.body {
                     NamespaceBody::Implicit(body) => {
@@ -255,6 +265,16 @@ pub trait Visitor<'a> {
                     }
                 }
             }
+            ClassLikeMember::Constant(constant) => {
+                for item in constant.items.iter() {
+                    self.traverse_expression(&item.value, source);
+                }
+            }
+            ClassLikeMember::EnumCase(case) => {
+                if let EnumCaseItem::Backed(backed) = &case.item {
+                    self.traverse_expression(&backed.value, source);
+                }
+            }
             _ => {}
         }
     }
@@ -369,6 +389,23 @@ pub trait Visitor<'a> {
                 // Traverse arrow function expression
                 self.traverse_expression(arrow.expression, source);
             }
+            Expression::Match(m) => {
+                self.traverse_expression(m.expression, source);
+                for arm in m.arms.iter() {
+                    if let MatchArm::Expression(arm) = arm {
+                        for condition in arm.conditions.iter() {
+                            self.traverse_expression(condition, source);
+                        }
+                    }
+                    self.traverse_expression(arm.expression(), source);
+                }
+            }
+            Expression::Throw(throw) => {
+                self.traverse_expression(throw.exception, source);
+            }
+            Expression::Clone(clone) => {
+                self.traverse_expression(clone.object, source);
+            }
             _ => {}
         }
     }
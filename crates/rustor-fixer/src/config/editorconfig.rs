@@ -0,0 +1,461 @@
+//! EditorConfig support
+//!
+//! Settings are resolved per file: `.editorconfig` files are read from the
+//! file's directory up to the first one with `root = true`, and the sections
+//! matching the file apply in order, the nearest file last.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use regex::Regex;
+
+use super::php_parser::PhpCsFixerConfig;
+use super::whitespace::{IndentStyle, LineEnding};
+use crate::fixers::FixerConfig;
+
+/// A parsed `.editorconfig` file
+#[derive(Debug, Clone, Default)]
+pub struct EditorConfig {
+    /// Whether `root = true` stops the search for parent files
+    pub root: bool,
+    sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone)]
+struct Section {
+    /// Matches paths relative to the file's directory, with `/` separators
+    pattern: Regex,
+    properties: Vec<(String, String)>,
+}
+
+/// Settings of one file, as far as `.editorconfig` defines them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditorConfigSettings {
+    /// `indent_style`: `Some(true)` for tabs, `Some(false)` for spaces
+    pub indent_tabs: Option<bool>,
+    /// `indent_size` (`tab` resolves to `tab_width`)
+    pub indent_size: Option<usize>,
+    /// `end_of_line` (`cr` isn't supported)
+    pub line_ending: Option<LineEnding>,
+    /// `insert_final_newline`
+    pub insert_final_newline: Option<bool>,
+    /// `trim_trailing_whitespace`
+    pub trim_trailing_whitespace: Option<bool>,
+}
+
+impl EditorConfig {
+    /// Parse the content of a `.editorconfig` file
+    ///
+    /// Sections with globs that can't be compiled are skipped.
+    pub fn parse(content: &str) -> Self {
+        let mut config = EditorConfig::default();
+        let mut current: Option<Section> = None;
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.rfind(']').map(|end| &l[..end])) {
+                config.sections.extend(current.take());
+                current = Regex::new(&glob_to_regex(glob))
+                    .ok()
+                    .map(|pattern| Section { pattern, properties: Vec::new() });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim().to_string();
+            match &mut current {
+                Some(section) => section.properties.push((key, value)),
+                None if key == "root" => config.root = value.eq_ignore_ascii_case("true"),
+                None => {}
+            }
+        }
+
+        config.sections.extend(current);
+        config
+    }
+
+    /// Properties of the sections matching `relative_path`, in order
+    fn properties_for<'a>(&'a self, relative_path: &'a str) -> impl Iterator<Item = &'a (String, String)> {
+        self.sections
+            .iter()
+            .filter(move |section| section.pattern.is_match(relative_path))
+            .flat_map(|section| section.properties.iter())
+    }
+}
+
+impl EditorConfigSettings {
+    /// Whether no setting is defined
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Indentation to use instead of `current`, if the settings define one
+    ///
+    /// A size without a style only applies to space indentation.
+    pub fn indent(&self, current: IndentStyle) -> Option<IndentStyle> {
+        match (self.indent_tabs, self.indent_size) {
+            (Some(true), _) => Some(IndentStyle::Tabs),
+            (Some(false), size) => Some(IndentStyle::Spaces(size.unwrap_or(current.width()))),
+            (None, Some(size)) if matches!(current, IndentStyle::Spaces(_)) => Some(IndentStyle::Spaces(size)),
+            (None, _) => None,
+        }
+    }
+
+    /// Apply the settings to a fixer config
+    ///
+    /// Settings of `explicit` win: `setIndent()`, `setLineEnding()` and rules
+    /// it configures are kept as they are.
+    pub fn apply(&self, config: &mut FixerConfig, explicit: Option<&PhpCsFixerConfig>) {
+        if !explicit.is_some_and(|c| c.indent_set) {
+            if let Some(indent) = self.indent(config.indent) {
+                config.indent = indent;
+            }
+        }
+        if !explicit.is_some_and(|c| c.line_ending_set) {
+            if let Some(line_ending) = self.line_ending {
+                config.line_ending = line_ending;
+            }
+        }
+
+        let fixers = [
+            ("single_blank_line_at_end_of_file", self.insert_final_newline),
+            ("no_trailing_whitespace", self.trim_trailing_whitespace),
+        ];
+        for (fixer, enabled) in fixers {
+            let Some(enabled) = enabled else {
+                continue;
+            };
+            if !explicit.is_some_and(|c| c.rules.contains_key(fixer)) {
+                config.fixer_overrides.insert(fixer.to_string(), enabled);
+            }
+        }
+    }
+
+    fn from_properties(properties: &HashMap<String, String>) -> Self {
+        let get = |key: &str| properties.get(key).map(|v| v.to_ascii_lowercase());
+        let bool_value = |key: &str| match get(key).as_deref() {
+            Some("true") => Some(true),
+            Some("false") => Some(false),
+            _ => None,
+        };
+
+        let indent_size = match get("indent_size").as_deref() {
+            Some("tab") => get("tab_width").and_then(|w| w.parse().ok()),
+            Some(size) => size.parse().ok(),
+            None => None,
+        };
+        EditorConfigSettings {
+            indent_tabs: match get("indent_style").as_deref() {
+                Some("tab") => Some(true),
+                Some("space") => Some(false),
+                _ => None,
+            },
+            indent_size: indent_size.filter(|&size| size > 0),
+            line_ending: match get("end_of_line").as_deref() {
+                Some("lf") => Some(LineEnding::Lf),
+                Some("crlf") => Some(LineEnding::CrLf),
+                _ => None,
+            },
+            insert_final_newline: bool_value("insert_final_newline"),
+            trim_trailing_whitespace: bool_value("trim_trailing_whitespace"),
+        }
+    }
+}
+
+/// Resolves `.editorconfig` settings for files, reading each
+/// `.editorconfig` once
+#[derive(Default)]
+pub struct EditorConfigResolver {
+    files: Mutex<HashMap<PathBuf, Option<Arc<EditorConfig>>>>,
+}
+
+impl EditorConfigResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settings for a file; empty if no `.editorconfig` applies
+    pub fn resolve(&self, path: &Path) -> EditorConfigSettings {
+        let Ok(path) = std::path::absolute(path) else {
+            return EditorConfigSettings::default();
+        };
+
+        // Nearest file first, up to the root one
+        let mut files = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            if let Some(file) = self.load(dir) {
+                let root = file.root;
+                files.push((dir, file));
+                if root {
+                    break;
+                }
+            }
+        }
+
+        let mut properties = HashMap::new();
+        for (dir, file) in files.iter().rev() {
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            for (key, value) in file.properties_for(&relative) {
+                properties.insert(key.clone(), value.clone());
+            }
+        }
+        EditorConfigSettings::from_properties(&properties)
+    }
+
+    fn load(&self, dir: &Path) -> Option<Arc<EditorConfig>> {
+        let mut files = self.files.lock().unwrap();
+        files
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let content = std::fs::read_to_string(dir.join(".editorconfig")).ok()?;
+                Some(Arc::new(EditorConfig::parse(&content)))
+            })
+            .clone()
+    }
+}
+
+/// Regex for an EditorConfig section glob
+///
+/// Globs without a `/` match the file name in any directory; others match
+/// the path relative to the `.editorconfig` file.
+fn glob_to_regex(glob: &str) -> String {
+    if glob.contains('/') {
+        format!("^{}$", convert_glob(glob.strip_prefix('/').unwrap_or(glob)))
+    } else {
+        format!("^(?:.*/)?{}$", convert_glob(glob))
+    }
+}
+
+fn convert_glob(glob: &str) -> String {
+    let chars: Vec<char> = glob.chars().collect();
+    let mut regex = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                regex.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                regex.push_str(".*");
+                i += 1;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']') {
+                Some(len) => {
+                    let class: String = chars[i + 1..i + 1 + len].iter().collect();
+                    let (negate, class) = match class.strip_prefix('!') {
+                        Some(rest) => ("^", rest.to_string()),
+                        None => ("", class),
+                    };
+                    let class: String = class
+                        .chars()
+                        .flat_map(|c| match c {
+                            '\\' | '[' | '^' | '&' | '~' => vec!['\\', c],
+                            c => vec![c],
+                        })
+                        .collect();
+                    regex.push_str(&format!("[{}{}]", negate, class));
+                    i += len + 1;
+                }
+                None => regex.push_str("\\["),
+            },
+            '{' => match closing_brace(&chars, i) {
+                Some(end) => {
+                    let inner: String = chars[i + 1..end].iter().collect();
+                    regex.push_str(&convert_braces(&inner));
+                    i = end;
+                }
+                None => regex.push_str("\\{"),
+            },
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    regex
+}
+
+/// Index of the `}` closing the `{` at `open`
+fn closing_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `{a,b}` alternatives or a `{1..10}` number range
+fn convert_braces(inner: &str) -> String {
+    if let Some((from, to)) = inner.split_once("..") {
+        if let (Ok(from), Ok(to)) = (from.parse::<i64>(), to.parse::<i64>()) {
+            let (from, to) = (from.min(to), from.max(to));
+            if to - from <= 1000 {
+                let numbers: Vec<String> = (from..=to).map(|n| n.to_string()).collect();
+                return format!("(?:{})", numbers.join("|"));
+            }
+            return "[+-]?[0-9]+".to_string();
+        }
+    }
+
+    // Split on commas outside nested braces
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut current = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                current.push(c);
+                current.extend(chars.next());
+                continue;
+            }
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    alternatives.push(current);
+
+    if alternatives.len() == 1 {
+        // A single word in braces is literal
+        return format!("\\{{{}\\}}", convert_glob(inner));
+    }
+    let alternatives: Vec<String> = alternatives.iter().map(|a| convert_glob(a)).collect();
+    format!("(?:{})", alternatives.join("|"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        Regex::new(&glob_to_regex(glob)).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(matches("*", "src/Foo.php"));
+        assert!(matches("*.php", "src/Foo.php"));
+        assert!(!matches("*.php", "src/Foo.phpt"));
+        assert!(matches("*.{php,inc}", "lib/a.inc"));
+        assert!(matches("src/*.php", "src/Foo.php"));
+        assert!(!matches("src/*.php", "src/Sub/Foo.php"));
+        assert!(matches("src/**.php", "src/Sub/Foo.php"));
+        assert!(matches("/src/**", "src/a/b"));
+        assert!(!matches("src/*.php", "lib/src/Foo.php"));
+        assert!(matches("file?.php", "file1.php"));
+        assert!(matches("[!a]*.php", "b.php"));
+        assert!(!matches("[!a]*.php", "a.php"));
+        assert!(matches("v{1..3}.php", "v2.php"));
+        assert!(!matches("v{1..3}.php", "v4.php"));
+        assert!(matches("{single}.php", "{single}.php"));
+    }
+
+    #[test]
+    fn test_resolve_nested_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join(".editorconfig"),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = 4\nend_of_line = lf\ninsert_final_newline = true\n\n[*.md]\ntrim_trailing_whitespace = false\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("packages/legacy/src")).unwrap();
+        std::fs::write(
+            root.join("packages/legacy/.editorconfig"),
+            "# Tabs here\n[src/**.php]\nindent_style = tab\nend_of_line = CRLF\ninsert_final_newline = unset\n",
+        )
+        .unwrap();
+
+        let resolver = EditorConfigResolver::new();
+        let settings = resolver.resolve(&root.join("src/Foo.php"));
+        assert_eq!(settings.indent_tabs, Some(false));
+        assert_eq!(settings.indent_size, Some(4));
+        assert_eq!(settings.line_ending, Some(LineEnding::Lf));
+        assert_eq!(settings.insert_final_newline, Some(true));
+        assert_eq!(settings.trim_trailing_whitespace, None);
+
+        let settings = resolver.resolve(&root.join("packages/legacy/src/Foo.php"));
+        assert_eq!(settings.indent(IndentStyle::Spaces(4)), Some(IndentStyle::Tabs));
+        assert_eq!(settings.line_ending, Some(LineEnding::CrLf));
+        assert_eq!(settings.insert_final_newline, None);
+
+        let settings = resolver.resolve(&root.join("packages/legacy/README.md"));
+        assert_eq!(settings.trim_trailing_whitespace, Some(false));
+        assert_eq!(settings.indent(IndentStyle::Tabs), Some(IndentStyle::Spaces(4)));
+    }
+
+    #[test]
+    fn test_indent_size() {
+        let settings = EditorConfigSettings::from_properties(&HashMap::from([
+            ("indent_size".to_string(), "tab".to_string()),
+            ("tab_width".to_string(), "2".to_string()),
+        ]));
+        assert_eq!(settings.indent(IndentStyle::Spaces(4)), Some(IndentStyle::Spaces(2)));
+        // Without a style, the size doesn't turn tabs into spaces
+        assert_eq!(settings.indent(IndentStyle::Tabs), None);
+    }
+
+    #[test]
+    fn test_apply_keeps_explicit_settings() {
+        let settings = EditorConfigSettings {
+            indent_tabs: Some(true),
+            line_ending: Some(LineEnding::CrLf),
+            insert_final_newline: Some(false),
+            trim_trailing_whitespace: Some(false),
+            ..Default::default()
+        };
+
+        let mut config = FixerConfig::default();
+        settings.apply(&mut config, None);
+        assert_eq!(config.indent, IndentStyle::Tabs);
+        assert_eq!(config.line_ending, LineEnding::CrLf);
+        assert_eq!(config.fixer_overrides.get("single_blank_line_at_end_of_file"), Some(&false));
+        assert_eq!(config.fixer_overrides.get("no_trailing_whitespace"), Some(&false));
+
+        let explicit = crate::config::parse_php_cs_fixer_config(
+            "<?php\nreturn (new PhpCsFixer\\Config())->setIndent('  ')->setRules(['no_trailing_whitespace' => true]);\n",
+        )
+        .unwrap();
+        let mut config = FixerConfig {
+            indent: IndentStyle::Spaces(2),
+            ..Default::default()
+        };
+        settings.apply(&mut config, Some(&explicit));
+        assert_eq!(config.indent, IndentStyle::Spaces(2));
+        assert_eq!(config.line_ending, LineEnding::CrLf);
+        assert!(!config.fixer_overrides.contains_key("no_trailing_whitespace"));
+        assert_eq!(config.fixer_overrides.get("single_blank_line_at_end_of_file"), Some(&false));
+    }
+}
@@ -1,12 +1,18 @@
 //! Configuration parsing for PHP-CS-Fixer compatibility
 //!
-//! This module handles parsing `.php-cs-fixer.php` configuration files
-//! and converting them into rustor's internal configuration format.
+//! This module handles parsing `.php-cs-fixer.php` configuration files and
+//! PHP_CodeSniffer rulesets, resolving `.editorconfig` settings, and
+//! converting them into rustor's internal configuration format.
 
+mod editorconfig;
+mod php_eval;
 mod php_parser;
+mod phpcs;
 mod whitespace;
 mod presets;
 
 pub use php_parser::{PhpCsFixerConfig, FinderConfig, RuleConfig, parse_php_cs_fixer_config, ConfigValue as PhpConfigValue};
+pub use phpcs::{PhpcsRuleset, PhpcsRule, PhpcsImport, parse_phpcs_ruleset, is_phpcs_ruleset};
+pub use editorconfig::{EditorConfig, EditorConfigResolver, EditorConfigSettings};
 pub use whitespace::{WhitespaceConfig, IndentStyle, LineEnding};
 pub use presets::{Preset, get_preset_rules, get_preset_options, PresetOptionValue};
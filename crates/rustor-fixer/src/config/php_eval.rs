@@ -0,0 +1,772 @@
+//! Static evaluation of `.php-cs-fixer.php` files
+//!
+//! Config files are PHP, but almost all of them only use a constant-foldable
+//! subset of it: scalars and arrays, variables, `__DIR__`, string
+//! concatenation, `array_merge()` and method chains on `Config` and `Finder`
+//! objects. This module interprets that subset on the mago AST. Expressions
+//! outside of it evaluate to an [`EvalError`], which is only reported once the
+//! value is actually needed.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+
+/// Where a path built from `__DIR__` or `getcwd()` is rooted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PathBase {
+    /// `__DIR__`, the directory of the config file
+    ConfigDir,
+    /// `getcwd()`, the directory rustor runs in
+    Cwd,
+}
+
+/// Key of a PHP array entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum ArrayKey {
+    Int(i64),
+    String(String),
+}
+
+impl fmt::Display for ArrayKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArrayKey::Int(n) => write!(f, "{}", n),
+            ArrayKey::String(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+/// An ordered PHP array
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct PhpArray {
+    entries: Vec<(ArrayKey, Value)>,
+    next_index: i64,
+}
+
+impl PhpArray {
+    pub(super) fn entries(&self) -> &[(ArrayKey, Value)] {
+        &self.entries
+    }
+
+    pub(super) fn into_entries(self) -> Vec<(ArrayKey, Value)> {
+        self.entries
+    }
+
+    /// Whether the keys are `0..n` in order
+    pub(super) fn is_list(&self) -> bool {
+        self.entries
+            .iter()
+            .enumerate()
+            .all(|(i, (key, _))| *key == ArrayKey::Int(i as i64))
+    }
+
+    fn get(&self, key: &ArrayKey) -> Option<&Value> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn get_mut(&mut self, key: &ArrayKey) -> Option<&mut Value> {
+        self.entries.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    fn push(&mut self, value: Value) {
+        let key = ArrayKey::Int(self.next_index);
+        self.insert(key, value);
+    }
+
+    fn insert(&mut self, key: ArrayKey, value: Value) {
+        if let ArrayKey::Int(n) = key {
+            self.next_index = self.next_index.max(n + 1);
+        }
+        match self.get_mut(&key) {
+            Some(existing) => *existing = value,
+            None => self.entries.push((key, value)),
+        }
+    }
+
+    /// `$a + $b`: entries of `$b` are only added for keys missing from `$a`
+    fn union(mut self, other: PhpArray) -> PhpArray {
+        for (key, value) in other.entries {
+            if self.get(&key).is_none() {
+                self.insert(key, value);
+            }
+        }
+        self
+    }
+
+    /// `array_merge()`: string keys are overwritten, integer keys appended
+    fn merge(&mut self, other: PhpArray) {
+        for (key, value) in other.entries {
+            match key {
+                ArrayKey::Int(_) => self.push(value),
+                key => self.insert(key, value),
+            }
+        }
+    }
+
+    /// `array_replace()`: all keys are overwritten
+    fn replace(&mut self, other: PhpArray) {
+        for (key, value) in other.entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+/// Handle of a `Config` or `Finder` object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct ObjectId(usize);
+
+/// A statically known PHP value
+#[derive(Debug, Clone, PartialEq)]
+pub(super) enum Value {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    /// A path relative to the config directory or the working directory
+    Path(PathBase, String),
+    Array(PhpArray),
+    Object(ObjectId),
+}
+
+impl Value {
+    pub(super) fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Bool(_) => "bool",
+            Value::Int(_) => "int",
+            Value::Float(_) => "float",
+            Value::String(_) | Value::Path(..) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    /// The value after conversion to a string, for scalars
+    pub(super) fn to_php_string(&self) -> Option<String> {
+        match self {
+            Value::Null => Some(String::new()),
+            Value::Bool(b) => Some(if *b { "1" } else { "" }.to_string()),
+            Value::Int(n) => Some(n.to_string()),
+            Value::Float(f) => Some(f.to_string()),
+            Value::String(s) => Some(s.clone()),
+            Value::Path(_, rest) => Some(format!(".{}", rest)),
+            Value::Array(_) | Value::Object(_) => None,
+        }
+    }
+
+    fn is_truthy(&self) -> bool {
+        match self {
+            Value::Null => false,
+            Value::Bool(b) => *b,
+            Value::Int(n) => *n != 0,
+            Value::Float(f) => *f != 0.0,
+            Value::String(s) => !s.is_empty() && s != "0",
+            Value::Path(..) | Value::Object(_) => true,
+            Value::Array(array) => !array.entries.is_empty(),
+        }
+    }
+}
+
+/// Class of the objects config files build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ObjectClass {
+    Config,
+    Finder,
+}
+
+/// A method called on a `Config` or `Finder` object
+#[derive(Debug, Clone)]
+pub(super) struct MethodCall {
+    pub name: String,
+    /// Arguments, evaluated lazily so unused ones can't fail the config
+    pub args: Vec<Result<Value, EvalError>>,
+    pub line: usize,
+}
+
+/// A `Config` or `Finder` object with the methods called on it, in order
+#[derive(Debug, Clone)]
+pub(super) struct Object {
+    pub class: ObjectClass,
+    pub calls: Vec<MethodCall>,
+}
+
+/// Something that could not be evaluated statically
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// Result of evaluating a config file
+#[derive(Debug)]
+pub(super) struct Evaluation {
+    /// The value of the top-level `return` statement
+    pub returned: Result<Value, EvalError>,
+    pub objects: Vec<Object>,
+    /// Statements that were skipped because they can't be evaluated
+    pub warnings: Vec<String>,
+}
+
+impl Evaluation {
+    pub(super) fn object(&self, id: ObjectId) -> &Object {
+        &self.objects[id.0]
+    }
+}
+
+/// Parse and evaluate a config file; fails only on syntax errors
+pub(super) fn evaluate(source: &str) -> Result<Evaluation, EvalError> {
+    let arena = Bump::new();
+    let file_id = FileId::new(".php-cs-fixer.php");
+    let (program, parse_error) = mago_syntax::parser::parse_file_content(&arena, file_id, source);
+
+    let mut evaluator = Evaluator {
+        source,
+        variables: HashMap::new(),
+        objects: Vec::new(),
+        warnings: Vec::new(),
+    };
+    if let Some(error) = parse_error {
+        return Err(evaluator.error(error.span(), format!("syntax error: {}", error)));
+    }
+
+    let returned = evaluator
+        .statements(program.statements.iter())
+        .unwrap_or_else(|| Err(EvalError { line: 1, message: "the config file doesn't return anything".to_string() }));
+
+    Ok(Evaluation {
+        returned,
+        objects: evaluator.objects,
+        warnings: evaluator.warnings,
+    })
+}
+
+type EvalResult = Result<Value, EvalError>;
+
+struct Evaluator<'s> {
+    source: &'s str,
+    /// Variables; failed assignments are kept so uses report the original error
+    variables: HashMap<String, EvalResult>,
+    objects: Vec<Object>,
+    warnings: Vec<String>,
+}
+
+impl<'s> Evaluator<'s> {
+    fn line(&self, span: impl HasSpan) -> usize {
+        let offset = (span.span().start.offset as usize).min(self.source.len());
+        self.source[..offset].matches('\n').count() + 1
+    }
+
+    fn error(&self, span: impl HasSpan, message: String) -> EvalError {
+        EvalError {
+            line: self.line(span),
+            message,
+        }
+    }
+
+    fn unsupported(&self, span: impl HasSpan, what: &str) -> EvalError {
+        self.error(span, format!("{} can't be evaluated statically", what))
+    }
+
+    fn text(&self, span: impl HasSpan) -> &'s str {
+        let span = span.span();
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    /// Run statements until a `return`, whose value is returned
+    fn statements<'a>(&mut self, statements: impl Iterator<Item = &'a Statement<'a>>) -> Option<EvalResult> {
+        for statement in statements {
+            match statement {
+                Statement::Return(ret) => {
+                    return Some(match &ret.value {
+                        Some(value) => self.eval(value),
+                        None => Ok(Value::Null),
+                    });
+                }
+                Statement::Expression(statement) => {
+                    let result = self.eval(statement.expression);
+                    // Failed assignments are reported when the variable is used
+                    if let (Err(error), false) = (result, matches!(statement.expression, Expression::Assignment(_))) {
+                        self.warnings.push(format!("skipped statement at {}", error));
+                    }
+                }
+                Statement::Namespace(namespace) => {
+                    if let Some(value) = self.statements(namespace.statements().iter()) {
+                        return Some(value);
+                    }
+                }
+                Statement::Block(block) => {
+                    if let Some(value) = self.statements(block.statements.iter()) {
+                        return Some(value);
+                    }
+                }
+                Statement::OpeningTag(_)
+                | Statement::ClosingTag(_)
+                | Statement::Inline(_)
+                | Statement::Use(_)
+                | Statement::Declare(_)
+                | Statement::Function(_)
+                | Statement::Class(_)
+                | Statement::Interface(_)
+                | Statement::Trait(_)
+                | Statement::Enum(_)
+                | Statement::Noop(_) => {}
+                other => {
+                    let keyword = self.text(other).split(|c: char| !c.is_alphanumeric()).next().unwrap_or_default();
+                    self.warnings.push(format!(
+                        "skipped `{}` statement at line {}: it can't be evaluated statically",
+                        keyword,
+                        self.line(other)
+                    ));
+                }
+            }
+        }
+        None
+    }
+
+    fn eval(&mut self, expr: &Expression<'_>) -> EvalResult {
+        match expr {
+            Expression::Literal(literal) => self.literal(literal),
+            Expression::CompositeString(string) => self.composite_string(string),
+            Expression::Parenthesized(inner) => self.eval(inner.expression),
+            Expression::Array(array) => self.array(array.elements.iter()),
+            Expression::LegacyArray(array) => self.array(array.elements.iter()),
+            Expression::Variable(Variable::Direct(variable)) => match self.variables.get(variable.name) {
+                Some(value) => value.clone(),
+                None => Err(self.error(variable, format!("undefined variable {}", variable.name))),
+            },
+            Expression::Assignment(assignment) => self.assign(assignment),
+            Expression::Binary(binary) => self.binary(binary),
+            Expression::UnaryPrefix(unary) => {
+                let operand = self.eval(unary.operand)?;
+                match (&unary.operator, operand) {
+                    (UnaryPrefixOperator::Not(_), value) => Ok(Value::Bool(!value.is_truthy())),
+                    (UnaryPrefixOperator::Negation(_), Value::Int(n)) => Ok(Value::Int(-n)),
+                    (UnaryPrefixOperator::Negation(_), Value::Float(f)) => Ok(Value::Float(-f)),
+                    (UnaryPrefixOperator::Plus(_), value @ (Value::Int(_) | Value::Float(_))) => Ok(value),
+                    _ => Err(self.unsupported(unary, &format!("`{}`", self.text(unary)))),
+                }
+            }
+            Expression::Conditional(conditional) => {
+                let condition = self.eval(conditional.condition)?;
+                if condition.is_truthy() {
+                    match conditional.then {
+                        Some(then) => self.eval(then),
+                        None => Ok(condition),
+                    }
+                } else {
+                    self.eval(conditional.r#else)
+                }
+            }
+            Expression::ConstantAccess(constant) => {
+                let name = constant.name.value();
+                match name.rsplit('\\').next().unwrap_or(name) {
+                    "PHP_EOL" => Ok(Value::String("\n".to_string())),
+                    "DIRECTORY_SEPARATOR" => Ok(Value::String("/".to_string())),
+                    _ => Err(self.unsupported(constant, &format!("constant {}", name))),
+                }
+            }
+            Expression::MagicConstant(MagicConstant::Directory(_)) => Ok(Value::Path(PathBase::ConfigDir, String::new())),
+            Expression::ArrayAccess(access) => {
+                let array = self.eval(access.array)?;
+                let key = self.eval(access.index)?;
+                let key = self.array_key(access.index, key)?;
+                match array {
+                    Value::Array(array) => match array.get(&key) {
+                        Some(value) => Ok(value.clone()),
+                        None => Err(self.error(access, format!("undefined array key \"{}\"", key))),
+                    },
+                    other => Err(self.error(access, format!("cannot use {} as an array", other.type_name()))),
+                }
+            }
+            Expression::Instantiation(instantiation) => match object_class(instantiation.class) {
+                Some(class) => Ok(self.new_object(class)),
+                None => Err(self.unsupported(instantiation, &format!("`new {}`", self.text(instantiation.class)))),
+            },
+            Expression::Call(Call::Function(call)) => self.function_call(call),
+            Expression::Call(Call::StaticMethod(call)) => {
+                let is_create = matches!(&call.method, ClassLikeMemberSelector::Identifier(method)
+                    if method.value.eq_ignore_ascii_case("create"));
+                match object_class(call.class) {
+                    Some(class) if is_create => Ok(self.new_object(class)),
+                    _ => Err(self.unsupported(call, &format!("call to {}()", self.text(call).split('(').next().unwrap_or_default()))),
+                }
+            }
+            Expression::Call(Call::Method(call)) => self.method_call(call),
+            Expression::Closure(_) | Expression::ArrowFunction(_) => Err(self.unsupported(expr, "a closure")),
+            Expression::Construct(_) => Err(self.unsupported(expr, &format!("`{}`", self.text(expr)))),
+            _ => Err(self.unsupported(expr, &format!("`{}`", self.text(expr)))),
+        }
+    }
+
+    fn literal(&self, literal: &Literal<'_>) -> EvalResult {
+        Ok(match literal {
+            Literal::String(string) => Value::String(match string.value {
+                Some(value) => value.to_string(),
+                None => return Err(self.unsupported(string, "this string")),
+            }),
+            Literal::Integer(integer) => match integer.value.and_then(|n| i64::try_from(n).ok()) {
+                Some(n) => Value::Int(n),
+                None => return Err(self.unsupported(integer, &format!("integer {}", integer.raw))),
+            },
+            Literal::Float(float) => Value::Float(float.value.into_inner()),
+            Literal::True(_) => Value::Bool(true),
+            Literal::False(_) => Value::Bool(false),
+            Literal::Null(_) => Value::Null,
+        })
+    }
+
+    fn composite_string(&mut self, string: &CompositeString<'_>) -> EvalResult {
+        let escaped = match string {
+            CompositeString::Interpolated(_) => true,
+            CompositeString::Document(document) => document.kind == DocumentKind::Heredoc,
+            CompositeString::ShellExecute(_) => return Err(self.unsupported(string, "a shell command")),
+        };
+
+        let mut value = String::new();
+        for part in string.parts().iter() {
+            let expression = match part {
+                StringPart::Literal(literal) if escaped => {
+                    value.push_str(&unescape(literal.value));
+                    continue;
+                }
+                StringPart::Literal(literal) => {
+                    value.push_str(literal.value);
+                    continue;
+                }
+                StringPart::Expression(expression) => *expression,
+                StringPart::BracedExpression(braced) => braced.expression,
+            };
+            let part_value = self.eval(expression)?;
+            match part_value.to_php_string() {
+                Some(s) => value.push_str(&s),
+                None => return Err(self.error(expression, format!("cannot convert {} to string", part_value.type_name()))),
+            }
+        }
+        Ok(Value::String(value))
+    }
+
+    fn array<'a>(&mut self, elements: impl Iterator<Item = &'a ArrayElement<'a>>) -> EvalResult {
+        let mut array = PhpArray::default();
+        for element in elements {
+            match element {
+                ArrayElement::KeyValue(entry) => {
+                    let key = self.eval(entry.key)?;
+                    let key = self.array_key(entry.key, key)?;
+                    array.insert(key, self.eval(entry.value)?);
+                }
+                ArrayElement::Value(entry) => array.push(self.eval(entry.value)?),
+                ArrayElement::Variadic(entry) => match self.eval(entry.value)? {
+                    Value::Array(spread) => array.merge(spread),
+                    other => return Err(self.error(entry, format!("cannot unpack {}", other.type_name()))),
+                },
+                ArrayElement::Missing(missing) => return Err(self.error(missing, "missing array element".to_string())),
+            }
+        }
+        Ok(Value::Array(array))
+    }
+
+    /// Convert a value to an array key the way PHP does
+    fn array_key(&self, expr: &Expression<'_>, key: Value) -> Result<ArrayKey, EvalError> {
+        Ok(match key {
+            Value::Int(n) => ArrayKey::Int(n),
+            Value::Bool(b) => ArrayKey::Int(b as i64),
+            Value::Float(f) => ArrayKey::Int(f as i64),
+            Value::Null => ArrayKey::String(String::new()),
+            Value::String(s) => match s.parse::<i64>() {
+                Ok(n) if n.to_string() == s => ArrayKey::Int(n),
+                _ => ArrayKey::String(s),
+            },
+            path @ Value::Path(..) => ArrayKey::String(path.to_php_string().unwrap_or_default()),
+            other => return Err(self.error(expr, format!("cannot use {} as an array key", other.type_name()))),
+        })
+    }
+
+    fn assign(&mut self, assignment: &Assignment<'_>) -> EvalResult {
+        // `$var[...][...] = value` and `$var[] = value`
+        let mut path = Vec::new();
+        let mut target = assignment.lhs;
+        loop {
+            match target {
+                Expression::ArrayAccess(access) => {
+                    path.push(Some(access.index));
+                    target = access.array;
+                }
+                Expression::ArrayAppend(append) => {
+                    path.push(None);
+                    target = append.array;
+                }
+                _ => break,
+            }
+        }
+        path.reverse();
+        let Expression::Variable(Variable::Direct(variable)) = target else {
+            return Err(self.unsupported(assignment, "assignment to this expression"));
+        };
+        let name = variable.name.to_string();
+
+        if !path.is_empty() {
+            if !assignment.operator.is_assign() {
+                return Err(self.unsupported(assignment, &format!("`{}`", self.text(&assignment.operator))));
+            }
+            let result = self.assign_element(&name, &path, assignment.rhs);
+            if let Err(error) = &result {
+                // The array is unknown from now on
+                self.variables.insert(name, Err(error.clone()));
+            }
+            return result;
+        }
+
+        let value = match &assignment.operator {
+            AssignmentOperator::Assign(_) => self.eval(assignment.rhs),
+            AssignmentOperator::Coalesce(_) => match self.variables.get(&name) {
+                Some(Ok(Value::Null)) | None => self.eval(assignment.rhs),
+                Some(current) => current.clone(),
+            },
+            AssignmentOperator::Addition(_) | AssignmentOperator::Concat(_) => {
+                let current = match self.variables.get(&name) {
+                    Some(current) => current.clone(),
+                    None => Err(self.error(variable, format!("undefined variable {}", name))),
+                };
+                current.and_then(|current| {
+                    let rhs = self.eval(assignment.rhs)?;
+                    let concat = matches!(assignment.operator, AssignmentOperator::Concat(_));
+                    self.arithmetic(assignment, concat, current, rhs)
+                })
+            }
+            operator => Err(self.unsupported(assignment, &format!("`{}`", self.text(operator)))),
+        };
+        self.variables.insert(name, value.clone());
+        value
+    }
+
+    fn assign_element(&mut self, name: &str, path: &[Option<&Expression<'_>>], rhs: &Expression<'_>) -> EvalResult {
+        let mut keys = Vec::with_capacity(path.len());
+        for index in path {
+            keys.push(match index {
+                Some(index) => {
+                    let key = self.eval(index)?;
+                    Some(self.array_key(index, key)?)
+                }
+                None => None,
+            });
+        }
+        let value = self.eval(rhs)?;
+
+        let mut root = match self.variables.remove(name) {
+            Some(Ok(Value::Array(array))) => array,
+            Some(Ok(Value::Null)) | None => PhpArray::default(),
+            Some(Ok(other)) => return Err(self.error(rhs, format!("cannot use {} as an array", other.type_name()))),
+            Some(Err(error)) => return Err(error),
+        };
+        if !set_element(&mut root, &keys, value.clone()) {
+            return Err(self.error(rhs, format!("cannot use {} as an array", name)));
+        }
+        self.variables.insert(name.to_string(), Ok(Value::Array(root)));
+        Ok(value)
+    }
+
+    fn binary(&mut self, binary: &Binary<'_>) -> EvalResult {
+        match &binary.operator {
+            BinaryOperator::NullCoalesce(_) => {
+                let undefined = matches!(binary.lhs, Expression::Variable(Variable::Direct(variable))
+                    if !self.variables.contains_key(variable.name));
+                if undefined {
+                    return self.eval(binary.rhs);
+                }
+                match self.eval(binary.lhs)? {
+                    Value::Null => self.eval(binary.rhs),
+                    value => Ok(value),
+                }
+            }
+            BinaryOperator::And(_) | BinaryOperator::LowAnd(_) => {
+                Ok(Value::Bool(self.eval(binary.lhs)?.is_truthy() && self.eval(binary.rhs)?.is_truthy()))
+            }
+            BinaryOperator::Or(_) | BinaryOperator::LowOr(_) => {
+                Ok(Value::Bool(self.eval(binary.lhs)?.is_truthy() || self.eval(binary.rhs)?.is_truthy()))
+            }
+            BinaryOperator::StringConcat(_) | BinaryOperator::Addition(_) => {
+                let lhs = self.eval(binary.lhs)?;
+                let rhs = self.eval(binary.rhs)?;
+                let concat = matches!(binary.operator, BinaryOperator::StringConcat(_));
+                self.arithmetic(binary, concat, lhs, rhs)
+            }
+            operator => Err(self.unsupported(binary, &format!("operator `{}`", operator.as_str()))),
+        }
+    }
+
+    /// `.` (when `concat` is set) or `+`
+    fn arithmetic(&self, span: impl HasSpan, concat: bool, lhs: Value, rhs: Value) -> EvalResult {
+        if concat {
+            let (Some(left), Some(right)) = (lhs.to_php_string(), rhs.to_php_string()) else {
+                return Err(self.error(span, "cannot concatenate arrays or objects".to_string()));
+            };
+            return Ok(match lhs {
+                Value::Path(base, rest) => Value::Path(base, rest + &right),
+                _ => Value::String(left + &right),
+            });
+        }
+
+        match (lhs, rhs) {
+            (Value::Array(lhs), Value::Array(rhs)) => Ok(Value::Array(lhs.union(rhs))),
+            (Value::Int(a), Value::Int(b)) => Ok(a.checked_add(b).map_or(Value::Float(a as f64 + b as f64), Value::Int)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 + b)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a + b as f64)),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
+            (lhs, rhs) => Err(self.error(span, format!("unsupported operand types: {} + {}", lhs.type_name(), rhs.type_name()))),
+        }
+    }
+
+    fn function_call(&mut self, call: &FunctionCall<'_>) -> EvalResult {
+        let Expression::Identifier(identifier) = call.function else {
+            return Err(self.unsupported(call, "a dynamic function call"));
+        };
+        let name = identifier.value().trim_start_matches('\\').to_ascii_lowercase();
+
+        match name.as_str() {
+            "getcwd" => Ok(Value::Path(PathBase::Cwd, String::new())),
+            "array_merge" | "array_replace" => {
+                let mut result = PhpArray::default();
+                for argument in call.argument_list.arguments.iter() {
+                    let value = argument.value();
+                    let arrays = match (argument, self.eval(value)?) {
+                        // array_merge(...$lists)
+                        (Argument::Positional(positional), Value::Array(lists)) if positional.ellipsis.is_some() => lists
+                            .into_entries()
+                            .into_iter()
+                            .map(|(_, list)| list)
+                            .collect(),
+                        (_, other) => vec![other],
+                    };
+                    for array in arrays {
+                        match array {
+                            Value::Array(array) if name == "array_merge" => result.merge(array),
+                            Value::Array(array) => result.replace(array),
+                            other => {
+                                return Err(self.error(value, format!("{}() expects arrays, got {}", name, other.type_name())))
+                            }
+                        }
+                    }
+                }
+                Ok(Value::Array(result))
+            }
+            _ => Err(self.unsupported(call, &format!("call to {}()", identifier.value()))),
+        }
+    }
+
+    fn method_call(&mut self, call: &mago_syntax::ast::MethodCall<'_>) -> EvalResult {
+        let ClassLikeMemberSelector::Identifier(method) = &call.method else {
+            return Err(self.unsupported(call, "a dynamic method call"));
+        };
+        let id = match self.eval(call.object)? {
+            Value::Object(id) => id,
+            other => return Err(self.error(call, format!("call to ->{}() on {}", method.value, other.type_name()))),
+        };
+        if method.value.len() > 3 && method.value.get(..3).is_some_and(|prefix| prefix.eq_ignore_ascii_case("get")) {
+            return Err(self.unsupported(call, &format!("->{}()", method.value)));
+        }
+
+        let args = call
+            .argument_list
+            .arguments
+            .iter()
+            .map(|argument| self.eval(argument.value()))
+            .collect();
+        let line = self.line(method);
+        self.objects[id.0].calls.push(MethodCall {
+            name: method.value.to_string(),
+            args,
+            line,
+        });
+
+        // Config and Finder setters are fluent
+        Ok(Value::Object(id))
+    }
+
+    fn new_object(&mut self, class: ObjectClass) -> Value {
+        self.objects.push(Object { class, calls: Vec::new() });
+        Value::Object(ObjectId(self.objects.len() - 1))
+    }
+}
+
+/// `PhpCsFixer\Config`, `Config`, `PhpCsFixer\Finder`, ...
+fn object_class(expr: &Expression<'_>) -> Option<ObjectClass> {
+    let Expression::Identifier(identifier) = expr else {
+        return None;
+    };
+    let name = identifier.value();
+    match name.rsplit('\\').next().unwrap_or(name).to_ascii_lowercase().as_str() {
+        "config" => Some(ObjectClass::Config),
+        "finder" => Some(ObjectClass::Finder),
+        _ => None,
+    }
+}
+
+/// Set `array[k1][k2]... = value`, creating nested arrays; `None` keys append
+fn set_element(array: &mut PhpArray, keys: &[Option<ArrayKey>], value: Value) -> bool {
+    let Some((key, rest)) = keys.split_first() else {
+        return false;
+    };
+    if rest.is_empty() {
+        match key {
+            Some(key) => array.insert(key.clone(), value),
+            None => array.push(value),
+        }
+        return true;
+    }
+
+    let key = match key {
+        Some(key) => key.clone(),
+        None => {
+            array.push(Value::Array(PhpArray::default()));
+            ArrayKey::Int(array.next_index - 1)
+        }
+    };
+    if array.get(&key).is_none_or(|v| *v == Value::Null) {
+        array.insert(key.clone(), Value::Array(PhpArray::default()));
+    }
+    match array.get_mut(&key) {
+        Some(Value::Array(nested)) => set_element(nested, rest, value),
+        _ => false,
+    }
+}
+
+/// Resolve the escape sequences of double-quoted strings and heredocs
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('v') => value.push('\x0b'),
+            Some('e') => value.push('\x1b'),
+            Some('f') => value.push('\x0c'),
+            Some('0') => value.push('\0'),
+            Some(c @ ('\\' | '$' | '"')) => value.push(c),
+            Some(c) => {
+                value.push('\\');
+                value.push(c);
+            }
+            None => value.push('\\'),
+        }
+    }
+    value
+}
@@ -1,12 +1,13 @@
 //! Parser for .php-cs-fixer.php configuration files
 //!
-//! Extracts configuration using regex patterns to parse PHP code directly.
+//! The file is evaluated statically (see [`super::php_eval`]) and the methods
+//! called on the returned `Config` and its `Finder` are read back in order.
 
 use std::collections::HashMap;
 use std::path::Path;
-use regex::Regex;
 use thiserror::Error;
 
+use super::php_eval::{self, ArrayKey, EvalError, Evaluation, MethodCall, ObjectClass, PathBase, PhpArray, Value};
 use super::whitespace::{IndentStyle, LineEnding, WhitespaceConfig};
 
 #[derive(Debug, Error)]
@@ -15,8 +16,8 @@ pub enum ParseError {
     IoError(#[from] std::io::Error),
     #[error("Invalid PHP config format: {0}")]
     InvalidFormat(String),
-    #[error("Regex error: {0}")]
-    RegexError(#[from] regex::Error),
+    #[error("Could not evaluate config statically: {0}")]
+    NotStatic(#[from] EvalError),
 }
 
 /// Configuration for a single fixer rule
@@ -62,6 +63,10 @@ pub struct FinderConfig {
 pub struct PhpCsFixerConfig {
     /// Whitespace configuration
     pub whitespace: WhitespaceConfig,
+    /// Whether the indentation was set explicitly (it then wins over .editorconfig)
+    pub indent_set: bool,
+    /// Whether the line ending was set explicitly (it then wins over .editorconfig)
+    pub line_ending_set: bool,
     /// Enabled rules with their configurations
     pub rules: HashMap<String, RuleConfig>,
     /// Whether risky rules are allowed
@@ -70,16 +75,21 @@ pub struct PhpCsFixerConfig {
     pub finder: FinderConfig,
     /// Cache directory (if configured)
     pub cache_file: Option<String>,
+    /// Statements that were skipped because they can't be evaluated statically
+    pub warnings: Vec<String>,
 }
 
 impl Default for PhpCsFixerConfig {
     fn default() -> Self {
         Self {
             whitespace: WhitespaceConfig::psr12(),
+            indent_set: false,
+            line_ending_set: false,
             rules: HashMap::new(),
             risky_allowed: false,
             finder: FinderConfig::default(),
             cache_file: None,
+            warnings: Vec::new(),
         }
     }
 }
@@ -103,289 +113,320 @@ impl PhpCsFixerConfig {
 }
 
 /// Parse PHP-CS-Fixer configuration from a string
+///
+/// Paths built from `__DIR__` are returned relative to the config file.
 pub fn parse_php_cs_fixer_config(content: &str) -> Result<PhpCsFixerConfig, ParseError> {
-    let mut config = PhpCsFixerConfig::default();
-
-    // Parse indentation: ->setIndent('    ') or ->setIndent("\t")
-    config.whitespace.indent = parse_indent(content);
-
-    // Parse line ending: ->setLineEnding("\n") or ->setLineEnding("\r\n")
-    config.whitespace.line_ending = parse_line_ending(content);
-
-    // Parse risky allowed: ->setRiskyAllowed(true)
-    config.risky_allowed = parse_risky_allowed(content);
-
-    // Parse rules: ->setRules([...])
-    config.rules = parse_rules(content)?;
-
-    // Parse finder: PhpCsFixer\Finder::create()->in(...)->exclude(...)
-    config.finder = parse_finder(content)?;
-
-    // Parse cache file: ->setCacheFile(...)
-    config.cache_file = parse_cache_file(content);
+    let evaluation = php_eval::evaluate(content)?;
+    let mut config = PhpCsFixerConfig {
+        warnings: evaluation.warnings.clone(),
+        ..Default::default()
+    };
+
+    let id = match &evaluation.returned {
+        Ok(Value::Object(id)) if evaluation.object(*id).class == ObjectClass::Config => *id,
+        Ok(other) => {
+            return Err(ParseError::InvalidFormat(format!(
+                "the config file must return a PhpCsFixer\\Config instance, got {}",
+                other.type_name()
+            )))
+        }
+        Err(error) => return Err(error.clone().into()),
+    };
+
+    for call in &evaluation.object(id).calls {
+        match call.name.to_ascii_lowercase().as_str() {
+            // ->setIndent('    ') or ->setIndent("\t")
+            "setindent" => {
+                config.whitespace.indent = IndentStyle::from_php_config(&string_arg(call)?);
+                config.indent_set = true;
+            }
+            // ->setLineEnding("\n") or ->setLineEnding("\r\n")
+            "setlineending" => {
+                config.whitespace.line_ending = LineEnding::from_php_config(&string_arg(call)?);
+                config.line_ending_set = true;
+            }
+            // ->setRiskyAllowed(true)
+            "setriskyallowed" => config.risky_allowed = bool_arg(call)?,
+            // ->setRules([...])
+            "setrules" => match arg(call)? {
+                Value::Array(rules) => config.rules = parse_rules(call, rules)?,
+                other => return Err(invalid_arg(call, "an array", other)),
+            },
+            // ->setFinder($finder) or ->setFinder(['src', 'tests'])
+            "setfinder" => config.finder = parse_finder(&evaluation, call)?,
+            // ->setCacheFile(__DIR__.'/.php-cs-fixer.cache')
+            "setcachefile" => config.cache_file = Some(string_arg(call)?),
+            _ => {}
+        }
+    }
 
     Ok(config)
 }
 
-/// Parse indentation setting
-fn parse_indent(content: &str) -> IndentStyle {
-    // Match ->setIndent('    ') or ->setIndent("\t")
-    let re = Regex::new(r#"->setIndent\s*\(\s*['"](.+?)['"]\s*\)"#).unwrap();
-
-    if let Some(caps) = re.captures(content) {
-        let indent_str = &caps[1];
-        IndentStyle::from_php_config(indent_str)
-    } else {
-        IndentStyle::default()
+/// The first argument of a call
+fn arg(call: &MethodCall) -> Result<&Value, ParseError> {
+    match call.args.first() {
+        Some(Ok(value)) => Ok(value),
+        Some(Err(error)) => Err(error.clone().into()),
+        None => Err(ParseError::InvalidFormat(format!("line {}: {}() expects an argument", call.line, call.name))),
     }
 }
 
-/// Parse line ending setting
-fn parse_line_ending(content: &str) -> LineEnding {
-    // Match ->setLineEnding("\n") or ->setLineEnding("\r\n")
-    let re = Regex::new(r#"->setLineEnding\s*\(\s*['"](.+?)['"]\s*\)"#).unwrap();
+fn invalid_arg(call: &MethodCall, expected: &str, found: &Value) -> ParseError {
+    ParseError::InvalidFormat(format!(
+        "line {}: {}() expects {}, got {}",
+        call.line,
+        call.name,
+        expected,
+        found.type_name()
+    ))
+}
 
-    if let Some(caps) = re.captures(content) {
-        let ending_str = &caps[1];
-        LineEnding::from_php_config(ending_str)
-    } else {
-        LineEnding::default()
-    }
+fn string_arg(call: &MethodCall) -> Result<String, ParseError> {
+    let value = arg(call)?;
+    scalar_string(value).ok_or_else(|| invalid_arg(call, "a string", value))
 }
 
-/// Parse risky allowed setting
-fn parse_risky_allowed(content: &str) -> bool {
-    // Match ->setRiskyAllowed(true) or ->setRiskyAllowed(false)
-    let re = Regex::new(r#"->setRiskyAllowed\s*\(\s*(true|false)\s*\)"#).unwrap();
+fn bool_arg(call: &MethodCall) -> Result<bool, ParseError> {
+    match arg(call)? {
+        Value::Bool(b) => Ok(*b),
+        other => Err(invalid_arg(call, "a bool", other)),
+    }
+}
 
-    if let Some(caps) = re.captures(content) {
-        &caps[1] == "true"
-    } else {
-        false
+/// A string or path; paths are made relative to their base directory
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Path(_, rest) => {
+            let rest = rest.trim_start_matches('/');
+            Some(if rest.is_empty() { ".".to_string() } else { rest.to_string() })
+        }
+        Value::Int(_) | Value::Float(_) | Value::Bool(_) => value.to_php_string(),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
     }
 }
 
+/// A string or a list of strings
+fn string_list(call: &MethodCall) -> Result<Vec<String>, ParseError> {
+    let value = arg(call)?;
+    let values = match value {
+        Value::Array(array) => array.entries().iter().map(|(_, v)| v).collect(),
+        value => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|v| scalar_string(v).ok_or_else(|| invalid_arg(call, "a string or an array of strings", v)))
+        .collect()
+}
+
 /// Parse rules configuration
-fn parse_rules(content: &str) -> Result<HashMap<String, RuleConfig>, ParseError> {
+fn parse_rules(call: &MethodCall, entries: &PhpArray) -> Result<HashMap<String, RuleConfig>, ParseError> {
     let mut rules = HashMap::new();
 
-    // Match ->setRules([...]) - capture the array content
-    let rules_re = Regex::new(r#"->setRules\s*\(\s*\[([\s\S]*?)\]\s*\)"#)?;
-
-    if let Some(caps) = rules_re.captures(content) {
-        let rules_content = &caps[1];
-
-        // Parse preset references like @PSR12, @Symfony
-        let preset_re = Regex::new(r#"'@(PSR\d+|PSR-\d+|Symfony|PhpCsFixer)'(?:\s*=>\s*(true|false))?"#)?;
-        for cap in preset_re.captures_iter(rules_content) {
-            let preset_name = &cap[1];
-            let enabled = cap.get(2).map(|m| m.as_str() == "true").unwrap_or(true);
-
-            if enabled {
-                // Add all rules from the preset
-                let preset_rules = super::presets::get_preset_rules(preset_name);
-                for rule_name in preset_rules {
-                    rules.insert(rule_name.to_string(), RuleConfig {
-                        enabled: true,
-                        options: HashMap::new(),
-                    });
-                }
-
-                // Apply preset-specific options
-                let preset_options = super::presets::get_preset_options(preset_name);
-                for (rule_name, rule_opts) in preset_options {
-                    if let Some(rule_config) = rules.get_mut(rule_name) {
-                        for (opt_name, opt_value) in rule_opts {
-                            let config_value = match opt_value {
-                                super::presets::PresetOptionValue::Bool(b) => ConfigValue::Bool(b),
-                                super::presets::PresetOptionValue::String(s) => ConfigValue::String(s.to_string()),
-                                super::presets::PresetOptionValue::Number(n) => ConfigValue::Number(n),
-                            };
-                            rule_config.options.insert(opt_name.to_string(), config_value);
-                        }
-                    }
-                }
+    let mut named = Vec::with_capacity(entries.entries().len());
+    for (key, value) in entries.entries() {
+        match key {
+            ArrayKey::String(name) => named.push((name.as_str(), value)),
+            ArrayKey::Int(_) => {
+                return Err(ParseError::InvalidFormat(format!(
+                    "line {}: setRules() expects rule names as keys, got a list entry",
+                    call.line
+                )))
             }
         }
+    }
+
+    // Presets first, so individual rules override them wherever they appear
+    for (key, value) in &named {
+        let Some(preset_name) = key.strip_prefix('@') else {
+            continue;
+        };
+        if !matches!(value, Value::Bool(true)) {
+            continue;
+        }
 
-        // Parse individual rules: 'rule_name' => true/false
-        let simple_rule_re = Regex::new(r#"'([a-z_]+)'\s*=>\s*(true|false)"#)?;
-        for cap in simple_rule_re.captures_iter(rules_content) {
-            let rule_name = cap[1].to_string();
-            let enabled = &cap[2] == "true";
-            rules.insert(rule_name, RuleConfig {
-                enabled,
+        // Add all rules from the preset
+        let preset_rules = super::presets::get_preset_rules(preset_name);
+        for rule_name in preset_rules {
+            rules.insert(rule_name.to_string(), RuleConfig {
+                enabled: true,
                 options: HashMap::new(),
             });
         }
 
-        // Parse rules with array options: 'rule_name' => ['option' => 'value']
-        let array_rule_re = Regex::new(r#"'([a-z_]+)'\s*=>\s*\[([\s\S]*?)\]"#)?;
-        for cap in array_rule_re.captures_iter(rules_content) {
-            let rule_name = cap[1].to_string();
-            let options_str = &cap[2];
-
-            let mut options = HashMap::new();
-
-            // Parse string options: 'option' => 'value'
-            let string_opt_re = Regex::new(r#"'([a-z_]+)'\s*=>\s*'([^']+)'"#)?;
-            for opt_cap in string_opt_re.captures_iter(options_str) {
-                options.insert(
-                    opt_cap[1].to_string(),
-                    ConfigValue::String(opt_cap[2].to_string()),
-                );
-            }
-
-            // Parse boolean options: 'option' => true/false
-            let bool_opt_re = Regex::new(r#"'([a-z_]+)'\s*=>\s*(true|false)"#)?;
-            for opt_cap in bool_opt_re.captures_iter(options_str) {
-                options.insert(
-                    opt_cap[1].to_string(),
-                    ConfigValue::Bool(&opt_cap[2] == "true"),
-                );
-            }
-
-            // Parse array options: 'option' => ['a', 'b']
-            let arr_opt_re = Regex::new(r#"'([a-z_]+)'\s*=>\s*\[([^\]]*)\]"#)?;
-            for opt_cap in arr_opt_re.captures_iter(options_str) {
-                let values: Vec<String> = Regex::new(r#"'([^']+)'"#)?
-                    .captures_iter(&opt_cap[2])
-                    .map(|c| c[1].to_string())
-                    .collect();
-                if !values.is_empty() {
-                    options.insert(
-                        opt_cap[1].to_string(),
-                        ConfigValue::Array(values),
-                    );
+        // Apply preset-specific options
+        let preset_options = super::presets::get_preset_options(preset_name);
+        for (rule_name, rule_opts) in preset_options {
+            if let Some(rule_config) = rules.get_mut(rule_name) {
+                for (opt_name, opt_value) in rule_opts {
+                    let config_value = match opt_value {
+                        super::presets::PresetOptionValue::Bool(b) => ConfigValue::Bool(b),
+                        super::presets::PresetOptionValue::String(s) => ConfigValue::String(s.to_string()),
+                        super::presets::PresetOptionValue::Number(n) => ConfigValue::Number(n),
+                    };
+                    rule_config.options.insert(opt_name.to_string(), config_value);
                 }
             }
+        }
+    }
 
-            rules.insert(rule_name, RuleConfig {
-                enabled: true,
-                options,
-            });
+    // Individual rules: 'rule_name' => true/false or 'rule_name' => [options]
+    for (rule_name, value) in named {
+        if rule_name.starts_with('@') {
+            continue;
         }
+        let rule = match value {
+            Value::Bool(enabled) => RuleConfig {
+                enabled: *enabled,
+                options: HashMap::new(),
+            },
+            Value::Array(options) => RuleConfig {
+                enabled: true,
+                options: options
+                    .entries()
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.to_string(), into_config_value(value)?)))
+                    .collect(),
+            },
+            other => {
+                return Err(ParseError::InvalidFormat(format!(
+                    "line {}: rule \"{}\" must be configured with a bool or an array, got {}",
+                    call.line,
+                    rule_name,
+                    other.type_name()
+                )))
+            }
+        };
+        rules.insert(rule_name.to_string(), rule);
     }
 
     Ok(rules)
 }
 
-/// Parse finder configuration
-fn parse_finder(content: &str) -> Result<FinderConfig, ParseError> {
-    let mut finder = FinderConfig::default();
-
-    // Match ->in(getcwd()) - use current working directory
-    if content.contains("->in(getcwd())") {
-        finder.use_cwd = true;
-    }
-
-    // Match ->in('path') or ->in(['path1', 'path2']) or ->in(__DIR__.'/path')
-    let in_re = Regex::new(r#"->in\s*\(\s*(?:'([^']+)'|\[([^\]]+)\]|__DIR__\s*\.\s*'([^']*)')"#)?;
-    for cap in in_re.captures_iter(content) {
-        if let Some(single) = cap.get(1) {
-            finder.paths.push(single.as_str().to_string());
-        } else if let Some(array) = cap.get(2) {
-            let paths_re = Regex::new(r#"'([^']+)'"#)?;
-            for path_cap in paths_re.captures_iter(array.as_str()) {
-                finder.paths.push(path_cap[1].to_string());
-            }
-        } else if let Some(dir_relative) = cap.get(3) {
-            finder.paths.push(dir_relative.as_str().trim_start_matches('/').to_string());
-        }
-    }
-
-    // Match ->exclude('path') or ->exclude(['path1', 'path2'])
-    let exclude_re = Regex::new(r#"->exclude\s*\(\s*(?:'([^']+)'|\[([^\]]+)\])"#)?;
-    for cap in exclude_re.captures_iter(content) {
-        if let Some(single) = cap.get(1) {
-            finder.exclude.push(single.as_str().to_string());
-        } else if let Some(array) = cap.get(2) {
-            let paths_re = Regex::new(r#"'([^']+)'"#)?;
-            for path_cap in paths_re.captures_iter(array.as_str()) {
-                finder.exclude.push(path_cap[1].to_string());
-            }
-        }
+/// Convert an option value; lists become arrays and keyed arrays become maps
+fn into_config_value(value: &Value) -> Option<ConfigValue> {
+    match value {
+        Value::Bool(b) => Some(ConfigValue::Bool(*b)),
+        Value::Int(n) => Some(ConfigValue::Number(*n)),
+        Value::Float(_) | Value::String(_) | Value::Path(..) => scalar_string(value).map(ConfigValue::String),
+        Value::Null | Value::Object(_) => None,
+        Value::Array(array) if array.is_list() => Some(ConfigValue::Array(
+            array.entries().iter().filter_map(|(_, value)| option_string(value)).collect(),
+        )),
+        Value::Array(array) => Some(ConfigValue::Map(
+            array
+                .entries()
+                .iter()
+                .filter_map(|(key, value)| Some((key.to_string(), option_string(value)?)))
+                .collect(),
+        )),
     }
+}
 
-    // Match ->name('*.php')
-    let name_re = Regex::new(r#"->name\s*\(\s*'([^']+)'\s*\)"#)?;
-    for cap in name_re.captures_iter(content) {
-        finder.name_patterns.push(cap[1].to_string());
+/// Scalars nested in option arrays, as strings
+fn option_string(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Null => Some("null".to_string()),
+        value => scalar_string(value),
     }
+}
 
-    // Match ->notName('*.generated.php')
-    let not_name_re = Regex::new(r#"->notName\s*\(\s*'([^']+)'\s*\)"#)?;
-    for cap in not_name_re.captures_iter(content) {
-        finder.not_name_patterns.push(cap[1].to_string());
-    }
+/// Parse finder configuration
+fn parse_finder(evaluation: &Evaluation, set_finder: &MethodCall) -> Result<FinderConfig, ParseError> {
+    let mut finder = FinderConfig::default();
 
-    // Match ->notPath('path') or ->notPath(['path1', 'path2'])
-    let not_path_re = Regex::new(r#"->notPath\s*\(\s*(?:'([^']+)'|\[([^\]]+)\])"#)?;
-    for cap in not_path_re.captures_iter(content) {
-        if let Some(single) = cap.get(1) {
-            finder.not_path_patterns.push(single.as_str().to_string());
-        } else if let Some(array) = cap.get(2) {
-            let paths_re = Regex::new(r#"'([^']+)'"#)?;
-            for path_cap in paths_re.captures_iter(array.as_str()) {
-                finder.not_path_patterns.push(path_cap[1].to_string());
+    let id = match arg(set_finder)? {
+        Value::Object(id) if evaluation.object(*id).class == ObjectClass::Finder => *id,
+        // Any iterable of paths works too
+        Value::Array(_) => {
+            finder.paths = string_list(set_finder)?;
+            return Ok(finder);
+        }
+        other => return Err(invalid_arg(set_finder, "a PhpCsFixer\\Finder", other)),
+    };
+
+    for call in &evaluation.object(id).calls {
+        match call.name.to_ascii_lowercase().as_str() {
+            // ->in('src'), ->in(['src', 'app']), ->in(__DIR__.'/src') or ->in(getcwd())
+            "in" => {
+                if matches!(arg(call)?, Value::Path(PathBase::Cwd, rest) if rest.is_empty()) {
+                    finder.use_cwd = true;
+                    continue;
+                }
+                finder.paths.extend(string_list(call)?);
             }
+            // ->exclude('path') or ->exclude(['path1', 'path2'])
+            "exclude" => finder.exclude.extend(string_list(call)?),
+            // ->name('*.php')
+            "name" => finder.name_patterns.extend(string_list(call)?),
+            // ->notName('*.generated.php')
+            "notname" => finder.not_name_patterns.extend(string_list(call)?),
+            // ->notPath('path') or ->notPath(['path1', 'path2'])
+            "notpath" => finder.not_path_patterns.extend(string_list(call)?),
+            // ->ignoreVCSIgnored(true)
+            "ignorevcsignored" => finder.ignore_vcs_ignored = bool_arg(call)?,
+            _ => {}
         }
     }
 
-    // Match ->ignoreVCSIgnored(true)
-    let vcs_re = Regex::new(r#"->ignoreVCSIgnored\s*\(\s*(true|false)\s*\)"#)?;
-    if let Some(cap) = vcs_re.captures(content) {
-        finder.ignore_vcs_ignored = &cap[1] == "true";
-    }
-
     Ok(finder)
 }
 
-/// Parse cache file setting
-fn parse_cache_file(content: &str) -> Option<String> {
-    // Match ->setCacheFile('path') or ->setCacheFile(__DIR__.'/.php-cs-fixer.cache')
-    let re = Regex::new(r#"->setCacheFile\s*\(\s*(?:'([^']+)'|__DIR__\s*\.\s*'([^']*)')"#).ok()?;
-
-    re.captures(content).and_then(|cap| {
-        cap.get(1)
-            .or_else(|| cap.get(2))
-            .map(|m| m.as_str().trim_start_matches('/').to_string())
-    })
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Parse a config returning `(new PhpCsFixer\Config())` followed by `chain`
+    fn parse_chain(chain: &str) -> PhpCsFixerConfig {
+        parse_php_cs_fixer_config(&format!("<?php\nreturn (new PhpCsFixer\\Config()){};\n", chain)).unwrap()
+    }
+
+    fn parse_rules(chain: &str) -> HashMap<String, RuleConfig> {
+        parse_chain(chain).rules
+    }
+
+    /// Parse a config whose finder is `PhpCsFixer\Finder::create()` followed by `chain`
+    fn parse_finder(chain: &str) -> FinderConfig {
+        let content = format!(
+            "<?php\n$finder = PhpCsFixer\\Finder::create(){};\nreturn (new PhpCsFixer\\Config())->setFinder($finder);\n",
+            chain
+        );
+        parse_php_cs_fixer_config(&content).unwrap().finder
+    }
+
     #[test]
     fn test_parse_indent_spaces() {
-        let content = r#"$config->setIndent('    ')"#;
-        assert_eq!(parse_indent(content), IndentStyle::Spaces(4));
+        let config = parse_chain(r#"->setIndent('    ')"#);
+        assert_eq!(config.whitespace.indent, IndentStyle::Spaces(4));
+        assert!(config.indent_set);
+        assert!(!config.line_ending_set);
     }
 
     #[test]
     fn test_parse_indent_tabs() {
-        let content = r#"$config->setIndent("\t")"#;
-        assert_eq!(parse_indent(content), IndentStyle::Tabs);
+        let config = parse_chain(r#"->setIndent("\t")"#);
+        assert_eq!(config.whitespace.indent, IndentStyle::Tabs);
     }
 
     #[test]
     fn test_parse_line_ending_lf() {
-        let content = r#"$config->setLineEnding("\n")"#;
-        assert_eq!(parse_line_ending(content), LineEnding::Lf);
+        let config = parse_chain(r#"->setLineEnding("\n")"#);
+        assert_eq!(config.whitespace.line_ending, LineEnding::Lf);
     }
 
     #[test]
     fn test_parse_line_ending_crlf() {
-        let content = r#"$config->setLineEnding("\r\n")"#;
-        assert_eq!(parse_line_ending(content), LineEnding::CrLf);
+        let config = parse_chain(r#"->setLineEnding("\r\n")"#);
+        assert_eq!(config.whitespace.line_ending, LineEnding::CrLf);
     }
 
     #[test]
     fn test_parse_risky_allowed() {
-        assert!(parse_risky_allowed("->setRiskyAllowed(true)"));
-        assert!(!parse_risky_allowed("->setRiskyAllowed(false)"));
-        assert!(!parse_risky_allowed("// no risky setting"));
+        assert!(parse_chain("->setRiskyAllowed(true)").risky_allowed);
+        assert!(!parse_chain("->setRiskyAllowed(false)").risky_allowed);
+        assert!(!parse_chain("// no risky setting\n").risky_allowed);
     }
 
     #[test]
@@ -397,7 +438,7 @@ mod tests {
                 'single_quote' => false,
             ])
         "#;
-        let rules = parse_rules(content).unwrap();
+        let rules = parse_rules(content);
 
         assert!(rules.get("array_syntax").unwrap().enabled);
         assert!(rules.get("no_trailing_whitespace").unwrap().enabled);
@@ -414,7 +455,7 @@ mod tests {
                 ],
             ])
         "#;
-        let rules = parse_rules(content).unwrap();
+        let rules = parse_rules(content);
 
         let rule = rules.get("ordered_imports").unwrap();
         assert!(rule.enabled);
@@ -426,13 +467,46 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_finder_single_path() {
+    fn test_parse_rules_with_nested_options() {
         let content = r#"
-            $finder = Finder::create()
-                ->in('src')
-                ->exclude('vendor')
+            ->setRules([
+                '@PSR12' => true,
+                'binary_operator_spaces' => [
+                    'default' => 'single_space',
+                    'operators' => ['=>' => 'align_single_space_minimal', '|' => 'no_space'],
+                ],
+                'phpdoc_align' => ['align' => 'left', 'spacing' => ['param' => 2]],
+                // 'yoda_style' => false,
+                'yoda_style' => array('equal' => false, 'less_and_greater' => null),
+                'concat_space' => ['spacing' => 'one'],
+            ])
         "#;
-        let finder = parse_finder(content).unwrap();
+        let rules = parse_rules(content);
+
+        let binary = &rules["binary_operator_spaces"].options;
+        assert!(matches!(binary.get("default"), Some(ConfigValue::String(s)) if s == "single_space"));
+        match binary.get("operators") {
+            Some(ConfigValue::Map(map)) => {
+                assert_eq!(map["=>"], "align_single_space_minimal");
+                assert_eq!(map["|"], "no_space");
+            }
+            other => panic!("Expected map option, got {:?}", other),
+        }
+        assert!(!binary.contains_key("=>"));
+
+        assert!(matches!(rules["phpdoc_align"].options.get("spacing"),
+            Some(ConfigValue::Map(map)) if map["param"] == "2"));
+        let yoda = &rules["yoda_style"];
+        assert!(yoda.enabled);
+        assert!(matches!(yoda.options.get("equal"), Some(ConfigValue::Bool(false))));
+        assert!(!yoda.options.contains_key("less_and_greater"));
+        assert!(!rules.contains_key("=>"));
+        assert!(!rules.contains_key("spacing"));
+    }
+
+    #[test]
+    fn test_parse_finder_single_path() {
+        let finder = parse_finder("->in('src')->exclude('vendor')");
 
         assert_eq!(finder.paths, vec!["src"]);
         assert_eq!(finder.exclude, vec!["vendor"]);
@@ -441,11 +515,10 @@ mod tests {
     #[test]
     fn test_parse_finder_array_paths() {
         let content = r#"
-            $finder = Finder::create()
                 ->in(['src', 'app', 'lib'])
                 ->exclude(['vendor', 'node_modules'])
         "#;
-        let finder = parse_finder(content).unwrap();
+        let finder = parse_finder(content);
 
         assert_eq!(finder.paths, vec!["src", "app", "lib"]);
         assert_eq!(finder.exclude, vec!["vendor", "node_modules"]);
@@ -453,11 +526,7 @@ mod tests {
 
     #[test]
     fn test_parse_finder_dir_relative() {
-        let content = r#"
-            $finder = Finder::create()
-                ->in(__DIR__.'/src')
-        "#;
-        let finder = parse_finder(content).unwrap();
+        let finder = parse_finder("->in(__DIR__.'/src')");
 
         assert_eq!(finder.paths, vec!["src"]);
     }
@@ -465,11 +534,11 @@ mod tests {
     #[test]
     fn test_parse_cache_file() {
         assert_eq!(
-            parse_cache_file("->setCacheFile('.php-cs-fixer.cache')"),
+            parse_chain("->setCacheFile('.php-cs-fixer.cache')").cache_file,
             Some(".php-cs-fixer.cache".to_string())
         );
         assert_eq!(
-            parse_cache_file("->setCacheFile(__DIR__.'/.php-cs-fixer.cache')"),
+            parse_chain("->setCacheFile(__DIR__.'/.php-cs-fixer.cache')").cache_file,
             Some(".php-cs-fixer.cache".to_string())
         );
     }
@@ -509,12 +578,7 @@ mod tests {
 
     #[test]
     fn test_parse_finder_getcwd() {
-        let content = r#"
-            $finder = Finder::create()
-                ->in(getcwd())
-                ->ignoreVCSIgnored(true)
-        "#;
-        let finder = parse_finder(content).unwrap();
+        let finder = parse_finder("->in(getcwd())->ignoreVCSIgnored(true)");
 
         assert!(finder.use_cwd);
         assert!(finder.ignore_vcs_ignored);
@@ -523,11 +587,10 @@ mod tests {
     #[test]
     fn test_parse_finder_not_path() {
         let content = r#"
-            $finder = Finder::create()
                 ->notPath('server.php')
                 ->notPath('bootstrap/cache')
         "#;
-        let finder = parse_finder(content).unwrap();
+        let finder = parse_finder(content);
 
         assert_eq!(finder.not_path_patterns, vec!["server.php", "bootstrap/cache"]);
     }
@@ -600,4 +663,107 @@ return (new PhpCsFixer\Config())
         assert!(matches!(ordered.options.get("sort_algorithm"),
             Some(ConfigValue::String(s)) if s == "alpha"));
     }
+
+    #[test]
+    fn test_evaluate_variables_and_merges() {
+        let content = r#"<?php
+
+declare(strict_types=1);
+
+use PhpCsFixer\Config;
+use PhpCsFixer\Finder;
+
+$dirs = ['src', 'tests'];
+$paths = array_map(fn ($dir) => __DIR__ . '/' . $dir, $dirs);
+$finder = Finder::create()
+    ->in(array_merge([__DIR__ . '/src'], [__DIR__ . "/{$dirs[1]}"]))
+    ->exclude(['fixtures']);
+$finder->name('*.php');
+
+$rules = [
+    '@PSR12' => true,
+    'array_syntax' => ['syntax' => 'short'],
+];
+$rules['single_quote'] = true;
+$rules += ['array_syntax' => false, 'no_unused_imports' => true];
+$shared = ['ordered_imports' => ['sort_algorithm' => 'alpha']];
+
+$config = new Config();
+$config
+    ->setRules(array_merge($rules, $shared, ['single_quote' => false]))
+    ->setFinder($finder);
+
+return $config->setRiskyAllowed(true);
+"#;
+        let config = parse_php_cs_fixer_config(content).unwrap();
+
+        assert!(config.risky_allowed);
+        assert_eq!(config.finder.paths, vec!["src", "tests"]);
+        assert_eq!(config.finder.exclude, vec!["fixtures"]);
+        assert_eq!(config.finder.name_patterns, vec!["*.php"]);
+
+        // `+` keeps the existing entry, array_merge() overrides it
+        assert!(matches!(config.rules["array_syntax"].options.get("syntax"),
+            Some(ConfigValue::String(s)) if s == "short"));
+        assert!(config.is_rule_enabled("no_unused_imports"));
+        assert!(config.is_rule_enabled("ordered_imports"));
+        assert!(!config.is_rule_enabled("single_quote"));
+        assert!(config.is_rule_enabled("no_trailing_whitespace"));
+
+        // The unused closure isn't an error
+        assert_eq!(config.warnings, Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_report_dynamic_config() {
+        let content = r#"<?php
+$rules = require __DIR__ . '/rules.php';
+
+return (new PhpCsFixer\Config())
+    ->setRules($rules);
+"#;
+        let error = parse_php_cs_fixer_config(content).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not evaluate config statically: line 2: `require __DIR__ . '/rules.php'` can't be evaluated statically"
+        );
+
+        let content = r#"<?php
+$rules = ['@PSR12' => true];
+if (getenv('CI')) {
+    $rules['strict_param'] = true;
+}
+return (new PhpCsFixer\Config())->setRules($rules)->setIndent(getIndent());
+"#;
+        let error = parse_php_cs_fixer_config(content).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Could not evaluate config statically: line 6: call to getIndent() can't be evaluated statically"
+        );
+
+        let content = r#"<?php
+$rules = ['@PSR12' => true];
+if (getenv('CI')) {
+    $rules['strict_param'] = true;
+}
+return (new PhpCsFixer\Config())->setRules($rules);
+"#;
+        let config = parse_php_cs_fixer_config(content).unwrap();
+        assert!(config.is_rule_enabled("no_trailing_whitespace"));
+        assert_eq!(
+            config.warnings,
+            vec!["skipped `if` statement at line 3: it can't be evaluated statically"]
+        );
+
+        // Getters return values that aren't known statically
+        let error = parse_php_cs_fixer_config("<?php\n$config = new PhpCsFixer\\Config();\nreturn $config->setRules($config->getRules());\n").unwrap_err();
+        assert!(error.to_string().contains("line 3: ->getRules() can't be evaluated statically"));
+        let config = parse_php_cs_fixer_config("<?php\n$config = new PhpCsFixer\\Config();\n$config->a日b();\nreturn $config;\n");
+        assert!(config.is_ok());
+
+        let error = parse_php_cs_fixer_config("<?php\nreturn ['rules' => []];\n").unwrap_err();
+        assert!(error.to_string().contains("must return a PhpCsFixer\\Config instance, got array"));
+        let error = parse_php_cs_fixer_config("<?php\nreturn (new PhpCsFixer\\Config())->setRules('x');\n").unwrap_err();
+        assert!(error.to_string().contains("line 2: setRules() expects an array, got string"));
+    }
 }
@@ -0,0 +1,755 @@
+//! Import of PHP_CodeSniffer rulesets (phpcs.xml, ruleset.xml)
+//!
+//! The ruleset is read into [`PhpcsRuleset`] and converted to a
+//! [`PhpCsFixerConfig`] by mapping each sniff onto the fixers that fix the
+//! same issues. Sniffs without an equivalent fixer, such as line length or
+//! naming checks, are reported back instead.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use super::php_parser::{ConfigValue, ParseError, PhpCsFixerConfig, RuleConfig};
+use super::whitespace::{IndentStyle, LineEnding};
+
+/// A `<rule>` element of a ruleset
+#[derive(Debug, Clone, Default)]
+pub struct PhpcsRule {
+    /// Standard, category, sniff or message code (`ref` attribute)
+    pub reference: String,
+    /// Sniffs excluded from the reference (`<exclude name="..."/>`)
+    pub excludes: Vec<String>,
+    /// Paths the rule doesn't apply to (`<exclude-pattern>`)
+    pub exclude_patterns: Vec<String>,
+    /// Sniff properties (`<properties><property .../></properties>`)
+    pub properties: HashMap<String, ConfigValue>,
+    /// `<severity>`; 0 disables the rule
+    pub severity: Option<i64>,
+}
+
+/// Parsed PHP_CodeSniffer ruleset
+#[derive(Debug, Clone, Default)]
+pub struct PhpcsRuleset {
+    /// Ruleset name (`name` attribute)
+    pub name: Option<String>,
+    /// Paths to check (`<file>`)
+    pub files: Vec<String>,
+    /// Paths to skip (top-level `<exclude-pattern>`)
+    pub exclude_patterns: Vec<String>,
+    /// Command line arguments (`<arg name="..." value="..."/>`); short
+    /// flags like `<arg value="sp"/>` have no name
+    pub args: Vec<(Option<String>, String)>,
+    /// Rules in the order they appear
+    pub rules: Vec<PhpcsRule>,
+}
+
+/// Result of converting a ruleset
+#[derive(Debug, Clone)]
+pub struct PhpcsImport {
+    /// Equivalent fixer configuration
+    pub config: PhpCsFixerConfig,
+    /// Sniffs, standards and rulesets with no equivalent fixer
+    pub unmapped: Vec<String>,
+}
+
+impl PhpcsRuleset {
+    /// Parse a phpcs.xml file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ParseError> {
+        let content = std::fs::read_to_string(path)?;
+        parse_phpcs_ruleset(&content)
+    }
+
+    /// Value of a named `<arg>`
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .rev()
+            .find(|(arg, _)| arg.as_deref() == Some(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Convert to a fixer configuration
+    ///
+    /// Rules apply in order: a later `<rule>` can re-enable a sniff an
+    /// earlier one excluded, or override its properties.
+    pub fn to_fixer_config(&self) -> PhpcsImport {
+        let mut config = PhpCsFixerConfig::default();
+        let mut unmapped = Vec::new();
+
+        // Enabled sniffs with their properties, in order
+        let mut sniffs: Vec<(&'static str, HashMap<String, ConfigValue>)> = Vec::new();
+        for rule in &self.rules {
+            let Some(resolved) = resolve(&rule.reference) else {
+                unmapped.push(rule.reference.clone());
+                continue;
+            };
+            // A message code only matters to turn it off; other settings
+            // of a message (type, message text) have no fixer equivalent
+            let is_message = rule.reference.split('.').count() == 4;
+
+            if rule.severity == Some(0) {
+                sniffs.retain(|(sniff, _)| !resolved.contains(sniff));
+                continue;
+            }
+            if !rule.exclude_patterns.is_empty() {
+                config.warnings.push(format!(
+                    "<exclude-pattern> of rule {} ignored: fixers can only exclude paths for all rules",
+                    rule.reference
+                ));
+            }
+            let excluded: HashSet<&str> = rule.excludes.iter().filter_map(|name| resolve(name)).flatten().collect();
+
+            for sniff in resolved {
+                if excluded.contains(sniff) {
+                    continue;
+                }
+                match sniffs.iter_mut().find(|(name, _)| *name == sniff) {
+                    Some((_, properties)) => properties.extend(rule.properties.clone()),
+                    None if !is_message => sniffs.push((sniff, rule.properties.clone())),
+                    None => {}
+                }
+            }
+            sniffs.retain(|(sniff, _)| !excluded.contains(sniff));
+        }
+
+        for (sniff, properties) in &sniffs {
+            if !apply_sniff(sniff, properties, &mut config) {
+                unmapped.push(sniff.to_string());
+            }
+        }
+
+        config.finder.paths = self.files.clone();
+        config.finder.not_path_patterns = self.exclude_patterns.clone();
+        if let Some(extensions) = self.arg("extensions") {
+            // "php,inc/php": the part after '/' is the tokenizer to use
+            config.finder.name_patterns = extensions
+                .split(',')
+                .map(|ext| ext.split('/').next().unwrap_or(ext).trim())
+                .filter(|ext| !ext.is_empty())
+                .map(|ext| format!("*.{}", ext))
+                .collect();
+        }
+
+        let mut seen = HashSet::new();
+        unmapped.retain(|name| seen.insert(name.clone()));
+        PhpcsImport { config, unmapped }
+    }
+}
+
+/// Whether a config path looks like a PHP_CodeSniffer ruleset
+pub fn is_phpcs_ruleset(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.ends_with(".xml") || name.ends_with(".xml.dist")
+}
+
+/// Parse a PHP_CodeSniffer ruleset from a string
+pub fn parse_phpcs_ruleset(content: &str) -> Result<PhpcsRuleset, ParseError> {
+    let document = roxmltree::Document::parse(content)
+        .map_err(|e| ParseError::InvalidFormat(format!("invalid XML: {}", e)))?;
+    let root = document.root_element();
+    if root.tag_name().name() != "ruleset" {
+        return Err(ParseError::InvalidFormat(format!(
+            "expected a <ruleset> root element, got <{}>",
+            root.tag_name().name()
+        )));
+    }
+
+    let mut ruleset = PhpcsRuleset {
+        name: root.attribute("name").map(str::to_string),
+        ..Default::default()
+    };
+
+    for node in root.children().filter(|n| n.is_element()) {
+        match node.tag_name().name() {
+            "file" => ruleset.files.push(element_text(&node)),
+            "exclude-pattern" => ruleset.exclude_patterns.push(element_text(&node)),
+            "arg" => {
+                if let Some(value) = node.attribute("value") {
+                    ruleset.args.push((node.attribute("name").map(str::to_string), value.to_string()));
+                }
+            }
+            "rule" => ruleset.rules.push(parse_rule(&node)?),
+            // <description>, <config>, <ini>, <autoload>
+            _ => {}
+        }
+    }
+
+    Ok(ruleset)
+}
+
+fn parse_rule(node: &roxmltree::Node) -> Result<PhpcsRule, ParseError> {
+    let reference = node.attribute("ref").ok_or_else(|| {
+        let line = node.document().text_pos_at(node.range().start).row;
+        ParseError::InvalidFormat(format!("line {}: <rule> without a ref attribute", line))
+    })?;
+    let mut rule = PhpcsRule {
+        reference: reference.trim().to_string(),
+        ..Default::default()
+    };
+
+    for child in node.children().filter(|n| n.is_element()) {
+        match child.tag_name().name() {
+            "exclude" => rule.excludes.extend(child.attribute("name").map(str::to_string)),
+            "exclude-pattern" => rule.exclude_patterns.push(element_text(&child)),
+            "severity" => rule.severity = element_text(&child).parse().ok(),
+            "properties" => {
+                for property in child.children().filter(|n| n.has_tag_name("property")) {
+                    if let Some(name) = property.attribute("name") {
+                        rule.properties.insert(name.to_string(), property_value(&property));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(rule)
+}
+
+/// Value of a `<property>`: a string, or for `type="array"` a list or a map
+fn property_value(property: &roxmltree::Node) -> ConfigValue {
+    if property.attribute("type") != Some("array") {
+        return ConfigValue::String(property.attribute("value").unwrap_or("").to_string());
+    }
+
+    // Deprecated comma-separated form: value="a,b" or value="key=>value,..."
+    if let Some(value) = property.attribute("value") {
+        let items: Vec<&str> = value.split(',').map(str::trim).filter(|s| !s.is_empty()).collect();
+        if items.iter().any(|item| item.contains("=>")) {
+            return ConfigValue::Map(
+                items
+                    .iter()
+                    .filter_map(|item| item.split_once("=>"))
+                    .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+                    .collect(),
+            );
+        }
+        return ConfigValue::Array(items.iter().map(|s| s.to_string()).collect());
+    }
+
+    let elements: Vec<_> = property.children().filter(|n| n.has_tag_name("element")).collect();
+    if elements.iter().any(|e| e.attribute("key").is_some()) {
+        ConfigValue::Map(
+            elements
+                .iter()
+                .filter_map(|e| Some((e.attribute("key")?.to_string(), e.attribute("value").unwrap_or("").to_string())))
+                .collect(),
+        )
+    } else {
+        ConfigValue::Array(elements.iter().filter_map(|e| e.attribute("value")).map(str::to_string).collect())
+    }
+}
+
+fn element_text(node: &roxmltree::Node) -> String {
+    node.text().unwrap_or("").trim().to_string()
+}
+
+/// Sniffs a rule reference stands for, or `None` if nothing is known about it
+///
+/// A message code (`Standard.Category.Sniff.Code`) resolves to its sniff.
+fn resolve(reference: &str) -> Option<Vec<&'static str>> {
+    // Paths to other rulesets or to custom sniffs
+    if reference.contains('/') || reference.contains('\\') || reference.ends_with(".xml") {
+        return None;
+    }
+
+    let parts: Vec<&str> = reference.split('.').collect();
+    let sniffs: Vec<&'static str> = match parts.len() {
+        1 => STANDARDS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(reference))
+            .map(|(_, sniffs)| sniffs.to_vec())?,
+        2 => SNIFFS
+            .iter()
+            .map(|(sniff, _)| *sniff)
+            .filter(|sniff| sniff.starts_with(reference) && sniff[reference.len()..].starts_with('.'))
+            .collect(),
+        _ => {
+            let sniff = parts[..3].join(".");
+            SNIFFS.iter().map(|(name, _)| *name).filter(|name| *name == sniff).collect()
+        }
+    };
+    (!sniffs.is_empty()).then_some(sniffs)
+}
+
+/// Enable the fixers for a sniff and map its properties onto their options
+///
+/// Returns false if the sniff has no equivalent fixer.
+fn apply_sniff(sniff: &str, properties: &HashMap<String, ConfigValue>, config: &mut PhpCsFixerConfig) -> bool {
+    let fixers = SNIFFS.iter().find(|(name, _)| *name == sniff).map(|(_, fixers)| *fixers).unwrap_or(&[]);
+    if fixers.is_empty() {
+        return false;
+    }
+    for fixer in fixers {
+        config.rules.entry(fixer.to_string()).or_insert_with(|| RuleConfig {
+            enabled: true,
+            options: HashMap::new(),
+        });
+    }
+
+    let string = |name: &str| match properties.get(name) {
+        Some(ConfigValue::String(s)) => Some(s.as_str()),
+        _ => None,
+    };
+
+    // Properties with a fixer equivalent
+    let mut used: &[&str] = &[];
+    match sniff {
+        "Generic.PHP.UpperCaseConstant" => set_option(config, "constant_case", "case", ConfigValue::String("upper".into())),
+        "SlevomatCodingStandard.Namespaces.AlphabeticallySortedUses" => {
+            set_option(config, "ordered_imports", "sort_algorithm", ConfigValue::String("alpha".into()))
+        }
+        "SlevomatCodingStandard.ControlStructures.DisallowYodaComparison"
+        | "SlevomatCodingStandard.ControlStructures.RequireYodaComparison" => {
+            let yoda = sniff.ends_with("RequireYodaComparison");
+            for option in ["equal", "identical", "less_and_greater"] {
+                set_option(config, "yoda_style", option, ConfigValue::Bool(yoda));
+            }
+        }
+        "Squiz.Strings.ConcatenationSpacing" => {
+            used = &["spacing", "ignoreNewlines"];
+            let spacing = if string("spacing").is_some_and(|s| s != "0") { "one" } else { "none" };
+            set_option(config, "concat_space", "spacing", ConfigValue::String(spacing.into()));
+        }
+        "Generic.Files.LineEndings" => {
+            used = &["eolChar"];
+            if let Some(eol) = string("eolChar") {
+                config.whitespace.line_ending = LineEnding::from_php_config(&eol.replace("\\r", "\r").replace("\\n", "\n"));
+                config.line_ending_set = true;
+            }
+        }
+        "Generic.WhiteSpace.ScopeIndent" => {
+            used = &["indent", "tabIndent", "exact", "ignoreIndentationTokens"];
+            if string("tabIndent") == Some("true") {
+                config.whitespace.indent = IndentStyle::Tabs;
+                config.indent_set = true;
+            } else if let Some(width) = string("indent").and_then(|s| s.parse().ok()) {
+                config.whitespace.indent = IndentStyle::Spaces(width);
+                config.indent_set = true;
+            }
+        }
+        "Generic.WhiteSpace.DisallowSpaceIndent" => {
+            config.whitespace.indent = IndentStyle::Tabs;
+            config.indent_set = true;
+        }
+        // Line length limits only affect which lines are reported
+        "Generic.Files.LineLength" => used = &["lineLimit", "absoluteLineLimit", "ignoreComments"],
+        _ => {}
+    }
+
+    let mut ignored: Vec<&str> = properties.keys().map(|s| s.as_str()).filter(|name| !used.contains(name)).collect();
+    if !ignored.is_empty() {
+        ignored.sort_unstable();
+        config.warnings.push(format!("{}: properties not supported: {}", sniff, ignored.join(", ")));
+    }
+    true
+}
+
+fn set_option(config: &mut PhpCsFixerConfig, fixer: &str, option: &str, value: ConfigValue) {
+    if let Some(rule) = config.rules.get_mut(fixer) {
+        rule.options.insert(option.to_string(), value);
+    }
+}
+
+/// Standards and the sniffs they include (as far as they have known sniffs)
+const STANDARDS: &[(&str, &[&str])] = &[
+    ("PSR1", PSR1_SNIFFS),
+    ("PSR2", &[
+        "Generic.Files.ByteOrderMark",
+        "Generic.PHP.DisallowShortOpenTag",
+        "Generic.NamingConventions.UpperCaseConstantName",
+        "PSR1.Classes.ClassDeclaration",
+        "PSR1.Files.SideEffects",
+        "PSR1.Methods.CamelCapsMethodName",
+        "Squiz.Classes.ValidClassName",
+        "Generic.Files.LineEndings",
+        "Generic.Files.LineLength",
+        "Squiz.WhiteSpace.SuperfluousWhitespace",
+        "Generic.Formatting.DisallowMultipleStatements",
+        "Generic.WhiteSpace.ScopeIndent",
+        "Generic.WhiteSpace.DisallowTabIndent",
+        "Generic.PHP.LowerCaseKeyword",
+        "Generic.PHP.LowerCaseConstant",
+        "PSR2.Classes.ClassDeclaration",
+        "PSR2.Classes.PropertyDeclaration",
+        "Squiz.Scope.MethodScope",
+        "Squiz.WhiteSpace.ScopeKeywordSpacing",
+        "PSR2.Methods.MethodDeclaration",
+        "Squiz.Functions.FunctionDeclaration",
+        "Squiz.Functions.LowercaseFunctionKeywords",
+        "Squiz.Functions.FunctionDeclarationArgumentSpacing",
+        "Squiz.Functions.MultiLineFunctionDeclaration",
+        "PSR2.Methods.FunctionCallSignature",
+        "Generic.Functions.FunctionCallArgumentSpacing",
+        "PSR2.Methods.FunctionClosingBrace",
+        "Squiz.ControlStructures.ControlSignature",
+        "Squiz.WhiteSpace.ControlStructureSpacing",
+        "Squiz.WhiteSpace.ScopeClosingBrace",
+        "Squiz.ControlStructures.ForEachLoopDeclaration",
+        "Squiz.ControlStructures.ForLoopDeclaration",
+        "Squiz.ControlStructures.LowercaseDeclaration",
+        "PSR2.ControlStructures.ControlStructureSpacing",
+        "PSR2.ControlStructures.ElseIfDeclaration",
+        "PSR2.ControlStructures.SwitchDeclaration",
+        "Generic.ControlStructures.InlineControlStructure",
+        "PSR2.Files.ClosingTag",
+        "PSR2.Files.EndFileNewline",
+        "PSR2.Namespaces.NamespaceDeclaration",
+        "PSR2.Namespaces.UseDeclaration",
+    ]),
+    ("PSR12", &[
+        "Generic.Files.ByteOrderMark",
+        "Generic.PHP.DisallowShortOpenTag",
+        "Generic.NamingConventions.UpperCaseConstantName",
+        "PSR1.Classes.ClassDeclaration",
+        "PSR1.Files.SideEffects",
+        "PSR1.Methods.CamelCapsMethodName",
+        "Squiz.Classes.ValidClassName",
+        "Generic.Files.LineEndings",
+        "Generic.Files.LineLength",
+        "Squiz.WhiteSpace.SuperfluousWhitespace",
+        "Generic.Formatting.DisallowMultipleStatements",
+        "Generic.WhiteSpace.ScopeIndent",
+        "Generic.WhiteSpace.DisallowTabIndent",
+        "Generic.PHP.LowerCaseKeyword",
+        "Generic.PHP.LowerCaseConstant",
+        "Generic.PHP.LowerCaseType",
+        "PSR12.Files.FileHeader",
+        "PSR12.Files.OpenTag",
+        "PSR12.Files.ImportStatement",
+        "PSR12.Files.DeclareStatement",
+        "PSR12.Namespaces.CompoundNamespaceDepth",
+        "PSR12.Classes.ClassInstantiation",
+        "PSR12.Classes.ClosingBrace",
+        "PSR12.Classes.OpeningBraceSpace",
+        "PSR12.Classes.AnonClassDeclaration",
+        "PSR12.Traits.UseDeclaration",
+        "PSR12.Properties.ConstantVisibility",
+        "PSR12.Keywords.ShortFormTypeKeywords",
+        "PSR12.Functions.NullableTypeDeclaration",
+        "PSR12.Functions.ReturnTypeDeclaration",
+        "PSR12.ControlStructures.BooleanOperatorPlacement",
+        "PSR12.ControlStructures.ControlStructureSpacing",
+        "PSR12.Operators.OperatorSpacing",
+        "Generic.WhiteSpace.IncrementDecrementSpacing",
+        "PSR2.Classes.ClassDeclaration",
+        "PSR2.Classes.PropertyDeclaration",
+        "Squiz.Scope.MethodScope",
+        "Squiz.WhiteSpace.ScopeKeywordSpacing",
+        "Squiz.WhiteSpace.CastSpacing",
+        "PSR2.Methods.MethodDeclaration",
+        "Squiz.Functions.FunctionDeclaration",
+        "Squiz.Functions.LowercaseFunctionKeywords",
+        "Squiz.Functions.FunctionDeclarationArgumentSpacing",
+        "Squiz.Functions.MultiLineFunctionDeclaration",
+        "PSR2.Methods.FunctionCallSignature",
+        "Generic.Functions.FunctionCallArgumentSpacing",
+        "PSR2.Methods.FunctionClosingBrace",
+        "Squiz.ControlStructures.ControlSignature",
+        "Squiz.WhiteSpace.ControlStructureSpacing",
+        "Squiz.WhiteSpace.ScopeClosingBrace",
+        "Squiz.ControlStructures.ForEachLoopDeclaration",
+        "Squiz.ControlStructures.ForLoopDeclaration",
+        "Squiz.ControlStructures.LowercaseDeclaration",
+        "PSR2.ControlStructures.ElseIfDeclaration",
+        "PSR2.ControlStructures.SwitchDeclaration",
+        "Generic.ControlStructures.InlineControlStructure",
+        "PSR2.Files.ClosingTag",
+        "PSR2.Files.EndFileNewline",
+        "PSR2.Namespaces.NamespaceDeclaration",
+    ]),
+];
+
+const PSR1_SNIFFS: &[&str] = &[
+    "Generic.Files.ByteOrderMark",
+    "Generic.PHP.DisallowShortOpenTag",
+    "Generic.NamingConventions.UpperCaseConstantName",
+    "PSR1.Classes.ClassDeclaration",
+    "PSR1.Files.SideEffects",
+    "PSR1.Methods.CamelCapsMethodName",
+    "Squiz.Classes.ValidClassName",
+];
+
+/// Known sniffs and the fixers that fix what they report
+///
+/// Sniffs with no fixers only report problems (naming, line length, side
+/// effects) and are listed as unmapped.
+const SNIFFS: &[(&str, &[&str])] = &[
+    // Generic
+    ("Generic.Arrays.DisallowLongArraySyntax", &["array_syntax"]),
+    ("Generic.Arrays.DisallowShortArraySyntax", &[]),
+    ("Generic.CodeAnalysis.EmptyPHPStatement", &["no_empty_statement"]),
+    ("Generic.ControlStructures.InlineControlStructure", &["control_structure_braces"]),
+    ("Generic.Files.ByteOrderMark", &["encoding"]),
+    ("Generic.Files.LineEndings", &["line_ending"]),
+    ("Generic.Files.LineLength", &[]),
+    ("Generic.Formatting.DisallowMultipleStatements", &["no_multiple_statements_per_line"]),
+    ("Generic.Formatting.SpaceAfterCast", &["cast_spaces"]),
+    ("Generic.Functions.FunctionCallArgumentSpacing", &["method_argument_space"]),
+    ("Generic.NamingConventions.UpperCaseConstantName", &[]),
+    ("Generic.PHP.DisallowShortOpenTag", &["full_opening_tag"]),
+    ("Generic.PHP.LowerCaseConstant", &["constant_case"]),
+    ("Generic.PHP.LowerCaseKeyword", &["lowercase_keywords"]),
+    ("Generic.PHP.LowerCaseType", &["native_type_declaration_casing", "lowercase_cast"]),
+    ("Generic.PHP.UpperCaseConstant", &["constant_case"]),
+    ("Generic.Strings.UnnecessaryStringConcat", &["no_useless_concat_operator"]),
+    ("Generic.WhiteSpace.DisallowSpaceIndent", &["indentation_type"]),
+    ("Generic.WhiteSpace.DisallowTabIndent", &["indentation_type"]),
+    ("Generic.WhiteSpace.IncrementDecrementSpacing", &["unary_operator_spaces"]),
+    ("Generic.WhiteSpace.ScopeIndent", &["indentation_type", "statement_indentation"]),
+    // PSR-1
+    ("PSR1.Classes.ClassDeclaration", &[]),
+    ("PSR1.Files.SideEffects", &[]),
+    ("PSR1.Methods.CamelCapsMethodName", &[]),
+    // PSR-2
+    ("PSR2.Classes.ClassDeclaration", &["class_definition", "braces_position"]),
+    ("PSR2.Classes.PropertyDeclaration", &["visibility_required", "single_class_element_per_statement"]),
+    ("PSR2.ControlStructures.ControlStructureSpacing", &["spaces_inside_parentheses"]),
+    ("PSR2.ControlStructures.ElseIfDeclaration", &["elseif"]),
+    ("PSR2.ControlStructures.SwitchDeclaration", &["switch_case_space", "switch_case_semicolon_to_colon"]),
+    ("PSR2.Files.ClosingTag", &["no_closing_tag"]),
+    ("PSR2.Files.EndFileNewline", &["single_blank_line_at_end_of_file"]),
+    ("PSR2.Methods.FunctionCallSignature", &["method_argument_space", "no_spaces_after_function_name"]),
+    ("PSR2.Methods.FunctionClosingBrace", &["braces_position"]),
+    ("PSR2.Methods.MethodDeclaration", &["visibility_required"]),
+    ("PSR2.Namespaces.NamespaceDeclaration", &["blank_line_after_namespace"]),
+    ("PSR2.Namespaces.UseDeclaration", &["single_import_per_statement", "single_line_after_imports"]),
+    // PSR-12
+    ("PSR12.Classes.AnonClassDeclaration", &["class_definition"]),
+    ("PSR12.Classes.ClassInstantiation", &["new_with_parentheses"]),
+    ("PSR12.Classes.ClosingBrace", &[]),
+    ("PSR12.Classes.OpeningBraceSpace", &["no_blank_lines_after_class_opening"]),
+    ("PSR12.ControlStructures.BooleanOperatorPlacement", &[]),
+    ("PSR12.ControlStructures.ControlStructureSpacing", &["spaces_inside_parentheses"]),
+    ("PSR12.Files.DeclareStatement", &["declare_equal_normalize", "declare_parentheses"]),
+    ("PSR12.Files.FileHeader", &["blank_line_after_opening_tag"]),
+    ("PSR12.Files.ImportStatement", &["no_leading_import_slash"]),
+    ("PSR12.Files.OpenTag", &["linebreak_after_opening_tag"]),
+    ("PSR12.Functions.NullableTypeDeclaration", &["compact_nullable_type_declaration"]),
+    ("PSR12.Functions.ReturnTypeDeclaration", &["return_type_declaration"]),
+    ("PSR12.Keywords.ShortFormTypeKeywords", &[]),
+    ("PSR12.Namespaces.CompoundNamespaceDepth", &[]),
+    ("PSR12.Operators.OperatorSpacing", &["binary_operator_spaces"]),
+    ("PSR12.Properties.ConstantVisibility", &["visibility_required"]),
+    ("PSR12.Traits.UseDeclaration", &["single_trait_insert_per_statement"]),
+    // Squiz
+    ("Squiz.Arrays.ArrayBracketSpacing", &["no_spaces_around_offset"]),
+    ("Squiz.Classes.ValidClassName", &[]),
+    ("Squiz.ControlStructures.ControlSignature", &["control_structure_braces", "control_structure_continuation_position"]),
+    ("Squiz.ControlStructures.ForEachLoopDeclaration", &["spaces_inside_parentheses"]),
+    ("Squiz.ControlStructures.ForLoopDeclaration", &["spaces_inside_parentheses"]),
+    ("Squiz.ControlStructures.LowercaseDeclaration", &["lowercase_keywords"]),
+    ("Squiz.Functions.FunctionDeclaration", &["function_declaration"]),
+    ("Squiz.Functions.FunctionDeclarationArgumentSpacing", &["method_argument_space"]),
+    ("Squiz.Functions.LowercaseFunctionKeywords", &["lowercase_keywords"]),
+    ("Squiz.Functions.MultiLineFunctionDeclaration", &["function_declaration", "method_argument_space"]),
+    ("Squiz.Scope.MethodScope", &["visibility_required"]),
+    ("Squiz.Strings.ConcatenationSpacing", &["concat_space"]),
+    ("Squiz.Strings.DoubleQuoteUsage", &["single_quote"]),
+    ("Squiz.WhiteSpace.CastSpacing", &["cast_spaces"]),
+    ("Squiz.WhiteSpace.ControlStructureSpacing", &["spaces_inside_parentheses"]),
+    ("Squiz.WhiteSpace.ScopeClosingBrace", &["braces_position"]),
+    ("Squiz.WhiteSpace.ScopeKeywordSpacing", &["single_space_around_construct"]),
+    ("Squiz.WhiteSpace.SemicolonSpacing", &["no_singleline_whitespace_before_semicolons"]),
+    ("Squiz.WhiteSpace.SuperfluousWhitespace", &["no_trailing_whitespace", "no_whitespace_in_blank_line"]),
+    // Slevomat
+    ("SlevomatCodingStandard.Arrays.TrailingArrayComma", &["trailing_comma_in_multiline"]),
+    ("SlevomatCodingStandard.Classes.ClassConstantVisibility", &["visibility_required"]),
+    ("SlevomatCodingStandard.Classes.TraitUseDeclaration", &["single_trait_insert_per_statement"]),
+    ("SlevomatCodingStandard.Commenting.EmptyComment", &["no_empty_comment"]),
+    ("SlevomatCodingStandard.ControlStructures.DisallowYodaComparison", &["yoda_style"]),
+    ("SlevomatCodingStandard.ControlStructures.NewWithParentheses", &["new_with_parentheses"]),
+    ("SlevomatCodingStandard.ControlStructures.RequireNullCoalesceEqualOperator", &["assign_null_coalescing_to_coalesce_equal"]),
+    ("SlevomatCodingStandard.ControlStructures.RequireNullCoalesceOperator", &["ternary_to_null_coalescing"]),
+    ("SlevomatCodingStandard.ControlStructures.RequireYodaComparison", &["yoda_style"]),
+    ("SlevomatCodingStandard.ControlStructures.UselessIfConditionWithReturn", &["simplified_if_return"]),
+    ("SlevomatCodingStandard.Functions.RequireTrailingCommaInCall", &["trailing_comma_in_multiline"]),
+    ("SlevomatCodingStandard.Functions.RequireTrailingCommaInDeclaration", &["trailing_comma_in_multiline"]),
+    ("SlevomatCodingStandard.Namespaces.AlphabeticallySortedUses", &["ordered_imports"]),
+    ("SlevomatCodingStandard.Namespaces.DisallowGroupUse", &["single_import_per_statement"]),
+    ("SlevomatCodingStandard.Namespaces.MultipleUsesPerLine", &["single_import_per_statement"]),
+    ("SlevomatCodingStandard.Namespaces.NamespaceSpacing", &["blank_line_after_namespace"]),
+    ("SlevomatCodingStandard.Namespaces.UnusedUses", &["no_unused_imports"]),
+    ("SlevomatCodingStandard.Namespaces.UseDoesNotStartWithBackslash", &["no_leading_import_slash"]),
+    ("SlevomatCodingStandard.Namespaces.UselessAlias", &["no_unneeded_import_alias"]),
+    ("SlevomatCodingStandard.Namespaces.UseSpacing", &["single_line_after_imports"]),
+    ("SlevomatCodingStandard.Operators.DisallowEqualOperators", &["strict_comparison"]),
+    ("SlevomatCodingStandard.PHP.ShortList", &["list_syntax"]),
+    ("SlevomatCodingStandard.PHP.UselessSemicolon", &["no_empty_statement"]),
+    ("SlevomatCodingStandard.TypeHints.DeclareStrictTypes", &["declare_strict_types"]),
+    ("SlevomatCodingStandard.TypeHints.LongTypeHints", &["phpdoc_scalar"]),
+    ("SlevomatCodingStandard.TypeHints.NullableTypeForNullDefaultValue", &["nullable_type_declaration_for_default_null_value"]),
+    ("SlevomatCodingStandard.TypeHints.ParameterTypeHintSpacing", &["type_declaration_spaces"]),
+    ("SlevomatCodingStandard.TypeHints.ReturnTypeHintSpacing", &["return_type_declaration"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import(xml: &str) -> PhpcsImport {
+        parse_phpcs_ruleset(xml).unwrap().to_fixer_config()
+    }
+
+    #[test]
+    fn test_parse_ruleset() {
+        let ruleset = parse_phpcs_ruleset(r#"<?xml version="1.0"?>
+<ruleset name="Project">
+    <description>Coding standard</description>
+    <file>src</file>
+    <file>tests</file>
+    <exclude-pattern>*/cache/*</exclude-pattern>
+    <arg name="extensions" value="php,inc/php"/>
+    <arg value="sp"/>
+    <rule ref="PSR12">
+        <exclude name="Generic.Files.LineLength"/>
+    </rule>
+    <rule ref="Generic.PHP.ForbiddenFunctions">
+        <exclude-pattern>tests/*</exclude-pattern>
+        <properties>
+            <property name="forbiddenFunctions" type="array">
+                <element key="sizeof" value="count"/>
+            </property>
+            <property name="error" value="true"/>
+        </properties>
+    </rule>
+    <rule ref="Squiz.Strings.DoubleQuoteUsage"><severity>0</severity></rule>
+</ruleset>
+"#).unwrap();
+
+        assert_eq!(ruleset.name.as_deref(), Some("Project"));
+        assert_eq!(ruleset.files, vec!["src", "tests"]);
+        assert_eq!(ruleset.exclude_patterns, vec!["*/cache/*"]);
+        assert_eq!(ruleset.arg("extensions"), Some("php,inc/php"));
+        assert_eq!(ruleset.args[1], (None, "sp".to_string()));
+        assert_eq!(ruleset.rules.len(), 3);
+        assert_eq!(ruleset.rules[0].excludes, vec!["Generic.Files.LineLength"]);
+        assert_eq!(ruleset.rules[1].exclude_patterns, vec!["tests/*"]);
+        assert!(matches!(
+            &ruleset.rules[1].properties["forbiddenFunctions"],
+            ConfigValue::Map(map) if map["sizeof"] == "count"
+        ));
+        assert!(matches!(&ruleset.rules[1].properties["error"], ConfigValue::String(s) if s == "true"));
+        assert_eq!(ruleset.rules[2].severity, Some(0));
+    }
+
+    #[test]
+    fn test_invalid_ruleset() {
+        assert!(parse_phpcs_ruleset("<ruleset><rule ref=\"PSR12\"></ruleset>").is_err());
+        assert!(parse_phpcs_ruleset("<phpunit/>").is_err());
+        assert!(parse_phpcs_ruleset("<ruleset><rule/></ruleset>").is_err());
+    }
+
+    #[test]
+    fn test_psr12_with_exclusions() {
+        let import = import(r#"<ruleset>
+    <rule ref="PSR12">
+        <exclude name="PSR2.Files.ClosingTag"/>
+        <exclude name="Squiz.WhiteSpace"/>
+    </rule>
+</ruleset>"#);
+        let rules = &import.config.rules;
+        assert!(rules["lowercase_keywords"].enabled);
+        assert!(rules.contains_key("blank_line_after_namespace"));
+        assert!(rules.contains_key("declare_equal_normalize"));
+        assert!(!rules.contains_key("no_closing_tag"));
+        assert!(!rules.contains_key("cast_spaces"));
+        assert!(import.unmapped.contains(&"Generic.Files.LineLength".to_string()));
+        assert!(import.unmapped.contains(&"PSR1.Files.SideEffects".to_string()));
+    }
+
+    #[test]
+    fn test_sniffs_and_categories() {
+        let import = import(r#"<ruleset>
+    <rule ref="Generic.Arrays.DisallowLongArraySyntax"/>
+    <rule ref="SlevomatCodingStandard.Namespaces">
+        <exclude name="SlevomatCodingStandard.Namespaces.AlphabeticallySortedUses"/>
+    </rule>
+    <rule ref="SlevomatCodingStandard.Namespaces.FullyQualifiedGlobalFunctions"/>
+    <rule ref="Doctrine"/>
+    <rule ref="./vendor/acme/standard/ruleset.xml"/>
+</ruleset>"#);
+        let rules = &import.config.rules;
+        assert!(rules.contains_key("array_syntax"));
+        assert!(rules.contains_key("no_unused_imports"));
+        assert!(rules.contains_key("no_leading_import_slash"));
+        assert!(!rules.contains_key("ordered_imports"));
+        assert_eq!(
+            import.unmapped,
+            vec![
+                "SlevomatCodingStandard.Namespaces.FullyQualifiedGlobalFunctions",
+                "Doctrine",
+                "./vendor/acme/standard/ruleset.xml",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_severity_zero_disables_sniff() {
+        let import = import(r#"<ruleset>
+    <rule ref="PSR12"/>
+    <rule ref="PSR2.Files.ClosingTag.NotAllowed"><severity>0</severity></rule>
+</ruleset>"#);
+        assert!(!import.config.rules.contains_key("no_closing_tag"));
+        assert!(import.config.rules.contains_key("single_blank_line_at_end_of_file"));
+    }
+
+    #[test]
+    fn test_properties() {
+        let import = import(r#"<ruleset>
+    <rule ref="Generic.WhiteSpace.ScopeIndent">
+        <properties><property name="indent" value="2"/></properties>
+    </rule>
+    <rule ref="Generic.Files.LineEndings">
+        <properties><property name="eolChar" value="\r\n"/></properties>
+    </rule>
+    <rule ref="Squiz.Strings.ConcatenationSpacing">
+        <properties><property name="spacing" value="1"/></properties>
+    </rule>
+    <rule ref="Generic.PHP.UpperCaseConstant"/>
+    <rule ref="SlevomatCodingStandard.Namespaces.UnusedUses">
+        <properties><property name="searchAnnotations" value="true"/></properties>
+    </rule>
+</ruleset>"#);
+        let config = &import.config;
+        assert_eq!(config.whitespace.indent, IndentStyle::Spaces(2));
+        assert_eq!(config.whitespace.line_ending, LineEnding::CrLf);
+        assert!(matches!(&config.rules["concat_space"].options["spacing"], ConfigValue::String(s) if s == "one"));
+        assert!(matches!(&config.rules["constant_case"].options["case"], ConfigValue::String(s) if s == "upper"));
+        assert_eq!(
+            config.warnings,
+            vec!["SlevomatCodingStandard.Namespaces.UnusedUses: properties not supported: searchAnnotations"]
+        );
+    }
+
+    #[test]
+    fn test_files_and_patterns() {
+        let import = import(r#"<ruleset>
+    <file>src</file>
+    <exclude-pattern>*/Migrations/*</exclude-pattern>
+    <arg name="extensions" value="php,inc/php"/>
+    <rule ref="PSR12.Files.FileHeader">
+        <exclude-pattern>bin/*</exclude-pattern>
+    </rule>
+</ruleset>"#);
+        let finder = &import.config.finder;
+        assert_eq!(finder.paths, vec!["src"]);
+        assert_eq!(finder.not_path_patterns, vec!["*/Migrations/*"]);
+        assert_eq!(finder.name_patterns, vec!["*.php", "*.inc"]);
+        assert_eq!(import.config.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_mapped_fixers_exist() {
+        let registry = crate::fixers::FixerRegistry::new();
+        for (sniff, fixers) in SNIFFS {
+            for fixer in *fixers {
+                assert!(registry.get_by_php_name(fixer).is_some(), "{} maps to unknown fixer {}", sniff, fixer);
+            }
+        }
+        for (standard, sniffs) in STANDARDS {
+            for sniff in *sniffs {
+                assert!(SNIFFS.iter().any(|(name, _)| name == sniff), "{} includes unknown sniff {}", standard, sniff);
+            }
+        }
+    }
+}
@@ -56,6 +56,17 @@ pub fn get_preset_options(preset_name: &str) -> std::collections::HashMap<&'stat
             let mut ordered_imports_opts = HashMap::new();
             ordered_imports_opts.insert("sort_algorithm", PresetOptionValue::String("none"));
             options.insert("ordered_imports", ordered_imports_opts);
+
+            // PSR-12 only requires at least one space around binary operators
+            let mut binary_operator_opts = HashMap::new();
+            binary_operator_opts.insert("default", PresetOptionValue::String("at_least_single_space"));
+            options.insert("binary_operator_spaces", binary_operator_opts);
+        }
+        "PER" | "PERCS" | "@PER" | "@PERCS" => {
+            // Inherited from PSR-12
+            let mut binary_operator_opts = HashMap::new();
+            binary_operator_opts.insert("default", PresetOptionValue::String("at_least_single_space"));
+            options.insert("binary_operator_spaces", binary_operator_opts);
         }
         "SYMFONY" | "@SYMFONY" | "PHPCSFIXER" | "@PHPCSFIXER" => {
             // Symfony and PhpCsFixer presets DO split grouped imports (default behavior)
@@ -2,6 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Widest indentation `IndentStyle::as_str` returns
+const SPACES: &str = "                ";
+
 /// Indentation style
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum IndentStyle {
@@ -21,13 +24,7 @@ impl IndentStyle {
     /// Get the indentation string for one level
     pub fn as_str(&self) -> &'static str {
         match self {
-            IndentStyle::Spaces(2) => "  ",
-            IndentStyle::Spaces(4) => "    ",
-            IndentStyle::Spaces(n) => {
-                // For non-standard sizes, we'll need to generate at runtime
-                // This is a limitation - we use 4 spaces as fallback
-                if *n <= 2 { "  " } else { "    " }
-            }
+            IndentStyle::Spaces(n) => &SPACES[..(*n).min(SPACES.len())],
             IndentStyle::Tabs => "\t",
         }
     }
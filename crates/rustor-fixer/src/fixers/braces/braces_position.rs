@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Controls placement of opening braces for classes, methods, control structures
 pub struct BracesPositionFixer;
@@ -44,10 +45,40 @@ impl Fixer for BracesPositionFixer {
                 option_type: OptionType::Enum(vec!["same_line", "next_line_unless_newline_at_signature_end"]),
                 default: Some(ConfigValue::String("next_line_unless_newline_at_signature_end".to_string())),
             },
+            // Anonymous functions and classes are left as they are; these
+            // are accepted so PHP-CS-Fixer configs validate
+            FixerOption {
+                name: "anonymous_functions_opening_brace",
+                description: "Position for closure braces (not changed yet)",
+                option_type: OptionType::Enum(vec!["same_line", "next_line_unless_newline_at_signature_end"]),
+                default: Some(ConfigValue::String("same_line".to_string())),
+            },
+            FixerOption {
+                name: "anonymous_classes_opening_brace",
+                description: "Position for anonymous class braces (not changed yet)",
+                option_type: OptionType::Enum(vec!["same_line", "next_line_unless_newline_at_signature_end"]),
+                default: Some(ConfigValue::String("same_line".to_string())),
+            },
+            FixerOption {
+                name: "allow_single_line_anonymous_functions",
+                description: "Allow closures on a single line (not changed yet)",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(true)),
+            },
+            FixerOption {
+                name: "allow_single_line_empty_anonymous_classes",
+                description: "Allow empty anonymous classes on a single line (not changed yet)",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(true)),
+            },
         ]
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         let line_ending = config.line_ending.as_str();
 
@@ -85,7 +116,7 @@ impl Fixer for BracesPositionFixer {
                 let keyword = cap.get(1).unwrap().as_str();
                 let condition = cap.get(2).map(|m| m.as_str()).unwrap_or("");
 
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -118,7 +149,7 @@ impl Fixer for BracesPositionFixer {
                 let full_match = cap.get(0).unwrap();
                 let signature = cap.get(1).unwrap().as_str();
 
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -155,7 +186,7 @@ impl Fixer for BracesPositionFixer {
                 let full_match = cap.get(0).unwrap();
                 let signature = cap.get(1).unwrap().as_str();
 
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -180,7 +211,7 @@ impl Fixer for BracesPositionFixer {
                 let full_match = cap.get(0).unwrap();
                 let signature = cap.get(1).unwrap().as_str();
 
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -215,7 +246,7 @@ impl Fixer for BracesPositionFixer {
                 let full_match = cap.get(0).unwrap();
                 let signature = cap.get(1).unwrap().as_str();
 
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -240,7 +271,7 @@ impl Fixer for BracesPositionFixer {
             let full_match = cap.get(0).unwrap();
             let prefix = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -264,24 +295,6 @@ impl Fixer for BracesPositionFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures control structures use braces
 pub struct ControlStructureBracesFixer;
@@ -25,6 +26,10 @@ impl Fixer for ControlStructureBracesFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         let line_ending = config.line_ending.as_str();
 
@@ -37,7 +42,7 @@ impl Fixer for ControlStructureBracesFixer {
             let condition = cap.get(1).unwrap().as_str();
             let statement = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -70,7 +75,7 @@ impl Fixer for ControlStructureBracesFixer {
                 continue;
             }
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -98,7 +103,7 @@ impl Fixer for ControlStructureBracesFixer {
             let condition = cap.get(1).unwrap().as_str();
             let statement = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -126,7 +131,7 @@ impl Fixer for ControlStructureBracesFixer {
             let condition = cap.get(1).unwrap().as_str();
             let statement = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -147,7 +152,7 @@ impl Fixer for ControlStructureBracesFixer {
             let condition = cap.get(1).unwrap().as_str();
             let statement = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -164,24 +169,6 @@ impl Fixer for ControlStructureBracesFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
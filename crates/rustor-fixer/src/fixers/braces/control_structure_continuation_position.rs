@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures control structure continuations are on the same line as closing brace
 pub struct ControlStructureContinuationPositionFixer;
@@ -26,7 +27,11 @@ impl Fixer for ControlStructureContinuationPositionFixer {
         35
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match `}\n  else` or `}\n  elseif` or `}\n  catch` or `}\n  finally`
@@ -39,8 +44,8 @@ impl Fixer for ControlStructureContinuationPositionFixer {
             let keyword = cap.get(2).unwrap().as_str();
             let ws_str = whitespace.as_str();
 
-            // Skip if in string
-            if is_in_string(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -63,24 +68,6 @@ impl Fixer for ControlStructureContinuationPositionFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
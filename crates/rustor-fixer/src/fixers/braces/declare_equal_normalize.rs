@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Normalizes spacing in declare statements
 pub struct DeclareEqualNormalizeFixer;
@@ -24,7 +25,11 @@ impl Fixer for DeclareEqualNormalizeFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match declare statements with incorrect spacing around =
@@ -42,7 +47,7 @@ impl Fixer for DeclareEqualNormalizeFixer {
             let current = full_match.as_str();
 
             if current != normalized {
-                if is_in_string(&source[..full_match.start()]) {
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -60,24 +65,6 @@ impl Fixer for DeclareEqualNormalizeFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Converts `else if` to `elseif` for PSR-12 compliance
 pub struct ElseifFixer;
@@ -24,7 +25,11 @@ impl Fixer for ElseifFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match "else if" with various whitespace patterns
@@ -33,7 +38,7 @@ impl Fixer for ElseifFixer {
 
         for mat in re.find_iter(source) {
             // Check if in string or comment
-            if is_in_string_or_comment(&source[..mat.start()]) {
+            if tokens.is_in_string_or_comment(mat.start()) {
                 continue;
             }
 
@@ -50,51 +55,6 @@ impl Fixer for ElseifFixer {
     }
 }
 
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
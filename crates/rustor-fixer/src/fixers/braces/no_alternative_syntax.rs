@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Replaces alternative control structure syntax with braces
 pub struct NoAlternativeSyntaxFixer;
@@ -26,7 +27,11 @@ impl Fixer for NoAlternativeSyntaxFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // This is a complex transformation that requires careful handling.
@@ -35,7 +40,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `if (...):` pattern
         let if_colon_re = Regex::new(r"\bif\s*\([^)]+\)\s*:").unwrap();
         for m in if_colon_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             // Just flag for now - full transformation is complex
@@ -53,7 +58,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `endif;`
         let endif_re = Regex::new(r"\bendif\s*;").unwrap();
         for m in endif_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             edits.push(edit_with_rule(
@@ -68,7 +73,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `foreach (...):` pattern
         let foreach_colon_re = Regex::new(r"\bforeach\s*\([^)]+\)\s*:").unwrap();
         for m in foreach_colon_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             let colon_pos = m.as_str().rfind(':').unwrap();
@@ -85,7 +90,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `endforeach;`
         let endforeach_re = Regex::new(r"\bendforeach\s*;").unwrap();
         for m in endforeach_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             edits.push(edit_with_rule(
@@ -100,7 +105,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `while (...):` pattern
         let while_colon_re = Regex::new(r"\bwhile\s*\([^)]+\)\s*:").unwrap();
         for m in while_colon_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             let colon_pos = m.as_str().rfind(':').unwrap();
@@ -117,7 +122,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `endwhile;`
         let endwhile_re = Regex::new(r"\bendwhile\s*;").unwrap();
         for m in endwhile_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             edits.push(edit_with_rule(
@@ -132,7 +137,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `for (...):` pattern
         let for_colon_re = Regex::new(r"\bfor\s*\([^)]+\)\s*:").unwrap();
         for m in for_colon_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             let colon_pos = m.as_str().rfind(':').unwrap();
@@ -149,7 +154,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `endfor;`
         let endfor_re = Regex::new(r"\bendfor\s*;").unwrap();
         for m in endfor_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             edits.push(edit_with_rule(
@@ -164,7 +169,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `switch (...):` pattern
         let switch_colon_re = Regex::new(r"\bswitch\s*\([^)]+\)\s*:").unwrap();
         for m in switch_colon_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             let colon_pos = m.as_str().rfind(':').unwrap();
@@ -181,7 +186,7 @@ impl Fixer for NoAlternativeSyntaxFixer {
         // Match `endswitch;`
         let endswitch_re = Regex::new(r"\bendswitch\s*;").unwrap();
         for m in endswitch_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
             edits.push(edit_with_rule(
@@ -197,24 +202,6 @@ impl Fixer for NoAlternativeSyntaxFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
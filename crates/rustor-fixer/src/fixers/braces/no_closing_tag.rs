@@ -93,13 +93,12 @@ impl Fixer for NoClosingTagFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{IndentStyle, LineEnding};
+    use crate::config::LineEnding;
 
     fn check(source: &str) -> Vec<Edit> {
         NoClosingTagFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
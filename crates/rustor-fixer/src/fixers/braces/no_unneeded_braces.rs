@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Removes unnecessary curly braces
 pub struct NoUnneededBracesFixer;
@@ -26,7 +27,11 @@ impl Fixer for NoUnneededBracesFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match standalone braces that aren't part of a control structure
@@ -36,7 +41,7 @@ impl Fixer for NoUnneededBracesFixer {
         // Match `; { }` pattern (empty braces after statement)
         let empty_braces_re = Regex::new(r";\s*\{\s*\}").unwrap();
         for m in empty_braces_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
 
@@ -58,7 +63,7 @@ impl Fixer for NoUnneededBracesFixer {
         for cap in case_braces_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -85,24 +90,6 @@ impl Fixer for NoUnneededBracesFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
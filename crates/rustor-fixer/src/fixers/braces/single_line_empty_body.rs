@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures empty bodies are written on a single line
 pub struct SingleLineEmptyBodyFixer;
@@ -26,7 +27,11 @@ impl Fixer for SingleLineEmptyBodyFixer {
         25
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match multiline empty bodies: {\n  \n} or {\n}
@@ -36,8 +41,8 @@ impl Fixer for SingleLineEmptyBodyFixer {
         for cap in re.find_iter(source) {
             let match_str = cap.as_str();
 
-            // Skip if in string
-            if is_in_string(&source[..cap.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(cap.start()) {
                 continue;
             }
 
@@ -58,24 +63,6 @@ impl Fixer for SingleLineEmptyBodyFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Converts semicolons in case/default statements to colons
 pub struct SwitchCaseSemicolonToColonFixer;
@@ -24,7 +25,11 @@ impl Fixer for SwitchCaseSemicolonToColonFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match case statements with semicolon instead of colon
@@ -37,7 +42,7 @@ impl Fixer for SwitchCaseSemicolonToColonFixer {
             let full_match = cap.get(0).unwrap();
             let value = cap.get(1).unwrap().as_str().trim();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -55,7 +60,7 @@ impl Fixer for SwitchCaseSemicolonToColonFixer {
         let default_re = Regex::new(r"(?i)\bdefault\s*;").unwrap();
 
         for mat in default_re.find_iter(source) {
-            if is_in_string(&source[..mat.start()]) {
+            if tokens.is_in_string_or_comment(mat.start()) {
                 continue;
             }
 
@@ -72,24 +77,6 @@ impl Fixer for SwitchCaseSemicolonToColonFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule, ConfigValue, FixerOption, OptionType};
+use crate::tokens::TokenStream;
 
 /// Ensures true, false, null are lowercase (by default) or uppercase
 pub struct ConstantCaseFixer;
@@ -34,6 +35,10 @@ impl Fixer for ConstantCaseFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let use_upper = config.options.get("case")
             .and_then(|v| match v {
                 ConfigValue::String(s) => Some(s.as_str()),
@@ -67,7 +72,7 @@ impl Fixer for ConstantCaseFixer {
                 }
 
                 // Skip if inside string/comment
-                if is_in_string_or_comment(&source[..matched.start()]) {
+                if tokens.is_in_string_or_comment(matched.start()) {
                     continue;
                 }
 
@@ -89,52 +94,6 @@ impl Fixer for ConstantCaseFixer {
     }
 }
 
-/// Simple check if position is in string or comment
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures cast operators are lowercase
 pub struct LowercaseCastFixer;
@@ -26,7 +27,11 @@ impl Fixer for LowercaseCastFixer {
         40
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match uppercase or mixed case casts
@@ -39,7 +44,7 @@ impl Fixer for LowercaseCastFixer {
             let cast_type = cap.get(1).unwrap();
 
             // Skip if in string or comment
-            if is_in_string_or_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -63,51 +68,6 @@ impl Fixer for LowercaseCastFixer {
     }
 }
 
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// PHP keywords that should be lowercase
 const PHP_KEYWORDS: &[&str] = &[
@@ -41,7 +42,11 @@ impl Fixer for LowercaseKeywordsFixer {
         40
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Build regex pattern for all keywords (case insensitive)
@@ -59,9 +64,8 @@ impl Fixer for LowercaseKeywordsFixer {
                     continue;
                 }
 
-                // Skip if inside a string or comment (simple heuristic)
-                let before = &source[..matched.start()];
-                if is_in_string_or_comment(before) {
+                // Skip if inside a string or comment
+                if tokens.is_in_string_or_comment(matched.start()) {
                     continue;
                 }
 
@@ -79,56 +83,6 @@ impl Fixer for LowercaseKeywordsFixer {
     }
 }
 
-/// Simple heuristic to check if position might be in string or comment
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        // Check for line comment start
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        // Check for block comment
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        // Reset line comment at newline
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        // Track string state
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,12 +158,11 @@ mod tests {
     }
 
     #[test]
-    fn test_is_in_string() {
-        assert!(is_in_string_or_comment("$a = '"));
-        assert!(is_in_string_or_comment("$a = \""));
-        assert!(!is_in_string_or_comment("$a = 'test'; "));
-        assert!(is_in_string_or_comment("// comment "));
-        assert!(is_in_string_or_comment("/* block "));
-        assert!(!is_in_string_or_comment("/* block */ "));
+    fn test_skip_in_heredoc_and_inline_html() {
+        let source = "<p>IF</p><?php\n$a = <<<EOT\nIF $b RETURN\nEOT;\n/* IF */ IF ($a) {}\n";
+        let edits = check(source);
+
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].replacement, "if");
     }
 }
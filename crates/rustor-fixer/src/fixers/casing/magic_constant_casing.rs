@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures magic constants use correct (uppercase) casing
 pub struct MagicConstantCasingFixer;
@@ -36,7 +37,11 @@ impl Fixer for MagicConstantCasingFixer {
         40
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match magic constants with wrong casing
@@ -54,7 +59,7 @@ impl Fixer for MagicConstantCasingFixer {
 
             if let Some(&correct_const) = correct {
                 if const_str != correct_const {
-                    if is_in_string(&source[..const_match.start()]) {
+                    if tokens.is_in_string_or_comment(const_match.start()) {
                         continue;
                     }
 
@@ -73,24 +78,6 @@ impl Fixer for MagicConstantCasingFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures magic methods use correct casing
 pub struct MagicMethodCasingFixer;
@@ -45,7 +46,11 @@ impl Fixer for MagicMethodCasingFixer {
         40
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match function declarations that look like magic methods
@@ -63,7 +68,7 @@ impl Fixer for MagicMethodCasingFixer {
 
             if let Some(&correct) = correct_casing {
                 if func_str != correct {
-                    if is_in_string(&source[..func_name.start()]) {
+                    if tokens.is_in_string_or_comment(func_name.start()) {
                         continue;
                     }
 
@@ -82,24 +87,6 @@ impl Fixer for MagicMethodCasingFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
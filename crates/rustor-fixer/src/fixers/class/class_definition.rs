@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures proper spacing in class definitions
 pub struct ClassDefinitionFixer;
@@ -24,7 +25,11 @@ impl Fixer for ClassDefinitionFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Fix multiple spaces after class/interface/trait keyword
@@ -35,8 +40,8 @@ impl Fixer for ClassDefinitionFixer {
             let keyword = cap.get(1).unwrap().as_str();
             let first_char = cap.get(2).unwrap().as_str();
 
-            // Check not in string
-            if is_in_string(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -56,7 +61,7 @@ impl Fixer for ClassDefinitionFixer {
             let full_match = cap.get(0).unwrap();
             let last_char = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -76,7 +81,7 @@ impl Fixer for ClassDefinitionFixer {
             let full_match = cap.get(0).unwrap();
             let first_char = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -96,7 +101,7 @@ impl Fixer for ClassDefinitionFixer {
             let full_match = cap.get(0).unwrap();
             let last_char = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -116,7 +121,7 @@ impl Fixer for ClassDefinitionFixer {
             let full_match = cap.get(0).unwrap();
             let first_char = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -133,24 +138,6 @@ impl Fixer for ClassDefinitionFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,306 @@
+//! Token-based scanner for the members of class-like bodies
+//!
+//! Shared by the fixers that reorder or separate class members.
+
+use crate::tokens::{Region, Token, TokenStream};
+use mago_syntax::token::TokenKind;
+
+/// Kind of a class member
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MemberKind {
+    UseTrait,
+    Case,
+    Constant,
+    Property,
+    Method,
+}
+
+/// Visibility of a class member (members without one are public)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Visibility {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Visibility {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Protected => "protected",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+/// A member of a class, interface, trait or enum body
+#[derive(Debug, Clone)]
+pub(crate) struct ClassMember {
+    pub kind: MemberKind,
+    pub visibility: Visibility,
+    pub is_static: bool,
+    pub is_abstract: bool,
+    pub is_readonly: bool,
+    /// Name of the method, constant, case or (first) property, without `$`
+    pub name: String,
+    /// Start of the member, including its docblock, attributes and comments
+    pub start: usize,
+    /// End of the member, including a comment on the same line
+    pub end: usize,
+    /// Whether the member has a docblock or attributes
+    pub has_meta: bool,
+}
+
+/// The body of a class-like declaration
+#[derive(Debug, Clone)]
+pub(crate) struct ClassBody {
+    pub members: Vec<ClassMember>,
+}
+
+/// Find all class-like bodies in the source, including anonymous classes
+pub(crate) fn class_bodies(source: &str, tokens: &TokenStream) -> Vec<ClassBody> {
+    // Code and comments; strings and whitespace don't matter for the structure
+    let tokens: Vec<&Token> = tokens
+        .tokens()
+        .iter()
+        .filter(|t| t.kind != TokenKind::Whitespace && matches!(t.region, Region::Code | Region::Comment))
+        .collect();
+
+    let mut bodies = Vec::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        if !matches!(token.kind, TokenKind::Class | TokenKind::Trait | TokenKind::Interface | TokenKind::Enum) {
+            continue;
+        }
+        let prev = tokens[..idx].iter().rev().find(|t| t.region == Region::Code);
+        // `Foo::class`
+        if prev.is_some_and(|t| t.kind == TokenKind::ColonColon) {
+            continue;
+        }
+
+        // The body starts at the first brace outside of parentheses (anonymous class arguments)
+        let mut depth = 0i32;
+        let mut open = None;
+        for (offset, t) in tokens[idx + 1..].iter().enumerate() {
+            match t.kind {
+                TokenKind::LeftParenthesis => depth += 1,
+                TokenKind::RightParenthesis => depth -= 1,
+                TokenKind::LeftBrace if depth == 0 => {
+                    open = Some(idx + 1 + offset);
+                    break;
+                }
+                TokenKind::Semicolon if depth == 0 => break,
+                _ => {}
+            }
+        }
+        if let Some(open) = open {
+            if let Some(body) = scan_body(source, &tokens, open) {
+                bodies.push(body);
+            }
+        }
+    }
+
+    bodies
+}
+
+/// Index of the token closing the bracket opened at `open`
+fn matching_close(tokens: &[&Token], open: usize) -> Option<usize> {
+    let mut depth = 0i32;
+    for (idx, token) in tokens.iter().enumerate().skip(open) {
+        if token.region != Region::Code {
+            continue;
+        }
+        match token.kind {
+            TokenKind::LeftBrace
+            | TokenKind::DollarLeftBrace
+            | TokenKind::LeftBracket
+            | TokenKind::HashLeftBracket
+            | TokenKind::LeftParenthesis => depth += 1,
+            TokenKind::RightBrace | TokenKind::RightBracket | TokenKind::RightParenthesis => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index of the token ending a declaration that starts at `from`: a `;` or the
+/// `}` closing a body, whichever comes first outside of brackets
+fn declaration_end(tokens: &[&Token], from: usize) -> Option<usize> {
+    let mut idx = from;
+    while idx < tokens.len() {
+        let token = tokens[idx];
+        if token.region == Region::Code {
+            match token.kind {
+                TokenKind::Semicolon => return Some(idx),
+                TokenKind::LeftBrace => return matching_close(tokens, idx),
+                TokenKind::RightBrace => return None,
+                TokenKind::LeftParenthesis | TokenKind::LeftBracket | TokenKind::HashLeftBracket => {
+                    idx = matching_close(tokens, idx)?;
+                }
+                _ => {}
+            }
+        }
+        idx += 1;
+    }
+    None
+}
+
+fn scan_body(source: &str, tokens: &[&Token], open: usize) -> Option<ClassBody> {
+    let close = matching_close(tokens, open)?;
+    let mut members = Vec::new();
+
+    let mut idx = open + 1;
+    let mut pending_start: Option<usize> = None;
+    let mut has_meta = false;
+
+    while idx < close {
+        let token = tokens[idx];
+
+        if token.region == Region::Comment {
+            pending_start.get_or_insert(token.start);
+            has_meta |= token.kind == TokenKind::DocBlockComment;
+            idx += 1;
+            continue;
+        }
+        match token.kind {
+            TokenKind::HashLeftBracket => {
+                pending_start.get_or_insert(token.start);
+                has_meta = true;
+                idx = matching_close(tokens, idx)? + 1;
+                continue;
+            }
+            TokenKind::Semicolon => {
+                idx += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let decl_start = idx;
+        let mut visibility = Visibility::Public;
+        let (mut is_static, mut is_abstract, mut is_readonly) = (false, false, false);
+        while idx < close {
+            match tokens[idx].kind {
+                TokenKind::Public => visibility = Visibility::Public,
+                TokenKind::Protected => visibility = Visibility::Protected,
+                TokenKind::Private => visibility = Visibility::Private,
+                TokenKind::Static => is_static = true,
+                TokenKind::Abstract => is_abstract = true,
+                TokenKind::Readonly => is_readonly = true,
+                TokenKind::Final | TokenKind::Var | TokenKind::PublicSet | TokenKind::ProtectedSet
+                | TokenKind::PrivateSet => {}
+                _ => break,
+            }
+            idx += 1;
+        }
+
+        let code_after = |from: usize| tokens[from..close].iter().find(|t| t.region == Region::Code);
+        let (kind, name) = match tokens.get(idx).map(|t| t.kind) {
+            Some(TokenKind::Use) => (MemberKind::UseTrait, String::new()),
+            Some(TokenKind::Case) => (
+                MemberKind::Case,
+                code_after(idx + 1).map(|t| t.text(source).to_string()).unwrap_or_default(),
+            ),
+            Some(TokenKind::Const) => {
+                // The name is right before `=` (constants may be typed)
+                let name = tokens[idx + 1..close]
+                    .windows(2)
+                    .find(|pair| pair[1].kind == TokenKind::Equal)
+                    .map(|pair| pair[0].text(source).to_string())
+                    .unwrap_or_default();
+                (MemberKind::Constant, name)
+            }
+            Some(TokenKind::Function) => {
+                let name = tokens[idx + 1..close]
+                    .iter()
+                    .find(|t| t.region == Region::Code && t.kind != TokenKind::Ampersand)
+                    .map(|t| t.text(source).to_string())
+                    .unwrap_or_default();
+                (MemberKind::Method, name)
+            }
+            _ => {
+                let name = tokens[idx..close]
+                    .iter()
+                    .find(|t| t.kind == TokenKind::Variable)
+                    .map(|t| t.text(source).trim_start_matches('$').to_string())
+                    .unwrap_or_default();
+                (MemberKind::Property, name)
+            }
+        };
+
+        let end_idx = match declaration_end(tokens, idx) {
+            Some(end_idx) if end_idx < close => end_idx,
+            _ => break,
+        };
+        let mut end = tokens[end_idx].end;
+        idx = end_idx + 1;
+
+        // A comment on the same line belongs to the member
+        if let Some(comment) = tokens.get(idx).filter(|t| t.region == Region::Comment && idx < close) {
+            if !source[end..comment.start].contains('\n') {
+                end = comment.end;
+                idx += 1;
+            }
+        }
+
+        members.push(ClassMember {
+            kind,
+            visibility,
+            is_static,
+            is_abstract,
+            is_readonly,
+            name,
+            start: pending_start.take().unwrap_or(tokens[decl_start].start),
+            end,
+            has_meta,
+        });
+        has_meta = false;
+    }
+
+    Some(ClassBody { members })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_members() {
+        let source = "<?php\nclass A {\n    use T1, T2 { foo as bar; }\n    /** Doc */\n    #[Attr]\n    final public const int X = 1; // trailing\n    protected static ?Foo $a = null, $b;\n    abstract protected function &run(array $x = [1]);\n    public function __construct() { $f = function () {}; }\n}\n$x = A::class;\n";
+        let bodies = class_bodies(source, &TokenStream::new(source));
+        assert_eq!(bodies.len(), 1);
+
+        let members = &bodies[0].members;
+        let summary: Vec<_> = members.iter().map(|m| (m.kind, m.name.as_str(), m.has_meta)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (MemberKind::UseTrait, "", false),
+                (MemberKind::Constant, "X", true),
+                (MemberKind::Property, "a", false),
+                (MemberKind::Method, "run", false),
+                (MemberKind::Method, "__construct", false),
+            ]
+        );
+        assert!(source[members[1].start..members[1].end].starts_with("/** Doc */"));
+        assert!(source[members[1].start..members[1].end].ends_with("// trailing"));
+        assert!(members[2].is_static && members[2].visibility == Visibility::Protected);
+        assert!(members[3].is_abstract);
+        assert!(source[members[4].start..members[4].end].ends_with("{}; }"));
+    }
+
+    #[test]
+    fn test_anonymous_class_and_enum() {
+        let source = "<?php\n$a = new class($b) { public $x; };\nenum Suit: string {\n    case Hearts = 'H';\n    const Wild = self::Hearts;\n}\n";
+        let bodies = class_bodies(source, &TokenStream::new(source));
+        assert_eq!(bodies.len(), 2);
+        assert_eq!(bodies[0].members[0].kind, MemberKind::Property);
+        assert_eq!(bodies[1].members[0].kind, MemberKind::Case);
+        assert_eq!(bodies[1].members[0].name, "Hearts");
+        assert_eq!(bodies[1].members[1].name, "Wild");
+    }
+}
@@ -9,6 +9,7 @@ mod class_definition;
 mod single_class_element_per_statement;
 mod single_trait_insert_per_statement;
 mod ordered_class_elements;
+pub(crate) mod class_members;
 
 pub use visibility_required::VisibilityRequiredFixer;
 pub use no_blank_lines_after_class_opening::NoBlankLinesAfterClassOpeningFixer;
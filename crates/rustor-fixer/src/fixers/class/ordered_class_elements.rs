@@ -1,40 +1,91 @@
 //! Order class elements according to PSR-12
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::TokenStream;
+use super::class_members::{class_bodies, ClassMember, MemberKind};
 
-/// Orders class elements according to PSR conventions
+/// Orders class elements according to the `order` option
 pub struct OrderedClassElementsFixer;
 
+/// Element types accepted by the `order` option
+const ELEMENT_TYPES: &[&str] = &[
+    "case",
+    "constant",
+    "constant_private",
+    "constant_protected",
+    "constant_public",
+    "construct",
+    "destruct",
+    "magic",
+    "method",
+    "method_abstract",
+    "method_private",
+    "method_private_abstract",
+    "method_private_abstract_static",
+    "method_private_static",
+    "method_protected",
+    "method_protected_abstract",
+    "method_protected_abstract_static",
+    "method_protected_static",
+    "method_public",
+    "method_public_abstract",
+    "method_public_abstract_static",
+    "method_public_static",
+    "method_static",
+    "phpunit",
+    "private",
+    "property",
+    "property_private",
+    "property_private_readonly",
+    "property_private_static",
+    "property_protected",
+    "property_protected_readonly",
+    "property_protected_static",
+    "property_public",
+    "property_public_readonly",
+    "property_public_static",
+    "property_readonly",
+    "property_static",
+    "protected",
+    "public",
+    "use_trait",
+];
+
 // PSR-12 recommended order:
 // 1. use traits
 // 2. constants
 // 3. properties (public, protected, private)
 // 4. constructor
 // 5. methods (public, protected, private)
-
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-enum ElementType {
-    UseTrait,
-    Constant,
-    PublicProperty,
-    ProtectedProperty,
-    PrivateProperty,
-    Constructor,
-    Destructor,
-    PublicMethod,
-    ProtectedMethod,
-    PrivateMethod,
-}
-
-#[derive(Debug, Clone)]
-struct ClassElement {
-    element_type: ElementType,
-    content: String,
-    start: usize,
-    end: usize,
-}
+const DEFAULT_ORDER: &[&str] = &[
+    "use_trait",
+    "case",
+    "constant_public",
+    "constant_protected",
+    "constant_private",
+    "property_public",
+    "property_protected",
+    "property_private",
+    "construct",
+    "destruct",
+    "magic",
+    "phpunit",
+    "method_public",
+    "method_protected",
+    "method_private",
+];
+
+/// PHPUnit template methods
+const PHPUNIT_METHODS: &[&str] = &[
+    "setupbeforeclass",
+    "teardownafterclass",
+    "setup",
+    "teardown",
+    "assertpreconditions",
+    "assertpostconditions",
+    "onnotsuccessfultest",
+];
 
 impl Fixer for OrderedClassElementsFixer {
     fn name(&self) -> &'static str {
@@ -53,162 +104,151 @@ impl Fixer for OrderedClassElementsFixer {
         20
     }
 
-    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-        let line_ending = config.line_ending.as_str();
-
-        // Find class bodies
-        let class_re = Regex::new(r"(?s)\b(?:class|trait|interface)\s+\w+[^{]*\{").unwrap();
-
-        for class_match in class_re.find_iter(source) {
-            let class_start = class_match.end();
-
-            // Find the matching closing brace
-            if let Some(class_end) = find_matching_brace(source, class_start - 1) {
-                let class_body = &source[class_start..class_end];
-
-                // Parse elements from the class body
-                let elements = parse_class_elements(class_body, class_start);
-
-                if elements.is_empty() {
-                    continue;
-                }
-
-                // Check if already sorted
-                let mut sorted_elements = elements.clone();
-                sorted_elements.sort_by(|a, b| a.element_type.cmp(&b.element_type));
+    fn options(&self) -> Vec<FixerOption> {
+        vec![
+            FixerOption {
+                name: "order",
+                description: "Order of element types; types that aren't listed go last",
+                option_type: OptionType::EnumArray(ELEMENT_TYPES.to_vec()),
+                default: Some(ConfigValue::Array(DEFAULT_ORDER.iter().map(|s| s.to_string()).collect())),
+            },
+            FixerOption {
+                name: "sort_algorithm",
+                description: "How elements of the same type are sorted: none, alpha",
+                option_type: OptionType::Enum(vec!["none", "alpha"]),
+                default: Some(ConfigValue::String("none".to_string())),
+            },
+            FixerOption {
+                name: "case_sensitive",
+                description: "Whether alpha sorting is case sensitive",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(false)),
+            },
+        ]
+    }
 
-                let is_sorted = elements
-                    .iter()
-                    .zip(sorted_elements.iter())
-                    .all(|(a, b)| std::ptr::eq(a.content.as_str(), b.content.as_str()) || a.element_type == b.element_type);
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
 
-                if is_sorted {
-                    continue;
-                }
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let order: Vec<&str> = match config.get_array("order") {
+            Some(order) => order.iter().map(|s| s.as_str()).collect(),
+            None => DEFAULT_ORDER.to_vec(),
+        };
+        let alpha = config.get_string("sort_algorithm") == Some("alpha");
+        let case_sensitive = config.get_bool("case_sensitive").unwrap_or(false);
+
+        let mut edits: Vec<Edit> = Vec::new();
+        for body in class_bodies(source, tokens) {
+            let members = &body.members;
+            let (Some(first), Some(last)) = (members.first(), members.last()) else {
+                continue;
+            };
+            // Nested classes inside an element that is already being moved
+            if edits.iter().any(|e| e.span.start.offset as usize <= first.start && last.end <= e.span.end.offset as usize) {
+                continue;
+            }
 
-                // Generate sorted class body
-                let sorted_content: String = sorted_elements
-                    .iter()
-                    .map(|e| e.content.clone())
-                    .collect::<Vec<_>>()
-                    .join(line_ending);
+            let sort_key = |member: &ClassMember| {
+                let name = if !alpha {
+                    String::new()
+                } else if case_sensitive {
+                    member.name.clone()
+                } else {
+                    member.name.to_lowercase()
+                };
+                (position(member, &order), name)
+            };
+            let mut sorted: Vec<usize> = (0..members.len()).collect();
+            // Stable, so elements of the same type keep their relative order
+            sorted.sort_by_cached_key(|&i| sort_key(&members[i]));
+
+            if sorted.iter().enumerate().all(|(i, &j)| i == j) {
+                continue;
+            }
 
-                // Find the actual content range (excluding opening/closing braces whitespace)
-                if let (Some(first), Some(last)) = (elements.first(), elements.last()) {
-                    edits.push(edit_with_rule(
-                        first.start,
-                        last.end,
-                        sorted_content,
-                        "Reorder class elements".to_string(),
-                        "ordered_class_elements",
-                    ));
+            // Keep the whitespace between elements where it was
+            let mut content = String::new();
+            for (slot, &idx) in sorted.iter().enumerate() {
+                content.push_str(&source[members[idx].start..members[idx].end]);
+                if let Some(next) = members.get(slot + 1) {
+                    content.push_str(&source[members[slot].end..next.start]);
                 }
             }
+
+            edits.push(edit_with_rule(
+                first.start,
+                last.end,
+                content,
+                "Reorder class elements".to_string(),
+                "ordered_class_elements",
+            ));
         }
 
         edits
     }
 }
 
-fn find_matching_brace(source: &str, start: usize) -> Option<usize> {
-    let bytes = source.as_bytes();
-    let mut depth = 0;
-    let mut i = start;
+/// The element types a member matches, from the most to the least specific
+fn element_types(member: &ClassMember) -> Vec<String> {
+    let visibility = member.visibility.as_str();
+    let mut types = Vec::new();
 
-    while i < bytes.len() {
-        match bytes[i] {
-            b'{' => depth += 1,
-            b'}' => {
-                depth -= 1;
-                if depth == 0 {
-                    return Some(i);
-                }
+    let base = match member.kind {
+        MemberKind::UseTrait => return vec!["use_trait".to_string()],
+        MemberKind::Case => return vec!["case".to_string()],
+        MemberKind::Constant => {
+            return vec![format!("constant_{}", visibility), "constant".to_string(), visibility.to_string()];
+        }
+        MemberKind::Property => "property",
+        MemberKind::Method => {
+            let name = member.name.to_lowercase();
+            if name == "__construct" {
+                types.push("construct".to_string());
+            } else if name == "__destruct" {
+                types.push("destruct".to_string());
+            } else if name.starts_with("__") {
+                types.push("magic".to_string());
+            } else if PHPUNIT_METHODS.contains(&name.as_str()) {
+                types.push("phpunit".to_string());
             }
-            _ => {}
+            "method"
         }
-        i += 1;
-    }
-
-    None
-}
-
-fn parse_class_elements(body: &str, offset: usize) -> Vec<ClassElement> {
-    let mut elements = Vec::new();
+    };
 
-    // Match trait use statements
-    let trait_re = Regex::new(r"(?m)^[ \t]*use\s+[\w\\,\s]+;").unwrap();
-    for mat in trait_re.find_iter(body) {
-        elements.push(ClassElement {
-            element_type: ElementType::UseTrait,
-            content: mat.as_str().to_string(),
-            start: offset + mat.start(),
-            end: offset + mat.end(),
-        });
+    let mut flags = Vec::new();
+    if member.is_abstract {
+        flags.push("abstract");
     }
-
-    // Match constants
-    let const_re = Regex::new(r"(?m)^[ \t]*(?:public|protected|private|final|\s)*const\s+\w+\s*=\s*[^;]+;").unwrap();
-    for mat in const_re.find_iter(body) {
-        elements.push(ClassElement {
-            element_type: ElementType::Constant,
-            content: mat.as_str().to_string(),
-            start: offset + mat.start(),
-            end: offset + mat.end(),
-        });
+    if member.is_static {
+        flags.push("static");
     }
-
-    // Match properties
-    let prop_re = Regex::new(r"(?m)^[ \t]*(public|protected|private)(?:\s+(?:static|readonly))?\s+(?:\??\w+\s+)?\$\w+(?:\s*=\s*[^;]+)?;").unwrap();
-    for cap in prop_re.captures_iter(body) {
-        let mat = cap.get(0).unwrap();
-        let visibility = cap.get(1).unwrap().as_str();
-        let element_type = match visibility {
-            "public" => ElementType::PublicProperty,
-            "protected" => ElementType::ProtectedProperty,
-            "private" => ElementType::PrivateProperty,
-            _ => ElementType::PublicProperty,
-        };
-        elements.push(ClassElement {
-            element_type,
-            content: mat.as_str().to_string(),
-            start: offset + mat.start(),
-            end: offset + mat.end(),
-        });
+    if member.is_readonly {
+        flags.push("readonly");
     }
 
-    // Match methods
-    let method_re = Regex::new(r"(?ms)^[ \t]*(public|protected|private)?(?:\s+(?:static|final|abstract))?\s*function\s+(\w+)\s*\([^)]*\)(?:\s*:\s*\??\w+)?\s*\{[^{}]*(?:\{[^{}]*\}[^{}]*)*\}").unwrap();
-    for cap in method_re.captures_iter(body) {
-        let mat = cap.get(0).unwrap();
-        let visibility = cap.get(1).map(|m| m.as_str()).unwrap_or("public");
-        let method_name = cap.get(2).unwrap().as_str();
-
-        let element_type = if method_name == "__construct" {
-            ElementType::Constructor
-        } else if method_name == "__destruct" {
-            ElementType::Destructor
-        } else {
-            match visibility {
-                "public" => ElementType::PublicMethod,
-                "protected" => ElementType::ProtectedMethod,
-                "private" => ElementType::PrivateMethod,
-                _ => ElementType::PublicMethod,
-            }
-        };
-
-        elements.push(ClassElement {
-            element_type,
-            content: mat.as_str().to_string(),
-            start: offset + mat.start(),
-            end: offset + mat.end(),
-        });
+    if flags.len() > 1 {
+        types.push(format!("{}_{}_{}", base, visibility, flags.join("_")));
     }
+    for flag in &flags {
+        types.push(format!("{}_{}_{}", base, visibility, flag));
+    }
+    types.push(format!("{}_{}", base, visibility));
+    for flag in &flags {
+        types.push(format!("{}_{}", base, flag));
+    }
+    types.push(base.to_string());
+    types.push(visibility.to_string());
+    types
+}
 
-    // Sort by position first to maintain relative order within same type
-    elements.sort_by_key(|e| e.start);
-
-    elements
+/// Position of a member in the configured order (unlisted types go last)
+fn position(member: &ClassMember, order: &[&str]) -> usize {
+    element_types(member)
+        .iter()
+        .find_map(|ty| order.iter().position(|o| o == ty))
+        .unwrap_or(order.len())
 }
 
 #[cfg(test)]
@@ -216,50 +256,52 @@ mod tests {
     use super::*;
     use crate::config::LineEnding;
 
-    fn check(source: &str) -> Vec<Edit> {
-        OrderedClassElementsFixer.check(source, &FixerConfig {
+    fn fix(source: &str, options: &[(&str, ConfigValue)]) -> String {
+        let config = FixerConfig {
             line_ending: LineEnding::Lf,
+            options: options.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
             ..Default::default()
-        })
+        };
+        let edits = OrderedClassElementsFixer.check(source, &config);
+        rustor_core::apply_edits(source, &edits).unwrap()
     }
 
     #[test]
     fn test_already_ordered() {
         let source = "<?php\nclass A {\n    use TraitA;\n    const A = 1;\n    public $a;\n    public function foo() {}\n}\n";
-        let edits = check(source);
-        // May or may not have edits depending on exact parsing
-        // This is a complex fixer, so we just verify it doesn't crash
-        let _ = edits;
+        assert_eq!(fix(source, &[]), source);
     }
 
     #[test]
-    fn test_parse_elements() {
-        let body = "\n    use TraitA;\n    const A = 1;\n    public $a;\n    public function foo() {}\n";
-        let elements = parse_class_elements(body, 0);
-
-        // Should find trait, const, property, and method
-        assert!(elements.iter().any(|e| e.element_type == ElementType::UseTrait));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::Constant));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::PublicProperty));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::PublicMethod));
+    fn test_default_order() {
+        let source = "<?php\nclass A {\n    private function helper() { if (true) { return 1; } }\n\n    /** The name */\n    protected $name;\n    public function __construct() {}\n    const A = 1;\n    use TraitA;\n}\n";
+        assert_eq!(
+            fix(source, &[]),
+            "<?php\nclass A {\n    use TraitA;\n\n    const A = 1;\n    /** The name */\n    protected $name;\n    public function __construct() {}\n    private function helper() { if (true) { return 1; } }\n}\n"
+        );
     }
 
     #[test]
-    fn test_visibility_detection() {
-        let body = "\n    public $a;\n    protected $b;\n    private $c;\n";
-        let elements = parse_class_elements(body, 0);
-
-        assert!(elements.iter().any(|e| e.element_type == ElementType::PublicProperty));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::ProtectedProperty));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::PrivateProperty));
+    fn test_element_types() {
+        let source = "<?php\nclass A {\n    public static function make() {}\n    protected readonly int $id;\n    public function __toString() {}\n    protected function setUp(): void {}\n}\n";
+        let body = &class_bodies(source, &TokenStream::new(source))[0];
+        assert_eq!(element_types(&body.members[0])[0], "method_public_static");
+        assert!(element_types(&body.members[1]).contains(&"property_protected_readonly".to_string()));
+        assert_eq!(element_types(&body.members[2])[0], "magic");
+        assert_eq!(element_types(&body.members[3])[0], "phpunit");
     }
 
     #[test]
-    fn test_constructor_detection() {
-        let body = "\n    public function __construct() {}\n    public function foo() {}\n";
-        let elements = parse_class_elements(body, 0);
-
-        assert!(elements.iter().any(|e| e.element_type == ElementType::Constructor));
-        assert!(elements.iter().any(|e| e.element_type == ElementType::PublicMethod));
+    fn test_custom_order_and_alpha() {
+        let source = "<?php\nclass A {\n    public function b() {}\n    public static function c() {}\n    public function A() {}\n    private $x;\n}\n";
+        let order = ConfigValue::Array(vec!["property".to_string(), "method_static".to_string(), "method".to_string()]);
+        assert_eq!(
+            fix(source, &[("order", order.clone())]),
+            "<?php\nclass A {\n    private $x;\n    public static function c() {}\n    public function b() {}\n    public function A() {}\n}\n"
+        );
+        assert_eq!(
+            fix(source, &[("order", order), ("sort_algorithm", ConfigValue::String("alpha".to_string()))]),
+            "<?php\nclass A {\n    private $x;\n    public static function c() {}\n    public function A() {}\n    public function b() {}\n}\n"
+        );
     }
 }
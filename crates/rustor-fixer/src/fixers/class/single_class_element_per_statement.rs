@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Splits multiple property declarations into separate statements
 pub struct SingleClassElementPerStatementFixer;
@@ -25,6 +26,10 @@ impl Fixer for SingleClassElementPerStatementFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         let line_ending = config.line_ending.as_str();
 
@@ -43,7 +48,7 @@ impl Fixer for SingleClassElementPerStatementFixer {
             let type_hint = cap.get(3).map(|m| m.as_str()).unwrap_or("");
             let vars = cap.get(4).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -91,7 +96,7 @@ impl Fixer for SingleClassElementPerStatementFixer {
             let const_prefix = cap.get(2).unwrap().as_str();
             let consts = cap.get(3).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -147,24 +152,6 @@ fn split_const_declarations(s: &str) -> Vec<&str> {
     result
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
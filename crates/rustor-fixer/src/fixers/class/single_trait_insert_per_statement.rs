@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Splits multiple trait imports into separate statements
 pub struct SingleTraitInsertPerStatementFixer;
@@ -25,6 +26,10 @@ impl Fixer for SingleTraitInsertPerStatementFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         let line_ending = config.line_ending.as_str();
 
@@ -41,7 +46,7 @@ impl Fixer for SingleTraitInsertPerStatementFixer {
             let use_prefix = cap.get(2).unwrap().as_str();
             let traits = cap.get(3).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -88,24 +93,6 @@ impl Fixer for SingleTraitInsertPerStatementFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
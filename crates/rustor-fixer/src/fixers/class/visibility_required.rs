@@ -2,7 +2,10 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+
+/// PHP-CS-Fixer's `elements` values
+const ELEMENTS: &[&str] = &["const", "method", "property"];
 
 /// Ensures visibility modifiers are declared on methods and properties
 pub struct VisibilityRequiredFixer;
@@ -24,138 +27,155 @@ impl Fixer for VisibilityRequiredFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-
-        // Check for function declarations without visibility in class context
-        // This is a simplified check - real implementation would need AST
-        let func_re = Regex::new(r"(?m)^([ \t]*)(static\s+)?function\s+(\w+)\s*\(").unwrap();
-
-        for cap in func_re.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let indent = cap.get(1).unwrap().as_str();
-            let is_static = cap.get(2).is_some();
-            let func_name = cap.get(3).unwrap().as_str();
-
-            // Skip if not in a class context (check for class keyword before)
-            if !is_in_class_context(&source[..full_match.start()]) {
-                continue;
-            }
-
-            // Skip if already has visibility
-            let before_line = get_line_before(source, full_match.start());
-            if has_visibility(before_line) || before_line.contains("abstract") {
-                continue;
-            }
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "elements",
+            description: "Elements that must declare their visibility",
+            option_type: OptionType::EnumArray(ELEMENTS.to_vec()),
+            default: Some(ConfigValue::Array(ELEMENTS.iter().map(|s| s.to_string()).collect())),
+        }]
+    }
 
-            // Skip constructors __construct and magic methods that might be special
-            if func_name.starts_with("__") {
-                // Still need visibility, but skip for now as detection is complex
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        let mut edits = Vec::new();
+        let enabled = |element: &str| config.get_array("elements").is_none_or(|elements| elements.iter().any(|e| e == element));
+
+        if enabled("method") {
+            // Check for function declarations without visibility in class context
+            // This is a simplified check - real implementation would need AST
+            let func_re = Regex::new(r"(?m)^([ \t]*)(static\s+)?function\s+(\w+)\s*\(").unwrap();
+
+            for cap in func_re.captures_iter(source) {
+                let full_match = cap.get(0).unwrap();
+                let indent = cap.get(1).unwrap().as_str();
+                let is_static = cap.get(2).is_some();
+                let func_name = cap.get(3).unwrap().as_str();
+
+                // Skip if not in a class context (check for class keyword before)
+                if !is_in_class_context(&source[..full_match.start()]) {
+                    continue;
+                }
+
+                // Skip if already has visibility
+                let before_line = get_line_before(source, full_match.start());
+                if has_visibility(before_line) || before_line.contains("abstract") {
+                    continue;
+                }
+
+                // Skip constructors __construct and magic methods that might be special
+                if func_name.starts_with("__") {
+                    // Still need visibility, but skip for now as detection is complex
+                }
+
+                let replacement = if is_static {
+                    format!("{}public static function {}(", indent, func_name)
+                } else {
+                    format!("{}public function {}(", indent, func_name)
+                };
+
+                edits.push(edit_with_rule(
+                    full_match.start(),
+                    full_match.end(),
+                    replacement,
+                    format!("Add visibility modifier to method '{}'", func_name),
+                    "visibility_required",
+                ));
             }
-
-            let replacement = if is_static {
-                format!("{}public static function {}(", indent, func_name)
-            } else {
-                format!("{}public function {}(", indent, func_name)
-            };
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                replacement,
-                format!("Add visibility modifier to method '{}'", func_name),
-                "visibility_required",
-            ));
         }
 
-        // Check for property declarations without visibility
-        // Match: var $prop or just $prop at class level
-        let var_re = Regex::new(r"(?m)^([ \t]*)var\s+\$(\w+)").unwrap();
-
-        for cap in var_re.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let indent = cap.get(1).unwrap().as_str();
-            let prop_name = cap.get(2).unwrap().as_str();
-
-            if !is_in_class_context(&source[..full_match.start()]) {
-                continue;
+        if enabled("property") {
+            // Check for property declarations without visibility
+            // Match: var $prop or just $prop at class level
+            let var_re = Regex::new(r"(?m)^([ \t]*)var\s+\$(\w+)").unwrap();
+
+            for cap in var_re.captures_iter(source) {
+                let full_match = cap.get(0).unwrap();
+                let indent = cap.get(1).unwrap().as_str();
+                let prop_name = cap.get(2).unwrap().as_str();
+
+                if !is_in_class_context(&source[..full_match.start()]) {
+                    continue;
+                }
+
+                edits.push(edit_with_rule(
+                    full_match.start(),
+                    full_match.end(),
+                    format!("{}public ${}", indent, prop_name),
+                    format!("Replace 'var' with 'public' for property '{}'", prop_name),
+                    "visibility_required",
+                ));
             }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{}public ${}", indent, prop_name),
-                format!("Replace 'var' with 'public' for property '{}'", prop_name),
-                "visibility_required",
-            ));
         }
 
-        // Check for static properties without visibility
-        // Match: static $prop (without visibility modifier before)
-        let static_prop_re = Regex::new(r"(?m)^([ \t]*)(static\s+)\$(\w+)").unwrap();
-
-        for cap in static_prop_re.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let indent = cap.get(1).unwrap().as_str();
-            let static_keyword = cap.get(2).unwrap().as_str();
-            let prop_name = cap.get(3).unwrap().as_str();
-
-            if !is_in_class_context(&source[..full_match.start()]) {
-                continue;
-            }
-
-            // Skip if already has visibility
-            let before_line = get_line_before(source, full_match.start());
-            if has_visibility(before_line) {
-                continue;
+        if enabled("property") {
+            // Check for static properties without visibility
+            // Match: static $prop (without visibility modifier before)
+            let static_prop_re = Regex::new(r"(?m)^([ \t]*)(static\s+)\$(\w+)").unwrap();
+
+            for cap in static_prop_re.captures_iter(source) {
+                let full_match = cap.get(0).unwrap();
+                let indent = cap.get(1).unwrap().as_str();
+                let static_keyword = cap.get(2).unwrap().as_str();
+                let prop_name = cap.get(3).unwrap().as_str();
+
+                if !is_in_class_context(&source[..full_match.start()]) {
+                    continue;
+                }
+
+                // Skip if already has visibility
+                let before_line = get_line_before(source, full_match.start());
+                if has_visibility(before_line) {
+                    continue;
+                }
+
+                edits.push(edit_with_rule(
+                    full_match.start(),
+                    full_match.end(),
+                    format!("{}public {}${}", indent, static_keyword, prop_name),
+                    format!("Add visibility modifier to static property '{}'", prop_name),
+                    "visibility_required",
+                ));
             }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{}public {}${}", indent, static_keyword, prop_name),
-                format!("Add visibility modifier to static property '{}'", prop_name),
-                "visibility_required",
-            ));
         }
 
-        // Check for const declarations without visibility (PHP 7.1+)
-        // Match: const FOO = value; (without visibility modifier)
-        let const_re = Regex::new(r"(?m)^([ \t]*)(const\s+)(\w+\s*=)").unwrap();
-
-        for cap in const_re.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let indent = cap.get(1).unwrap().as_str();
-            let const_keyword = cap.get(2).unwrap().as_str();
-            let const_name_eq = cap.get(3).unwrap().as_str();
-
-            if !is_in_class_context(&source[..full_match.start()]) {
-                continue;
+        if enabled("const") {
+            // Check for const declarations without visibility (PHP 7.1+)
+            // Match: const FOO = value; (without visibility modifier)
+            let const_re = Regex::new(r"(?m)^([ \t]*)(const\s+)(\w+\s*=)").unwrap();
+
+            for cap in const_re.captures_iter(source) {
+                let full_match = cap.get(0).unwrap();
+                let indent = cap.get(1).unwrap().as_str();
+                let const_keyword = cap.get(2).unwrap().as_str();
+                let const_name_eq = cap.get(3).unwrap().as_str();
+
+                if !is_in_class_context(&source[..full_match.start()]) {
+                    continue;
+                }
+
+                // Skip if already has visibility
+                let before_line = get_line_before(source, full_match.start());
+                if has_visibility(before_line) {
+                    continue;
+                }
+
+                // Check if 'const' appears right at indent start or if there's something before it
+                let line_content = &source[full_match.start()..full_match.end()];
+                let trimmed = line_content.trim_start();
+                if !trimmed.starts_with("const ") {
+                    // There's something before const (like visibility), skip
+                    continue;
+                }
+
+                edits.push(edit_with_rule(
+                    full_match.start(),
+                    full_match.end(),
+                    format!("{}public {}{}", indent, const_keyword, const_name_eq),
+                    "Add visibility modifier to class constant".to_string(),
+                    "visibility_required",
+                ));
             }
-
-            // Skip if already has visibility
-            let before_line = get_line_before(source, full_match.start());
-            if has_visibility(before_line) {
-                continue;
-            }
-
-            // Check if 'const' appears right at indent start or if there's something before it
-            let line_content = &source[full_match.start()..full_match.end()];
-            let trimmed = line_content.trim_start();
-            if !trimmed.starts_with("const ") {
-                // There's something before const (like visibility), skip
-                continue;
-            }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{}public {}{}", indent, const_keyword, const_name_eq),
-                "Add visibility modifier to class constant".to_string(),
-                "visibility_required",
-            ));
         }
-
         edits
     }
 }
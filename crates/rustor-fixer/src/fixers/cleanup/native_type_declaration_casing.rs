@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NativeTypeDeclarationCasingFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for NativeTypeDeclarationCasingFixer {
     fn description(&self) -> &'static str { "Lowercase native types" }
     fn priority(&self) -> i32 { 40 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match type context: after ( or : or | or ? with optional space before type
@@ -36,7 +41,7 @@ impl Fixer for NativeTypeDeclarationCasingFixer {
                 let full = cap.get(0).unwrap();
                 let prefix = cap.get(1).unwrap().as_str();
 
-                if is_in_string(&source[..full.start()]) { continue; }
+                if tokens.is_in_string_or_comment(full.start()) { continue; }
 
                 edits.push(edit_with_rule(
                     full.start(), full.end(),
@@ -51,34 +56,24 @@ impl Fixer for NativeTypeDeclarationCasingFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     #[test]
     fn test_uppercase_int() {
-        let edits = NativeTypeDeclarationCasingFixer.check("function f(Int )", &FixerConfig::default());
+        let edits = NativeTypeDeclarationCasingFixer.check("<?php\nfunction f(Int )", &FixerConfig::default());
         assert!(!edits.is_empty());
     }
 
     #[test]
     fn test_lowercase_int() {
-        let edits = NativeTypeDeclarationCasingFixer.check("function f(int )", &FixerConfig::default());
+        let edits = NativeTypeDeclarationCasingFixer.check("<?php\nfunction f(int )", &FixerConfig::default());
         assert!(edits.is_empty());
     }
 
     #[test]
     fn test_return_type() {
-        let edits = NativeTypeDeclarationCasingFixer.check("function f(): Int {}", &FixerConfig::default());
+        let edits = NativeTypeDeclarationCasingFixer.check("<?php\nfunction f(): Int {}", &FixerConfig::default());
         assert!(!edits.is_empty());
     }
 }
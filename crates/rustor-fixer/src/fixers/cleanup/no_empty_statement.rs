@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoEmptyStatementFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for NoEmptyStatementFixer {
     fn description(&self) -> &'static str { "Remove empty statements" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         // Match double semicolons or semicolon after opening brace
         let patterns = [
@@ -23,7 +28,7 @@ impl Fixer for NoEmptyStatementFixer {
         for (pattern, replacement) in patterns {
             let re = Regex::new(pattern).unwrap();
             for m in re.find_iter(source) {
-                if is_in_string(&source[..m.start()]) { continue; }
+                if tokens.is_in_string_or_comment(m.start()) { continue; }
                 edits.push(edit_with_rule(
                     m.start(), m.end(), replacement.to_string(),
                     "Remove empty statement".to_string(), "no_empty_statement",
@@ -34,16 +39,6 @@ impl Fixer for NoEmptyStatementFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoUnsetCastFixer;
 
@@ -11,15 +12,18 @@ impl Fixer for NoUnsetCastFixer {
     fn php_cs_fixer_name(&self) -> &'static str { "no_unset_cast" }
     fn description(&self) -> &'static str { "Replace (unset) cast with null" }
     fn priority(&self) -> i32 { 20 }
-    fn is_risky(&self) -> bool { true }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         // Match (unset)$var pattern - deprecated in PHP 7.2, removed in 8.0
         let re = Regex::new(r"(?i)\(\s*unset\s*\)\s*\$\w+").unwrap();
 
         for m in re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) { continue; }
+            if tokens.is_in_string_or_comment(m.start()) { continue; }
 
             edits.push(edit_with_rule(
                 m.start(), m.end(), "null".to_string(),
@@ -31,16 +35,6 @@ impl Fixer for NoUnsetCastFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoUselessNullsafeOperatorFixer;
 
@@ -11,9 +12,12 @@ impl Fixer for NoUselessNullsafeOperatorFixer {
     fn php_cs_fixer_name(&self) -> &'static str { "no_useless_nullsafe_operator" }
     fn description(&self) -> &'static str { "Remove nullsafe when value cannot be null" }
     fn priority(&self) -> i32 { 20 }
-    fn is_risky(&self) -> bool { true }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match new Foo()?->method() - new cannot return null
@@ -22,7 +26,7 @@ impl Fixer for NoUselessNullsafeOperatorFixer {
             let full = cap.get(0).unwrap();
             let new_expr = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full.start()]) { continue; }
+            if tokens.is_in_string_or_comment(full.start()) { continue; }
 
             edits.push(edit_with_rule(
                 full.start(), full.end(),
@@ -38,7 +42,7 @@ impl Fixer for NoUselessNullsafeOperatorFixer {
             let full = cap.get(0).unwrap();
             let clone_expr = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full.start()]) { continue; }
+            if tokens.is_in_string_or_comment(full.start()) { continue; }
 
             edits.push(edit_with_rule(
                 full.start(), full.end(),
@@ -52,16 +56,6 @@ impl Fixer for NoUselessNullsafeOperatorFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -11,7 +11,6 @@ impl Fixer for SwitchContinueToBreakFixer {
     fn php_cs_fixer_name(&self) -> &'static str { "switch_continue_to_break" }
     fn description(&self) -> &'static str { "Replace continue in switch with break" }
     fn priority(&self) -> i32 { 20 }
-    fn is_risky(&self) -> bool { true }
 
     fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
@@ -3,6 +3,8 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::{Region, TokenStream};
+use mago_syntax::token::TokenKind;
 
 /// Removes or ensures proper whitespace before semicolons
 pub struct MultilineWhitespaceBeforeSemicolonsFixer;
@@ -24,25 +26,24 @@ impl Fixer for MultilineWhitespaceBeforeSemicolonsFixer {
         50
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match whitespace (including newlines) before semicolons
-        // But skip cases in strings
         let re = Regex::new(r"[ \t]+;").unwrap();
 
         for mat in re.find_iter(source) {
-            let before = &source[..mat.start()];
-
-            // Skip if in string
-            if is_in_string(before) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(mat.start()) {
                 continue;
             }
 
             // Skip if in for loop header (for ($i = 0; $i < 10; $i++))
-            // Check for unbalanced parentheses
-            let paren_depth = count_parens(before);
-            if paren_depth > 0 {
+            if paren_depth(tokens, mat.start()) > 0 {
                 continue;
             }
 
@@ -59,50 +60,19 @@ impl Fixer for MultilineWhitespaceBeforeSemicolonsFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn count_parens(s: &str) -> i32 {
-    let mut depth: i32 = 0;
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in s.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-
-        if !in_single_quote && !in_double_quote {
-            match c {
-                '(' => depth += 1,
-                ')' => depth -= 1,
-                _ => {}
-            }
-        }
-
-        prev_char = c;
-    }
-
-    depth
+/// Number of parentheses in code still open at `offset`
+fn paren_depth(tokens: &TokenStream, offset: usize) -> i32 {
+    tokens
+        .tokens()
+        .iter()
+        .take_while(|t| t.start < offset)
+        .filter(|t| t.region == Region::Code)
+        .map(|t| match t.kind {
+            TokenKind::LeftParenthesis => 1,
+            TokenKind::RightParenthesis => -1,
+            _ => 0,
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -2,7 +2,9 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+use crate::tokens::TokenStream;
+use mago_syntax::token::TokenKind;
 
 /// Converts # comments to // comments
 pub struct SingleLineCommentStyleFixer;
@@ -24,8 +26,24 @@ impl Fixer for SingleLineCommentStyleFixer {
         60
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "comment_types",
+            description: "Comment types to convert; only hash is supported",
+            option_type: OptionType::EnumArray(vec!["hash"]),
+            default: Some(ConfigValue::Array(vec!["hash".to_string()])),
+        }]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
+        if config.get_array("comment_types").is_some_and(|types| !types.iter().any(|t| t == "hash")) {
+            return edits;
+        }
 
         // Match # comments at start of line (with optional leading whitespace)
         let re = Regex::new(r"(?m)^([ \t]*)#").unwrap();
@@ -34,14 +52,9 @@ impl Fixer for SingleLineCommentStyleFixer {
             let full_match = cap.get(0).unwrap();
             let indent = cap.get(1).unwrap().as_str();
 
-            // Skip #[ which is attribute syntax in PHP 8
-            let after_match = &source[full_match.end()..];
-            if after_match.starts_with('[') {
-                continue;
-            }
-
-            // Check if we're in a string by looking at context
-            if is_in_string_or_heredoc(&source[..full_match.start()]) {
+            // Only # comments, not #[ attributes or # in strings
+            let hash = full_match.end() - 1;
+            if !tokens.token_at(hash).is_some_and(|t| t.kind == TokenKind::HashComment && t.start == hash) {
                 continue;
             }
 
@@ -58,34 +71,6 @@ impl Fixer for SingleLineCommentStyleFixer {
     }
 }
 
-fn is_in_string_or_heredoc(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_heredoc = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_heredoc {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        // Very basic heredoc detection - look for <<<
-        if c == '<' && prev_char == '<' && !in_single_quote && !in_double_quote {
-            // This is a simplification - real heredoc detection is complex
-            in_heredoc = true;
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_heredoc
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,4 +126,13 @@ mod tests {
         let edits = check(source);
         assert!(edits.is_empty());
     }
+
+    #[test]
+    fn test_after_heredoc() {
+        let source = "<?php\n$a = <<<EOT\n# not a comment\nEOT;\n# comment\n";
+        let edits = check(source);
+
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].span.start.offset as usize, source.rfind('#').unwrap());
+    }
 }
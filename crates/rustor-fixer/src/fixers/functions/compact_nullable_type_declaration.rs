@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures no whitespace after `?` in nullable type declarations
 pub struct CompactNullableTypeDeclarationFixer;
@@ -26,7 +27,11 @@ impl Fixer for CompactNullableTypeDeclarationFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match `? int`, `? string`, `? array`, `? ClassName`, etc.
@@ -38,13 +43,8 @@ impl Fixer for CompactNullableTypeDeclarationFixer {
             let question = cap.get(1).unwrap();
             let type_name = cap.get(2).unwrap().as_str();
 
-            // Skip if in string
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            // Skip if in comment
-            if is_in_comment(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -79,39 +79,6 @@ impl Fixer for CompactNullableTypeDeclarationFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn is_in_comment(before: &str) -> bool {
-    if let Some(last_line_start) = before.rfind('\n') {
-        let last_line = &before[last_line_start..];
-        if last_line.contains("//") || last_line.contains('#') {
-            return true;
-        }
-    } else if before.contains("//") {
-        return true;
-    }
-
-    let open_count = before.matches("/*").count();
-    let close_count = before.matches("*/").count();
-    open_count > close_count
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
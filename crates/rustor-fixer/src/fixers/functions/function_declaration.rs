@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures proper spacing in function declarations
 pub struct FunctionDeclarationFixer;
@@ -24,7 +25,11 @@ impl Fixer for FunctionDeclarationFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Fix space between function name and opening parenthesis
@@ -35,7 +40,7 @@ impl Fixer for FunctionDeclarationFixer {
             let full_match = cap.get(0).unwrap();
             let func_name = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -56,7 +61,7 @@ impl Fixer for FunctionDeclarationFixer {
             let full_match = cap.get(0).unwrap();
             let func_name = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -84,7 +89,7 @@ impl Fixer for FunctionDeclarationFixer {
         for cap in closure_space.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -105,7 +110,7 @@ impl Fixer for FunctionDeclarationFixer {
             let full_match = cap.get(0).unwrap();
             let func_name = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -122,24 +127,6 @@ impl Fixer for FunctionDeclarationFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,10 +1,16 @@
 //! Fix spacing in method/function arguments
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use mago_syntax::token::TokenKind;
+use crate::fixers::{Fixer, FixerConfig, ConfigValue, FixerOption, OptionType, edit_with_rule};
+use crate::fixers::layout::{tokens_in, Layout, List, ListKind, Syntax};
+use crate::tokens::{Region, Token, TokenStream};
 
 /// Ensures proper spacing around function/method arguments
+///
+/// Applies to call arguments and to parameter lists. There is no space before
+/// a comma and one space after it. An argument list that spans several lines
+/// is handled according to `on_multiline`.
 pub struct MethodArgumentSpaceFixer;
 
 impl Fixer for MethodArgumentSpaceFixer {
@@ -24,187 +30,189 @@ impl Fixer for MethodArgumentSpaceFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
+    fn options(&self) -> Vec<FixerOption> {
+        vec![
+            FixerOption {
+                name: "on_multiline",
+                description: "Argument lists spanning lines: 'ensure_fully_multiline' (one argument per line), 'ensure_single_line' or 'ignore'",
+                option_type: OptionType::Enum(vec!["ensure_fully_multiline", "ensure_single_line", "ignore"]),
+                default: Some(ConfigValue::String("ensure_fully_multiline".to_string())),
+            },
+            FixerOption {
+                name: "keep_multiple_spaces_after_comma",
+                description: "Whether to keep multiple spaces after a comma",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(false)),
+            },
+            FixerOption {
+                name: "after_heredoc",
+                description: "Whether to remove the whitespace between a heredoc end and a comma",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(false)),
+            },
+        ]
+    }
 
-        // Fix space after opening parenthesis in function calls/definitions
-        // foo( $a) -> foo($a)
-        let space_after_open = Regex::new(r"(\w+)\([ \t]+([^\s\)])").unwrap();
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
 
-        for cap in space_after_open.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let func = cap.get(1).unwrap().as_str();
-            let first_char = cap.get(2).unwrap().as_str();
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let Some(syntax) = Syntax::parse(source) else {
+            return Vec::new();
+        };
 
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{}({}", func, first_char),
-                "Remove space after opening parenthesis".to_string(),
-                "method_argument_space",
-            ));
-        }
+        let on_multiline = config.get_string("on_multiline").unwrap_or("ensure_fully_multiline");
+        let keep_multiple_spaces = config.get_bool("keep_multiple_spaces_after_comma").unwrap_or(false);
+        let after_heredoc = config.get_bool("after_heredoc").unwrap_or(false);
 
-        // Fix space before closing parenthesis
-        // foo($a ) -> foo($a)
-        let space_before_close = Regex::new(r"([^\s\(])[ \t]+\)").unwrap();
+        let all = tokens.tokens();
+        let index = |offset: usize| all.partition_point(|t| t.start < offset);
+        let whitespace = |i: usize| all.get(i).filter(|t| t.kind == TokenKind::Whitespace);
 
-        for cap in space_before_close.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let last_char = cap.get(1).unwrap().as_str();
-
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
+        let mut edits = Vec::new();
+        let mut layout = Layout::new(source, tokens, config);
+
+        let lists = syntax.lists.iter().filter(|l| l.kind != ListKind::Array && !l.items.is_empty());
+        for list in lists {
+            let shape = Shape::of(source, tokens_in(tokens, list.open + 1..list.close));
+            let reflow = shape.multiline && !shape.comments && on_multiline == "ensure_fully_multiline";
+            let single_line = shape.multiline
+                && !shape.comments
+                && !shape.heredoc
+                && !shape.inner_newlines
+                && on_multiline == "ensure_single_line";
+
+            // No whitespace before a comma, unless it follows a comment or heredoc
+            for &comma in &list.commas {
+                let i = index(comma);
+                let (Some(space), Some(prev)) = (i.checked_sub(1).and_then(whitespace), i.checked_sub(2).and_then(|p| all.get(p))) else {
+                    continue;
+                };
+                if prev.region == Region::Comment || (prev.kind == TokenKind::DocumentEnd && !after_heredoc) {
+                    continue;
+                }
+                if space.text(source).contains('\n') {
+                    layout.join(comma, "", "Remove line break before comma");
+                } else {
+                    edits.push(edit_with_rule(space.start, space.end, String::new(), "Remove space before comma".to_string(), "method_argument_space"));
+                }
             }
 
-            // Skip array syntax [ ]
-            if last_char == "]" || last_char == "[" {
-                continue;
+            // One space after a comma
+            for (n, &comma) in list.commas.iter().enumerate() {
+                let Some(next) = list.items.get(n + 1) else {
+                    continue;
+                };
+                if reflow {
+                    own_line(&mut layout, list, next.start, 1);
+                    continue;
+                }
+                match whitespace(index(comma) + 1) {
+                    Some(space) if space.text(source).contains('\n') || keep_multiple_spaces => {}
+                    Some(space) if space.text(source) != " " => edits.push(edit_with_rule(
+                        space.start,
+                        space.end,
+                        " ".to_string(),
+                        "Use single space after comma".to_string(),
+                        "method_argument_space",
+                    )),
+                    Some(_) => {}
+                    None => edits.push(edit_with_rule(
+                        comma + 1,
+                        comma + 1,
+                        " ".to_string(),
+                        "Add space after comma".to_string(),
+                        "method_argument_space",
+                    )),
+                }
             }
 
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{})", last_char),
-                "Remove space before closing parenthesis".to_string(),
-                "method_argument_space",
-            ));
-        }
-
-        // Fix missing space after comma
-        // foo($a,$b) -> foo($a, $b)
-        // Note: Only applies to function/method calls, NOT arrays
-        let no_space_after_comma = Regex::new(r",([^\s\n])").unwrap();
-
-        for cap in no_space_after_comma.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let next_char = cap.get(1).unwrap().as_str();
-
-            if is_in_string(&source[..full_match.start()]) {
+            if reflow {
+                own_line(&mut layout, list, list.items[0].start, 1);
+                own_line(&mut layout, list, list.close, 0);
                 continue;
             }
 
-            // Skip if in array (we're only fixing function args)
-            // Check if we're inside square brackets by counting unmatched [ and ]
-            if is_in_array(&source[..full_match.start()]) {
-                continue;
+            if single_line {
+                for &end in &shape.line_breaks {
+                    let next = &all[index(end)];
+                    let after_open = source[list.open + 1..end].trim().is_empty();
+                    let with = if end == list.close || next.kind == TokenKind::Comma || after_open { "" } else { " " };
+                    layout.join(end, with, "Put arguments on a single line");
+                }
             }
 
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!(", {}", next_char),
-                "Add space after comma".to_string(),
-                "method_argument_space",
-            ));
-        }
-
-        // Fix multiple spaces after comma
-        // foo($a,  $b) -> foo($a, $b)
-        // Note: Only applies to function/method calls, NOT arrays
-        let multi_space_after_comma = Regex::new(r",[ \t]{2,}(\S)").unwrap();
-
-        for cap in multi_space_after_comma.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let next_char = cap.get(1).unwrap().as_str();
-
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
+            // No whitespace just inside the parentheses
+            if let Some(space) = whitespace(index(list.open) + 1).filter(|s| !s.text(source).contains('\n')) {
+                edits.push(edit_with_rule(space.start, space.end, String::new(), "Remove space after opening parenthesis".to_string(), "method_argument_space"));
             }
-
-            if is_in_array(&source[..full_match.start()]) {
-                continue;
+            if let Some(space) = index(list.close).checked_sub(1).and_then(whitespace).filter(|s| !s.text(source).contains('\n')) {
+                edits.push(edit_with_rule(space.start, space.end, String::new(), "Remove space before closing parenthesis".to_string(), "method_argument_space"));
             }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!(", {}", next_char),
-                "Single space after comma".to_string(),
-                "method_argument_space",
-            ));
-        }
-
-        // Fix space before comma
-        // foo($a , $b) -> foo($a, $b)
-        // Note: Only applies to function/method calls, NOT arrays
-        let space_before_comma = Regex::new(r"(\S)[ \t]+,").unwrap();
-
-        for cap in space_before_comma.captures_iter(source) {
-            let full_match = cap.get(0).unwrap();
-            let prev_char = cap.get(1).unwrap().as_str();
-
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            if is_in_array(&source[..full_match.start()]) {
-                continue;
-            }
-
-            edits.push(edit_with_rule(
-                full_match.start(),
-                full_match.end(),
-                format!("{},", prev_char),
-                "Remove space before comma".to_string(),
-                "method_argument_space",
-            ));
         }
 
+        edits.extend(layout.edits("method_argument_space", "Fix argument indentation"));
         edits
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
+/// Put `offset` at the start of a line, `levels` deeper than the line the list opens on
+fn own_line(layout: &mut Layout, list: &List, offset: usize, levels: usize) {
+    let message = "Put each argument on its own line";
+    if layout.is_line_start(offset) {
+        layout.indent(offset, list.open, levels, message);
+    } else {
+        layout.break_before(offset, list.open, levels, message);
     }
+}
 
-    in_single_quote || in_double_quote
+/// Line structure of an argument list, nested brackets aside
+#[derive(Default)]
+struct Shape {
+    /// Whether whitespace between the arguments breaks lines
+    multiline: bool,
+    /// Ends of that whitespace
+    line_breaks: Vec<usize>,
+    /// Whether arguments span lines themselves (closures, arrays, strings)
+    inner_newlines: bool,
+    /// Whether there are comments between the arguments
+    comments: bool,
+    /// Whether an argument is a heredoc or nowdoc
+    heredoc: bool,
 }
 
-/// Check if position is inside an array (between [ and ])
-fn is_in_array(before: &str) -> bool {
-    let mut bracket_depth = 0;
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        // Track string context
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
+impl Shape {
+    fn of(source: &str, tokens: &[Token]) -> Self {
+        let mut shape = Shape::default();
+        let mut depth = 0usize;
+
+        for token in tokens {
+            let text = token.text(source);
+            if token.region == Region::Code {
+                match token.kind {
+                    TokenKind::LeftParenthesis | TokenKind::LeftBracket | TokenKind::LeftBrace | TokenKind::HashLeftBracket => depth += 1,
+                    TokenKind::RightParenthesis | TokenKind::RightBracket | TokenKind::RightBrace => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
 
-        // Only count brackets outside of strings
-        if !in_single_quote && !in_double_quote {
-            if c == '[' {
-                bracket_depth += 1;
-            } else if c == ']' {
-                bracket_depth -= 1;
+            if depth == 0 && token.kind == TokenKind::Whitespace && text.contains('\n') {
+                shape.multiline = true;
+                shape.line_breaks.push(token.end);
+            } else if text.contains('\n') {
+                shape.inner_newlines = true;
+            }
+            if depth == 0 && token.region == Region::Comment {
+                shape.comments = true;
+            }
+            if depth == 0 && matches!(token.kind, TokenKind::DocumentStart(_)) {
+                shape.heredoc = true;
             }
         }
-        prev_char = c;
-    }
 
-    bracket_depth > 0
+        shape
+    }
 }
 
 #[cfg(test)]
@@ -215,6 +223,19 @@ mod tests {
         MethodArgumentSpaceFixer.check(source, &FixerConfig::default())
     }
 
+    fn fix(source: &str) -> String {
+        rustor_core::apply_edits(source, &check(source)).unwrap()
+    }
+
+    fn fix_with(source: &str, options: &[(&str, ConfigValue)]) -> String {
+        let mut config = FixerConfig::default();
+        for (name, value) in options {
+            config.options.insert(name.to_string(), value.clone());
+        }
+        let edits = MethodArgumentSpaceFixer.check(source, &config);
+        rustor_core::apply_edits(source, &edits).unwrap()
+    }
+
     #[test]
     fn test_correct_unchanged() {
         let source = "<?php\nfoo($a, $b, $c);\n";
@@ -228,7 +249,7 @@ mod tests {
         let edits = check(source);
 
         assert_eq!(edits.len(), 1);
-        assert!(edits[0].replacement.contains("foo($"));
+        assert_eq!(fix(source), "<?php\nfoo($a);\n");
     }
 
     #[test]
@@ -237,32 +258,32 @@ mod tests {
         let edits = check(source);
 
         assert_eq!(edits.len(), 1);
-        assert!(edits[0].replacement.ends_with(")"));
+        assert_eq!(fix(source), "<?php\nfoo($a);\n");
     }
 
     #[test]
     fn test_no_space_after_comma() {
         let source = "<?php\nfoo($a,$b);\n";
-        let edits = check(source);
-
-        assert!(edits.iter().any(|e| e.replacement.contains(", ")));
+        assert_eq!(fix(source), "<?php\nfoo($a, $b);\n");
     }
 
     #[test]
     fn test_multiple_spaces_after_comma() {
         let source = "<?php\nfoo($a,  $b);\n";
-        let edits = check(source);
+        assert_eq!(fix(source), "<?php\nfoo($a, $b);\n");
+    }
 
-        assert!(edits.iter().any(|e| e.replacement == ", $"));
+    #[test]
+    fn test_keep_multiple_spaces_after_comma() {
+        let source = "<?php\nfoo($a,  $b,$c);\n";
+        let fixed = fix_with(source, &[("keep_multiple_spaces_after_comma", ConfigValue::Bool(true))]);
+        assert_eq!(fixed, "<?php\nfoo($a,  $b, $c);\n");
     }
 
     #[test]
     fn test_space_before_comma() {
         let source = "<?php\nfoo($a , $b);\n";
-        let edits = check(source);
-
-        // Regex captures single char before space, so replacement is "a,"
-        assert!(edits.iter().any(|e| e.replacement == "a,"));
+        assert_eq!(fix(source), "<?php\nfoo($a, $b);\n");
     }
 
     #[test]
@@ -275,8 +296,7 @@ mod tests {
     #[test]
     fn test_function_definition() {
         let source = "<?php\nfunction foo( $a , $b ) {}\n";
-        let edits = check(source);
-        assert!(!edits.is_empty());
+        assert_eq!(fix(source), "<?php\nfunction foo($a, $b) {}\n");
     }
 
     #[test]
@@ -285,7 +305,98 @@ mod tests {
         // Array comma spacing is handled by whitespace_after_comma_in_array
         let source = "<?php\n$a = [1,2,3];\n";
         let edits = check(source);
-        // No edits for array commas
-        assert!(edits.iter().all(|e| !e.message.contains("comma")));
+        assert!(edits.is_empty());
+    }
+
+    #[test]
+    fn test_ensure_fully_multiline() {
+        let source = "<?php\nfoo($a,\n    $b, $c);\n";
+        assert_eq!(fix(source), "<?php\nfoo(\n    $a,\n    $b,\n    $c\n);\n");
+    }
+
+    #[test]
+    fn test_fully_multiline_unchanged() {
+        let source = "<?php\nfoo(\n    $a,\n    $b\n);\n";
+        assert!(check(source).is_empty());
+    }
+
+    #[test]
+    fn test_trailing_closure_is_not_multiline() {
+        let source = "<?php\nfoo($a, function () {\n    return 1;\n});\n";
+        assert!(check(source).is_empty());
+    }
+
+    #[test]
+    fn test_reflow_reindents_closure_body() {
+        let source = "<?php\nfoo($a, function () {\n    return 1;\n},\n    $b);\n";
+        assert_eq!(
+            fix(source),
+            "<?php\nfoo(\n    $a,\n    function () {\n        return 1;\n    },\n    $b\n);\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_closure_argument_indentation() {
+        let source = "<?php\nfunction f() {\n    $x = bar(\n    $a,\n    fn ($b) => baz(\n    $b\n    )\n    );\n}\n";
+        assert_eq!(
+            fix(source),
+            "<?php\nfunction f() {\n    $x = bar(\n        $a,\n        fn ($b) => baz(\n            $b\n        )\n    );\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_multiline_parameters() {
+        let source = "<?php\nfunction foo($a,\n    $b) {\n}\n";
+        assert_eq!(fix(source), "<?php\nfunction foo(\n    $a,\n    $b\n) {\n}\n");
+    }
+
+    #[test]
+    fn test_ensure_single_line() {
+        let source = "<?php\nfoo(\n    $a,\n    $b\n);\n";
+        let fixed = fix_with(source, &[("on_multiline", ConfigValue::String("ensure_single_line".to_string()))]);
+        assert_eq!(fixed, "<?php\nfoo($a, $b);\n");
+    }
+
+    #[test]
+    fn test_ensure_single_line_keeps_multiline_arguments() {
+        let source = "<?php\nfoo(\n    $a,\n    [\n        1,\n    ]\n);\n";
+        let fixed = fix_with(source, &[("on_multiline", ConfigValue::String("ensure_single_line".to_string()))]);
+        assert_eq!(fixed, source);
+    }
+
+    #[test]
+    fn test_ignore_multiline() {
+        let source = "<?php\nfoo($a,\n    $b);\n";
+        let fixed = fix_with(source, &[("on_multiline", ConfigValue::String("ignore".to_string()))]);
+        assert_eq!(fixed, source);
+    }
+
+    #[test]
+    fn test_heredoc_before_comma_kept() {
+        let source = "<?php\nfoo(<<<EOT\ntext\nEOT\n, $b);\n";
+        let fixed = fix_with(source, &[("on_multiline", ConfigValue::String("ignore".to_string()))]);
+        assert_eq!(fixed, source);
+    }
+
+    #[test]
+    fn test_after_heredoc() {
+        let source = "<?php\nfoo(<<<EOT\ntext\nEOT\n, $b);\n";
+        let fixed = fix_with(source, &[
+            ("on_multiline", ConfigValue::String("ignore".to_string())),
+            ("after_heredoc", ConfigValue::Bool(true)),
+        ]);
+        assert_eq!(fixed, "<?php\nfoo(<<<EOT\ntext\nEOT, $b);\n");
+    }
+
+    #[test]
+    fn test_comment_keeps_layout() {
+        let source = "<?php\nfoo($a, // first\n    $b);\n";
+        assert!(check(source).is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_untouched() {
+        let source = "<?php\nfoo( $a,$b\n";
+        assert!(check(source).is_empty());
     }
 }
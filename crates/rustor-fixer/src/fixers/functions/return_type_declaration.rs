@@ -2,7 +2,8 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures proper spacing in return type declarations
 pub struct ReturnTypeDeclarationFixer;
@@ -24,7 +25,20 @@ impl Fixer for ReturnTypeDeclarationFixer {
         30
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "space_before",
+            description: "Spacing before the colon; only none is supported",
+            option_type: OptionType::Enum(vec!["none"]),
+            default: Some(ConfigValue::String("none".to_string())),
+        }]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // PSR-12: No space before colon, one space after
@@ -38,7 +52,7 @@ impl Fixer for ReturnTypeDeclarationFixer {
             let nullable = cap.get(2).unwrap().as_str();
             let type_name = cap.get(3).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -63,7 +77,7 @@ impl Fixer for ReturnTypeDeclarationFixer {
             let nullable = cap.get(1).unwrap().as_str();
             let first_char = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -92,7 +106,7 @@ impl Fixer for ReturnTypeDeclarationFixer {
             let nullable = cap.get(1).unwrap().as_str();
             let first_char = cap.get(2).unwrap().as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -115,24 +129,6 @@ impl Fixer for ReturnTypeDeclarationFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
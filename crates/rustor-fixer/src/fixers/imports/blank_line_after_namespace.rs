@@ -88,13 +88,12 @@ impl Fixer for BlankLineAfterNamespaceFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{IndentStyle, LineEnding};
+    use crate::config::LineEnding;
 
     fn check(source: &str) -> Vec<Edit> {
         BlankLineAfterNamespaceFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
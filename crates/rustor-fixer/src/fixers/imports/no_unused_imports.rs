@@ -25,10 +25,6 @@ impl Fixer for NoUnusedImportsFixer {
         10  // Run after other import fixers
     }
 
-    fn is_risky(&self) -> bool {
-        true  // Removing imports could break code
-    }
-
     fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Splits grouped use statements into individual statements
 pub struct SingleImportPerStatementFixer;
@@ -36,6 +37,10 @@ impl Fixer for SingleImportPerStatementFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
         let line_ending = config.line_ending.as_str();
 
@@ -60,8 +65,8 @@ impl Fixer for SingleImportPerStatementFixer {
                 let namespace = cap.get(3).unwrap().as_str().trim_end_matches('\\');
                 let items = cap.get(4).unwrap().as_str();
 
-                // Check not in string
-                if is_in_string(&source[..full_match.start()]) {
+                // Skip if in string or comment
+                if tokens.is_in_string_or_comment(full_match.start()) {
                     continue;
                 }
 
@@ -115,8 +120,8 @@ impl Fixer for SingleImportPerStatementFixer {
                 continue;
             }
 
-            // Check not in string
-            if is_in_string(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -150,24 +155,6 @@ impl Fixer for SingleImportPerStatementFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,13 +102,12 @@ impl Fixer for SingleLineAfterImportsFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{IndentStyle, LineEnding};
+    use crate::config::LineEnding;
 
     fn check(source: &str) -> Vec<Edit> {
         SingleLineAfterImportsFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
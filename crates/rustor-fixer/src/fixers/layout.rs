@@ -0,0 +1,449 @@
+//! Layout of multi-line constructs
+//!
+//! `array_indentation`, `method_chaining_indentation` and `method_argument_space`
+//! find arrays, argument lists and `->` chains in the mago-syntax tree rather
+//! than guessing them from brackets. Each decides where a few lines go (array
+//! elements, chain links, arguments of a reflowed call) through a [`Layout`],
+//! which then moves every other line inside brackets along with the line it
+//! hangs off, so closure bodies and nested arrays keep their shape.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::{HasSpan, Span};
+use mago_syntax::ast::*;
+use mago_syntax::token::TokenKind;
+use mago_syntax::walker::Walker;
+use rustor_core::Edit;
+use crate::fixers::{FixerConfig, edit_with_rule};
+use crate::tokens::{Region, Token, TokenStream};
+
+/// What a [`List`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListKind {
+    /// `[...]`, `array(...)` or `list(...)`
+    Array,
+    /// Arguments of a call, `new` or attribute
+    Arguments,
+    /// Parameters of a function, method, closure or arrow function
+    Parameters,
+}
+
+/// A bracketed, comma-separated list
+#[derive(Debug, Clone)]
+pub(crate) struct List {
+    pub kind: ListKind,
+    /// Offset of the opening bracket
+    pub open: usize,
+    /// Offset of the closing bracket
+    pub close: usize,
+    /// Byte ranges of the items
+    pub items: Vec<Range<usize>>,
+    /// Offsets of the commas, including a trailing one
+    pub commas: Vec<usize>,
+}
+
+/// An object operator (`->` or `?->`) of a property access or method call
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChainLink {
+    /// Offset of the operator
+    pub arrow: usize,
+    /// Offset where the whole chain starts
+    pub root: usize,
+    /// Offset where the link ends, after its arguments
+    pub end: usize,
+}
+
+/// The multi-line constructs of a file
+#[derive(Debug, Default)]
+pub(crate) struct Syntax {
+    pub lists: Vec<List>,
+    pub links: Vec<ChainLink>,
+}
+
+impl Syntax {
+    /// Parse the source, or `None` if it has syntax errors (fixers leave such files alone)
+    pub fn parse(source: &str) -> Option<Self> {
+        let arena = Bump::new();
+        let (program, error) = mago_syntax::parser::parse_file_content(&arena, FileId::zero(), source);
+        if error.is_some() {
+            return None;
+        }
+
+        let mut syntax = Syntax::default();
+        Collector.walk_program(program, &mut syntax);
+        Some(syntax)
+    }
+
+    fn push_list(&mut self, kind: ListKind, open: Span, items: impl Iterator<Item = Span>, commas: &[mago_syntax::token::Token<'_>], close: Span) {
+        self.lists.push(List {
+            kind,
+            open: open.start.offset as usize,
+            close: close.start.offset as usize,
+            items: items.map(|span| span.start.offset as usize..span.end.offset as usize).collect(),
+            commas: commas.iter().map(|t| t.span.start.offset as usize).collect(),
+        });
+    }
+
+    fn push_array(&mut self, open: Span, elements: &mago_syntax::ast::sequence::TokenSeparatedSequence<'_, ArrayElement<'_>>, close: Span) {
+        let items = elements.iter().filter(|e| !matches!(e, ArrayElement::Missing(_))).map(|e| e.span());
+        self.push_list(ListKind::Array, open, items, &elements.tokens, close);
+    }
+
+    fn push_link(&mut self, arrow: Span, whole: Span) {
+        self.links.push(ChainLink {
+            arrow: arrow.start.offset as usize,
+            root: whole.start.offset as usize,
+            end: whole.end.offset as usize,
+        });
+    }
+}
+
+struct Collector;
+
+impl<'ast, 'arena> Walker<'ast, 'arena, Syntax> for Collector {
+    fn walk_in_argument_list(&self, list: &'ast ArgumentList<'arena>, syntax: &mut Syntax) {
+        let items = list.arguments.iter().map(|a| a.span());
+        syntax.push_list(ListKind::Arguments, list.left_parenthesis, items, &list.arguments.tokens, list.right_parenthesis);
+    }
+
+    fn walk_in_function_like_parameter_list(&self, list: &'ast FunctionLikeParameterList<'arena>, syntax: &mut Syntax) {
+        let items = list.parameters.iter().map(|p| p.span());
+        syntax.push_list(ListKind::Parameters, list.left_parenthesis, items, &list.parameters.tokens, list.right_parenthesis);
+    }
+
+    fn walk_in_array(&self, array: &'ast Array<'arena>, syntax: &mut Syntax) {
+        syntax.push_array(array.left_bracket, &array.elements, array.right_bracket);
+    }
+
+    fn walk_in_legacy_array(&self, array: &'ast LegacyArray<'arena>, syntax: &mut Syntax) {
+        syntax.push_array(array.left_parenthesis, &array.elements, array.right_parenthesis);
+    }
+
+    fn walk_in_list(&self, list: &'ast mago_syntax::ast::List<'arena>, syntax: &mut Syntax) {
+        syntax.push_array(list.left_parenthesis, &list.elements, list.right_parenthesis);
+    }
+
+    fn walk_in_method_call(&self, call: &'ast MethodCall<'arena>, syntax: &mut Syntax) {
+        syntax.push_link(call.arrow, call.span());
+    }
+
+    fn walk_in_null_safe_method_call(&self, call: &'ast NullSafeMethodCall<'arena>, syntax: &mut Syntax) {
+        syntax.push_link(call.question_mark_arrow, call.span());
+    }
+
+    fn walk_in_property_access(&self, access: &'ast PropertyAccess<'arena>, syntax: &mut Syntax) {
+        syntax.push_link(access.arrow, access.span());
+    }
+
+    fn walk_in_null_safe_property_access(&self, access: &'ast NullSafePropertyAccess<'arena>, syntax: &mut Syntax) {
+        syntax.push_link(access.question_mark_arrow, access.span());
+    }
+}
+
+/// Where a line is placed explicitly: one or more indentation levels past a base line
+struct Anchor {
+    /// Any offset on the base line
+    base: usize,
+    levels: usize,
+    message: &'static str,
+}
+
+/// The line another line moves along with
+#[derive(Debug, Clone, Copy)]
+struct Reference {
+    line: usize,
+    /// Levels the line nests past `line`: 1 if it starts a statement, element
+    /// or argument in a bracket opened on `line`, 0 if it starts with the
+    /// closing bracket, `None` if it continues an item starting on `line`
+    depth: Option<usize>,
+}
+
+/// A line of the laid-out file
+struct Line {
+    /// Offset of its first character
+    start: usize,
+    /// Indentation of the original line it starts on
+    indent: Range<usize>,
+    /// Whether it starts at an inserted line break
+    inserted: bool,
+}
+
+/// Line breaks and indentation a fixer wants, turned into edits by [`Layout::edits`]
+///
+/// Lines a fixer places explicitly are indented relative to their base line.
+/// Any other line inside brackets keeps its indentation relative to the line
+/// its statement, element or argument starts on, or else to the line of the
+/// innermost opening bracket, so it moves whenever that line moves. Inside
+/// [`Layout::reindent`] ranges, lines that don't continue an item are
+/// indented by nesting instead.
+pub(crate) struct Layout<'s> {
+    source: &'s str,
+    tokens: &'s TokenStream,
+    unit: &'static str,
+    line_ending: &'static str,
+    anchors: HashMap<usize, Anchor>,
+    breaks: Vec<usize>,
+    joins: HashMap<usize, (&'static str, &'static str)>,
+    reindent: Vec<Range<usize>>,
+}
+
+impl<'s> Layout<'s> {
+    pub fn new(source: &'s str, tokens: &'s TokenStream, config: &FixerConfig) -> Self {
+        Self {
+            source,
+            tokens,
+            unit: config.indent.as_str(),
+            line_ending: config.line_ending.as_str(),
+            anchors: HashMap::new(),
+            breaks: Vec::new(),
+            joins: HashMap::new(),
+            reindent: Vec::new(),
+        }
+    }
+
+    /// Whether only whitespace precedes `offset` on its line
+    pub fn is_line_start(&self, offset: usize) -> bool {
+        self.source[..offset].bytes().rev().take_while(|&b| b != b'\n').all(|b| b == b' ' || b == b'\t')
+    }
+
+    /// Indent the line starting at `offset` `levels` deeper than the line holding `base`
+    pub fn indent(&mut self, offset: usize, base: usize, levels: usize, message: &'static str) {
+        self.anchors.insert(offset, Anchor { base, levels, message });
+    }
+
+    /// Start a new line at `offset`, indented `levels` deeper than the line holding `base`
+    pub fn break_before(&mut self, offset: usize, base: usize, levels: usize, message: &'static str) {
+        if !self.breaks.contains(&offset) {
+            self.breaks.push(offset);
+        }
+        self.indent(offset, base, levels, message);
+    }
+
+    /// Replace all whitespace before `offset`, line breaks included, with `with`
+    pub fn join(&mut self, offset: usize, with: &'static str, message: &'static str) {
+        self.joins.insert(offset, (with, message));
+    }
+
+    /// Indent the lines inside `range` one level per bracket line they nest
+    /// in, rather than keeping their offset to the line they move along with
+    pub fn reindent(&mut self, range: Range<usize>) {
+        self.reindent.push(range);
+    }
+
+    /// Edits laying out the lines, tagged with `rule`; lines moved along with
+    /// another one get `message`
+    pub fn edits(self, rule: &str, message: &str) -> Vec<Edit> {
+        let lines = self.lines();
+        let refs = self.references(&lines);
+        let line_of = |offset: usize| lines.partition_point(|l| l.start <= offset).saturating_sub(1);
+
+        let mut indents: Vec<String> = Vec::with_capacity(lines.len());
+        let mut edits = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let original = &self.source[line.indent.clone()];
+            let anchor = self.anchors.get(&line.start).filter(|a| line_of(a.base) < i);
+            let indent = match (anchor, refs[i]) {
+                _ if i == 0 => original.to_string(),
+                (Some(anchor), _) => format!("{}{}", indents[line_of(anchor.base)], self.unit.repeat(anchor.levels)),
+                (None, Some(Reference { line: r, depth: Some(depth) }))
+                    if self.reindent.iter().any(|range| range.contains(&line.start)) =>
+                {
+                    format!("{}{}", indents[r], self.unit.repeat(depth))
+                }
+                (None, Some(Reference { line: r, .. })) => match original.strip_prefix(&self.source[lines[r].indent.clone()]) {
+                    Some(rest) => format!("{}{}", indents[r], rest),
+                    None => original.to_string(),
+                },
+                (None, None) => original.to_string(),
+            };
+
+            let message = anchor.map_or(message, |a| a.message);
+            if line.inserted {
+                let gap = self.gap_before(line.start);
+                edits.push(edit_with_rule(gap.start, gap.end, format!("{}{}", self.line_ending, indent), message.to_string(), rule));
+            } else if i > 0 && indent != original {
+                edits.push(edit_with_rule(line.indent.start, line.indent.end, indent.clone(), message.to_string(), rule));
+            }
+            indents.push(indent);
+        }
+
+        for (&offset, &(with, message)) in &self.joins {
+            let gap = self.gap_before(offset);
+            if &self.source[gap.clone()] != with {
+                edits.push(edit_with_rule(gap.start, gap.end, with.to_string(), message.to_string(), rule));
+            }
+        }
+
+        edits
+    }
+
+    /// Whitespace (line breaks included) right before `offset`
+    fn gap_before(&self, offset: usize) -> Range<usize> {
+        let len = self.source[..offset].bytes().rev().take_while(|b| b.is_ascii_whitespace()).count();
+        offset - len..offset
+    }
+
+    /// Indentation of the original line holding `offset`
+    fn indent_at(&self, offset: usize) -> Range<usize> {
+        let start = self.source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let len = self.source[start..].bytes().take_while(|&b| b == b' ' || b == b'\t').count();
+        start..start + len
+    }
+
+    /// Lines after layout, the first line of the file included; lines inside
+    /// strings, heredocs and inline HTML are left out
+    fn lines(&self) -> Vec<Line> {
+        let bytes = self.source.as_bytes();
+        let mut lines = vec![Line { start: 0, indent: self.indent_at(0), inserted: false }];
+
+        for (newline, _) in self.source.match_indices('\n') {
+            let indent = self.indent_at(newline + 1);
+            let start = indent.end;
+            if start >= bytes.len()
+                || matches!(bytes[start], b'\n' | b'\r')
+                || matches!(self.tokens.region_at(newline), Region::String | Region::InlineHtml)
+                || self.joins.contains_key(&start)
+            {
+                continue;
+            }
+            lines.push(Line { start, indent, inserted: false });
+        }
+
+        for &start in &self.breaks {
+            lines.push(Line { start, indent: self.indent_at(start), inserted: true });
+        }
+
+        lines.sort_by_key(|l| l.start);
+        lines
+    }
+
+    /// For each line, the line it moves along with
+    fn references(&self, lines: &[Line]) -> Vec<Option<Reference>> {
+        struct Level {
+            open: usize,
+            brace: bool,
+            item: Option<usize>,
+        }
+
+        let line_of = |offset: usize| lines.partition_point(|l| l.start <= offset).saturating_sub(1);
+        let mut refs = vec![None; lines.len()];
+        let mut stack: Vec<Level> = Vec::new();
+        let mut next = 1;
+
+        for token in self.tokens.tokens() {
+            if token.kind == TokenKind::Whitespace || token.region == Region::InlineHtml {
+                continue;
+            }
+
+            while next < lines.len() && lines[next].start <= token.start {
+                let start = lines[next].start;
+                refs[next] = stack.last().map(|level| match level.item {
+                    _ if start == token.start && token.region == Region::Code && is_closer(token) => {
+                        Reference { line: line_of(level.open), depth: Some(0) }
+                    }
+                    Some(item) if item < start => Reference { line: line_of(item), depth: None },
+                    _ => Reference { line: line_of(level.open), depth: Some(1) },
+                });
+                next += 1;
+            }
+
+            if token.region == Region::Comment {
+                continue;
+            }
+            if token.region == Region::Code && is_closer(token) {
+                stack.pop();
+                if let Some(level) = stack.last_mut() {
+                    if level.brace && token.kind == TokenKind::RightBrace {
+                        level.item = None;
+                    }
+                }
+                continue;
+            }
+            if token.region == Region::Code && matches!(token.kind, TokenKind::Comma | TokenKind::Semicolon) {
+                if let Some(level) = stack.last_mut() {
+                    level.item = None;
+                }
+                continue;
+            }
+            if let Some(level) = stack.last_mut() {
+                level.item.get_or_insert(token.start);
+            }
+            if token.region == Region::Code && is_opener(token) {
+                stack.push(Level { open: token.start, brace: token.kind == TokenKind::LeftBrace, item: None });
+            }
+        }
+
+        refs
+    }
+}
+
+fn is_opener(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::LeftParenthesis | TokenKind::LeftBracket | TokenKind::LeftBrace | TokenKind::HashLeftBracket)
+}
+
+fn is_closer(token: &Token) -> bool {
+    matches!(token.kind, TokenKind::RightParenthesis | TokenKind::RightBracket | TokenKind::RightBrace)
+}
+
+/// Tokens starting inside `range`
+pub(crate) fn tokens_in(tokens: &TokenStream, range: Range<usize>) -> &[Token] {
+    let all = tokens.tokens();
+    let from = all.partition_point(|t| t.start < range.start);
+    let to = all.partition_point(|t| t.start < range.end);
+    &all[from..to]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(source: &str, place: impl FnOnce(&mut Layout)) -> String {
+        let tokens = TokenStream::new(source);
+        let config = FixerConfig::default();
+        let mut layout = Layout::new(source, &tokens, &config);
+        place(&mut layout);
+        let edits = layout.edits("test", "Move line");
+        rustor_core::apply_edits(source, &edits).unwrap()
+    }
+
+    #[test]
+    fn test_parse_collects_lists_and_links() {
+        let syntax = Syntax::parse("<?php\n$a->b(1, [2, 3])?->c;\nfunction f($x, $y,) {}\n").unwrap();
+        let kinds: Vec<ListKind> = syntax.lists.iter().map(|l| l.kind).collect();
+        assert!(kinds.contains(&ListKind::Arguments));
+        assert!(kinds.contains(&ListKind::Array));
+        assert!(kinds.contains(&ListKind::Parameters));
+        assert_eq!(syntax.links.len(), 2);
+        let params = syntax.lists.iter().find(|l| l.kind == ListKind::Parameters).unwrap();
+        assert_eq!(params.commas.len(), params.items.len());
+    }
+
+    #[test]
+    fn test_parse_error_gives_none() {
+        assert!(Syntax::parse("<?php\nfoo(;\n").is_none());
+    }
+
+    #[test]
+    fn test_closure_body_follows_its_line() {
+        let source = "<?php\nfoo($a, function () {\n    return 1;\n});\n";
+        let function = source.find("function").unwrap();
+        let open = source.find('(').unwrap();
+        let close = source.rfind(')').unwrap();
+        let fixed = layout(source, |l| {
+            l.break_before(source.find("$a").unwrap(), open, 1, "Break");
+            l.break_before(function, open, 1, "Break");
+            l.break_before(close, open, 0, "Break");
+        });
+        assert_eq!(fixed, "<?php\nfoo(\n    $a,\n    function () {\n        return 1;\n    }\n);\n");
+    }
+
+    #[test]
+    fn test_statements_after_moved_line_stay() {
+        let source = "<?php\nfunction f() {\n    $a\n    ->b();\n    $c = 1;\n}\n";
+        let arrow = source.find("->").unwrap();
+        let fixed = layout(source, |l| l.indent(arrow, source.find("$a").unwrap(), 1, "Indent"));
+        assert_eq!(fixed, "<?php\nfunction f() {\n    $a\n        ->b();\n    $c = 1;\n}\n");
+    }
+}
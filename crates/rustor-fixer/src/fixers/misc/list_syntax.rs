@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct ListSyntaxFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for ListSyntaxFixer {
     fn description(&self) -> &'static str { "Use short list syntax" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match: list($a, $b) = ...
@@ -22,7 +27,7 @@ impl Fixer for ListSyntaxFixer {
             let full = cap.get(0).unwrap();
             let vars = cap.get(1).unwrap().as_str();
 
-            if is_in_string(&source[..full.start()]) { continue; }
+            if tokens.is_in_string_or_comment(full.start()) { continue; }
 
             edits.push(edit_with_rule(
                 full.start(), full.end(),
@@ -36,16 +41,6 @@ impl Fixer for ListSyntaxFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
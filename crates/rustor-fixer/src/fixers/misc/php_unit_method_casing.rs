@@ -2,7 +2,7 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
 
 pub struct PhpUnitMethodCasingFixer;
 
@@ -12,6 +12,15 @@ impl Fixer for PhpUnitMethodCasingFixer {
     fn description(&self) -> &'static str { "Test method casing style" }
     fn priority(&self) -> i32 { 20 }
 
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "case",
+            description: "Casing of test methods; only camel_case is supported",
+            option_type: OptionType::Enum(vec!["camel_case"]),
+            default: Some(ConfigValue::String("camel_case".to_string())),
+        }]
+    }
+
     fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
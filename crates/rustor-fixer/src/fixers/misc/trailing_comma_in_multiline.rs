@@ -2,7 +2,11 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+
+/// Supported `elements` values; `match` isn't supported yet
+const ELEMENTS: &[&str] = &["arguments", "array_destructuring", "arrays", "parameters"];
+const DEFAULT_ELEMENTS: &[&str] = &["arguments", "arrays", "parameters"];
 
 pub struct TrailingCommaInMultilineFixer;
 
@@ -12,14 +16,34 @@ impl Fixer for TrailingCommaInMultilineFixer {
     fn description(&self) -> &'static str { "Add trailing comma in multiline" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "elements",
+            description: "Multiline structures that get a trailing comma",
+            option_type: OptionType::EnumArray(ELEMENTS.to_vec()),
+            default: Some(ConfigValue::Array(DEFAULT_ELEMENTS.iter().map(|s| s.to_string()).collect())),
+        }]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
+        let enabled = |elements: &[&str]| {
+            let configured = config.get_array("elements");
+            elements.iter().any(|element| match configured {
+                Some(configured) => configured.iter().any(|c| c == element),
+                None => DEFAULT_ELEMENTS.contains(element),
+            })
+        };
+        // Brackets are arrays or destructuring, parentheses are calls or
+        // declarations; the regexes can't tell them apart
+        let brackets = enabled(&["arrays", "array_destructuring"]);
+        let parentheses = enabled(&["arguments", "parameters"]);
 
         // Match multiline array without trailing comma
         // Pattern: value followed by newline and closing bracket
         let re = Regex::new(r"([^\s,\[\{])\s*\n(\s*)\]").unwrap();
 
-        for cap in re.captures_iter(source) {
+        for cap in re.captures_iter(source).filter(|_| brackets) {
             let full = cap.get(0).unwrap();
             let last_char = cap.get(1).unwrap().as_str();
             let indent = cap.get(2).unwrap().as_str();
@@ -37,7 +61,7 @@ impl Fixer for TrailingCommaInMultilineFixer {
 
         // Same for function parameters
         let re2 = Regex::new(r"([^\s,\(])\s*\n(\s*)\)").unwrap();
-        for cap in re2.captures_iter(source) {
+        for cap in re2.captures_iter(source).filter(|_| parentheses) {
             let full = cap.get(0).unwrap();
             let last_char = cap.get(1).unwrap().as_str();
             let indent = cap.get(2).unwrap().as_str();
@@ -69,4 +93,16 @@ $a = [
         let edits = TrailingCommaInMultilineFixer.check(code, &FixerConfig::default());
         assert!(!edits.is_empty());
     }
+
+    #[test]
+    fn test_elements_option() {
+        let code = "<?php\n$a = [\n    1\n];\nfoo(\n    1\n);\n";
+        let config = FixerConfig {
+            options: [("elements".to_string(), ConfigValue::Array(vec!["arrays".to_string()]))].into_iter().collect(),
+            ..Default::default()
+        };
+        let edits = TrailingCommaInMultilineFixer.check(code, &config);
+        assert_eq!(edits.len(), 1);
+        assert!(edits[0].replacement.ends_with(']'));
+    }
 }
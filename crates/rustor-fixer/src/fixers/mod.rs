@@ -4,6 +4,8 @@
 //! to enforce formatting standards like PSR-12.
 
 mod registry;
+mod options;
+mod layout;
 pub mod whitespace;
 pub mod casing;
 pub mod braces;
@@ -20,10 +22,14 @@ pub mod types;
 pub mod misc;
 
 pub use registry::{FixerRegistry, FixerInfo};
+pub use options::{OptionError, validate_options};
 
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::ops::Range;
 use rustor_core::Edit;
 use crate::config::{WhitespaceConfig, IndentStyle, LineEnding};
+use crate::tokens::TokenStream;
 
 /// Configuration passed to fixers
 #[derive(Debug, Clone)]
@@ -32,8 +38,18 @@ pub struct FixerConfig {
     pub indent: IndentStyle,
     /// Line ending style
     pub line_ending: LineEnding,
-    /// Rule-specific options
+    /// Options shared by every fixer
     pub options: HashMap<String, ConfigValue>,
+    /// Options for individual fixers, keyed by PHP-CS-Fixer rule name
+    pub rule_options: HashMap<String, HashMap<String, ConfigValue>>,
+    /// Whether risky fixers may run (PHP-CS-Fixer's `setRiskyAllowed`)
+    pub risky_allowed: bool,
+    /// Byte ranges of the original source fixers may change; edits outside
+    /// them are dropped. `None` allows the whole file.
+    pub ranges: Option<Vec<Range<usize>>>,
+    /// Fixers to run (`true`) or skip (`false`) whatever was requested,
+    /// keyed by PHP-CS-Fixer rule name
+    pub fixer_overrides: HashMap<String, bool>,
 }
 
 impl Default for FixerConfig {
@@ -42,6 +58,77 @@ impl Default for FixerConfig {
             indent: IndentStyle::default(),
             line_ending: LineEnding::default(),
             options: HashMap::new(),
+            rule_options: HashMap::new(),
+            risky_allowed: false,
+            ranges: None,
+            fixer_overrides: HashMap::new(),
+        }
+    }
+}
+
+impl FixerConfig {
+    /// The configuration as seen by one fixer
+    ///
+    /// The fixer's own options take precedence over the shared ones, and options
+    /// of other fixers are not visible, so fixers sharing an option name
+    /// (`operators`, `elements`, ...) don't clobber each other.
+    pub fn for_fixer(&self, rule: &str) -> Cow<'_, FixerConfig> {
+        match self.rule_options.get(rule) {
+            Some(rule_options) if !rule_options.is_empty() => {
+                let mut options = self.options.clone();
+                options.extend(rule_options.iter().map(|(k, v)| (k.clone(), v.clone())));
+                Cow::Owned(FixerConfig {
+                    indent: self.indent,
+                    line_ending: self.line_ending,
+                    options,
+                    rule_options: HashMap::new(),
+                    risky_allowed: self.risky_allowed,
+                    ranges: self.ranges.clone(),
+                    fixer_overrides: self.fixer_overrides.clone(),
+                })
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Get a boolean option
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        match self.options.get(name) {
+            Some(ConfigValue::Bool(b)) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Get a string option
+    pub fn get_string(&self, name: &str) -> Option<&str> {
+        match self.options.get(name) {
+            Some(ConfigValue::String(s)) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Get a numeric option
+    pub fn get_number(&self, name: &str) -> Option<i64> {
+        match self.options.get(name) {
+            Some(ConfigValue::Number(n)) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// Get a list option
+    pub fn get_array(&self, name: &str) -> Option<&[String]> {
+        match self.options.get(name) {
+            Some(ConfigValue::Array(values)) => Some(values.as_slice()),
+            _ => None,
+        }
+    }
+
+    /// Get a map option (an empty PHP array counts as an empty map)
+    pub fn get_map(&self, name: &str) -> Option<Cow<'_, HashMap<String, String>>> {
+        match self.options.get(name) {
+            Some(ConfigValue::StringMap(map)) => Some(Cow::Borrowed(map)),
+            Some(ConfigValue::Array(values)) if values.is_empty() => Some(Cow::Owned(HashMap::new())),
+            _ => None,
         }
     }
 }
@@ -52,6 +139,10 @@ impl From<&WhitespaceConfig> for FixerConfig {
             indent: ws.indent,
             line_ending: ws.line_ending,
             options: HashMap::new(),
+            rule_options: HashMap::new(),
+            risky_allowed: false,
+            ranges: None,
+            fixer_overrides: HashMap::new(),
         }
     }
 }
@@ -98,6 +189,14 @@ pub trait Fixer: Send + Sync {
     /// Check the source and return edits to apply
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit>;
 
+    /// Check the source using the registry's shared token stream
+    ///
+    /// Fixers that need to know whether an offset is code, a string, a comment
+    /// or inline HTML override this instead of rescanning the source.
+    fn check_tokens(&self, source: &str, _tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        self.check(source, config)
+    }
+
     /// Get configurable options for this fixer
     fn options(&self) -> Vec<FixerOption> {
         vec![]
@@ -121,6 +220,14 @@ pub enum OptionType {
     Number,
     StringArray,
     Enum(Vec<&'static str>),
+    /// List whose values must be among the given ones
+    EnumArray(Vec<&'static str>),
+    /// Map with any keys and values
+    StringMap,
+    /// Map with keys and values among the given ones (no key restriction when empty)
+    EnumMap(Vec<&'static str>, Vec<&'static str>),
+    /// A number, or a map of numbers
+    NumberOrMap,
 }
 
 /// Create an Edit with a rule name
@@ -139,6 +246,7 @@ pub fn edit_with_rule(start: usize, end: usize, replacement: String, message: St
         replacement,
         message,
         rule: Some(rule.to_string()),
+        group: None,
     }
 }
 
@@ -1,9 +1,28 @@
 //! Configure spacing around binary operators
 
+use std::collections::{BTreeMap, HashMap, HashSet};
 use rustor_core::Edit;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::{Region, TokenStream};
+use mago_syntax::token::TokenKind;
+
+/// Spacing policies accepted by the `default` and `operators` options
+const POLICIES: &[&str] = &[
+    "single_space",
+    "at_least_single_space",
+    "align",
+    "align_by_scope",
+    "align_single_space",
+    "align_single_space_by_scope",
+    "align_single_space_minimal",
+    "align_single_space_minimal_by_scope",
+    "no_space",
+];
 
 /// Ensures single space around binary operators
+///
+/// The `align*` policies are supported for `=` and `=>`; other operators
+/// configured to align fall back to single (or at least single) spacing.
 pub struct BinaryOperatorSpacesFixer;
 
 impl Fixer for BinaryOperatorSpacesFixer {
@@ -23,17 +42,59 @@ impl Fixer for BinaryOperatorSpacesFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
+    fn options(&self) -> Vec<FixerOption> {
+        vec![
+            FixerOption {
+                name: "default",
+                description: "Default spacing policy for all operators",
+                option_type: OptionType::Enum(POLICIES.to_vec()),
+                default: Some(ConfigValue::String("single_space".to_string())),
+            },
+            FixerOption {
+                name: "operators",
+                description: "Spacing policy per operator, e.g. ['=>' => 'align_single_space_minimal']",
+                option_type: OptionType::EnumMap(vec![], POLICIES.to_vec()),
+                default: None,
+            },
+        ]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let chars: Vec<char> = source.chars().collect();
         let len = chars.len();
+        // Byte offset of each char (plus the end of the source)
+        let byte_offsets: Vec<usize> = source
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(source.len()))
+            .collect();
+        let declare_ranges = declare_ranges(source, tokens);
+
+        let policies = Policies::from_config(config);
+        let mut aligned = HashSet::new();
+        let mut edits = Vec::new();
+        for (op, kind) in [("=", TokenKind::Equal), ("=>", TokenKind::EqualGreaterThan)] {
+            if let Spacing::Align(align) = policies.get(op) {
+                edits.extend(align_operator(source, tokens, kind, op, align, &mut aligned));
+            }
+        }
+        let ctx = SpacingContext {
+            chars: &chars,
+            byte_offsets: &byte_offsets,
+            policies: &policies,
+            aligned: &aligned,
+        };
         let mut i = 0;
 
         while i < len {
-            let byte_pos: usize = chars[..i].iter().map(|c| c.len_utf8()).sum();
+            let byte_pos = byte_offsets[i];
 
             // Skip strings and comments
-            if is_in_string_or_comment(&source[..byte_pos]) {
+            if tokens.is_in_string_or_comment(byte_pos) {
                 i += 1;
                 continue;
             }
@@ -47,9 +108,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
             if i + 2 < len {
                 let three = format!("{}{}{}", curr, next.unwrap(), next2.unwrap());
                 if matches!(three.as_str(), "===" | "!==" | "**=" | "<<=" | ">>=" | "<=>") {
-                    if let Some(edit) = check_operator_spacing(&chars, i, 3, &three) {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 3, &three));
                     i += 3;
                     continue;
                 }
@@ -61,9 +120,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
 
                 // ??= is 3 chars but starts with ??
                 if two == "??" && next2 == Some('=') {
-                    if let Some(edit) = check_operator_spacing(&chars, i, 3, "??=") {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 3, "??="));
                     i += 3;
                     continue;
                 }
@@ -80,7 +137,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                     "&&" | "||" | "=>" | "<<" | ">>"
                 ) {
                     // Skip => inside declare()
-                    if two == "=>" && is_in_declare(&source[..byte_pos]) {
+                    if two == "=>" && is_in_declare(&declare_ranges, byte_pos) {
                         i += 2;
                         continue;
                     }
@@ -91,9 +148,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                         continue;
                     }
 
-                    if let Some(edit) = check_operator_spacing(&chars, i, 2, &two) {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 2, &two));
                     i += 2;
                     continue;
                 }
@@ -104,7 +159,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                 '=' => {
                     // Skip if part of multi-char operator (already handled above)
                     // Also skip in declare()
-                    if is_in_declare(&source[..byte_pos]) {
+                    if is_in_declare(&declare_ranges, byte_pos) {
                         i += 1;
                         continue;
                     }
@@ -123,9 +178,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                         continue;
                     }
 
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, "=") {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, "="));
                 }
                 '<' | '>' => {
                     // Skip if part of multi-char operator
@@ -147,9 +200,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                     // Skip generic type hints like array<int>
 
                     let op = curr.to_string();
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, &op) {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, &op));
                 }
                 '+' | '-' => {
                     // Skip if part of compound operator +=, -=
@@ -198,9 +249,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                     }
 
                     let op = curr.to_string();
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, &op) {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, &op));
                 }
                 '*' => {
                     // Skip if part of compound operator *=, **
@@ -219,9 +268,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                         continue;
                     }
 
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, "*") {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, "*"));
                 }
                 '/' => {
                     // Skip if part of compound operator /=
@@ -235,9 +282,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                         continue;
                     }
 
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, "/") {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, "/"));
                 }
                 '%' => {
                     // Skip if part of compound operator %=
@@ -246,9 +291,7 @@ impl Fixer for BinaryOperatorSpacesFixer {
                         continue;
                     }
 
-                    if let Some(edit) = check_operator_spacing(&chars, i, 1, "%") {
-                        edits.push(edit);
-                    }
+                    edits.extend(ctx.operator_edit(i, 1, "%"));
                 }
                 // Note: '.' (concatenation) is NOT handled by binary_operator_spaces
                 // in PHP-CS-Fixer. Use concat_space fixer instead.
@@ -258,12 +301,336 @@ impl Fixer for BinaryOperatorSpacesFixer {
             i += 1;
         }
 
+        edits.sort_by_key(|e| e.span.start.offset);
         edits
     }
 }
 
+/// How to space an operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spacing {
+    SingleSpace,
+    AtLeastSingleSpace,
+    NoSpace,
+    Align(Align),
+}
+
+/// Variant of the `align*` policies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Align {
+    /// Exactly one space after the operator, and around unaligned ones
+    single_space: bool,
+    /// Align to the longest left-hand side instead of the rightmost operator
+    minimal: bool,
+    /// Lines in a nested scope end the group
+    by_scope: bool,
+}
+
+impl Spacing {
+    fn parse(policy: &str) -> Option<Self> {
+        Some(match policy {
+            "single_space" => Spacing::SingleSpace,
+            "at_least_single_space" => Spacing::AtLeastSingleSpace,
+            "no_space" => Spacing::NoSpace,
+            _ => {
+                let rest = policy.strip_prefix("align")?;
+                let (rest, by_scope) = match rest.strip_suffix("_by_scope") {
+                    Some(rest) => (rest, true),
+                    None => (rest, false),
+                };
+                let (single_space, minimal) = match rest {
+                    "" => (false, false),
+                    "_single_space" => (true, false),
+                    "_single_space_minimal" => (true, true),
+                    _ => return None,
+                };
+                Spacing::Align(Align { single_space, minimal, by_scope })
+            }
+        })
+    }
+
+    /// The spacing of an operator that isn't part of an aligned group
+    fn unaligned(self) -> Self {
+        match self {
+            Spacing::Align(Align { single_space: true, .. }) => Spacing::SingleSpace,
+            Spacing::Align(_) => Spacing::AtLeastSingleSpace,
+            spacing => spacing,
+        }
+    }
+}
+
+/// The `default` and `operators` options
+struct Policies {
+    default: Spacing,
+    operators: HashMap<String, Spacing>,
+}
+
+impl Policies {
+    fn from_config(config: &FixerConfig) -> Self {
+        let default = config
+            .get_string("default")
+            .and_then(Spacing::parse)
+            .unwrap_or(Spacing::SingleSpace);
+        let operators = config
+            .get_map("operators")
+            .map(|map| {
+                map.iter()
+                    .filter_map(|(op, policy)| Some((op.clone(), Spacing::parse(policy)?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { default, operators }
+    }
+
+    fn get(&self, op: &str) -> Spacing {
+        self.operators.get(op).copied().unwrap_or(self.default)
+    }
+}
+
+struct SpacingContext<'a> {
+    chars: &'a [char],
+    byte_offsets: &'a [usize],
+    policies: &'a Policies,
+    /// Byte offsets of operators already handled by alignment
+    aligned: &'a HashSet<usize>,
+}
+
+impl SpacingContext<'_> {
+    fn operator_edit(&self, pos: usize, op_len: usize, op: &str) -> Vec<Edit> {
+        if self.aligned.contains(&self.byte_offsets[pos]) {
+            return Vec::new();
+        }
+        match self.policies.get(op).unaligned() {
+            Spacing::SingleSpace => exact_spacing(self.chars, self.byte_offsets, pos, op_len, op, " "),
+            Spacing::NoSpace => exact_spacing(self.chars, self.byte_offsets, pos, op_len, op, ""),
+            _ => check_operator_spacing(self.chars, self.byte_offsets, pos, op_len, op).into_iter().collect(),
+        }
+    }
+}
+
+/// Set the spacing on both sides of an operator to exactly `space`
+///
+/// A side whose whitespace contains a line break is left alone.
+fn exact_spacing(chars: &[char], byte_offsets: &[usize], pos: usize, op_len: usize, op: &str, space: &str) -> Vec<Edit> {
+    let is_blank = |c: char| c == ' ' || c == '\t';
+    let op_end = pos + op_len;
+
+    let mut start = pos;
+    while start > 0 && is_blank(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = op_end;
+    while end < chars.len() && is_blank(chars[end]) {
+        end += 1;
+    }
+
+    let at_line_start = start == 0 || chars[start - 1] == '\n' || chars[start - 1] == '\r';
+    let at_line_end = end >= chars.len() || chars[end] == '\n' || chars[end] == '\r';
+    let fix_before = !at_line_start && !chars[start..pos].iter().copied().eq(space.chars());
+    let fix_after = !at_line_end && !chars[op_end..end].iter().copied().eq(space.chars());
+
+    if start == pos && fix_before {
+        // Nothing to remove before the operator, so one edit covers both sides
+        let after: String = if fix_after { space.to_string() } else { chars[op_end..end].iter().collect() };
+        return vec![edit_with_rule(
+            byte_offsets[pos],
+            byte_offsets[end],
+            format!("{}{}{}", space, op, after),
+            format!("Fix spacing around {} operator", op),
+            "binary_operator_spaces",
+        )];
+    }
+
+    // Whitespace-only edits, so the leading whitespace isn't preserved when applied
+    let mut edits = Vec::new();
+    if fix_before {
+        edits.push(whitespace_edit(byte_offsets[start], byte_offsets[pos], space, op));
+    }
+    if fix_after {
+        edits.push(whitespace_edit(byte_offsets[op_end], byte_offsets[end], space, op));
+    }
+    edits
+}
+
+/// Replace the whitespace between `start` and `end` next to an operator
+fn whitespace_edit(start: usize, end: usize, space: &str, op: &str) -> Edit {
+    edit_with_rule(
+        start,
+        end,
+        space.to_string(),
+        format!("Fix spacing around {} operator", op),
+        "binary_operator_spaces",
+    )
+}
+
+/// What a line contributes to alignment groups
+#[derive(Debug, Default, Clone)]
+struct LineInfo {
+    /// Nesting depth of the line, if it has code
+    depth: Option<usize>,
+    /// The operators at the line's own depth
+    candidates: Vec<usize>,
+    /// Whether a token starts on this line
+    has_tokens: bool,
+    /// Whether the line starts by closing a bracket, continuing an earlier statement
+    closes_scope: bool,
+}
+
+/// Align `op` across consecutive lines, recording aligned operator offsets
+fn align_operator(
+    source: &str,
+    tokens: &TokenStream,
+    kind: TokenKind,
+    op: &str,
+    align: Align,
+    aligned: &mut HashSet<usize>,
+) -> Vec<Edit> {
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let line_of = |offset: usize| line_starts.partition_point(|&start| start <= offset) - 1;
+    let mut lines = vec![LineInfo::default(); line_starts.len()];
+    let declare_ranges = declare_ranges(source, tokens);
+
+    let mut depth = 0usize;
+    let mut code_lines = HashSet::new();
+    for token in tokens.tokens() {
+        if token.kind == TokenKind::Whitespace {
+            continue;
+        }
+        let info = &mut lines[line_of(token.start)];
+        info.has_tokens = true;
+        if token.region != Region::Code {
+            continue;
+        }
+
+        let closing = matches!(token.kind, TokenKind::RightParenthesis | TokenKind::RightBracket | TokenKind::RightBrace);
+        if code_lines.insert(line_of(token.start)) {
+            info.depth = Some(if closing { depth.saturating_sub(1) } else { depth });
+            info.closes_scope = closing;
+        }
+        if token.kind == kind && Some(depth) == info.depth && !is_in_declare(&declare_ranges, token.start) {
+            info.candidates.push(token.start);
+        }
+
+        match token.kind {
+            TokenKind::LeftParenthesis | TokenKind::LeftBracket | TokenKind::LeftBrace
+            | TokenKind::DollarLeftBrace | TokenKind::HashLeftBracket => depth += 1,
+            _ if closing => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    let mut edits = Vec::new();
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut flush = |group: Vec<usize>, edits: &mut Vec<Edit>| {
+        if group.len() > 1 {
+            edits.extend(align_group(source, &line_starts, &group, op, align));
+            aligned.extend(group);
+        }
+    };
+
+    for (idx, info) in lines.iter().enumerate() {
+        let Some(line_depth) = info.depth else {
+            // Blank lines end every group; comments and string continuations don't
+            let line_end = line_starts.get(idx + 1).copied().unwrap_or(source.len());
+            let blank = source[line_starts[idx]..line_end].trim().is_empty()
+                && tokens.token_at(line_starts[idx]).is_none_or(|t| t.kind == TokenKind::Whitespace);
+            if blank && !info.has_tokens {
+                for (_, group) in std::mem::take(&mut groups) {
+                    flush(group, &mut edits);
+                }
+            }
+            continue;
+        };
+
+        // Groups in scopes that were closed, or that this line is nested in
+        let ended: Vec<usize> = groups
+            .keys()
+            .copied()
+            .filter(|&d| d > line_depth || (align.by_scope && d < line_depth))
+            .collect();
+        for d in ended {
+            if let Some(group) = groups.remove(&d) {
+                flush(group, &mut edits);
+            }
+        }
+
+        match info.candidates.as_slice() {
+            [candidate] => groups.entry(line_depth).or_default().push(*candidate),
+            [] if info.closes_scope => {}
+            _ => {
+                if let Some(group) = groups.remove(&line_depth) {
+                    flush(group, &mut edits);
+                }
+            }
+        }
+    }
+    for (_, group) in groups {
+        flush(group, &mut edits);
+    }
+
+    edits
+}
+
+/// Edits aligning the operators at the given offsets, each on its own line
+fn align_group(source: &str, line_starts: &[usize], group: &[usize], op: &str, align: Align) -> Vec<Edit> {
+    let column = |line_start: usize, offset: usize| source[line_start..offset].chars().count();
+
+    // (line start, end of the left-hand side, operator offset)
+    let positions: Vec<(usize, usize, usize)> = group
+        .iter()
+        .map(|&op_start| {
+            let line_start = line_starts[line_starts.partition_point(|&s| s <= op_start) - 1];
+            let lhs_end = source[..op_start].trim_end_matches([' ', '\t']).len().max(line_start);
+            (line_start, lhs_end, op_start)
+        })
+        .collect();
+
+    let target = positions
+        .iter()
+        .map(|&(line_start, lhs_end, op_start)| {
+            let minimal = column(line_start, lhs_end) + 1;
+            if align.minimal {
+                minimal
+            } else {
+                minimal.max(column(line_start, op_start))
+            }
+        })
+        .max()
+        .unwrap_or(0);
+
+    let mut edits = Vec::new();
+    for (line_start, lhs_end, op_start) in positions {
+        if lhs_end == line_start {
+            continue;
+        }
+        let op_end = op_start + op.len();
+        let rest = &source[op_end..];
+        let after_len = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        let after_end = op_end + after_len;
+        let at_line_end = rest[after_len..].is_empty() || rest[after_len..].starts_with(['\n', '\r']);
+
+        let padding = " ".repeat(target - column(line_start, lhs_end));
+        if padding != source[lhs_end..op_start] {
+            edits.push(edit_with_rule(
+                lhs_end,
+                op_start,
+                padding,
+                format!("Align {} operator", op),
+                "binary_operator_spaces",
+            ));
+        }
+        let keep_after = at_line_end || (!align.single_space && after_len > 0);
+        if !keep_after && &source[op_end..after_end] != " " {
+            edits.push(whitespace_edit(op_end, after_end, " ", op));
+        }
+    }
+    edits
+}
+
 /// Check if an operator at position `pos` with given length needs spacing, and return an Edit if so
-fn check_operator_spacing(chars: &[char], pos: usize, op_len: usize, op: &str) -> Option<Edit> {
+fn check_operator_spacing(chars: &[char], byte_offsets: &[usize], pos: usize, op_len: usize, op: &str) -> Option<Edit> {
     let len = chars.len();
     let prev = if pos > 0 { Some(chars[pos - 1]) } else { None };
     let after_pos = pos + op_len;
@@ -281,8 +648,8 @@ fn check_operator_spacing(chars: &[char], pos: usize, op_len: usize, op: &str) -
             format!("{} ", op)
         };
 
-        let byte_pos: usize = chars[..pos].iter().map(|c| c.len_utf8()).sum();
-        let byte_end: usize = chars[..after_pos].iter().map(|c| c.len_utf8()).sum();
+        let byte_pos = byte_offsets[pos];
+        let byte_end = byte_offsets[after_pos];
 
         Some(edit_with_rule(
             byte_pos,
@@ -296,70 +663,40 @@ fn check_operator_spacing(chars: &[char], pos: usize, op_len: usize, op: &str) -
     }
 }
 
-/// Check if we're inside a declare() statement (between 'declare(' and ')')
-fn is_in_declare(before: &str) -> bool {
-    let lower = before.to_lowercase();
-    if let Some(declare_pos) = lower.rfind("declare(") {
-        let after_declare = &before[declare_pos..];
+/// Byte ranges of the parentheses of `declare(...)` statements
+fn declare_ranges(source: &str, tokens: &TokenStream) -> Vec<(usize, usize)> {
+    let mut ranges = Vec::new();
+    let code: Vec<_> = tokens.tokens().iter().filter(|t| !t.kind.is_trivia()).collect();
+
+    for (idx, token) in code.iter().enumerate() {
+        if token.region != Region::Code || !token.text(source).eq_ignore_ascii_case("declare") {
+            continue;
+        }
+        let Some(open) = code.get(idx + 1).filter(|t| t.kind == TokenKind::LeftParenthesis) else {
+            continue;
+        };
         let mut depth = 0;
-        for c in after_declare.chars() {
-            if c == '(' {
-                depth += 1;
-            } else if c == ')' {
-                depth -= 1;
-                if depth == 0 {
-                    return false;
+        for inner in &code[idx + 1..] {
+            match inner.kind {
+                TokenKind::LeftParenthesis => depth += 1,
+                TokenKind::RightParenthesis => {
+                    depth -= 1;
+                    if depth == 0 {
+                        ranges.push((open.start, inner.end));
+                        break;
+                    }
                 }
+                _ => {}
             }
         }
-        return depth > 0;
     }
-    false
-}
-
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
 
-        prev_char = c;
-    }
+    ranges
+}
 
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
+/// Check if we're inside a declare() statement (between 'declare(' and ')')
+fn is_in_declare(declare_ranges: &[(usize, usize)], pos: usize) -> bool {
+    declare_ranges.iter().any(|&(start, end)| start < pos && pos < end)
 }
 
 #[cfg(test)]
@@ -578,4 +915,63 @@ mod tests {
         let edits = check("<?php\n$a += 1; $b -= 1; $c == $d; $e => $f;\n");
         assert!(edits.is_empty());
     }
+
+    fn fix_with(source: &str, options: &[(&str, ConfigValue)]) -> String {
+        let config = FixerConfig {
+            options: options.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            ..Default::default()
+        };
+        let edits = BinaryOperatorSpacesFixer.check(source, &config);
+        rustor_core::apply_edits(source, &edits).unwrap()
+    }
+
+    fn policy(name: &str) -> ConfigValue {
+        ConfigValue::String(name.to_string())
+    }
+
+    #[test]
+    fn test_single_space_collapses_extra_spaces() {
+        assert_eq!(fix_with("<?php\n$a  =   1;\n$b = $c  ===  $d;\n", &[]), "<?php\n$a = 1;\n$b = $c === $d;\n");
+        assert_eq!(
+            fix_with("<?php\n$a  =   1;\n", &[("default", policy("at_least_single_space"))]),
+            "<?php\n$a  =   1;\n"
+        );
+    }
+
+    #[test]
+    fn test_operator_policies() {
+        let operators: HashMap<String, String> = [("===".to_string(), "no_space".to_string())].into_iter().collect();
+        assert_eq!(
+            fix_with("<?php\n$a=$b === $c;\n", &[("operators", ConfigValue::StringMap(operators))]),
+            "<?php\n$a = $b===$c;\n"
+        );
+    }
+
+    #[test]
+    fn test_align_single_space_minimal() {
+        let source = "<?php\n$a = 1;\n$long    = 2;\n\n$b  =  3;\n$x = [\n    'k' => 1,\n    'key'=>2,\n];\n";
+        let operators: HashMap<String, String> = [
+            ("=".to_string(), "align_single_space_minimal".to_string()),
+            ("=>".to_string(), "align_single_space_minimal".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            fix_with(source, &[("operators", ConfigValue::StringMap(operators))]),
+            "<?php\n$a    = 1;\n$long = 2;\n\n$b = 3;\n$x = [\n    'k'   => 1,\n    'key' => 2,\n];\n"
+        );
+    }
+
+    #[test]
+    fn test_align_keeps_rightmost_column_and_scope() {
+        let source = "<?php\n$a = 1;\n$bb    = 2;\n$c = [\n    1,\n];\n$d = 3;\n";
+        assert_eq!(
+            fix_with(source, &[("default", policy("align"))]),
+            "<?php\n$a     = 1;\n$bb    = 2;\n$c     = [\n    1,\n];\n$d     = 3;\n"
+        );
+        assert_eq!(
+            fix_with(source, &[("default", policy("align_single_space_minimal_by_scope"))]),
+            "<?php\n$a  = 1;\n$bb = 2;\n$c  = [\n    1,\n];\n$d = 3;\n"
+        );
+    }
 }
@@ -2,6 +2,7 @@
 
 use rustor_core::Edit;
 use crate::fixers::{Fixer, FixerConfig, ConfigValue, FixerOption, OptionType, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Configures spacing around the `.` concatenation operator
 pub struct ConcatSpaceFixer;
@@ -33,6 +34,10 @@ impl Fixer for ConcatSpaceFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
         let use_space = config.options.get("spacing")
             .and_then(|v| match v {
                 ConfigValue::String(s) => Some(s.as_str()),
@@ -48,7 +53,7 @@ impl Fixer for ConcatSpaceFixer {
         while i < bytes.len() {
             if bytes[i] == b'.' {
                 // Check if this is a concat operator (not a decimal point or method call)
-                if is_concat_operator(source, i) && !is_in_string_or_comment(source, i) {
+                if is_concat_operator(source, i) && !tokens.is_in_string_or_comment(i) {
                     let (space_before, space_after) = get_surrounding_spaces(source, i);
 
                     if use_space {
@@ -155,53 +160,6 @@ fn get_surrounding_spaces(source: &str, i: usize) -> (usize, usize) {
     (space_before, space_after)
 }
 
-/// Check if position is inside a string or comment
-fn is_in_string_or_comment(source: &str, pos: usize) -> bool {
-    let before = &source[..pos];
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
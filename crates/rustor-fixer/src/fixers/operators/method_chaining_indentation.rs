@@ -1,10 +1,15 @@
 //! Fix method chaining indentation
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig};
+use crate::fixers::layout::{Layout, Syntax};
+use crate::tokens::TokenStream;
 
 /// Ensures method chains have proper indentation
+///
+/// A `->` or `?->` starting a line is indented once past the line the chain
+/// starts on; closures and arrays passed along the chain are indented
+/// relative to it.
 pub struct MethodChainingIndentationFixer;
 
 impl Fixer for MethodChainingIndentationFixer {
@@ -25,73 +30,24 @@ impl Fixer for MethodChainingIndentationFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-        let indent_str = config.indent.as_str();
-
-        // Match method chains where -> is at the start of a new line
-        // The line should be indented relative to the first line
-        let chain_re = Regex::new(r"(?m)^([ \t]*)(->)(\w+)").unwrap();
-
-        let lines: Vec<&str> = source.lines().collect();
-
-        for (line_idx, line) in lines.iter().enumerate() {
-            // Check if this line starts with whitespace + ->
-            if let Some(cap) = chain_re.captures(line) {
-                let current_indent = cap.get(1).unwrap().as_str();
-                let arrow = cap.get(2).unwrap();
-                let method = cap.get(3).unwrap().as_str();
-
-                // Find the base indent (from the line that started the chain)
-                if let Some(base_indent) = find_chain_base_indent(&lines, line_idx) {
-                    // Expected indent is base + one level
-                    let expected_indent = format!("{}{}", base_indent, indent_str);
-
-                    if current_indent != expected_indent {
-                        // Calculate the position in the source
-                        let line_start = lines[..line_idx]
-                            .iter()
-                            .map(|l| l.len() + 1) // +1 for newline
-                            .sum::<usize>();
-
-                        let match_end = line_start + arrow.end() + method.len();
-
-                        edits.push(edit_with_rule(
-                            line_start,
-                            match_end,
-                            format!("{}->{}", expected_indent, method),
-                            "Fix method chain indentation".to_string(),
-                            "method_chaining_indentation",
-                        ));
-                    }
-                }
-            }
-        }
-
-        edits
+        self.check_tokens(source, &TokenStream::new(source), config)
     }
-}
 
-fn find_chain_base_indent(lines: &[&str], current_line: usize) -> Option<String> {
-    // Look backwards for the line that started this chain
-    // It's the first line that doesn't start with whitespace + ->
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let Some(syntax) = Syntax::parse(source) else {
+            return Vec::new();
+        };
 
-    for i in (0..current_line).rev() {
-        let line = lines[i].trim_start();
-
-        // Skip empty lines
-        if line.is_empty() {
-            continue;
+        let mut layout = Layout::new(source, tokens, config);
+        for link in &syntax.links {
+            if layout.is_line_start(link.arrow) {
+                layout.indent(link.arrow, link.root, 1, "Fix method chain indentation");
+                layout.reindent(link.arrow..link.end);
+            }
         }
 
-        // If this line doesn't start with ->, it's our base
-        if !line.starts_with("->") {
-            // Get the indent of this line
-            let indent_len = lines[i].len() - lines[i].trim_start().len();
-            return Some(lines[i][..indent_len].to_string());
-        }
+        layout.edits("method_chaining_indentation", "Fix method chain indentation")
     }
-
-    None
 }
 
 #[cfg(test)]
@@ -107,6 +63,10 @@ mod tests {
         })
     }
 
+    fn fix(source: &str) -> String {
+        rustor_core::apply_edits(source, &check(source)).unwrap()
+    }
+
     #[test]
     fn test_correct_unchanged() {
         let source = "<?php\n$a\n    ->foo()\n    ->bar();\n";
@@ -120,7 +80,7 @@ mod tests {
         let edits = check(source);
 
         assert_eq!(edits.len(), 1);
-        assert!(edits[0].replacement.contains("    ->foo"));
+        assert_eq!(fix(source), "<?php\n$a\n    ->foo();\n");
     }
 
     #[test]
@@ -132,16 +92,54 @@ mod tests {
     }
 
     #[test]
-    fn test_find_base_indent() {
-        let lines = vec!["<?php", "$a = $b", "    ->foo()", "    ->bar();"];
-        let base = find_chain_base_indent(&lines, 2);
-        assert_eq!(base, Some("".to_string()));
+    fn test_base_is_chain_start_line() {
+        let source = "<?php\n$a = $b\n        ->foo()\n  ->bar();\n";
+        assert_eq!(fix(source), "<?php\n$a = $b\n    ->foo()\n    ->bar();\n");
     }
 
     #[test]
     fn test_indented_base() {
-        let lines = vec!["<?php", "    $a = $b", "        ->foo()"];
-        let base = find_chain_base_indent(&lines, 2);
-        assert_eq!(base, Some("    ".to_string()));
+        let source = "<?php\nfunction f() {\n    $a = $b\n    ->foo();\n    return $a;\n}\n";
+        assert_eq!(fix(source), "<?php\nfunction f() {\n    $a = $b\n        ->foo();\n    return $a;\n}\n");
+    }
+
+    #[test]
+    fn test_null_safe_and_property_access() {
+        let source = "<?php\n$a\n?->foo\n->bar();\n";
+        assert_eq!(fix(source), "<?php\n$a\n    ?->foo\n    ->bar();\n");
+    }
+
+    #[test]
+    fn test_closure_argument_moves_with_link() {
+        let source = "<?php\n$q\n->then(function () {\n    return 1;\n})\n->done();\n";
+        assert_eq!(fix(source), "<?php\n$q\n    ->then(function () {\n        return 1;\n    })\n    ->done();\n");
+    }
+
+    #[test]
+    fn test_misindented_closure_body() {
+        let source = "<?php\n$foo\n    ->bar()\n->baz(function () {\n    return 1;\n        });\n";
+        assert_eq!(
+            fix(source),
+            "<?php\n$foo\n    ->bar()\n    ->baz(function () {\n        return 1;\n    });\n"
+        );
+
+        // Continuation lines keep their offset, nested chains their own indent
+        let source = "<?php\n$a\n->b(function () {\n  $x = 1\n      + 2;\n  return $c\n  ->d();\n    }, [\n1,\n]);\n";
+        assert_eq!(
+            fix(source),
+            "<?php\n$a\n    ->b(function () {\n        $x = 1\n            + 2;\n        return $c\n            ->d();\n    }, [\n        1,\n    ]);\n"
+        );
+    }
+
+    #[test]
+    fn test_chain_inside_arguments() {
+        let source = "<?php\nfoo(\n    $a\n    ->bar()\n);\n";
+        assert_eq!(fix(source), "<?php\nfoo(\n    $a\n        ->bar()\n);\n");
+    }
+
+    #[test]
+    fn test_arrow_in_string_ignored() {
+        let source = "<?php\n$a = '\n->foo';\n";
+        assert!(check(source).is_empty());
     }
 }
@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures new expressions include parentheses
 pub struct NewWithParenthesesFixer;
@@ -26,7 +27,11 @@ impl Fixer for NewWithParenthesesFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match `new ClassName` - we'll check for parentheses manually
@@ -36,13 +41,8 @@ impl Fixer for NewWithParenthesesFixer {
             let full_match = cap.get(0).unwrap();
             let class_name = cap.get(1).unwrap();
 
-            // Skip if in string
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            // Skip if in comment
-            if is_in_comment(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -76,39 +76,6 @@ impl Fixer for NewWithParenthesesFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn is_in_comment(before: &str) -> bool {
-    if let Some(last_line_start) = before.rfind('\n') {
-        let last_line = &before[last_line_start..];
-        if last_line.contains("//") || last_line.contains('#') {
-            return true;
-        }
-    } else if before.contains("//") {
-        return true;
-    }
-
-    let open_count = before.matches("/*").count();
-    let close_count = before.matches("*/").count();
-    open_count > close_count
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
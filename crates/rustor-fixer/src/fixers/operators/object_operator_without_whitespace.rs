@@ -5,6 +5,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Removes whitespace around the object operator
 pub struct ObjectOperatorWithoutWhitespaceFixer;
@@ -26,20 +27,20 @@ impl Fixer for ObjectOperatorWithoutWhitespaceFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match space before ->
         // $obj -> method -> $obj->method
-        let before_re = Regex::new(r"(\$\w+|\)|\])\s+->").unwrap();
+        let before_re = Regex::new(r"(\$\w+|\)|\])[ \t]+->").unwrap();
         for cap in before_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            if is_in_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -55,15 +56,11 @@ impl Fixer for ObjectOperatorWithoutWhitespaceFixer {
 
         // Match space after ->
         // $obj-> method -> $obj->method
-        let after_re = Regex::new(r"->\s+(\w)").unwrap();
+        let after_re = Regex::new(r"->[ \t]+(\w)").unwrap();
         for cap in after_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            if is_in_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -78,15 +75,11 @@ impl Fixer for ObjectOperatorWithoutWhitespaceFixer {
         }
 
         // Also handle nullsafe operator ?->
-        let nullsafe_before_re = Regex::new(r"(\$\w+|\)|\])\s+\?->").unwrap();
+        let nullsafe_before_re = Regex::new(r"(\$\w+|\)|\])[ \t]+\?->").unwrap();
         for cap in nullsafe_before_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            if is_in_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -100,15 +93,11 @@ impl Fixer for ObjectOperatorWithoutWhitespaceFixer {
             ));
         }
 
-        let nullsafe_after_re = Regex::new(r"\?->\s+(\w)").unwrap();
+        let nullsafe_after_re = Regex::new(r"\?->[ \t]+(\w)").unwrap();
         for cap in nullsafe_after_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            if is_in_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -126,39 +115,6 @@ impl Fixer for ObjectOperatorWithoutWhitespaceFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn is_in_comment(before: &str) -> bool {
-    if let Some(last_line_start) = before.rfind('\n') {
-        let last_line = &before[last_line_start..];
-        if last_line.contains("//") || last_line.contains('#') {
-            return true;
-        }
-    } else if before.contains("//") {
-        return true;
-    }
-
-    let open_count = before.matches("/*").count();
-    let close_count = before.matches("*/").count();
-    open_count > close_count
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,4 +185,11 @@ mod tests {
         let edits = check(source);
         assert!(edits.is_empty());
     }
+
+    #[test]
+    fn test_multiline_chain_kept() {
+        let source = "<?php\n$obj\n    ->foo()\n    ?->bar();";
+        let edits = check(source);
+        assert!(edits.is_empty());
+    }
 }
@@ -6,6 +6,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures proper spacing around ternary operator
 pub struct TernaryOperatorSpacesFixer;
@@ -27,7 +28,11 @@ impl Fixer for TernaryOperatorSpacesFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // This is a conservative implementation.
@@ -43,7 +48,7 @@ impl Fixer for TernaryOperatorSpacesFixer {
         for cap in var_q_var.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -64,7 +69,7 @@ impl Fixer for TernaryOperatorSpacesFixer {
         for cap in bool_q.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -85,7 +90,7 @@ impl Fixer for TernaryOperatorSpacesFixer {
         for cap in paren_q.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -107,7 +112,7 @@ impl Fixer for TernaryOperatorSpacesFixer {
         for cap in elvis_no_space.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -159,7 +164,7 @@ impl Fixer for TernaryOperatorSpacesFixer {
             let full_match = cap.get(0).unwrap();
             let match_str = full_match.as_str();
 
-            if is_in_string(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -197,24 +202,6 @@ impl Fixer for TernaryOperatorSpacesFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -265,6 +252,13 @@ mod tests {
         assert!(edits.is_empty());
     }
 
+    #[test]
+    fn test_skip_in_comment_and_heredoc() {
+        let source = "<?php\n// $b?$c:$d\n$a = <<<EOT\n$b?$c:$d\nEOT;\n";
+        let edits = check(source);
+        assert!(edits.is_empty());
+    }
+
     #[test]
     fn test_paren_then_question() {
         let source = "<?php\n$a = ($b)?$c : $d;";
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Ensures no space after unary operators (!, ++, --, ~)
 pub struct UnaryOperatorSpacesFixer;
@@ -24,7 +25,11 @@ impl Fixer for UnaryOperatorSpacesFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match ! followed by space(s) then variable/expression
@@ -34,7 +39,7 @@ impl Fixer for UnaryOperatorSpacesFixer {
         for cap in not_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string_or_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -64,7 +69,7 @@ impl Fixer for UnaryOperatorSpacesFixer {
         for cap in tilde_re.captures_iter(source) {
             let full_match = cap.get(0).unwrap();
 
-            if is_in_string_or_comment(&source[..full_match.start()]) {
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -85,7 +90,7 @@ impl Fixer for UnaryOperatorSpacesFixer {
         let inc_re = Regex::new(r"\+\+\s+\$").unwrap();
 
         for mat in inc_re.find_iter(source) {
-            if is_in_string_or_comment(&source[..mat.start()]) {
+            if tokens.is_in_string_or_comment(mat.start()) {
                 continue;
             }
 
@@ -101,7 +106,7 @@ impl Fixer for UnaryOperatorSpacesFixer {
         let dec_re = Regex::new(r"--\s+\$").unwrap();
 
         for mat in dec_re.find_iter(source) {
-            if is_in_string_or_comment(&source[..mat.start()]) {
+            if tokens.is_in_string_or_comment(mat.start()) {
                 continue;
             }
 
@@ -118,51 +123,6 @@ impl Fixer for UnaryOperatorSpacesFixer {
     }
 }
 
-fn is_in_string_or_comment(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut in_line_comment = false;
-    let mut in_block_comment = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if !in_single_quote && !in_double_quote && !in_block_comment {
-            if c == '/' && prev_char == '/' {
-                in_line_comment = true;
-            }
-            if c == '#' {
-                in_line_comment = true;
-            }
-        }
-
-        if !in_single_quote && !in_double_quote && !in_line_comment {
-            if c == '*' && prev_char == '/' {
-                in_block_comment = true;
-            }
-            if c == '/' && prev_char == '*' && in_block_comment {
-                in_block_comment = false;
-            }
-        }
-
-        if c == '\n' {
-            in_line_comment = false;
-        }
-
-        if !in_line_comment && !in_block_comment {
-            if c == '\'' && prev_char != '\\' && !in_double_quote {
-                in_single_quote = !in_single_quote;
-            }
-            if c == '"' && prev_char != '\\' && !in_single_quote {
-                in_double_quote = !in_double_quote;
-            }
-        }
-
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote || in_line_comment || in_block_comment
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,183 @@
+//! Validation of fixer options against their declarations
+
+use std::collections::HashMap;
+use thiserror::Error;
+
+use super::{ConfigValue, Fixer, FixerOption, OptionType};
+
+/// An invalid option in a fixer's configuration
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OptionError {
+    #[error("[{fixer}] Unknown option \"{option}\". Available options: {available}")]
+    UnknownOption {
+        fixer: String,
+        option: String,
+        available: String,
+    },
+    #[error("[{fixer}] Option \"{option}\" must be {expected}")]
+    InvalidType {
+        fixer: String,
+        option: String,
+        expected: String,
+    },
+    #[error("[{fixer}] Invalid value \"{value}\" for option \"{option}\". Allowed values: {allowed}")]
+    InvalidValue {
+        fixer: String,
+        option: String,
+        value: String,
+        allowed: String,
+    },
+}
+
+/// Validate options against the options a fixer declares
+///
+/// A fixer that declares no options takes none, so any option is unknown.
+pub fn validate_options(fixer: &dyn Fixer, options: &HashMap<String, ConfigValue>) -> Vec<OptionError> {
+    let declared = fixer.options();
+    let fixer_name = fixer.php_cs_fixer_name();
+    let mut names: Vec<&String> = options.keys().collect();
+    names.sort();
+
+    let mut errors = Vec::new();
+    for name in names {
+        let value = &options[name];
+        match declared.iter().find(|o| o.name == name) {
+            Some(option) => errors.extend(validate_value(fixer_name, option, value)),
+            None => errors.push(OptionError::UnknownOption {
+                fixer: fixer_name.to_string(),
+                option: name.clone(),
+                available: if declared.is_empty() {
+                    "none".to_string()
+                } else {
+                    declared.iter().map(|o| format!("\"{}\"", o.name)).collect::<Vec<_>>().join(", ")
+                },
+            }),
+        }
+    }
+    errors
+}
+
+fn validate_value(fixer: &str, option: &FixerOption, value: &ConfigValue) -> Option<OptionError> {
+    let invalid_type = |expected: &str| {
+        Some(OptionError::InvalidType {
+            fixer: fixer.to_string(),
+            option: option.name.to_string(),
+            expected: expected.to_string(),
+        })
+    };
+    let invalid_value = |value: &str, allowed: &[&str]| {
+        Some(OptionError::InvalidValue {
+            fixer: fixer.to_string(),
+            option: option.name.to_string(),
+            value: value.to_string(),
+            allowed: allowed.iter().map(|a| format!("\"{}\"", a)).collect::<Vec<_>>().join(", "),
+        })
+    };
+    let is_empty_array = matches!(value, ConfigValue::Array(values) if values.is_empty());
+
+    match (&option.option_type, value) {
+        (OptionType::Bool, ConfigValue::Bool(_)) => None,
+        (OptionType::Bool, _) => invalid_type("a bool"),
+        (OptionType::String, ConfigValue::String(_)) => None,
+        (OptionType::String, _) => invalid_type("a string"),
+        (OptionType::Number, ConfigValue::Number(_)) => None,
+        (OptionType::Number, _) => invalid_type("an int"),
+        (OptionType::StringArray, ConfigValue::Array(_)) => None,
+        (OptionType::StringArray, _) => invalid_type("an array of strings"),
+        (OptionType::Enum(allowed), ConfigValue::String(s)) => {
+            if allowed.contains(&s.as_str()) {
+                None
+            } else {
+                invalid_value(s, allowed)
+            }
+        }
+        (OptionType::Enum(_), _) => invalid_type("a string"),
+        (OptionType::EnumArray(allowed), ConfigValue::Array(values)) => values
+            .iter()
+            .find(|v| !allowed.contains(&v.as_str()))
+            .and_then(|v| invalid_value(v, allowed)),
+        (OptionType::EnumArray(_), _) => invalid_type("an array of strings"),
+        (OptionType::StringMap, ConfigValue::StringMap(_)) => None,
+        (OptionType::StringMap, _) if is_empty_array => None,
+        (OptionType::StringMap, _) => invalid_type("an array map"),
+        (OptionType::EnumMap(keys, values), ConfigValue::StringMap(map)) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort();
+            entries.into_iter().find_map(|(k, v)| {
+                if !keys.is_empty() && !keys.contains(&k.as_str()) {
+                    invalid_value(k, keys)
+                } else if !values.contains(&v.as_str()) {
+                    invalid_value(v, values)
+                } else {
+                    None
+                }
+            })
+        }
+        (OptionType::EnumMap(..), _) if is_empty_array => None,
+        (OptionType::EnumMap(..), _) => invalid_type("an array map"),
+        (OptionType::NumberOrMap, ConfigValue::Number(_)) => None,
+        (OptionType::NumberOrMap, ConfigValue::StringMap(map)) => {
+            if map.values().all(|v| v.parse::<i64>().is_ok()) {
+                None
+            } else {
+                invalid_type("an int or an array of ints")
+            }
+        }
+        (OptionType::NumberOrMap, _) => invalid_type("an int or an array of ints"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixers::imports::OrderedImportsFixer;
+    use crate::fixers::operators::BinaryOperatorSpacesFixer;
+    use crate::fixers::whitespace::TrailingWhitespaceFixer;
+
+    #[test]
+    fn test_unknown_and_invalid_options() {
+        let mut options = HashMap::new();
+        options.insert("sort_algorithm".to_string(), ConfigValue::String("random".to_string()));
+        options.insert("imports_order".to_string(), ConfigValue::Bool(true));
+        options.insert("sort".to_string(), ConfigValue::Bool(true));
+
+        let errors = validate_options(&OrderedImportsFixer, &options);
+        assert_eq!(errors.len(), 3);
+        assert_eq!(
+            errors[0].to_string(),
+            "[ordered_imports] Option \"imports_order\" must be an array of strings"
+        );
+        assert!(matches!(&errors[1], OptionError::UnknownOption { option, .. } if option == "sort"));
+        assert_eq!(
+            errors[2].to_string(),
+            "[ordered_imports] Invalid value \"random\" for option \"sort_algorithm\". Allowed values: \"alpha\", \"length\", \"none\""
+        );
+    }
+
+    #[test]
+    fn test_map_options() {
+        let mut operators = HashMap::new();
+        operators.insert("=>".to_string(), "align_single_space_minimal".to_string());
+        let mut options = HashMap::new();
+        options.insert("operators".to_string(), ConfigValue::StringMap(operators.clone()));
+        assert!(validate_options(&BinaryOperatorSpacesFixer, &options).is_empty());
+
+        operators.insert("=".to_string(), "sideways".to_string());
+        options.insert("operators".to_string(), ConfigValue::StringMap(operators));
+        let errors = validate_options(&BinaryOperatorSpacesFixer, &options);
+        assert!(matches!(&errors[..], [OptionError::InvalidValue { value, .. }] if value == "sideways"));
+    }
+
+    #[test]
+    fn test_fixers_without_declared_options() {
+        assert!(validate_options(&TrailingWhitespaceFixer, &HashMap::new()).is_empty());
+
+        let mut options = HashMap::new();
+        options.insert("typo".to_string(), ConfigValue::Bool(true));
+        let errors = validate_options(&TrailingWhitespaceFixer, &options);
+        assert_eq!(
+            errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+            vec!["[no_trailing_whitespace] Unknown option \"typo\". Available options: none"]
+        );
+    }
+}
@@ -2,7 +2,9 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, ConfigValue, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+
+const DEFAULT_ANNOTATIONS: &[&str] = &["author", "package", "subpackage"];
 
 pub struct GeneralPhpdocAnnotationRemoveFixer;
 
@@ -12,13 +14,22 @@ impl Fixer for GeneralPhpdocAnnotationRemoveFixer {
     fn description(&self) -> &'static str { "Remove specific PHPDoc annotations" }
     fn priority(&self) -> i32 { 20 }
 
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "annotations",
+            description: "Annotations to remove, without the @",
+            option_type: OptionType::StringArray,
+            default: Some(ConfigValue::Array(DEFAULT_ANNOTATIONS.iter().map(|s| s.to_string()).collect())),
+        }]
+    }
+
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Get annotations to remove from config
         let annotations_to_remove = match config.options.get("annotations") {
             Some(ConfigValue::Array(arr)) => arr.clone(),
-            _ => vec!["author".to_string(), "package".to_string(), "subpackage".to_string()],
+            _ => DEFAULT_ANNOTATIONS.iter().map(|s| s.to_string()).collect(),
         };
 
         for annotation in &annotations_to_remove {
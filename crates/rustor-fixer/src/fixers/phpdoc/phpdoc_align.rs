@@ -1,64 +1,258 @@
 //! Align PHPDoc tags
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use mago_syntax::token::TokenKind;
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct PhpdocAlignFixer;
 
+const DEFAULT_TAGS: &[&str] = &[
+    "method", "param", "property", "property-read", "property-write", "return", "throws", "type", "var",
+];
+
 impl Fixer for PhpdocAlignFixer {
     fn name(&self) -> &'static str { "phpdoc_align" }
     fn php_cs_fixer_name(&self) -> &'static str { "phpdoc_align" }
     fn description(&self) -> &'static str { "Align PHPDoc tags vertically" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![
+            FixerOption {
+                name: "align",
+                description: "Whether to align vertically or only fix spacing: vertical, left",
+                option_type: OptionType::Enum(vec!["vertical", "left"]),
+                default: Some(ConfigValue::String("vertical".to_string())),
+            },
+            FixerOption {
+                name: "tags",
+                description: "Tags to align",
+                option_type: OptionType::StringArray,
+                default: Some(ConfigValue::Array(DEFAULT_TAGS.iter().map(|s| s.to_string()).collect())),
+            },
+            FixerOption {
+                name: "spacing",
+                description: "Spaces between columns, or a map of tag name (or `_default`) to spaces",
+                option_type: OptionType::NumberOrMap,
+                default: Some(ConfigValue::Number(1)),
+            },
+        ]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let vertical = config.get_string("align") != Some("left");
+        let tags: Vec<&str> = match config.get_array("tags") {
+            Some(tags) => tags.iter().map(|s| s.as_str()).collect(),
+            None => DEFAULT_TAGS.to_vec(),
+        };
+        let spacing = Spacing::from_config(config);
         let mut edits = Vec::new();
-        let doc_re = Regex::new(r"(?ms)/\*\*.*?\*/").unwrap();
-
-        for doc_match in doc_re.find_iter(source) {
-            let doc = doc_match.as_str();
-            let start = doc_match.start();
-
-            // Find @param, @return, @throws, @var tags
-            let tag_re = Regex::new(r"(?m)^(\s*\*\s*)(@(?:param|return|throws|var))\s+(\S+)").unwrap();
-            let tags: Vec<_> = tag_re.captures_iter(doc).collect();
-
-            if tags.len() < 2 { continue; }
-
-            // Calculate max lengths for alignment
-            let max_tag_len = tags.iter()
-                .map(|c| c.get(2).unwrap().as_str().len())
-                .max()
-                .unwrap_or(0);
-
-            for cap in tag_re.captures_iter(doc) {
-                let full = cap.get(0).unwrap();
-                let prefix = cap.get(1).unwrap().as_str();
-                let tag = cap.get(2).unwrap().as_str();
-                let rest = cap.get(3).unwrap().as_str();
-
-                let padding = " ".repeat(max_tag_len - tag.len() + 1);
-                let new_text = format!("{}{}{}{}", prefix, tag, padding, rest);
-
-                if full.as_str() != new_text {
-                    edits.push(edit_with_rule(
-                        start + full.start(), start + full.end(),
-                        new_text,
-                        "Align PHPDoc tags".to_string(),
-                        "phpdoc_align",
-                    ));
+
+        for doc in tokens.tokens().iter().filter(|t| t.kind == TokenKind::DocBlockComment) {
+            // Consecutive lines with tags to align
+            let mut group: Vec<TagLine> = Vec::new();
+            let mut offset = doc.start;
+            for line in doc.text(source).split_inclusive('\n') {
+                match TagLine::parse(line, offset, &tags) {
+                    Some(tag_line) => group.push(tag_line),
+                    None => edits.extend(align_group(std::mem::take(&mut group), vertical, &spacing)),
                 }
+                offset += line.len();
             }
+            edits.extend(align_group(group, vertical, &spacing));
         }
 
         edits
     }
 }
 
+/// The `spacing` option
+struct Spacing {
+    default: usize,
+    per_tag: std::collections::HashMap<String, usize>,
+}
+
+impl Spacing {
+    fn from_config(config: &FixerConfig) -> Self {
+        if let Some(n) = config.get_number("spacing") {
+            return Self { default: n.max(0) as usize, per_tag: Default::default() };
+        }
+        let mut per_tag: std::collections::HashMap<String, usize> = config
+            .get_map("spacing")
+            .map(|map| map.iter().filter_map(|(k, v)| Some((k.clone(), v.parse().ok()?))).collect())
+            .unwrap_or_default();
+        let default = per_tag.remove("_default").unwrap_or(1);
+        Self { default, per_tag }
+    }
+
+    fn get(&self, tag: &str) -> usize {
+        self.per_tag.get(tag).copied().unwrap_or(self.default)
+    }
+}
+
+/// A docblock line with a tag, split into columns
+#[derive(Debug)]
+struct TagLine {
+    /// Offset of the `@`
+    start: usize,
+    /// Offset of the end of the line's content
+    end: usize,
+    /// The text from the tag to the end of the line
+    original: String,
+    tag: String,
+    hint: String,
+    variable: String,
+    description: String,
+}
+
+impl TagLine {
+    fn parse(line: &str, offset: usize, tags: &[&str]) -> Option<Self> {
+        let content = line.trim_end_matches(['\n', '\r']);
+        let content = content.strip_suffix("*/").unwrap_or(content).trim_end();
+        let at = content.find('@')?;
+        if !content[..at].trim_start().starts_with('*') || content[..at].trim_start().trim_start_matches('*').trim() != "" {
+            return None;
+        }
+
+        let rest = &content[at + 1..];
+        let tag_len = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let tag = &rest[..tag_len];
+        if !tags.contains(&tag) {
+            return None;
+        }
+
+        let mut words = Words(rest[tag_len..].trim_start());
+        let (hint, variable) = match tag {
+            "method" => {
+                // `[static] [type] name(args)`: everything before the signature is the type
+                let mut hint = Vec::new();
+                let mut signature = String::new();
+                while let Some(word) = words.next_word() {
+                    if word.contains('(') {
+                        signature = word.to_string();
+                        break;
+                    }
+                    hint.push(word);
+                }
+                (hint.join(" "), signature)
+            }
+            "return" | "throws" => (words.next_word().unwrap_or_default().to_string(), String::new()),
+            _ => {
+                let first = words.next_word().unwrap_or_default();
+                if is_variable(first) {
+                    (String::new(), first.to_string())
+                } else {
+                    let mut variable = "";
+                    if words.peek_word().is_some_and(is_variable) {
+                        variable = words.next_word().unwrap_or_default();
+                    }
+                    (first.to_string(), variable.to_string())
+                }
+            }
+        };
+
+        Some(Self {
+            start: offset + content[..at].len(),
+            end: offset + content.len(),
+            original: content[at..].to_string(),
+            tag: format!("@{}", tag),
+            hint,
+            variable,
+            description: words.0.trim().to_string(),
+        })
+    }
+}
+
+fn is_variable(word: &str) -> bool {
+    word.trim_start_matches(['&', '.']).starts_with('$')
+}
+
+/// Splits words, keeping brackets in types like `array<int, string>` together
+struct Words<'a>(&'a str);
+
+impl<'a> Words<'a> {
+    fn word_len(&self) -> usize {
+        let mut depth = 0i32;
+        for (i, c) in self.0.char_indices() {
+            match c {
+                '<' | '(' | '{' | '[' => depth += 1,
+                '>' | ')' | '}' | ']' => depth -= 1,
+                c if c.is_whitespace() && depth <= 0 => return i,
+                _ => {}
+            }
+        }
+        self.0.len()
+    }
+
+    fn peek_word(&self) -> Option<&'a str> {
+        let len = self.word_len();
+        (len > 0).then(|| &self.0[..len])
+    }
+
+    fn next_word(&mut self) -> Option<&'a str> {
+        let word = self.peek_word()?;
+        self.0 = self.0[word.len()..].trim_start();
+        Some(word)
+    }
+}
+
+fn align_group(group: Vec<TagLine>, vertical: bool, spacing: &Spacing) -> Vec<Edit> {
+    let width = |f: fn(&TagLine) -> &str| group.iter().map(|l| f(l).chars().count()).max().unwrap_or(0);
+    let (tag_width, hint_width, variable_width) = (width(|l| &l.tag), width(|l| &l.hint), width(|l| &l.variable));
+
+    let mut edits = Vec::new();
+    for line in &group {
+        let space = spacing.get(line.tag.trim_start_matches('@'));
+        let mut columns: Vec<(&str, usize)> = vec![(&line.tag, tag_width)];
+        if !line.hint.is_empty() || (vertical && hint_width > 0) {
+            columns.push((&line.hint, hint_width));
+        }
+        if !line.variable.is_empty() || (vertical && variable_width > 0) {
+            columns.push((&line.variable, variable_width));
+        }
+        columns.push((&line.description, 0));
+
+        // Drop empty trailing columns, then pad each column to its width
+        while columns.len() > 1 && columns.last().is_some_and(|(text, _)| text.is_empty()) {
+            columns.pop();
+        }
+        let mut text = String::new();
+        for (idx, (column, column_width)) in columns.iter().enumerate() {
+            text.push_str(column);
+            if idx + 1 < columns.len() {
+                let padding = if vertical { column_width - column.chars().count() } else { 0 };
+                if !column.is_empty() || vertical {
+                    text.push_str(&" ".repeat(padding + space));
+                }
+            }
+        }
+
+        if text != line.original {
+            edits.push(edit_with_rule(line.start, line.end, text, "Align PHPDoc tags".to_string(), "phpdoc_align"));
+        }
+    }
+
+    edits
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn fix(code: &str, options: &[(&str, ConfigValue)]) -> String {
+        let config = FixerConfig {
+            options: options.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+            ..Default::default()
+        };
+        let edits = PhpdocAlignFixer.check(code, &config);
+        rustor_core::apply_edits(code, &edits).unwrap()
+    }
+
     #[test]
     fn test_align() {
         let code = "<?php
@@ -67,7 +261,32 @@ mod tests {
  * @return int
  */";
         let edits = PhpdocAlignFixer.check(code, &FixerConfig::default());
-        // Basic test - alignment is complex
-        assert!(edits.is_empty() || !edits.is_empty());
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].replacement, "@param  string $x");
+    }
+
+    #[test]
+    fn test_vertical_alignment() {
+        let code = "<?php\n/**\n * Summary.\n *\n * @param array<int, string> $items The items\n * @param  int $count\n * @return   bool  Whether it worked\n * @throws \\RuntimeException\n */\n";
+        assert_eq!(
+            fix(code, &[]),
+            "<?php\n/**\n * Summary.\n *\n * @param  array<int, string> $items The items\n * @param  int                $count\n * @return bool                      Whether it worked\n * @throws \\RuntimeException\n */\n"
+        );
+    }
+
+    #[test]
+    fn test_left_alignment_and_spacing() {
+        let code = "<?php\n/**\n * @param  int    $a  First\n * @return   bool\n */\n";
+        assert_eq!(
+            fix(code, &[("align", ConfigValue::String("left".to_string()))]),
+            "<?php\n/**\n * @param int $a First\n * @return bool\n */\n"
+        );
+
+        let spacing: std::collections::HashMap<String, String> =
+            [("param".to_string(), "2".to_string())].into_iter().collect();
+        assert_eq!(
+            fix(code, &[("align", ConfigValue::String("left".to_string())), ("spacing", ConfigValue::StringMap(spacing))]),
+            "<?php\n/**\n * @param  int  $a  First\n * @return bool\n */\n"
+        );
     }
 }
@@ -4,10 +4,14 @@
 //! to look them up by name and apply them in priority order.
 
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::Arc;
 use rustor_core::Edit;
+use mago_span::{Position, Span};
 
-use super::{Fixer, FixerConfig};
+use super::{ConfigValue, Fixer, FixerConfig, OptionError};
+use crate::offset_map::OffsetMap;
+use crate::tokens::TokenStream;
 use super::whitespace::{
     TrailingWhitespaceFixer,
     LineEndingFixer,
@@ -494,7 +498,17 @@ impl FixerRegistry {
     /// to the source before running the next fixer. This ensures fixers see
     /// the already-modified code, matching PHP-CS-Fixer behavior.
     ///
-    /// Returns (fixed_source, edits) where edits are for display purposes only.
+    /// The source is lexed once up front, and the token stream is updated
+    /// incrementally after each fixer's edits.
+    ///
+    /// `config.fixer_overrides` adds fixers to or removes them from
+    /// `fixer_names`. Risky fixers are skipped unless `config.risky_allowed`
+    /// is set, and edits outside `config.ranges` (in original source
+    /// offsets) are dropped.
+    ///
+    /// Returns (fixed_source, edits) where edits are for display purposes only:
+    /// their spans are mapped back to the original source, so they show where
+    /// each fixer changed the file, even when earlier fixers moved the code.
     pub fn check(
         &self,
         source: &str,
@@ -506,26 +520,39 @@ impl FixerRegistry {
         let mut all_edits = Vec::new();
         let mut current_source = source.to_string();
 
-        // Get fixers in priority order
-        let mut fixers_to_run: Vec<_> = fixer_names
-            .iter()
-            .filter_map(|name| {
-                self.get_by_php_name(name)
-                    .or_else(|| self.get(name))
-            })
-            .collect();
+        let mut tokens = TokenStream::new(&current_source);
+        let mut offsets = OffsetMap::default();
 
-        // Sort by priority (higher priority runs first)
-        fixers_to_run.sort_by(|a, b| b.priority().cmp(&a.priority()));
+        for fixer in self.fixers_to_run(fixer_names, config) {
+            let fixer_config = config.for_fixer(fixer.php_cs_fixer_name());
+            let mut edits = fixer.check_tokens(&current_source, &tokens, &fixer_config);
 
-        for fixer in fixers_to_run {
-            let edits = fixer.check(&current_source, config);
+            if let Some(ranges) = &config.ranges {
+                edits.retain(|edit| {
+                    let (start, end) = offsets.original_span(edit.start_offset(), edit.end_offset());
+                    in_ranges(ranges, start, end)
+                });
+            }
 
             if !edits.is_empty() {
                 // Apply this fixer's edits to the current source
                 if let Ok(new_source) = apply_edits(&current_source, &edits) {
-                    // Track edits (positions will be relative to source at time of edit)
-                    all_edits.extend(edits);
+                    // Record the text actually inserted, so offsets stay exact
+                    for edit in &mut edits {
+                        edit.replacement = edit.applied_replacement(&current_source);
+                    }
+                    tokens.update(&new_source, &edits);
+                    all_edits.extend(edits.iter().map(|edit| {
+                        let (start, end) = offsets.original_span(edit.start_offset(), edit.end_offset());
+                        let mut original = edit.clone();
+                        original.span = Span::new(
+                            edit.span.file_id,
+                            Position::new(start as u32),
+                            Position::new(end as u32),
+                        );
+                        original
+                    }));
+                    offsets.apply(&edits);
                     current_source = new_source;
                 }
             }
@@ -534,6 +561,39 @@ impl FixerRegistry {
         (current_source, all_edits)
     }
 
+    /// Fixers `check` runs for `fixer_names` and `config`, in priority order
+    fn fixers_to_run(&self, fixer_names: &[&str], config: &FixerConfig) -> Vec<&Arc<dyn Fixer>> {
+        let mut fixers_to_run: Vec<_> = fixer_names
+            .iter()
+            .filter_map(|name| {
+                self.get_by_php_name(name)
+                    .or_else(|| self.get(name))
+            })
+            .filter(|fixer| config.fixer_overrides.get(fixer.php_cs_fixer_name()) != Some(&false))
+            .collect();
+        for (name, _) in config.fixer_overrides.iter().filter(|(_, &enabled)| enabled) {
+            if let Some(fixer) = self.get_by_php_name(name) {
+                if !fixers_to_run.iter().any(|f| Arc::ptr_eq(f, fixer)) {
+                    fixers_to_run.push(fixer);
+                }
+            }
+        }
+        fixers_to_run.retain(|fixer| config.risky_allowed || !fixer.is_risky());
+
+        // Sort by priority (higher priority runs first)
+        fixers_to_run.sort_by(|a, b| b.priority().cmp(&a.priority()));
+        fixers_to_run
+    }
+
+    /// PHP-CS-Fixer names of the fixers `check` runs for `fixer_names` and
+    /// `config`, in priority order
+    pub fn active(&self, fixer_names: &[&str], config: &FixerConfig) -> Vec<&'static str> {
+        self.fixers_to_run(fixer_names, config)
+            .into_iter()
+            .map(|fixer| fixer.php_cs_fixer_name())
+            .collect()
+    }
+
     /// Check source with all fixers
     pub fn check_all(&self, source: &str, config: &FixerConfig) -> (String, Vec<Edit>) {
         let names: Vec<&str> = self.fixers.iter().map(|f| f.php_cs_fixer_name()).collect();
@@ -549,25 +609,53 @@ impl FixerRegistry {
         // Apply preset-specific options to the config
         let mut config = config.clone();
         let preset_options = crate::config::get_preset_options(preset);
-        for (_rule_name, rule_opts) in preset_options {
+        for (rule_name, rule_opts) in preset_options {
+            let options = config.rule_options.entry(rule_name.to_string()).or_default();
             for (opt_name, opt_value) in rule_opts {
-                match opt_value {
-                    crate::config::PresetOptionValue::Bool(b) => {
-                        config.options.insert(opt_name.to_string(), super::ConfigValue::Bool(b));
-                    }
-                    crate::config::PresetOptionValue::String(s) => {
-                        config.options.insert(opt_name.to_string(), super::ConfigValue::String(s.to_string()));
-                    }
-                    crate::config::PresetOptionValue::Number(n) => {
-                        config.options.insert(opt_name.to_string(), super::ConfigValue::Number(n));
-                    }
-                }
+                let value = match opt_value {
+                    crate::config::PresetOptionValue::Bool(b) => super::ConfigValue::Bool(b),
+                    crate::config::PresetOptionValue::String(s) => super::ConfigValue::String(s.to_string()),
+                    crate::config::PresetOptionValue::Number(n) => super::ConfigValue::Number(n),
+                };
+                // Options set explicitly by the user win over the preset's
+                options.entry(opt_name.to_string()).or_insert(value);
             }
         }
 
         self.check(source, &names, &config)
     }
 
+    /// Requested fixers that `check` will skip because they are risky
+    ///
+    /// Returns PHP-CS-Fixer names, sorted; empty when risky fixers are allowed.
+    pub fn skipped_risky(&self, fixer_names: &[&str], config: &FixerConfig) -> Vec<&'static str> {
+        if config.risky_allowed {
+            return Vec::new();
+        }
+        let mut skipped: Vec<&'static str> = fixer_names
+            .iter()
+            .filter_map(|name| self.get_by_php_name(name).or_else(|| self.get(name)))
+            .filter(|fixer| fixer.is_risky())
+            .map(|fixer| fixer.php_cs_fixer_name())
+            .collect();
+        skipped.sort_unstable();
+        skipped.dedup();
+        skipped
+    }
+
+    /// Validate per-rule options against the options each fixer declares
+    ///
+    /// Rules that aren't known to the registry are skipped.
+    pub fn validate_options(&self, rule_options: &HashMap<String, HashMap<String, ConfigValue>>) -> Vec<OptionError> {
+        let mut rules: Vec<&String> = rule_options.keys().collect();
+        rules.sort();
+        rules
+            .into_iter()
+            .filter_map(|rule| self.get_by_php_name(rule).map(|fixer| (fixer, &rule_options[rule])))
+            .flat_map(|(fixer, options)| super::validate_options(fixer.as_ref(), options))
+            .collect()
+    }
+
     /// Number of registered fixers
     pub fn len(&self) -> usize {
         self.fixers.len()
@@ -585,6 +673,18 @@ impl Default for FixerRegistry {
     }
 }
 
+/// Whether an edit of `start..end` touches one of the ranges; an insertion
+/// must fall inside one
+fn in_ranges(ranges: &[Range<usize>], start: usize, end: usize) -> bool {
+    ranges.iter().any(|range| {
+        if start == end {
+            range.contains(&start)
+        } else {
+            start < range.end && range.start < end
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -639,6 +739,34 @@ mod tests {
         assert!(!edits.is_empty());
     }
 
+    #[test]
+    fn test_ranges_drop_edits_outside() {
+        let registry = FixerRegistry::new();
+        let source = "<?php\n$a = 1;   \n$b = 2;   \n$c = 3;   \n";
+        let second_line = source.find("$b").unwrap();
+        let config = FixerConfig {
+            ranges: Some(vec![Range { start: second_line, end: second_line + 11 }]),
+            ..Default::default()
+        };
+
+        let (fixed, edits) = registry.check(source, &["no_trailing_whitespace"], &config);
+        assert_eq!(fixed, "<?php\n$a = 1;   \n$b = 2;\n$c = 3;   \n");
+        assert_eq!(edits.len(), 1);
+    }
+
+    #[test]
+    fn test_fixer_overrides() {
+        let registry = FixerRegistry::new();
+        let source = "<?php\n$a = 1;   \n$b = 2;";
+        let mut config = FixerConfig::default();
+        config.fixer_overrides.insert("no_trailing_whitespace".to_string(), false);
+        config.fixer_overrides.insert("single_blank_line_at_end_of_file".to_string(), true);
+
+        let (fixed, _) = registry.check(source, &["no_trailing_whitespace"], &config);
+        assert_eq!(fixed, "<?php\n$a = 1;   \n$b = 2;\n");
+        assert_eq!(registry.active(&["no_trailing_whitespace"], &config), vec!["single_blank_line_at_end_of_file"]);
+    }
+
     #[test]
     fn test_list_fixers() {
         let registry = FixerRegistry::new();
@@ -730,4 +858,52 @@ $a = "simple string";
             "Expected single quotes in output, got: {}", fixed
         );
     }
+
+    #[test]
+    fn test_token_stream_follows_edits() {
+        let registry = FixerRegistry::new();
+        let config = FixerConfig::default();
+
+        // single_quote rewrites the string before lowercase_keywords runs on the
+        // updated token stream; the keyword inside the string must stay intact
+        let source = "<?php\n$a = \"IF\";\nIF ($a) {}\n";
+        let (fixed, _) = registry.check(source, &["single_quote", "lowercase_keywords"], &config);
+
+        assert_eq!(fixed, "<?php\n$a = 'IF';\nif ($a) {}\n");
+    }
+
+    #[test]
+    fn test_risky_fixers_need_permission() {
+        let registry = FixerRegistry::new();
+        let source = "<?php\nif ($a == $b) {}\n";
+        let rules = ["strict_comparison", "no_trailing_whitespace"];
+
+        let mut config = FixerConfig::default();
+        let (fixed, edits) = registry.check(source, &rules, &config);
+        assert_eq!(fixed, source);
+        assert!(edits.is_empty());
+        assert_eq!(registry.skipped_risky(&rules, &config), vec!["strict_comparison"]);
+
+        config.risky_allowed = true;
+        let (fixed, _) = registry.check(source, &rules, &config);
+        assert_eq!(fixed, "<?php\nif ($a === $b) {}\n");
+        assert!(registry.skipped_risky(&rules, &config).is_empty());
+    }
+
+    #[test]
+    fn test_edits_map_to_original_source() {
+        let registry = FixerRegistry::new();
+        let source = "<?php\n$a=TRUE;\n\n\n\n$b=FALSE;\n";
+        let rules = ["no_extra_blank_lines", "binary_operator_spaces", "constant_case"];
+        let (fixed, edits) = registry.check(source, &rules, &FixerConfig::default());
+        assert_eq!(fixed, "<?php\n$a = true;\n\n$b = false;\n");
+
+        // Each fixer ran on code moved by the others, but spans refer to the original
+        let changed: Vec<(&str, &str)> = edits
+            .iter()
+            .map(|edit| (edit.rule.as_deref().unwrap_or(""), &source[edit.start_offset()..edit.end_offset()]))
+            .filter(|(rule, _)| *rule == "constant_case")
+            .collect();
+        assert_eq!(changed, vec![("constant_case", "TRUE"), ("constant_case", "FALSE")]);
+    }
 }
@@ -6,6 +6,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Replaces function aliases with their canonical function names
 pub struct NoAliasFunctionsFixer;
@@ -31,7 +32,11 @@ impl Fixer for NoAliasFunctionsFixer {
         true
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Build regex pattern for all function aliases
@@ -78,13 +83,8 @@ impl Fixer for NoAliasFunctionsFixer {
             let re = Regex::new(&pattern).unwrap();
 
             for m in re.find_iter(source) {
-                // Skip if in string
-                if is_in_string(&source[..m.start()]) {
-                    continue;
-                }
-
-                // Skip if in comment
-                if is_in_comment(&source[..m.start()]) {
+                // Skip if in string or comment
+                if tokens.is_in_string_or_comment(m.start()) {
                     continue;
                 }
 
@@ -116,41 +116,6 @@ impl Fixer for NoAliasFunctionsFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn is_in_comment(before: &str) -> bool {
-    // Check for single-line comment
-    if let Some(last_line_start) = before.rfind('\n') {
-        let last_line = &before[last_line_start..];
-        if last_line.contains("//") || last_line.contains('#') {
-            return true;
-        }
-    } else if before.contains("//") || before.contains('#') {
-        return true;
-    }
-
-    // Check for multi-line comment (not closed)
-    let open_count = before.matches("/*").count();
-    let close_count = before.matches("*/").count();
-    open_count > close_count
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
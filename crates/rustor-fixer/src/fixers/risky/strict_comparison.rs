@@ -6,6 +6,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Converts loose comparisons (==, !=) to strict comparisons (===, !==)
 pub struct StrictComparisonFixer;
@@ -31,7 +32,11 @@ impl Fixer for StrictComparisonFixer {
         true
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match == that is not === (negative lookbehind/lookahead simulation)
@@ -50,13 +55,8 @@ impl Fixer for StrictComparisonFixer {
                 continue;
             }
 
-            // Skip if in string
-            if is_in_string(&source[..full_match.start()]) {
-                continue;
-            }
-
-            // Skip if in comment
-            if is_in_comment(&source[..full_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full_match.start()) {
                 continue;
             }
 
@@ -90,13 +90,8 @@ impl Fixer for StrictComparisonFixer {
                 continue;
             }
 
-            // Skip if in string
-            if is_in_string(&source[..op_match.start()]) {
-                continue;
-            }
-
-            // Skip if in comment
-            if is_in_comment(&source[..op_match.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(op_match.start()) {
                 continue;
             }
 
@@ -122,41 +117,6 @@ impl Fixer for StrictComparisonFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
-fn is_in_comment(before: &str) -> bool {
-    // Check for single-line comment
-    if let Some(last_line_start) = before.rfind('\n') {
-        let last_line = &before[last_line_start..];
-        if last_line.contains("//") || last_line.contains('#') {
-            return true;
-        }
-    } else if before.contains("//") || before.contains('#') {
-        return true;
-    }
-
-    // Check for multi-line comment (not closed)
-    let open_count = before.matches("/*").count();
-    let close_count = before.matches("*/").count();
-    open_count > close_count
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4,7 +4,8 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Converts array() to [] syntax
 pub struct ArraySyntaxFixer;
@@ -26,7 +27,20 @@ impl Fixer for ArraySyntaxFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "syntax",
+            description: "Array syntax to use; only short is supported",
+            option_type: OptionType::Enum(vec!["short"]),
+            default: Some(ConfigValue::String("short".to_string())),
+        }]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match array() with contents - need to handle nested arrays
@@ -34,11 +48,7 @@ impl Fixer for ArraySyntaxFixer {
         let array_re = Regex::new(r"\barray\s*\(").unwrap();
 
         for m in array_re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) {
-                continue;
-            }
-
-            if is_in_comment(&source[..m.start()]) {
+            if tokens.is_in_string_or_comment(m.start()) {
                 continue;
             }
 
@@ -100,26 +110,6 @@ fn find_matching_paren(source: &str, start: usize) -> Option<usize> {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single = false;
-    let mut in_double = false;
-    let mut prev = '\0';
-    for c in before.chars() {
-        if c == '\'' && prev != '\\' && !in_double { in_single = !in_single; }
-        if c == '"' && prev != '\\' && !in_single { in_double = !in_double; }
-        prev = c;
-    }
-    in_single || in_double
-}
-
-fn is_in_comment(before: &str) -> bool {
-    if let Some(pos) = before.rfind('\n') {
-        let line = &before[pos..];
-        if line.contains("//") || line.contains('#') { return true; }
-    }
-    before.matches("/*").count() > before.matches("*/").count()
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
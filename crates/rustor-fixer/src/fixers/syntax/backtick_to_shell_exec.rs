@@ -1,8 +1,9 @@
 //! Backtick to shell_exec fixer
 
 use rustor_core::Edit;
-use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
+use mago_syntax::token::TokenKind;
 
 /// Converts backtick operator to shell_exec()
 pub struct BacktickToShellExecFixer;
@@ -13,18 +14,21 @@ impl Fixer for BacktickToShellExecFixer {
     fn description(&self) -> &'static str { "Convert backtick operator to shell_exec()" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-        let re = Regex::new(r"`([^`]*)`").unwrap();
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
 
-        for cap in re.captures_iter(source) {
-            let full = cap.get(0).unwrap();
-            let cmd = cap.get(1).unwrap().as_str();
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
+        let mut edits = Vec::new();
 
-            if is_in_string(&source[..full.start()]) { continue; }
+        // Commands are the text between pairs of backtick tokens; backticks in
+        // strings and comments aren't tokens of their own
+        let mut backticks = tokens.tokens().iter().filter(|t| t.kind == TokenKind::Backtick);
+        while let (Some(open), Some(close)) = (backticks.next(), backticks.next()) {
+            let cmd = &source[open.end..close.start];
 
             edits.push(edit_with_rule(
-                full.start(), full.end(),
+                open.start, close.end,
                 format!("shell_exec('{}')", cmd.replace('\'', "\\'")),
                 "Use shell_exec() instead of backticks".to_string(),
                 "backtick_to_shell_exec",
@@ -34,18 +38,6 @@ impl Fixer for BacktickToShellExecFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single = false;
-    let mut in_double = false;
-    let mut prev = '\0';
-    for c in before.chars() {
-        if c == '\'' && prev != '\\' && !in_double { in_single = !in_single; }
-        if c == '"' && prev != '\\' && !in_single { in_double = !in_double; }
-        prev = c;
-    }
-    in_single || in_double
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -58,4 +50,12 @@ mod tests {
         assert_eq!(edits.len(), 1);
         assert!(edits[0].replacement.contains("shell_exec"));
     }
+
+    #[test]
+    fn test_skip_backticks_in_strings_and_comments() {
+        let source = "<?php\n$a = '`a`'; // `b`\n$c = `ls`;";
+        let edits = check(source);
+        assert_eq!(edits.len(), 1);
+        assert_eq!(edits[0].replacement, "shell_exec('ls')");
+    }
 }
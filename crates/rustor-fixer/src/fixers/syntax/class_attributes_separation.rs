@@ -1,8 +1,10 @@
 //! Class attributes separation fixer
 
+use std::collections::HashMap;
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+use crate::fixers::class::class_members::{class_bodies, ClassMember, MemberKind};
+use crate::tokens::TokenStream;
 
 pub struct ClassAttributesSeparationFixer;
 
@@ -12,33 +14,86 @@ impl Fixer for ClassAttributesSeparationFixer {
     fn description(&self) -> &'static str { "Add blank lines between class members" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "elements",
+            description: "Spacing before each kind of element: none, one, only_if_meta",
+            option_type: OptionType::EnumMap(
+                vec!["const", "method", "property", "trait_import", "case"],
+                vec!["none", "one", "only_if_meta"],
+            ),
+            default: None,
+        }]
+    }
+
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let elements: HashMap<String, String> = match config.get_map("elements") {
+            Some(elements) => elements.into_owned(),
+            None => [("const", "one"), ("method", "one"), ("property", "one"), ("trait_import", "none"), ("case", "none")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        let line_ending = config.line_ending.as_str();
         let mut edits = Vec::new();
 
-        // Match method followed by method without blank line
-        let re = Regex::new(r"(\}\n)([ \t]*)(public|protected|private|function)").unwrap();
-
-        for cap in re.captures_iter(source) {
-            let full = cap.get(0).unwrap();
-            let close = cap.get(1).unwrap().as_str();
-            let indent = cap.get(2).unwrap().as_str();
-            let keyword = cap.get(3).unwrap().as_str();
-
-            edits.push(edit_with_rule(
-                full.start(), full.end(),
-                format!("{}\n{}{}", close, indent, keyword),
-                "Add blank line between class members".to_string(),
-                "class_attributes_separation",
-            ));
+        for body in class_bodies(source, tokens) {
+            for pair in body.members.windows(2) {
+                let (prev, member) = (&pair[0], &pair[1]);
+                let Some(spacing) = elements.get(element_key(member)) else {
+                    continue;
+                };
+                let blank_lines = match spacing.as_str() {
+                    "one" => 1,
+                    "only_if_meta" if member.has_meta => 1,
+                    // Only collapse runs of the same kind of element
+                    _ if prev.kind == member.kind => 0,
+                    _ => continue,
+                };
+
+                let gap = &source[prev.end..member.start];
+                // Elements on the same line are left to other fixers
+                let Some(last_newline) = gap.rfind('\n') else {
+                    continue;
+                };
+                let indent = &gap[last_newline + 1..];
+                let replacement = format!("{}{}", line_ending.repeat(blank_lines + 1), indent);
+                if gap != replacement {
+                    edits.push(edit_with_rule(
+                        prev.end,
+                        member.start,
+                        replacement,
+                        "Fix blank lines between class members".to_string(),
+                        "class_attributes_separation",
+                    ));
+                }
+            }
         }
 
         edits
     }
 }
 
+/// The `elements` key for a member
+fn element_key(member: &ClassMember) -> &'static str {
+    match member.kind {
+        MemberKind::UseTrait => "trait_import",
+        MemberKind::Case => "case",
+        MemberKind::Constant => "const",
+        MemberKind::Property => "property",
+        MemberKind::Method => "method",
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixers::ConfigValue;
+
     #[test]
     fn test_method_separation() {
         let code = "<?php
@@ -49,4 +104,22 @@ class Foo {
         let edits = ClassAttributesSeparationFixer.check(code, &FixerConfig::default());
         assert!(!edits.is_empty());
     }
+
+    #[test]
+    fn test_configured_elements() {
+        let code = "<?php\nclass Foo {\n    use A;\n\n    use B;\n    const X = 1;\n\n\n    const Y = 2;\n    public $a;\n    /** @var int */\n    public $b;\n    public function a() { if (true) {} }\n    public function b() {}\n}\n";
+        let elements: HashMap<String, String> = [("const", "none"), ("property", "only_if_meta"), ("trait_import", "none")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let config = FixerConfig {
+            options: [("elements".to_string(), ConfigValue::StringMap(elements))].into_iter().collect(),
+            ..Default::default()
+        };
+        let edits = ClassAttributesSeparationFixer.check(code, &config);
+        assert_eq!(
+            rustor_core::apply_edits(code, &edits).unwrap(),
+            "<?php\nclass Foo {\n    use A;\n    use B;\n    const X = 1;\n    const Y = 2;\n    public $a;\n\n    /** @var int */\n    public $b;\n    public function a() { if (true) {} }\n    public function b() {}\n}\n"
+        );
+    }
 }
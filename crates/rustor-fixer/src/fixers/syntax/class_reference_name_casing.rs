@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct ClassReferenceNameCasingFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for ClassReferenceNameCasingFixer {
     fn description(&self) -> &'static str { "Lowercase self, static, parent" }
     fn priority(&self) -> i32 { 40 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match uppercase variants
@@ -25,7 +30,7 @@ impl Fixer for ClassReferenceNameCasingFixer {
         for (pattern, replacement) in &patterns {
             let re = Regex::new(pattern).unwrap();
             for m in re.find_iter(source) {
-                if is_in_string(&source[..m.start()]) { continue; }
+                if tokens.is_in_string_or_comment(m.start()) { continue; }
 
                 edits.push(edit_with_rule(
                     m.start(), m.end(),
@@ -40,16 +45,6 @@ impl Fixer for ClassReferenceNameCasingFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
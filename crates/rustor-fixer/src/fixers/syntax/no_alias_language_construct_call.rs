@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoAliasLanguageConstructCallFixer;
 
@@ -12,14 +13,18 @@ impl Fixer for NoAliasLanguageConstructCallFixer {
     fn description(&self) -> &'static str { "die() to exit()" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match die()
         let re = Regex::new(r"\bdie\s*\(").unwrap();
 
         for m in re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) { continue; }
+            if tokens.is_in_string_or_comment(m.start()) { continue; }
 
             edits.push(edit_with_rule(
                 m.start(), m.end(),
@@ -33,7 +38,7 @@ impl Fixer for NoAliasLanguageConstructCallFixer {
         let re2 = Regex::new(r"\bdie\s*;").unwrap();
 
         for m in re2.find_iter(source) {
-            if is_in_string(&source[..m.start()]) { continue; }
+            if tokens.is_in_string_or_comment(m.start()) { continue; }
 
             edits.push(edit_with_rule(
                 m.start(), m.end(),
@@ -47,16 +52,6 @@ impl Fixer for NoAliasLanguageConstructCallFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
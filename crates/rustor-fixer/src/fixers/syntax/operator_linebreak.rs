@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct OperatorLinebreakFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for OperatorLinebreakFixer {
     fn description(&self) -> &'static str { "Control operator linebreak position" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // By default, operators should be at the beginning of the line
@@ -28,8 +33,8 @@ impl Fixer for OperatorLinebreakFixer {
                 let operator = cap.get(2).unwrap().as_str();
                 let indent = cap.get(3).unwrap().as_str();
 
-                // Check if in string
-                if is_in_string(&source[..full.start()]) { continue; }
+                // Skip if in string or comment
+                if tokens.is_in_string_or_comment(full.start()) { continue; }
 
                 // Move operator to next line
                 edits.push(edit_with_rule(
@@ -45,16 +50,6 @@ impl Fixer for OperatorLinebreakFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct SingleSpaceAroundConstructFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for SingleSpaceAroundConstructFixer {
     fn description(&self) -> &'static str { "Single space around constructs" }
     fn priority(&self) -> i32 { 30 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Control structures that need single space before (
@@ -54,8 +59,8 @@ impl Fixer for SingleSpaceAroundConstructFixer {
         for cap in brace_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
 
-            // Skip if in string
-            if is_in_string(&source[..full.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -74,8 +79,8 @@ impl Fixer for SingleSpaceAroundConstructFixer {
         for cap in else_brace_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
 
-            // Skip if in string
-            if is_in_string(&source[..full.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -93,7 +98,7 @@ impl Fixer for SingleSpaceAroundConstructFixer {
         for cap in try_brace_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full.start()]) {
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -110,24 +115,6 @@ impl Fixer for SingleSpaceAroundConstructFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = '\0';
-
-    for c in before.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        }
-        if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
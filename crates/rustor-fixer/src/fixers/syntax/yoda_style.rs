@@ -3,8 +3,9 @@
 //! Converts comparisons to Yoda style (constant on left).
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use mago_syntax::token::TokenKind;
+use crate::fixers::{Fixer, FixerConfig, FixerOption, OptionType, ConfigValue, edit_with_rule};
+use crate::tokens::{Region, Token, TokenStream};
 
 /// Converts comparisons to Yoda style
 pub struct YodaStyleFixer;
@@ -26,70 +27,293 @@ impl Fixer for YodaStyleFixer {
         20
     }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-
-        // Match $var === null, $var === true, $var === false
-        // Convert to null === $var, true === $var, false === $var
-        let patterns = [
-            (r"(\$\w+)\s*(===|==|!==|!=)\s*(null)\b", true),
-            (r"(\$\w+)\s*(===|==|!==|!=)\s*(true)\b", true),
-            (r"(\$\w+)\s*(===|==|!==|!=)\s*(false)\b", true),
-            // Also match numeric literals
-            (r"(\$\w+)\s*(===|==|!==|!=)\s*(\d+)\b", true),
-            // Match string literals
-            (r#"(\$\w+)\s*(===|==|!==|!=)\s*('[^']*')"#, true),
-            (r#"(\$\w+)\s*(===|==|!==|!=)\s*("[^"]*")"#, true),
-        ];
-
-        for (pattern, _) in patterns {
-            let re = Regex::new(pattern).unwrap();
-            for cap in re.captures_iter(source) {
-                let full_match = cap.get(0).unwrap();
-                let var = cap.get(1).unwrap().as_str();
-                let op = cap.get(2).unwrap().as_str();
-                let constant = cap.get(3).unwrap().as_str();
-
-                if is_in_string(&source[..full_match.start()]) {
-                    continue;
-                }
+    fn options(&self) -> Vec<FixerOption> {
+        vec![
+            FixerOption {
+                name: "equal",
+                description: "Style for == and != (true: Yoda, false: non-Yoda)",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(true)),
+            },
+            FixerOption {
+                name: "identical",
+                description: "Style for === and !== (true: Yoda, false: non-Yoda)",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(true)),
+            },
+            FixerOption {
+                name: "less_and_greater",
+                description: "Style for <, <=, > and >= (unset: unchanged)",
+                option_type: OptionType::Bool,
+                default: None,
+            },
+            FixerOption {
+                name: "always_move_variable",
+                description: "Also move variables compared to function calls in Yoda style",
+                option_type: OptionType::Bool,
+                default: Some(ConfigValue::Bool(false)),
+            },
+        ]
+    }
 
-                if is_in_comment(&source[..full_match.start()]) {
-                    continue;
-                }
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
 
-                edits.push(edit_with_rule(
-                    full_match.start(),
-                    full_match.end(),
-                    format!("{} {} {}", constant, op, var),
-                    "Use Yoda style comparison".to_string(),
-                    "yoda_style",
-                ));
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let equal = Some(config.get_bool("equal").unwrap_or(true));
+        let identical = Some(config.get_bool("identical").unwrap_or(true));
+        let less_and_greater = config.get_bool("less_and_greater");
+        let always_move_variable = config.get_bool("always_move_variable").unwrap_or(false);
+
+        let code: Vec<&Token> = tokens.tokens().iter().filter(|t| t.kind != TokenKind::Whitespace).collect();
+        let mut edits: Vec<Edit> = Vec::new();
+        let mut idx = 1;
+
+        while idx < code.len() {
+            let Some((comparison, next)) = parse_comparison(source, &code, idx, always_move_variable) else {
+                idx += 1;
+                continue;
+            };
+            let yoda = match code[comparison.op].kind {
+                TokenKind::EqualEqual | TokenKind::BangEqual | TokenKind::LessThanGreaterThan => equal,
+                TokenKind::EqualEqualEqual | TokenKind::BangEqualEqual => identical,
+                _ => less_and_greater,
+            };
+            let swap = match yoda {
+                Some(true) => comparison.left == Operand::Variable && comparison.right != Operand::Variable,
+                Some(false) => comparison.left == Operand::Constant && comparison.right == Operand::Variable,
+                None => false,
+            };
+            if !swap {
+                idx = comparison.op + 1;
+                continue;
             }
+
+            let (left_start, left_end) = (code[idx].start, code[comparison.op - 1].end);
+            let (right_start, right_end) = (code[comparison.op + 1].start, code[next - 1].end);
+            let op = code[comparison.op];
+            edits.push(edit_with_rule(
+                left_start,
+                right_end,
+                format!(
+                    "{}{}{}{}{}",
+                    &source[right_start..right_end],
+                    &source[left_end..op.start],
+                    flip(op.text(source)),
+                    &source[op.end..right_start],
+                    &source[left_start..left_end],
+                ),
+                if yoda == Some(true) {
+                    "Use Yoda style comparison".to_string()
+                } else {
+                    "Use non-Yoda style comparison".to_string()
+                },
+                "yoda_style",
+            ));
+            idx = next;
         }
 
         edits
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let mut in_single = false;
-    let mut in_double = false;
-    let mut prev = '\0';
-    for c in before.chars() {
-        if c == '\'' && prev != '\\' && !in_double { in_single = !in_single; }
-        if c == '"' && prev != '\\' && !in_single { in_double = !in_double; }
-        prev = c;
+/// What one side of a comparison is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    /// A variable, property or array element
+    Variable,
+    /// A literal or constant
+    Constant,
+    /// A function call (only with `always_move_variable`)
+    Call,
+}
+
+struct Comparison {
+    left: Operand,
+    op: usize,
+    right: Operand,
+}
+
+/// Parse `operand OP operand` starting at `idx`, returning it and the index after it
+///
+/// Only comparisons whose operands can't bind to neighbouring operators are
+/// considered, so swapping the operands doesn't change the meaning.
+fn parse_comparison(source: &str, code: &[&Token], idx: usize, calls: bool) -> Option<(Comparison, usize)> {
+    if code[idx].region != Region::Code || !bounds_left(code[idx - 1].kind) {
+        return None;
+    }
+    let (left, op) = parse_operand(source, code, idx, calls)?;
+    if !is_comparison(code.get(op)?.kind) {
+        return None;
+    }
+    let (right, next) = parse_operand(source, code, op + 1, calls)?;
+    if !code.get(next).is_some_and(|t| bounds_right(t.kind)) {
+        return None;
+    }
+    Some((Comparison { left, op, right }, next))
+}
+
+/// Parse an operand at `idx`, returning its kind and the index after it
+fn parse_operand(source: &str, code: &[&Token], idx: usize, calls: bool) -> Option<(Operand, usize)> {
+    let token = code.get(idx)?;
+    if token.region != Region::Code && token.kind != TokenKind::LiteralString {
+        return None;
+    }
+    let kind_at = |i: usize| code.get(i).map(|t| t.kind);
+
+    match token.kind {
+        TokenKind::Variable => {
+            let mut end = idx + 1;
+            loop {
+                match kind_at(end) {
+                    Some(TokenKind::MinusGreaterThan | TokenKind::QuestionMinusGreaterThan)
+                        if matches!(kind_at(end + 1), Some(TokenKind::Identifier | TokenKind::Variable)) =>
+                    {
+                        end += 2
+                    }
+                    Some(TokenKind::LeftBracket) => end = matching_bracket(code, end)? + 1,
+                    _ => break,
+                }
+            }
+            // Method calls are neither variables nor constants
+            if kind_at(end) == Some(TokenKind::LeftParenthesis) {
+                return None;
+            }
+            Some((Operand::Variable, end))
+        }
+        TokenKind::Null
+        | TokenKind::True
+        | TokenKind::False
+        | TokenKind::LiteralInteger
+        | TokenKind::LiteralFloat
+        | TokenKind::LiteralString => Some((Operand::Constant, idx + 1)),
+        TokenKind::Minus if matches!(kind_at(idx + 1), Some(TokenKind::LiteralInteger | TokenKind::LiteralFloat)) => {
+            Some((Operand::Constant, idx + 2))
+        }
+        TokenKind::Identifier
+        | TokenKind::QualifiedIdentifier
+        | TokenKind::FullyQualifiedIdentifier
+        | TokenKind::Static
+        | TokenKind::Self_
+        | TokenKind::Parent => {
+            match kind_at(idx + 1) {
+                // `Foo::BAR`
+                Some(TokenKind::ColonColon) => {
+                    let name = code.get(idx + 2).filter(|t| t.kind == TokenKind::Identifier)?;
+                    if kind_at(idx + 3) == Some(TokenKind::LeftParenthesis) || !is_constant_name(name.text(source)) {
+                        return None;
+                    }
+                    Some((Operand::Constant, idx + 3))
+                }
+                Some(TokenKind::LeftParenthesis) if calls => {
+                    Some((Operand::Call, matching_bracket(code, idx + 1)? + 1))
+                }
+                Some(TokenKind::LeftParenthesis) => None,
+                _ if is_constant_name(token.text(source)) => Some((Operand::Constant, idx + 1)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Index of the token closing the bracket at `open`
+fn matching_bracket(code: &[&Token], open: usize) -> Option<usize> {
+    let mut depth = 0i32;
+    for (idx, token) in code.iter().enumerate().skip(open) {
+        if token.region != Region::Code {
+            continue;
+        }
+        match token.kind {
+            TokenKind::LeftParenthesis | TokenKind::LeftBracket | TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightParenthesis | TokenKind::RightBracket | TokenKind::RightBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(idx);
+                }
+            }
+            _ => {}
+        }
     }
-    in_single || in_double
+    None
+}
+
+/// `FOO_BAR`-style constant names
+fn is_constant_name(name: &str) -> bool {
+    let name = name.rsplit('\\').next().unwrap_or(name);
+    name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && name.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn is_comparison(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::EqualEqual
+            | TokenKind::EqualEqualEqual
+            | TokenKind::BangEqual
+            | TokenKind::BangEqualEqual
+            | TokenKind::LessThanGreaterThan
+            | TokenKind::LessThan
+            | TokenKind::LessThanEqual
+            | TokenKind::GreaterThan
+            | TokenKind::GreaterThanEqual
+    )
 }
 
-fn is_in_comment(before: &str) -> bool {
-    if let Some(pos) = before.rfind('\n') {
-        let line = &before[pos..];
-        if line.contains("//") || line.contains('#') { return true; }
+/// Tokens with lower precedence than comparisons, that may precede one
+fn bounds_left(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::LeftParenthesis
+            | TokenKind::LeftBracket
+            | TokenKind::LeftBrace
+            | TokenKind::Comma
+            | TokenKind::Semicolon
+            | TokenKind::Equal
+            | TokenKind::EqualGreaterThan
+            | TokenKind::AmpersandAmpersand
+            | TokenKind::PipePipe
+            | TokenKind::And
+            | TokenKind::Or
+            | TokenKind::Xor
+            | TokenKind::Question
+            | TokenKind::Colon
+            | TokenKind::Return
+            | TokenKind::Echo
+    )
+}
+
+/// Tokens with lower precedence than comparisons, that may follow one
+fn bounds_right(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::RightParenthesis
+            | TokenKind::RightBracket
+            | TokenKind::RightBrace
+            | TokenKind::Comma
+            | TokenKind::Semicolon
+            | TokenKind::EqualGreaterThan
+            | TokenKind::AmpersandAmpersand
+            | TokenKind::PipePipe
+            | TokenKind::And
+            | TokenKind::Or
+            | TokenKind::Xor
+            | TokenKind::Question
+            | TokenKind::Colon
+            | TokenKind::CloseTag
+    )
+}
+
+/// The operator to use once the operands are swapped
+fn flip(op: &str) -> &str {
+    match op {
+        "<" => ">",
+        ">" => "<",
+        "<=" => ">=",
+        ">=" => "<=",
+        op => op,
     }
-    before.matches("/*").count() > before.matches("*/").count()
 }
 
 #[cfg(test)]
@@ -150,4 +374,40 @@ mod tests {
         let source = "<?php\n$a = '$x === null';";
         assert!(check(source).is_empty());
     }
+
+    fn fix(source: &str, options: &[(&str, bool)]) -> String {
+        let config = FixerConfig {
+            options: options.iter().map(|(k, v)| (k.to_string(), ConfigValue::Bool(*v))).collect(),
+            ..Default::default()
+        };
+        let edits = YodaStyleFixer.check(source, &config);
+        rustor_core::apply_edits(source, &edits).unwrap()
+    }
+
+    #[test]
+    fn test_constants_and_precedence() {
+        assert_eq!(
+            fix("<?php\nif ($a->b === self::FOO && $c['x'] != PHP_EOL) {}\n", &[]),
+            "<?php\nif (self::FOO === $a->b && PHP_EOL != $c['x']) {}\n"
+        );
+        // `.` binds tighter than `===`, and method calls aren't variables
+        let source = "<?php\nif ($a . $b === 'x' || $c->d() === null) {}\n";
+        assert_eq!(fix(source, &[]), source);
+        // Only with always_move_variable
+        assert_eq!(
+            fix("<?php\nreturn $a === count($b);\n", &[("always_move_variable", true)]),
+            "<?php\nreturn count($b) === $a;\n"
+        );
+    }
+
+    #[test]
+    fn test_non_yoda_and_less_and_greater() {
+        let options = [("equal", false), ("identical", true), ("less_and_greater", false)];
+        assert_eq!(
+            fix("<?php\n$x = null == $a;\n$y = 1 < $b;\n$z = $c === 2;\n", &options),
+            "<?php\n$x = $a == null;\n$y = $b > 1;\n$z = 2 === $c;\n"
+        );
+        let source = "<?php\n$y = $b < 1;\n";
+        assert_eq!(fix(source, &[]), source);
+    }
 }
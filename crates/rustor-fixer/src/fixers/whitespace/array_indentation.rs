@@ -3,10 +3,15 @@
 //! Ensures multiline arrays have consistent indentation.
 
 use rustor_core::Edit;
-use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{Fixer, FixerConfig};
+use crate::fixers::layout::{tokens_in, Layout, ListKind, Syntax};
+use crate::tokens::{Region, TokenStream};
 
 /// Ensures consistent indentation in multiline arrays
+///
+/// Each element starting a line is indented once past the line the array
+/// opens on, and the closing bracket lines up with that line. Lines inside an
+/// element (closure bodies, nested calls) move along with it.
 pub struct ArrayIndentationFixer;
 
 impl Fixer for ArrayIndentationFixer {
@@ -27,117 +32,107 @@ impl Fixer for ArrayIndentationFixer {
     }
 
     fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
-        let mut edits = Vec::new();
-        let indent_str = config.indent.as_str();
-        let lines: Vec<&str> = source.lines().collect();
-
-        // Track array depth and expected indent
-        let mut in_array_stack: Vec<usize> = Vec::new(); // stack of base indents
-        let mut offset = 0;
-
-        for (line_num, line) in lines.iter().enumerate() {
-            let line_start = offset;
-            let trimmed = line.trim_start();
-            let current_indent = line.len() - trimmed.len();
-
-            // Skip empty lines
-            if trimmed.is_empty() {
-                offset += line.len() + 1;
-                continue;
-            }
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
 
-            // Check for array opening
-            if trimmed.contains('[') && !is_in_string_simple(line) {
-                // Count opening and closing brackets
-                let opens = trimmed.matches('[').count();
-                let closes = trimmed.matches(']').count();
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, config: &FixerConfig) -> Vec<Edit> {
+        let Some(syntax) = Syntax::parse(source) else {
+            return Vec::new();
+        };
 
-                if opens > closes {
-                    // Array opened but not closed on same line
-                    in_array_stack.push(current_indent);
-                }
+        let mut layout = Layout::new(source, tokens, config);
+        for array in syntax.lists.iter().filter(|l| l.kind == ListKind::Array) {
+            if !source[array.open..array.close].contains('\n') {
+                continue;
             }
 
-            // Check for array closing
-            if !in_array_stack.is_empty() && trimmed.starts_with(']') {
-                let expected_indent = in_array_stack.last().copied().unwrap_or(0);
-
-                if current_indent != expected_indent {
-                    // Fix indentation of closing bracket
-                    let expected_ws = " ".repeat(expected_indent);
-                    edits.push(edit_with_rule(
-                        line_start,
-                        line_start + current_indent,
-                        expected_ws,
-                        "Fix array closing bracket indentation".to_string(),
-                        "array_indentation",
-                    ));
-                }
-
-                in_array_stack.pop();
-            } else if !in_array_stack.is_empty() {
-                // Inside an array - check element indentation
-                let base_indent = in_array_stack.last().copied().unwrap_or(0);
-                let expected_indent = base_indent + indent_str.len();
-
-                // Only fix if we're clearly an array element (starts with value, key, or comma continuation)
-                if !trimmed.starts_with("//") && !trimmed.starts_with("/*") && !trimmed.starts_with("*") {
-                    if current_indent != expected_indent && current_indent != base_indent {
-                        // Check if this line is a continuation or new element
-                        let expected_ws = " ".repeat(expected_indent);
-                        edits.push(edit_with_rule(
-                            line_start,
-                            line_start + current_indent,
-                            expected_ws,
-                            "Fix array element indentation".to_string(),
-                            "array_indentation",
-                        ));
-                    }
+            for item in &array.items {
+                if layout.is_line_start(item.start) {
+                    layout.indent(item.start, array.open, 1, "Fix array element indentation");
                 }
             }
 
-            // Handle closing bracket in middle of line
-            if trimmed.contains(']') && !trimmed.starts_with(']') {
-                let closes = trimmed.matches(']').count();
-                let opens = trimmed.matches('[').count();
-                for _ in 0..(closes.saturating_sub(opens)) {
-                    in_array_stack.pop();
+            // Comments between elements are indented like elements
+            for comment in tokens_in(tokens, array.open + 1..array.close) {
+                if comment.region == Region::Comment
+                    && !array.items.iter().any(|item| item.contains(&comment.start))
+                    && layout.is_line_start(comment.start)
+                {
+                    layout.indent(comment.start, array.open, 1, "Fix array element indentation");
                 }
             }
 
-            offset += line.len() + 1;
+            if layout.is_line_start(array.close) {
+                layout.indent(array.close, array.open, 0, "Fix array closing bracket indentation");
+            }
         }
 
-        edits
+        layout.edits("array_indentation", "Fix array indentation")
     }
 }
 
-fn is_in_string_simple(line: &str) -> bool {
-    // Simple check - if line has unbalanced quotes, might be in string
-    let single_quotes = line.matches('\'').count();
-    let double_quotes = line.matches('"').count();
-    single_quotes % 2 != 0 || double_quotes % 2 != 0
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn check(source: &str) -> Vec<Edit> {
-        ArrayIndentationFixer.check(source, &FixerConfig::default())
+    fn fix(source: &str) -> String {
+        let edits = ArrayIndentationFixer.check(source, &FixerConfig::default());
+        rustor_core::apply_edits(source, &edits).unwrap()
     }
 
     #[test]
     fn test_correct_unchanged() {
         let source = "<?php\n$arr = [\n    'a',\n    'b',\n];\n";
-        let edits = check(source);
+        let edits = ArrayIndentationFixer.check(source, &FixerConfig::default());
         assert!(edits.is_empty());
     }
 
     #[test]
     fn test_single_line_unchanged() {
         let source = "<?php\n$arr = ['a', 'b', 'c'];\n";
-        let edits = check(source);
+        let edits = ArrayIndentationFixer.check(source, &FixerConfig::default());
         assert!(edits.is_empty());
     }
+
+    #[test]
+    fn test_wrong_indent() {
+        let source = "<?php\n$arr = [\n'a',\n        'b',\n  ];\n";
+        assert_eq!(fix(source), "<?php\n$arr = [\n    'a',\n    'b',\n];\n");
+    }
+
+    #[test]
+    fn test_nested_arrays() {
+        let source = "<?php\n$arr = [\n  'a' => [\n  1,\n  ],\n];\n";
+        assert_eq!(fix(source), "<?php\n$arr = [\n    'a' => [\n        1,\n    ],\n];\n");
+    }
+
+    #[test]
+    fn test_array_in_match_arm() {
+        let source = "<?php\n$x = match ($a) {\n    1 => [\n    'a',\n    ],\n};\n";
+        assert_eq!(fix(source), "<?php\n$x = match ($a) {\n    1 => [\n        'a',\n    ],\n};\n");
+    }
+
+    #[test]
+    fn test_closure_body_moves_with_element() {
+        let source = "<?php\n$arr = [\n  'cb' => function () {\n      return 1;\n  },\n];\n";
+        assert_eq!(fix(source), "<?php\n$arr = [\n    'cb' => function () {\n        return 1;\n    },\n];\n");
+    }
+
+    #[test]
+    fn test_array_in_call_arguments() {
+        let source = "<?php\nfoo($a, [\n        'x' => 1,\n        ]);\n";
+        assert_eq!(fix(source), "<?php\nfoo($a, [\n    'x' => 1,\n]);\n");
+    }
+
+    #[test]
+    fn test_comment_between_elements() {
+        let source = "<?php\n$arr = [\n// first\n    'a',\n];\n";
+        assert_eq!(fix(source), "<?php\n$arr = [\n    // first\n    'a',\n];\n");
+    }
+
+    #[test]
+    fn test_heredoc_element_untouched() {
+        let source = "<?php\n$arr = [\n  <<<EOT\n  text\n  EOT,\n];\n";
+        assert_eq!(fix(source), "<?php\n$arr = [\n    <<<EOT\n  text\n  EOT,\n];\n");
+    }
 }
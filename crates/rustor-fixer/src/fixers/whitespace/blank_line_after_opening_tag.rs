@@ -90,13 +90,12 @@ impl Fixer for BlankLineAfterOpeningTagFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{IndentStyle, LineEnding};
+    use crate::config::LineEnding;
 
     fn check(source: &str) -> Vec<Edit> {
         BlankLineAfterOpeningTagFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
@@ -28,7 +28,7 @@ impl Fixer for IndentationFixer {
         let mut edits = Vec::new();
         let mut offset = 0;
 
-        for (line_num, line) in source.lines().enumerate() {
+        for line in source.lines() {
             // Find leading whitespace
             let leading: String = line.chars().take_while(|c| c.is_whitespace()).collect();
 
@@ -40,7 +40,7 @@ impl Fixer for IndentationFixer {
                         offset,
                         offset + leading.len(),
                         normalized,
-                        format!("Normalize indentation on line {}", line_num + 1),
+                        "Normalize indentation".to_string(),
                         "indentation_type",
                     ));
                 }
@@ -103,7 +103,7 @@ mod tests {
         IndentationFixer.check(source, &FixerConfig {
             indent: IndentStyle::Spaces(size),
             line_ending: crate::config::LineEnding::Lf,
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
@@ -111,7 +111,7 @@ mod tests {
         IndentationFixer.check(source, &FixerConfig {
             indent: IndentStyle::Tabs,
             line_ending: crate::config::LineEnding::Lf,
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
@@ -83,21 +83,18 @@ impl Fixer for LineEndingFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::IndentStyle;
 
     fn check_lf(source: &str) -> Vec<Edit> {
         LineEndingFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
     fn check_crlf(source: &str) -> Vec<Edit> {
         LineEndingFixer.check(source, &FixerConfig {
             line_ending: LineEnding::CrLf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
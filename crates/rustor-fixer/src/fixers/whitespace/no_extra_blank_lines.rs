@@ -2,7 +2,25 @@
 
 use rustor_core::Edit;
 use regex::Regex;
-use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::fixers::{ConfigValue, Fixer, FixerConfig, FixerOption, OptionType, edit_with_rule};
+
+/// PHP-CS-Fixer's `tokens` values
+const TOKENS: &[&str] = &[
+    "attribute",
+    "break",
+    "case",
+    "continue",
+    "curly_brace_block",
+    "default",
+    "extra",
+    "parenthesis_brace_block",
+    "return",
+    "square_brace_block",
+    "switch",
+    "throw",
+    "use",
+    "use_trait",
+];
 
 pub struct NoExtraBlankLinesFixer;
 
@@ -12,6 +30,15 @@ impl Fixer for NoExtraBlankLinesFixer {
     fn description(&self) -> &'static str { "Remove extra blank lines" }
     fn priority(&self) -> i32 { 20 }
 
+    fn options(&self) -> Vec<FixerOption> {
+        vec![FixerOption {
+            name: "tokens",
+            description: "Tokens around which extra blank lines are removed; they are removed everywhere for now",
+            option_type: OptionType::EnumArray(TOKENS.to_vec()),
+            default: Some(ConfigValue::Array(vec!["extra".to_string()])),
+        }]
+    }
+
     fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoSinglelineWhitespaceBeforeSemicolonsFixer;
 
@@ -12,14 +13,18 @@ impl Fixer for NoSinglelineWhitespaceBeforeSemicolonsFixer {
     fn description(&self) -> &'static str { "No space before semicolon" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match space(s) before semicolon (not newlines)
         let re = Regex::new(r"[ \t]+;").unwrap();
 
         for m in re.find_iter(source) {
-            if is_in_string(&source[..m.start()]) { continue; }
+            if tokens.is_in_string_or_comment(m.start()) { continue; }
 
             edits.push(edit_with_rule(
                 m.start(), m.end(),
@@ -33,16 +38,6 @@ impl Fixer for NoSinglelineWhitespaceBeforeSemicolonsFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
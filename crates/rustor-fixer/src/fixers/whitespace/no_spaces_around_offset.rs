@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 pub struct NoSpacesAroundOffsetFixer;
 
@@ -12,7 +13,11 @@ impl Fixer for NoSpacesAroundOffsetFixer {
     fn description(&self) -> &'static str { "No spaces in array access" }
     fn priority(&self) -> i32 { 20 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Match $var[ space or space ]
@@ -21,6 +26,11 @@ impl Fixer for NoSpacesAroundOffsetFixer {
             let full = cap.get(0).unwrap();
             let var = cap.get(1).unwrap().as_str();
 
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full.start()) {
+                continue;
+            }
+
             edits.push(edit_with_rule(
                 full.start(), full.end(),
                 format!("{}[", var),
@@ -33,7 +43,7 @@ impl Fixer for NoSpacesAroundOffsetFixer {
         for m in re2.find_iter(source) {
             // Check if this is array access context
             let before = &source[..m.start()];
-            if before.contains('[') && !is_in_string(before) {
+            if before.contains('[') && !tokens.is_in_string_or_comment(m.start()) {
                 edits.push(edit_with_rule(
                     m.start(), m.end(),
                     "]".to_string(),
@@ -47,16 +57,6 @@ impl Fixer for NoSpacesAroundOffsetFixer {
     }
 }
 
-fn is_in_string(before: &str) -> bool {
-    let (mut s, mut d, mut p) = (false, false, '\0');
-    for c in before.chars() {
-        if c == '\'' && p != '\\' && !d { s = !s; }
-        if c == '"' && p != '\\' && !s { d = !d; }
-        p = c;
-    }
-    s || d
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -27,14 +27,14 @@ impl Fixer for NoWhitespaceInBlankLineFixer {
         let mut edits = Vec::new();
         let mut offset = 0;
 
-        for (line_num, line) in source.lines().enumerate() {
+        for line in source.lines() {
             // A blank line is one that contains only whitespace
             if !line.is_empty() && line.trim().is_empty() {
                 edits.push(edit_with_rule(
                     offset,
                     offset + line.len(),
                     String::new(),
-                    format!("Remove whitespace from blank line {}", line_num + 1),
+                    "Remove whitespace from blank line".to_string(),
                     "no_whitespace_in_blank_line",
                 ));
             }
@@ -73,21 +73,19 @@ impl Fixer for SingleBlankLineAtEofFixer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::{IndentStyle, LineEnding};
+    use crate::config::LineEnding;
 
     fn check_lf(source: &str) -> Vec<Edit> {
         SingleBlankLineAtEofFixer.check(source, &FixerConfig {
             line_ending: LineEnding::Lf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
     fn check_crlf(source: &str) -> Vec<Edit> {
         SingleBlankLineAtEofFixer.check(source, &FixerConfig {
             line_ending: LineEnding::CrLf,
-            indent: IndentStyle::default(),
-            options: Default::default(),
+            ..Default::default()
         })
     }
 
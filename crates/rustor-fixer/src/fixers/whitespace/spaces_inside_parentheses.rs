@@ -3,6 +3,7 @@
 use rustor_core::Edit;
 use regex::Regex;
 use crate::fixers::{Fixer, FixerConfig, edit_with_rule};
+use crate::tokens::TokenStream;
 
 /// Remove or add spaces inside parentheses
 pub struct SpacesInsideParenthesesFixer;
@@ -13,18 +14,23 @@ impl Fixer for SpacesInsideParenthesesFixer {
     fn description(&self) -> &'static str { "Remove spaces inside parentheses" }
     fn priority(&self) -> i32 { 35 }
 
-    fn check(&self, source: &str, _config: &FixerConfig) -> Vec<Edit> {
+    fn check(&self, source: &str, config: &FixerConfig) -> Vec<Edit> {
+        self.check_tokens(source, &TokenStream::new(source), config)
+    }
+
+    fn check_tokens(&self, source: &str, tokens: &TokenStream, _config: &FixerConfig) -> Vec<Edit> {
         let mut edits = Vec::new();
 
         // Remove space after opening paren: ( $a → ($a
-        // Match "( " followed by non-whitespace
-        let open_space_re = Regex::new(r"\(\s+([^\s\)])").unwrap();
+        // Match "( " followed by non-whitespace. Line breaks are left to
+        // method_argument_space, which puts multi-line arguments on their own lines
+        let open_space_re = Regex::new(r"\([ \t]+([^\s\)])").unwrap();
         for cap in open_space_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
             let after = cap.get(1).unwrap().as_str();
 
-            // Skip if in string context (basic check)
-            if is_in_string(&source[..full.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -39,13 +45,13 @@ impl Fixer for SpacesInsideParenthesesFixer {
 
         // Remove space before closing paren: $a ) → $a)
         // Match non-whitespace followed by " )"
-        let close_space_re = Regex::new(r"([^\s\(])\s+\)").unwrap();
+        let close_space_re = Regex::new(r"([^\s\(])[ \t]+\)").unwrap();
         for cap in close_space_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
             let before = cap.get(1).unwrap().as_str();
 
-            // Skip if in string context
-            if is_in_string(&source[..full.start()]) {
+            // Skip if in string or comment
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -59,11 +65,11 @@ impl Fixer for SpacesInsideParenthesesFixer {
         }
 
         // Handle empty parens with spaces: ( ) → ()
-        let empty_re = Regex::new(r"\(\s+\)").unwrap();
+        let empty_re = Regex::new(r"\([ \t]+\)").unwrap();
         for cap in empty_re.captures_iter(source) {
             let full = cap.get(0).unwrap();
 
-            if is_in_string(&source[..full.start()]) {
+            if tokens.is_in_string_or_comment(full.start()) {
                 continue;
             }
 
@@ -80,24 +86,6 @@ impl Fixer for SpacesInsideParenthesesFixer {
     }
 }
 
-/// Basic check if position is inside a string
-fn is_in_string(text: &str) -> bool {
-    let mut in_single_quote = false;
-    let mut in_double_quote = false;
-    let mut prev_char = ' ';
-
-    for c in text.chars() {
-        if c == '\'' && prev_char != '\\' && !in_double_quote {
-            in_single_quote = !in_single_quote;
-        } else if c == '"' && prev_char != '\\' && !in_single_quote {
-            in_double_quote = !in_double_quote;
-        }
-        prev_char = c;
-    }
-
-    in_single_quote || in_double_quote
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -137,4 +125,11 @@ mod tests {
         // Should have edits for both sides
         assert!(edits.len() >= 2);
     }
+
+    #[test]
+    fn test_multiline_arguments_untouched() {
+        let code = "<?php\nfoo(\n    $a,\n    $b\n);\nbar(\n);";
+        let edits = SpacesInsideParenthesesFixer.check(code, &FixerConfig::default());
+        assert!(edits.is_empty());
+    }
 }
@@ -27,7 +27,7 @@ impl Fixer for TrailingWhitespaceFixer {
         let mut edits = Vec::new();
         let mut offset = 0;
 
-        for (line_num, line) in source.lines().enumerate() {
+        for line in source.lines() {
             let trimmed = line.trim_end();
             if trimmed.len() < line.len() {
                 let trailing_start = offset + trimmed.len();
@@ -37,7 +37,7 @@ impl Fixer for TrailingWhitespaceFixer {
                     trailing_start,
                     trailing_end,
                     String::new(),
-                    format!("Remove trailing whitespace on line {}", line_num + 1),
+                    "Remove trailing whitespace".to_string(),
                     "no_trailing_whitespace",
                 ));
             }
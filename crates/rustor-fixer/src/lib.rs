@@ -10,6 +10,7 @@
 //! - PSR-12 preset support (~50 fixers)
 //! - Same formatting output as PHP-CS-Fixer
 //! - Priority-based execution order
+//! - Each file is lexed once and the token stream is shared between fixers
 //!
 //! # Example
 //!
@@ -24,6 +25,9 @@
 
 pub mod config;
 pub mod fixers;
+pub mod tokens;
+mod offset_map;
 
 pub use config::{PhpCsFixerConfig, WhitespaceConfig, IndentStyle, LineEnding, PhpConfigValue};
-pub use fixers::{Fixer, FixerRegistry, FixerConfig, ConfigValue};
+pub use fixers::{Fixer, FixerRegistry, FixerConfig, ConfigValue, OptionError};
+pub use tokens::{Region, TokenStream};
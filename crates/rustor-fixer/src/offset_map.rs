@@ -0,0 +1,142 @@
+//! Mapping of offsets in fixed source back to the original source
+//!
+//! Fixers run one after another, each on the output of the previous one, so
+//! their edits refer to intermediate versions of the file. The map keeps the
+//! regions replaced so far, which is enough to translate any later offset.
+
+use rustor_core::Edit;
+
+/// A replaced region: `current` in the edited source came from `original`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Region {
+    current: (usize, usize),
+    original: (usize, usize),
+}
+
+/// Translates offsets in edited source back to the original source
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OffsetMap {
+    /// Sorted, non-overlapping regions in current coordinates
+    regions: Vec<Region>,
+}
+
+impl OffsetMap {
+    /// Original position of `pos`; positions inside a replaced region map to
+    /// its start or end
+    fn original(&self, pos: usize, at_start: bool) -> usize {
+        let mut delta = 0isize;
+        for region in &self.regions {
+            if pos <= region.current.0 {
+                break;
+            }
+            if pos < region.current.1 {
+                return if at_start { region.original.0 } else { region.original.1 };
+            }
+            delta = region.original.1 as isize - region.current.1 as isize;
+        }
+        (pos as isize + delta) as usize
+    }
+
+    /// Original span of `start..end` in the current source
+    ///
+    /// Spans overlapping text inserted by earlier edits grow to cover the
+    /// original text that was replaced.
+    pub(crate) fn original_span(&self, start: usize, end: usize) -> (usize, usize) {
+        (self.original(start, true), self.original(end, false))
+    }
+
+    /// Record edits applied to the current source; replacements must be the
+    /// text that was actually inserted
+    pub(crate) fn apply(&mut self, edits: &[Edit]) {
+        let mut sorted: Vec<&Edit> = edits.iter().collect();
+        sorted.sort_by_key(|edit| (edit.start_offset(), edit.end_offset()));
+
+        let mut regions = Vec::with_capacity(self.regions.len() + sorted.len());
+        let mut old = self.regions.iter().copied().peekable();
+        // Difference between new and current offsets after the edits so far
+        let mut shift = 0isize;
+        let shifted = |region: Region, shift: isize| Region {
+            current: (
+                (region.current.0 as isize + shift) as usize,
+                (region.current.1 as isize + shift) as usize,
+            ),
+            original: region.original,
+        };
+
+        for edit in sorted {
+            let (start, end) = (edit.start_offset(), edit.end_offset());
+            let mut original = self.original_span(start, end);
+            // Current range of the new region, including the rest of absorbed regions
+            let mut current = (start, end);
+
+            while let Some(&region) = old.peek() {
+                let overlaps = (region.current.0 < end && region.current.1 > start)
+                    || (start < end && region.current.0 >= start && region.current.1 <= end);
+                if overlaps {
+                    original = (original.0.min(region.original.0), original.1.max(region.original.1));
+                    current = (current.0.min(region.current.0), current.1.max(region.current.1));
+                } else if region.current.1 <= start {
+                    regions.push(shifted(region, shift));
+                } else {
+                    break;
+                }
+                old.next();
+            }
+
+            let growth = edit.replacement.len() as isize - (end - start) as isize;
+            let new_start = (current.0 as isize + shift) as usize;
+            regions.push(Region {
+                current: (new_start, ((current.1 as isize) + shift + growth) as usize),
+                original,
+            });
+            shift += growth;
+        }
+        regions.extend(old.map(|region| shifted(region, shift)));
+
+        self.regions = regions;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mago_database::file::FileId;
+    use mago_span::{Position, Span};
+
+    fn edit(start: usize, end: usize, replacement: &str) -> Edit {
+        let span = Span::new(FileId::zero(), Position::new(start as u32), Position::new(end as u32));
+        Edit::new(span, replacement, "")
+    }
+
+    #[test]
+    fn test_map_through_rounds() {
+        // "<?php\n$a=1;\n$b=2;\n" -> spaces around the first `=`
+        let mut map = OffsetMap::default();
+        map.apply(&[edit(8, 9, " = ")]);
+        assert_eq!(map.original_span(14, 15), (12, 13));
+
+        // "<?php\n$a = 1;\n$b=2;\n" -> spaces around the second `=`
+        assert_eq!(map.original_span(16, 17), (14, 15));
+        map.apply(&[edit(16, 17, " = ")]);
+
+        // Anything touching the first replacement maps to the original `=`
+        assert_eq!(map.original_span(9, 10), (8, 9));
+        assert_eq!(map.original_span(6, 12), (6, 10));
+
+        // "<?php\n$a = 1;\n$b = 2;\n": `2` was at 15
+        assert_eq!(map.original_span(19, 20), (15, 16));
+        assert_eq!(map.original_span(22, 22), (18, 18));
+    }
+
+    #[test]
+    fn test_overlapping_edits_merge_regions() {
+        let mut map = OffsetMap::default();
+        map.apply(&[edit(2, 4, "abcdef"), edit(10, 10, "xy")]);
+        // A later edit spanning both inserted texts covers both originals
+        assert_eq!(map.original_span(3, 15), (2, 10));
+        map.apply(&[edit(3, 15, "")]);
+        // "ay" is what's left of both replacements
+        assert_eq!(map.original_span(2, 4), (2, 10));
+        assert_eq!(map.original_span(4, 5), (10, 11));
+    }
+}
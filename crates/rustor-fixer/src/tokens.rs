@@ -0,0 +1,300 @@
+//! Shared token stream for fixers
+//!
+//! The registry lexes each file once with mago-syntax's lexer and hands the
+//! resulting [`TokenStream`] to every fixer. Each token is tagged with the
+//! [`Region`] it belongs to, so fixers can ask whether an offset is code,
+//! part of a string (including heredoc/nowdoc bodies and interpolation),
+//! a comment, or inline HTML outside `<?php ... ?>`, without rescanning the
+//! source from the beginning.
+//!
+//! After a fixer's edits are applied, [`TokenStream::update`] re-lexes only
+//! the changed part of the file and reuses the tokens after it.
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::Position;
+use mago_syntax::lexer::Lexer;
+use mago_syntax::token::TokenKind;
+use mago_syntax_core::input::Input;
+use rustor_core::Edit;
+
+/// The kind of source region a token belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Region {
+    /// PHP code
+    Code,
+    /// String literals, heredoc/nowdoc bodies and backtick commands,
+    /// including any interpolated expressions
+    String,
+    /// Line, block and doc comments
+    Comment,
+    /// Text outside of PHP tags
+    InlineHtml,
+}
+
+/// A lexed token with its byte range in the source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub start: usize,
+    pub end: usize,
+    pub region: Region,
+}
+
+impl Token {
+    /// The token's text in the source it was lexed from
+    pub fn text<'s>(&self, source: &'s str) -> &'s str {
+        &source[self.start..self.end]
+    }
+
+    /// Whether the lexer can be restarted in scripting mode at this token
+    fn is_resync_point(&self) -> bool {
+        self.kind == TokenKind::Whitespace && self.region == Region::Code
+    }
+}
+
+/// Tokens of a whole file, in source order
+#[derive(Debug, Clone, Default)]
+pub struct TokenStream {
+    tokens: Vec<Token>,
+}
+
+impl TokenStream {
+    /// Lex the whole source
+    pub fn new(source: &str) -> Self {
+        let mut stream = Self { tokens: Vec::new() };
+        stream.lex(source, 0, false, None);
+        stream
+    }
+
+    /// All tokens in source order
+    pub fn tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// The token containing the given byte offset
+    pub fn token_at(&self, offset: usize) -> Option<&Token> {
+        let idx = self.tokens.partition_point(|t| t.end <= offset);
+        self.tokens.get(idx).filter(|t| t.start <= offset)
+    }
+
+    /// The region of the given byte offset (code if it isn't covered by a token)
+    pub fn region_at(&self, offset: usize) -> Region {
+        self.token_at(offset).map_or(Region::Code, |t| t.region)
+    }
+
+    /// Whether the given byte offset is PHP code
+    pub fn is_code(&self, offset: usize) -> bool {
+        self.region_at(offset) == Region::Code
+    }
+
+    /// Whether the given byte offset is inside a string, comment or inline HTML
+    pub fn is_in_string_or_comment(&self, offset: usize) -> bool {
+        !self.is_code(offset)
+    }
+
+    /// Bring the stream up to date after `edits` were applied, producing `source`
+    ///
+    /// Lexing restarts at the last whitespace in code before the first edit and
+    /// stops as soon as it's back in step with the old tokens after the last edit.
+    pub fn update(&mut self, source: &str, edits: &[Edit]) {
+        let Some(changed_start) = edits.iter().map(|e| e.span.start.offset as usize).min() else {
+            return;
+        };
+        let old_changed_end = edits.iter().map(|e| e.span.end.offset as usize).max().unwrap_or(changed_start);
+        let delta: isize = edits
+            .iter()
+            .map(|e| e.replacement.len() as isize - (e.span.end.offset - e.span.start.offset) as isize)
+            .sum();
+
+        let restart = self.tokens[..self.tokens.partition_point(|t| t.start <= changed_start)]
+            .iter()
+            .rposition(|t| t.is_resync_point());
+        let old_tail = self.tokens.split_off(restart.unwrap_or(0));
+        let (offset, scripting) = match old_tail.first() {
+            Some(token) if restart.is_some() => (token.start, true),
+            _ => (0, false),
+        };
+
+        let resync = Resync {
+            old_tail: &old_tail,
+            new_changed_end: (old_changed_end as isize + delta) as usize,
+            delta,
+        };
+        self.lex(source, offset, scripting, Some(resync));
+    }
+
+    /// Lex `source` from `offset`, appending to the stream
+    fn lex(&mut self, source: &str, offset: usize, scripting: bool, resync: Option<Resync<'_>>) {
+        let arena = Bump::new();
+        let input = Input::anchored_at(FileId::zero(), &source.as_bytes()[offset..], Position::new(offset as u32));
+        let mut lexer = if scripting {
+            Lexer::scripting(&arena, input)
+        } else {
+            Lexer::new(&arena, input)
+        };
+        let mut classifier = RegionClassifier::default();
+
+        // Stop at the first lexer error; the rest of the file is treated as code
+        while let Some(Ok(token)) = lexer.advance() {
+            let token = Token {
+                kind: token.kind,
+                start: token.span.start.offset as usize,
+                end: token.span.end.offset as usize,
+                region: classifier.classify(token.kind),
+            };
+
+            if let Some(resync) = &resync {
+                if let Some(rest) = resync.matching_tail(&token) {
+                    self.tokens.extend(rest);
+                    return;
+                }
+            }
+            self.tokens.push(token);
+        }
+    }
+}
+
+/// Old tokens after an edit, used to stop re-lexing early
+struct Resync<'t> {
+    old_tail: &'t [Token],
+    new_changed_end: usize,
+    delta: isize,
+}
+
+impl Resync<'_> {
+    /// If `token` lines up with an old token past the edits, the old tokens from
+    /// there on, shifted to their new positions
+    fn matching_tail(&self, token: &Token) -> Option<impl Iterator<Item = Token> + '_> {
+        if token.start < self.new_changed_end || !token.is_resync_point() {
+            return None;
+        }
+        let old_start = (token.start as isize - self.delta) as usize;
+        let idx = self.old_tail.binary_search_by_key(&old_start, |t| t.start).ok()?;
+        let old = &self.old_tail[idx];
+        if !old.is_resync_point() || old.end - old.start != token.end - token.start {
+            return None;
+        }
+
+        let delta = self.delta;
+        Some(self.old_tail[idx..].iter().map(move |t| Token {
+            start: (t.start as isize + delta) as usize,
+            end: (t.end as isize + delta) as usize,
+            ..*t
+        }))
+    }
+}
+
+/// String contexts the lexer can be inside of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frame {
+    DoubleQuote,
+    Backtick,
+    Document,
+    Interpolation,
+}
+
+/// Tracks nesting of interpolated strings to assign regions to tokens
+#[derive(Debug, Default)]
+struct RegionClassifier {
+    frames: Vec<Frame>,
+}
+
+impl RegionClassifier {
+    fn classify(&mut self, kind: TokenKind) -> Region {
+        let in_string = !self.frames.is_empty();
+        let top = self.frames.last().copied();
+
+        match kind {
+            TokenKind::DoubleQuote if top == Some(Frame::DoubleQuote) => {
+                self.frames.pop();
+            }
+            TokenKind::Backtick if top == Some(Frame::Backtick) => {
+                self.frames.pop();
+            }
+            TokenKind::DocumentEnd if top == Some(Frame::Document) => {
+                self.frames.pop();
+            }
+            TokenKind::RightBrace if top == Some(Frame::Interpolation) => {
+                self.frames.pop();
+            }
+            TokenKind::DoubleQuote => self.frames.push(Frame::DoubleQuote),
+            TokenKind::Backtick => self.frames.push(Frame::Backtick),
+            TokenKind::DocumentStart(_) => self.frames.push(Frame::Document),
+            // `{$` and `${` inside a string, or a nested brace within one
+            TokenKind::LeftBrace | TokenKind::DollarLeftBrace if in_string => self.frames.push(Frame::Interpolation),
+            _ => {}
+        }
+
+        if in_string || !self.frames.is_empty() {
+            return Region::String;
+        }
+        match kind {
+            TokenKind::LiteralString | TokenKind::PartialLiteralString => Region::String,
+            kind if kind.is_comment() => Region::Comment,
+            TokenKind::InlineText | TokenKind::InlineShebang => Region::InlineHtml,
+            _ => Region::Code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustor_core::apply_edits;
+
+    fn region_of(source: &str, needle: &str) -> Region {
+        TokenStream::new(source).region_at(source.find(needle).unwrap())
+    }
+
+    #[test]
+    fn test_regions() {
+        let source = "<html><?php\n$a = 'IF'; // IF\n/* IF */ $b = \"x {$c['IF']} IF\";\n?>IF";
+        assert_eq!(region_of(source, "html"), Region::InlineHtml);
+        assert_eq!(region_of(source, "$a"), Region::Code);
+        assert_eq!(region_of(source, "'IF'"), Region::String);
+        assert_eq!(region_of(source, "// IF"), Region::Comment);
+        assert_eq!(region_of(source, "/* IF"), Region::Comment);
+        assert_eq!(region_of(source, "$c"), Region::String);
+        assert_eq!(region_of(source, "} IF"), Region::String);
+        assert_eq!(region_of(source, "?>"), Region::Code);
+        assert_eq!(TokenStream::new(source).region_at(source.len() - 1), Region::InlineHtml);
+    }
+
+    #[test]
+    fn test_heredoc_is_string() {
+        let source = "<?php\n$a = <<<EOT\nIF $b {$c->d} IF\nEOT;\n$e = 1;\n";
+        assert_eq!(region_of(source, "IF $b"), Region::String);
+        assert_eq!(region_of(source, "$b"), Region::String);
+        assert_eq!(region_of(source, "->d"), Region::String);
+        assert_eq!(region_of(source, "$e"), Region::Code);
+    }
+
+    #[test]
+    fn test_update_matches_full_relex() {
+        let source = "<?php\n$a = 1;\n$b = \"x $a\";\n// done\n$c = 3;\n";
+        let mut stream = TokenStream::new(source);
+
+        let start = source.find("1;").unwrap();
+        let edit = crate::fixers::edit_with_rule(start, start + 1, "'one'".to_string(), String::new(), "test");
+        let updated = apply_edits(source, std::slice::from_ref(&edit)).unwrap();
+        stream.update(&updated, &[edit]);
+
+        assert_eq!(stream.tokens(), TokenStream::new(&updated).tokens());
+        assert_eq!(stream.region_at(updated.find("'one'").unwrap()), Region::String);
+    }
+
+    #[test]
+    fn test_update_opening_string() {
+        // An edit that turns following code into a string must not resync early
+        let source = "<?php\n$a = 1;\n$b = 2;\n";
+        let mut stream = TokenStream::new(source);
+
+        let start = source.find("1;").unwrap();
+        let edit = crate::fixers::edit_with_rule(start, start + 1, "<<<EOT\n".to_string(), String::new(), "test");
+        let updated = apply_edits(source, std::slice::from_ref(&edit)).unwrap();
+        stream.update(&updated, &[edit]);
+
+        assert_eq!(stream.tokens(), TokenStream::new(&updated).tokens());
+    }
+}
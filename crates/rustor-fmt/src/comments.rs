@@ -0,0 +1,91 @@
+//! Comments of the file being formatted
+//!
+//! Comments aren't part of the AST, so the formatter places them itself: as it
+//! prints statements, members and list items it takes the comments before and
+//! after each one. Comments the formatter couldn't place inside a node make it
+//! fall back to printing that node verbatim.
+
+use std::cell::RefCell;
+
+use mago_syntax::ast::{Program, TriviaKind};
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Comment<'a> {
+    pub start: usize,
+    pub end: usize,
+    pub text: &'a str,
+    /// A `//` or `#` comment, which must be followed by a line break
+    pub is_line: bool,
+}
+
+pub(crate) struct Comments<'a> {
+    /// All comments, in source order
+    list: Vec<Comment<'a>>,
+    taken: RefCell<Vec<bool>>,
+}
+
+impl<'a> Comments<'a> {
+    pub(crate) fn new(program: &Program<'_>, source: &'a str) -> Self {
+        let list: Vec<Comment<'a>> = program
+            .trivia
+            .iter()
+            .filter(|trivia| trivia.kind.is_comment())
+            .map(|trivia| Comment {
+                start: trivia.span.start.offset as usize,
+                end: trivia.span.end.offset as usize,
+                text: source[trivia.span.start.offset as usize..trivia.span.end.offset as usize].trim_end(),
+                is_line: matches!(trivia.kind, TriviaKind::SingleLineComment | TriviaKind::HashComment),
+            })
+            .collect();
+        let taken = RefCell::new(vec![false; list.len()]);
+        Self { list, taken }
+    }
+
+    /// Take all remaining comments starting before `offset`
+    pub(crate) fn take_before(&self, offset: usize) -> Vec<Comment<'a>> {
+        let end = self.list.partition_point(|c| c.start < offset);
+        let mut taken = self.taken.borrow_mut();
+        (0..end)
+            .filter(|&idx| !std::mem::replace(&mut taken[idx], true))
+            .map(|idx| self.list[idx])
+            .collect()
+    }
+
+    /// Take the comments after `offset` on the same line
+    pub(crate) fn take_trailing(&self, offset: usize, source: &str) -> Vec<Comment<'a>> {
+        let mut taken = self.taken.borrow_mut();
+        let mut trailing = Vec::new();
+        let mut from = offset;
+        for idx in self.list.partition_point(|c| c.start < offset)..self.list.len() {
+            let comment = self.list[idx];
+            if taken[idx] || !source[from..comment.start].trim().is_empty() || source[from..comment.start].contains('\n') {
+                break;
+            }
+            taken[idx] = true;
+            trailing.push(comment);
+            if comment.is_line {
+                break;
+            }
+            from = comment.end;
+        }
+        trailing
+    }
+
+    /// Whether any comment in `start..end` hasn't been placed yet
+    pub(crate) fn any_within(&self, start: usize, end: usize) -> bool {
+        let taken = self.taken.borrow();
+        let first = self.list.partition_point(|c| c.start < start);
+        (first..self.list.len())
+            .take_while(|&idx| self.list[idx].start < end)
+            .any(|idx| !taken[idx])
+    }
+
+    /// Mark all comments in `start..end` as placed
+    pub(crate) fn take_within(&self, start: usize, end: usize) {
+        let mut taken = self.taken.borrow_mut();
+        let first = self.list.partition_point(|c| c.start < start);
+        for idx in (first..self.list.len()).take_while(|&idx| self.list[idx].start < end) {
+            taken[idx] = true;
+        }
+    }
+}
@@ -0,0 +1,321 @@
+//! Layout documents and the line-fitting printer
+//!
+//! The formatter describes its output as a [`Doc`]: text, places where a line
+//! may break, indentation and groups. The printer lays out each group on one
+//! line if it fits in the remaining width, and breaks all of the group's lines
+//! otherwise, in the style of Wadler's "prettier printer" and Prettier.
+
+use std::collections::HashMap;
+
+/// Identifies a group, so other parts of the document can depend on whether
+/// it was broken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct GroupId(pub usize);
+
+#[derive(Debug, Clone)]
+pub(crate) enum Doc {
+    /// Text without newlines
+    Text(String),
+    /// A space, or a newline if the enclosing group is broken
+    Line,
+    /// Nothing, or a newline if the enclosing group is broken
+    SoftLine,
+    /// Always a newline; breaks all enclosing groups
+    HardLine,
+    /// A newline without indentation, for text that must be kept verbatim
+    LiteralLine,
+    Concat(Vec<Doc>),
+    Indent(Box<Doc>),
+    Group {
+        contents: Box<Doc>,
+        id: Option<GroupId>,
+        /// Contains a hard line, so it can never be flat
+        broken: bool,
+    },
+    /// Alternative layouts of the same content: the first if it fits flat,
+    /// else the first of the others that fits up to its first forced break,
+    /// else the last one, broken
+    ConditionalGroup(Vec<Doc>),
+    /// Prints `broken` if the group (by default the enclosing one) is broken
+    IfBreak {
+        broken: Box<Doc>,
+        flat: Box<Doc>,
+        group: Option<GroupId>,
+    },
+}
+
+pub(crate) fn text(s: impl Into<String>) -> Doc {
+    Doc::Text(s.into())
+}
+
+pub(crate) fn concat(docs: Vec<Doc>) -> Doc {
+    Doc::Concat(docs)
+}
+
+pub(crate) fn indent(doc: Doc) -> Doc {
+    Doc::Indent(Box::new(doc))
+}
+
+pub(crate) fn group(doc: Doc) -> Doc {
+    group_with_id(doc, None)
+}
+
+pub(crate) fn group_with_id(doc: Doc, id: Option<GroupId>) -> Doc {
+    let broken = doc.forces_break();
+    Doc::Group { contents: Box::new(doc), id, broken }
+}
+
+/// A group that is always broken
+pub(crate) fn broken_group(doc: Doc) -> Doc {
+    Doc::Group { contents: Box::new(doc), id: None, broken: true }
+}
+
+/// The same group, forced to break
+pub(crate) fn force_break(doc: Doc) -> Doc {
+    match doc {
+        Doc::Group { contents, id, .. } => Doc::Group { contents, id, broken: true },
+        other => broken_group(other),
+    }
+}
+
+pub(crate) fn conditional_group(states: Vec<Doc>) -> Doc {
+    Doc::ConditionalGroup(states)
+}
+
+/// `broken` if the enclosing group breaks, `flat` otherwise
+pub(crate) fn if_break(broken: Doc, flat: Doc) -> Doc {
+    Doc::IfBreak { broken: Box::new(broken), flat: Box::new(flat), group: None }
+}
+
+/// `broken` if the given group breaks, `flat` otherwise
+pub(crate) fn if_group_breaks(id: GroupId, broken: Doc, flat: Doc) -> Doc {
+    Doc::IfBreak { broken: Box::new(broken), flat: Box::new(flat), group: Some(id) }
+}
+
+/// Source text kept as is, with its own line breaks and indentation
+pub(crate) fn verbatim(source: &str) -> Doc {
+    let mut parts = Vec::new();
+    for (idx, line) in source.split('\n').enumerate() {
+        if idx > 0 {
+            parts.push(Doc::LiteralLine);
+        }
+        if !line.is_empty() {
+            parts.push(text(line));
+        }
+    }
+    concat(parts)
+}
+
+/// Join documents with a separator
+pub(crate) fn join(docs: Vec<Doc>, separator: Doc) -> Doc {
+    let mut parts = Vec::with_capacity(docs.len() * 2);
+    for (idx, doc) in docs.into_iter().enumerate() {
+        if idx > 0 {
+            parts.push(separator.clone());
+        }
+        parts.push(doc);
+    }
+    concat(parts)
+}
+
+impl Doc {
+    /// Whether this document always breaks the group it is in
+    pub(crate) fn forces_break(&self) -> bool {
+        match self {
+            Doc::HardLine | Doc::LiteralLine => true,
+            Doc::Text(_) | Doc::Line | Doc::SoftLine => false,
+            Doc::Concat(docs) => docs.iter().any(Doc::forces_break),
+            Doc::Indent(doc) => doc.forces_break(),
+            Doc::Group { broken, .. } => *broken,
+            Doc::ConditionalGroup(states) => states.iter().all(Doc::forces_break),
+            Doc::IfBreak { flat, .. } => flat.forces_break(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Lay out a document
+pub(crate) fn print(doc: &Doc, width: usize, indent_unit: &str, newline: &str) -> String {
+    let indent_width = indent_unit.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum::<usize>();
+    let mut out = String::new();
+    let mut column = 0usize;
+    // Indentation is written lazily, so blank lines don't get trailing whitespace
+    let mut pending_indent: Option<usize> = None;
+    let mut group_modes: HashMap<GroupId, Mode> = HashMap::new();
+    let mut stack: Vec<(usize, Mode, &Doc)> = vec![(0, Mode::Break, doc)];
+
+    while let Some((level, mode, doc)) = stack.pop() {
+        match doc {
+            Doc::Text(s) => write_text(&mut out, &mut column, &mut pending_indent, indent_unit, s),
+            Doc::Line if mode == Mode::Flat => {
+                write_text(&mut out, &mut column, &mut pending_indent, indent_unit, " ");
+            }
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine => {
+                out.push_str(newline);
+                pending_indent = Some(level);
+                column = level * indent_width;
+            }
+            Doc::LiteralLine => {
+                out.push_str(newline);
+                pending_indent = None;
+                column = 0;
+            }
+            Doc::Concat(docs) => {
+                for doc in docs.iter().rev() {
+                    stack.push((level, mode, doc));
+                }
+            }
+            Doc::Indent(doc) => stack.push((level + 1, mode, doc)),
+            Doc::Group { contents, id, broken } => {
+                let group_mode = if !*broken
+                    && (mode == Mode::Flat
+                        || fits(contents, width as isize - column as isize, &stack, &group_modes))
+                {
+                    Mode::Flat
+                } else {
+                    Mode::Break
+                };
+                if let Some(id) = id {
+                    group_modes.insert(*id, group_mode);
+                }
+                stack.push((level, group_mode, contents));
+            }
+            Doc::ConditionalGroup(states) => {
+                let remaining = width as isize - column as isize;
+                let (first, last) = (&states[0], &states[states.len() - 1]);
+                if !first.forces_break() && (mode == Mode::Flat || fits(first, remaining, &stack, &group_modes)) {
+                    stack.push((level, Mode::Flat, first));
+                } else {
+                    let state = states[1..states.len() - 1]
+                        .iter()
+                        .find(|state| fits(state, remaining, &stack, &group_modes))
+                        .map(|state| (Mode::Flat, state))
+                        .unwrap_or((Mode::Break, last));
+                    stack.push((level, state.0, state.1));
+                }
+            }
+            Doc::IfBreak { broken, flat, group } => {
+                let group_mode = group.and_then(|id| group_modes.get(&id).copied()).unwrap_or(mode);
+                let doc = if group_mode == Mode::Break { broken } else { flat };
+                stack.push((level, mode, doc));
+            }
+        }
+    }
+
+    out
+}
+
+fn write_text(out: &mut String, column: &mut usize, pending_indent: &mut Option<usize>, indent_unit: &str, s: &str) {
+    if s.is_empty() {
+        return;
+    }
+    if let Some(level) = pending_indent.take() {
+        for _ in 0..level {
+            out.push_str(indent_unit);
+        }
+    }
+    out.push_str(s);
+    *column += s.chars().count();
+}
+
+/// Whether `doc` fits flat in `remaining` columns, followed by the rest of
+/// the line from `rest`
+///
+/// Groups that must break are measured up to their first line break.
+fn fits(doc: &Doc, mut remaining: isize, rest: &[(usize, Mode, &Doc)], group_modes: &HashMap<GroupId, Mode>) -> bool {
+    let mut pending: Vec<(Mode, &Doc)> = vec![(Mode::Flat, doc)];
+    let mut rest_idx = rest.len();
+
+    while remaining >= 0 {
+        let (mode, doc) = match pending.pop() {
+            Some(next) => next,
+            None => {
+                if rest_idx == 0 {
+                    return true;
+                }
+                rest_idx -= 1;
+                let (_, mode, doc) = rest[rest_idx];
+                (mode, doc)
+            }
+        };
+
+        match doc {
+            Doc::Text(s) => remaining -= s.chars().count() as isize,
+            Doc::Line if mode == Mode::Flat => remaining -= 1,
+            Doc::SoftLine if mode == Mode::Flat => {}
+            Doc::Line | Doc::SoftLine | Doc::HardLine | Doc::LiteralLine => return true,
+            Doc::Concat(docs) => pending.extend(docs.iter().rev().map(|doc| (mode, doc))),
+            Doc::Indent(doc) => pending.push((mode, doc)),
+            Doc::Group { contents, broken, .. } => {
+                pending.push((if *broken { Mode::Break } else { Mode::Flat }, contents));
+            }
+            Doc::ConditionalGroup(states) => pending.push((mode, &states[0])),
+            Doc::IfBreak { broken, flat, group } => {
+                let group_mode = group.and_then(|id| group_modes.get(&id).copied()).unwrap_or(mode);
+                pending.push((mode, if group_mode == Mode::Break { broken } else { flat }));
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(args: Vec<&str>) -> Doc {
+        let args = args.into_iter().map(text).collect();
+        group(concat(vec![
+            text("foo("),
+            indent(concat(vec![Doc::SoftLine, join(args, concat(vec![text(","), Doc::Line])), if_break(text(","), text(""))])),
+            Doc::SoftLine,
+            text(");"),
+        ]))
+    }
+
+    #[test]
+    fn test_group_fits_or_breaks() {
+        let doc = call(vec!["$a", "$b"]);
+        assert_eq!(print(&doc, 20, "    ", "\n"), "foo($a, $b);");
+        assert_eq!(print(&doc, 10, "    ", "\n"), "foo(\n    $a,\n    $b,\n);");
+    }
+
+    #[test]
+    fn test_hard_line_breaks_enclosing_group() {
+        let doc = group(concat(vec![text("["), indent(concat(vec![Doc::Line, verbatim("<<<X\n  a\nX")])), Doc::Line, text("]")]));
+        assert_eq!(print(&doc, 80, "    ", "\n"), "[\n    <<<X\n  a\nX\n]");
+    }
+
+    #[test]
+    fn test_blank_lines_have_no_indentation() {
+        let doc = indent(concat(vec![Doc::HardLine, text("a;"), Doc::HardLine, Doc::HardLine, text("b;")]));
+        assert_eq!(print(&doc, 80, "\t", "\n"), "\n\ta;\n\n\tb;");
+    }
+
+    #[test]
+    fn test_conditional_group_hugs_last_argument() {
+        let array = group(concat(vec![text("["), indent(concat(vec![Doc::SoftLine, text("'a' => 1,"), Doc::Line, text("'b' => 2")])), Doc::SoftLine, text("]")]));
+        let hugged = concat(vec![text("foo($x, "), array.clone(), text(");")]);
+        let expanded = concat(vec![text("foo($x, "), force_break(array), text(");")]);
+        let doc = conditional_group(vec![hugged, expanded, call(vec!["$x", "[...]"])]);
+        assert_eq!(print(&doc, 80, "    ", "\n"), "foo($x, ['a' => 1, 'b' => 2]);");
+        assert_eq!(print(&doc, 20, "    ", "\n"), "foo($x, [\n    'a' => 1,\n    'b' => 2\n]);");
+        assert_eq!(print(&doc, 6, "    ", "\n"), "foo(\n    $x,\n    [...],\n);");
+    }
+
+    #[test]
+    fn test_if_group_breaks() {
+        let id = GroupId(1);
+        let params = group_with_id(concat(vec![text("("), indent(concat(vec![Doc::SoftLine, text("$a")])), Doc::SoftLine, text(")")]), Some(id));
+        let doc = concat(vec![text("function f"), params, if_group_breaks(id, text(" {"), concat(vec![Doc::HardLine, text("{")]))]);
+        assert_eq!(print(&doc, 80, "    ", "\n"), "function f($a)\n{");
+        assert_eq!(print(&doc, 12, "    ", "\n"), "function f(\n    $a\n) {");
+    }
+}
@@ -0,0 +1,283 @@
+//! Classes, interfaces, traits, enums, functions and their members
+
+use mago_span::{HasSpan, Span};
+use mago_syntax::ast::sequence::{Sequence, TokenSeparatedSequence};
+use mago_syntax::ast::*;
+
+use super::Formatter;
+use crate::doc::{concat, if_group_breaks, join, text, Doc, GroupId};
+
+/// Members are separated by a blank line around methods
+fn blank_between_members(previous: &ClassLikeMember<'_>, next: &ClassLikeMember<'_>) -> bool {
+    matches!(previous, ClassLikeMember::Method(_)) || matches!(next, ClassLikeMember::Method(_))
+}
+
+impl Formatter<'_> {
+    pub(super) fn class(&self, class: &Class<'_>) -> Doc {
+        let mut parts = self.attribute_lines(class.attribute_lists.as_slice());
+        parts.push(self.modifiers(&class.modifiers));
+        parts.push(self.keyword(&class.class));
+        parts.push(text(" "));
+        parts.push(text(self.text(class.name.span())));
+        parts.push(self.inheritance(class.extends.as_ref(), class.implements.as_ref()));
+        parts.push(Doc::HardLine);
+        parts.push(self.members(&class.members, class.right_brace));
+        concat(parts)
+    }
+
+    pub(super) fn interface(&self, interface: &Interface<'_>) -> Doc {
+        let mut parts = self.attribute_lines(interface.attribute_lists.as_slice());
+        parts.push(self.keyword(&interface.interface));
+        parts.push(text(" "));
+        parts.push(text(self.text(interface.name.span())));
+        parts.push(self.inheritance(interface.extends.as_ref(), None));
+        parts.push(Doc::HardLine);
+        parts.push(self.members(&interface.members, interface.right_brace));
+        concat(parts)
+    }
+
+    pub(super) fn r#trait(&self, r#trait: &Trait<'_>) -> Doc {
+        let mut parts = self.attribute_lines(r#trait.attribute_lists.as_slice());
+        parts.push(self.keyword(&r#trait.r#trait));
+        parts.push(text(" "));
+        parts.push(text(self.text(r#trait.name.span())));
+        parts.push(Doc::HardLine);
+        parts.push(self.members(&r#trait.members, r#trait.right_brace));
+        concat(parts)
+    }
+
+    pub(super) fn r#enum(&self, r#enum: &Enum<'_>) -> Doc {
+        let mut parts = self.attribute_lines(r#enum.attribute_lists.as_slice());
+        parts.push(self.keyword(&r#enum.r#enum));
+        parts.push(text(" "));
+        parts.push(text(self.text(r#enum.name.span())));
+        if let Some(backing) = &r#enum.backing_type_hint {
+            parts.push(text(": "));
+            parts.push(self.verbatim(backing.hint.span()));
+        }
+        parts.push(self.inheritance(None, r#enum.implements.as_ref()));
+        parts.push(Doc::HardLine);
+        parts.push(self.members(&r#enum.members, r#enum.right_brace));
+        concat(parts)
+    }
+
+    pub(super) fn anonymous_class(&self, class: &AnonymousClass<'_>) -> Doc {
+        let mut parts = vec![self.keyword(&class.new), text(" ")];
+        parts.extend(self.inline_attributes(class.attribute_lists.as_slice()));
+        parts.push(self.modifiers(&class.modifiers));
+        parts.push(self.keyword(&class.class));
+        if let Some(arguments) = &class.argument_list {
+            parts.push(self.arguments(arguments));
+        }
+        parts.push(self.inheritance(class.extends.as_ref(), class.implements.as_ref()));
+        parts.push(text(" "));
+        parts.push(self.members(&class.members, class.right_brace));
+        concat(parts)
+    }
+
+    pub(super) fn function(&self, function: &Function<'_>) -> Doc {
+        let mut parts = self.attribute_lines(function.attribute_lists.as_slice());
+        parts.push(self.keyword(&function.function));
+        parts.push(text(" "));
+        if function.ampersand.is_some() {
+            parts.push(text("&"));
+        }
+        parts.push(text(self.text(function.name.span())));
+        parts.extend(self.signature(&function.parameter_list, function.return_type_hint.as_ref(), Some(&function.body)));
+        concat(parts)
+    }
+
+    /// ` extends A implements B, C`
+    fn inheritance(&self, extends: Option<&Extends<'_>>, implements: Option<&Implements<'_>>) -> Doc {
+        let mut parts = Vec::new();
+        if let Some(extends) = extends {
+            parts.push(text(" "));
+            parts.push(self.keyword(&extends.extends));
+            parts.push(text(" "));
+            parts.push(self.names(&extends.types));
+        }
+        if let Some(implements) = implements {
+            parts.push(text(" "));
+            parts.push(self.keyword(&implements.implements));
+            parts.push(text(" "));
+            parts.push(self.names(&implements.types));
+        }
+        concat(parts)
+    }
+
+    fn names(&self, names: &TokenSeparatedSequence<'_, Identifier<'_>>) -> Doc {
+        join(names.iter().map(|name| text(self.text(name.span()))).collect(), text(", "))
+    }
+
+    /// `public static `, lowercased
+    fn modifiers(&self, modifiers: &Sequence<'_, Modifier<'_>>) -> Doc {
+        concat(
+            modifiers
+                .iter()
+                .map(|modifier| text(format!("{} ", self.text(modifier.span()).to_ascii_lowercase())))
+                .collect(),
+        )
+    }
+
+    /// Attribute lists of a declaration, each on its own line
+    fn attribute_lines(&self, lists: &[AttributeList<'_>]) -> Vec<Doc> {
+        lists.iter().flat_map(|list| [self.attribute_list(list), Doc::HardLine]).collect()
+    }
+
+    /// Attribute lists of a parameter or closure, on the same line
+    pub(super) fn inline_attributes(&self, lists: &[AttributeList<'_>]) -> Vec<Doc> {
+        lists.iter().flat_map(|list| [self.attribute_list(list), text(" ")]).collect()
+    }
+
+    fn attribute_list(&self, list: &AttributeList<'_>) -> Doc {
+        self.comma_list(list.hash_left_bracket, &list.attributes, list.right_bracket, false, None, |attribute| {
+            let mut parts = vec![text(self.text(attribute.name.span()))];
+            if let Some(arguments) = &attribute.argument_list {
+                parts.push(self.arguments(arguments));
+            }
+            concat(parts)
+        })
+    }
+
+    fn members(&self, members: &Sequence<'_, ClassLikeMember<'_>>, right_brace: Span) -> Doc {
+        let end = right_brace.start.offset as usize;
+        self.braced(self.line_list(members.as_slice(), end, |member| Some(self.member(member)), blank_between_members))
+    }
+
+    fn member(&self, member: &ClassLikeMember<'_>) -> Doc {
+        match member {
+            ClassLikeMember::TraitUse(TraitUse {
+                r#use,
+                trait_names,
+                specification: TraitUseSpecification::Abstract(TraitUseAbstractSpecification(terminator)),
+            }) => concat(vec![self.keyword(r#use), text(" "), self.names(trait_names), self.terminator(terminator)]),
+            ClassLikeMember::Constant(constant) => {
+                let mut parts = self.attribute_lines(constant.attribute_lists.as_slice());
+                parts.push(self.modifiers(&constant.modifiers));
+                parts.push(self.keyword(&constant.r#const));
+                parts.push(text(" "));
+                if let Some(hint) = &constant.hint {
+                    parts.push(self.verbatim(hint.span()));
+                    parts.push(text(" "));
+                }
+                let items = constant
+                    .items
+                    .iter()
+                    .map(|item| self.assigned(text(self.text(item.name.span())), &item.value))
+                    .collect();
+                parts.push(join(items, text(", ")));
+                parts.push(self.terminator(&constant.terminator));
+                concat(parts)
+            }
+            ClassLikeMember::Property(Property::Plain(property)) => {
+                let mut parts = self.attribute_lines(property.attribute_lists.as_slice());
+                parts.push(self.modifiers(&property.modifiers));
+                if let Some(var) = &property.var {
+                    parts.push(self.keyword(var));
+                    parts.push(text(" "));
+                }
+                if let Some(hint) = &property.hint {
+                    parts.push(self.verbatim(hint.span()));
+                    parts.push(text(" "));
+                }
+                let items = property
+                    .items
+                    .iter()
+                    .map(|item| match item {
+                        PropertyItem::Abstract(item) => text(self.text(item.variable.span())),
+                        PropertyItem::Concrete(item) => self.assigned(text(self.text(item.variable.span())), &item.value),
+                    })
+                    .collect();
+                parts.push(join(items, text(", ")));
+                parts.push(self.terminator(&property.terminator));
+                concat(parts)
+            }
+            ClassLikeMember::EnumCase(case) => {
+                let mut parts = self.attribute_lines(case.attribute_lists.as_slice());
+                parts.push(self.keyword(&case.case));
+                parts.push(text(" "));
+                parts.push(match &case.item {
+                    EnumCaseItem::Unit(item) => text(self.text(item.name.span())),
+                    EnumCaseItem::Backed(item) => self.assigned(text(self.text(item.name.span())), &item.value),
+                });
+                parts.push(self.terminator(&case.terminator));
+                concat(parts)
+            }
+            ClassLikeMember::Method(method) => {
+                let mut parts = self.attribute_lines(method.attribute_lists.as_slice());
+                parts.push(self.modifiers(&method.modifiers));
+                parts.push(self.keyword(&method.function));
+                parts.push(text(" "));
+                if method.ampersand.is_some() {
+                    parts.push(text("&"));
+                }
+                parts.push(text(self.text(method.name.span())));
+                let body = match &method.body {
+                    MethodBody::Concrete(block) => Some(block),
+                    MethodBody::Abstract(_) => None,
+                };
+                parts.extend(self.signature(&method.parameter_list, method.return_type_hint.as_ref(), body));
+                concat(parts)
+            }
+            other => self.verbatim(other.span()),
+        }
+    }
+
+    /// Parameters, return type and body of a function or method: the brace
+    /// goes on its own line, unless the parameters were broken
+    fn signature(
+        &self,
+        parameters: &FunctionLikeParameterList<'_>,
+        return_type: Option<&FunctionLikeReturnTypeHint<'_>>,
+        body: Option<&Block<'_>>,
+    ) -> Vec<Doc> {
+        let id = self.group_id();
+        let mut parts = vec![self.parameter_list(parameters, Some(id))];
+        if let Some(return_type) = return_type {
+            parts.push(self.return_type(return_type));
+        }
+        match body {
+            Some(body) => {
+                parts.push(if_group_breaks(id, text(" "), Doc::HardLine));
+                parts.push(self.block(body));
+            }
+            None => parts.push(text(";")),
+        }
+        parts
+    }
+
+    pub(super) fn parameter_list(&self, list: &FunctionLikeParameterList<'_>, id: Option<GroupId>) -> Doc {
+        self.comma_list(list.left_parenthesis, &list.parameters, list.right_parenthesis, false, id, |parameter| {
+            self.parameter(parameter)
+        })
+    }
+
+    fn parameter(&self, parameter: &FunctionLikeParameter<'_>) -> Doc {
+        if parameter.hooks.is_some() {
+            return self.verbatim(parameter.span());
+        }
+
+        let mut parts = self.inline_attributes(parameter.attribute_lists.as_slice());
+        parts.push(self.modifiers(&parameter.modifiers));
+        if let Some(hint) = &parameter.hint {
+            parts.push(self.verbatim(hint.span()));
+            parts.push(text(" "));
+        }
+        if parameter.ampersand.is_some() {
+            parts.push(text("&"));
+        }
+        if parameter.ellipsis.is_some() {
+            parts.push(text("..."));
+        }
+        let variable = text(self.text(parameter.variable.span()));
+        match &parameter.default_value {
+            Some(default) => parts.push(self.assigned(variable, &default.value)),
+            None => parts.push(variable),
+        }
+        concat(parts)
+    }
+
+    pub(super) fn return_type(&self, return_type: &FunctionLikeReturnTypeHint<'_>) -> Doc {
+        concat(vec![text(": "), self.verbatim(return_type.hint.span())])
+    }
+}
@@ -0,0 +1,419 @@
+//! Expressions: operators, calls, member chains, arrays and closures
+
+use mago_span::{HasSpan, Span};
+use mago_syntax::ast::*;
+use mago_syntax::token::GetPrecedence;
+
+use super::Formatter;
+use crate::doc::{concat, conditional_group, force_break, group, if_break, indent, join, text, Doc};
+
+/// One `->name` or `->name(...)` of a member chain
+struct Link<'a, 'arena> {
+    operator: Span,
+    selector: Span,
+    arguments: Option<&'a ArgumentList<'arena>>,
+}
+
+/// Method calls in a chain from which it is broken one call per line
+const CHAIN_BREAK_CALLS: usize = 3;
+
+/// Arguments that keep their opening on the call's line when they break
+fn is_huggable(expression: &Expression<'_>) -> bool {
+    match expression {
+        Expression::Closure(_) | Expression::Match(_) | Expression::AnonymousClass(_) => true,
+        Expression::Array(array) => !array.elements.is_empty(),
+        Expression::LegacyArray(array) => !array.elements.is_empty(),
+        _ => false,
+    }
+}
+
+fn argument_value<'a, 'arena>(argument: &'a Argument<'arena>) -> &'a Expression<'arena> {
+    match argument {
+        Argument::Positional(argument) => &argument.value,
+        Argument::Named(argument) => &argument.value,
+    }
+}
+
+impl Formatter<'_> {
+    pub(super) fn expression(&self, expression: &Expression<'_>) -> Doc {
+        match expression {
+            Expression::Binary(binary) => {
+                let (first, rest) = self.binary_chain(binary);
+                group(concat(vec![first, indent(concat(rest))]))
+            }
+            Expression::UnaryPrefix(unary) => self.unary_prefix(unary),
+            Expression::UnaryPostfix(unary) => {
+                concat(vec![self.expression(unary.operand), text(self.text(unary.operator.span()))])
+            }
+            Expression::Parenthesized(parenthesized) => {
+                concat(vec![text("("), self.expression(parenthesized.expression), text(")")])
+            }
+            Expression::Assignment(assignment) => concat(vec![
+                self.expression(assignment.lhs),
+                text(" "),
+                text(self.text(assignment.operator.span())),
+                text(" "),
+                self.expression(assignment.rhs),
+            ]),
+            Expression::Conditional(conditional) => self.conditional(conditional),
+            Expression::Array(array) => {
+                self.array(array.left_bracket, &array.elements, array.right_bracket, array.span())
+            }
+            Expression::LegacyArray(array) => concat(vec![
+                self.keyword(&array.array),
+                self.array(array.left_parenthesis, &array.elements, array.right_parenthesis, array.span()),
+            ]),
+            Expression::List(list) => concat(vec![
+                self.keyword(&list.list),
+                self.array(list.left_parenthesis, &list.elements, list.right_parenthesis, list.span()),
+            ]),
+            Expression::ArrayAccess(access) => concat(vec![
+                self.expression(access.array),
+                text("["),
+                self.expression(access.index),
+                text("]"),
+            ]),
+            Expression::ArrayAppend(append) => concat(vec![self.expression(append.array), text("[]")]),
+            Expression::AnonymousClass(class) => self.anonymous_class(class),
+            Expression::Closure(closure) => self.closure(closure),
+            Expression::ArrowFunction(arrow_function) => self.arrow_function(arrow_function),
+            Expression::Match(r#match) => self.r#match(r#match),
+            Expression::Throw(throw) => {
+                concat(vec![self.keyword(&throw.throw), text(" "), self.expression(throw.exception)])
+            }
+            Expression::Clone(clone) => {
+                concat(vec![self.keyword(&clone.clone), text(" "), self.expression(clone.object)])
+            }
+            Expression::Call(Call::Function(call)) => {
+                concat(vec![self.expression(call.function), self.arguments(&call.argument_list)])
+            }
+            Expression::Call(Call::StaticMethod(call)) => concat(vec![
+                self.expression(call.class),
+                text("::"),
+                self.verbatim(call.method.span()),
+                self.arguments(&call.argument_list),
+            ]),
+            Expression::Access(Access::StaticProperty(access)) => concat(vec![
+                self.expression(access.class),
+                text("::"),
+                text(self.text(access.property.span())),
+            ]),
+            Expression::Access(Access::ClassConstant(access)) => concat(vec![
+                self.expression(access.class),
+                text("::"),
+                self.verbatim(access.constant.span()),
+            ]),
+            Expression::Call(Call::Method(_) | Call::NullSafeMethod(_))
+            | Expression::Access(Access::Property(_) | Access::NullSafeProperty(_)) => self.member_chain(expression),
+            Expression::Instantiation(instantiation) => {
+                let mut parts = vec![self.keyword(&instantiation.new), text(" "), self.expression(instantiation.class)];
+                if let Some(arguments) = &instantiation.argument_list {
+                    parts.push(self.arguments(arguments));
+                }
+                concat(parts)
+            }
+            // A heredoc's span only covers its opening line
+            Expression::CompositeString(CompositeString::Document(document)) => {
+                self.verbatim(document.open.join(document.close))
+            }
+            other => self.verbatim(other.span()),
+        }
+    }
+
+    /// An expression whose line breaks don't add indentation, for conditions
+    /// that are already indented inside their parentheses
+    pub(super) fn unindented(&self, expression: &Expression<'_>) -> Doc {
+        match expression {
+            Expression::Binary(binary) => {
+                let (first, rest) = self.binary_chain(binary);
+                concat(vec![first, concat(rest)])
+            }
+            other => self.expression(other),
+        }
+    }
+
+    /// `name = value`
+    pub(super) fn assigned(&self, name: Doc, value: &Expression<'_>) -> Doc {
+        concat(vec![name, text(" = "), self.expression(value)])
+    }
+
+    /// The first operand of a chain of operators with the same precedence,
+    /// and each following operator with its operand
+    fn binary_chain(&self, binary: &Binary<'_>) -> (Doc, Vec<Doc>) {
+        let precedence = binary.operator.precedence();
+        let mut operations = vec![binary];
+        let mut first = binary.lhs;
+        while let Expression::Binary(inner) = first {
+            if inner.operator.precedence() != precedence {
+                break;
+            }
+            operations.push(inner);
+            first = inner.lhs;
+        }
+
+        let rest = operations
+            .iter()
+            .rev()
+            .map(|operation| {
+                concat(vec![
+                    Doc::Line,
+                    text(self.text(operation.operator.span()).to_ascii_lowercase()),
+                    text(" "),
+                    self.expression(operation.rhs),
+                ])
+            })
+            .collect();
+        (self.expression(first), rest)
+    }
+
+    fn unary_prefix(&self, unary: &UnaryPrefix<'_>) -> Doc {
+        let operator = self.text(unary.operator.span());
+        let separator = if unary.operator.is_cast() {
+            " "
+        } else {
+            // `- -$a` and `+ ++$a` must not run together
+            match unary.operand {
+                Expression::UnaryPrefix(inner) => {
+                    let inner = self.text(inner.operator.span());
+                    if inner.starts_with(['-', '+']) && operator.ends_with(&inner[..1]) { " " } else { "" }
+                }
+                _ => "",
+            }
+        };
+        concat(vec![text(operator), text(separator), self.expression(unary.operand)])
+    }
+
+    fn conditional(&self, conditional: &Conditional<'_>) -> Doc {
+        let branches = match conditional.then {
+            Some(then) => concat(vec![
+                Doc::Line,
+                text("? "),
+                self.expression(then),
+                Doc::Line,
+                text(": "),
+                self.expression(conditional.r#else),
+            ]),
+            None => {
+                let between = Span::new(conditional.question_mark.file_id, conditional.question_mark.end, conditional.colon.start);
+                let operator = if self.text(between).is_empty() { "?: " } else { "? : " };
+                concat(vec![Doc::Line, text(operator), self.expression(conditional.r#else)])
+            }
+        };
+        group(concat(vec![self.expression(conditional.condition), indent(branches)]))
+    }
+
+    /// `[...]`, `array(...)` or `list(...)`, broken if the source breaks
+    /// after the opening delimiter
+    fn array(
+        &self,
+        open: Span,
+        elements: &sequence::TokenSeparatedSequence<'_, ArrayElement<'_>>,
+        close: Span,
+        span: Span,
+    ) -> Doc {
+        if elements.iter().any(|element| matches!(element, ArrayElement::Missing(_))) {
+            return self.verbatim(span);
+        }
+        let first = elements.first_span().map(|span| span.start).unwrap_or(close.start);
+        let multiline = self.source[open.end.offset as usize..first.offset as usize].contains('\n');
+
+        self.comma_list(open, elements, close, multiline, None, |element| match element {
+            ArrayElement::KeyValue(element) => {
+                concat(vec![self.expression(element.key), text(" => "), self.expression(element.value)])
+            }
+            ArrayElement::Value(element) => self.expression(element.value),
+            ArrayElement::Variadic(element) => concat(vec![text("..."), self.expression(element.value)]),
+            ArrayElement::Missing(_) => text(""),
+        })
+    }
+
+    /// `$a->b()->c()`, broken one call per line if there are enough calls
+    /// and it doesn't fit
+    fn member_chain(&self, expression: &Expression<'_>) -> Doc {
+        let mut links = Vec::new();
+        let mut base = expression;
+        loop {
+            let link = match base {
+                Expression::Call(Call::Method(call)) => {
+                    Link { operator: call.arrow, selector: call.method.span(), arguments: Some(&call.argument_list) }
+                }
+                Expression::Call(Call::NullSafeMethod(call)) => Link {
+                    operator: call.question_mark_arrow,
+                    selector: call.method.span(),
+                    arguments: Some(&call.argument_list),
+                },
+                Expression::Access(Access::Property(access)) => {
+                    Link { operator: access.arrow, selector: access.property.span(), arguments: None }
+                }
+                Expression::Access(Access::NullSafeProperty(access)) => {
+                    Link { operator: access.question_mark_arrow, selector: access.property.span(), arguments: None }
+                }
+                _ => break,
+            };
+            base = match base {
+                Expression::Call(Call::Method(call)) => call.object,
+                Expression::Call(Call::NullSafeMethod(call)) => call.object,
+                Expression::Access(Access::Property(access)) => access.object,
+                Expression::Access(Access::NullSafeProperty(access)) => access.object,
+                _ => unreachable!(),
+            };
+            links.push(link);
+        }
+        links.reverse();
+
+        let print_link = |link: &Link<'_, '_>| {
+            let mut parts = vec![text(self.text(link.operator)), self.verbatim(link.selector)];
+            if let Some(arguments) = link.arguments {
+                parts.push(self.arguments(arguments));
+            }
+            concat(parts)
+        };
+
+        // Property fetches before the first call stay with the head: `$this->repository`
+        let head_links = links.iter().take_while(|link| link.arguments.is_none()).count();
+        let mut head = vec![self.expression(base)];
+        head.extend(links[..head_links].iter().map(print_link));
+
+        let calls = links.iter().filter(|link| link.arguments.is_some()).count();
+        let tail: Vec<Doc> = links[head_links..].iter().map(print_link).collect();
+        if calls < CHAIN_BREAK_CALLS {
+            head.extend(tail);
+            return concat(head);
+        }
+
+        let tail = tail.into_iter().flat_map(|link| [Doc::SoftLine, link]).collect();
+        group(concat(vec![concat(head), indent(concat(tail))]))
+    }
+
+    /// `(...)` of a call, hugging a sole closure, array or match among
+    /// simple arguments: `foo($x, function () {` rather than one argument
+    /// per line
+    pub(super) fn arguments(&self, list: &ArgumentList<'_>) -> Doc {
+        let arguments = &list.arguments;
+        let hugged = match arguments.len() {
+            0 => None,
+            count => {
+                let huggable: Vec<usize> =
+                    (0..count).filter(|&idx| is_huggable(argument_value(&arguments.nodes[idx]))).collect();
+                match huggable.as_slice() {
+                    [idx] if *idx == count - 1 || (*idx == 0 && matches!(argument_value(&arguments.nodes[0]), Expression::Closure(_))) => {
+                        Some(*idx)
+                    }
+                    _ => None,
+                }
+            }
+        };
+        let hugged = hugged.filter(|&idx| {
+            let span = arguments.nodes[idx].span();
+            let outside = [
+                (list.left_parenthesis.end.offset, span.start.offset),
+                (span.end.offset, list.right_parenthesis.start.offset),
+            ];
+            let others_simple = arguments
+                .iter()
+                .all(|argument| !matches!(argument_value(argument), Expression::ArrowFunction(_) | Expression::Closure(_)) || argument.span() == span);
+            others_simple && !outside.iter().any(|&(start, end)| self.comments.any_within(start as usize, end as usize))
+        });
+
+        let Some(hugged) = hugged else {
+            return self.comma_list(list.left_parenthesis, arguments, list.right_parenthesis, false, None, |argument| {
+                self.argument(argument)
+            });
+        };
+
+        // Each argument is printed once: printing takes the comments inside it
+        let docs: Vec<Doc> = arguments.iter().map(|argument| self.argument(argument)).collect();
+        let flat = concat(vec![text("("), join(docs.clone(), text(", ")), text(")")]);
+
+        let mut hugging = docs.clone();
+        hugging[hugged] = force_break(hugging[hugged].clone());
+        let hugging = concat(vec![text("("), join(hugging, text(", ")), text(")")]);
+
+        let expanded = group(concat(vec![
+            text("("),
+            indent(concat(vec![Doc::SoftLine, join(docs, concat(vec![text(","), Doc::Line])), if_break(text(","), text(""))])),
+            Doc::SoftLine,
+            text(")"),
+        ]));
+
+        conditional_group(vec![flat, hugging, expanded])
+    }
+
+    fn argument(&self, argument: &Argument<'_>) -> Doc {
+        match argument {
+            Argument::Positional(argument) => {
+                let spread = if argument.ellipsis.is_some() { "..." } else { "" };
+                concat(vec![text(spread), self.expression(&argument.value)])
+            }
+            Argument::Named(argument) => {
+                concat(vec![text(self.text(argument.name.span())), text(": "), self.expression(&argument.value)])
+            }
+        }
+    }
+
+    fn closure(&self, closure: &Closure<'_>) -> Doc {
+        let mut parts = self.inline_attributes(closure.attribute_lists.as_slice());
+        if let Some(r#static) = &closure.r#static {
+            parts.push(self.keyword(r#static));
+            parts.push(text(" "));
+        }
+        parts.push(self.keyword(&closure.function));
+        parts.push(text(" "));
+        if closure.ampersand.is_some() {
+            parts.push(text("&"));
+        }
+        parts.push(self.parameter_list(&closure.parameter_list, None));
+        if let Some(clause) = &closure.use_clause {
+            parts.push(text(" "));
+            parts.push(self.keyword(&clause.r#use));
+            parts.push(text(" "));
+            parts.push(self.comma_list(
+                clause.left_parenthesis,
+                &clause.variables,
+                clause.right_parenthesis,
+                false,
+                None,
+                |variable| text(self.text(variable.span())),
+            ));
+        }
+        if let Some(return_type) = &closure.return_type_hint {
+            parts.push(self.return_type(return_type));
+        }
+        parts.push(text(" "));
+        parts.push(self.block(&closure.body));
+        concat(parts)
+    }
+
+    fn arrow_function(&self, arrow_function: &ArrowFunction<'_>) -> Doc {
+        let mut parts = self.inline_attributes(arrow_function.attribute_lists.as_slice());
+        if let Some(r#static) = &arrow_function.r#static {
+            parts.push(self.keyword(r#static));
+            parts.push(text(" "));
+        }
+        parts.push(self.keyword(&arrow_function.r#fn));
+        parts.push(text(" "));
+        if arrow_function.ampersand.is_some() {
+            parts.push(text("&"));
+        }
+        parts.push(self.parameter_list(&arrow_function.parameter_list, None));
+        if let Some(return_type) = &arrow_function.return_type_hint {
+            parts.push(self.return_type(return_type));
+        }
+        parts.push(text(" => "));
+        parts.push(self.expression(arrow_function.expression));
+        concat(parts)
+    }
+
+    fn r#match(&self, r#match: &Match<'_>) -> Doc {
+        let left_brace = r#match.left_brace;
+        let arms = self.comma_list(left_brace, &r#match.arms, r#match.right_brace, true, None, |arm| match arm {
+            MatchArm::Expression(arm) => concat(vec![
+                join(arm.conditions.iter().map(|condition| self.expression(condition)).collect(), text(", ")),
+                text(" => "),
+                self.expression(arm.expression),
+            ]),
+            MatchArm::Default(arm) => concat(vec![self.keyword(&arm.default), text(" => "), self.expression(arm.expression)]),
+        });
+        concat(vec![self.keyword(&r#match.r#match), text(" ("), self.expression(r#match.expression), text(") "), arms])
+    }
+}
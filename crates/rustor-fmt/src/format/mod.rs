@@ -0,0 +1,252 @@
+//! Conversion of the AST to a layout document
+//!
+//! The layout follows PER Coding Style. Nodes the formatter doesn't handle,
+//! and nodes with comments it can't place, are printed verbatim.
+
+mod declaration;
+mod expression;
+mod statement;
+
+use std::cell::Cell;
+
+use mago_span::{HasSpan, Span};
+use mago_syntax::ast::sequence::TokenSeparatedSequence;
+use mago_syntax::ast::{Keyword, Program};
+
+use crate::comments::{Comment, Comments};
+use crate::doc::{concat, group_with_id, if_break, indent, text, verbatim, Doc, GroupId};
+
+pub(crate) struct Formatter<'s> {
+    source: &'s str,
+    comments: Comments<'s>,
+    next_group: Cell<usize>,
+}
+
+impl<'s> Formatter<'s> {
+    pub(crate) fn new(source: &'s str, program: &Program<'_>) -> Self {
+        Self {
+            source,
+            comments: Comments::new(program, source),
+            next_group: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn program(&self, program: &Program<'_>) -> Doc {
+        self.statement_list(program.statements.as_slice(), self.source.len())
+            .unwrap_or_else(|| text(""))
+    }
+
+    fn group_id(&self) -> GroupId {
+        let id = self.next_group.get();
+        self.next_group.set(id + 1);
+        GroupId(id)
+    }
+
+    /// Source text of a node
+    fn text(&self, span: Span) -> &'s str {
+        &self.source[span.start.offset as usize..span.end.offset as usize]
+    }
+
+    /// A node printed as it is in the source, with the comments inside it
+    fn verbatim(&self, span: Span) -> Doc {
+        self.comments.take_within(span.start.offset as usize, span.end.offset as usize);
+        verbatim(self.text(span))
+    }
+
+    /// A keyword, in lowercase
+    fn keyword(&self, keyword: &Keyword<'_>) -> Doc {
+        text(keyword.value.to_ascii_lowercase())
+    }
+
+    /// Whether there is an empty line between two offsets
+    fn has_blank_line(&self, from: usize, to: usize) -> bool {
+        if from >= to {
+            return false;
+        }
+        let lines: Vec<&str> = self.source[from..to].split('\n').collect();
+        lines.len() > 2 && lines[1..lines.len() - 1].iter().any(|line| line.trim().is_empty())
+    }
+
+    fn comment(&self, comment: &Comment<'_>) -> Doc {
+        let mut parts = Vec::new();
+        for (idx, line) in comment.text.split('\n').enumerate() {
+            let line = line.trim_end();
+            if idx == 0 {
+                parts.push(text(line));
+            } else if line.trim_start().starts_with('*') {
+                // Docblock lines are aligned under the opening `/**`
+                parts.push(Doc::HardLine);
+                parts.push(text(format!(" {}", line.trim_start())));
+            } else {
+                parts.push(Doc::LiteralLine);
+                parts.push(text(line));
+            }
+        }
+        concat(parts)
+    }
+
+    /// Items printed one per line, such as statements or class members, with
+    /// the comments around them and blank lines between them (at most one)
+    ///
+    /// `end` is where the list's container ends; comments before it that
+    /// follow the last item are printed after it. Returns `None` if there is
+    /// nothing to print.
+    fn line_list<T: HasSpan>(
+        &self,
+        items: &[T],
+        end: usize,
+        print: impl Fn(&T) -> Option<Doc>,
+        blank_between: impl Fn(&T, &T) -> bool,
+    ) -> Option<Doc> {
+        let mut parts: Vec<Doc> = Vec::new();
+        let mut last_end: Option<usize> = None;
+        let mut previous: Option<&T> = None;
+
+        let separate = |parts: &mut Vec<Doc>, last_end: Option<usize>, start: usize, blank: bool| {
+            if !parts.is_empty() {
+                parts.push(Doc::HardLine);
+                if blank || last_end.is_some_and(|last_end| self.has_blank_line(last_end, start)) {
+                    parts.push(Doc::HardLine);
+                }
+            }
+        };
+
+        for item in items {
+            let span = item.span();
+            let (start, stop) = (span.start.offset as usize, span.end.offset as usize);
+            let mut blank = previous.is_some_and(|previous| blank_between(previous, item));
+
+            for comment in self.comments.take_before(start) {
+                separate(&mut parts, last_end, comment.start, blank);
+                blank = false;
+                parts.push(self.comment(&comment));
+                last_end = Some(comment.end);
+            }
+
+            let Some(mut doc) = print(item) else {
+                continue;
+            };
+            if self.comments.any_within(start, stop) {
+                doc = self.verbatim(span);
+            }
+            separate(&mut parts, last_end, start, blank);
+            parts.push(doc);
+            last_end = Some(stop);
+
+            for comment in self.comments.take_trailing(stop, self.source) {
+                parts.push(text(" "));
+                parts.push(self.comment(&comment));
+                last_end = Some(comment.end);
+            }
+            previous = Some(item);
+        }
+
+        for comment in self.comments.take_before(end) {
+            separate(&mut parts, last_end, comment.start, false);
+            parts.push(self.comment(&comment));
+            last_end = Some(comment.end);
+        }
+
+        (!parts.is_empty()).then(|| concat(parts))
+    }
+
+    /// Contents of a `{ ... }` body, one item per line
+    fn braced(&self, body: Option<Doc>) -> Doc {
+        match body {
+            Some(body) => concat(vec![text("{"), indent(concat(vec![Doc::HardLine, body])), Doc::HardLine, text("}")]),
+            None => concat(vec![text("{"), Doc::HardLine, text("}")]),
+        }
+    }
+
+    /// A comma-separated list between delimiters, kept on one line if it
+    /// fits, and otherwise one item per line with a trailing comma
+    ///
+    /// `close` is the closing delimiter's span. Comments between items break
+    /// the list, as do blank lines between items and `force_break`.
+    fn comma_list<T: HasSpan>(
+        &self,
+        open: Span,
+        items: &TokenSeparatedSequence<'_, T>,
+        close: Span,
+        force_break: bool,
+        id: Option<GroupId>,
+        print: impl Fn(&T) -> Doc,
+    ) -> Doc {
+        let close_start = close.start.offset as usize;
+        let open_end = open.end.offset as usize;
+        let (open, close_text) = (self.text(open), self.text(close));
+        if items.is_empty() && !self.comments.any_within(open_end, close_start) {
+            return group_with_id(concat(vec![text(open), text(close_text)]), id);
+        }
+
+        let mut broken = force_break;
+        let mut body = vec![Doc::SoftLine];
+        let mut after: Option<usize> = None;
+        let count = items.len();
+
+        for (idx, item, comma) in items.iter_with_tokens() {
+            let span = item.span();
+            let start = span.start.offset as usize;
+
+            if after.is_some_and(|after| self.has_blank_line(after, start)) {
+                body.push(Doc::SoftLine);
+                broken = true;
+            }
+            for comment in self.comments.take_before(start) {
+                body.push(self.comment(&comment));
+                if comment.is_line || self.source[comment.end..start].contains('\n') {
+                    body.push(Doc::SoftLine);
+                    broken = true;
+                } else {
+                    body.push(text(" "));
+                }
+            }
+
+            body.push(print(item));
+            // Comments between an item and its comma stay before the comma
+            if let Some(comma) = comma {
+                for comment in self.comments.take_before(comma.span.start.offset as usize) {
+                    body.push(text(" "));
+                    body.push(self.comment(&comment));
+                    if comment.is_line {
+                        body.push(Doc::HardLine);
+                        broken = true;
+                    }
+                }
+            }
+            let separator_end = comma.map(|comma| comma.span.end.offset as usize).unwrap_or(span.end.offset as usize);
+            if idx + 1 < count {
+                body.push(text(","));
+            } else {
+                body.push(if_break(text(","), text("")));
+            }
+
+            let mut item_end = separator_end;
+            for comment in self.comments.take_trailing(separator_end, self.source) {
+                body.push(text(" "));
+                body.push(self.comment(&comment));
+                broken |= comment.is_line;
+                item_end = comment.end;
+            }
+            if idx + 1 < count {
+                body.push(Doc::Line);
+            }
+            after = Some(item_end);
+        }
+
+        for comment in self.comments.take_before(close_start) {
+            if count > 0 {
+                body.push(Doc::SoftLine);
+            }
+            body.push(self.comment(&comment));
+            broken = true;
+        }
+
+        let doc = concat(vec![text(open), indent(concat(body)), Doc::SoftLine, text(close_text)]);
+        if broken {
+            Doc::Group { contents: Box::new(doc), id, broken: true }
+        } else {
+            group_with_id(doc, id)
+        }
+    }
+}
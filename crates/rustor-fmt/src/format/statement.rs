@@ -0,0 +1,335 @@
+//! Statements, blocks and control structures
+
+use mago_span::HasSpan;
+use mago_syntax::ast::*;
+
+use super::Formatter;
+use crate::doc::{concat, group, indent, join, text, Doc};
+
+/// Whether PER Coding Style wants a blank line between two statements
+fn blank_between(previous: &Statement<'_>, next: &Statement<'_>) -> bool {
+    let is_declaration = |statement: &Statement<'_>| {
+        matches!(
+            statement,
+            Statement::Class(_) | Statement::Interface(_) | Statement::Trait(_) | Statement::Enum(_) | Statement::Function(_)
+        )
+    };
+    match (previous, next) {
+        (Statement::OpeningTag(_), _) | (Statement::Declare(_), _) => true,
+        (Statement::Use(_), Statement::Use(_)) => false,
+        (Statement::Use(_), _) => true,
+        _ => is_declaration(previous) || is_declaration(next),
+    }
+}
+
+impl Formatter<'_> {
+    /// Statements one per line; `None` if there is nothing to print
+    pub(super) fn statement_list(&self, statements: &[Statement<'_>], end: usize) -> Option<Doc> {
+        self.line_list(statements, end, |statement| self.statement(statement), blank_between)
+    }
+
+    /// A statement, or `None` for whitespace that isn't printed
+    pub(super) fn statement(&self, statement: &Statement<'_>) -> Option<Doc> {
+        let doc = match statement {
+            Statement::OpeningTag(_) => text("<?php"),
+            Statement::ClosingTag(_) => text("?>"),
+            Statement::Inline(inline) if self.text(inline.span()).trim().is_empty() => return None,
+            Statement::Namespace(namespace) => self.namespace(namespace),
+            Statement::Use(r#use) => concat(vec![
+                self.keyword(&r#use.r#use),
+                text(" "),
+                self.verbatim(r#use.items.span()),
+                self.terminator(&r#use.terminator),
+            ]),
+            Statement::Class(class) => self.class(class),
+            Statement::Interface(interface) => self.interface(interface),
+            Statement::Trait(r#trait) => self.r#trait(r#trait),
+            Statement::Enum(r#enum) => self.r#enum(r#enum),
+            Statement::Function(function) => self.function(function),
+            Statement::Block(block) => self.block(block),
+            Statement::Constant(constant) if constant.attribute_lists.is_empty() => concat(vec![
+                self.keyword(&constant.r#const),
+                text(" "),
+                join(
+                    constant
+                        .items
+                        .iter()
+                        .map(|item| self.assigned(text(self.text(item.name.span())), &item.value))
+                        .collect(),
+                    text(", "),
+                ),
+                self.terminator(&constant.terminator),
+            ]),
+            Statement::Declare(declare) => self.declare(declare),
+            Statement::Try(r#try) => self.r#try(r#try),
+            Statement::Foreach(foreach) => self.foreach(foreach),
+            Statement::For(r#for) => self.r#for(r#for),
+            Statement::While(r#while) => self.r#while(r#while),
+            Statement::DoWhile(do_while) => self.do_while(do_while),
+            Statement::Switch(switch) => self.switch(switch),
+            Statement::If(r#if) => self.r#if(r#if),
+            Statement::Return(r#return) => {
+                let mut parts = vec![self.keyword(&r#return.r#return)];
+                if let Some(value) = &r#return.value {
+                    parts.push(text(" "));
+                    parts.push(self.expression(value));
+                }
+                parts.push(self.terminator(&r#return.terminator));
+                concat(parts)
+            }
+            Statement::Expression(statement) => {
+                concat(vec![self.expression(statement.expression), self.terminator(&statement.terminator)])
+            }
+            Statement::Echo(echo) => concat(vec![
+                self.keyword(&echo.echo),
+                text(" "),
+                join(echo.values.iter().map(|value| self.expression(value)).collect(), text(", ")),
+                self.terminator(&echo.terminator),
+            ]),
+            Statement::Global(global) => concat(vec![
+                self.keyword(&global.global),
+                text(" "),
+                join(global.variables.iter().map(|variable| text(self.text(variable.span()))).collect(), text(", ")),
+                self.terminator(&global.terminator),
+            ]),
+            Statement::Noop(_) => text(";"),
+            other => self.verbatim(other.span()),
+        };
+        Some(doc)
+    }
+
+    pub(super) fn terminator(&self, terminator: &Terminator<'_>) -> Doc {
+        match terminator {
+            Terminator::Semicolon(_) => text(";"),
+            Terminator::ClosingTag(_) => text(" ?>"),
+            other => self.verbatim(other.span()),
+        }
+    }
+
+    pub(super) fn block(&self, block: &Block<'_>) -> Doc {
+        self.braced(self.statement_list(block.statements.as_slice(), block.right_brace.start.offset as usize))
+    }
+
+    /// `keyword (condition)`, breaking inside the parentheses if too long
+    fn condition(&self, keyword: &Keyword<'_>, condition: &Expression<'_>) -> Doc {
+        self.parenthesized_header(self.keyword(keyword), self.unindented(condition))
+    }
+
+    fn parenthesized_header(&self, keyword: Doc, inside: Doc) -> Doc {
+        group(concat(vec![
+            keyword,
+            text(" ("),
+            indent(concat(vec![Doc::SoftLine, inside])),
+            Doc::SoftLine,
+            text(")"),
+        ]))
+    }
+
+    /// The body of a control structure, and whether it is a block
+    fn body(&self, statement: &Statement<'_>) -> (Doc, bool) {
+        match statement {
+            Statement::Block(block) => (concat(vec![text(" "), self.block(block)]), true),
+            Statement::Noop(_) => (text(";"), false),
+            other => {
+                let doc = self.statement(other).unwrap_or_else(|| text(""));
+                (indent(concat(vec![Doc::HardLine, doc])), false)
+            }
+        }
+    }
+
+    fn r#if(&self, r#if: &If<'_>) -> Doc {
+        let IfBody::Statement(body) = &r#if.body else {
+            return self.verbatim(r#if.span());
+        };
+
+        let (doc, mut after_block) = self.body(body.statement);
+        let mut parts = vec![self.condition(&r#if.r#if, r#if.condition), doc];
+        let clause_separator = |after_block: bool| if after_block { text(" ") } else { Doc::HardLine };
+
+        for clause in body.else_if_clauses.iter() {
+            let (doc, is_block) = self.body(clause.statement);
+            parts.push(clause_separator(after_block));
+            parts.push(self.condition(&clause.elseif, clause.condition));
+            parts.push(doc);
+            after_block = is_block;
+        }
+        if let Some(clause) = &body.else_clause {
+            parts.push(clause_separator(after_block));
+            parts.push(self.keyword(&clause.r#else));
+            match clause.statement {
+                Statement::If(nested) => {
+                    parts.push(text(" "));
+                    parts.push(self.r#if(nested));
+                }
+                statement => parts.push(self.body(statement).0),
+            }
+        }
+
+        concat(parts)
+    }
+
+    fn r#while(&self, r#while: &While<'_>) -> Doc {
+        match &r#while.body {
+            WhileBody::Statement(statement) => {
+                concat(vec![self.condition(&r#while.r#while, r#while.condition), self.body(statement).0])
+            }
+            WhileBody::ColonDelimited(_) => self.verbatim(r#while.span()),
+        }
+    }
+
+    fn do_while(&self, do_while: &DoWhile<'_>) -> Doc {
+        let Statement::Block(block) = do_while.statement else {
+            return self.verbatim(do_while.span());
+        };
+        concat(vec![
+            self.keyword(&do_while.r#do),
+            text(" "),
+            self.block(block),
+            text(" "),
+            self.condition(&do_while.r#while, do_while.condition),
+            self.terminator(&do_while.terminator),
+        ])
+    }
+
+    fn foreach(&self, foreach: &Foreach<'_>) -> Doc {
+        let ForeachBody::Statement(statement) = &foreach.body else {
+            return self.verbatim(foreach.span());
+        };
+
+        let mut inside = vec![self.expression(foreach.expression), text(" "), self.keyword(&foreach.r#as), text(" ")];
+        match &foreach.target {
+            ForeachTarget::Value(target) => inside.push(self.expression(target.value)),
+            ForeachTarget::KeyValue(target) => {
+                inside.push(self.expression(target.key));
+                inside.push(text(" => "));
+                inside.push(self.expression(target.value));
+            }
+        }
+
+        concat(vec![self.parenthesized_header(self.keyword(&foreach.foreach), concat(inside)), self.body(statement).0])
+    }
+
+    fn r#for(&self, r#for: &For<'_>) -> Doc {
+        let ForBody::Statement(statement) = &r#for.body else {
+            return self.verbatim(r#for.span());
+        };
+
+        let section = |expressions: &sequence::TokenSeparatedSequence<'_, Expression<'_>>| {
+            join(expressions.iter().map(|expression| self.expression(expression)).collect(), text(", "))
+        };
+        let sections = [&r#for.initializations, &r#for.conditions, &r#for.increments];
+        let mut inside = Vec::new();
+        for (idx, expressions) in sections.iter().enumerate() {
+            if idx > 0 {
+                inside.push(text(";"));
+                if !expressions.is_empty() {
+                    inside.push(Doc::Line);
+                }
+            }
+            inside.push(section(expressions));
+        }
+
+        concat(vec![self.parenthesized_header(self.keyword(&r#for.r#for), concat(inside)), self.body(statement).0])
+    }
+
+    fn switch(&self, switch: &Switch<'_>) -> Doc {
+        let SwitchBody::BraceDelimited(body) = &switch.body else {
+            return self.verbatim(switch.span());
+        };
+        if body.optional_terminator.is_some() {
+            return self.verbatim(switch.span());
+        }
+
+        let cases = self.line_list(
+            body.cases.as_slice(),
+            body.right_brace.start.offset as usize,
+            |case| Some(self.switch_case(case)),
+            |_, _| false,
+        );
+        concat(vec![self.condition(&switch.switch, switch.expression), text(" "), self.braced(cases)])
+    }
+
+    fn switch_case(&self, case: &SwitchCase<'_>) -> Doc {
+        let (mut head, separator, statements) = match case {
+            SwitchCase::Expression(case) => (
+                vec![self.keyword(&case.case), text(" "), self.expression(case.expression)],
+                &case.separator,
+                &case.statements,
+            ),
+            SwitchCase::Default(case) => (vec![self.keyword(&case.default)], &case.separator, &case.statements),
+        };
+        head.push(text(match separator {
+            SwitchCaseSeparator::Colon(_) => ":",
+            SwitchCaseSeparator::SemiColon(_) => ";",
+        }));
+
+        if let Some(body) = self.statement_list(statements.as_slice(), case.span().end.offset as usize) {
+            head.push(indent(concat(vec![Doc::HardLine, body])));
+        }
+        concat(head)
+    }
+
+    fn r#try(&self, r#try: &Try<'_>) -> Doc {
+        let mut parts = vec![self.keyword(&r#try.r#try), text(" "), self.block(&r#try.block)];
+        for clause in r#try.catch_clauses.iter() {
+            parts.push(text(" "));
+            parts.push(self.keyword(&clause.r#catch));
+            parts.push(text(" ("));
+            parts.push(text(self.text(clause.hint.span())));
+            if let Some(variable) = &clause.variable {
+                parts.push(text(" "));
+                parts.push(text(self.text(variable.span())));
+            }
+            parts.push(text(") "));
+            parts.push(self.block(&clause.block));
+        }
+        if let Some(clause) = &r#try.finally_clause {
+            parts.push(text(" "));
+            parts.push(self.keyword(&clause.r#finally));
+            parts.push(text(" "));
+            parts.push(self.block(&clause.block));
+        }
+        concat(parts)
+    }
+
+    fn declare(&self, declare: &Declare<'_>) -> Doc {
+        let body = match &declare.body {
+            DeclareBody::Statement(Statement::Noop(_)) => text(";"),
+            DeclareBody::Statement(Statement::Block(block)) => concat(vec![text(" "), self.block(block)]),
+            _ => return self.verbatim(declare.span()),
+        };
+        let items = declare
+            .items
+            .iter()
+            .map(|item| concat(vec![text(self.text(item.name.span())), text("="), self.expression(&item.value)]))
+            .collect();
+        concat(vec![self.keyword(&declare.declare), text("("), join(items, text(", ")), text(")"), body])
+    }
+
+    fn namespace(&self, namespace: &Namespace<'_>) -> Doc {
+        let mut head = vec![self.keyword(&namespace.namespace)];
+        if let Some(name) = &namespace.name {
+            head.push(text(" "));
+            head.push(text(self.text(name.span())));
+        }
+
+        match &namespace.body {
+            NamespaceBody::Implicit(body) => {
+                head.push(self.terminator(&body.terminator));
+                // The statements after `namespace Foo;` belong to it, but are printed at the same level
+                let end = namespace.span().end.offset as usize;
+                if let Some(statements) = self.statement_list(body.statements.as_slice(), end) {
+                    head.push(Doc::HardLine);
+                    head.push(Doc::HardLine);
+                    head.push(statements);
+                }
+                concat(head)
+            }
+            NamespaceBody::BraceDelimited(block) => {
+                head.push(text(" "));
+                head.push(self.block(block));
+                concat(head)
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+//! rustor-fmt: opinionated PHP pretty-printer for rustor
+//!
+//! Unlike the fixers in `rustor-fixer`, which rewrite individual tokens, this
+//! crate prints the whole file again from the mago-syntax AST, choosing line
+//! breaks to fit a maximum line width, in the style of Prettier, gofmt and
+//! `mago fmt`. The defaults follow PER Coding Style.
+//!
+//! # Features
+//!
+//! - Width-aware breaking of argument and parameter lists, arrays, method
+//!   chains, binary expressions, conditions and attributes
+//! - Comments are kept, and blank lines between statements and members are
+//!   kept (collapsed to one)
+//! - Constructs the printer doesn't know are kept verbatim
+//! - The result is lexed again and compared with the input, so formatting
+//!   never changes what the code does
+//!
+//! # Example
+//!
+//! ```ignore
+//! use rustor_fmt::{format, FormatSettings};
+//!
+//! let formatted = format(source, &FormatSettings::default())?;
+//! ```
+
+mod comments;
+mod doc;
+mod format;
+mod verify;
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_syntax::parser::parse_file_content;
+use thiserror::Error;
+
+/// Errors that prevent formatting a file
+#[derive(Debug, Error)]
+pub enum FormatError {
+    #[error("Parse error: {0}")]
+    Parse(String),
+    #[error("Unsupported file: {0}")]
+    Unsupported(&'static str),
+    #[error("Formatting would change the code; the file was left unchanged")]
+    Unstable,
+}
+
+/// How the formatter lays out code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSettings {
+    /// Maximum line width the printer tries to keep to
+    pub line_width: usize,
+    /// Spaces per indentation level, if not using tabs
+    pub indent_size: usize,
+    /// Indent with tabs instead of spaces
+    pub use_tabs: bool,
+    /// Line ending: "\n" or "\r\n"
+    pub line_ending: String,
+}
+
+impl Default for FormatSettings {
+    /// PER Coding Style: 4 spaces, LF, 120 columns soft limit
+    fn default() -> Self {
+        Self {
+            line_width: 120,
+            indent_size: 4,
+            use_tabs: false,
+            line_ending: "\n".to_string(),
+        }
+    }
+}
+
+impl FormatSettings {
+    fn indent_unit(&self) -> String {
+        if self.use_tabs {
+            "\t".to_string()
+        } else {
+            " ".repeat(self.indent_size)
+        }
+    }
+}
+
+/// Format PHP source code
+pub fn format(source: &str, settings: &FormatSettings) -> Result<String, FormatError> {
+    if let Some(reason) = verify::unsupported_layout(source) {
+        return Err(FormatError::Unsupported(reason));
+    }
+
+    let arena = Bump::new();
+    let (program, error) = parse_file_content(&arena, FileId::zero(), source);
+    if let Some(error) = error {
+        return Err(FormatError::Parse(error.to_string()));
+    }
+
+    let doc = format::Formatter::new(source, program).program(program);
+    let mut formatted = doc::print(&doc, settings.line_width, &settings.indent_unit(), &settings.line_ending);
+    formatted.push_str(&settings.line_ending);
+
+    if !verify::same_code(source, &formatted) {
+        return Err(FormatError::Unstable);
+    }
+
+    Ok(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(source: &str) -> String {
+        let formatted = format(source, &FormatSettings::default()).unwrap();
+        // Formatting is idempotent
+        assert_eq!(format(&formatted, &FormatSettings::default()).unwrap(), formatted);
+        formatted
+    }
+
+    fn fmt_width(source: &str, line_width: usize) -> String {
+        let settings = FormatSettings { line_width, ..Default::default() };
+        format(source, &settings).unwrap()
+    }
+
+    #[test]
+    fn test_statements_and_blank_lines() {
+        let source = "<?php\ndeclare(strict_types=1);\nnamespace App;\nuse Foo\\Bar;\n$a=1;\n\n\n\n$b  =  2 ;\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\ndeclare(strict_types=1);\n\nnamespace App;\n\nuse Foo\\Bar;\n\n$a = 1;\n\n$b = 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_control_structures() {
+        let source = "<?php\nif($a){foo();}elseif($b){bar();}else{baz();}\nforeach($items as $k=>$v){echo $v;}\nwhile($x)$x--;\ntry{a();}catch(A|B $e){}finally{c();}\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\nif ($a) {\n    foo();\n} elseif ($b) {\n    bar();\n} else {\n    baz();\n}\nforeach ($items as $k => $v) {\n    echo $v;\n}\nwhile ($x)\n    $x--;\ntry {\n    a();\n} catch (A|B $e) {\n} finally {\n    c();\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_class_layout() {
+        let source = "<?php\n#[Attr]\nfinal class Foo extends Bar implements A,B{\nuse T;\nconst X=1;\nprivate ?int $a=null;\npublic function __construct(private int $x){}\nabstract protected function run(array $a=[]):void;\n}\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\n#[Attr]\nfinal class Foo extends Bar implements A, B\n{\n    use T;\n    const X = 1;\n    private ?int $a = null;\n\n    public function __construct(private int $x)\n    {\n    }\n\n    abstract protected function run(array $a = []): void;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_breaks_long_argument_lists() {
+        let source = "<?php\n$result = $service->process($firstArgument, $secondArgument, $thirdArgument);\n";
+        assert_eq!(fmt_width(source, 120), source.replace("<?php\n", "<?php\n\n"));
+        assert_eq!(
+            fmt_width(source, 40),
+            "<?php\n\n$result = $service->process(\n    $firstArgument,\n    $secondArgument,\n    $thirdArgument,\n);\n"
+        );
+    }
+
+    #[test]
+    fn test_breaks_long_parameter_lists() {
+        let source = "<?php\nfunction handle(RequestInterface $request, ResponseInterface $response): ResponseInterface { return $response; }\n";
+        assert_eq!(
+            fmt_width(source, 60),
+            "<?php\n\nfunction handle(\n    RequestInterface $request,\n    ResponseInterface $response,\n): ResponseInterface {\n    return $response;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_breaks_method_chains() {
+        let source = "<?php\n$query = $this->builder->select('id')->from('users')->where('active', true)->orderBy('name')->get();\n";
+        assert_eq!(
+            fmt_width(source, 60),
+            "<?php\n\n$query = $this->builder\n    ->select('id')\n    ->from('users')\n    ->where('active', true)\n    ->orderBy('name')\n    ->get();\n"
+        );
+    }
+
+    #[test]
+    fn test_breaks_binary_expressions_and_conditions() {
+        let source = "<?php\nif ($user->isAdmin() && $user->isActive() && !$user->isLocked()) { $message = 'Hello ' . $user->getName() . ', welcome back'; }\n";
+        assert_eq!(
+            fmt_width(source, 50),
+            "<?php\n\nif (\n    $user->isAdmin()\n    && $user->isActive()\n    && !$user->isLocked()\n) {\n    $message = 'Hello '\n        . $user->getName()\n        . ', welcome back';\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_arrays_keep_multiline_intent() {
+        let source = "<?php\n$a = [1, 2,3];\n$b = [\n'x' => 1, 'y' => [1, 2]];\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\n$a = [1, 2, 3];\n$b = [\n    'x' => 1,\n    'y' => [1, 2],\n];\n"
+        );
+    }
+
+    #[test]
+    fn test_hugs_last_closure_argument() {
+        let source = "<?php\n$items = array_map(function ($item) use ($factor) { return $item * $factor; }, $list);\nusort($list, fn($a, $b) => $a <=> $b);\n$c->each(function ($x) { echo $x; });\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\n$items = array_map(function ($item) use ($factor) {\n    return $item * $factor;\n}, $list);\nusort($list, fn ($a, $b) => $a <=> $b);\n$c->each(function ($x) {\n    echo $x;\n});\n"
+        );
+    }
+
+    #[test]
+    fn test_match_and_switch() {
+        let source = "<?php\n$r = match($x) { 1, 2 => 'a', default => 'b' };\nswitch ($x) { case 1: foo(); break; default: bar(); }\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\n$r = match ($x) {\n    1, 2 => 'a',\n    default => 'b',\n};\nswitch ($x) {\n    case 1:\n        foo();\n        break;\n    default:\n        bar();\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_comments() {
+        let source = "<?php\n// Leading\n$a = 1; // trailing\n\n/**\n   * Doc\n   */\nfunction foo() {\n    // only comment\n}\n$b = [\n    1, // one\n    // two\n    2,\n];\n$c = foo(/* inline */ $x);\n";
+        assert_eq!(
+            fmt(source),
+            "<?php\n\n// Leading\n$a = 1; // trailing\n\n/**\n * Doc\n */\nfunction foo()\n{\n    // only comment\n}\n\n$b = [\n    1, // one\n    // two\n    2,\n];\n$c = foo(/* inline */ $x);\n"
+        );
+    }
+
+    #[test]
+    fn test_keeps_heredoc_verbatim() {
+        let source = "<?php\n$sql = sprintf(<<<SQL\n    SELECT *\n      FROM t\n    SQL, $x);\n";
+        let formatted = fmt(source);
+        assert!(formatted.contains("<<<SQL\n    SELECT *\n      FROM t\n    SQL"));
+    }
+
+    #[test]
+    fn test_settings() {
+        let source = "<?php\nif ($a) {\nfoo();\n}\n";
+        let settings = FormatSettings { use_tabs: true, line_ending: "\r\n".to_string(), ..Default::default() };
+        assert_eq!(format(source, &settings).unwrap(), "<?php\r\n\r\nif ($a) {\r\n\tfoo();\r\n}\r\n");
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(format("<?php\nfoo(;\n", &FormatSettings::default()), Err(FormatError::Parse(_))));
+        assert!(matches!(format("<p><?= $a ?></p>", &FormatSettings::default()), Err(FormatError::Unsupported(_))));
+    }
+}
@@ -0,0 +1,104 @@
+//! Checks that formatting only changed layout
+//!
+//! The formatted file is lexed again and its tokens compared with the
+//! original's. Whitespace is ignored, as are trailing commas (which the
+//! formatter adds to broken lists and match arms), the case of keywords and the indentation
+//! of comment lines.
+
+use bumpalo::Bump;
+use mago_database::file::FileId;
+use mago_span::Position;
+use mago_syntax::lexer::Lexer;
+use mago_syntax::token::TokenKind;
+use mago_syntax_core::input::Input;
+
+/// Tokens that matter for comparing two versions of a file
+fn significant_tokens(source: &str) -> Option<Vec<(TokenKind, String)>> {
+    let arena = Bump::new();
+    let input = Input::anchored_at(FileId::zero(), source.as_bytes(), Position::new(0));
+    let mut lexer = Lexer::new(&arena, input);
+
+    let mut tokens: Vec<(TokenKind, String)> = Vec::new();
+    while let Some(token) = lexer.advance() {
+        let token = token.ok()?;
+        let value = match token.kind {
+            TokenKind::Whitespace => continue,
+            TokenKind::InlineText if token.value.trim().is_empty() => continue,
+            TokenKind::OpenTag | TokenKind::CloseTag => token.value.trim().to_string(),
+            TokenKind::SingleLineComment
+            | TokenKind::HashComment
+            | TokenKind::MultiLineComment
+            | TokenKind::DocBlockComment => {
+                token.value.lines().map(str::trim).collect::<Vec<_>>().join("\n")
+            }
+            TokenKind::RightParenthesis | TokenKind::RightBracket | TokenKind::RightBrace => {
+                // Trailing commas don't change the code
+                let last_code = tokens.iter().rposition(|(kind, _)| !kind.is_comment());
+                if let Some(idx) = last_code.filter(|&idx| tokens[idx].0 == TokenKind::Comma) {
+                    tokens.remove(idx);
+                }
+                token.value.to_string()
+            }
+            kind if kind.is_keyword() => token.value.to_ascii_lowercase(),
+            _ => token.value.to_string(),
+        };
+        tokens.push((token.kind, value));
+    }
+
+    Some(tokens)
+}
+
+/// Whether `formatted` is the same code as `original`
+pub(crate) fn same_code(original: &str, formatted: &str) -> bool {
+    match (significant_tokens(original), significant_tokens(formatted)) {
+        (Some(original), Some(formatted)) => original == formatted,
+        _ => false,
+    }
+}
+
+/// Why the file can't be formatted, if it mixes PHP with inline HTML
+pub(crate) fn unsupported_layout(source: &str) -> Option<&'static str> {
+    let arena = Bump::new();
+    let input = Input::anchored_at(FileId::zero(), source.as_bytes(), Position::new(0));
+    let mut lexer = Lexer::new(&arena, input);
+
+    let mut index = 0;
+    let mut closed = false;
+    while let Some(Ok(token)) = lexer.advance() {
+        match token.kind {
+            TokenKind::OpenTag if index > 0 => return Some("files with several PHP tags aren't supported"),
+            TokenKind::EchoTag | TokenKind::ShortOpenTag => return Some("short and echo tags aren't supported"),
+            TokenKind::InlineText if !token.value.trim().is_empty() || index == 0 => {
+                return Some("inline HTML isn't supported");
+            }
+            TokenKind::CloseTag => closed = true,
+            TokenKind::Whitespace | TokenKind::InlineText => {}
+            _ if closed => return Some("code after a closing tag isn't supported"),
+            _ => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_code_ignores_layout() {
+        assert!(same_code("<?php\nfoo($a,$b);", "<?php\n\nfoo(\n    $a,\n    $b,\n);\n"));
+        assert!(same_code("<?php\n/**\n   * Doc\n   */\n", "<?php\n/**\n * Doc\n */\n"));
+        assert!(same_code("<?php\nIF ($a) ECHO 1;", "<?php\nif ($a) echo 1;"));
+        assert!(!same_code("<?php\n$a = -$b;", "<?php\n$a = --$b;"));
+        assert!(!same_code("<?php\nfoo($a, $b);", "<?php\nfoo($a);"));
+    }
+
+    #[test]
+    fn test_unsupported_layout() {
+        assert!(unsupported_layout("<?php\necho 1;\n").is_none());
+        assert!(unsupported_layout("<?php\necho 1;\n?>\n").is_none());
+        assert!(unsupported_layout("<html><?php echo 1; ?></html>").is_some());
+        assert!(unsupported_layout("<?php echo 1; ?>\n<?php echo 2;").is_some());
+    }
+}
@@ -154,7 +154,7 @@ pub use registry::{
 
 // Re-export yaml_rules types
 pub use yaml_rules::{
-    YamlRule, YamlRuleInterpreter, MatchPattern, Replacement, TestCase,
+    YamlRule, YamlRuleInterpreter, MatchPattern, Replacement, TestCase, TestResult,
     load_rules_from_file, load_rules_from_dir, load_rules_from_string,
 };
 
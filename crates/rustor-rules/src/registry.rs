@@ -670,17 +670,20 @@ impl RuleRegistry {
     ///
     /// This loads rules shipped with rustor-rules.
     pub fn load_bundled_yaml_rules(&mut self) -> Result<usize, String> {
-        // Try to find the rules directory relative to the manifest
-        let rules_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("rules");
-
-        if rules_dir.is_dir() {
-            self.load_yaml_rules_from_dir(&rules_dir)
-        } else {
+        match Self::bundled_yaml_rules_dir() {
+            Some(rules_dir) => self.load_yaml_rules_from_dir(&rules_dir),
             // Rules directory not found - this is OK in release builds
-            Ok(0)
+            None => Ok(0),
         }
     }
 
+    /// The directory of YAML rules shipped with rustor-rules, if present
+    pub fn bundled_yaml_rules_dir() -> Option<std::path::PathBuf> {
+        // Try to find the rules directory relative to the manifest
+        let rules_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("rules");
+        rules_dir.is_dir().then_some(rules_dir)
+    }
+
     /// Get the list of YAML rule directories that have been loaded
     pub fn yaml_rule_dirs(&self) -> &[std::path::PathBuf] {
         &self.yaml_rule_dirs
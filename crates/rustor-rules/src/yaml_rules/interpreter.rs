@@ -5,7 +5,7 @@
 
 use mago_span::HasSpan;
 use mago_syntax::ast::*;
-use rustor_core::{Edit, Visitor};
+use rustor_core::{apply_edits, Edit, Visitor};
 
 use super::matcher::PatternMatcher;
 use super::replacer::Replacer;
@@ -29,7 +29,8 @@ impl YamlRuleInterpreter {
     }
 
     /// Run tests defined in the YAML rule
-    #[cfg(test)]
+    ///
+    /// Skipped tests are left out.
     pub fn run_tests(&self) -> Vec<TestResult> {
         self.rule
             .tests
@@ -39,8 +40,11 @@ impl YamlRuleInterpreter {
             .collect()
     }
 
-    #[cfg(test)]
-    fn run_single_test(&self, test: &super::schema::TestCase) -> TestResult {
+    /// Run one test case
+    ///
+    /// The input is checked as `<?php {input};`, and all edits are applied
+    /// before comparing with the expected output.
+    pub fn run_single_test(&self, test: &super::schema::TestCase) -> TestResult {
         use bumpalo::Bump;
         use mago_database::file::FileId;
         use mago_syntax::parser::parse_file_content;
@@ -57,31 +61,38 @@ impl YamlRuleInterpreter {
         if let Some(expected_output) = &test.output {
             // Apply edits and compare
             if edits.is_empty() {
-                TestResult {
+                return TestResult {
                     input: test.input.clone(),
                     expected: Some(expected_output.clone()),
                     actual: None,
                     passed: false,
                     error: Some("No edits produced".to_string()),
+                };
+            }
+
+            match apply_edits(&full_input, &edits) {
+                Ok(actual) => {
+                    // Extract just the expression part (after "<?php " and before ";")
+                    let actual_expr = actual
+                        .strip_prefix("<?php ")
+                        .and_then(|s| s.strip_suffix(';'))
+                        .unwrap_or(&actual);
+
+                    TestResult {
+                        input: test.input.clone(),
+                        expected: Some(expected_output.clone()),
+                        actual: Some(actual_expr.to_string()),
+                        passed: actual_expr == expected_output,
+                        error: None,
+                    }
                 }
-            } else {
-                // Apply the first edit
-                let edit = &edits[0];
-                let actual = self.apply_edit(&full_input, edit);
-                // Extract just the expression part (after "<?php " and before ";")
-                let actual_expr = actual
-                    .strip_prefix("<?php ")
-                    .and_then(|s| s.strip_suffix(';'))
-                    .unwrap_or(&actual);
-
-                let passed = actual_expr == expected_output;
-                TestResult {
+                Err(e) => TestResult {
                     input: test.input.clone(),
                     expected: Some(expected_output.clone()),
-                    actual: Some(actual_expr.to_string()),
-                    passed,
-                    error: None,
-                }
+                    actual: None,
+                    passed: false,
+                    error: Some(e.to_string()),
+                },
             }
         } else {
             // No expected output means the rule should not match
@@ -104,13 +115,6 @@ impl YamlRuleInterpreter {
         }
     }
 
-    #[cfg(test)]
-    fn apply_edit(&self, source: &str, edit: &Edit) -> String {
-        let start = edit.span.start.offset as usize;
-        let end = edit.span.end.offset as usize;
-        format!("{}{}{}", &source[..start], edit.replacement, &source[end..])
-    }
-
     fn check_internal<'a>(&self, program: &Program<'a>, source: &str) -> Vec<Edit> {
         let mut visitor = YamlRuleVisitor {
             source,
@@ -123,13 +127,17 @@ impl YamlRuleInterpreter {
 }
 
 /// Result of running a single test case
-#[cfg(test)]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TestResult {
+    /// The test input, without `<?php`
     pub input: String,
+    /// Expected output, `None` when the rule should not match
     pub expected: Option<String>,
+    /// Output after applying the rule's edits, or the replacement of an unexpected match
     pub actual: Option<String>,
+    /// Whether the test passed
     pub passed: bool,
+    /// Why the test failed, when not a plain output mismatch
     pub error: Option<String>,
 }
 
@@ -37,5 +37,5 @@ pub mod loader;
 pub use schema::{YamlRule, MatchPattern, Replacement, TestCase, RuleCondition};
 pub use matcher::{PatternMatcher, CapturedBindings};
 pub use replacer::Replacer;
-pub use interpreter::YamlRuleInterpreter;
+pub use interpreter::{TestResult, YamlRuleInterpreter};
 pub use loader::{load_rules_from_file, load_rules_from_dir, load_rules_from_string};